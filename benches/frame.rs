@@ -0,0 +1,76 @@
+//! Benchmarks for per-frame CPU work with `N` objects in the world.
+//!
+//! * `flatten/*` — building the world `MeshData` from the scene graph, the
+//!   CPU half of `Scene::draw_world`.
+//! * `draw_world/*` — a full frame (flatten + upload + submit) on a headless
+//!   software adapter; skipped when none is installed.
+//!
+//! **Run:**
+//! ```sh
+//! cargo bench --bench frame
+//! ```
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use vertra::camera::Camera;
+use vertra::geometry::{Geometry, SphereTessellation};
+use vertra::mesh::MeshData;
+use vertra::objects::Object;
+use vertra::pipeline::{Pipeline, PipelineConfig};
+use vertra::scene::Scene;
+use vertra::transform::Transform;
+use vertra::world::World;
+
+const COUNTS: [usize; 3] = [100, 1_000, 10_000];
+
+fn object(i: usize) -> Object {
+    let geometry = match i % 3 {
+        0 => Geometry::Cube { size: 0.5 },
+        1 => Geometry::Sphere { radius: 0.25, subdivisions: 12, tessellation: SphereTessellation::Uv },
+        _ => Geometry::Pyramid { base_size: 0.5, height: 0.5 },
+    };
+    Object {
+        name: format!("obj_{i}"),
+        geometry: Some(geometry),
+        transform: Transform::from_position((i % 100) as f32, 0.0, (i / 100) as f32),
+        ..Default::default()
+    }
+}
+
+fn flatten(c: &mut Criterion) {
+    for n in COUNTS {
+        let mut world = World::new();
+        for i in 0..n {
+            world.spawn_object(object(i), None);
+        }
+        let identity = Transform::default();
+        let mut mesh = MeshData::new();
+        c.bench_function(&format!("flatten/{n}"), |b| b.iter(|| {
+            mesh.clear();
+            for &root in &world.roots {
+                mesh.add_object(&world, root, &identity);
+            }
+            mesh.indices.len()
+        }));
+    }
+}
+
+fn draw_world(c: &mut Criterion) {
+    let config = PipelineConfig::from_env().with_force_fallback_adapter(true);
+    for n in COUNTS {
+        let pipeline = match pollster::block_on(Pipeline::headless(&config, 256, 256)) {
+            Ok(p) => p,
+            Err(e) => {
+                eprintln!("skipping draw_world benches: {e}");
+                return;
+            }
+        };
+        let mut scene = Scene::new(pipeline, Camera::new().with_position([50.0, 20.0, -30.0]));
+        for i in 0..n {
+            scene.spawn(object(i), None);
+        }
+        c.bench_function(&format!("draw_world/{n}"), |b| b.iter(|| scene.draw_world().unwrap()));
+    }
+}
+
+criterion_group!(benches, flatten, draw_world);
+criterion_main!(benches);
@@ -0,0 +1,48 @@
+//! Benchmarks for CPU-side mesh generation and math.
+//!
+//! **Run:**
+//! ```sh
+//! cargo bench --bench geometry
+//! cargo bench --bench geometry -- sphere   # benches whose name matches
+//! ```
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use vertra::geometry::{Geometry, SphereTessellation};
+use vertra::math::Matrix4;
+use vertra::transform::Transform;
+
+fn geometry(c: &mut Criterion) {
+    for subdivisions in [32, 128, 512] {
+        let sphere = Geometry::Sphere { radius: 1.0, subdivisions, tessellation: SphereTessellation::Uv };
+        c.bench_function(&format!("geometry/sphere/{subdivisions}"), |b| b.iter(|| sphere.build()));
+        let capsule = Geometry::Capsule { radius: 0.5, height: 1.0, subdivisions };
+        c.bench_function(&format!("geometry/capsule/{subdivisions}"), |b| b.iter(|| capsule.build()));
+    }
+    let cube = Geometry::Cube { size: 1.0 };
+    c.bench_function("geometry/cube", |b| b.iter(|| cube.build()));
+}
+
+fn transform(c: &mut Criterion) {
+    let transform = Transform {
+        position: [1.0, 2.0, 3.0],
+        rotation: [15.0, 30.0, 45.0],
+        scale: [1.0, 2.0, 0.5],
+    };
+    let quad = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]];
+    c.bench_function("transform/apply/quad", |b| b.iter(|| transform.apply(black_box(quad))));
+    let child = Transform::from_position(0.5, 0.0, 0.0);
+    c.bench_function("transform/combine", |b| b.iter(|| transform.combine(black_box(&child))));
+    c.bench_function("transform/to_matrix", |b| b.iter(|| black_box(&transform).to_matrix()));
+}
+
+fn matrix(c: &mut Criterion) {
+    let view = Matrix4::look_at([0.0, 2.0, -5.0], [0.0; 3], [0.0, 1.0, 0.0]);
+    let proj = Matrix4::perspective(60.0, 16.0 / 9.0, 0.1, 100.0);
+    c.bench_function("matrix4/mul", |b| b.iter(|| black_box(proj) * black_box(view)));
+    c.bench_function("matrix4/mul_vec4", |b| b.iter(|| black_box(view).mul_vec4(black_box([1.0, 2.0, 3.0, 1.0]))));
+}
+
+criterion_group!(benches, geometry, transform, matrix);
+criterion_main!(benches);
@@ -101,6 +101,55 @@ impl Camera {
         }
     }
 
+    /// Returns the render layers this camera draws.
+    #[wasm_bindgen(getter)]
+    pub fn layers_mask(&self) -> u32 {
+        unsafe { (*self.inner).layers_mask }
+    }
+
+    /// Restricts the camera to objects whose `layer` shares a bit with
+    /// `mask`.  Bit 31 is the editor gizmo layer.
+    #[wasm_bindgen(setter)]
+    pub fn set_layers_mask(&mut self, mask: u32) {
+        unsafe { (*self.inner).layers_mask = mask; }
+    }
+
+    /// Returns the exposure multiplier applied before tonemapping.
+    #[wasm_bindgen(getter)]
+    pub fn exposure(&self) -> f32 {
+        unsafe { (*self.inner).exposure }
+    }
+
+    /// Sets the exposure multiplier (HDR only; see `Scene.set_hdr`).
+    #[wasm_bindgen(setter)]
+    pub fn set_exposure(&mut self, exposure: f32) {
+        unsafe { (*self.inner).exposure = exposure; }
+    }
+
+    /// Returns the distance that stays sharp with depth of field.
+    #[wasm_bindgen(getter)]
+    pub fn focus_distance(&self) -> f32 {
+        unsafe { (*self.inner).focus_distance }
+    }
+
+    /// Sets the focus distance (see `Scene.set_depth_of_field`).
+    #[wasm_bindgen(setter)]
+    pub fn set_focus_distance(&mut self, distance: f32) {
+        unsafe { (*self.inner).focus_distance = distance; }
+    }
+
+    /// Returns the depth-of-field strength in pixels of blur.
+    #[wasm_bindgen(getter)]
+    pub fn aperture(&self) -> f32 {
+        unsafe { (*self.inner).aperture }
+    }
+
+    /// Sets the depth-of-field strength; `0` keeps everything sharp.
+    #[wasm_bindgen(setter)]
+    pub fn set_aperture(&mut self, aperture: f32) {
+        unsafe { (*self.inner).aperture = aperture; }
+    }
+
     /// Rotates the camera by applying yaw and pitch deltas.
     ///
     /// Typically called with the raw `movementX` / `movementY` values from a
@@ -1,5 +1,5 @@
 use wasm_bindgen::prelude::*;
-use vertra::geometry::Geometry as CoreGeometry;
+use vertra::geometry::{Geometry as CoreGeometry, SphereTessellation};
 
 /// Represents a 3D mesh definition that can be attached to a scene object for rendering.
 #[wasm_bindgen]
@@ -28,7 +28,66 @@ impl Geometry {
     /// * `depth`  - Size along the Z-axis.
     #[wasm_bindgen(js_name = box)]
     pub fn box_geo(width: f32, height: f32, depth: f32) -> Geometry {
-        Geometry { inner: CoreGeometry::Box { width, height, depth } }
+        Geometry { inner: CoreGeometry::Box { width, height, depth, segments: [1; 3] } }
+    }
+
+    /// Creates a rectangular box whose faces are split into a grid.
+    ///
+    /// # Arguments
+    ///
+    /// * `width`, `height`, `depth` - Size along the X, Y and Z axes.
+    /// * `x_segments`, `y_segments`, `z_segments` - Grid cells along each
+    ///   axis; `1` gives one quad per face.
+    #[wasm_bindgen]
+    pub fn segmented_box(
+        width: f32,
+        height: f32,
+        depth: f32,
+        x_segments: usize,
+        y_segments: usize,
+        z_segments: usize,
+    ) -> Geometry {
+        Geometry {
+            inner: CoreGeometry::Box { width, height, depth, segments: [x_segments, y_segments, z_segments] },
+        }
+    }
+
+    /// Creates a box with rounded edges and corners.
+    ///
+    /// # Arguments
+    ///
+    /// * `width`, `height`, `depth` - Size along the X, Y and Z axes.
+    /// * `radius`       - Bevel radius, clamped to half the smallest size.
+    /// * `subdivisions` - Segments per quarter-circle of bevel.
+    #[wasm_bindgen]
+    pub fn rounded_box(width: f32, height: f32, depth: f32, radius: f32, subdivisions: usize) -> Geometry {
+        Geometry { inner: CoreGeometry::RoundedBox { width, height, depth, radius, subdivisions } }
+    }
+
+    /// Creates an arrow pointing along +Y with its tail at the origin.
+    ///
+    /// # Arguments
+    ///
+    /// * `length`       - Tail to tip, head included.
+    /// * `shaft_radius` - Radius of the shaft.
+    /// * `head_size`    - Length of the cone head; its base radius is half of this.
+    #[wasm_bindgen]
+    pub fn arrow(length: f32, shaft_radius: f32, head_size: f32) -> Geometry {
+        Geometry { inner: CoreGeometry::Arrow { length, shaft_radius, head_size } }
+    }
+
+    /// Creates a flat, double-sided rectangle in the XY plane facing -Z,
+    /// showing one region of its texture.
+    ///
+    /// # Arguments
+    ///
+    /// * `width`, `height` - Full extents in world units.
+    /// * `uv`              - `[u0, v0, u1, v1]` texture region, `v0` at the top.
+    ///   Ignored (whole texture) unless it has exactly 4 elements.
+    #[wasm_bindgen]
+    pub fn sprite(width: f32, height: f32, uv: Vec<f32>) -> Geometry {
+        let uv = <[f32; 4]>::try_from(uv).unwrap_or([0.0, 0.0, 1.0, 1.0]);
+        Geometry { inner: CoreGeometry::Sprite { width, height, uv } }
     }
 
     /// Creates a flat, square surface lying on the XZ plane.
@@ -38,7 +97,19 @@ impl Geometry {
     /// * `size` - The side length of the square plane in world units.
     #[wasm_bindgen]
     pub fn plane(size: f32) -> Geometry {
-        Geometry { inner: CoreGeometry::Plane { size } }
+        Geometry { inner: CoreGeometry::Plane { size, subdivisions: 1 } }
+    }
+
+    /// Creates a square plane on the XZ plane split into a grid, e.g. for
+    /// terrain or vertex displacement.
+    ///
+    /// # Arguments
+    ///
+    /// * `size`         - The side length of the square plane in world units.
+    /// * `subdivisions` - Grid cells along each side.
+    #[wasm_bindgen]
+    pub fn subdivided_plane(size: f32, subdivisions: usize) -> Geometry {
+        Geometry { inner: CoreGeometry::Plane { size, subdivisions } }
     }
 
     /// Creates a spherical mesh.
@@ -49,7 +120,20 @@ impl Geometry {
     /// * `subdivisions` - Smoothness level; higher values produce more triangles.
     #[wasm_bindgen]
     pub fn sphere(radius: f32, subdivisions: usize) -> Geometry {
-        Geometry { inner: CoreGeometry::Sphere { radius, subdivisions } }
+        Geometry { inner: CoreGeometry::Sphere { radius, subdivisions, tessellation: SphereTessellation::Uv } }
+    }
+
+    /// Creates an icosphere: a subdivided icosahedron whose triangles are
+    /// all about the same size.
+    ///
+    /// # Arguments
+    ///
+    /// * `radius`       - Distance from the centre to the surface in world units.
+    /// * `subdivisions` - Smoothness level; gives about as many triangles as a
+    ///   `sphere` with the same value.
+    #[wasm_bindgen]
+    pub fn icosphere(radius: f32, subdivisions: usize) -> Geometry {
+        Geometry { inner: CoreGeometry::Sphere { radius, subdivisions, tessellation: SphereTessellation::Icosphere } }
     }
 
     /// Creates a four-sided pyramid with a square base.
@@ -188,6 +188,19 @@ impl Object {
         unsafe { (*self.inner).texture_path = path; }
     }
 
+    /// Returns the render-layer bits of this object.
+    #[wasm_bindgen(getter)]
+    pub fn layer(&self) -> u32 {
+        unsafe { (*self.inner).layer }
+    }
+
+    /// Sets the render-layer bits.  The object is drawn only by cameras
+    /// whose `layers_mask` shares a bit with it.
+    #[wasm_bindgen(setter)]
+    pub fn set_layer(&mut self, layer: u32) {
+        unsafe { (*self.inner).layer = layer; }
+    }
+
     /// Returns the number of direct children attached to this object.
     #[wasm_bindgen(getter)]
     pub fn children_count(&self) -> usize {
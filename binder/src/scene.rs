@@ -81,6 +81,19 @@ impl Scene {
         }
     }
 
+    /// Returns a handle to the 2D overlay [`World`].
+    ///
+    /// Objects spawned here are positioned in canvas pixels, with `(0, 0)`
+    /// at the top-left, and drawn unlit on top of everything else.
+    #[wasm_bindgen(getter)]
+    pub fn overlay(&self) -> World {
+        unsafe {
+            World {
+                inner: &mut (*self.inner).overlay.world as *mut vertra::world::World
+            }
+        }
+    }
+
     /// Returns the primary camera used to render this scene.
     ///
     /// The camera is owned by the scene; do not attempt to manually destroy it
@@ -136,6 +149,63 @@ impl Scene {
         unsafe { (*self.inner).disable_editor_mode(); }
     }
     
+    /// Enables or disables HDR rendering with tonemapping.
+    ///
+    /// With `auto_exposure` the exposure adapts to the frame's brightness
+    /// where compute shaders are available (not on WebGL2); the camera's
+    /// `exposure` then acts as compensation.
+    pub fn set_hdr(&mut self, hdr: bool, auto_exposure: bool) {
+        let pipeline = unsafe { &mut (*self.inner).pipeline };
+        let settings = pipeline.post_settings().clone()
+            .with_hdr(hdr)
+            .with_auto_exposure(auto_exposure.then(vertra::post::AutoExposure::default));
+        pipeline.set_post_settings(settings);
+    }
+
+    /// Sets a flat ambient light colour.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - A 3-element `[r, g, b]` array; `[1, 1, 1]` (the default)
+    ///   leaves colours unchanged.  Ignored unless it has exactly 3 elements.
+    pub fn set_ambient(&mut self, color: Vec<f32>) {
+        if let [r, g, b] = color[..] {
+            unsafe { (*self.inner).lighting.ambient = vertra::lighting::AmbientLight::Flat([r, g, b]); }
+        }
+    }
+
+    /// Sets a hemispheric ambient light: `sky` on upward-facing surfaces,
+    /// `ground` on downward-facing ones.
+    ///
+    /// Both arguments are `[r, g, b]` arrays; ignored unless each has
+    /// exactly 3 elements.
+    pub fn set_hemisphere_ambient(&mut self, sky: Vec<f32>, ground: Vec<f32>) {
+        if let ([sr, sg, sb], [gr, gg, gb]) = (&sky[..], &ground[..]) {
+            unsafe {
+                (*self.inner).lighting.ambient = vertra::lighting::AmbientLight::Hemisphere {
+                    sky: [*sr, *sg, *sb],
+                    ground: [*gr, *gg, *gb],
+                };
+            }
+        }
+    }
+
+    /// Enables or disables FXAA, a cheap full-screen anti-aliasing pass
+    /// suited to WebGL2 where multisampling is expensive.
+    pub fn set_fxaa(&mut self, fxaa: bool) {
+        let pipeline = unsafe { &mut (*self.inner).pipeline };
+        let settings = pipeline.post_settings().clone().with_fxaa(fxaa);
+        pipeline.set_post_settings(settings);
+    }
+
+    /// Enables or disables depth of field, driven by the camera's
+    /// `focus_distance` and `aperture`.
+    pub fn set_depth_of_field(&mut self, enabled: bool) {
+        let pipeline = unsafe { &mut (*self.inner).pipeline };
+        let settings = pipeline.post_settings().clone().with_depth_of_field(enabled);
+        pipeline.set_post_settings(settings);
+    }
+
     /// Exports the entire scene (camera + world) as a VTR binary buffer.
     ///
     /// The buffer can be stored, transferred, and later reloaded with
@@ -178,6 +248,41 @@ impl Scene {
 
             (*self.inner).camera = scene_data.camera;
             (*self.inner).world  = scene_data.world;
+            (*self.inner).static_batches.invalidate();
+            Ok(())
+        }
+    }
+
+    /// Exports only the camera as a VTR binary buffer, to bookmark a
+    /// viewpoint.  Restore it with [`Scene::load_camera`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`JsValue`] error string on serialisation failure.
+    pub fn save_camera(&self) -> Result<Vec<u8>, JsValue> {
+        unsafe {
+            let mut buf = Vec::new();
+            vertra::vtr::write_camera(&mut buf, &(*self.inner).camera)
+                .map_err(|e| JsValue::from_str(&e.to_string()))?;
+            Ok(buf)
+        }
+    }
+
+    /// Replaces the camera from a buffer produced by [`Scene::save_camera`]
+    /// (or [`Scene::save_vtr`]), leaving the world untouched.  The current
+    /// aspect ratio is kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`JsValue`] error string when the data is corrupt, truncated,
+    /// or written by an incompatible format version.
+    pub fn load_camera(&mut self, data: &[u8]) -> Result<(), JsValue> {
+        unsafe {
+            let mut cur = Cursor::new(data);
+            let camera = vertra::vtr::read_camera(&mut cur)
+                .map_err(|e| JsValue::from_str(&e.to_string()))?;
+            let aspect = (*self.inner).camera.aspect;
+            (*self.inner).camera = vertra::camera::Camera { aspect, ..camera };
             Ok(())
         }
     }
@@ -36,6 +36,29 @@ pub struct FrameContext {
     pub draw_calls: u32,
     /// Triangles rendered during the most recently rendered frame.
     pub triangle_count: u32,
+    /// Instances drawn during the most recently rendered frame.
+    pub instance_count: u32,
+    /// Bytes uploaded to the GPU for the most recently rendered frame.
+    pub bytes_uploaded: f64,
+    /// GPU buffers (re)allocated for the most recently rendered frame.
+    pub buffer_reallocations: u32,
+    /// Bind groups created rather than reused for the most recently rendered frame.
+    pub bind_groups_created: u32,
+    /// GPU memory held by meshes, textures, buffers and render targets after
+    /// the most recently rendered frame, in bytes.
+    pub gpu_memory_bytes: f64,
+    /// Device pixels per CSS pixel (`window.devicePixelRatio`).
+    pub scale_factor: f32,
+    /// Seconds since startup (the sum of every `dt` so far).
+    pub elapsed: f64,
+    /// Frames rendered so far.
+    pub frame: f64,
+    /// Progress towards the next fixed update, in `[0, 1)`.
+    pub alpha: f32,
+    /// Canvas width in device pixels.
+    pub width: u32,
+    /// Canvas height in device pixels.
+    pub height: u32,
 }
 
 /// Represents an input event sent from the engine to the JavaScript handler.
@@ -136,7 +159,8 @@ impl WebWindow {
 
     /// Initializes the engine and starts the RequestAnimationFrame loop.
     /// @param {string} canvas_id - The ID of the HTMLCanvasElement to target.
-    pub fn start(mut self, canvas_id: String) {
+    /// Throws if the event loop cannot be set up.
+    pub fn start(mut self, canvas_id: String) -> Result<(), JsError> {
         // Initialize the engine window with JsValue as the state type S
         let camera_val = unsafe {
             if self.camera.owned {
@@ -165,6 +189,17 @@ impl WebWindow {
                 frame_time_ms: ctx.frame_time_ms,
                 draw_calls: ctx.draw_calls,
                 triangle_count: ctx.triangle_count,
+                instance_count: ctx.render_stats.instance_count,
+                bytes_uploaded: ctx.render_stats.bytes_uploaded as f64,
+                buffer_reallocations: ctx.render_stats.buffer_reallocations,
+                bind_groups_created: ctx.render_stats.bind_groups_created,
+                gpu_memory_bytes: ctx.render_stats.memory.total() as f64,
+                scale_factor: ctx.scale_factor,
+                elapsed: ctx.elapsed,
+                frame: ctx.frame as f64,
+                alpha: ctx.alpha,
+                width: ctx.window_size.0,
+                height: ctx.window_size.1,
             }
         }
 
@@ -401,6 +436,6 @@ impl WebWindow {
             });
         }
 
-        engine_window.create();
+        engine_window.create().map_err(|e| JsError::new(&e.to_string()))
     }
 }
\ No newline at end of file
@@ -0,0 +1,15 @@
+//! Build script: cfg aliases for platform-dependent APIs.
+
+fn main() {
+    // `pump_events`: winit can pump the event loop from the host's own main
+    // loop, which backs `window::App`.  Not on the web or iOS.
+    println!("cargo::rustc-check-cfg=cfg(pump_events)");
+    let os = std::env::var("CARGO_CFG_TARGET_OS").unwrap_or_default();
+    let pumpable = matches!(
+        os.as_str(),
+        "windows" | "macos" | "android" | "linux" | "dragonfly" | "freebsd" | "netbsd" | "openbsd"
+    );
+    if pumpable {
+        println!("cargo::rustc-cfg=pump_events");
+    }
+}
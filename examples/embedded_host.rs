@@ -0,0 +1,97 @@
+//! # Embedded Host
+//!
+//! Renders a Vertra scene into a window owned by the *host* application,
+//! without using [`vertra::window::Window`].  The host runs its own event
+//! loop and only hands Vertra a window handle — the same approach works for
+//! any toolkit exposing `raw-window-handle` (Qt, Tauri, SDL, …).
+//!
+//! **Run:**
+//! ```sh
+//! cargo run --example embedded_host
+//! ```
+//!
+//! **Controls:** close the window to exit.
+
+use std::sync::Arc;
+use winit::application::ApplicationHandler;
+use winit::event::WindowEvent;
+use winit::event_loop::{ActiveEventLoop, EventLoop};
+use winit::window::{Window, WindowId};
+
+use vertra::camera::Camera;
+use vertra::geometry::Geometry;
+use vertra::objects::Object;
+use vertra::pipeline::{Pipeline, PipelineConfig, RenderError};
+use vertra::scene::Scene;
+
+/// The host application: it creates and owns the window and event loop.
+#[derive(Default)]
+struct Host {
+    window: Option<Arc<Window>>,
+    scene: Option<Scene>,
+    cube_id: usize,
+}
+
+impl ApplicationHandler for Host {
+    // Windows and surfaces can only be created once the platform is ready.
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        if self.scene.is_some() {
+            return;
+        }
+        let attributes = Window::default_attributes().with_title("Vertra inside a host window");
+        let host_window = Arc::new(event_loop.create_window(attributes).unwrap());
+        let size = host_window.inner_size();
+        let pipeline = pollster::block_on(Pipeline::from_window_handle(
+            &PipelineConfig::from_env(),
+            Arc::clone(&host_window),
+            size.width,
+            size.height,
+        ))
+        .unwrap();
+        let camera = Camera::new()
+            .with_aspect(size.width.max(1) as f32 / size.height.max(1) as f32)
+            .with_position([0.0, 2.0, -5.0])
+            .with_rotation(90.0, -15.0);
+        let mut scene = Scene::new(pipeline, camera);
+        self.cube_id = scene.spawn(
+            Object {
+                name: "Cube".into(),
+                geometry: Some(Geometry::Cube { size: 1.5 }),
+                color: [0.2, 0.6, 0.9, 1.0],
+                ..Default::default()
+            },
+            None,
+        );
+        self.window = Some(host_window);
+        self.scene = Some(scene);
+    }
+
+    fn window_event(&mut self, event_loop: &ActiveEventLoop, _: WindowId, event: WindowEvent) {
+        let (Some(host_window), Some(scene)) = (&self.window, &mut self.scene) else { return };
+        match event {
+            WindowEvent::CloseRequested => event_loop.exit(),
+            WindowEvent::Resized(size) => scene.resize(size.width, size.height),
+            WindowEvent::RedrawRequested => {
+                if let Some(cube) = scene.world.get_mut(self.cube_id) {
+                    cube.transform.rotation[1] += 0.5;
+                }
+                // Re-create a lost surface; other errors just drop the frame.
+                if let Err(RenderError::SurfaceLost) = scene.draw_world() {
+                    scene.pipeline.suspend();
+                    scene.pipeline.resume(Arc::clone(host_window));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn about_to_wait(&mut self, _: &ActiveEventLoop) {
+        if let Some(host_window) = &self.window {
+            host_window.request_redraw();
+        }
+    }
+}
+
+fn main() {
+    EventLoop::new().unwrap().run_app(&mut Host::default()).unwrap();
+}
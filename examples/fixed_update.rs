@@ -26,10 +26,10 @@
 //! > the simulation run.
 
 use vertra::camera::Camera;
-use vertra::geometry::Geometry;
+use vertra::geometry::{Geometry, SphereTessellation};
 use vertra::objects::Object;
 use vertra::transform::Transform;
-use vertra::window::Window;
+use vertra::window::{Window, WindowError};
 
 /// Simulation and render state.
 struct AppState {
@@ -41,7 +41,7 @@ struct AppState {
     ball_vy: f32,
 }
 
-fn main() {
+fn main() -> Result<(), WindowError> {
     Window::new(AppState {
         ball_id: None,
         cube_id: None,
@@ -62,6 +62,7 @@ fn main() {
                 geometry: Some(Geometry::Sphere {
                     radius: 0.5,
                     subdivisions: 20,
+                    tessellation: SphereTessellation::Uv,
                 }),
                 color: [0.3, 0.7, 1.0, 1.0],
                 transform: Transform::from_position(0.0, 4.0, 0.0),
@@ -75,7 +76,7 @@ fn main() {
             Object {
                 name: "Ground".to_string(),
                 str_id: "ground".to_string(),
-                geometry: Some(Geometry::Plane { size: 12.0 }),
+                geometry: Some(Geometry::Plane { size: 12.0, subdivisions: 1 }),
                 color: [0.3, 0.6, 0.3, 1.0],
                 transform: Transform::from_position(0.0, 0.0, 0.0),
                 ..Default::default()
@@ -133,6 +134,6 @@ fn main() {
             spinner.transform.rotation[0] += 45.0 * ctx.dt;
         }
     })
-    .create();
+    .create()
 }
 
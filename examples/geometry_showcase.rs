@@ -3,14 +3,14 @@
 //! Displays all six built-in geometry types side by side in a static editor
 //! scene so you can visually inspect and compare them.
 //!
-//! | Position | Shape    | Geometry variant                           |
-//! |----------|----------|--------------------------------------------|
-//! | x = -7.5 | Cube     | `Geometry::Cube { size }`                  |
-//! | x = -4.5 | Box      | `Geometry::Box { width, height, depth }`   |
-//! | x = -1.5 | Plane    | `Geometry::Plane { size }`                 |
-//! | x =  1.5 | Pyramid  | `Geometry::Pyramid { base_size, height }`  |
-//! | x =  4.5 | Capsule  | `Geometry::Capsule { radius, height, … }`  |
-//! | x =  7.5 | Sphere   | `Geometry::Sphere { radius, subdivisions }`|
+//! | Position | Shape    | Geometry variant                                    |
+//! |----------|----------|-----------------------------------------------------|
+//! | x = -7.5 | Cube     | `Geometry::Cube { size }`                           |
+//! | x = -4.5 | Box      | `Geometry::Box { width, height, depth, segments }`  |
+//! | x = -1.5 | Plane    | `Geometry::Plane { size, subdivisions }`            |
+//! | x =  1.5 | Pyramid  | `Geometry::Pyramid { base_size, height }`           |
+//! | x =  4.5 | Capsule  | `Geometry::Capsule { radius, height, … }`           |
+//! | x =  7.5 | Sphere   | `Geometry::Sphere { radius, subdivisions, … }`      |
 //!
 //! **Run:**
 //! ```sh
@@ -21,12 +21,12 @@
 //! transform in the gizmo overlay.
 
 use vertra::camera::Camera;
-use vertra::geometry::Geometry;
+use vertra::geometry::{Geometry, SphereTessellation};
 use vertra::objects::Object;
 use vertra::transform::Transform;
-use vertra::window::Window;
+use vertra::window::{Window, WindowError};
 
-fn main() {
+fn main() -> Result<(), WindowError> {
     Window::new(())
         .with_title("Geometry Showcase — Vertra example")
         .with_camera(
@@ -49,13 +49,14 @@ fn main() {
                         width: 1.2,
                         height: 2.0,
                         depth: 0.8,
+                        segments: [1; 3],
                     },
                     [0.9, 0.6, 0.2, 1.0], // orange
                 ),
                 (
                     "Plane",
                     "geo_plane",
-                    Geometry::Plane { size: 2.0 },
+                    Geometry::Plane { size: 2.0, subdivisions: 1 },
                     [0.9, 0.9, 0.2, 1.0], // yellow
                 ),
                 (
@@ -83,6 +84,7 @@ fn main() {
                     Geometry::Sphere {
                         radius: 1.0,
                         subdivisions: 24,
+                        tessellation: SphereTessellation::Uv,
                     },
                     [0.7, 0.2, 0.9, 1.0], // purple
                 ),
@@ -110,6 +112,6 @@ fn main() {
 
             scene.enable_editor_mode();
         })
-        .create();
+        .create()
 }
 
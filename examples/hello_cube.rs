@@ -15,7 +15,7 @@ use vertra::camera::Camera;
 use vertra::geometry::Geometry;
 use vertra::objects::Object;
 use vertra::transform::Transform;
-use vertra::window::Window;
+use vertra::window::{Window, WindowError};
 
 /// Application state — we cache the numeric ID resolved during startup so that
 /// `on_update` never pays the cost of a string-hash lookup every frame.
@@ -23,7 +23,7 @@ struct AppState {
     cube_id: Option<usize>,
 }
 
-fn main() {
+fn main() -> Result<(), WindowError> {
     Window::new(AppState { cube_id: None })
         .with_title("Hello, Cube!")
         .with_camera(
@@ -53,6 +53,6 @@ fn main() {
                 cube.transform.rotation[1] += 45.0 * ctx.dt;
             }
         })
-        .create();
+        .create()
 }
 
@@ -23,12 +23,12 @@
 
 use std::collections::HashSet;
 use vertra::camera::Camera;
-use vertra::geometry::Geometry;
+use vertra::geometry::{Geometry, SphereTessellation};
 use vertra::objects::Object;
 use vertra::scene::Scene;
 use vertra::script::ObjectScript;
 use vertra::transform::Transform;
-use vertra::window::{FrameContext, Window};
+use vertra::window::{Window, WindowError};
 use vertra::world::World;
 use vertra::event::{Event, WindowEvent, DeviceEvent, ElementState};
 use winit::keyboard::{KeyCode, PhysicalKey};
@@ -167,7 +167,7 @@ struct AppState {
     keys: HashSet<KeyCode>,
 }
 
-fn main() {
+fn main() -> Result<(), WindowError> {
     Window::new(AppState { keys: HashSet::new() })
         .with_title("Scripted Objects")
         .with_camera(
@@ -187,7 +187,7 @@ fn main() {
         .with_event_handler(|state, scene, event, _| {
             handle_input(state, scene, event);
         })
-        .create();
+        .create()
 }
 
 fn spawn_scene(scene: &mut Scene) {
@@ -211,7 +211,7 @@ fn spawn_scene(scene: &mut Scene) {
             name:     "BobbingSphere".into(),
             str_id:   "bob_sphere".into(),
             transform: Transform::from_position(-2.0, 0.0, 0.0),
-            geometry: Some(Geometry::Sphere { radius: 0.8, subdivisions: 20 }),
+            geometry: Some(Geometry::Sphere { radius: 0.8, subdivisions: 20, tessellation: SphereTessellation::Uv }),
             color:    [0.2, 0.8, 0.3, 1.0],
             ..Default::default()
         },
@@ -225,7 +225,7 @@ fn spawn_scene(scene: &mut Scene) {
             name:     "PulseSphere".into(),
             str_id:   "pulse_sphere".into(),
             transform: Transform::from_position(2.0, 0.0, 0.0),
-            geometry: Some(Geometry::Sphere { radius: 0.8, subdivisions: 20 }),
+            geometry: Some(Geometry::Sphere { radius: 0.8, subdivisions: 20, tessellation: SphereTessellation::Uv }),
             color:    [0.3, 0.5, 1.0, 1.0],
             ..Default::default()
         },
@@ -239,7 +239,7 @@ fn spawn_scene(scene: &mut Scene) {
             name:     "ColorPlane".into(),
             str_id:   "color_plane".into(),
             transform: Transform::from_position(6.0, 0.0, 0.0),
-            geometry: Some(Geometry::Plane { size: 2.0 }),
+            geometry: Some(Geometry::Plane { size: 2.0, subdivisions: 1 }),
             color:    [1.0, 1.0, 1.0, 1.0],
             ..Default::default()
         },
@@ -272,10 +272,8 @@ fn handle_input(state: &mut AppState, scene: &mut vertra::scene::Scene, event: E
     match event {
         Event::DeviceEvent {
             event: DeviceEvent::MouseMotion { delta: (dx, dy) }, ..
-        } => {
-            if scene.editor.is_none() {
-                scene.camera.rotate(dx as f32 * 0.15, dy as f32 * 0.15, false);
-            }
+        } if scene.editor.is_none() => {
+            scene.camera.rotate(dx as f32 * 0.15, dy as f32 * 0.15, false);
         }
         Event::WindowEvent {
             event: WindowEvent::KeyboardInput { event: ke, .. }, ..
@@ -27,9 +27,9 @@
 //! | Scroll     | Dolly in / out                       |
 
 use vertra::camera::Camera;
-use vertra::window::Window;
+use vertra::window::{Window, WindowError};
 use vertra::transform::Transform;
-use vertra::geometry::Geometry;
+use vertra::geometry::{Geometry, SphereTessellation};
 use vertra::objects::Object;
 use vertra::editor::{EditorStateEvent, GizmoMode, DragAxis};
 
@@ -39,7 +39,7 @@ struct AppState {
     moon_id: Option<usize>,
 }
 
-fn main() {
+fn main() -> Result<(), WindowError> {
     let initial_state = AppState {
         sun_id: None,
         earth_id: None,
@@ -70,7 +70,7 @@ fn main() {
                 name: "Planet".to_string(),
                 str_id: "earth".to_string(),
                 transform: Transform::from_position(6.0, 0.0, 0.0),
-                geometry: Some(Geometry::Sphere { radius: 0.8, subdivisions: 24 }),
+                geometry: Some(Geometry::Sphere { radius: 0.8, subdivisions: 24, tessellation: SphereTessellation::Uv }),
                 color: [0.2, 0.5, 1.0, 1.0],
                 ..Default::default()
             };
@@ -81,7 +81,7 @@ fn main() {
                 name: "Moon".to_string(),
                 str_id: "moon".to_string(),
                 transform: Transform::from_position(1.5, 0.0, 0.0),
-                geometry: Some(Geometry::Sphere { radius: 0.3, subdivisions: 16 }),
+                geometry: Some(Geometry::Sphere { radius: 0.3, subdivisions: 16, tessellation: SphereTessellation::Uv }),
                 color: [0.7, 0.7, 0.7, 1.0],
                 ..Default::default()
             };
@@ -133,5 +133,5 @@ fn main() {
                 }
             }
         })
-        .create();
+        .create()
 }
\ No newline at end of file
@@ -29,7 +29,7 @@ use vertra::camera::Camera;
 use vertra::geometry::Geometry;
 use vertra::objects::Object;
 use vertra::transform::Transform;
-use vertra::window::Window;
+use vertra::window::{Window, WindowError};
 
 const TEXTURE_PATH: &str = "examples/assets/texture.png";
 
@@ -37,7 +37,7 @@ struct AppState {
     cube_id: Option<usize>,
 }
 
-fn main() {
+fn main() -> Result<(), WindowError> {
     Window::new(AppState { cube_id: None })
         .with_title("Textured Cube — Vertra")
         .with_camera(
@@ -82,6 +82,6 @@ fn main() {
                 cube.transform.rotation[0] += 15.0 * ctx.dt; // 15°/s around X
             }
         })
-        .create();
+        .create()
 }
 
@@ -21,7 +21,7 @@
 use std::io::Cursor;
 
 use vertra::camera::Camera;
-use vertra::geometry::Geometry;
+use vertra::geometry::{Geometry, SphereTessellation};
 use vertra::objects::Object;
 use vertra::transform::Transform;
 use vertra::vtr;
@@ -57,6 +57,7 @@ fn main() {
             geometry: Some(Geometry::Sphere {
                 radius: 0.5,
                 subdivisions: 16,
+                tessellation: SphereTessellation::Uv,
             }),
             color: [0.4, 0.8, 0.4, 1.0],
             transform: Transform::from_position(3.0, 0.0, 0.0),
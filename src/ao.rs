@@ -0,0 +1,330 @@
+//! Per-vertex ambient occlusion, baked on the CPU.
+//!
+//! For every vertex, [`bake_mesh`] casts a fixed, cosine-weighted set of
+//! rays over the hemisphere around the vertex normal and counts those that
+//! hit [`Occluders`] within [`AoSettings::max_distance`].  The occluded
+//! fraction darkens the vertex colour, so creases, corners and contact
+//! points read as shaded without any lighting pass and at no cost per frame.
+//!
+//! The scene bakes its static batches this way when asked to:
+//!
+//! ```no_run
+//! # use vertra::ao::AoSettings;
+//! # fn demo(scene: &mut vertra::scene::Scene) {
+//! scene.set_ambient_occlusion(Some(AoSettings::default().with_max_distance(2.0)));
+//! # }
+//! ```
+//!
+//! Occluders are indexed by a bounding volume hierarchy, so baking scales
+//! with the number of vertices rather than vertices × triangles.  Native
+//! builds spread vertices over one thread per core.
+
+use crate::constants::ao;
+use crate::mesh::MeshData;
+
+/// How [`bake_mesh`] samples and applies occlusion.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AoSettings {
+    /// Rays cast per vertex.  More rays give smoother gradients.
+    pub samples: u32,
+    /// Hits farther than this from the vertex, in world units, do not
+    /// occlude.
+    pub max_distance: f32,
+    /// How much a fully occluded vertex is darkened, from `0.0` (not at all)
+    /// to `1.0` (black).
+    pub strength: f32,
+}
+
+impl Default for AoSettings {
+    fn default() -> Self {
+        Self {
+            samples: ao::DEFAULT_SAMPLES,
+            max_distance: ao::DEFAULT_MAX_DISTANCE,
+            strength: ao::DEFAULT_STRENGTH,
+        }
+    }
+}
+
+impl AoSettings {
+    /// Set the number of rays per vertex.
+    pub fn with_samples(mut self, samples: u32) -> Self {
+        self.samples = samples;
+        self
+    }
+
+    /// Set the distance beyond which geometry does not occlude.
+    pub fn with_max_distance(mut self, max_distance: f32) -> Self {
+        self.max_distance = max_distance;
+        self
+    }
+
+    /// Set how dark a fully occluded vertex becomes.
+    pub fn with_strength(mut self, strength: f32) -> Self {
+        self.strength = strength;
+        self
+    }
+}
+
+/// Triangles that block occlusion rays, with a bounding volume hierarchy
+/// over them.  Both faces of a triangle occlude.
+#[derive(Debug, Clone, Default)]
+pub struct Occluders {
+    triangles: Vec<[[f32; 3]; 3]>,
+    nodes: Vec<Node>,
+}
+
+/// A BVH node: a leaf over `triangles[start..start + count]`, or (with
+/// `count == 0`) an inner node whose children are at `start` and
+/// `start + 1`.
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    min: [f32; 3],
+    max: [f32; 3],
+    start: u32,
+    count: u32,
+}
+
+impl Occluders {
+    /// Gather the triangles of `meshes`, in the space their vertices are
+    /// in.
+    pub fn new<'a>(meshes: impl IntoIterator<Item = &'a MeshData>) -> Self {
+        let mut triangles = Vec::new();
+        for mesh in meshes {
+            triangles.extend(mesh.indices.chunks_exact(3).map(|tri| {
+                [0, 1, 2].map(|k| mesh.vertices[tri[k] as usize].position)
+            }));
+        }
+        let mut occluders = Self { triangles, nodes: Vec::new() };
+        if !occluders.triangles.is_empty() {
+            occluders.nodes.push(Node { min: [0.0; 3], max: [0.0; 3], start: 0, count: 0 });
+            occluders.build(0, 0, occluders.triangles.len());
+        }
+        occluders
+    }
+
+    /// Number of occluding triangles.
+    pub fn len(&self) -> usize {
+        self.triangles.len()
+    }
+
+    /// Whether there is nothing to occlude.
+    pub fn is_empty(&self) -> bool {
+        self.triangles.is_empty()
+    }
+
+    /// Whether a ray from `origin` along the unit vector `direction` hits a
+    /// triangle closer than `max_distance`.
+    pub fn hits(&self, origin: [f32; 3], direction: [f32; 3], max_distance: f32) -> bool {
+        if self.nodes.is_empty() {
+            return false;
+        }
+        let inverse = direction.map(|d| 1.0 / d);
+        let mut stack = vec![0u32];
+        while let Some(index) = stack.pop() {
+            let node = self.nodes[index as usize];
+            if !ray_hits_box(origin, inverse, max_distance, node.min, node.max) {
+                continue;
+            }
+            if node.count == 0 {
+                stack.extend([node.start, node.start + 1]);
+                continue;
+            }
+            let leaf = &self.triangles[node.start as usize..(node.start + node.count) as usize];
+            if leaf.iter().any(|tri| ray_hits_triangle(origin, direction, max_distance, tri)) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Fraction of the rays over the hemisphere around `normal` at `point`
+    /// that are blocked, from `0.0` (open sky) to `1.0` (fully enclosed).
+    pub fn occlusion(&self, point: [f32; 3], normal: [f32; 3], settings: &AoSettings) -> f32 {
+        if settings.samples == 0 {
+            return 0.0;
+        }
+        let (tangent, bitangent) = basis(normal);
+        let origin = add(point, scale(normal, ao::RAY_BIAS));
+        let blocked = (0..settings.samples)
+            .filter(|&i| {
+                let [x, y, z] = hemisphere_sample(i, settings.samples);
+                let direction = add(add(scale(tangent, x), scale(bitangent, y)), scale(normal, z));
+                self.hits(origin, direction, settings.max_distance)
+            })
+            .count();
+        blocked as f32 / settings.samples as f32
+    }
+
+    /// Fit node `index` around `triangles[start..end]` and split it until
+    /// leaves hold at most [`ao::BVH_LEAF_SIZE`] triangles.
+    fn build(&mut self, index: usize, start: usize, end: usize) {
+        let triangles = &mut self.triangles[start..end];
+        let (mut min, mut max) = ([f32::INFINITY; 3], [f32::NEG_INFINITY; 3]);
+        let (mut centroid_min, mut centroid_max) = ([f32::INFINITY; 3], [f32::NEG_INFINITY; 3]);
+        for tri in triangles.iter() {
+            let c = centroid(tri);
+            for k in 0..3 {
+                for p in tri {
+                    min[k] = min[k].min(p[k]);
+                    max[k] = max[k].max(p[k]);
+                }
+                centroid_min[k] = centroid_min[k].min(c[k]);
+                centroid_max[k] = centroid_max[k].max(c[k]);
+            }
+        }
+        if triangles.len() <= ao::BVH_LEAF_SIZE {
+            self.nodes[index] = Node { min, max, start: start as u32, count: triangles.len() as u32 };
+            return;
+        }
+
+        let extent = [0, 1, 2].map(|k| centroid_max[k] - centroid_min[k]);
+        let axis = (0..3).max_by(|&a, &b| extent[a].total_cmp(&extent[b])).unwrap_or(0);
+        let mid = triangles.len() / 2;
+        triangles.select_nth_unstable_by(mid, |a, b| centroid(a)[axis].total_cmp(&centroid(b)[axis]));
+
+        let left = self.nodes.len();
+        let blank = Node { min: [0.0; 3], max: [0.0; 3], start: 0, count: 0 };
+        self.nodes.extend([blank, blank]);
+        self.nodes[index] = Node { min, max, start: left as u32, count: 0 };
+        self.build(left, start, start + mid);
+        self.build(left + 1, start + mid, end);
+    }
+}
+
+/// Darken every vertex colour of `mesh` by its occlusion against
+/// `occluders`, which usually include `mesh` itself.
+///
+/// Vertex normals are the area-weighted average of the faces sharing the
+/// vertex, facing the way the renderer treats as front.
+pub fn bake_mesh(mesh: &mut MeshData, occluders: &Occluders, settings: &AoSettings) {
+    let normals = vertex_normals(mesh);
+    let points: Vec<([f32; 3], [f32; 3])> = mesh.vertices.iter().zip(normals).map(|(v, n)| (v.position, n)).collect();
+    let occlusion_of = |chunk: &[([f32; 3], [f32; 3])]| -> Vec<f32> {
+        chunk
+            .iter()
+            .map(|&(point, normal)| {
+                if normal == [0.0; 3] { 0.0 } else { occluders.occlusion(point, normal, settings) }
+            })
+            .collect()
+    };
+
+    let threads = if cfg!(target_arch = "wasm32") || points.len() < ao::PARALLEL_MIN_VERTICES {
+        1
+    } else {
+        std::thread::available_parallelism().map_or(1, |n| n.get())
+    };
+    let occlusion: Vec<f32> = if threads <= 1 {
+        occlusion_of(&points)
+    } else {
+        let chunk_len = points.len().div_ceil(threads);
+        std::thread::scope(|s| {
+            let workers: Vec<_> = points.chunks(chunk_len).map(|chunk| s.spawn(move || occlusion_of(chunk))).collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().expect("ambient occlusion worker panicked"))
+                .collect()
+        })
+    };
+
+    let strength = settings.strength.clamp(0.0, 1.0);
+    for (vertex, occlusion) in mesh.vertices.iter_mut().zip(occlusion) {
+        let shade = 1.0 - strength * occlusion;
+        vertex.color = vertex.color.map(|c| c * shade);
+    }
+}
+
+/// Unit normal per vertex of `mesh`; zero for vertices on no triangle (or
+/// only degenerate ones).
+fn vertex_normals(mesh: &MeshData) -> Vec<[f32; 3]> {
+    let mut normals = vec![[0.0; 3]; mesh.vertices.len()];
+    for tri in mesh.indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|k| mesh.vertices[tri[k] as usize].position);
+        // Front faces wind clockwise seen from outside in world space.
+        let face = cross(sub(c, a), sub(b, a));
+        for &i in tri {
+            normals[i as usize] = add(normals[i as usize], face);
+        }
+    }
+    normals.into_iter().map(normalize).collect()
+}
+
+/// Direction `i` of `count`, cosine-weighted over the +Z hemisphere.  Uses
+/// a Hammersley set so every vertex samples the same even pattern.
+fn hemisphere_sample(i: u32, count: u32) -> [f32; 3] {
+    let u = (i as f32 + 0.5) / count as f32;
+    let phi = std::f32::consts::TAU * (i.reverse_bits() as f32 / 2f32.powi(32));
+    let r = u.sqrt();
+    [r * phi.cos(), r * phi.sin(), (1.0 - u).sqrt()]
+}
+
+/// Two unit vectors perpendicular to `normal` and each other.
+fn basis(normal: [f32; 3]) -> ([f32; 3], [f32; 3]) {
+    let helper = if normal[0].abs() < 0.9 { [1.0, 0.0, 0.0] } else { [0.0, 1.0, 0.0] };
+    let tangent = normalize(cross(helper, normal));
+    (tangent, cross(normal, tangent))
+}
+
+/// Slab test: does the ray enter the box before `max_distance`?
+fn ray_hits_box(origin: [f32; 3], inverse: [f32; 3], max_distance: f32, min: [f32; 3], max: [f32; 3]) -> bool {
+    let (mut near, mut far) = (0.0f32, max_distance);
+    for k in 0..3 {
+        let t0 = (min[k] - origin[k]) * inverse[k];
+        let t1 = (max[k] - origin[k]) * inverse[k];
+        near = near.max(t0.min(t1));
+        far = far.min(t0.max(t1));
+    }
+    near <= far
+}
+
+/// Möller–Trumbore, accepting either face and hits in `(RAY_BIAS, max_distance)`.
+fn ray_hits_triangle(origin: [f32; 3], direction: [f32; 3], max_distance: f32, tri: &[[f32; 3]; 3]) -> bool {
+    let edge1 = sub(tri[1], tri[0]);
+    let edge2 = sub(tri[2], tri[0]);
+    let p = cross(direction, edge2);
+    let det = dot(edge1, p);
+    if det.abs() < f32::EPSILON {
+        return false;
+    }
+    let inv_det = 1.0 / det;
+    let s = sub(origin, tri[0]);
+    let u = dot(s, p) * inv_det;
+    if !(0.0..=1.0).contains(&u) {
+        return false;
+    }
+    let q = cross(s, edge1);
+    let v = dot(direction, q) * inv_det;
+    if v < 0.0 || u + v > 1.0 {
+        return false;
+    }
+    let t = dot(edge2, q) * inv_det;
+    t > ao::RAY_BIAS && t < max_distance
+}
+
+fn centroid(tri: &[[f32; 3]; 3]) -> [f32; 3] {
+    [0, 1, 2].map(|k| (tri[0][k] + tri[1][k] + tri[2][k]) / 3.0)
+}
+
+fn add(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn scale(a: [f32; 3], s: f32) -> [f32; 3] {
+    a.map(|x| x * s)
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+fn normalize(a: [f32; 3]) -> [f32; 3] {
+    let len = dot(a, a).sqrt();
+    if len > 0.0 { scale(a, 1.0 / len) } else { [0.0; 3] }
+}
@@ -0,0 +1,284 @@
+//! Sound playback (requires the `audio` feature; native targets only).
+//!
+//! [`Audio`] wraps a [rodio](https://docs.rs/rodio) output stream with a
+//! small clip library: load WAV or Ogg Vorbis files once under a name, then
+//! play them as one-shots or loops.  A sound can follow an object, in which
+//! case its volume falls off with the object's distance to the camera
+//! according to [`Attenuation`]:
+//!
+//! ```no_run
+//! # use vertra::audio::PlayOptions;
+//! # fn demo(scene: &mut vertra::scene::Scene, engine_id: usize) -> Result<(), vertra::audio::AudioError> {
+//! let audio = scene.enable_audio()?;
+//! audio.load("engine", "assets/engine.ogg")?;
+//! audio.load("click", "assets/click.wav")?;
+//! audio.play("engine", PlayOptions::default().with_looping(true).with_emitter(engine_id))?;
+//! audio.play("click", PlayOptions::default().with_volume(0.5))?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`crate::window::Window`] calls [`Audio::update`] every frame through
+//! [`crate::scene::Scene::update_audio`].  Attenuation only scales volume;
+//! there is no panning or doppler.
+
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::path::Path;
+use std::sync::Arc;
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink, Source};
+use crate::camera::Camera;
+use crate::constants::audio;
+use crate::editor::math::compute_world_transform;
+use crate::world::World;
+
+/// Errors from [`Audio`].
+#[derive(Debug)]
+pub enum AudioError {
+    /// No output device, or it could not be opened.
+    Device(String),
+    /// Reading a clip file failed.
+    Io(std::io::Error),
+    /// The data is not a supported WAV / Ogg Vorbis stream.
+    Decode(String),
+    /// [`Audio::play`] was given a name that was never loaded.
+    UnknownClip(String),
+}
+
+impl std::fmt::Display for AudioError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AudioError::Device(e) => write!(f, "Audio device error: {e}"),
+            AudioError::Io(e) => write!(f, "I/O error: {e}"),
+            AudioError::Decode(e) => write!(f, "Could not decode audio: {e}"),
+            AudioError::UnknownClip(name) => write!(f, "No audio clip loaded as '{name}'"),
+        }
+    }
+}
+
+impl std::error::Error for AudioError {}
+
+impl From<std::io::Error> for AudioError {
+    fn from(e: std::io::Error) -> Self {
+        AudioError::Io(e)
+    }
+}
+
+/// How a positioned sound gets quieter with distance, following the
+/// inverse-distance model of OpenAL / Web Audio.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Attenuation {
+    /// Distance up to which the sound plays at full volume.
+    pub reference_distance: f32,
+    /// Distance beyond which the sound gets no quieter.
+    pub max_distance: f32,
+    /// How fast the volume drops; `0.0` disables attenuation.
+    pub rolloff: f32,
+}
+
+impl Default for Attenuation {
+    fn default() -> Self {
+        Self {
+            reference_distance: audio::REFERENCE_DISTANCE,
+            max_distance: audio::MAX_DISTANCE,
+            rolloff: audio::ROLLOFF,
+        }
+    }
+}
+
+impl Attenuation {
+    /// Volume factor in `0.0..=1.0` at `distance` from the listener.
+    pub fn gain(&self, distance: f32) -> f32 {
+        let reference = self.reference_distance.max(f32::EPSILON);
+        let d = distance.clamp(reference, self.max_distance.max(reference));
+        reference / (reference + self.rolloff.max(0.0) * (d - reference))
+    }
+}
+
+/// Per-sound settings for [`Audio::play`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlayOptions {
+    /// Volume before attenuation; `1.0` plays the clip as recorded.
+    pub volume: f32,
+    /// Repeat until stopped.
+    pub looping: bool,
+    /// Object whose distance to the camera attenuates the sound.
+    pub emitter: Option<usize>,
+}
+
+impl Default for PlayOptions {
+    fn default() -> Self {
+        Self { volume: 1.0, looping: false, emitter: None }
+    }
+}
+
+impl PlayOptions {
+    /// Play at `volume` (before attenuation).
+    pub fn with_volume(mut self, volume: f32) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    /// Repeat until stopped.
+    pub fn with_looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    /// Attenuate by the distance between object `id` and the camera.
+    pub fn with_emitter(mut self, id: usize) -> Self {
+        self.emitter = Some(id);
+        self
+    }
+}
+
+/// Handle to a playing sound, returned by [`Audio::play`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SoundId(u64);
+
+struct Voice {
+    sink: Sink,
+    volume: f32,
+    emitter: Option<usize>,
+}
+
+/// Output stream, loaded clips and the sounds currently playing.
+///
+/// Usually owned by the scene (see
+/// [`crate::scene::Scene::enable_audio`]); create one directly to manage
+/// audio yourself.
+pub struct Audio {
+    /// Must outlive every sink; dropping it silences all sounds.
+    _stream: OutputStream,
+    handle: OutputStreamHandle,
+    clips: HashMap<String, Arc<[u8]>>,
+    voices: HashMap<SoundId, Voice>,
+    next_id: u64,
+    /// Multiplies the volume of every sound.
+    pub master_volume: f32,
+    /// Distance falloff for sounds with an emitter.
+    pub attenuation: Attenuation,
+}
+
+impl Audio {
+    /// Open the default output device.
+    pub fn new() -> Result<Self, AudioError> {
+        let (stream, handle) = OutputStream::try_default().map_err(|e| AudioError::Device(e.to_string()))?;
+        Ok(Self {
+            _stream: stream,
+            handle,
+            clips: HashMap::new(),
+            voices: HashMap::new(),
+            next_id: 0,
+            master_volume: 1.0,
+            attenuation: Attenuation::default(),
+        })
+    }
+
+    /// Load a WAV or Ogg Vorbis file as clip `name`, replacing any clip of
+    /// that name.  Sounds already playing are unaffected.
+    pub fn load(&mut self, name: &str, path: impl AsRef<Path>) -> Result<(), AudioError> {
+        let bytes = std::fs::read(path)?;
+        self.load_bytes(name, bytes)
+    }
+
+    /// Like [`Self::load`], from encoded file contents in memory.
+    pub fn load_bytes(&mut self, name: &str, bytes: Vec<u8>) -> Result<(), AudioError> {
+        let bytes: Arc<[u8]> = bytes.into();
+        // Decode the header now so bad files fail here, not in `play`.
+        decode(&bytes)?;
+        self.clips.insert(name.to_string(), bytes);
+        Ok(())
+    }
+
+    /// Returns `true` if a clip is loaded as `name`.
+    pub fn has_clip(&self, name: &str) -> bool {
+        self.clips.contains_key(name)
+    }
+
+    /// Start playing clip `name`.
+    pub fn play(&mut self, name: &str, options: PlayOptions) -> Result<SoundId, AudioError> {
+        let bytes = self.clips.get(name).ok_or_else(|| AudioError::UnknownClip(name.to_string()))?;
+        let source = decode(bytes)?;
+        let sink = Sink::try_new(&self.handle).map_err(|e| AudioError::Device(e.to_string()))?;
+        if options.looping {
+            sink.append(source.repeat_infinite());
+        } else {
+            sink.append(source);
+        }
+        // Emitters get their real volume on the next `update`.
+        sink.set_volume(options.volume * self.master_volume);
+        let id = SoundId(self.next_id);
+        self.next_id += 1;
+        self.voices.insert(id, Voice { sink, volume: options.volume, emitter: options.emitter });
+        Ok(id)
+    }
+
+    /// Stop sound `id`.  Returns `false` if it had already finished.
+    pub fn stop(&mut self, id: SoundId) -> bool {
+        self.voices.remove(&id).inspect(|voice| voice.sink.stop()).is_some()
+    }
+
+    /// Stop every sound.
+    pub fn stop_all(&mut self) {
+        for (_, voice) in self.voices.drain() {
+            voice.sink.stop();
+        }
+    }
+
+    /// Change the volume of sound `id`.  Returns `false` if it has finished.
+    pub fn set_volume(&mut self, id: SoundId, volume: f32) -> bool {
+        let master = self.master_volume;
+        self.voices.get_mut(&id).map(|voice| {
+            voice.volume = volume;
+            if voice.emitter.is_none() {
+                voice.sink.set_volume(volume * master);
+            }
+        }).is_some()
+    }
+
+    /// Pause or resume sound `id`.  Returns `false` if it has finished.
+    pub fn set_paused(&mut self, id: SoundId, paused: bool) -> bool {
+        self.voices.get(&id).map(|voice| if paused { voice.sink.pause() } else { voice.sink.play() }).is_some()
+    }
+
+    /// Returns `true` while sound `id` is playing or paused.
+    pub fn is_playing(&self, id: SoundId) -> bool {
+        self.voices.get(&id).is_some_and(|voice| !voice.sink.empty())
+    }
+
+    /// Number of sounds playing or paused.
+    pub fn playing_count(&self) -> usize {
+        self.voices.values().filter(|voice| !voice.sink.empty()).count()
+    }
+
+    /// Forget finished sounds and re-apply volumes, attenuating sounds with
+    /// an emitter by its distance to `camera`.  Sounds whose emitter was
+    /// deleted are stopped.
+    pub fn update(&mut self, camera: &Camera, world: &World) {
+        let (master, attenuation) = (self.master_volume, self.attenuation);
+        self.voices.retain(|_, voice| {
+            if voice.sink.empty() {
+                return false;
+            }
+            let gain = match voice.emitter {
+                None => 1.0,
+                Some(id) if world.objects.contains_key(&id) => {
+                    let p = compute_world_transform(world, id).position;
+                    let d = [0, 1, 2].map(|k| p[k] - camera.eye[k]);
+                    attenuation.gain((d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt())
+                }
+                Some(_) => {
+                    voice.sink.stop();
+                    return false;
+                }
+            };
+            voice.sink.set_volume(voice.volume * master * gain);
+            true
+        });
+    }
+}
+
+fn decode(bytes: &Arc<[u8]>) -> Result<Decoder<Cursor<Arc<[u8]>>>, AudioError> {
+    Decoder::new(Cursor::new(bytes.clone())).map_err(|e| AudioError::Decode(e.to_string()))
+}
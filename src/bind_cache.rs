@@ -0,0 +1,200 @@
+//! Bind group and bind group layout caching.
+//!
+//! [`BindGroupCache`] hands out bind group layouts keyed on their entries and
+//! bind groups keyed on their layout plus the identity of every bound
+//! resource, so asking twice for the same combination returns the same
+//! handle instead of creating a new GPU object.  Lookups that hit do not
+//! allocate, which keeps per-frame binding free of allocations once a scene
+//! has settled.
+//!
+//! Cached bind groups hold their resources alive.  [`BindGroupCache::end_frame`]
+//! drops entries that have not been requested for
+//! [`crate::constants::pipeline::BIND_GROUP_MAX_UNUSED_FRAMES`] frames, so
+//! textures and buffers released by the application are eventually freed.
+//! Handles already returned stay valid after eviction.
+//!
+//! [`crate::pipeline::Pipeline::bind_group`] and
+//! [`crate::pipeline::Pipeline::bind_group_layout`] go through the
+//! pipeline's own cache.
+
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use wgpu::Device;
+use crate::constants::pipeline::BIND_GROUP_MAX_UNUSED_FRAMES;
+
+/// Identity of one bound resource.  wgpu handles compare and hash by the
+/// object they refer to, not by content.
+#[derive(PartialEq, Eq, Hash)]
+enum ResourceKey {
+    Buffer(wgpu::Buffer, wgpu::BufferAddress, Option<wgpu::BufferSize>),
+    Sampler(wgpu::Sampler),
+    TextureView(wgpu::TextureView),
+}
+
+impl ResourceKey {
+    /// Key for `resource`, or `None` for kinds the cache does not track
+    /// (binding arrays, acceleration structures, external textures).
+    fn new(resource: &wgpu::BindingResource) -> Option<Self> {
+        Some(match resource {
+            wgpu::BindingResource::Buffer(b) => Self::Buffer(b.buffer.clone(), b.offset, b.size),
+            wgpu::BindingResource::Sampler(s) => Self::Sampler((*s).clone()),
+            wgpu::BindingResource::TextureView(v) => Self::TextureView((*v).clone()),
+            _ => return None,
+        })
+    }
+
+    /// Returns `true` if `resource` is the resource this key was made from.
+    fn matches(&self, resource: &wgpu::BindingResource) -> bool {
+        match (self, resource) {
+            (Self::Buffer(buffer, offset, size), wgpu::BindingResource::Buffer(b)) => {
+                buffer == b.buffer && *offset == b.offset && *size == b.size
+            }
+            (Self::Sampler(sampler), wgpu::BindingResource::Sampler(s)) => sampler == *s,
+            (Self::TextureView(view), wgpu::BindingResource::TextureView(v)) => view == *v,
+            _ => false,
+        }
+    }
+}
+
+/// Hash of a bind group request, computed from borrowed handles so that a
+/// lookup needs no owned key.  `None` if any resource is untracked.
+fn request_hash(layout: &wgpu::BindGroupLayout, entries: &[wgpu::BindGroupEntry]) -> Option<u64> {
+    let mut hasher = DefaultHasher::new();
+    layout.hash(&mut hasher);
+    for entry in entries {
+        entry.binding.hash(&mut hasher);
+        match &entry.resource {
+            wgpu::BindingResource::Buffer(b) => (0u8, b.buffer, b.offset, b.size).hash(&mut hasher),
+            wgpu::BindingResource::Sampler(s) => (1u8, *s).hash(&mut hasher),
+            wgpu::BindingResource::TextureView(v) => (2u8, *v).hash(&mut hasher),
+            _ => return None,
+        }
+    }
+    Some(hasher.finish())
+}
+
+/// A bind group together with what it was created from.
+struct CachedBindGroup {
+    layout:     wgpu::BindGroupLayout,
+    resources:  Vec<(u32, ResourceKey)>,
+    bind_group: wgpu::BindGroup,
+    /// Frame of the last request; see [`BindGroupCache::end_frame`].
+    last_used:  u64,
+}
+
+impl CachedBindGroup {
+    fn matches(&self, layout: &wgpu::BindGroupLayout, entries: &[wgpu::BindGroupEntry]) -> bool {
+        self.layout == *layout
+            && self.resources.len() == entries.len()
+            && self.resources.iter().zip(entries).all(|((binding, key), entry)| {
+                *binding == entry.binding && key.matches(&entry.resource)
+            })
+    }
+}
+
+/// Creates bind groups and layouts on first request and reuses them after.
+#[derive(Default)]
+pub struct BindGroupCache {
+    layouts:     HashMap<Vec<wgpu::BindGroupLayoutEntry>, wgpu::BindGroupLayout>,
+    /// Buckets of bind groups by [`request_hash`].
+    bind_groups: HashMap<u64, Vec<CachedBindGroup>>,
+    frame:       u64,
+    /// Bind groups created since the last [`Self::take_created`].
+    created:     u32,
+}
+
+impl BindGroupCache {
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The layout with exactly `entries`, created on first request.  `label`
+    /// is only used when the layout is created.
+    pub fn layout(
+        &mut self,
+        device: &Device,
+        label: Option<&str>,
+        entries: &[wgpu::BindGroupLayoutEntry],
+    ) -> wgpu::BindGroupLayout {
+        if let Some(layout) = self.layouts.get(entries) {
+            return layout.clone();
+        }
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor { label, entries });
+        self.layouts.insert(entries.to_vec(), layout.clone());
+        layout
+    }
+
+    /// The bind group of `layout` binding `entries`, created on first
+    /// request.  `label` is only used when the bind group is created.
+    ///
+    /// Bind groups using binding arrays, acceleration structures or external
+    /// textures are created every time.
+    pub fn bind_group(
+        &mut self,
+        device: &Device,
+        label: Option<&str>,
+        layout: &wgpu::BindGroupLayout,
+        entries: &[wgpu::BindGroupEntry],
+    ) -> wgpu::BindGroup {
+        let create = || device.create_bind_group(&wgpu::BindGroupDescriptor { label, layout, entries });
+        let Some(hash) = request_hash(layout, entries) else {
+            self.created += 1;
+            return create();
+        };
+        let bucket = self.bind_groups.entry(hash).or_default();
+        if let Some(cached) = bucket.iter_mut().find(|cached| cached.matches(layout, entries)) {
+            cached.last_used = self.frame;
+            return cached.bind_group.clone();
+        }
+        self.created += 1;
+        let bind_group = create();
+        bucket.push(CachedBindGroup {
+            layout: layout.clone(),
+            resources: entries
+                .iter()
+                .filter_map(|entry| Some((entry.binding, ResourceKey::new(&entry.resource)?)))
+                .collect(),
+            bind_group: bind_group.clone(),
+            last_used: self.frame,
+        });
+        bind_group
+    }
+
+    /// Advance the frame counter and drop bind groups not requested for
+    /// [`BIND_GROUP_MAX_UNUSED_FRAMES`] frames.  Layouts are kept.
+    pub fn end_frame(&mut self) {
+        self.frame += 1;
+        let frame = self.frame;
+        self.bind_groups.retain(|_, bucket| {
+            bucket.retain(|cached| frame - cached.last_used <= BIND_GROUP_MAX_UNUSED_FRAMES);
+            !bucket.is_empty()
+        });
+    }
+
+    /// Number of bind groups created since the last call, cached or not.
+    pub fn take_created(&mut self) -> u32 {
+        std::mem::take(&mut self.created)
+    }
+
+    /// Number of cached bind groups.
+    pub fn len(&self) -> usize {
+        self.bind_groups.values().map(Vec::len).sum()
+    }
+
+    /// Returns `true` if no bind groups are cached.
+    pub fn is_empty(&self) -> bool {
+        self.bind_groups.is_empty()
+    }
+
+    /// Number of cached layouts.
+    pub fn layout_count(&self) -> usize {
+        self.layouts.len()
+    }
+
+    /// Forget every cached bind group and layout.
+    pub fn clear(&mut self) {
+        self.layouts.clear();
+        self.bind_groups.clear();
+    }
+}
@@ -0,0 +1,172 @@
+//! Texture copies, scaled blits and mipmap generation.
+//!
+//! [`Blitter`] draws a source texture into a render target through a
+//! fullscreen pass with a linear sampler, so the target may differ from the
+//! source in size and colour format: downscaled thumbnails, HDR targets
+//! resolved to 8 bits, or each level of a mip chain built from the one
+//! above.  Copies between textures of the same size and format skip the
+//! pass and use `copy_texture_to_texture`.
+//!
+//! Most code goes through the [`crate::pipeline::Pipeline`] wrappers, which
+//! keep one `Blitter` and submit the work:
+//!
+//! | Method                                                | Does                                    |
+//! |-------------------------------------------------------|-----------------------------------------|
+//! | [`crate::pipeline::Pipeline::create_render_target`]   | A texture usable as source and target   |
+//! | [`crate::pipeline::Pipeline::copy_texture`]           | Raw copy if compatible, otherwise blit  |
+//! | [`crate::pipeline::Pipeline::blit`]                   | Fullscreen blit between two views       |
+//! | [`crate::pipeline::Pipeline::generate_mipmaps`]       | Fill levels `1..` from level 0          |
+//!
+//! Sources must have a filterable float format (`Rgba8Unorm`, `Rgba16Float`,
+//! …) and `TEXTURE_BINDING` usage; targets need `RENDER_ATTACHMENT`.
+
+use std::collections::HashMap;
+use wgpu::Device;
+
+/// The blit shader, its sampler and one render pipeline per target format.
+pub struct Blitter {
+    layout:    wgpu::BindGroupLayout,
+    shader:    wgpu::ShaderModule,
+    sampler:   wgpu::Sampler,
+    pipelines: HashMap<wgpu::TextureFormat, wgpu::RenderPipeline>,
+}
+
+impl Blitter {
+    /// Compile the blit shader.  Pipelines are created per target format on
+    /// first use.
+    pub fn new(device: &Device) -> Self {
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("blit_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let shader = device.create_shader_module(wgpu::include_wgsl!("blit.wgsl"));
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("blit_sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        Self { layout, shader, sampler, pipelines: HashMap::new() }
+    }
+
+    /// Record drawing `source` over the whole of `target`, whose texture
+    /// has the format `target_format`.
+    pub fn blit(
+        &mut self,
+        device: &Device,
+        enc: &mut wgpu::CommandEncoder,
+        source: &wgpu::TextureView,
+        target: &wgpu::TextureView,
+        target_format: wgpu::TextureFormat,
+    ) {
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("blit_bind_group"),
+            layout: &self.layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(source) },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&self.sampler) },
+            ],
+        });
+        let pipeline = self.pipeline(device, target_format);
+        let mut rp = enc.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Blit Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: wgpu::Operations { load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT), store: wgpu::StoreOp::Store },
+                depth_slice: None,
+            })],
+            ..Default::default()
+        });
+        rp.set_pipeline(pipeline);
+        rp.set_bind_group(0, &bind_group, &[]);
+        rp.draw(0..3, 0..1);
+    }
+
+    /// Record a copy of level 0 of `source` into level 0 of `target`: a
+    /// raw copy when the sizes match and the formats differ at most in
+    /// sRGB-ness, otherwise a [`Self::blit`].
+    pub fn copy(
+        &mut self,
+        device: &Device,
+        enc: &mut wgpu::CommandEncoder,
+        source: &wgpu::Texture,
+        target: &wgpu::Texture,
+    ) {
+        if source.size() == target.size()
+            && source.format().remove_srgb_suffix() == target.format().remove_srgb_suffix()
+        {
+            enc.copy_texture_to_texture(source.as_image_copy(), target.as_image_copy(), source.size());
+        } else {
+            let source_view = mip_view(source, 0);
+            let target_view = mip_view(target, 0);
+            self.blit(device, enc, &source_view, &target_view, target.format());
+        }
+    }
+
+    /// Record filling mip levels `1..` of `texture` by blitting each level
+    /// from the one above.
+    pub fn generate_mipmaps(&mut self, device: &Device, enc: &mut wgpu::CommandEncoder, texture: &wgpu::Texture) {
+        for level in 1..texture.mip_level_count() {
+            let source = mip_view(texture, level - 1);
+            let target = mip_view(texture, level);
+            self.blit(device, enc, &source, &target, texture.format());
+        }
+    }
+
+    /// The pipeline drawing into `format`, created on first use.
+    fn pipeline(&mut self, device: &Device, format: wgpu::TextureFormat) -> &wgpu::RenderPipeline {
+        self.pipelines.entry(format).or_insert_with(|| {
+            let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Blit Pipeline Layout"),
+                bind_group_layouts: &[Some(&self.layout)],
+                immediate_size: 0,
+            });
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("Blit Pipeline"),
+                layout: Some(&layout),
+                cache: None, multiview_mask: None,
+                vertex: wgpu::VertexState {
+                    module: &self.shader, entry_point: Some("vs_main"),
+                    compilation_options: Default::default(),
+                    buffers: &[],
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &self.shader, entry_point: Some("fs_main"),
+                    compilation_options: Default::default(),
+                    targets: &[Some(wgpu::ColorTargetState { format, blend: None, write_mask: wgpu::ColorWrites::ALL })],
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+            })
+        })
+    }
+}
+
+/// A view of the single mip `level` of `texture`.
+fn mip_view(texture: &wgpu::Texture, level: u32) -> wgpu::TextureView {
+    texture.create_view(&wgpu::TextureViewDescriptor {
+        label: Some("blit_mip_view"),
+        base_mip_level: level,
+        mip_level_count: Some(1),
+        ..Default::default()
+    })
+}
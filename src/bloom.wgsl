@@ -0,0 +1,51 @@
+// Bloom (see `post.rs`).  Run three times into half-resolution targets:
+// with a zero direction it keeps what the HDR scene has above the
+// threshold, then it blurs that horizontally and vertically.  The
+// tonemapper adds the result back.
+
+struct BloomUniform {
+    // x = threshold, y = blur radius in target pixels, zw = blur direction
+    // (zero for the threshold pass).
+    params: vec4<f32>,
+};
+@group(0) @binding(0) var<uniform> bloom: BloomUniform;
+@group(0) @binding(1) var t_source: texture_2d<f32>;
+@group(0) @binding(2) var s_source: sampler;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let x = f32(i32(index & 1u) * 4 - 1);
+    let y = f32(i32(index >> 1u) * 4 - 1);
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(x, y, 0.0, 1.0);
+    out.uv = vec2<f32>((x + 1.0) * 0.5, (1.0 - y) * 0.5);
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let direction = bloom.params.zw;
+    if all(direction == vec2<f32>(0.0)) {
+        // Keep the hue, scaled down to the part above the threshold.
+        let color = textureSample(t_source, s_source, in.uv).rgb;
+        let brightness = max(color.r, max(color.g, color.b));
+        let excess = max(brightness - bloom.params.x, 0.0);
+        return vec4<f32>(color * excess / max(brightness, 1e-4), 1.0);
+    }
+    // 9-tap Gaussian (sigma = 2 taps) spread over the radius, centre first.
+    var weights = array<f32, 5>(0.2042, 0.1802, 0.1238, 0.0663, 0.0276);
+    let step = direction * bloom.params.y * 0.25 / vec2<f32>(textureDimensions(t_source));
+    var sum = textureSample(t_source, s_source, in.uv).rgb * weights[0];
+    for (var i = 1; i < 5; i++) {
+        let offset = step * f32(i);
+        let pair = textureSample(t_source, s_source, in.uv + offset).rgb
+            + textureSample(t_source, s_source, in.uv - offset).rgb;
+        sum += pair * weights[i];
+    }
+    return vec4<f32>(sum, 1.0);
+}
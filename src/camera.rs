@@ -1,10 +1,11 @@
 use std::collections::HashSet;
+use serde::{Deserialize, Serialize};
 use winit::keyboard::KeyCode;
 use crate::math::Matrix4;
-use crate::constants::camera;
+use crate::constants::{camera, layers};
 use crate::window::FrameContext;
 
-/// A perspective camera that defines the observer's position and orientation
+/// A perspective or orthographic camera that defines the observer's position and orientation
 /// in world space, and supplies the view-projection matrix used by the
 /// rendering pipeline.
 ///
@@ -21,7 +22,11 @@ use crate::window::FrameContext;
 ///     .with_fov(60.0)
 ///     .with_rotation(90.0, -20.0);
 /// ```
-#[derive(Debug, Copy, Clone, PartialEq)]
+///
+/// The camera implements serde's `Serialize` / `Deserialize`; fields missing
+/// from the input take their [`Camera::new`] defaults.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Camera {
     /// World-space position of the camera (the "eye" point).
     pub eye: [f32; 3],
@@ -46,6 +51,94 @@ pub struct Camera {
     /// Vertical (pitch) angle in degrees, clamped to `(-89°, 89°)` to prevent
     /// gimbal flip.
     pub ud_rot: f32,
+    /// Render layers this camera draws; an object is visible when
+    /// `object.layer & layers_mask != 0`.  Defaults to [`layers::ALL`].
+    pub layers_mask: u32,
+    /// Linear multiplier applied to the HDR image before tonemapping; with
+    /// auto exposure it is compensation on top of the adapted value.  Has no
+    /// effect unless [`crate::post::PostSettings::hdr`] is on.
+    pub exposure: f32,
+    /// Distance along the view axis that stays sharp with
+    /// [`crate::post::PostSettings::depth_of_field`].
+    pub focus_distance: f32,
+    /// Depth-of-field strength: the blur radius in pixels of points far
+    /// behind the focus plane.  Points nearer than it blur faster, up to 16
+    /// pixels.  `0.0` keeps everything sharp.
+    pub aperture: f32,
+    /// Height in world units of an orthographic view volume; `None` (the
+    /// default) uses the perspective projection and [`Self::fov`].  The
+    /// width follows [`Self::aspect`].
+    pub ortho_height: Option<f32>,
+}
+
+/// The pixel rectangle a camera renders into, for converting between
+/// screen and world coordinates.
+///
+/// Screen coordinates grow right and **down** from the top-left corner of
+/// the window, like cursor positions.  Use physical pixels with
+/// [`crate::scene::Scene::resize`] sizes, or logical pixels with cursor
+/// positions divided by the scale factor — as long as both sides agree.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Viewport {
+    /// Left edge in pixels.
+    pub x: f32,
+    /// Top edge in pixels.
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Viewport {
+    /// A viewport covering a `width` × `height` window from its top-left
+    /// corner.
+    pub fn new(width: f32, height: f32) -> Self {
+        Self { x: 0.0, y: 0.0, width, height }
+    }
+
+    /// Move the viewport's top-left corner to `(x, y)`, e.g. for a camera
+    /// drawing into part of the window.
+    pub fn with_origin(mut self, x: f32, y: f32) -> Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+
+    /// The largest rectangle of aspect ratio `aspect` (`width / height`)
+    /// that fits a `width` × `height` window, centred, with whole-pixel
+    /// edges.  The rest of the window is left for letterbox (top and bottom)
+    /// or pillarbox (left and right) bars.
+    ///
+    /// Returns the whole window if `aspect` is not positive and finite.
+    pub fn letterbox(width: f32, height: f32, aspect: f32) -> Self {
+        if !(aspect.is_finite() && aspect > 0.0) || height <= 0.0 {
+            return Self::new(width, height);
+        }
+        let (w, h) = if width / height > aspect {
+            ((height * aspect).round().max(1.0), height)
+        } else {
+            (width, (width / aspect).round().max(1.0))
+        };
+        Self::new(w, h).with_origin(((width - w) * 0.5).floor(), ((height - h) * 0.5).floor())
+    }
+
+    /// `width / height`, or `1.0` for a viewport with no height.
+    pub fn aspect_ratio(self) -> f32 {
+        if self.height > 0.0 { self.width / self.height } else { 1.0 }
+    }
+
+    /// Map a screen point to normalised device coordinates (`[-1, 1]`, y up).
+    fn to_ndc(self, screen: [f32; 2]) -> [f32; 2] {
+        [
+            (screen[0] - self.x) / self.width * 2.0 - 1.0,
+            1.0 - (screen[1] - self.y) / self.height * 2.0,
+        ]
+    }
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Camera {
@@ -62,6 +155,11 @@ impl Camera {
             zfar: camera::FAR_PLANE,
             lr_rot: camera::DEFAULT_ROTATION,
             ud_rot: camera::DEFAULT_ROTATION,
+            layers_mask: layers::ALL,
+            exposure: camera::DEFAULT_EXPOSURE,
+            focus_distance: camera::DEFAULT_FOCUS_DISTANCE,
+            aperture: camera::DEFAULT_APERTURE,
+            ortho_height: None,
         }
     }
 
@@ -80,6 +178,13 @@ impl Camera {
         self
     }
 
+    /// Switch to an orthographic projection `height` world units tall, or
+    /// back to perspective with `None`.
+    pub fn with_ortho_height(mut self, height: Option<f32>) -> Self {
+        self.ortho_height = height;
+        self
+    }
+
     /// Set the near and far clipping planes.
     ///
     /// * `znear` - objects closer than this distance are clipped.
@@ -90,6 +195,36 @@ impl Camera {
         self
     }
 
+    /// Restrict the camera to the render layers in `mask`.
+    ///
+    /// ```rust,ignore
+    /// // A minimap camera that skips UI and editor gizmos.
+    /// let cam = Camera::new().with_layers_mask(layers::ALL & !(layers::UI | layers::GIZMO));
+    /// ```
+    pub fn with_layers_mask(mut self, mask: u32) -> Self {
+        self.layers_mask = mask;
+        self
+    }
+
+    /// `true` when any bit of `layer` is in [`Self::layers_mask`].
+    pub fn sees_layer(&self, layer: u32) -> bool {
+        self.layers_mask & layer != 0
+    }
+
+    /// Set the exposure multiplier, e.g. `2.0` for one stop brighter.
+    pub fn with_exposure(mut self, exposure: f32) -> Self {
+        self.exposure = exposure;
+        self
+    }
+
+    /// Set the depth-of-field focus distance and aperture (see
+    /// [`Self::focus_distance`] and [`Self::aperture`]).
+    pub fn with_focus(mut self, focus_distance: f32, aperture: f32) -> Self {
+        self.focus_distance = focus_distance;
+        self.aperture = aperture;
+        self
+    }
+
     /// Set the world-space eye position.
     pub fn with_position(mut self, pos: [f32; 3]) -> Self {
         self.eye = pos;
@@ -118,11 +253,77 @@ impl Camera {
     /// NDC clip space.
     pub fn build_view_projection_matrix(&self) -> Matrix4 {
         let view = Matrix4::look_at(self.eye, self.target, self.up);
-        let proj = Matrix4::perspective(self.fov, self.aspect, self.znear, self.zfar);
+        let proj = match self.ortho_height {
+            Some(height) => {
+                let (half_w, half_h) = (height * 0.5 * self.aspect, height * 0.5);
+                Matrix4::orthographic(-half_w, half_w, -half_h, half_h, self.znear, self.zfar)
+            }
+            None => Matrix4::perspective(self.fov, self.aspect, self.znear, self.zfar),
+        };
 
         proj * view
     }
 
+    /// World units covered by one pixel of a view `viewport_height` pixels
+    /// tall, `distance` in front of the camera; the same at every distance
+    /// for orthographic cameras.  Turns a screen-space error into the
+    /// world-space one [`crate::geometry::Accuracy::MaxError`] takes.
+    pub fn pixel_size_at(&self, distance: f32, viewport_height: f32) -> f32 {
+        let height = match self.ortho_height {
+            Some(height) => height,
+            None => 2.0 * distance * (self.fov.to_radians() * 0.5).tan(),
+        };
+        height / viewport_height.max(1.0)
+    }
+
+    /// Project the world-space `point` into `viewport`.
+    ///
+    /// Returns `[x, y, depth]`: the screen position in pixels and the depth
+    /// in `[0, 1]` between the near and far planes (outside that range for
+    /// points beyond them).  `None` when the point is behind the camera.
+    /// Points off screen still project, to coordinates outside the viewport.
+    ///
+    /// ```rust,ignore
+    /// // Pin a label 1 unit above an object.
+    /// let [x, y, _] = camera.world_to_screen([pos[0], pos[1] + 1.0, pos[2]], viewport)?;
+    /// ```
+    pub fn world_to_screen(&self, point: [f32; 3], viewport: Viewport) -> Option<[f32; 3]> {
+        let clip = self.build_view_projection_matrix().mul_vec4([point[0], point[1], point[2], 1.0]);
+        if clip[3] <= 0.0 {
+            return None;
+        }
+        let ndc = [clip[0] / clip[3], clip[1] / clip[3], clip[2] / clip[3]];
+        Some([
+            viewport.x + (ndc[0] + 1.0) * 0.5 * viewport.width,
+            viewport.y + (1.0 - ndc[1]) * 0.5 * viewport.height,
+            ndc[2],
+        ])
+    }
+
+    /// Unproject the screen point `screen` at `depth` (`0.0` on the near
+    /// plane, `1.0` on the far plane) back into world space; the inverse of
+    /// [`Self::world_to_screen`].
+    ///
+    /// `None` if the view-projection matrix cannot be inverted (e.g. a zero
+    /// aspect ratio).
+    pub fn screen_to_world(&self, screen: [f32; 2], depth: f32, viewport: Viewport) -> Option<[f32; 3]> {
+        let [x, y] = viewport.to_ndc(screen);
+        let world = self.build_view_projection_matrix().inverse()?.mul_vec4([x, y, depth, 1.0]);
+        Some([world[0] / world[3], world[1] / world[3], world[2] / world[3]])
+    }
+
+    /// The world-space ray through the screen point `screen`, as
+    /// `(origin, direction)`: it starts on the near plane and its direction
+    /// is unit length.  Intersect it with scene geometry or a ground plane
+    /// to turn clicks into world positions.
+    pub fn screen_ray(&self, screen: [f32; 2], viewport: Viewport) -> Option<([f32; 3], [f32; 3])> {
+        let near = self.screen_to_world(screen, 0.0, viewport)?;
+        let far = self.screen_to_world(screen, 1.0, viewport)?;
+        let d = [far[0] - near[0], far[1] - near[1], far[2] - near[2]];
+        let len = (d[0]*d[0] + d[1]*d[1] + d[2]*d[2]).sqrt();
+        (len > 0.0).then(|| (near, [d[0] / len, d[1] / len, d[2] / len]))
+    }
+
     fn update_target_from_angles(&mut self) {
         let lr_rad = self.lr_rot.to_radians();
         let ud_rad = self.ud_rot.to_radians();
@@ -222,6 +423,39 @@ impl Camera {
         self.target[2] += dz;
     }
 
+    /// Orbit-style zoom: move the eye towards (`steps > 0`) or away from
+    /// the target, keeping the look direction.  Each step covers
+    /// `sensitivity` of the remaining distance (see
+    /// [`DEFAULT_DOLLY_SENSITIVITY`](camera::DEFAULT_DOLLY_SENSITIVITY)),
+    /// so zooming feels the same near and far; the eye stops
+    /// [`MIN_DOLLY_DISTANCE`](camera::MIN_DOLLY_DISTANCE) short of the
+    /// target.  Feed it [`InputEvent::zoom_steps`](crate::replay::InputEvent::zoom_steps).
+    pub fn dolly(&mut self, steps: f32, sensitivity: f32) {
+        let offset = [
+            self.eye[0] - self.target[0],
+            self.eye[1] - self.target[1],
+            self.eye[2] - self.target[2],
+        ];
+        let dist = (offset[0]*offset[0] + offset[1]*offset[1] + offset[2]*offset[2]).sqrt();
+        if dist < f32::EPSILON {
+            return;
+        }
+        let new_dist = (dist * (1.0 - sensitivity).powf(steps)).max(camera::MIN_DOLLY_DISTANCE.min(dist));
+        let scale = new_dist / dist;
+        self.eye = [
+            self.target[0] + offset[0] * scale,
+            self.target[1] + offset[1] * scale,
+            self.target[2] + offset[2] * scale,
+        ];
+    }
+
+    /// Lens-style zoom for first-person cameras: narrow (`steps > 0`) or
+    /// widen the field of view by `degrees_per_step`, within
+    /// [`MIN_FOV`](camera::MIN_FOV)–[`MAX_FOV`](camera::MAX_FOV).
+    pub fn zoom_fov(&mut self, steps: f32, degrees_per_step: f32) {
+        self.fov = (self.fov - steps * degrees_per_step).clamp(camera::MIN_FOV, camera::MAX_FOV);
+    }
+
     /// Process WASD keyboard movement for the current frame.
     ///
     /// Reads `W/A/S/D` from `keys` and moves the camera along the forward /
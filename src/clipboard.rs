@@ -0,0 +1,149 @@
+//! System clipboard access (requires the `clipboard` feature; native
+//! targets only).
+//!
+//! [`Clipboard`] wraps an [arboard](https://docs.rs/arboard) handle with
+//! plain text plus two encodings editor-style apps need: colours as
+//! `#RRGGBBAA` hex, and whole objects as tagged JSON that other vertra apps
+//! recognise on paste.
+//!
+//! ```no_run
+//! # use vertra::clipboard::Clipboard;
+//! # fn demo(scene: &mut vertra::scene::Scene, selected: usize) -> Result<(), vertra::clipboard::ClipboardError> {
+//! let mut clipboard = Clipboard::new()?;
+//! clipboard.copy_object(&scene.world.objects[&selected])?;
+//! // … later, possibly in another app …
+//! let pasted = clipboard.paste_object()?;
+//! scene.spawn(pasted, None);
+//! # Ok(())
+//! # }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::objects::Object;
+
+/// Errors from [`Clipboard`].
+#[derive(Debug)]
+pub enum ClipboardError {
+    /// The system clipboard could not be opened or accessed.
+    Unavailable(String),
+    /// The clipboard holds no text.
+    Empty,
+    /// The clipboard text is not in the requested format.
+    Format(String),
+}
+
+impl std::fmt::Display for ClipboardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unavailable(e) => write!(f, "clipboard unavailable: {e}"),
+            Self::Empty => f.write_str("clipboard holds no text"),
+            Self::Format(e) => write!(f, "unexpected clipboard contents: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ClipboardError {}
+
+impl From<arboard::Error> for ClipboardError {
+    fn from(error: arboard::Error) -> Self {
+        match error {
+            arboard::Error::ContentNotAvailable => Self::Empty,
+            other => Self::Unavailable(other.to_string()),
+        }
+    }
+}
+
+/// Handle to the system clipboard.
+///
+/// Keep one alive for as long as copied data should stay available: on
+/// Linux the clipboard is served by the copying process, so contents may
+/// vanish when the handle is dropped.
+pub struct Clipboard {
+    inner: arboard::Clipboard,
+}
+
+impl Clipboard {
+    /// Open the system clipboard.
+    ///
+    /// # Errors
+    /// [`ClipboardError::Unavailable`] without a clipboard, e.g. on a
+    /// headless Linux machine.
+    pub fn new() -> Result<Self, ClipboardError> {
+        Ok(Self { inner: arboard::Clipboard::new()? })
+    }
+
+    /// The clipboard text.
+    pub fn text(&mut self) -> Result<String, ClipboardError> {
+        Ok(self.inner.get_text()?)
+    }
+
+    /// Replace the clipboard contents with `text`.
+    pub fn set_text(&mut self, text: impl Into<String>) -> Result<(), ClipboardError> {
+        Ok(self.inner.set_text(text.into())?)
+    }
+
+    /// Copy an RGBA colour as `#RRGGBBAA`; see [`format_color`].
+    pub fn copy_color(&mut self, color: [f32; 4]) -> Result<(), ClipboardError> {
+        self.set_text(format_color(color))
+    }
+
+    /// Paste a colour written as hex; see [`parse_color`].
+    pub fn paste_color(&mut self) -> Result<[f32; 4], ClipboardError> {
+        let text = self.text()?;
+        parse_color(&text).ok_or_else(|| ClipboardError::Format(format!("{text:?} is not a hex colour")))
+    }
+
+    /// Copy `object`'s data (not its children); see [`encode_object`].
+    pub fn copy_object(&mut self, object: &Object) -> Result<(), ClipboardError> {
+        self.set_text(encode_object(object))
+    }
+
+    /// Paste an object copied by [`Self::copy_object`], ready to spawn;
+    /// see [`decode_object`].
+    pub fn paste_object(&mut self) -> Result<Object, ClipboardError> {
+        decode_object(&self.text()?)
+    }
+}
+
+/// `color` as `#RRGGBBAA` hex, each channel clamped to `[0, 1]` and rounded
+/// to 8 bits.
+pub fn format_color(color: [f32; 4]) -> String {
+    let [r, g, b, a] = color.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+    format!("#{r:02X}{g:02X}{b:02X}{a:02X}")
+}
+
+/// Parse `#RRGGBB` or `#RRGGBBAA` hex (the `#` is optional, surrounding
+/// whitespace is ignored) into RGBA in `[0, 1]`; alpha defaults to `1.0`.
+pub fn parse_color(text: &str) -> Option<[f32; 4]> {
+    let hex = text.trim();
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok().map(|c| c as f32 / 255.0);
+    Some([channel(0)?, channel(2)?, channel(4)?, if hex.len() == 8 { channel(6)? } else { 1.0 }])
+}
+
+/// Clipboard payload of [`encode_object`], tagged so other JSON is not
+/// mistaken for an object.
+#[derive(Serialize, Deserialize)]
+struct ObjectPayload {
+    #[serde(rename = "vertra.object")]
+    object: Object,
+}
+
+/// `object` as tagged JSON.  Its hierarchy links and registry mesh are not
+/// included, so the text is self-contained.
+pub fn encode_object(object: &Object) -> String {
+    let object = Object { children: Vec::new(), parent: None, mesh: None, ..object.clone() };
+    serde_json::to_string(&ObjectPayload { object }).expect("objects serialise to JSON")
+}
+
+/// Parse text written by [`encode_object`].  The object gets a fresh
+/// [`Object::str_id`], so pasting next to the original keeps ids unique.
+pub fn decode_object(text: &str) -> Result<Object, ClipboardError> {
+    let payload: ObjectPayload = serde_json::from_str(text.trim())
+        .map_err(|e| ClipboardError::Format(format!("not a vertra object: {e}")))?;
+    Ok(Object { str_id: Uuid::new_v4().to_string(), ..payload.object })
+}
@@ -0,0 +1,179 @@
+//! Colour-space conversions and palette helpers.
+//!
+//! Colours throughout the engine are linear RGBA `[f32; 4]` (see
+//! [`Object::color`](crate::objects::Object::color)).  This module converts
+//! them to and from:
+//!
+//! - **HSV / HSL** — hue in degrees `[0, 360)`, saturation and value /
+//!   lightness in `[0, 1]`, computed directly on the RGB components; handy
+//!   for "same colour, brighter" or hue-indexed debug colours
+//! - **OKLab** — a perceptual space where straight-line interpolation gives
+//!   even-looking gradients without the muddy midpoints of RGB lerps
+//!
+//! Palette helpers ([`rotate_hue`], [`analogous`], [`lerp_oklab`],
+//! [`gradient`], [`distinct`]) keep the alpha channel of their input.
+//!
+//! ```
+//! use vertra::color;
+//! let base = color::hsv(200.0, 0.6, 0.9);
+//! let palette = color::analogous(base, 5, 60.0);
+//! assert_eq!(palette.len(), 5);
+//! let mid = color::lerp_oklab(palette[0], palette[4], 0.5);
+//! assert_eq!(mid[3], 1.0);
+//! ```
+
+/// Opaque colour from hue (degrees), saturation and value.
+pub fn hsv(hue: f32, saturation: f32, value: f32) -> [f32; 4] {
+    let [r, g, b] = hsv_to_rgb([hue, saturation, value]);
+    [r, g, b, 1.0]
+}
+
+/// Opaque colour from hue (degrees), saturation and lightness.
+pub fn hsl(hue: f32, saturation: f32, lightness: f32) -> [f32; 4] {
+    let [r, g, b] = hsl_to_rgb([hue, saturation, lightness]);
+    [r, g, b, 1.0]
+}
+
+/// Hue in degrees `[0, 360)` plus the chroma, max and min components of
+/// `rgb`.  Greys report hue `0`.
+fn hue_chroma([r, g, b]: [f32; 3]) -> (f32, f32, f32, f32) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let chroma = max - min;
+    let hue = if chroma <= 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / chroma).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / chroma + 2.0)
+    } else {
+        60.0 * ((r - g) / chroma + 4.0)
+    };
+    (hue, chroma, max, min)
+}
+
+/// RGB for `hue` (degrees, wrapped) at `chroma`, offset by `m` on every
+/// channel.
+fn from_hue_chroma(hue: f32, chroma: f32, m: f32) -> [f32; 3] {
+    let h = hue.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (h.rem_euclid(2.0) - 1.0).abs());
+    let [r, g, b] = match h as u32 {
+        0 => [chroma, x, 0.0],
+        1 => [x, chroma, 0.0],
+        2 => [0.0, chroma, x],
+        3 => [0.0, x, chroma],
+        4 => [x, 0.0, chroma],
+        _ => [chroma, 0.0, x],
+    };
+    [r + m, g + m, b + m]
+}
+
+/// Convert RGB to `[hue, saturation, value]`.
+pub fn rgb_to_hsv(rgb: [f32; 3]) -> [f32; 3] {
+    let (hue, chroma, max, _) = hue_chroma(rgb);
+    let saturation = if max > 0.0 { chroma / max } else { 0.0 };
+    [hue, saturation, max]
+}
+
+/// Convert `[hue, saturation, value]` to RGB.  Hue wraps and saturation is
+/// clamped to `[0, 1]`; values above `1` give HDR colours.
+pub fn hsv_to_rgb([hue, saturation, value]: [f32; 3]) -> [f32; 3] {
+    let value = value.max(0.0);
+    let chroma = value * saturation.clamp(0.0, 1.0);
+    from_hue_chroma(hue, chroma, value - chroma)
+}
+
+/// Convert RGB to `[hue, saturation, lightness]`.
+pub fn rgb_to_hsl(rgb: [f32; 3]) -> [f32; 3] {
+    let (hue, chroma, max, min) = hue_chroma(rgb);
+    let lightness = (max + min) * 0.5;
+    let denominator = 1.0 - (2.0 * lightness - 1.0).abs();
+    let saturation = if denominator > 0.0 { chroma / denominator } else { 0.0 };
+    [hue, saturation, lightness]
+}
+
+/// Convert `[hue, saturation, lightness]` to RGB.  Hue wraps; saturation
+/// and lightness are clamped to `[0, 1]`.
+pub fn hsl_to_rgb([hue, saturation, lightness]: [f32; 3]) -> [f32; 3] {
+    let lightness = lightness.clamp(0.0, 1.0);
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation.clamp(0.0, 1.0);
+    from_hue_chroma(hue, chroma, lightness - chroma * 0.5)
+}
+
+/// Convert linear RGB to OKLab `[L, a, b]`.
+pub fn rgb_to_oklab([r, g, b]: [f32; 3]) -> [f32; 3] {
+    let l = (0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b).cbrt();
+    let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+    let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+    [
+        0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+        1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+        0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+    ]
+}
+
+/// Convert OKLab `[L, a, b]` to linear RGB.  Out-of-gamut results are not
+/// clamped.
+pub fn oklab_to_rgb([l, a, b]: [f32; 3]) -> [f32; 3] {
+    let l_ = l + 0.396_337_78 * a + 0.215_803_76 * b;
+    let m_ = l - 0.105_561_346 * a - 0.063_854_17 * b;
+    let s_ = l - 0.089_484_18 * a - 1.291_485_5 * b;
+    let (l, m, s) = (l_ * l_ * l_, m_ * m_ * m_, s_ * s_ * s_);
+    [
+        4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s,
+        -1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s,
+        -0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s,
+    ]
+}
+
+/// Interpolate from `a` (`t = 0`) to `b` (`t = 1`) through OKLab, clamped
+/// to the displayable range.  Alpha is interpolated linearly.
+pub fn lerp_oklab(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
+    let from = rgb_to_oklab([a[0], a[1], a[2]]);
+    let to = rgb_to_oklab([b[0], b[1], b[2]]);
+    let [r, g, bl] = oklab_to_rgb([0, 1, 2].map(|k| from[k] + (to[k] - from[k]) * t))
+        .map(|c| c.clamp(0.0, 1.0));
+    [r, g, bl, a[3] + (b[3] - a[3]) * t]
+}
+
+/// `count` colours evenly spaced from `a` to `b` (both included) in OKLab.
+/// A single colour is `a`.
+pub fn gradient(a: [f32; 4], b: [f32; 4], count: usize) -> Vec<[f32; 4]> {
+    match count {
+        0 => Vec::new(),
+        1 => vec![a],
+        _ => (0..count).map(|i| lerp_oklab(a, b, i as f32 / (count - 1) as f32)).collect(),
+    }
+}
+
+/// `color` with its hue rotated by `degrees`, keeping saturation, value and
+/// alpha.
+pub fn rotate_hue(color: [f32; 4], degrees: f32) -> [f32; 4] {
+    let [h, s, v] = rgb_to_hsv([color[0], color[1], color[2]]);
+    let [r, g, b] = hsv_to_rgb([h + degrees, s, v]);
+    [r, g, b, color[3]]
+}
+
+/// `count` analogous colours: hues spread evenly across `spread` degrees
+/// centred on `color`'s hue, in ascending hue order.
+pub fn analogous(color: [f32; 4], count: usize, spread: f32) -> Vec<[f32; 4]> {
+    if count <= 1 {
+        return vec![color; count];
+    }
+    let step = spread / (count - 1) as f32;
+    (0..count).map(|i| rotate_hue(color, -spread * 0.5 + step * i as f32)).collect()
+}
+
+/// The complementary colour (hue rotated by 180°).
+pub fn complementary(color: [f32; 4]) -> [f32; 4] {
+    rotate_hue(color, 180.0)
+}
+
+/// `count` colours with hues spaced around the wheel by the golden angle,
+/// so consecutive entries always differ strongly — useful for colouring
+/// ids, clusters or debug categories.  Saturation and value are fixed.
+pub fn distinct(count: usize, saturation: f32, value: f32) -> Vec<[f32; 4]> {
+    /// 360° · (1 − 1/φ).
+    const GOLDEN_ANGLE: f32 = 137.507_76;
+    (0..count).map(|i| hsv(i as f32 * GOLDEN_ANGLE, saturation, value)).collect()
+}
@@ -3,7 +3,9 @@
 //! Grouped into sub-modules by subsystem:
 //! * [`window`] - default window size and fixed-update rate.
 //! * [`camera`] - default camera placement and projection parameters.
-//! * [`pipeline`] - initial GPU buffer allocation sizes.
+//! * [`pipeline`] - initial GPU buffer allocation sizes and adapter-selection
+//!   environment variables.
+//! * [`watch`] - file-watcher polling rate.
 
 /// Default windowing constants.
 pub mod window {
@@ -15,6 +17,19 @@ pub mod window {
     pub const DEFAULT_HEIGHT: u32 = 600;
     /// Fixed-update timestep in seconds (1 / 60 or approximately 16.67 ms).
     pub const FIXED_DELTA: f32 = 1.0 / 60.0;
+    /// Longest frame delta in seconds passed to updates.  Longer stalls
+    /// (a debugger break, a blocked main thread) are not caught up on, so
+    /// fixed updates never run more than a quarter second's worth at once.
+    pub const MAX_FRAME_DELTA: f32 = 0.25;
+    /// Environment variable overriding the initial size (`1920x1080`).
+    pub const SIZE_ENV: &str = "VERTRA_SIZE";
+    /// Environment variable opening the window borderless fullscreen.
+    pub const FULLSCREEN_ENV: &str = "VERTRA_FULLSCREEN";
+    /// Environment variable choosing the monitor to open on, by index.
+    pub const MONITOR_ENV: &str = "VERTRA_MONITOR";
+    /// Seconds [`crate::window::Window`] waits before redrawing after a
+    /// frame timed out.
+    pub const RENDER_TIMEOUT_BACKOFF_SECS: f32 = 0.1;
 }
 
 /// Default camera constants.
@@ -35,6 +50,73 @@ pub mod camera {
     pub const FAR_PLANE: f32 = 1000.0;
     /// Default yaw and pitch rotation in degrees.
     pub const DEFAULT_ROTATION: f32 = 0.0;
+    /// Default exposure multiplier applied before tonemapping.
+    pub const DEFAULT_EXPOSURE: f32 = 1.0;
+    /// Default depth-of-field focus distance in world units.
+    pub const DEFAULT_FOCUS_DISTANCE: f32 = 10.0;
+    /// Default depth-of-field aperture (blur radius in pixels far behind focus).
+    pub const DEFAULT_APERTURE: f32 = 4.0;
+    /// Fraction of the eye–target distance covered by one scroll step in
+    /// [`Camera::dolly`](crate::camera::Camera::dolly).
+    pub const DEFAULT_DOLLY_SENSITIVITY: f32 = 0.12;
+    /// Closest [`Camera::dolly`](crate::camera::Camera::dolly) gets to the target.
+    pub const MIN_DOLLY_DISTANCE: f32 = 0.3;
+    /// Narrowest field of view [`Camera::zoom_fov`](crate::camera::Camera::zoom_fov) allows, in degrees.
+    pub const MIN_FOV: f32 = 5.0;
+    /// Widest field of view [`Camera::zoom_fov`](crate::camera::Camera::zoom_fov) allows, in degrees.
+    pub const MAX_FOV: f32 = 120.0;
+    /// Pixel-precise (touchpad) scroll pixels per wheel step.
+    pub const PIXELS_PER_SCROLL_STEP: f32 = 10.0;
+    /// Wheel steps per unit of touchpad pinch (where `1.0` doubles the
+    /// pinch scale).
+    pub const PINCH_SCROLL_STEPS: f32 = 10.0;
+}
+
+/// Culling debug defaults (see [`crate::frustum::CullingDebug`]).
+pub mod frustum {
+    /// Width of [`crate::frustum::CullingDebug`] lines in pixels.
+    pub const LINE_WIDTH: f32 = 2.0;
+    /// Colour of the scene camera's frustum lines.
+    pub const CAMERA_COLOR: [f32; 4] = [1.0, 1.0, 0.2, 1.0];
+    /// Colour of the frozen camera's frustum lines.
+    pub const FROZEN_COLOR: [f32; 4] = [0.2, 1.0, 1.0, 1.0];
+    /// Colour mixed into culled objects; alpha is how much.
+    pub const CULLED_TINT: [f32; 4] = [1.0, 0.0, 1.0, 0.6];
+}
+
+/// Occlusion query constants (see [`crate::occlusion`]).
+pub mod occlusion {
+    /// Most objects queried in one frame; flagged objects beyond it are
+    /// always drawn.
+    pub const MAX_QUERIES: u32 = 4096;
+    /// Boxes a new [`crate::occlusion::OcclusionQueries`] holds before its
+    /// first reallocation.
+    pub const INITIAL_CAPACITY: u32 = 64;
+    /// Padding around each query box as a fraction of its largest side, so
+    /// faces that coincide with the object's own surface still pass the
+    /// depth test.
+    pub const BOX_MARGIN: f32 = 0.01;
+}
+
+/// Thick line and grid defaults (see [`crate::lines`]).
+pub mod lines {
+    /// Width of a default [`crate::lines::LineStyle`] in pixels.
+    pub const WIDTH: f32 = 2.0;
+    /// Longest miter, in line widths, before a joint is bevelled instead.
+    pub const MITER_LIMIT: f32 = 4.0;
+    /// Segments the line instance buffer holds before its first
+    /// reallocation.
+    pub const INITIAL_CAPACITY: u64 = 256;
+    /// World units between [`crate::lines::Grid`] lines.
+    pub const GRID_SPACING: f32 = 1.0;
+    /// Grid cells drawn on each side of the grid's centre.
+    pub const GRID_CELLS: u32 = 50;
+    /// Every this many lines, a grid line is drawn in the major colour.
+    pub const GRID_MAJOR_EVERY: u32 = 10;
+    /// Colour of minor grid lines.
+    pub const GRID_COLOR: [f32; 4] = [0.5, 0.5, 0.5, 0.35];
+    /// Colour of major grid lines.
+    pub const GRID_MAJOR_COLOR: [f32; 4] = [0.7, 0.7, 0.7, 0.7];
 }
 
 /// Default GPU pipeline constants.
@@ -43,9 +125,287 @@ pub mod pipeline {
     pub const INITIAL_VERTEX_LIMIT: u32 = 128;
     /// Initial capacity of the GPU index buffer in indices.
     pub const INITIAL_INDEX_LIMIT: u32 = 1024;
+    /// Environment variable restricting the wgpu backends (`vulkan,gl`, …).
+    pub const BACKEND_ENV: &str = "VERTRA_BACKEND";
+    /// Environment variable forcing a software (fallback) adapter.
+    pub const FORCE_FALLBACK_ADAPTER_ENV: &str = "VERTRA_FORCE_FALLBACK_ADAPTER";
+    /// Environment variable turning vertical sync on or off.
+    pub const VSYNC_ENV: &str = "VERTRA_VSYNC";
+    /// Frames a cached bind group may go unrequested before
+    /// [`crate::bind_cache::BindGroupCache::end_frame`] drops it.
+    pub const BIND_GROUP_MAX_UNUSED_FRAMES: u64 = 300;
+    /// Objects a new [`crate::instances::InstanceBuffer`] holds before its
+    /// first reallocation.
+    pub const INITIAL_INSTANCE_CAPACITY: u32 = 1024;
+    /// Colour of the bars around a fixed-aspect viewport (see
+    /// [`crate::pipeline::Pipeline::set_fixed_aspect`]).
+    pub const LETTERBOX_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+    /// Consecutive suboptimal frames presented before the surface is
+    /// reconfigured.
+    pub const SUBOPTIMAL_RECONFIGURE_FRAMES: u32 = 3;
+    /// Undrained [`crate::pipeline::SurfaceEvent`]s kept; older ones are
+    /// dropped first.
+    pub const MAX_SURFACE_EVENTS: usize = 64;
 }
 
 pub mod frame_stats {
     /// Width of the sampling window in seconds.
     pub const DEFAULT_SAMPLE_WINDOW_SECS: f32 = 0.5;
-}
\ No newline at end of file
+}
+/// File-watching constants.
+pub mod watch {
+    /// Minimum time between two file-system polls in seconds.
+    pub const DEFAULT_POLL_INTERVAL_SECS: f32 = 0.25;
+}
+/// Scene-management constants.
+pub mod scene {
+    /// ID of the scene a [`crate::scene::Scene`] starts with in its
+    /// [`crate::scene_manager::SceneManager`].
+    pub const MAIN_SCENE_ID: &str = "main";
+    /// Object count below which [`crate::scene::Scene::draw_world`] prepares
+    /// its draw list on the calling thread; handing work to the workers costs
+    /// more than it saves in smaller worlds.
+    pub const PARALLEL_PREP_MIN_OBJECTS: usize = 4096;
+}
+/// Render-layer bits for [`crate::objects::Object::layer`] and
+/// [`crate::camera::Camera::layers_mask`].
+pub mod layers {
+    /// Layer every object starts on.
+    pub const DEFAULT: u32 = 1 << 0;
+    /// Mirror and portal surfaces, hidden from their own render passes.
+    pub const MIRROR: u32 = 1 << 29;
+    /// Screen-space UI geometry.
+    pub const UI: u32 = 1 << 30;
+    /// Editor gizmos and other debug overlays.
+    pub const GIZMO: u32 = 1 << 31;
+    /// Mask that renders every layer.
+    pub const ALL: u32 = u32::MAX;
+}
+/// Post-processing constants.
+pub mod post {
+    /// Lower end of the auto-exposure histogram, in log2 luminance.
+    pub const MIN_LOG_LUMINANCE: f32 = -8.0;
+    /// Upper end of the auto-exposure histogram, in log2 luminance.
+    pub const MAX_LOG_LUMINANCE: f32 = 4.0;
+    /// Auto-exposure adaptation rate (1/s); higher reacts faster.
+    pub const ADAPTATION_SPEED: f32 = 1.5;
+    /// Average luminance auto exposure maps to (middle grey).
+    pub const EXPOSURE_KEY: f32 = 0.18;
+    /// Brightness above which bloom picks pixels up: only light past
+    /// display white glows.
+    pub const BLOOM_THRESHOLD: f32 = 1.0;
+    /// How strongly the blurred bloom is added back.
+    pub const BLOOM_INTENSITY: f32 = 0.6;
+    /// Bloom blur reach, in pixels of the half-resolution bloom target.
+    pub const BLOOM_RADIUS: f32 = 8.0;
+}
+
+/// Procedural geometry constants.
+pub mod geometry {
+    /// Radial segments of a [`crate::geometry::Geometry::Arrow`].
+    pub const ARROW_SEGMENTS: usize = 16;
+    /// Shaft radius of [`crate::geometry::Geometry::build_axes`] arrows, as
+    /// a fraction of their length.
+    pub const AXES_SHAFT_RATIO: f32 = 0.02;
+    /// Head length of [`crate::geometry::Geometry::build_axes`] arrows, as a
+    /// fraction of their length.
+    pub const AXES_HEAD_RATIO: f32 = 0.2;
+    /// Fewest subdivisions [`crate::geometry::Geometry::with_accuracy`]
+    /// picks.
+    pub const MIN_ADAPTIVE_SUBDIVISIONS: usize = 4;
+    /// Most subdivisions [`crate::geometry::Geometry::with_accuracy`] picks,
+    /// about 65k triangles for a UV sphere.
+    pub const MAX_ADAPTIVE_SUBDIVISIONS: usize = 256;
+    /// Edge length of an icosahedron inscribed in the unit sphere.
+    pub const ICOSAHEDRON_EDGE: f32 = 1.051_462_2;
+    /// Distance from the centre to each face of that icosahedron.
+    pub const ICOSAHEDRON_INRADIUS: f32 = 0.794_654_5;
+}
+
+/// Lighting constants.
+pub mod lighting {
+    /// Default flat ambient colour; white leaves unlit colours unchanged.
+    pub const DEFAULT_AMBIENT: [f32; 3] = [1.0, 1.0, 1.0];
+}
+
+/// Ambient occlusion baking constants.
+pub mod ao {
+    /// Rays cast per vertex by default.
+    pub const DEFAULT_SAMPLES: u32 = 32;
+    /// Default reach of occlusion rays in world units.
+    pub const DEFAULT_MAX_DISTANCE: f32 = 1.0;
+    /// Default darkening of a fully occluded vertex.
+    pub const DEFAULT_STRENGTH: f32 = 0.8;
+    /// Offset of ray origins along the vertex normal, and the nearest hit
+    /// that counts, so a surface does not occlude itself.
+    pub const RAY_BIAS: f32 = 1.0e-4;
+    /// Most triangles in one leaf of the occluder hierarchy.
+    pub const BVH_LEAF_SIZE: usize = 4;
+    /// Vertex count below which baking stays on the calling thread.
+    pub const PARALLEL_MIN_VERTICES: usize = 1024;
+}
+
+/// Defaults of the ready-made objects in [`crate::presets`].
+pub mod presets {
+    /// Grid cells per side of [`crate::presets::ground_plane`].
+    pub const GROUND_SUBDIVISIONS: usize = 8;
+    /// Colour of [`crate::presets::ground_plane`].
+    pub const GROUND_COLOR: [f32; 4] = [0.45, 0.47, 0.5, 1.0];
+    /// Radius of [`crate::presets::point_light_marker`].
+    pub const LIGHT_MARKER_RADIUS: f32 = 0.1;
+    /// Colour of [`crate::presets::point_light_marker`].
+    pub const LIGHT_MARKER_COLOR: [f32; 4] = [1.0, 0.9, 0.4, 1.0];
+    /// Half the edge length of [`crate::presets::skybox_mesh`]; inside the
+    /// default far plane from anywhere near the origin.
+    pub const SKYBOX_HALF_EXTENT: f32 = 450.0;
+    /// Overhead colour of [`crate::scene::Scene::spawn_skybox`].
+    pub const SKY_TOP: [f32; 4] = [0.35, 0.55, 0.85, 1.0];
+    /// Wall colour of [`crate::scene::Scene::spawn_skybox`].
+    pub const SKY_HORIZON: [f32; 4] = [0.7, 0.8, 0.92, 1.0];
+    /// Underfoot colour of [`crate::scene::Scene::spawn_skybox`].
+    pub const SKY_BOTTOM: [f32; 4] = [0.3, 0.3, 0.32, 1.0];
+}
+
+/// Screen overlay constants.
+pub mod overlay {
+    /// Overlay elements are clipped beyond this distance from `z = 0`, in
+    /// pixels.  Depth is otherwise ignored.
+    pub const DEPTH_RANGE: f32 = 1.0e4;
+    /// Side of the square a [`crate::view_axes::ViewAxes`] widget fills, in
+    /// logical pixels.
+    pub const VIEW_AXES_SIZE: f32 = 96.0;
+    /// Gap between the widget and the window corner, in logical pixels.
+    pub const VIEW_AXES_MARGIN: f32 = 16.0;
+    /// Thickness of the widget's axis lines, in logical pixels.
+    pub const VIEW_AXES_LINE_WIDTH: f32 = 3.0;
+    /// Radius of the clickable discs at the axis ends, in logical pixels.
+    pub const VIEW_AXES_CAP_RADIUS: f32 = 9.0;
+    /// Colours of the X, Y and Z axes, as in
+    /// [`crate::geometry::Geometry::build_axes`].
+    pub const VIEW_AXES_COLORS: [[f32; 4]; 3] = [
+        [0.95, 0.15, 0.15, 1.0],
+        [0.15, 0.95, 0.15, 1.0],
+        [0.15, 0.15, 0.95, 1.0],
+    ];
+    /// Brightness of the negative-axis discs relative to the positive ones.
+    pub const VIEW_AXES_NEGATIVE_SHADE: f32 = 0.45;
+}
+
+/// Sprite animation constants.
+pub mod sprite {
+    /// Frame rate of a new [`crate::sprite::AnimatedSprite`].
+    pub const DEFAULT_FPS: f32 = 12.0;
+}
+
+/// Audio constants.
+pub mod audio {
+    /// Distance up to which positioned sounds play at full volume.
+    pub const REFERENCE_DISTANCE: f32 = 1.0;
+    /// Distance beyond which positioned sounds get no quieter.
+    pub const MAX_DISTANCE: f32 = 100.0;
+    /// Default rolloff factor of the inverse-distance model.
+    pub const ROLLOFF: f32 = 1.0;
+}
+
+/// Touch gesture constants.
+pub mod touch {
+    /// Furthest a finger may move, in physical pixels, and still count as
+    /// a [`crate::touch::Gesture::Tap`].
+    pub const TAP_SLOP: f32 = 12.0;
+}
+
+/// Seeded random number constants.
+pub mod rng {
+    /// Seed of every new [`crate::scene::Scene::rng`].
+    pub const DEFAULT_SEED: u64 = 0x5EED;
+    /// Saturation of [`crate::rng::SceneRng::color`].
+    pub const RANDOM_COLOR_SATURATION: f32 = 0.65;
+    /// Value (brightness) of [`crate::rng::SceneRng::color`].
+    pub const RANDOM_COLOR_VALUE: f32 = 0.9;
+}
+
+/// Editor gizmo snapping defaults (see [`crate::editor::SnapSettings`]).
+pub mod editor {
+    /// Grid spacing translate drags snap to, in world units.
+    pub const SNAP_GRID: f32 = 0.5;
+    /// Angle rotate drags snap to, in degrees.
+    pub const SNAP_ANGLE: f32 = 15.0;
+    /// Increment scale drags snap to.
+    pub const SNAP_SCALE: f32 = 0.25;
+}
+
+/// Water surface defaults (see [`crate::water::Water`]).
+pub mod water {
+    /// Waves the water shader sums; extra [`crate::water::Water::waves`]
+    /// are ignored.
+    pub const MAX_WAVES: usize = 4;
+    /// Side length of the water plane, in world units.
+    pub const DEFAULT_SIZE: f32 = 200.0;
+    /// Grid cells along each side of the water plane.
+    pub const DEFAULT_RESOLUTION: u32 = 128;
+    /// Colour looking straight down into the water; alpha is its opacity.
+    pub const DEEP_COLOR: [f32; 4] = [0.02, 0.12, 0.2, 0.85];
+    /// Colour at grazing angles when reflections are off.
+    pub const FRESNEL_COLOR: [f32; 4] = [0.55, 0.7, 0.8, 1.0];
+    /// Exponent of the Schlick-style fresnel term; higher keeps the tint
+    /// closer to the horizon.
+    pub const FRESNEL_POWER: f32 = 5.0;
+    /// Reflection target size as a fraction of the viewport.
+    pub const REFLECTION_SCALE: f32 = 0.5;
+    /// How far surface normals shift the reflection lookup, in UV units.
+    pub const DISTORTION: f32 = 0.03;
+    /// Gravity used to derive the speed of [`crate::water::Wave::new`]
+    /// waves from their wavelength (m/s²).
+    pub const GRAVITY: f32 = 9.81;
+}
+
+/// Minimap defaults (see [`crate::minimap::Minimap`]).
+pub mod minimap {
+    /// Side of the square the map fills, in logical pixels.
+    pub const SIZE: f32 = 192.0;
+    /// Gap between the map and the window corner, in logical pixels.
+    pub const MARGIN: f32 = 16.0;
+    /// World units shown across the map's height.
+    pub const SPAN: f32 = 60.0;
+    /// Narrowest span [`Minimap::zoom`](crate::minimap::Minimap::zoom) allows.
+    pub const MIN_SPAN: f32 = 1.0;
+    /// Widest span [`Minimap::zoom`](crate::minimap::Minimap::zoom) allows.
+    pub const MAX_SPAN: f32 = 10_000.0;
+    /// World height of the map camera; geometry above it is not drawn.
+    pub const ALTITUDE: f32 = 500.0;
+    /// Distance below [`ALTITUDE`] the map camera sees down to.
+    pub const DEPTH: f32 = 1000.0;
+    /// Length of the camera marker from tip to base, in logical pixels.
+    pub const MARKER_SIZE: f32 = 12.0;
+    /// Colour of the camera marker.
+    pub const MARKER_COLOR: [f32; 4] = [1.0, 0.85, 0.2, 1.0];
+}
+
+/// Mirror and portal defaults (see [`crate::mirror::Mirror`]).
+pub mod mirror {
+    /// Height of a mirror's render texture in pixels; the width follows the
+    /// mirror's aspect ratio.
+    pub const DEFAULT_RESOLUTION: u32 = 512;
+    /// Largest side of a mirror's render texture, in pixels.
+    pub const MAX_RESOLUTION: u32 = 4096;
+}
+
+/// Voxel grid defaults (see [`crate::voxel`]).
+pub mod voxel {
+    /// Blocks per side of a chunk; each chunk is baked into one mesh.
+    pub const CHUNK_SIZE: usize = 16;
+}
+
+/// World streaming defaults (see [`crate::streaming`]).
+pub mod streaming {
+    /// Background threads generating cells.
+    pub const WORKERS: usize = 2;
+    /// Cells queued per worker thread.  A short queue keeps the cells
+    /// nearest a moving camera generated first.
+    pub const JOBS_PER_WORKER: usize = 2;
+    /// Generated cells baked into the scene per update.
+    pub const BAKE_BUDGET: usize = 4;
+    /// Cells a loaded cell may drift past the load radius before it
+    /// unloads.
+    pub const UNLOAD_MARGIN: f32 = 1.0;
+}
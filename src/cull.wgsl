@@ -0,0 +1,91 @@
+// GPU frustum culling for instanced draws.  One invocation per candidate
+// instance: survivors are appended to their draw group's range of
+// `visible` and counted in its indirect draw arguments.  See `culling.rs`.
+
+struct Instance {
+    model: mat4x4<f32>,
+    color: vec4<f32>,
+};
+
+// One instanced draw: a mesh's bounding sphere, its instance range and how
+// far displacement moves its vertices.
+struct CullGroup {
+    sphere: vec4<f32>,
+    first: u32,
+    count: u32,
+    index_count: u32,
+    reach: f32,
+};
+
+// Layout of `wgpu::util::DrawIndexedIndirectArgs`.
+struct DrawArgs {
+    index_count: u32,
+    instance_count: atomic<u32>,
+    first_index: u32,
+    base_vertex: i32,
+    first_instance: u32,
+};
+
+struct CullParams {
+    view_proj: mat4x4<f32>,
+    instance_count: u32,
+    group_count: u32,
+    _pad: vec2<u32>,
+};
+
+@group(0) @binding(0) var<uniform> params: CullParams;
+@group(0) @binding(1) var<storage, read> instances: array<Instance>;
+@group(0) @binding(2) var<storage, read> instance_order: array<u32>;
+@group(0) @binding(3) var<storage, read> groups: array<CullGroup>;
+@group(0) @binding(4) var<storage, read_write> args: array<DrawArgs>;
+@group(0) @binding(5) var<storage, read_write> visible: array<u32>;
+
+// Last group whose range starts at or before instance `i`.
+fn find_group(i: u32) -> u32 {
+    var lo = 0u;
+    var hi = params.group_count;
+    while lo + 1u < hi {
+        let mid = (lo + hi) / 2u;
+        if groups[mid].first <= i {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    return lo;
+}
+
+@compute @workgroup_size(64)
+fn cs_main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let i = id.x;
+    if i >= params.instance_count {
+        return;
+    }
+    let g = find_group(i);
+    let group = groups[g];
+    if i < group.first || i >= group.first + group.count {
+        return;
+    }
+    let slot = instance_order[i];
+    let model = instances[slot].model;
+    let center = (model * vec4<f32>(group.sphere.xyz, 1.0)).xyz;
+    let scale = max(length(model[0].xyz), max(length(model[1].xyz), length(model[2].xyz)));
+    let radius = group.sphere.w * scale + group.reach;
+
+    // Frustum planes from the rows of the view-projection matrix, with
+    // wgpu's [0, 1] depth range.
+    let m = params.view_proj;
+    let r0 = vec4<f32>(m[0].x, m[1].x, m[2].x, m[3].x);
+    let r1 = vec4<f32>(m[0].y, m[1].y, m[2].y, m[3].y);
+    let r2 = vec4<f32>(m[0].z, m[1].z, m[2].z, m[3].z);
+    let r3 = vec4<f32>(m[0].w, m[1].w, m[2].w, m[3].w);
+    var planes = array<vec4<f32>, 6>(r3 + r0, r3 - r0, r3 + r1, r3 - r1, r2, r3 - r2);
+    for (var p = 0; p < 6; p++) {
+        let plane = planes[p];
+        if dot(plane.xyz, center) + plane.w < -radius * length(plane.xyz) {
+            return;
+        }
+    }
+    let n = atomicAdd(&args[g].instance_count, 1u);
+    visible[group.first + n] = slot;
+}
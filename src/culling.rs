@@ -0,0 +1,264 @@
+//! GPU frustum culling for instanced draws.
+//!
+//! [`GpuCuller`] runs a compute pass over every instance pushed to an
+//! [`InstanceBuffer`]: each instance's world-space bounding sphere is
+//! tested against the camera frustum, survivors are compacted into a
+//! `visible` slot list, and the pass counts them into indirect draw
+//! arguments.  The render pass then draws each mesh with
+//! `draw_indexed_indirect`, reading `visible` in place of the draw order,
+//! so the CPU never learns — or waits for — what was culled.
+//!
+//! Culling is per instance against the mesh's
+//! [`crate::mesh::BakedMesh::bounding_sphere`], scaled by the largest axis
+//! of the instance transform.  Occlusion is not tested.
+//!
+//! [`crate::scene::Scene::gpu_culling`] turns this on for instanced
+//! rendering.  It needs compute shaders and indirect draws with a first
+//! instance; see [`crate::pipeline::Pipeline::supports_gpu_culling`].
+
+use bytemuck::{Pod, Zeroable};
+use wgpu::util::DrawIndexedIndirectArgs;
+use wgpu::{Device, Queue};
+use crate::constants::pipeline::INITIAL_INSTANCE_CAPACITY;
+use crate::instances::InstanceBuffer;
+use crate::math::matrix4::Matrix4;
+
+/// Invocations per compute workgroup; matches `@workgroup_size` in
+/// `cull.wgsl`.
+const WORKGROUP_SIZE: u32 = 64;
+/// Draw groups the buffers hold before their first reallocation.
+const INITIAL_GROUP_CAPACITY: u32 = 64;
+
+/// One instanced draw as the cull pass sees it.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Pod, Zeroable)]
+pub struct CullGroup {
+    /// Bounding sphere of the mesh in its own space, `[x, y, z, radius]`.
+    pub sphere:      [f32; 4],
+    /// First instance index of the draw.
+    pub first:       u32,
+    /// Number of instances.
+    pub count:       u32,
+    /// Indices per instance.
+    pub index_count: u32,
+    /// World-space distance added to the scaled radius for vertices moved
+    /// by [`crate::material::Displacement`].
+    pub reach:       f32,
+}
+
+impl CullGroup {
+    /// A draw of `index_count` indices for `instances` of a mesh bounded by
+    /// `sphere`, whose vertices move up to `reach` in world space.
+    pub fn new(sphere: [f32; 4], instances: std::ops::Range<u32>, index_count: u32, reach: f32) -> Self {
+        Self { sphere, first: instances.start, count: instances.len() as u32, index_count, reach }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct CullParams {
+    view_proj:      [[f32; 4]; 4],
+    instance_count: u32,
+    group_count:    u32,
+    _pad:           [u32; 2],
+}
+
+/// The cull compute pipeline and the per-frame buffers it fills.
+pub struct GpuCuller {
+    pipeline:          wgpu::ComputePipeline,
+    params:            wgpu::Buffer,
+    groups:            wgpu::Buffer,
+    /// One [`DrawIndexedIndirectArgs`] per group.
+    args:              wgpu::Buffer,
+    /// Surviving slots, laid out like the draw order.
+    visible:           wgpu::Buffer,
+    group_capacity:    u32,
+    instance_capacity: u32,
+    /// Instances to cull, set by the last [`Self::prepare`].
+    instance_count:    u32,
+}
+
+impl GpuCuller {
+    /// Bindings of `cull.wgsl`: parameters, instance data, draw order,
+    /// groups, indirect arguments and visible slots.
+    pub const LAYOUT_ENTRIES: [wgpu::BindGroupLayoutEntry; 6] = [
+        layout_entry(0, wgpu::BufferBindingType::Uniform),
+        layout_entry(1, wgpu::BufferBindingType::Storage { read_only: true }),
+        layout_entry(2, wgpu::BufferBindingType::Storage { read_only: true }),
+        layout_entry(3, wgpu::BufferBindingType::Storage { read_only: true }),
+        layout_entry(4, wgpu::BufferBindingType::Storage { read_only: false }),
+        layout_entry(5, wgpu::BufferBindingType::Storage { read_only: false }),
+    ];
+
+    /// Compile the cull pass for bind groups of `layout`, created from
+    /// [`Self::LAYOUT_ENTRIES`].
+    pub fn new(device: &Device, layout: &wgpu::BindGroupLayout) -> Self {
+        let shader = device.create_shader_module(wgpu::include_wgsl!("cull.wgsl"));
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Cull Pipeline Layout"),
+            bind_group_layouts: &[Some(layout)],
+            immediate_size: 0,
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("Cull Pipeline"),
+            layout: Some(&pipeline_layout),
+            module: &shader,
+            entry_point: Some("cs_main"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+        let params = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Cull Params Buffer"),
+            size: size_of::<CullParams>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let (groups, args) = create_group_buffers(device, INITIAL_GROUP_CAPACITY);
+        Self {
+            pipeline,
+            params,
+            groups,
+            args,
+            visible: create_visible_buffer(device, INITIAL_INSTANCE_CAPACITY),
+            group_capacity: INITIAL_GROUP_CAPACITY,
+            instance_capacity: INITIAL_INSTANCE_CAPACITY,
+            instance_count: 0,
+        }
+    }
+
+    /// Upload this frame's camera and `groups`, resetting every group's
+    /// instance count to zero, and grow the buffers when needed.  `groups`
+    /// must be sorted by [`CullGroup::first`] and cover at most
+    /// `instance_count` instances.  Returns `(bytes written, buffers
+    /// allocated)`.
+    pub fn prepare(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        view_proj: &Matrix4,
+        instance_count: u32,
+        groups: &[CullGroup],
+    ) -> (u64, u32) {
+        let mut allocated = 0;
+        let group_count = groups.len() as u32;
+        if group_count > self.group_capacity {
+            self.group_capacity = group_count.next_power_of_two();
+            (self.groups, self.args) = create_group_buffers(device, self.group_capacity);
+            allocated += 2;
+        }
+        if instance_count > self.instance_capacity {
+            self.instance_capacity = instance_count.next_power_of_two();
+            self.visible = create_visible_buffer(device, self.instance_capacity);
+            allocated += 1;
+        }
+        self.instance_count = instance_count;
+        let params = CullParams { view_proj: view_proj.data, instance_count, group_count, _pad: [0; 2] };
+        let args: Vec<u8> = groups
+            .iter()
+            .flat_map(|group| {
+                DrawIndexedIndirectArgs {
+                    index_count: group.index_count,
+                    instance_count: 0,
+                    first_index: 0,
+                    base_vertex: 0,
+                    first_instance: group.first,
+                }
+                .as_bytes()
+                .to_vec()
+            })
+            .collect();
+        queue.write_buffer(&self.params, 0, bytemuck::bytes_of(&params));
+        if !groups.is_empty() {
+            queue.write_buffer(&self.groups, 0, bytemuck::cast_slice(groups));
+            queue.write_buffer(&self.args, 0, &args);
+        }
+        ((size_of_val(&params) + size_of_val(groups) + args.len()) as u64, allocated)
+    }
+
+    /// The bind group entries of the cull pass over `instances`, for a bind
+    /// group of [`Self::LAYOUT_ENTRIES`].
+    pub fn bind_group_entries<'a>(&'a self, instances: &'a InstanceBuffer) -> [wgpu::BindGroupEntry<'a>; 6] {
+        let entry = |binding, buffer: &'a wgpu::Buffer| wgpu::BindGroupEntry {
+            binding,
+            resource: buffer.as_entire_binding(),
+        };
+        [
+            entry(0, &self.params),
+            entry(1, instances.data_buffer()),
+            entry(2, instances.order_buffer()),
+            entry(3, &self.groups),
+            entry(4, &self.args),
+            entry(5, &self.visible),
+        ]
+    }
+
+    /// Record the cull pass into `encoder`.  `bind_group` binds
+    /// [`Self::bind_group_entries`].
+    pub fn dispatch(&self, encoder: &mut wgpu::CommandEncoder, bind_group: &wgpu::BindGroup) {
+        if self.instance_count == 0 {
+            return;
+        }
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("Cull Pass"),
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, bind_group, &[]);
+        pass.dispatch_workgroups(self.instance_count.div_ceil(WORKGROUP_SIZE), 1, 1);
+    }
+
+    /// Indirect arguments of group `index`, as a byte offset into
+    /// [`Self::args_buffer`].
+    pub fn args_offset(index: usize) -> wgpu::BufferAddress {
+        (index * size_of::<DrawIndexedIndirectArgs>()) as wgpu::BufferAddress
+    }
+
+    /// One [`DrawIndexedIndirectArgs`] per group, filled by the cull pass.
+    pub fn args_buffer(&self) -> &wgpu::Buffer {
+        &self.args
+    }
+
+    /// Surviving slots by instance index; bind in place of
+    /// [`InstanceBuffer::order_buffer`] when drawing from
+    /// [`Self::args_buffer`].
+    pub fn visible_buffer(&self) -> &wgpu::Buffer {
+        &self.visible
+    }
+}
+
+const fn layout_entry(binding: u32, ty: wgpu::BufferBindingType) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::COMPUTE,
+        ty: wgpu::BindingType::Buffer { ty, has_dynamic_offset: false, min_binding_size: None },
+        count: None,
+    }
+}
+
+fn create_group_buffers(device: &Device, capacity: u32) -> (wgpu::Buffer, wgpu::Buffer) {
+    let groups = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Cull Group Buffer"),
+        size: (size_of::<CullGroup>() * capacity as usize) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let args = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Cull Indirect Args Buffer"),
+        size: (size_of::<DrawIndexedIndirectArgs>() * capacity as usize) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::STORAGE
+            | wgpu::BufferUsages::INDIRECT
+            | wgpu::BufferUsages::COPY_DST
+            | wgpu::BufferUsages::COPY_SRC,
+        mapped_at_creation: false,
+    });
+    (groups, args)
+}
+
+fn create_visible_buffer(device: &Device, capacity: u32) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Cull Visible Buffer"),
+        size: (size_of::<u32>() * capacity as usize) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+        mapped_at_creation: false,
+    })
+}
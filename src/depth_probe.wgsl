@@ -0,0 +1,21 @@
+// Copies one depth texel, bit for bit, into a 1x1 R32Uint target.  Depth
+// textures cannot be copied to buffers on GLES / WebGL2, colour ones can.
+// The depth texture is bound as unfilterable float: GLSL has no
+// `textureLoad` for depth textures.
+
+@group(0) @binding(0)
+var depth: texture_2d<f32>;
+@group(0) @binding(1)
+var<uniform> texel: vec4<u32>;
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+    let x = f32(i32(index & 1u) * 4 - 1);
+    let y = f32(i32(index >> 1u) * 4 - 1);
+    return vec4<f32>(x, y, 0.0, 1.0);
+}
+
+@fragment
+fn fs_main() -> @location(0) u32 {
+    return bitcast<u32>(textureLoad(depth, texel.xy, 0).x);
+}
@@ -0,0 +1,72 @@
+// Depth of field: one axis of a separable gather blur, run once
+// horizontally and once vertically.  Each pixel's circle of confusion (CoC)
+// comes from its view depth; a neighbour contributes when its own CoC reaches
+// the pixel, and background neighbours are capped at the pixel's CoC so a
+// blurred background does not bleed over a sharp foreground.
+
+struct DofParams {
+    focus_distance: f32,
+    // CoC radius in pixels of points far behind the focus plane.
+    aperture: f32,
+    znear: f32,
+    zfar: f32,
+    // (1, 0) for the horizontal pass, (0, 1) for the vertical one.
+    direction: vec2<f32>,
+    _pad: vec2<f32>,
+};
+
+@group(0) @binding(0) var<uniform> params: DofParams;
+@group(0) @binding(1) var t_color: texture_2d<f32>;
+// Bound as unfilterable float: GLSL cannot `textureLoad` depth textures.
+@group(0) @binding(2) var t_depth: texture_2d<f32>;
+
+// Largest CoC radius in pixels.
+const MAX_RADIUS: i32 = 16;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let x = f32(i32(index & 1u) * 4 - 1);
+    let y = f32(i32(index >> 1u) * 4 - 1);
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(x, y, 0.0, 1.0);
+    return out;
+}
+
+// Inverts the depth mapping of `Matrix4::perspective`.
+fn view_depth(p: vec2<i32>) -> f32 {
+    let d = textureLoad(t_depth, p, 0).x;
+    return params.znear * params.zfar / (params.zfar - d * (params.zfar - params.znear));
+}
+
+fn coc(z: f32) -> f32 {
+    return min(params.aperture * abs(z - params.focus_distance) / z, f32(MAX_RADIUS));
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let last = vec2<i32>(textureDimensions(t_color)) - 1;
+    let centre = vec2<i32>(in.clip_position.xy);
+    let centre_depth = view_depth(centre);
+    let centre_coc = coc(centre_depth);
+    let step = vec2<i32>(params.direction);
+
+    var sum = vec3<f32>(0.0);
+    var total = 0.0;
+    for (var i = -MAX_RADIUS; i <= MAX_RADIUS; i = i + 1) {
+        let p = clamp(centre + step * i, vec2<i32>(0), last);
+        let z = view_depth(p);
+        var radius = coc(z);
+        if z > centre_depth {
+            radius = min(radius, centre_coc);
+        }
+        // Spread each sample over its blur width; the centre always counts.
+        let weight = clamp(radius - abs(f32(i)) + 1.0, 0.0, 1.0) / (2.0 * radius + 1.0);
+        sum = sum + textureLoad(t_color, p, 0).rgb * weight;
+        total = total + weight;
+    }
+    return vec4<f32>(sum / total, 1.0);
+}
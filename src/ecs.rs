@@ -0,0 +1,146 @@
+//! Archetype ECS mirror of the scene graph (`ecs-mirror` feature).
+//!
+//! Every object in a [`World`] is also an entity in a
+//! [`hecs::World`], carrying its [`ObjectId`], [`Name`], [`Transform`],
+//! [`Color`] and [`Layer`], plus [`Parent`], [`Geometry`] and
+//! [`GeometryId`] when the object has them.  The mirror is a copy: objects
+//! still live in [`World::objects`], and the [`World`] API stays the source
+//! of truth:
+//!
+//! * spawning, deleting and reparenting update the mirror immediately;
+//! * other edits made through [`World::get_mut`] reach it at
+//!   [`World::end_frame`] (i.e. every [`crate::scene::Scene::draw_world`]),
+//!   so queries run during a frame see the values from before that frame's
+//!   edits: the mirror lags one frame behind.
+//!
+//! The mirror is read-only: use it for queries and move objects through
+//! the [`World`] API.  Nothing written to it is copied back, and
+//! [`World::end_frame`] overwrites the entities of edited objects.
+//!
+//! Queries run over densely packed component arrays instead of a hash map
+//! of objects, and since [`hecs::World`] is `Sync`, several systems can read
+//! it from separate threads:
+//!
+//! ```ignore
+//! use vertra::ecs::{Layer, ObjectId};
+//! use vertra::transform::Transform;
+//!
+//! let ecs = scene.world.ecs();
+//! let (high, on_layer) = std::thread::scope(|s| {
+//!     let high = s.spawn(|| ecs.query::<(&ObjectId, &Transform)>().iter()
+//!         .filter(|(_, transform)| transform.position[1] > 10.0)
+//!         .map(|(&ObjectId(id), _)| id)
+//!         .collect::<Vec<_>>());
+//!     let on_layer = s.spawn(|| ecs.query::<&Layer>().iter().filter(|layer| layer.0 & 2 != 0).count());
+//!     (high.join().unwrap(), on_layer.join().unwrap())
+//! });
+//! for id in high {
+//!     scene.world.get_mut(id).unwrap().transform.position[1] = 10.0;
+//! }
+//! ```
+
+use std::collections::HashMap;
+
+pub use hecs;
+
+pub use crate::geometry::{Geometry, GeometryId};
+pub use crate::transform::Transform;
+use crate::objects::Object;
+#[cfg(doc)]
+use crate::world::World;
+
+/// Integer ID of the object an entity mirrors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ObjectId(pub usize);
+
+/// Display name ([`Object::name`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Name(pub String);
+
+/// RGBA base colour ([`Object::color`]).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color(pub [f32; 4]);
+
+/// Render-layer bits ([`Object::layer`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layer(pub u32);
+
+/// Integer ID of the parent object; absent on roots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Parent(pub usize);
+
+/// The [`hecs::World`] kept in step with a [`World`], and the entity of
+/// each object.
+pub(crate) struct EcsMirror {
+    pub(crate) world: hecs::World,
+    entities: HashMap<usize, hecs::Entity>,
+}
+
+impl std::fmt::Debug for EcsMirror {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EcsMirror").field("entities", &self.entities.len()).finish()
+    }
+}
+
+impl EcsMirror {
+    pub(crate) fn new() -> Self {
+        Self { world: hecs::World::new(), entities: HashMap::new() }
+    }
+
+    pub(crate) fn from_objects(objects: &HashMap<usize, Object>) -> Self {
+        let mut mirror = Self::new();
+        for (&id, object) in objects {
+            mirror.upsert(id, object);
+        }
+        mirror
+    }
+
+    pub(crate) fn entity(&self, id: usize) -> Option<hecs::Entity> {
+        self.entities.get(&id).copied()
+    }
+
+    /// Spawn an entity for `id`, or overwrite the components of its existing
+    /// one.
+    pub(crate) fn upsert(&mut self, id: usize, object: &Object) {
+        let components = (
+            ObjectId(id),
+            Name(object.name.clone()),
+            object.transform.clone(),
+            Color(object.color),
+            Layer(object.layer),
+        );
+        let entity = match self.entities.get(&id) {
+            Some(&entity) => {
+                // Live: only `remove` despawns mirrored entities.
+                let _ = self.world.insert(entity, components);
+                entity
+            }
+            None => {
+                let entity = self.world.spawn(components);
+                self.entities.insert(id, entity);
+                entity
+            }
+        };
+        self.set_optional(entity, object.parent.map(Parent));
+        self.set_optional(entity, object.geometry.clone());
+        self.set_optional(entity, object.mesh);
+    }
+
+    pub(crate) fn remove(&mut self, id: usize) {
+        if let Some(entity) = self.entities.remove(&id) {
+            let _ = self.world.despawn(entity);
+        }
+    }
+
+    fn set_optional<T: hecs::Component>(&mut self, entity: hecs::Entity, value: Option<T>) {
+        // Both only fail for a missing entity, and `entity` is live.
+        match value {
+            Some(value) => {
+                let _ = self.world.insert_one(entity, value);
+            }
+            None => {
+                let _ = self.world.remove_one::<T>(entity);
+            }
+        }
+    }
+}
@@ -53,6 +53,7 @@ pub use types::{
     DragAxis,
     DragKind,
     DragState,
+    SnapSettings,
     // Inspector data
     InspectorData,
     Inspector,
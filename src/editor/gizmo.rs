@@ -1,6 +1,6 @@
 //! Gizmo and selection-box mesh builders.
 
-use crate::geometry::Geometry;
+use crate::geometry::{Geometry, SphereTessellation};
 use crate::mesh::{MeshData, Vertex};
 use crate::transform::Transform;
 
@@ -23,25 +23,25 @@ pub fn build_gizmo_mesh_data(center: [f32; 3], scale: f32) -> (Vec<Vertex>, Vec<
     let t = |pos: [f32; 3]| Transform::from_position(pos[0], pos[1], pos[2]);
     let [cx, cy, cz] = center;
 
-    Geometry::Sphere { radius: dot_r, subdivisions: 8 }
+    Geometry::Sphere { radius: dot_r, subdivisions: 8, tessellation: SphereTessellation::Uv }
         .generate_mesh_data(&mut mesh, &t(center), [0.9, 0.9, 0.9, 1.0]);
 
     // X (red)
-    Geometry::Box { width: shaft_len, height: shaft_h*2.0, depth: shaft_h*2.0 }
+    Geometry::Box { width: shaft_len, height: shaft_h*2.0, depth: shaft_h*2.0, segments: [1; 3] }
         .generate_mesh_data(&mut mesh, &t([cx+shaft_len*0.5, cy, cz]), [0.95,0.15,0.15,1.0]);
     push_cone(&mut mesh,
         [cx+scale, cy, cz], [cx+scale-cone_h, cy, cz],
         [0.0,1.0,0.0], [0.0,0.0,1.0], cone_r, [0.95,0.15,0.15,1.0]);
 
     // Y (green)
-    Geometry::Box { width: shaft_h*2.0, height: shaft_len, depth: shaft_h*2.0 }
+    Geometry::Box { width: shaft_h*2.0, height: shaft_len, depth: shaft_h*2.0, segments: [1; 3] }
         .generate_mesh_data(&mut mesh, &t([cx, cy+shaft_len*0.5, cz]), [0.15,0.95,0.15,1.0]);
     push_cone(&mut mesh,
         [cx, cy+scale, cz], [cx, cy+scale-cone_h, cz],
         [1.0,0.0,0.0], [0.0,0.0,1.0], cone_r, [0.15,0.95,0.15,1.0]);
 
     // Z (blue)
-    Geometry::Box { width: shaft_h*2.0, height: shaft_h*2.0, depth: shaft_len }
+    Geometry::Box { width: shaft_h*2.0, height: shaft_h*2.0, depth: shaft_len, segments: [1; 3] }
         .generate_mesh_data(&mut mesh, &t([cx, cy, cz+shaft_len*0.5]), [0.15,0.15,0.95,1.0]);
     push_cone(&mut mesh,
         [cx, cy, cz+scale], [cx, cy, cz+scale-cone_h],
@@ -62,7 +62,7 @@ pub fn build_rotate_gizmo_mesh_data(center: [f32; 3], scale: f32) -> (Vec<Vertex
     let dot_r    = scale * 0.07;
     let t = |pos: [f32; 3]| Transform::from_position(pos[0], pos[1], pos[2]);
 
-    Geometry::Sphere { radius: dot_r, subdivisions: 8 }
+    Geometry::Sphere { radius: dot_r, subdivisions: 8, tessellation: SphereTessellation::Uv }
         .generate_mesh_data(&mut mesh, &t(center), [0.9, 0.9, 0.9, 1.0]);
 
     push_ring_tube(&mut mesh, center, ring_r, tube_r,
@@ -89,25 +89,25 @@ pub fn build_scale_gizmo_mesh_data(center: [f32; 3], scale: f32) -> (Vec<Vertex>
     let t = |pos: [f32; 3]| Transform::from_position(pos[0], pos[1], pos[2]);
     let [cx, cy, cz] = center;
 
-    Geometry::Sphere { radius: dot_r, subdivisions: 8 }
+    Geometry::Sphere { radius: dot_r, subdivisions: 8, tessellation: SphereTessellation::Uv }
         .generate_mesh_data(&mut mesh, &t(center), [0.9, 0.9, 0.9, 1.0]);
 
     // X (red)
-    Geometry::Box { width: shaft_len, height: shaft_h*2.0, depth: shaft_h*2.0 }
+    Geometry::Box { width: shaft_len, height: shaft_h*2.0, depth: shaft_h*2.0, segments: [1; 3] }
         .generate_mesh_data(&mut mesh, &t([cx+shaft_len*0.5, cy, cz]), [0.95,0.15,0.15,1.0]);
-    Geometry::Box { width: cube_hs*2.0, height: cube_hs*2.0, depth: cube_hs*2.0 }
+    Geometry::Box { width: cube_hs*2.0, height: cube_hs*2.0, depth: cube_hs*2.0, segments: [1; 3] }
         .generate_mesh_data(&mut mesh, &t([cx+scale, cy, cz]), [0.95,0.15,0.15,1.0]);
 
     // Y (green)
-    Geometry::Box { width: shaft_h*2.0, height: shaft_len, depth: shaft_h*2.0 }
+    Geometry::Box { width: shaft_h*2.0, height: shaft_len, depth: shaft_h*2.0, segments: [1; 3] }
         .generate_mesh_data(&mut mesh, &t([cx, cy+shaft_len*0.5, cz]), [0.15,0.95,0.15,1.0]);
-    Geometry::Box { width: cube_hs*2.0, height: cube_hs*2.0, depth: cube_hs*2.0 }
+    Geometry::Box { width: cube_hs*2.0, height: cube_hs*2.0, depth: cube_hs*2.0, segments: [1; 3] }
         .generate_mesh_data(&mut mesh, &t([cx, cy+scale, cz]), [0.15,0.95,0.15,1.0]);
 
     // Z (blue)
-    Geometry::Box { width: shaft_h*2.0, height: shaft_h*2.0, depth: shaft_len }
+    Geometry::Box { width: shaft_h*2.0, height: shaft_h*2.0, depth: shaft_len, segments: [1; 3] }
         .generate_mesh_data(&mut mesh, &t([cx, cy, cz+shaft_len*0.5]), [0.15,0.15,0.95,1.0]);
-    Geometry::Box { width: cube_hs*2.0, height: cube_hs*2.0, depth: cube_hs*2.0 }
+    Geometry::Box { width: cube_hs*2.0, height: cube_hs*2.0, depth: cube_hs*2.0, segments: [1; 3] }
         .generate_mesh_data(&mut mesh, &t([cx, cy, cz+scale]), [0.15,0.15,0.95,1.0]);
 
     (mesh.vertices, mesh.indices)
@@ -125,31 +125,31 @@ pub fn build_selection_box(center: [f32; 3], half: [f32; 3]) -> (Vec<Vertex>, Ve
     let tr = |px: f32, py: f32, pz: f32| Transform::from_position(px, py, pz);
 
     // Bottom 4 edges
-    Geometry::Box { width: hx*2.0, height: tk, depth: tk }
+    Geometry::Box { width: hx*2.0, height: tk, depth: tk, segments: [1; 3] }
         .generate_mesh_data(&mut mesh, &tr(cx,      cy-hy, cz-hz), color);
-    Geometry::Box { width: hx*2.0, height: tk, depth: tk }
+    Geometry::Box { width: hx*2.0, height: tk, depth: tk, segments: [1; 3] }
         .generate_mesh_data(&mut mesh, &tr(cx,      cy-hy, cz+hz), color);
-    Geometry::Box { width: tk, height: tk, depth: hz*2.0 }
+    Geometry::Box { width: tk, height: tk, depth: hz*2.0, segments: [1; 3] }
         .generate_mesh_data(&mut mesh, &tr(cx-hx,   cy-hy, cz),    color);
-    Geometry::Box { width: tk, height: tk, depth: hz*2.0 }
+    Geometry::Box { width: tk, height: tk, depth: hz*2.0, segments: [1; 3] }
         .generate_mesh_data(&mut mesh, &tr(cx+hx,   cy-hy, cz),    color);
     // Top 4 edges
-    Geometry::Box { width: hx*2.0, height: tk, depth: tk }
+    Geometry::Box { width: hx*2.0, height: tk, depth: tk, segments: [1; 3] }
         .generate_mesh_data(&mut mesh, &tr(cx,      cy+hy, cz-hz), color);
-    Geometry::Box { width: hx*2.0, height: tk, depth: tk }
+    Geometry::Box { width: hx*2.0, height: tk, depth: tk, segments: [1; 3] }
         .generate_mesh_data(&mut mesh, &tr(cx,      cy+hy, cz+hz), color);
-    Geometry::Box { width: tk, height: tk, depth: hz*2.0 }
+    Geometry::Box { width: tk, height: tk, depth: hz*2.0, segments: [1; 3] }
         .generate_mesh_data(&mut mesh, &tr(cx-hx,   cy+hy, cz),    color);
-    Geometry::Box { width: tk, height: tk, depth: hz*2.0 }
+    Geometry::Box { width: tk, height: tk, depth: hz*2.0, segments: [1; 3] }
         .generate_mesh_data(&mut mesh, &tr(cx+hx,   cy+hy, cz),    color);
     // 4 vertical edges
-    Geometry::Box { width: tk, height: hy*2.0, depth: tk }
+    Geometry::Box { width: tk, height: hy*2.0, depth: tk, segments: [1; 3] }
         .generate_mesh_data(&mut mesh, &tr(cx-hx,   cy, cz-hz), color);
-    Geometry::Box { width: tk, height: hy*2.0, depth: tk }
+    Geometry::Box { width: tk, height: hy*2.0, depth: tk, segments: [1; 3] }
         .generate_mesh_data(&mut mesh, &tr(cx+hx,   cy, cz-hz), color);
-    Geometry::Box { width: tk, height: hy*2.0, depth: tk }
+    Geometry::Box { width: tk, height: hy*2.0, depth: tk, segments: [1; 3] }
         .generate_mesh_data(&mut mesh, &tr(cx-hx,   cy, cz+hz), color);
-    Geometry::Box { width: tk, height: hy*2.0, depth: tk }
+    Geometry::Box { width: tk, height: hy*2.0, depth: tk, segments: [1; 3] }
         .generate_mesh_data(&mut mesh, &tr(cx+hx,   cy, cz+hz), color);
 
     (mesh.vertices, mesh.indices)
@@ -158,19 +158,11 @@ pub fn build_selection_box(center: [f32; 3], half: [f32; 3]) -> (Vec<Vertex>, Ve
 /// Build the skybox mesh - a large box visible from inside, rendered with
 /// the overlay pipeline (`cull_mode: None`).
 pub fn build_skybox_mesh() -> (Vec<Vertex>, Vec<u32>) {
-    let mut mesh = MeshData::new();
-    let s   = 450.0_f32;
-    let top = [0.08, 0.12, 0.22, 1.0_f32];
-    let mid = [0.10, 0.13, 0.20, 1.0];
-    let bot = [0.05, 0.06, 0.09, 1.0];
-
-    mesh.push_quad([[-s,s,-s],[s,s,-s],[s,s,s],[-s,s,s]], top);
-    mesh.push_quad([[-s,-s,s],[s,-s,s],[s,-s,-s],[-s,-s,-s]], bot);
-    mesh.push_quad([[-s,s,-s],[-s,-s,-s],[s,-s,-s],[s,s,-s]], mid);
-    mesh.push_quad([[s,s,s],[s,-s,s],[-s,-s,s],[-s,s,s]], mid);
-    mesh.push_quad([[s,s,-s],[s,-s,-s],[s,-s,s],[s,s,s]], mid);
-    mesh.push_quad([[-s,s,s],[-s,-s,s],[-s,-s,-s],[-s,s,-s]], mid);
-
+    let mesh = crate::presets::skybox_mesh(
+        [0.08, 0.12, 0.22, 1.0],
+        [0.10, 0.13, 0.20, 1.0],
+        [0.05, 0.06, 0.09, 1.0],
+    );
     (mesh.vertices, mesh.indices)
 }
 
@@ -14,13 +14,17 @@ use crate::world::World;
 /// Approximate bounding-sphere radius of `geom` in world space.
 pub(crate) fn approx_radius(geom: &Option<Geometry>, t: &Transform) -> f32 {
     let base = match geom {
-        Some(Geometry::Sphere  { radius, .. })           => *radius,
-        Some(Geometry::Cube    { size })                 => *size * 0.5,
-        Some(Geometry::Box     { width, height, depth }) => width.max(*height).max(*depth) * 0.5,
-        Some(Geometry::Plane   { size })                 => *size * 0.5,
-        Some(Geometry::Pyramid { base_size, height })    => base_size.max(*height) * 0.5,
-        Some(Geometry::Capsule { radius, height, .. })   => radius + height * 0.5,
-        None                                             => 0.5,
+        Some(Geometry::Sphere  { radius, .. })                      => *radius,
+        Some(Geometry::Cube    { size })                            => *size * 0.5,
+        Some(Geometry::Box     { width, height, depth, .. })        => width.max(*height).max(*depth) * 0.5,
+        Some(Geometry::RoundedBox { width, height, depth, .. })     => width.max(*height).max(*depth) * 0.5,
+        Some(Geometry::Plane   { size, .. })                        => *size * 0.5,
+        Some(Geometry::Pyramid { base_size, height })               => base_size.max(*height) * 0.5,
+        Some(Geometry::Capsule { radius, height, .. })              => radius + height * 0.5,
+        // Arrows start at the origin rather than centring on it.
+        Some(Geometry::Arrow   { length, .. })                      => *length,
+        Some(Geometry::Sprite  { width, height, .. })               => width.max(*height) * 0.5,
+        None                                                        => 0.5,
     };
     base * t.scale[0].max(t.scale[1]).max(t.scale[2])
 }
@@ -28,13 +32,16 @@ pub(crate) fn approx_radius(geom: &Option<Geometry>, t: &Transform) -> f32 {
 /// Axis-aligned half-extents of `geom` in world space (accounts for scale).
 pub(crate) fn approx_half_extents(geom: &Option<Geometry>, t: &Transform) -> [f32; 3] {
     let base: [f32; 3] = match geom {
-        Some(Geometry::Sphere  { radius, .. })           => [*radius; 3],
-        Some(Geometry::Cube    { size })                 => [*size * 0.5; 3],
-        Some(Geometry::Box     { width, height, depth }) => [*width*0.5, *height*0.5, *depth*0.5],
-        Some(Geometry::Plane   { size })                 => [*size*0.5, 0.01, *size*0.5],
-        Some(Geometry::Pyramid { base_size, height })    => [*base_size*0.5, *height*0.5, *base_size*0.5],
-        Some(Geometry::Capsule { radius, height, .. })   => [*radius, *height*0.5 + *radius, *radius],
-        None                                             => [0.5; 3],
+        Some(Geometry::Sphere  { radius, .. })                      => [*radius; 3],
+        Some(Geometry::Cube    { size })                            => [*size * 0.5; 3],
+        Some(Geometry::Box     { width, height, depth, .. })        => [*width*0.5, *height*0.5, *depth*0.5],
+        Some(Geometry::RoundedBox { width, height, depth, .. })     => [*width*0.5, *height*0.5, *depth*0.5],
+        Some(Geometry::Plane   { size, .. })                        => [*size*0.5, 0.01, *size*0.5],
+        Some(Geometry::Pyramid { base_size, height })               => [*base_size*0.5, *height*0.5, *base_size*0.5],
+        Some(Geometry::Capsule { radius, height, .. })              => [*radius, *height*0.5 + *radius, *radius],
+        Some(Geometry::Arrow   { length, shaft_radius, head_size }) => [shaft_radius.max(head_size*0.5), *length, shaft_radius.max(head_size*0.5)],
+        Some(Geometry::Sprite  { width, height, .. })               => [*width*0.5, *height*0.5, 0.01],
+        None                                                        => [0.5; 3],
     };
     [
         (base[0] * t.scale[0]).max(0.05),
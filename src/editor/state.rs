@@ -3,8 +3,9 @@
 use std::collections::HashSet;
 use winit::keyboard::KeyCode;
 
-use crate::camera::Camera;
+use crate::camera::{Camera, Viewport};
 use crate::mesh::{BakedMesh, Vertex};
+use crate::transform::snap;
 use crate::world::World;
 
 use super::gizmo::{
@@ -21,7 +22,7 @@ use super::math::{
 };
 use super::types::{
     DragAxis, DragKind, DragState, EditorEvent, GizmoMode,
-    InspectorData, Inspector, EditorInput,
+    InspectorData, Inspector, EditorInput, SnapSettings,
 };
 
 /// All runtime state for the static scene editor.
@@ -37,6 +38,9 @@ pub struct EditorState {
     pub gizmo_ids:       HashSet<usize>,
     pub viewport_width:  f32,
     pub viewport_height: f32,
+    /// Window position of the viewport's top-left corner; cursor positions
+    /// are stored relative to it.
+    pub viewport_origin: [f32; 2],
     pub drag:            Option<DragState>,
     /// Active gizmo mode: Translate, Rotate, or Scale (T / R / E keys).
     pub gizmo_mode:      GizmoMode,
@@ -50,6 +54,8 @@ pub struct EditorState {
     pub group_ids:       Vec<usize>,
     /// All individually-selected object IDs (via Ctrl+Click).
     pub multi_selected:  Vec<usize>,
+    /// Grid / angle / scale snapping applied to gizmo drags.
+    pub snap:            SnapSettings,
 }
 
 impl EditorState {
@@ -62,6 +68,7 @@ impl EditorState {
             gizmo_ids:      HashSet::new(),
             viewport_width,
             viewport_height,
+            viewport_origin: [0.0, 0.0],
             drag:           None,
             gizmo_mode:     GizmoMode::default(),
             skybox:         None,
@@ -69,6 +76,7 @@ impl EditorState {
             camera_speed:   5.0,
             group_ids:      Vec::new(),
             multi_selected: Vec::new(),
+            snap:           SnapSettings::default(),
         }
     }
 
@@ -78,6 +86,13 @@ impl EditorState {
         self.viewport_height = h;
     }
 
+    /// Update the viewport size and origin, e.g. for a letterboxed scene
+    /// (see [`crate::scene::Scene::set_fixed_aspect`]).
+    pub fn set_viewport(&mut self, viewport: Viewport) {
+        self.set_viewport_size(viewport.width, viewport.height);
+        self.viewport_origin = [viewport.x, viewport.y];
+    }
+
     /// No-op: gizmos are rendered as an overlay mesh each frame rather than
     /// as world objects.  Kept for API compatibility.
     pub fn spawn_gizmos(&mut self, _world: &mut World) {}
@@ -88,22 +103,18 @@ impl EditorState {
         self.inspector.selected.as_ref()?;
 
         // Priority 1: multi-selection (Ctrl+Click)
-        if self.multi_selected.len() > 1 {
-            if let Some((mn, mx)) = combined_aabb(world, &self.multi_selected) {
-                let center = [(mn[0]+mx[0])*0.5, (mn[1]+mx[1])*0.5, (mn[2]+mx[2])*0.5];
-                let half   = [((mx[0]-mn[0])*0.5).max(0.05), ((mx[1]-mn[1])*0.5).max(0.05), ((mx[2]-mn[2])*0.5).max(0.05)];
-                let scale  = half[0].max(half[1]).max(half[2]) * 1.3;
-                return Some((center, scale, half));
-            }
+        if self.multi_selected.len() > 1 && let Some((mn, mx)) = combined_aabb(world, &self.multi_selected) {
+            let center = [(mn[0]+mx[0])*0.5, (mn[1]+mx[1])*0.5, (mn[2]+mx[2])*0.5];
+            let half   = [((mx[0]-mn[0])*0.5).max(0.05), ((mx[1]-mn[1])*0.5).max(0.05), ((mx[2]-mn[2])*0.5).max(0.05)];
+            let scale  = half[0].max(half[1]).max(half[2]) * 1.3;
+            return Some((center, scale, half));
         }
         // Priority 2: group expansion (G key)
-        if !self.group_ids.is_empty() {
-            if let Some((mn, mx)) = combined_aabb(world, &self.group_ids) {
-                let center = [(mn[0]+mx[0])*0.5, (mn[1]+mx[1])*0.5, (mn[2]+mx[2])*0.5];
-                let half   = [((mx[0]-mn[0])*0.5).max(0.05), ((mx[1]-mn[1])*0.5).max(0.05), ((mx[2]-mn[2])*0.5).max(0.05)];
-                let scale  = half[0].max(half[1]).max(half[2]) * 1.3;
-                return Some((center, scale, half));
-            }
+        if !self.group_ids.is_empty() && let Some((mn, mx)) = combined_aabb(world, &self.group_ids) {
+            let center = [(mn[0]+mx[0])*0.5, (mn[1]+mx[1])*0.5, (mn[2]+mx[2])*0.5];
+            let half   = [((mx[0]-mn[0])*0.5).max(0.05), ((mx[1]-mn[1])*0.5).max(0.05), ((mx[2]-mn[2])*0.5).max(0.05)];
+            let scale  = half[0].max(half[1]).max(half[2]) * 1.3;
+            return Some((center, scale, half));
         }
         // Priority 3: single selection
         let sel   = self.inspector.selected.as_ref().unwrap();
@@ -146,24 +157,27 @@ impl EditorState {
     pub fn process(&mut self, camera: &mut Camera, world: &mut World, event: EditorEvent) {
         match event {
             EditorEvent::CursorMoved { x, y } => {
-                self.input.cursor_x = x;
-                self.input.cursor_y = y;
+                self.input.cursor_x = x - self.viewport_origin[0];
+                self.input.cursor_y = y - self.viewport_origin[1];
             }
 
             EditorEvent::ModifiersChanged { alt, ctrl } => {
                 self.input.alt_held  = alt;
                 self.input.ctrl_held = ctrl;
+                // Toggling Ctrl mid-drag snaps (or unsnaps) immediately.
+                if self.drag.is_some() {
+                    self.apply_drag_offset(world);
+                    self.sync_inspector(world);
+                }
             }
 
             EditorEvent::KeyPressed(code) => {
                 self.pressed_keys.insert(code);
-                if code == KeyCode::KeyG {
-                    if let Some(sel) = &self.inspector.selected {
-                        let root_id = sel.id;
-                        let mut ids = Vec::new();
-                        collect_descendants(world, root_id, &mut ids);
-                        self.group_ids = ids;
-                    }
+                if code == KeyCode::KeyG && let Some(sel) = &self.inspector.selected {
+                    let root_id = sel.id;
+                    let mut ids = Vec::new();
+                    collect_descendants(world, root_id, &mut ids);
+                    self.group_ids = ids;
                 }
                 if code == KeyCode::KeyT { self.gizmo_mode = GizmoMode::Translate; }
                 if code == KeyCode::KeyR { self.gizmo_mode = GizmoMode::Rotate;    }
@@ -210,10 +224,8 @@ impl EditorState {
                                         ];
                                         let mut best: Option<(DragAxis, f32)> = None;
                                         for (da, n) in &axes {
-                                            if let Some(t) = ray_ring(ro, rd, c, *n, gs, hw) {
-                                                if best.map_or(true, |(_,bt)| t < bt) {
-                                                    best = Some((*da, t));
-                                                }
+                                            if let Some(t) = ray_ring(ro, rd, c, *n, gs, hw) && best.is_none_or(|(_,bt)| t < bt) {
+                                                best = Some((*da, t));
                                             }
                                         }
                                         best.map(|(da, _)| (id, c, da))
@@ -227,7 +239,10 @@ impl EditorState {
                                     GizmoMode::Rotate    => DragKind::Rotate,
                                     GizmoMode::Scale     => DragKind::Scale,
                                 };
-                                self.drag = Some(DragState { object_id: oid, axis, center, kind });
+                                let origins = filter_top_level_ids(world, &self.drag_ids(oid)).into_iter()
+                                    .filter_map(|id| world.objects.get(&id).map(|o| (id, o.transform.clone())))
+                                    .collect();
+                                self.drag = Some(DragState { object_id: oid, axis, center, kind, origins, accumulated: 0.0 });
                             } else {
                                 let hit = self.pick(camera, world, sx, sy);
                                 if self.input.ctrl_held {
@@ -309,12 +324,11 @@ impl EditorState {
     }
     
     fn apply_drag(&mut self, world: &mut World, camera: &Camera, dx: f32, dy: f32) {
-        let (object_id, axis, center, kind) = match &self.drag {
-            Some(d) => (d.object_id, d.axis, d.center, d.kind),
+        let (axis, center, kind) = match &self.drag {
+            Some(d) => (d.axis, d.center, d.kind),
             None    => return,
         };
         let axis_dir: [f32; 3] = match axis { DragAxis::X=>[1.,0.,0.], DragAxis::Y=>[0.,1.,0.], DragAxis::Z=>[0.,0.,1.] };
-        let axis_idx: usize    = match axis { DragAxis::X=>0, DragAxis::Y=>1, DragAxis::Z=>2 };
 
         let vp    = camera.build_view_projection_matrix();
         let c_ndc = vp.project_point(center);
@@ -326,23 +340,11 @@ impl EditorState {
         if len < 0.5 && kind != DragKind::Rotate { return; }
         let alignment = if len >= 0.5 { (dx*ax_px + (-dy)*ay_px) / len } else { 0.0 };
 
-        let raw_ids: Vec<usize> = if !self.group_ids.is_empty() { self.group_ids.clone() }
-            else if self.multi_selected.len() > 1 { self.multi_selected.clone() }
-            else { vec![object_id] };
-        let top_ids = filter_top_level_ids(world, &raw_ids);
-
-        match kind {
+        let delta = match kind {
             DragKind::Translate => {
-                let wpp   = v3_len(v3_sub(center, camera.eye)).max(0.001)
+                let wpp = v3_len(v3_sub(center, camera.eye)).max(0.001)
                     * (camera.fov.to_radians()*0.5).tan()*2.0 / self.viewport_height;
-                let delta = alignment * wpp;
-                for &id in &top_ids {
-                    if let Some(obj) = world.objects.get_mut(&id) {
-                        obj.transform.position[0] += axis_dir[0]*delta;
-                        obj.transform.position[1] += axis_dir[1]*delta;
-                        obj.transform.position[2] += axis_dir[2]*delta;
-                    }
-                }
+                alignment * wpp
             }
             DragKind::Rotate => {
                 let cam_dir  = v3_norm(v3_sub(center, camera.eye));
@@ -355,31 +357,59 @@ impl EditorState {
                 let vlen     = (vx*vx + vy*vy).sqrt().max(0.001);
                 let circ     = (dx*(-vy) + dy*vx)/vlen * if dot<0.0 {-1.0} else {1.0};
                 let perp     = if len > 0.1 { (dx*(-ay_px) + (-dy)*ax_px)/len } else { 0.0 };
-                let angle    = (perp*(1.0-abs_dot) + circ*abs_dot) * 0.5;
-                for &id in &top_ids {
-                    if let Some(obj) = world.objects.get_mut(&id) {
-                        obj.transform.rotation[axis_idx] += angle;
-                    }
-                }
+                (perp*(1.0-abs_dot) + circ*abs_dot) * 0.5
             }
-            DragKind::Scale => {
-                let ds = alignment / GIZMO_SCREEN_PX * 0.5;
-                for &id in &top_ids {
-                    if let Some(obj) = world.objects.get_mut(&id) {
-                        obj.transform.scale[axis_idx] = (obj.transform.scale[axis_idx]+ds).max(0.01);
-                    }
+            DragKind::Scale => alignment / GIZMO_SCREEN_PX * 0.5,
+        };
+        let Some(drag) = &mut self.drag else { return };
+        drag.accumulated += delta;
+        self.apply_drag_offset(world);
+        self.sync_inspector(world);
+    }
+
+    /// Set every dragged object's dragged component to its drag-start value
+    /// plus the accumulated offset, snapped when snapping is active.
+    fn apply_drag_offset(&self, world: &mut World) {
+        let Some(drag) = &self.drag else { return };
+        let axis_idx = match drag.axis { DragAxis::X=>0, DragAxis::Y=>1, DragAxis::Z=>2 };
+        let snapping = self.snap.enabled != self.input.ctrl_held;
+        let step = |s: f32| if snapping { s } else { 0.0 };
+        for (id, origin) in &drag.origins {
+            let Some(obj) = world.get_mut(*id) else { continue };
+            match drag.kind {
+                DragKind::Translate => {
+                    obj.transform.position[axis_idx] =
+                        snap(origin.position[axis_idx] + drag.accumulated, step(self.snap.grid));
+                }
+                DragKind::Rotate => {
+                    obj.transform.rotation[axis_idx] =
+                        snap(origin.rotation[axis_idx] + drag.accumulated, step(self.snap.angle));
+                }
+                DragKind::Scale => {
+                    let scale = snap(origin.scale[axis_idx] + drag.accumulated, step(self.snap.scale));
+                    obj.transform.scale[axis_idx] = scale.max(if snapping { self.snap.scale } else { 0.01 });
                 }
             }
         }
-        if let Some(sel) = &mut self.inspector.selected {
-            if let Some(obj) = world.objects.get(&sel.id) {
-                sel.position     = obj.transform.position;
-                sel.rotation_deg = obj.transform.rotation;
-                sel.scale        = obj.transform.scale;
-            }
+    }
+
+    /// Refresh the inspector's transform fields from the selected object.
+    fn sync_inspector(&mut self, world: &World) {
+        if let Some(sel) = &mut self.inspector.selected && let Some(obj) = world.objects.get(&sel.id) {
+            sel.position     = obj.transform.position;
+            sel.rotation_deg = obj.transform.rotation;
+            sel.scale        = obj.transform.scale;
         }
     }
     
+    /// Objects a drag on `object_id` moves: the group expansion, the
+    /// multi-selection, or just the object itself.
+    fn drag_ids(&self, object_id: usize) -> Vec<usize> {
+        if !self.group_ids.is_empty() { self.group_ids.clone() }
+        else if self.multi_selected.len() > 1 { self.multi_selected.clone() }
+        else { vec![object_id] }
+    }
+
     /// Zoom the camera towards / away from the pivot point.
     pub fn zoom(&mut self, camera: &mut Camera, delta: f32) {
         let off  = v3_sub(camera.eye, self.pivot);
@@ -422,20 +452,19 @@ impl EditorState {
         camera.target = point;
     }
     
-    /// Cast a ray from screen pixel `(sx, sy)` and return the nearest object ID.
+    /// Cast a ray from screen pixel `(sx, sy)` and return the nearest object
+    /// ID.  Objects on layers the camera does not render are ignored.
     pub fn pick(&self, camera: &Camera, world: &World, sx: f32, sy: f32) -> Option<usize> {
         let (ro, rd) = self.screen_to_ray(camera, sx, sy);
         let mut best_id   = None;
         let mut best_dist = f32::MAX;
         for (&id, obj) in &world.objects {
-            if self.gizmo_ids.contains(&id) || obj.geometry.is_none() { continue; }
+            if self.gizmo_ids.contains(&id) || obj.geometry.is_none() || !camera.sees_layer(obj.layer) { continue; }
             let wt   = compute_world_transform(world, id);
             // Use a per-axis AABB test so that scaling one axis only enlarges
             // the hit volume on that axis, not in every direction.
             let half = approx_half_extents(&obj.geometry, &wt);
-            if let Some(t) = ray_aabb(ro, rd, wt.position, half) {
-                if t < best_dist { best_dist = t; best_id = Some(id); }
-            }
+            if let Some(t) = ray_aabb(ro, rd, wt.position, half) && t < best_dist { best_dist = t; best_id = Some(id); }
         }
         best_id
     }
@@ -3,8 +3,10 @@
 use serde::{Deserialize, Serialize};
 use winit::keyboard::KeyCode;
 
+use crate::constants::editor;
 use crate::geometry::Geometry;
 use crate::objects::Object;
+use crate::transform::Transform;
 
 /// A snapshot of a selected scene object exposed by the editor inspector.
 ///
@@ -41,12 +43,15 @@ impl InspectorData {
 
 fn geometry_type_name(g: &Geometry) -> String {
     match g {
-        Geometry::Cube { .. }    => "Cube",
-        Geometry::Box { .. }     => "Box",
-        Geometry::Plane { .. }   => "Plane",
-        Geometry::Pyramid { .. } => "Pyramid",
-        Geometry::Capsule { .. } => "Capsule",
-        Geometry::Sphere { .. }  => "Sphere",
+        Geometry::Cube { .. }       => "Cube",
+        Geometry::Box { .. }        => "Box",
+        Geometry::RoundedBox { .. } => "RoundedBox",
+        Geometry::Plane { .. }      => "Plane",
+        Geometry::Pyramid { .. }    => "Pyramid",
+        Geometry::Capsule { .. }    => "Capsule",
+        Geometry::Sphere { .. }     => "Sphere",
+        Geometry::Arrow { .. }      => "Arrow",
+        Geometry::Sprite { .. }     => "Sprite",
     }.to_string()
 }
 
@@ -87,13 +92,47 @@ pub enum DragKind { Translate, Rotate, Scale }
 #[derive(Debug, Clone)]
 pub struct DragState {
     /// ID of the primary object being transformed.
-    pub object_id: usize,
+    pub object_id:   usize,
     /// Axis the drag is constrained to.
-    pub axis:      DragAxis,
+    pub axis:        DragAxis,
     /// World-space gizmo centre captured at drag-start (stable, no jitter).
-    pub center:    [f32; 3],
+    pub center:      [f32; 3],
     /// Which transform operation is being applied.
-    pub kind:      DragKind,
+    pub kind:        DragKind,
+    /// Local transforms of the dragged top-level objects at drag-start.
+    pub origins:     Vec<(usize, Transform)>,
+    /// Unsnapped change along [`Self::axis`] since drag-start: world units,
+    /// degrees or scale factor depending on [`Self::kind`].
+    pub accumulated: f32,
+}
+
+/// Grid and angle snapping for gizmo drags.
+///
+/// Snapping rounds the dragged component of each object's local transform
+/// to the nearest step, so objects land on clean values rather than moving
+/// by whole steps from wherever they started.  Holding **Ctrl** during a
+/// drag inverts [`Self::enabled`] for that motion.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SnapSettings {
+    /// Snap without holding Ctrl.
+    pub enabled: bool,
+    /// Translate step in world units.
+    pub grid:    f32,
+    /// Rotate step in degrees.
+    pub angle:   f32,
+    /// Scale step.
+    pub scale:   f32,
+}
+
+impl Default for SnapSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            grid:    editor::SNAP_GRID,
+            angle:   editor::SNAP_ANGLE,
+            scale:   editor::SNAP_SCALE,
+        }
+    }
 }
 
 /// Raw platform-agnostic input event fed into the editor subsystem.
@@ -162,7 +201,7 @@ pub enum EditorEvent {
 ///                 println!("[Editor] Selection → {name}"),
 ///         }
 ///     })
-///     .create();
+///     .create()?;
 /// ```
 #[derive(Debug, Clone, PartialEq)]
 pub enum EditorStateEvent {
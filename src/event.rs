@@ -8,6 +8,11 @@ pub use winit::{
         DeviceEvent, ElementState, Event, Modifiers, MouseButton,
         MouseScrollDelta, WindowEvent,
     },
-    event_loop::{ControlFlow, EventLoop, EventLoopWindowTarget},
+    event_loop::{ActiveEventLoop, ControlFlow, EventLoop, EventLoopClosed, EventLoopProxy},
     keyboard::PhysicalKey,
 };
+
+/// The Android activity handed to `android_main`; pass it to
+/// [`Window::with_android_app`](crate::window::Window::with_android_app).
+#[cfg(target_os = "android")]
+pub use winit::platform::android::activity::AndroidApp;
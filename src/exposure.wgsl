@@ -0,0 +1,99 @@
+// Auto exposure: a 256-bin log-luminance histogram of the HDR target, then a
+// single workgroup that averages it and eases the adapted exposure towards
+// `key / average luminance`.  Bin 0 collects near-black pixels, which are
+// left out of the average so a dark sky does not blow out the foreground.
+
+struct ExposureParams {
+    min_log_luminance: f32,
+    log_luminance_range: f32,
+    // 1 - exp(-dt * speed): fraction of the gap closed this frame.
+    adaptation: f32,
+    key: f32,
+    // Camera exposure, multiplied into the output.
+    compensation: f32,
+    pixel_count: f32,
+    _pad: vec2<f32>,
+};
+
+struct ExposureState {
+    adapted: f32,
+    // Exposure handed to the tonemapper (adapted * compensation).
+    output: f32,
+    average_luminance: f32,
+    _pad: f32,
+};
+
+@group(0) @binding(0) var<uniform> params: ExposureParams;
+@group(0) @binding(1) var t_hdr: texture_2d<f32>;
+@group(0) @binding(2) var<storage, read_write> histogram: array<atomic<u32>, 256>;
+@group(0) @binding(3) var<storage, read_write> state: ExposureState;
+
+const BLACK_THRESHOLD: f32 = 0.005;
+
+var<workgroup> local_bins: array<atomic<u32>, 256>;
+
+fn bin_of(color: vec3<f32>) -> u32 {
+    let lum = dot(color, vec3<f32>(0.2126, 0.7152, 0.0722));
+    if lum < BLACK_THRESHOLD {
+        return 0u;
+    }
+    let t = clamp((log2(lum) - params.min_log_luminance) / params.log_luminance_range, 0.0, 1.0);
+    return u32(t * 254.0 + 1.0);
+}
+
+@compute @workgroup_size(16, 16)
+fn build_histogram(
+    @builtin(global_invocation_id) gid: vec3<u32>,
+    @builtin(local_invocation_index) local: u32,
+) {
+    atomicStore(&local_bins[local], 0u);
+    workgroupBarrier();
+
+    let dims = textureDimensions(t_hdr);
+    if gid.x < dims.x && gid.y < dims.y {
+        let color = textureLoad(t_hdr, vec2<i32>(gid.xy), 0).rgb;
+        atomicAdd(&local_bins[bin_of(color)], 1u);
+    }
+    workgroupBarrier();
+
+    atomicAdd(&histogram[local], atomicLoad(&local_bins[local]));
+}
+
+var<workgroup> weighted: array<f32, 256>;
+
+@compute @workgroup_size(256)
+fn average(@builtin(local_invocation_index) local: u32) {
+    let count = atomicLoad(&histogram[local]);
+    weighted[local] = f32(count) * f32(local);
+    // Clear for the next frame.
+    atomicStore(&histogram[local], 0u);
+    workgroupBarrier();
+
+    for (var stride = 128u; stride > 0u; stride = stride >> 1u) {
+        if local < stride {
+            weighted[local] = weighted[local] + weighted[local + stride];
+        }
+        workgroupBarrier();
+    }
+
+    if local == 0u {
+        // `count` is bin 0 here: the near-black pixels.
+        let lit = params.pixel_count - f32(count);
+        if lit >= 1.0 {
+            let average_bin = weighted[0] / lit;
+            let log_lum = (average_bin - 1.0) / 254.0 * params.log_luminance_range + params.min_log_luminance;
+            let average_luminance = exp2(log_lum);
+            let target_exposure = params.key / average_luminance;
+            if state.adapted <= 0.0 {
+                // First frame: snap instead of fading in from black.
+                state.adapted = target_exposure;
+            } else {
+                state.adapted = state.adapted + (target_exposure - state.adapted) * params.adaptation;
+            }
+            state.average_luminance = average_luminance;
+        } else if state.adapted <= 0.0 {
+            state.adapted = 1.0;
+        }
+        state.output = state.adapted * params.compensation;
+    }
+}
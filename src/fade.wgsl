@@ -0,0 +1,20 @@
+// Full-screen colour fade drawn on top of the frame (scene transitions).
+// A single oversized triangle covers the viewport; no vertex buffer needed.
+
+struct FadeUniform {
+    color: vec4<f32>,
+};
+@group(0) @binding(0)
+var<uniform> fade: FadeUniform;
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+    let x = f32(i32(index & 1u) * 4 - 1);
+    let y = f32(i32(index >> 1u) * 4 - 1);
+    return vec4<f32>(x, y, 0.0, 1.0);
+}
+
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+    return fade.color;
+}
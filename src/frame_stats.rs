@@ -7,11 +7,12 @@
 //! are handed to every callback.
 
 use crate::constants::frame_stats::DEFAULT_SAMPLE_WINDOW_SECS;
+use crate::pipeline::RenderStats;
 
 /// Crate-internal smoothed performance counter.
 ///
 /// The committed public values (`fps`, `frame_time_ms`, `draw_calls`,
-/// `triangle_count`) are exposed directly on
+/// `triangle_count`, `render`) are exposed directly on
 /// [`FrameContext`](crate::window::FrameContext), this type is not part of
 /// the public API.
 #[derive(Debug, Clone)]
@@ -24,6 +25,8 @@ pub(crate) struct FrameStats {
     pub(crate) draw_calls: u32,
     /// Number of triangles rendered in the most recently rendered frame.
     pub(crate) triangle_count: u32,
+    /// Full renderer counters of the most recently rendered frame.
+    pub(crate) render: RenderStats,
 
     /// Timestamp of the start of the current accumulation window.
     pub(crate) last_sample_time: web_time::Instant,
@@ -40,6 +43,7 @@ impl FrameStats {
             frame_time_ms: 0.0,
             draw_calls: 0,
             triangle_count: 0,
+            render: RenderStats::default(),
             last_sample_time: web_time::Instant::now(),
             frames_collected: 0,
             sample_window_secs: DEFAULT_SAMPLE_WINDOW_SECS,
@@ -75,6 +79,14 @@ impl FrameStats {
         self.draw_calls     = draw_calls;
         self.triangle_count = triangle_count;
     }
+
+    /// Record the full [`RenderStats`] of a rendered frame.
+    ///
+    /// Like [`Self::set_gpu_stats`] the values are not smoothed.
+    pub(crate) fn set_render_stats(&mut self, stats: RenderStats) {
+        self.set_gpu_stats(stats.draw_calls, stats.triangle_count);
+        self.render = stats;
+    }
 }
 
 impl Default for FrameStats {
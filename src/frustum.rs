@@ -0,0 +1,240 @@
+//! View frustums and culling debug drawing.
+//!
+//! [`Frustum`] holds the six planes of a view-projection and tests spheres
+//! and boxes against them, the way [`crate::culling`] tests instances on the
+//! GPU.
+//!
+//! [`CullingDebug`], installed on
+//! [`crate::scene::Scene::culling_debug`], shows what culling does.
+//! Frustums are drawn as lines on top of the frame, and objects outside the
+//! culling frustum are tinted with [`CullingDebug::culled_tint`] instead of
+//! being left alone.  Call [`CullingDebug::freeze`] to keep culling against
+//! the camera as it is now, then fly away: the frozen frustum stays in
+//! place, everything outside it is tinted, and GPU-culled instances
+//! outside it disappear.  The scene camera's own frustum is only drawn
+//! where another camera sees it: in the minimap, mirrors and water
+//! reflections, or once the culling camera is frozen.
+//!
+//! Tinting covers dynamic geometry and registry meshes drawn one by one;
+//! static batches and instanced objects keep their colours.
+//!
+//! ```no_run
+//! # fn demo(scene: &mut vertra::scene::Scene) {
+//! use vertra::frustum::CullingDebug;
+//! let mut debug = CullingDebug::default();
+//! debug.freeze(&scene.camera);
+//! scene.culling_debug = Some(debug);
+//! # }
+//! ```
+
+use crate::camera::Camera;
+use crate::constants::frustum::{CAMERA_COLOR, CULLED_TINT, FROZEN_COLOR, LINE_WIDTH};
+use crate::material::Material;
+use crate::math::Matrix4;
+use crate::lines::{LineStyle, Lines};
+use crate::mesh::{BakedMesh, Vertex};
+use crate::occlusion::OcclusionBox;
+
+/// Loops of [`Frustum::corners`] outlining the near and far rectangles;
+/// each corner `i` of the near one is joined to `i + 4` on the far one.
+const LOOPS: [[usize; 4]; 2] = [[0, 1, 3, 2], [4, 5, 7, 6]];
+
+/// The six planes of a view-projection, each `[a, b, c, d]` with
+/// `a·x + b·y + c·z + d ≥ 0` inside.  Planes are not normalised.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frustum {
+    /// Left, right, bottom, top, near and far.
+    pub planes: [[f32; 4]; 6],
+}
+
+impl Frustum {
+    /// The frustum of `view_projection`, which maps depth to `[0, 1]`.
+    pub fn from_view_projection(view_projection: &Matrix4) -> Self {
+        let row = |i: usize| view_projection.data.map(|column| column[i]);
+        let [r0, r1, r2, r3] = [row(0), row(1), row(2), row(3)];
+        let add = |a: [f32; 4], b: [f32; 4]| [0, 1, 2, 3].map(|k| a[k] + b[k]);
+        let sub = |a: [f32; 4], b: [f32; 4]| [0, 1, 2, 3].map(|k| a[k] - b[k]);
+        Self { planes: [add(r3, r0), sub(r3, r0), add(r3, r1), sub(r3, r1), r2, sub(r3, r2)] }
+    }
+
+    /// The frustum `camera` renders.
+    pub fn from_camera(camera: &Camera) -> Self {
+        Self::from_view_projection(&camera.build_view_projection_matrix())
+    }
+
+    /// World corners of `view_projection`'s frustum; bit 0, 1 and 2 of the
+    /// index pick right over left, top over bottom and far over near.
+    /// `None` if the matrix cannot be inverted.
+    pub fn corners(view_projection: &Matrix4) -> Option<[[f32; 3]; 8]> {
+        let inverse = view_projection.inverse()?;
+        Some(std::array::from_fn(|i| {
+            let ndc = [(i & 1) as f32 * 2.0 - 1.0, (i >> 1 & 1) as f32 * 2.0 - 1.0, (i >> 2 & 1) as f32];
+            let [x, y, z, w] = inverse.mul_vec4([ndc[0], ndc[1], ndc[2], 1.0]);
+            [x / w, y / w, z / w]
+        }))
+    }
+
+    /// Whether any part of the sphere at `center` may be inside.
+    pub fn intersects_sphere(&self, center: [f32; 3], radius: f32) -> bool {
+        self.planes.iter().all(|p| {
+            let length = (p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt();
+            p[0] * center[0] + p[1] * center[1] + p[2] * center[2] + p[3] >= -radius * length
+        })
+    }
+
+    /// Whether any part of the axis-aligned box `[min, max]` may be inside.
+    /// Boxes near a frustum corner can pass without touching it.
+    pub fn intersects_box(&self, min: [f32; 3], max: [f32; 3]) -> bool {
+        self.planes.iter().all(|p| {
+            let far = [0, 1, 2].map(|k| if p[k] >= 0.0 { max[k] } else { min[k] });
+            p[0] * far[0] + p[1] * far[1] + p[2] * far[2] + p[3] >= 0.0
+        })
+    }
+}
+
+/// Culling debug settings.  Install on
+/// [`crate::scene::Scene::culling_debug`]; see the [module docs](self).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CullingDebug {
+    /// Camera culling is tested against in place of the scene camera, or
+    /// `None` to follow the scene camera.
+    pub frozen: Option<Camera>,
+    /// Colour of the scene camera's frustum lines, or `None` to hide them.
+    pub camera_color: Option<[f32; 4]>,
+    /// Colour of the frozen camera's frustum lines, or `None` to hide them.
+    pub frozen_color: Option<[f32; 4]>,
+    /// Colour mixed into objects outside the culling frustum; its alpha is
+    /// how much.
+    pub culled_tint: [f32; 4],
+    /// Width of frustum lines in pixels.
+    pub line_width: f32,
+}
+
+impl Default for CullingDebug {
+    fn default() -> Self {
+        Self {
+            frozen: None,
+            camera_color: Some(CAMERA_COLOR),
+            frozen_color: Some(FROZEN_COLOR),
+            culled_tint: CULLED_TINT,
+            line_width: LINE_WIDTH,
+        }
+    }
+}
+
+/// What [`CullingDebug`] draws in one frame, taken from the scene camera
+/// before any capture pass.
+pub(crate) struct DebugFrame {
+    /// Frustum objects are tinted against.
+    pub(crate) culling: Frustum,
+    /// Matrix of the frozen camera, when there is one.
+    pub(crate) frozen_view_projection: Option<Matrix4>,
+    /// Frustum corners and line colours; `true` marks the scene camera's.
+    outlines: Vec<([[f32; 3]; 8], [f32; 4], bool)>,
+}
+
+impl CullingDebug {
+    /// Set the colours of the scene camera's and the frozen camera's
+    /// frustum lines; `None` hides them.
+    pub fn with_colors(mut self, camera: Option<[f32; 4]>, frozen: Option<[f32; 4]>) -> Self {
+        self.camera_color = camera;
+        self.frozen_color = frozen;
+        self
+    }
+
+    /// Set the tint of culled objects.
+    pub fn with_culled_tint(mut self, tint: [f32; 4]) -> Self {
+        self.culled_tint = tint;
+        self
+    }
+
+    /// Set the width of frustum lines in pixels.
+    pub fn with_line_width(mut self, width: f32) -> Self {
+        self.line_width = width;
+        self
+    }
+
+    /// Cull against `camera` as it is now until [`Self::unfreeze`].
+    pub fn freeze(&mut self, camera: &Camera) {
+        self.frozen = Some(*camera);
+    }
+
+    /// Cull against the scene camera again.
+    pub fn unfreeze(&mut self) {
+        self.frozen = None;
+    }
+
+    /// The camera culling is tested against while `scene_camera` renders.
+    pub fn culling_camera<'a>(&'a self, scene_camera: &'a Camera) -> &'a Camera {
+        self.frozen.as_ref().unwrap_or(scene_camera)
+    }
+
+    /// `color` mixed with [`Self::culled_tint`].
+    pub fn tinted(&self, color: [f32; 4]) -> [f32; 4] {
+        let [r, g, b, amount] = self.culled_tint;
+        let mix = |from: f32, to: f32| from + (to - from) * amount.clamp(0.0, 1.0);
+        [mix(color[0], r), mix(color[1], g), mix(color[2], b), color[3]]
+    }
+
+    /// This frame's frustums seen from `scene_camera`.
+    pub(crate) fn frame(&self, scene_camera: &Camera) -> DebugFrame {
+        let frozen_view_projection = self.frozen.map(|camera| camera.build_view_projection_matrix());
+        let camera_view_projection = scene_camera.build_view_projection_matrix();
+        let outlines = [
+            (Some(camera_view_projection), self.camera_color, true),
+            (frozen_view_projection, self.frozen_color, false),
+        ]
+        .into_iter()
+        .filter_map(|(matrix, color, own)| Some((Frustum::corners(&matrix?)?, color?, own)))
+        .collect();
+        DebugFrame {
+            culling: Frustum::from_view_projection(frozen_view_projection.as_ref().unwrap_or(&camera_view_projection)),
+            frozen_view_projection,
+            outlines,
+        }
+    }
+
+    /// Tint the `vertices` of one object when their bounds, grown by the
+    /// displacement `reach`, are outside `frustum`.
+    pub(crate) fn tint_culled(&self, vertices: &mut [Vertex], reach: f32, frustum: &Frustum) {
+        let Some(first) = vertices.first() else { return };
+        let (min, max) = vertices.iter().fold((first.position, first.position), |(lo, hi), v| {
+            ([0, 1, 2].map(|k| lo[k].min(v.position[k])), [0, 1, 2].map(|k| hi[k].max(v.position[k])))
+        });
+        let (min, max) = (min.map(|v| v - reach), max.map(|v| v + reach));
+        if !frustum.intersects_box(min, max) {
+            for vertex in vertices {
+                let [r, g, b, _] = self.tinted([vertex.color[0], vertex.color[1], vertex.color[2], 1.0]);
+                vertex.color = [r, g, b];
+            }
+        }
+    }
+
+    /// `color` tinted when `mesh` placed by `model`, with `material`'s
+    /// displacement, is outside `frame`'s culling frustum.
+    pub(crate) fn mesh_color(&self, frame: &DebugFrame, mesh: &BakedMesh, model: &Matrix4, material: &Material, color: [f32; 4]) -> [f32; 4] {
+        let [x, y, z, r] = mesh.bounding_sphere;
+        let bbox = OcclusionBox::from_local(0, ([x - r, y - r, z - r], [x + r, y + r, z + r]), model)
+            .grown(material.displacement.reach());
+        if frame.culling.intersects_box(bbox.min, bbox.max) { color } else { self.tinted(color) }
+    }
+
+    /// Lines outlining `frame`'s frustums, drawn on top of the scene.  The
+    /// scene camera's own frustum is left out when `viewer_is_scene_camera`.
+    pub(crate) fn outline_lines(&self, frame: &DebugFrame, viewer_is_scene_camera: bool) -> Lines {
+        let mut lines = Lines::new();
+        let style = LineStyle::default().with_width(self.line_width).with_depth_test(false);
+        for (corners, color, own) in &frame.outlines {
+            if *own && viewer_is_scene_camera {
+                continue;
+            }
+            for corner_loop in LOOPS {
+                lines.push_closed_polyline(&corner_loop.map(|i| corners[i]), *color, style);
+            }
+            for i in 0..4 {
+                lines.push_segment(corners[i], corners[i + 4], *color, style);
+            }
+        }
+        lines
+    }
+}
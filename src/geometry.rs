@@ -1,12 +1,15 @@
+use crate::constants::geometry as consts;
+use std::collections::HashMap;
 use crate::mesh::{MeshData, Vertex};
 use crate::transform::Transform;
 use serde::{Serialize, Deserialize};
 
 /// A lightweight opaque handle to a geometry entry in a GPU registry.
 ///
-/// Returned by pipeline-internal registration routines; you do not typically
-/// need to construct or inspect this directly.
-#[derive(Debug, Copy, Clone)]
+/// Returned by [`crate::mesh::MeshRegistry::insert`] (usually via
+/// [`crate::scene::Scene::register_mesh`]) and stored on
+/// [`crate::objects::Object::mesh`] to reference a shared GPU mesh.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct GeometryId(pub usize);
 
 /// Procedural geometry primitives supported by the engine.
@@ -27,12 +30,31 @@ pub enum Geometry {
     /// An axis-aligned rectangular box centred at the origin.
     ///
     /// `width` = X extent, `height` = Y extent, `depth` = Z extent (full, not half).
-    Box { width: f32, height: f32, depth: f32 },
+    /// `segments` = grid cells per face along X, Y and Z; `[1, 1, 1]` gives
+    /// one quad per face.
+    Box {
+        width: f32,
+        height: f32,
+        depth: f32,
+        #[serde(default = "single_segments")]
+        segments: [usize; 3],
+    },
+    /// A box with rounded edges and corners, centred at the origin.
+    ///
+    /// `width`, `height` and `depth` are the full extents as for
+    /// [`Geometry::Box`]; `radius` is the bevel radius, clamped to half the
+    /// smallest extent.  `subdivisions` = segments per 90° of bevel.
+    RoundedBox { width: f32, height: f32, depth: f32, radius: f32, subdivisions: usize },
     /// A flat, double-sided horizontal plane centred at the origin lying in
     /// the XZ plane.
     ///
-    /// `size` is the full side length.
-    Plane { size: f32 },
+    /// `size` is the full side length.  `subdivisions` = grid cells along
+    /// each side; raise it for vertex displacement or terrain.
+    Plane {
+        size: f32,
+        #[serde(default = "single_segment")]
+        subdivisions: usize,
+    },
     /// A four-sided pyramid centred at the origin.
     ///
     /// The base is a square with full side `base_size` at `y = -height / 2`;
@@ -46,13 +68,81 @@ pub enum Geometry {
     /// * `subdivisions` — number of horizontal segments; higher values produce
     ///   a smoother silhouette.
     Capsule { radius: f32, height: f32, subdivisions: usize },
-    /// A UV sphere centred at the origin.
+    /// An arrow pointing along +Y with its tail at the origin, for
+    /// visualising direction vectors and normals.
+    ///
+    /// * `length`       — tail to tip, head included.
+    /// * `shaft_radius` — radius of the cylindrical shaft.
+    /// * `head_size`    — length of the cone head (capped at `length`); its
+    ///   base radius is half of this.
+    Arrow { length: f32, shaft_radius: f32, head_size: f32 },
+    /// A sphere centred at the origin, with vertices shared between its
+    /// triangles.
     ///
     /// * `radius`       — sphere radius.
-    /// * `subdivisions` — number of horizontal (longitude) segments; latitude
-    ///   segments are derived as `subdivisions / 2`.  Minimum effective value
-    ///   is 8 for a reasonable sphere.
-    Sphere { radius: f32, subdivisions: usize },
+    /// * `subdivisions` — smoothness; see [`SphereTessellation`] for how each
+    ///   tessellation reads it.  Both give about `subdivisions²` triangles.
+    /// * `tessellation` — how the surface is split into triangles.
+    Sphere {
+        radius: f32,
+        subdivisions: usize,
+        #[serde(default)]
+        tessellation: SphereTessellation,
+    },
+    /// A flat, double-sided `width` × `height` rectangle in the XY plane
+    /// facing -Z, textured with the `uv` region `[u0, v0, u1, v1]` (`v0` at
+    /// the top).  Driven by [`crate::sprite::AnimatedSprite`] for sprite
+    /// sheet animation.
+    Sprite { width: f32, height: f32, uv: [f32; 4] },
+}
+
+/// How [`Geometry::Sphere`] is split into triangles.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SphereTessellation {
+    /// Rings of latitude and longitude: `subdivisions` segments around
+    /// (at least 3) and half as many, at least 4, from pole to pole, each
+    /// pole a single vertex.  UVs wrap once around, with a seam of
+    /// duplicated vertices at `u = 0`.
+    #[default]
+    Uv,
+    /// A subdivided icosahedron, whose triangles are all about the same
+    /// size: each of its edges is split into `subdivisions / 4` segments
+    /// (at least 1).  UVs are projected as for [`Self::Uv`], with vertices
+    /// duplicated where triangles cross the seam.
+    Icosphere,
+}
+
+/// A tessellation target for [`Geometry::with_accuracy`], in world units.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Accuracy {
+    /// Longest triangle edge over the curved surface (a capsule's straight
+    /// body still spans its full height).
+    EdgeLength(f32),
+    /// Furthest an edge's midpoint may sit inside the true surface.  For an
+    /// error of at most `p` pixels on screen, pass `p` times
+    /// [`crate::camera::Camera::pixel_size_at`] the object's distance.
+    MaxError(f32),
+}
+
+impl Accuracy {
+    /// Widest angle one edge may span on a circle of `radius`.
+    fn segment_angle(self, radius: f32) -> f32 {
+        let radius = radius.abs().max(f32::EPSILON);
+        match self {
+            // Chord length 2r·sin(θ/2).
+            Accuracy::EdgeLength(edge) => 2.0 * (edge / (2.0 * radius)).clamp(0.0, 1.0).asin(),
+            // Sagitta r·(1 - cos(θ/2)).
+            Accuracy::MaxError(error) => 2.0 * (1.0 - error / radius).clamp(-1.0, 1.0).acos(),
+        }
+    }
+}
+
+fn single_segment() -> usize {
+    1
+}
+
+fn single_segments() -> [usize; 3] {
+    [1; 3]
 }
 
 impl Geometry {
@@ -78,14 +168,153 @@ impl Geometry {
     /// This is the low-level primitive used by the scene renderer to batch all
     /// objects into a single draw call each frame.
     pub fn generate_mesh_data(&self, mesh_data: &mut MeshData, transform: &Transform, color: [f32; 4]) {
+        self.generate_mesh_data_with(mesh_data, transform, |_| color);
+    }
+
+    /// This geometry with the `subdivisions` of a [`Geometry::Sphere`] or
+    /// [`Geometry::Capsule`] chosen from its radius to meet `accuracy`, so
+    /// bigger shapes get more triangles; other shapes are returned
+    /// unchanged.  Capped at [`consts::MAX_ADAPTIVE_SUBDIVISIONS`].
+    ///
+    /// ```rust,ignore
+    /// // At most half a pixel of error at the sphere's distance.
+    /// let error = 0.5 * camera.pixel_size_at(distance, viewport.height);
+    /// let sphere = Geometry::Sphere { radius: 40.0, subdivisions: 0, tessellation: SphereTessellation::Uv }
+    ///     .with_accuracy(Accuracy::MaxError(error));
+    /// ```
+    pub fn with_accuracy(mut self, accuracy: Accuracy) -> Self {
+        match &mut self {
+            Geometry::Sphere { radius, subdivisions, tessellation: SphereTessellation::Icosphere } => {
+                // Flat steps along a face span the widest angle at its
+                // centre once projected, where the face is closest to the
+                // centre of the sphere.
+                let widest = consts::ICOSAHEDRON_EDGE / consts::ICOSAHEDRON_INRADIUS;
+                let frequency = (widest / accuracy.segment_angle(*radius)).ceil();
+                *subdivisions = adaptive_subdivisions(4.0 * frequency);
+            }
+            Geometry::Sphere { radius, subdivisions, .. } | Geometry::Capsule { radius, subdivisions, .. } => {
+                // Quads of `step` by `step` at the equator have diagonals
+                // spanning acos(cos²(step)), the widest angle of any edge.
+                // Even, so the rings from pole to pole (half as many) take
+                // the same step.
+                let step = accuracy.segment_angle(*radius).cos().max(0.0).sqrt().acos();
+                let half = (std::f32::consts::PI / step).ceil();
+                *subdivisions = adaptive_subdivisions(2.0 * half);
+            }
+            _ => {}
+        }
+        self
+    }
+
+    /// Number of flat faces [`Self::generate_mesh_data_with`] colours
+    /// separately: 6 for boxes, 5 for pyramids, 2 for planes and 1 for curved
+    /// shapes.
+    ///
+    /// | Geometry          | Face order                                      |
+    /// |-------------------|-------------------------------------------------|
+    /// | `Cube`, `Box`,    | front (+Z), back, left (-X), right, top, bottom |
+    /// | `RoundedBox`      |                                                 |
+    /// | `Pyramid`         | front (+Z), right (+X), back, left, base        |
+    /// | `Plane`           | top, bottom                                     |
+    /// | `Sprite`          | front (-Z), back                                |
+    pub fn face_count(&self) -> usize {
+        match self {
+            Geometry::Cube { .. } | Geometry::Box { .. } | Geometry::RoundedBox { .. } => 6,
+            Geometry::Pyramid { .. } => 5,
+            Geometry::Plane { .. } | Geometry::Sprite { .. } => 2,
+            Geometry::Capsule { .. } | Geometry::Sphere { .. } | Geometry::Arrow { .. } => 1,
+        }
+    }
+
+    /// The variant and the bit patterns of its fields, for hashing shapes
+    /// (`f32` fields have no `Hash`).  Equal shapes give equal bits.
+    pub(crate) fn bits(&self) -> [u64; 7] {
+        let f = |x: f32| u64::from(x.to_bits());
+        let n = |x: usize| x as u64;
+        match *self {
+            Geometry::Cube { size } => [0, f(size), 0, 0, 0, 0, 0],
+            Geometry::Box { width, height, depth, segments: [sx, sy, sz] } => {
+                [1, f(width), f(height), f(depth), n(sx), n(sy), n(sz)]
+            }
+            Geometry::RoundedBox { width, height, depth, radius, subdivisions } => {
+                [2, f(width), f(height), f(depth), f(radius), n(subdivisions), 0]
+            }
+            Geometry::Plane { size, subdivisions } => [3, f(size), n(subdivisions), 0, 0, 0, 0],
+            Geometry::Pyramid { base_size, height } => [4, f(base_size), f(height), 0, 0, 0, 0],
+            Geometry::Capsule { radius, height, subdivisions } => [5, f(radius), f(height), n(subdivisions), 0, 0, 0],
+            Geometry::Arrow { length, shaft_radius, head_size } => [6, f(length), f(shaft_radius), f(head_size), 0, 0, 0],
+            Geometry::Sphere { radius, subdivisions, tessellation } => {
+                [7, f(radius), n(subdivisions), tessellation as u64, 0, 0, 0]
+            }
+            Geometry::Sprite { width, height, uv: [u0, v0, u1, v1] } => [8, f(width), f(height), f(u0), f(v0), f(u1), f(v1)],
+        }
+    }
+
+    /// Like [`Self::build`], but colours face `i` with
+    /// `colors[i % colors.len()]` (see [`Self::face_count`] for the order).
+    /// Handy for checking orientation and winding at a glance.
+    ///
+    /// ```rust,ignore
+    /// let (vertices, indices) = Geometry::Cube { size: 1.0 }.build_with_face_colors(&[
+    ///     [1.0, 0.0, 0.0, 1.0], [0.0, 1.0, 1.0, 1.0], // front, back
+    ///     [0.0, 1.0, 0.0, 1.0], [1.0, 0.0, 1.0, 1.0], // left, right
+    ///     [0.0, 0.0, 1.0, 1.0], [1.0, 1.0, 0.0, 1.0], // top, bottom
+    /// ]);
+    /// let mesh = scene.register_mesh(&MeshData { vertices, indices, ..MeshData::new() });
+    /// ```
+    ///
+    /// # Panics
+    /// If `colors` is empty.
+    pub fn build_with_face_colors(&self, colors: &[[f32; 4]]) -> (Vec<Vertex>, Vec<u32>) {
+        assert!(!colors.is_empty(), "build_with_face_colors needs at least one colour");
+        let mut mesh = MeshData::new();
+        self.generate_mesh_data_with(&mut mesh, &Transform::default(), |face| colors[face % colors.len()]);
+        (mesh.vertices, mesh.indices)
+    }
+
+    /// An XYZ axis helper: red, green and blue [`Geometry::Arrow`]s of
+    /// `length` along +X, +Y and +Z from the origin, for showing an
+    /// object's local frame.
+    ///
+    /// ```rust,ignore
+    /// let (vertices, indices) = Geometry::build_axes(1.0);
+    /// let mesh = scene.register_mesh(&MeshData { vertices, indices, ..MeshData::new() });
+    /// ```
+    pub fn build_axes(length: f32) -> (Vec<Vertex>, Vec<u32>) {
+        let arrow = Geometry::Arrow {
+            length,
+            shaft_radius: length * consts::AXES_SHAFT_RATIO,
+            head_size: length * consts::AXES_HEAD_RATIO,
+        };
+        let mut mesh = MeshData::new();
+        for (rotation, color) in [
+            ([0.0, 0.0, -90.0], [0.95, 0.15, 0.15, 1.0]), // X
+            ([0.0, 0.0, 0.0], [0.15, 0.95, 0.15, 1.0]),   // Y
+            ([90.0, 0.0, 0.0], [0.15, 0.15, 0.95, 1.0]),  // Z
+        ] {
+            let transform = Transform { rotation, ..Transform::default() };
+            arrow.generate_mesh_data(&mut mesh, &transform, color);
+        }
+        (mesh.vertices, mesh.indices)
+    }
+
+    /// [`Self::generate_mesh_data`] with a colour per face: `face_color` is
+    /// called with each face index in `0..self.face_count()`.
+    pub fn generate_mesh_data_with(
+        &self,
+        mesh_data: &mut MeshData,
+        transform: &Transform,
+        mut face_color: impl FnMut(usize) -> [f32; 4],
+    ) {
         match self {
             Geometry::Cube { size } => {
-                let s = *size * 0.5;
-                Geometry::Box { width: s, height: s, depth: s }.generate_mesh_data(
-                    mesh_data, transform, color
+                let s = *size;
+                Geometry::Box { width: s, height: s, depth: s, segments: [1; 3] }.generate_mesh_data_with(
+                    mesh_data, transform, face_color
                 );
             }
-            Geometry::Box { width, height, depth } => {
+            Geometry::Box { width, height, depth, segments } => {
+                let [sx, sy, sz] = *segments;
                 let w = width * 0.5;
                 let h = height * 0.5;
                 let d = depth * 0.5;
@@ -100,15 +329,54 @@ impl Geometry {
                 let p8 = [-w,  h, -d]; // Back-Top-Left
 
                 // Note: Winding order matters for culling!
-                mesh_data.add_transformed_quad([p1, p4, p3, p2], transform, color); // Front
-                mesh_data.add_transformed_quad([p6, p7, p8, p5], transform, color); // Back
-                mesh_data.add_transformed_quad([p5, p8, p4, p1], transform, color); // Left
-                mesh_data.add_transformed_quad([p2, p3, p7, p6], transform, color); // Right
-                mesh_data.add_transformed_quad([p4, p8, p7, p3], transform, color); // Top
-                mesh_data.add_transformed_quad([p5, p1, p2, p6], transform, color); // Bottom
-            }
-            Geometry::Plane { size } => {
+                // Segment counts follow each face's first and last edge.
+                mesh_data.add_transformed_grid([p1, p4, p3, p2], [sy, sx], transform, face_color(0)); // Front
+                mesh_data.add_transformed_grid([p6, p7, p8, p5], [sy, sx], transform, face_color(1)); // Back
+                mesh_data.add_transformed_grid([p5, p8, p4, p1], [sy, sz], transform, face_color(2)); // Left
+                mesh_data.add_transformed_grid([p2, p3, p7, p6], [sy, sz], transform, face_color(3)); // Right
+                mesh_data.add_transformed_grid([p4, p8, p7, p3], [sz, sx], transform, face_color(4)); // Top
+                mesh_data.add_transformed_grid([p5, p1, p2, p6], [sz, sx], transform, face_color(5)); // Bottom
+            }
+            Geometry::RoundedBox { width, height, depth, radius, subdivisions } => {
+                // Negative extents mirror to positive ones and NaN collapses to
+                // zero, so the clamp bounds below are always ordered.
+                let half = [width, height, depth].map(|e| (e * 0.5).abs().max(0.0));
+                let r = radius.max(0.0).min(half[0].min(half[1]).min(half[2]));
+                let inner = half.map(|h| h - r);
+                let steps = half.map(|h| bevel_steps(h, r, *subdivisions));
+                let [w, h, d] = half;
+
+                // Each face is a box face gridded so that its border cells
+                // cover half of every adjoining bevel, then pushed out onto
+                // the rounded surface.  Face order and winding match `Box`.
+                // Corners as for `Box`, plus the axes along c0→c1 and c0→c3.
+                let faces = [
+                    ([[-w, -h,  d], [-w,  h,  d], [ w,  h,  d], [ w, -h,  d]], [1, 0]), // Front
+                    ([[ w, -h, -d], [ w,  h, -d], [-w,  h, -d], [-w, -h, -d]], [1, 0]), // Back
+                    ([[-w, -h, -d], [-w,  h, -d], [-w,  h,  d], [-w, -h,  d]], [1, 2]), // Left
+                    ([[ w, -h,  d], [ w,  h,  d], [ w,  h, -d], [ w, -h, -d]], [1, 2]), // Right
+                    ([[-w,  h,  d], [-w,  h, -d], [ w,  h, -d], [ w,  h,  d]], [2, 0]), // Top
+                    ([[-w, -h, -d], [-w, -h,  d], [ w, -h,  d], [ w, -h, -d]], [2, 0]), // Bottom
+                ];
+                for (face, ([c0, c1, _, c3], [s_axis, t_axis])) in faces.into_iter().enumerate() {
+                    let color = face_color(face);
+                    let point = |s: f32, t: f32| {
+                        let q = [0, 1, 2].map(|k| c0[k] + s * (c1[k] - c0[k]) + t * (c3[k] - c0[k]));
+                        round_corner(q, inner, r)
+                    };
+                    for t in steps[t_axis].windows(2) {
+                        for s in steps[s_axis].windows(2) {
+                            mesh_data.add_transformed_quad(
+                                [point(s[0], t[0]), point(s[1], t[0]), point(s[1], t[1]), point(s[0], t[1])],
+                                transform, color
+                            );
+                        }
+                    }
+                }
+            }
+            Geometry::Plane { size, subdivisions } => {
                 let s = size * 0.5;
+                let n = *subdivisions;
 
                 // Since using culling makes the back of the geometry not visible,
                 // we can instead make 2 copies of switched vertices.
@@ -118,10 +386,26 @@ impl Geometry {
                 let p4 = [-s, 0.0, -s];
 
                 // Push the top face
-                mesh_data.add_transformed_quad([p1, p2, p3, p4], transform, color);
+                mesh_data.add_transformed_grid([p1, p2, p3, p4], [n, n], transform, face_color(0));
 
                 // Push the bottom face (reversed order)
-                mesh_data.add_transformed_quad([p4, p3, p2, p1], transform, color);
+                mesh_data.add_transformed_grid([p4, p3, p2, p1], [n, n], transform, face_color(1));
+            }
+            Geometry::Sprite { width, height, uv } => {
+                let (w, h) = (width * 0.5, height * 0.5);
+                let [u0, v0, u1, v1] = *uv;
+                let corners = [[-w, -h, 0.0], [w, -h, 0.0], [w, h, 0.0], [-w, h, 0.0]];
+                let uvs = [[u0, v1], [u1, v1], [u1, v0], [u0, v0]];
+
+                // The back face reuses the corners' UVs, so it shows the
+                // texture mirrored, like the reverse of a sheet of paper.
+                for (face, order) in [[0, 1, 2, 3], [3, 2, 1, 0]].into_iter().enumerate() {
+                    let start = mesh_data.vertices.len();
+                    mesh_data.add_transformed_quad(order.map(|i| corners[i]), transform, face_color(face));
+                    for (vertex, i) in mesh_data.vertices[start..].iter_mut().zip(order) {
+                        vertex.uv = uvs[i];
+                    }
+                }
             }
             Geometry::Pyramid { base_size, height } => {
                 let s = base_size * 0.5;
@@ -134,14 +418,15 @@ impl Geometry {
                 let b4 = [-s, -h, -s]; // Back-Left
 
                 // 4 Sides
-                mesh_data.add_transformed_triangle([tip, b1, b2], transform, color); // Front
-                mesh_data.add_transformed_triangle([tip, b2, b3], transform, color); // Right
-                mesh_data.add_transformed_triangle([tip, b3, b4], transform, color); // Back
-                mesh_data.add_transformed_triangle([tip, b4, b1], transform, color); // Left
+                mesh_data.add_transformed_triangle([tip, b2, b1], transform, face_color(0)); // Front
+                mesh_data.add_transformed_triangle([tip, b3, b2], transform, face_color(1)); // Right
+                mesh_data.add_transformed_triangle([tip, b4, b3], transform, face_color(2)); // Back
+                mesh_data.add_transformed_triangle([tip, b1, b4], transform, face_color(3)); // Left
                 // Base
-                mesh_data.add_transformed_quad([b4, b3, b2, b1], transform, color);
+                mesh_data.add_transformed_quad([b1, b2, b3, b4], transform, face_color(4));
             }
             Geometry::Capsule { radius, height, subdivisions } => {
+                let color = face_color(0);
                 let r = *radius;
                 let h = *height;
                 let subs = *subdivisions as f32;
@@ -179,64 +464,254 @@ impl Geometry {
                         let r1 = phi1.cos() * r; let y1 = phi1.sin() * r;
                         let r2 = phi2.cos() * r; let y2 = phi2.sin() * r;
 
+                        // The last ring meets at the pole, where a quad would
+                        // collapse into a zero-area triangle; emit a triangle instead.
+                        let at_pole = j + 1 == lat_subs;
+
                         // TOP CAP (Facing Outwards/Up)
-                        mesh_data.add_transformed_quad(
-                            [
-                                [x1 * r1,  half_h + y1, z1 * r1],
-                                [x2 * r1,  half_h + y1, z2 * r1],
-                                [x2 * r2,  half_h + y2, z2 * r2],
-                                [x1 * r2,  half_h + y2, z1 * r2],
-                            ],
-                            transform, color
-                        );
+                        if at_pole {
+                            mesh_data.add_transformed_triangle(
+                                [
+                                    [x1 * r1, half_h + y1, z1 * r1],
+                                    [x2 * r1, half_h + y1, z2 * r1],
+                                    [0.0,     half_h + r,  0.0],
+                                ],
+                                transform, color
+                            );
+                        } else {
+                            mesh_data.add_transformed_quad(
+                                [
+                                    [x1 * r1,  half_h + y1, z1 * r1],
+                                    [x2 * r1,  half_h + y1, z2 * r1],
+                                    [x2 * r2,  half_h + y2, z2 * r2],
+                                    [x1 * r2,  half_h + y2, z1 * r2],
+                                ],
+                                transform, color
+                            );
+                        }
 
                         // BOTTOM CAP (Facing Outwards/Down)
                         // To ensure the "base" renders, we reverse the sequence of x1 and x2
                         // so the normal faces DOWN.
-                        mesh_data.add_transformed_quad(
-                            [
-                                [x1 * r1, -half_h - y1, z1 * r1],
-                                [x1 * r2, -half_h - y2, z1 * r2],
-                                [x2 * r2, -half_h - y2, z2 * r2],
-                                [x2 * r1, -half_h - y1, z2 * r1],
-                            ],
-                            transform, color
-                        );
+                        if at_pole {
+                            mesh_data.add_transformed_triangle(
+                                [
+                                    [x1 * r1, -half_h - y1, z1 * r1],
+                                    [0.0,     -half_h - r,  0.0],
+                                    [x2 * r1, -half_h - y1, z2 * r1],
+                                ],
+                                transform, color
+                            );
+                        } else {
+                            mesh_data.add_transformed_quad(
+                                [
+                                    [x1 * r1, -half_h - y1, z1 * r1],
+                                    [x1 * r2, -half_h - y2, z1 * r2],
+                                    [x2 * r2, -half_h - y2, z2 * r2],
+                                    [x2 * r1, -half_h - y1, z2 * r1],
+                                ],
+                                transform, color
+                            );
+                        }
                     }
                 }
             }
-            Geometry::Sphere { radius, subdivisions } => {
-                let r = *radius;
-                let subs = *subdivisions as f32;
-                let lat_subs = (*subdivisions / 2).max(4);
+            Geometry::Arrow { length, shaft_radius, head_size } => {
+                let color = face_color(0);
+                let head = head_size.clamp(0.0, *length);
+                let (shaft_r, head_r) = (*shaft_radius, head * 0.5);
+                let neck = length - head;
+                let ring = |i: usize, r: f32, y: f32| {
+                    let t = i as f32 * std::f32::consts::TAU / consts::ARROW_SEGMENTS as f32;
+                    [t.cos() * r, y, t.sin() * r]
+                };
 
-                for i in 0..*subdivisions {
-                    let t1 = (i as f32 * 2.0 * std::f32::consts::PI) / subs;
-                    let t2 = ((i + 1) as f32 * 2.0 * std::f32::consts::PI) / subs;
+                for i in 0..consts::ARROW_SEGMENTS {
+                    let (s1, s2) = (ring(i, shaft_r, neck), ring(i + 1, shaft_r, neck));
+                    let (h1, h2) = (ring(i, head_r, neck), ring(i + 1, head_r, neck));
+                    let (b1, b2) = (ring(i, shaft_r, 0.0), ring(i + 1, shaft_r, 0.0));
 
-                    let (x1, z1) = (t1.cos(), t1.sin());
-                    let (x2, z2) = (t2.cos(), t2.sin());
+                    // Tail cap, shaft and cone.
+                    mesh_data.add_transformed_triangle([[0.0; 3], b2, b1], transform, color);
+                    if neck > 0.0 {
+                        mesh_data.add_transformed_quad([b1, b2, s2, s1], transform, color);
+                    }
+                    mesh_data.add_transformed_triangle([h1, h2, [0.0, *length, 0.0]], transform, color);
 
-                    for j in 0..lat_subs {
-                        // Angle from bottom (-PI/2) to top (PI/2)
-                        let phi1 = (j as f32 * std::f32::consts::PI) / lat_subs as f32 - std::f32::consts::FRAC_PI_2;
-                        let phi2 = ((j + 1) as f32 * std::f32::consts::PI) / lat_subs as f32 - std::f32::consts::FRAC_PI_2;
+                    // Ring under the head where it overhangs the shaft (or on
+                    // top of the shaft if the head is narrower).
+                    if head_r > shaft_r {
+                        mesh_data.add_transformed_quad([s2, h2, h1, s1], transform, color);
+                    } else if head_r < shaft_r {
+                        mesh_data.add_transformed_quad([h1, s1, s2, h2], transform, color);
+                    }
+                }
+            }
+            Geometry::Sphere { radius, subdivisions, tessellation } => {
+                let color = face_color(0);
+                let start = mesh_data.vertices.len();
+                match tessellation {
+                    SphereTessellation::Uv => uv_sphere(mesh_data, *subdivisions, color),
+                    SphereTessellation::Icosphere => icosphere(mesh_data, (*subdivisions / 4).max(1), color),
+                }
+                let matrix = transform.to_matrix();
+                for vertex in &mut mesh_data.vertices[start..] {
+                    let [x, y, z] = vertex.position.map(|c| c * radius);
+                    let [x, y, z, _] = matrix.mul_vec4([x, y, z, 1.0]);
+                    vertex.position = [x, y, z];
+                }
+            }
+        }
+    }
+}
 
-                        let r1 = phi1.cos() * r; let y1 = phi1.sin() * r;
-                        let r2 = phi2.cos() * r; let y2 = phi2.sin() * r;
+/// Append a unit [`SphereTessellation::Uv`] sphere with `segments` around.
+fn uv_sphere(mesh_data: &mut MeshData, segments: usize, color: [f32; 4]) {
+    let lon = segments.max(3);
+    let lat = (segments / 2).max(4);
+    let color = [color[0], color[1], color[2]];
+    let base = mesh_data.vertices.len() as u32;
 
-                        mesh_data.add_transformed_quad(
-                            [
-                                [x1 * r1, y1, z1 * r1],
-                                [x2 * r1, y1, z2 * r1],
-                                [x2 * r2, y2, z2 * r2],
-                                [x1 * r2, y2, z1 * r2],
-                            ],
-                            transform, color
-                        );
-                    }
+    // South pole, the rings from bottom to top with the first column
+    // repeated at `u = 1`, then the north pole.
+    mesh_data.vertices.push(Vertex { position: [0.0, -1.0, 0.0], color, uv: [0.5, 1.0] });
+    for j in 1..lat {
+        let phi = j as f32 * std::f32::consts::PI / lat as f32 - std::f32::consts::FRAC_PI_2;
+        let (y, ring) = (phi.sin(), phi.cos());
+        for i in 0..=lon {
+            let theta = i as f32 * std::f32::consts::TAU / lon as f32;
+            mesh_data.vertices.push(Vertex {
+                position: [theta.cos() * ring, y, theta.sin() * ring],
+                color,
+                uv: [i as f32 / lon as f32, 1.0 - j as f32 / lat as f32],
+            });
+        }
+    }
+    mesh_data.vertices.push(Vertex { position: [0.0, 1.0, 0.0], color, uv: [0.5, 0.0] });
+
+    let row = lon as u32 + 1;
+    let at = |i: usize, j: usize| base + 1 + (j as u32 - 1) * row + i as u32;
+    let north = base + 1 + (lat as u32 - 1) * row;
+    for i in 0..lon {
+        mesh_data.indices.extend([base, at(i + 1, 1), at(i, 1)]);
+        for j in 1..lat - 1 {
+            let [a, b, c, d] = [at(i, j), at(i + 1, j), at(i + 1, j + 1), at(i, j + 1)];
+            mesh_data.indices.extend([a, b, c, a, c, d]);
+        }
+        mesh_data.indices.extend([at(i, lat - 1), at(i + 1, lat - 1), north]);
+    }
+}
+
+/// Append a unit [`SphereTessellation::Icosphere`] whose icosahedron edges
+/// are split into `frequency` segments.
+fn icosphere(mesh_data: &mut MeshData, frequency: usize, color: [f32; 4]) {
+    let t = (1.0 + 5f32.sqrt()) * 0.5;
+    let corners = [
+        [-1.0, t, 0.0], [1.0, t, 0.0], [-1.0, -t, 0.0], [1.0, -t, 0.0],
+        [0.0, -1.0, t], [0.0, 1.0, t], [0.0, -1.0, -t], [0.0, 1.0, -t],
+        [t, 0.0, -1.0], [t, 0.0, 1.0], [-t, 0.0, -1.0], [-t, 0.0, 1.0],
+    ];
+    let faces: [[usize; 3]; 20] = [
+        [0, 11, 5], [0, 5, 1], [0, 1, 7], [0, 7, 10], [0, 10, 11],
+        [1, 5, 9], [5, 11, 4], [11, 10, 2], [10, 7, 6], [7, 1, 8],
+        [3, 9, 4], [3, 4, 2], [3, 2, 6], [3, 6, 8], [3, 8, 9],
+        [4, 9, 5], [2, 4, 11], [6, 2, 10], [8, 6, 7], [9, 8, 1],
+    ];
+    let color = [color[0], color[1], color[2]];
+
+    // Points of each face's triangular grid are named by their weights on
+    // the icosahedron corners, so points on shared edges are created once.
+    let mut points: HashMap<[(usize, usize); 3], u32> = HashMap::new();
+    let mut point = |mesh_data: &mut MeshData, weights: [(usize, usize); 3]| {
+        let mut key = weights.map(|(corner, w)| if w == 0 { (usize::MAX, 0) } else { (corner, w) });
+        key.sort_unstable();
+        *points.entry(key).or_insert_with(|| {
+            let p = weights.iter().fold([0.0f32; 3], |p, &(corner, w)| {
+                [0, 1, 2].map(|k| p[k] + corners[corner][k] * w as f32)
+            });
+            let len = (p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt();
+            let position = p.map(|c| c / len);
+            let u = (position[2].atan2(position[0]) / std::f32::consts::TAU).rem_euclid(1.0);
+            let v = 0.5 - position[1].clamp(-1.0, 1.0).asin() / std::f32::consts::PI;
+            mesh_data.vertices.push(Vertex { position, color, uv: [u, v] });
+            (mesh_data.vertices.len() - 1) as u32
+        })
+    };
+
+    let n = frequency;
+    let mut triangles = Vec::with_capacity(20 * n * n);
+    // The table winds counter-clockwise seen from outside; outward faces
+    // here are clockwise (see `Box`), hence `[a, c, b]`.
+    for [a, c, b] in faces {
+        let mut at = |i: usize, j: usize| point(mesh_data, [(a, n - i - j), (b, i), (c, j)]);
+        for j in 0..n {
+            for i in 0..n - j {
+                triangles.push([at(i, j), at(i + 1, j), at(i, j + 1)]);
+                if i + j + 1 < n {
+                    triangles.push([at(i + 1, j), at(i + 1, j + 1), at(i, j + 1)]);
                 }
             }
         }
     }
-}
\ No newline at end of file
+
+    // Triangles spanning the `u = 0` seam take copies of their low-`u`
+    // vertices shifted to `u + 1`.
+    let mut wrapped: HashMap<u32, u32> = HashMap::new();
+    for triangle in &mut triangles {
+        let us = triangle.map(|i| mesh_data.vertices[i as usize].uv[0]);
+        let (lo, hi) = us.iter().fold((1.0f32, 0.0f32), |(lo, hi), &u| (lo.min(u), hi.max(u)));
+        if hi - lo <= 0.5 {
+            continue;
+        }
+        for index in triangle.iter_mut() {
+            if mesh_data.vertices[*index as usize].uv[0] < 0.5 {
+                *index = *wrapped.entry(*index).or_insert_with(|| {
+                    let mut copy = mesh_data.vertices[*index as usize];
+                    copy.uv[0] += 1.0;
+                    mesh_data.vertices.push(copy);
+                    (mesh_data.vertices.len() - 1) as u32
+                });
+            }
+        }
+    }
+    mesh_data.indices.extend(triangles.into_iter().flatten());
+}
+
+/// `n` clamped to the subdivisions [`Geometry::with_accuracy`] may choose.
+fn adaptive_subdivisions(n: f32) -> usize {
+    (n as usize).clamp(consts::MIN_ADAPTIVE_SUBDIVISIONS, consts::MAX_ADAPTIVE_SUBDIVISIONS)
+}
+
+/// Grid fractions (0 → 1) across one box axis of half-extent `half` for a
+/// [`Geometry::RoundedBox`]: `n` cells over each 45° half-bevel at both ends
+/// and one across the flat middle.  Spacing by `tan` makes the cells equal
+/// arcs once [`round_corner`] projects them onto the bevel.
+fn bevel_steps(half: f32, radius: f32, n: usize) -> Vec<f32> {
+    // A flat axis has nothing to bevel, and the fractions would divide by zero.
+    if half <= 0.0 {
+        return vec![0.0, 1.0];
+    }
+    let n = n.max(1);
+    let inner = half - radius;
+    let offset = |i: usize| radius * (std::f32::consts::FRAC_PI_4 * i as f32 / n as f32).tan();
+    let mut steps: Vec<f32> = (0..=n)
+        .map(|i| -inner - offset(n - i))
+        .chain((0..=n).map(|i| inner + offset(i)))
+        .map(|x| (x + half) / (2.0 * half))
+        .collect();
+    // A zero radius or a fully rounded axis repeats a step.
+    steps.dedup_by(|a, b| (*a - *b).abs() < 1e-6);
+    steps
+}
+
+/// Project a point on the box surface onto the rounded box whose flat faces
+/// are inset to `inner` half-extents with bevel `radius`.
+fn round_corner(q: [f32; 3], inner: [f32; 3], radius: f32) -> [f32; 3] {
+    let c = [0, 1, 2].map(|k| q[k].clamp(-inner[k], inner[k]));
+    let d = [q[0] - c[0], q[1] - c[1], q[2] - c[2]];
+    let len = (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt();
+    if len <= f32::EPSILON {
+        return q;
+    }
+    [0, 1, 2].map(|k| c[k] + d[k] * radius / len)
+}
@@ -0,0 +1,315 @@
+//! glTF 2.0 scene export.
+//!
+//! [`export_glb`] and [`export_gltf`] write a [`World`] as a glTF scene that
+//! Blender, three.js and most engines open directly:
+//!
+//! - one node per object (ascending id), named after it, with its local
+//!   translation, rotation (as a quaternion) and scale, and the parent /
+//!   child hierarchy preserved; the object's `str_id` goes in `extras`
+//! - everything converted from Vertra's left-handed space to glTF's
+//!   right-handed one by mirroring Z: positions and translations negate Z,
+//!   rotations become `(-x, -y, z, w)`, and triangles, whose handedness the
+//!   mirror reverses, wind counter-clockwise seen from outside
+//! - procedural geometry as a mesh in the object's local space, shared by
+//!   every object with the same geometry and material
+//! - one material per distinct colour / texture: `baseColorFactor` from
+//!   [`Object::color`](crate::objects::Object::color) (`BLEND` when
+//!   translucent) and `baseColorTexture` referencing `texture_path` as an
+//!   external image
+//!
+//! Objects that only reference a registry
+//! [`Object::mesh`](crate::objects::Object::mesh) become empty nodes: those
+//! vertices live on the GPU.  Normals are not written; viewers fall back to
+//! flat shading.
+//!
+//! ```no_run
+//! # fn demo(scene: &vertra::scene::Scene) -> std::io::Result<()> {
+//! let file = std::io::BufWriter::new(std::fs::File::create("world.glb")?);
+//! vertra::gltf::export_glb(&scene.world, file)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::io::{self, Write};
+use serde_json::{json, Value};
+use crate::geometry::Geometry;
+use crate::mesh::{to_right_handed, MeshData};
+use crate::transform::Transform;
+use crate::world::World;
+
+/// `componentType` for 32-bit floats.
+const FLOAT: u32 = 5126;
+/// `componentType` for 32-bit unsigned integers.
+const UNSIGNED_INT: u32 = 5125;
+/// `target` of vertex attribute buffer views.
+const ARRAY_BUFFER: u32 = 34962;
+/// `target` of index buffer views.
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+/// Write `world` as binary glTF (`.glb`): the JSON document and its vertex
+/// buffer in one file.
+pub fn export_glb(world: &World, mut writer: impl Write) -> io::Result<()> {
+    let (mut document, buffer) = build(world);
+    if !buffer.is_empty() {
+        document["buffers"] = json!([{ "byteLength": buffer.len() }]);
+    }
+    let mut json = serde_json::to_vec(&document).map_err(io::Error::other)?;
+    pad(&mut json, b' ');
+    let mut bin = buffer;
+    pad(&mut bin, 0);
+
+    let mut length = 12 + 8 + json.len();
+    if !bin.is_empty() {
+        length += 8 + bin.len();
+    }
+    writer.write_all(b"glTF")?;
+    writer.write_all(&2u32.to_le_bytes())?;
+    writer.write_all(&(length as u32).to_le_bytes())?;
+    writer.write_all(&(json.len() as u32).to_le_bytes())?;
+    writer.write_all(b"JSON")?;
+    writer.write_all(&json)?;
+    if !bin.is_empty() {
+        writer.write_all(&(bin.len() as u32).to_le_bytes())?;
+        writer.write_all(b"BIN\0")?;
+        writer.write_all(&bin)?;
+    }
+    writer.flush()
+}
+
+/// Write `world` as a self-contained `.gltf` JSON document, the vertex
+/// buffer embedded as a base64 data URI.
+pub fn export_gltf(world: &World, mut writer: impl Write) -> io::Result<()> {
+    let (mut document, buffer) = build(world);
+    if !buffer.is_empty() {
+        document["buffers"] = json!([{
+            "byteLength": buffer.len(),
+            "uri": format!("data:application/octet-stream;base64,{}", base64(&buffer)),
+        }]);
+    }
+    serde_json::to_writer_pretty(&mut writer, &document).map_err(io::Error::other)?;
+    writer.flush()
+}
+
+/// Unit quaternion `[x, y, z, w]` for a [`Transform::rotation`] in Euler
+/// degrees, matching the `Ry · Rx · Rz` order of [`Transform::to_matrix`].
+///
+/// The quaternion is in Vertra's left-handed space; exported nodes store it
+/// mirrored into glTF's.
+pub fn euler_to_quaternion(rotation: [f32; 3]) -> [f32; 4] {
+    let axis = |k: usize| {
+        let half = rotation[k].to_radians() * 0.5;
+        let mut q = [0.0, 0.0, 0.0, half.cos()];
+        q[k] = half.sin();
+        q
+    };
+    mul(mul(axis(1), axis(0)), axis(2))
+}
+
+/// Hamilton product `a · b` of two `[x, y, z, w]` quaternions.
+fn mul(a: [f32; 4], b: [f32; 4]) -> [f32; 4] {
+    let [ax, ay, az, aw] = a;
+    let [bx, by, bz, bw] = b;
+    [
+        aw * bx + ax * bw + ay * bz - az * by,
+        aw * by - ax * bz + ay * bw + az * bx,
+        aw * bz + ax * by - ay * bx + az * bw,
+        aw * bw - ax * bx - ay * by - az * bz,
+    ]
+}
+
+/// Accumulates the binary buffer and the glTF arrays that index into it.
+#[derive(Default)]
+struct Builder {
+    buffer:        Vec<u8>,
+    views:         Vec<Value>,
+    accessors:     Vec<Value>,
+    meshes:        Vec<Value>,
+    mesh_keys:     Vec<(Geometry, usize)>,
+    materials:     Vec<Value>,
+    material_keys: Vec<([f32; 4], Option<String>)>,
+    images:        Vec<Value>,
+}
+
+impl Builder {
+    /// Append `bytes` as a new 4-byte aligned buffer view, returning its index.
+    fn view(&mut self, bytes: &[u8], target: u32) -> usize {
+        pad(&mut self.buffer, 0);
+        self.views.push(json!({
+            "buffer": 0,
+            "byteOffset": self.buffer.len(),
+            "byteLength": bytes.len(),
+            "target": target,
+        }));
+        self.buffer.extend_from_slice(bytes);
+        self.views.len() - 1
+    }
+
+    /// Append an accessor over a whole buffer view, returning its index.
+    fn accessor(&mut self, view: usize, component: u32, count: usize, kind: &str) -> usize {
+        self.accessors.push(json!({
+            "bufferView": view,
+            "componentType": component,
+            "count": count,
+            "type": kind,
+        }));
+        self.accessors.len() - 1
+    }
+
+    /// Index of the material for `color` and `texture`, created on first use.
+    fn material(&mut self, color: [f32; 4], texture: &Option<String>) -> usize {
+        let key = (color, texture.clone());
+        if let Some(index) = self.material_keys.iter().position(|k| *k == key) {
+            return index;
+        }
+        let mut pbr = json!({
+            "baseColorFactor": color,
+            "metallicFactor": 0.0,
+            "roughnessFactor": 1.0,
+        });
+        if let Some(uri) = texture {
+            self.images.push(json!({ "uri": uri }));
+            pbr["baseColorTexture"] = json!({ "index": self.images.len() - 1 });
+        }
+        let mut material = json!({ "pbrMetallicRoughness": pbr });
+        if color[3] < 1.0 {
+            material["alphaMode"] = json!("BLEND");
+        }
+        self.materials.push(material);
+        self.material_keys.push(key);
+        self.materials.len() - 1
+    }
+
+    /// Index of the mesh for `geometry` drawn with `material`, created on
+    /// first use, or `None` when the geometry produces no triangles.
+    fn mesh(&mut self, geometry: &Geometry, material: usize) -> Option<usize> {
+        let key = (geometry.clone(), material);
+        if let Some(index) = self.mesh_keys.iter().position(|k| *k == key) {
+            return Some(index);
+        }
+        let mut data = MeshData::new();
+        geometry.generate_mesh_data(&mut data, &Transform::default(), [1.0; 4]);
+        if data.indices.is_empty() {
+            return None;
+        }
+        for vertex in &mut data.vertices {
+            vertex.position = to_right_handed(vertex.position);
+        }
+        let (min, max) = data.bounds()?;
+        let count = data.vertices.len();
+
+        let view = self.view(&le_bytes(data.vertices.iter().flat_map(|v| v.position)), ARRAY_BUFFER);
+        let position = self.accessor(view, FLOAT, count, "VEC3");
+        self.accessors[position]["min"] = json!(min);
+        self.accessors[position]["max"] = json!(max);
+        let view = self.view(&le_bytes(data.vertices.iter().flat_map(|v| v.uv)), ARRAY_BUFFER);
+        let uv = self.accessor(view, FLOAT, count, "VEC2");
+        let mut attributes = json!({ "POSITION": position, "TEXCOORD_0": uv });
+        // Vertex colours only carry information when the geometry shades its
+        // faces itself; the object colour lives in the material.
+        if data.vertices.iter().any(|v| v.color != [1.0; 3]) {
+            let view = self.view(&le_bytes(data.vertices.iter().flat_map(|v| v.color)), ARRAY_BUFFER);
+            attributes["COLOR_0"] = json!(self.accessor(view, FLOAT, count, "VEC3"));
+        }
+        let indices: Vec<u8> = data.indices.iter().flat_map(|i| i.to_le_bytes()).collect();
+        let view = self.view(&indices, ELEMENT_ARRAY_BUFFER);
+        let indices = self.accessor(view, UNSIGNED_INT, data.indices.len(), "SCALAR");
+
+        self.meshes.push(json!({
+            "primitives": [{ "attributes": attributes, "indices": indices, "material": material }],
+        }));
+        self.mesh_keys.push(key);
+        Some(self.meshes.len() - 1)
+    }
+}
+
+/// Build the glTF document (without `buffers`) and its binary buffer.
+fn build(world: &World) -> (Value, Vec<u8>) {
+    let mut ids: Vec<usize> = world.objects.keys().copied().collect();
+    ids.sort_unstable();
+    let node_of = |id: usize| ids.binary_search(&id).ok();
+
+    let mut builder = Builder::default();
+    let mut nodes = Vec::with_capacity(ids.len());
+    for &id in &ids {
+        let object = &world.objects[&id];
+        let t = &object.transform;
+        // Mirroring Z conjugates the rotation: the X and Y axes flip sign.
+        let [x, y, z, w] = euler_to_quaternion(t.rotation);
+        let mut node = json!({
+            "name": object.name,
+            "translation": to_right_handed(t.position),
+            "rotation": [-x, -y, z, w],
+            "scale": t.scale,
+            "extras": { "str_id": object.str_id },
+        });
+        let children: Vec<usize> = object.children.iter().filter_map(|&c| node_of(c)).collect();
+        if !children.is_empty() {
+            node["children"] = json!(children);
+        }
+        if let Some(geometry) = &object.geometry {
+            let material = builder.material(object.color, &object.texture_path);
+            if let Some(mesh) = builder.mesh(geometry, material) {
+                node["mesh"] = json!(mesh);
+            }
+        }
+        nodes.push(node);
+    }
+    let roots: Vec<usize> = ids.iter().enumerate()
+        .filter(|&(_, id)| world.objects[id].parent.is_none())
+        .map(|(index, _)| index)
+        .collect();
+
+    let mut document = json!({
+        "asset": { "version": "2.0", "generator": concat!("vertra ", env!("CARGO_PKG_VERSION")) },
+        "scene": 0,
+        "scenes": [{ "nodes": roots }],
+        "nodes": nodes,
+    });
+    let arrays = [
+        ("meshes", builder.meshes),
+        ("materials", builder.materials),
+        ("accessors", builder.accessors),
+        ("bufferViews", builder.views),
+    ];
+    for (name, array) in arrays {
+        if !array.is_empty() {
+            document[name] = Value::Array(array);
+        }
+    }
+    if !builder.images.is_empty() {
+        let textures: Vec<Value> = (0..builder.images.len()).map(|i| json!({ "source": i })).collect();
+        document["images"] = Value::Array(builder.images);
+        document["textures"] = Value::Array(textures);
+    }
+    (document, builder.buffer)
+}
+
+/// Little-endian bytes of a run of floats.
+fn le_bytes(values: impl Iterator<Item = f32>) -> Vec<u8> {
+    values.flat_map(f32::to_le_bytes).collect()
+}
+
+/// Pad `bytes` with `fill` to a multiple of four, as glTF chunks and buffer
+/// views require.
+fn pad(bytes: &mut Vec<u8>, fill: u8) {
+    while !bytes.len().is_multiple_of(4) {
+        bytes.push(fill);
+    }
+}
+
+/// Standard base64 (RFC 4648, with padding).
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,65 @@
+// Instanced scene shader: per-object transform and tint come from storage
+// buffers indexed by `instance_index` instead of the group 2 model uniform.
+// Emission and displacement are shared by the draw and read from its model
+// uniform slot in group 3.
+// See `instances::InstanceBuffer`.
+#include "vertra/camera.wgsl"
+#include "vertra/vertex.wgsl"
+#include "vertra/lighting.wgsl"
+#include "vertra/displacement.wgsl"
+#include "vertra/debug.wgsl"
+
+struct Instance {
+    model: mat4x4<f32>,
+    color: vec4<f32>,
+};
+@group(2) @binding(0) var<storage, read> instances: array<Instance>;
+// Draw order: instance index -> slot in `instances`.
+@group(2) @binding(1) var<storage, read> instance_order: array<u32>;
+
+// Layout of `ModelUniform`; only the material fields are read.
+struct DrawMaterial {
+    model: mat4x4<f32>,
+    color: vec4<f32>,
+    flags: vec4<f32>,
+    emissive: vec4<f32>,
+    wave: vec4<f32>,
+    wave_vector: vec4<f32>,
+};
+@group(3) @binding(0) var<uniform> draw_material: DrawMaterial;
+
+override LIT: bool = true;
+override TEXTURED: bool = true;
+
+@group(1) @binding(0) var t_diffuse: texture_2d<f32>;
+@group(1) @binding(1) var s_diffuse: sampler;
+
+@vertex
+fn vs_main(model: VertexInput, @builtin(instance_index) instance_index: u32) -> VertexOutput {
+    let instance = instances[instance_order[instance_index]];
+    var out: VertexOutput;
+    let world = (instance.model * vec4<f32>(model.position, 1.0)).xyz;
+    let world_position = displace(world, draw_material.wave, draw_material.wave_vector);
+    out.clip_position = camera.view_proj * vec4<f32>(world_position, 1.0);
+    out.world_position = world_position;
+    out.color = model.color * instance.color.rgb;
+    out.emissive = draw_material.emissive.rgb;
+    out.uv = model.uv;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    if DEBUG_VIEW != 0u {
+        return debug_color(in);
+    }
+    var tex = vec4<f32>(1.0);
+    if TEXTURED {
+        tex = textureSample(t_diffuse, s_diffuse, in.uv);
+    }
+    var light = vec3<f32>(1.0);
+    if LIT {
+        light = ambient_light(flat_normal(in.world_position));
+    }
+    return vec4<f32>((in.color * light + in.emissive) * tex.rgb, tex.a);
+}
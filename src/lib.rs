@@ -6,31 +6,126 @@
 //!
 //! | Module            | Purpose                                                            |
 //! |-------------------|--------------------------------------------------------------------|
-//! | [`camera`]        | Perspective camera, view/projection matrix construction            |
+//! | [`camera`]        | Perspective or orthographic camera, view/projection matrices       |
 //! | [`scene`]         | Root scene container; spawn, texture, and draw APIs                |
+//! | [`resources`]     | Type-keyed store for state shared between callbacks                |
 //! | [`world`]         | Scene-graph (object hierarchy, events, spatial queries)            |
 //! | [`objects`]       | [`objects::Object`] - the fundamental scene-graph node             |
+//! | [`presets`]       | Ready-made ground plane, axes, skybox and light marker             |
 //! | [`geometry`]      | Procedural geometry primitives (cube, sphere, capsule, …)          |
+//! | [`object2d`]      | Filled or outlined 2D shapes (circle, polygon, ring, …)            |
+//! | [`overlay`]       | Pixel-space HUD layer drawn over the finished frame                |
+//! | [`view_axes`]     | Corner orientation widget with click-to-snap axis views            |
+//! | [`minimap`]       | Top-down orthographic minimap rendered into an overlay quad        |
+//! | [`mirror`]        | Planar mirrors and portals rendered to textures, obliquely clipped |
+//! | [`sprite`]        | Texture atlases and frame-by-frame sprite animation                |
+//! | `audio`           | Sound playback with distance attenuation (`audio` feature)         |
+//! | `clipboard`       | System clipboard text, colours and objects (`clipboard` feature)   |
+//! | `ecs`             | Read-only hecs mirror of the objects (`ecs-mirror` feature)        |
 //! | [`transform`]     | Local-space TRS transform and matrix conversion                    |
+//! | [`color`]         | HSV / HSL / OKLab conversions and palette generation               |
+//! | [`rng`]           | Seeded random numbers, points and colours for generated scenes     |
 //! | [`mesh`]          | CPU mesh builder and GPU buffer baking                             |
+//! | [`uniforms`]      | Per-frame uniform buffer with dynamic offsets                      |
+//! | [`instances`]     | Storage-buffer instance data, updated incrementally                |
+//! | [`culling`]       | Compute-pass frustum culling writing indirect draw arguments       |
+//! | [`occlusion`]     | Bounding-box occlusion queries that skip draws of hidden objects   |
+//! | [`frustum`]       | Frustum planes, outline lines and culled-object tinting for debug  |
+//! | [`lines`]         | Thick screen-space lines with miter/bevel joins and a ground grid  |
+//! | [`voxel`]         | Chunked block grids meshed into visible faces, rebaked on edit     |
+//! | [`streaming`]     | Cells generated on worker threads and baked around the camera      |
+//! | [`readback`]      | Reading frame pixels and depth back to the CPU                     |
+//! | [`blit`]          | Texture copies, scaled and format-converting blits, mipmaps        |
+//! | [`bind_cache`]    | Bind group and layout cache keyed on bound resources               |
+//! | [`render_hook`]   | User render passes recorded at fixed stages of the frame           |
+//! | [`pipeline_cache`]| Render pipelines created lazily per render-state key               |
+//! | [`material`]      | Per-object culling, depth state, emission and vertex displacement  |
+//! | [`shader`]        | WGSL preprocessor: `#include`, `#define`, conditionals             |
 //! | [`math`]          | Column-major 4×4 matrix for rendering math                        |
 //! | [`timer`]         | Simple countdown timer for use in game logic                       |
 //! | [`window`]        | Builder-pattern windowing and event-loop host                      |
+//! | [`replay`]        | Input recording and deterministic replay                           |
+//! | [`touch`]         | Touch points and tap / pan / pinch gesture recognition             |
 //! | [`editor`]        | Built-in static scene editor (gizmos, orbit cam, inspector)        |
 //! | [`vtr`]           | Binary `.vtr` scene serialization format                           |
+//! | [`vtm`]           | Binary `.vtm` mesh cache format for fast startup                   |
+//! | [`gltf`]          | glTF 2.0 export (`.glb` / `.gltf`) with hierarchy and materials    |
+//! | [`water`]         | Animated Gerstner-wave water with fresnel tint and reflections     |
+//! | [`watch`]         | Polling file watcher backing scene hot reload                      |
+//! | [`scene_manager`] | Named scenes sharing one pipeline, with fade transitions           |
+//! | [`lighting`]      | Scene lighting (flat or hemispheric ambient)                       |
+//! | [`ao`]            | Per-vertex ambient occlusion baked into static geometry            |
+//! | [`post`]          | HDR, exposure, tonemapping, DoF, bloom, FXAA, and full-screen fade |
 //! | [`constants`]     | Engine-wide default constants                                      |
 //! | [`event`]         | Re-exports of winit event types used throughout the API            |
+//!
+//! ## Profiling
+//!
+//! With the `profiling` feature, update callbacks, fixed updates, mesh baking,
+//! and rendering are wrapped in [`profiling`](https://docs.rs/profiling)
+//! scopes and every rendered frame ends with `profiling::finish_frame!`.
+//! Select a backend in your own manifest, e.g.
+//! `profiling = { version = "1", features = ["profile-with-puffin"] }`, and
+//! open `puffin_viewer` to inspect frame time.  Without the feature the
+//! scopes compile to nothing.
+
+/// Open a named profiling scope lasting until the end of the enclosing block.
+/// No-op unless the `profiling` feature is enabled.
+macro_rules! profile_scope {
+    ($name:expr) => {
+        #[cfg(feature = "profiling")]
+        profiling::scope!($name);
+    };
+}
+
+/// Mark the end of a rendered frame for the profiler.
+macro_rules! profile_finish_frame {
+    () => {
+        #[cfg(feature = "profiling")]
+        profiling::finish_frame!();
+    };
+}
+
 pub mod event;
 pub(crate) mod frame_stats;
 pub mod window;
+pub mod replay;
+pub mod touch;
 pub mod pipeline;
+pub mod render_hook;
 pub mod mesh;
+pub mod uniforms;
+pub mod instances;
+pub mod culling;
+pub mod occlusion;
+pub mod frustum;
+pub mod lines;
+pub mod voxel;
+pub mod streaming;
+pub mod readback;
+pub mod blit;
+pub mod bind_cache;
+pub mod pipeline_cache;
+pub mod material;
+pub mod shader;
 pub mod timer;
 pub mod transform;
+pub mod color;
+pub mod rng;
 pub mod geometry;
+pub mod object2d;
+pub mod overlay;
+pub mod sprite;
+#[cfg(all(feature = "audio", not(target_arch = "wasm32")))]
+pub mod audio;
+#[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+pub mod clipboard;
+#[cfg(feature = "ecs-mirror")]
+pub mod ecs;
 pub mod math;
 pub mod camera;
 pub mod scene;
+pub mod resources;
 pub mod constants;
 pub mod world;
 pub mod objects;
@@ -40,3 +135,15 @@ pub mod script;
 #[cfg(test)]
 mod tests;
 pub mod vtr;
+pub mod vtm;
+pub mod gltf;
+pub mod water;
+pub mod view_axes;
+pub mod minimap;
+pub mod mirror;
+pub mod watch;
+pub mod scene_manager;
+pub mod post;
+pub mod lighting;
+pub mod ao;
+pub mod presets;
@@ -0,0 +1,62 @@
+//! Scene lighting uploaded alongside the camera each frame.
+//!
+//! Only the ambient term exists so far.  It multiplies every lit surface, so
+//! the default — flat white — leaves colours exactly as authored.  A
+//! [`AmbientLight::Hemisphere`] blends between a sky colour on upward-facing
+//! surfaces and a ground colour on downward-facing ones, which keeps shapes
+//! readable without any directional light:
+//!
+//! ```no_run
+//! # use vertra::lighting::AmbientLight;
+//! # fn demo(scene: &mut vertra::scene::Scene) {
+//! scene.lighting.ambient = AmbientLight::Hemisphere {
+//!     sky: [0.9, 0.95, 1.0],
+//!     ground: [0.35, 0.3, 0.25],
+//! };
+//! # }
+//! ```
+//!
+//! The skybox and editor gizmos are never lit.
+
+use crate::constants::lighting;
+
+/// Light reaching every surface regardless of position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AmbientLight {
+    /// The same linear RGB colour from every direction.
+    Flat([f32; 3]),
+    /// `sky` on surfaces facing +Y, `ground` on surfaces facing -Y, blended
+    /// by the surface normal in between.
+    Hemisphere { sky: [f32; 3], ground: [f32; 3] },
+}
+
+impl Default for AmbientLight {
+    fn default() -> Self {
+        Self::Flat(lighting::DEFAULT_AMBIENT)
+    }
+}
+
+impl AmbientLight {
+    /// The `(sky, ground)` colour pair; equal for [`Self::Flat`].
+    pub fn sky_and_ground(&self) -> ([f32; 3], [f32; 3]) {
+        match *self {
+            Self::Flat(color) => (color, color),
+            Self::Hemisphere { sky, ground } => (sky, ground),
+        }
+    }
+}
+
+/// Scene-level lighting state.  Stored on [`crate::scene::Scene::lighting`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Lighting {
+    /// Ambient term applied to every lit surface.
+    pub ambient: AmbientLight,
+}
+
+impl Lighting {
+    /// Set the ambient term.
+    pub fn with_ambient(mut self, ambient: AmbientLight) -> Self {
+        self.ambient = ambient;
+        self
+    }
+}
@@ -0,0 +1,451 @@
+//! Thick screen-space lines and a ground grid.
+//!
+//! GPU line primitives are one pixel wide.  [`Lines`] collects polylines
+//! instead, and the vertex shader expands each segment into a quad
+//! [`LineStyle::width`] pixels wide facing the camera, so lines keep their
+//! width at any distance.  Joints between segments are mitered or bevelled
+//! ([`LineJoin`]); miters longer than [`MITER_LIMIT`] line widths fall back
+//! to bevels.  Segments crossing the near plane are clipped against it.
+//!
+//! Lines pushed to [`crate::scene::Scene::lines`] are drawn every frame
+//! until cleared, for debug drawing.  [`crate::scene::Scene::grid`] adds a
+//! [`Grid`] on the ground around the camera, and the
+//! [`crate::frustum::CullingDebug`] outlines go through the same renderer.
+//!
+//! ```no_run
+//! # fn demo(scene: &mut vertra::scene::Scene) {
+//! use vertra::lines::{Grid, LineJoin, LineStyle};
+//! let path = [[0.0, 0.0, 0.0], [2.0, 1.0, 0.0], [4.0, 0.0, 0.0]];
+//! let style = LineStyle::default().with_width(4.0).with_join(LineJoin::Bevel);
+//! scene.lines.push_polyline(&path, [1.0, 0.3, 0.1, 1.0], style);
+//! scene.grid = Some(Grid::default());
+//! # }
+//! ```
+
+use std::sync::{Mutex, PoisonError};
+use bytemuck::{Pod, Zeroable};
+use wgpu::{Device, PipelineCompilationOptions};
+use crate::constants::lines::{
+    GRID_CELLS, GRID_COLOR, GRID_MAJOR_COLOR, GRID_MAJOR_EVERY, GRID_SPACING, INITIAL_CAPACITY, MITER_LIMIT, WIDTH,
+};
+use crate::pipeline::{GpuMemory, RenderStats};
+use crate::shader::ShaderPreprocessor;
+
+/// How consecutive segments of a polyline meet.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum LineJoin {
+    /// Edges extended until they meet in a point.
+    #[default]
+    Miter,
+    /// The outside corner cut off flat.
+    Bevel,
+}
+
+/// Width, joins and depth testing of pushed lines.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineStyle {
+    /// Width in pixels.
+    pub width: f32,
+    pub join: LineJoin,
+    /// Whether scene geometry hides the line.  Off draws it on top of
+    /// everything, like the editor gizmos.
+    pub depth_test: bool,
+}
+
+impl Default for LineStyle {
+    fn default() -> Self {
+        Self { width: WIDTH, join: LineJoin::Miter, depth_test: true }
+    }
+}
+
+impl LineStyle {
+    /// Set the width in pixels.
+    pub fn with_width(mut self, width: f32) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Set the joins.
+    pub fn with_join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    /// Set whether the lines are depth-tested.
+    pub fn with_depth_test(mut self, depth_test: bool) -> Self {
+        self.depth_test = depth_test;
+        self
+    }
+}
+
+/// One segment as the line shader reads it; see `lines.wgsl`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Pod, Zeroable)]
+pub(crate) struct LineSegment {
+    /// xyz previous point, w 1 when there is one.
+    pub(crate) prev: [f32; 4],
+    /// xyz start, w width in pixels.
+    pub(crate) start: [f32; 4],
+    /// xyz end, w 1 for bevel joins.
+    pub(crate) end: [f32; 4],
+    /// xyz next point, w 1 when there is one.
+    pub(crate) next: [f32; 4],
+    pub(crate) color: [f32; 4],
+}
+
+/// Polylines to draw, split by depth testing.  See the
+/// [module docs](self).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Lines {
+    pub(crate) tested: Vec<LineSegment>,
+    pub(crate) on_top: Vec<LineSegment>,
+}
+
+impl Lines {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a single segment from `a` to `b`.
+    pub fn push_segment(&mut self, a: [f32; 3], b: [f32; 3], color: [f32; 4], style: LineStyle) {
+        self.push_path(&[a, b], false, color, style);
+    }
+
+    /// Add segments joining consecutive `points`.  Fewer than two points
+    /// add nothing.
+    pub fn push_polyline(&mut self, points: &[[f32; 3]], color: [f32; 4], style: LineStyle) {
+        self.push_path(points, false, color, style);
+    }
+
+    /// Like [`Self::push_polyline`], with a last segment joining the final
+    /// point back to the first, and a joint there as well.
+    pub fn push_closed_polyline(&mut self, points: &[[f32; 3]], color: [f32; 4], style: LineStyle) {
+        self.push_path(points, points.len() > 2, color, style);
+    }
+
+    /// Move all of `other`'s lines into this list.
+    pub fn append(&mut self, other: &mut Lines) {
+        self.tested.append(&mut other.tested);
+        self.on_top.append(&mut other.on_top);
+    }
+
+    /// Remove every line.
+    pub fn clear(&mut self) {
+        self.tested.clear();
+        self.on_top.clear();
+    }
+
+    /// Number of segments.
+    pub fn len(&self) -> usize {
+        self.tested.len() + self.on_top.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn push_path(&mut self, points: &[[f32; 3]], closed: bool, color: [f32; 4], style: LineStyle) {
+        let count = points.len();
+        if count < 2 {
+            return;
+        }
+        let target = if style.depth_test { &mut self.tested } else { &mut self.on_top };
+        let segments = if closed { count } else { count - 1 };
+        let point = |i: usize, flag: bool| {
+            let [x, y, z] = points[i % count];
+            [x, y, z, if flag { 1.0 } else { 0.0 }]
+        };
+        let bevel = style.join == LineJoin::Bevel;
+        target.extend((0..segments).map(|i| {
+            let has_prev = closed || i > 0;
+            let has_next = closed || i + 2 < count;
+            let [ax, ay, az] = points[i];
+            let [bx, by, bz] = points[(i + 1) % count];
+            LineSegment {
+                prev: point(i + count - 1, has_prev),
+                start: [ax, ay, az, style.width],
+                end: [bx, by, bz, if bevel { 1.0 } else { 0.0 }],
+                next: point(i + 2, has_next),
+                color,
+            }
+        }));
+    }
+}
+
+/// A square of grid lines on a horizontal plane, with every
+/// [`Self::major_every`]th line in [`Self::major_color`].  Installed on
+/// [`crate::scene::Scene::grid`], it follows the camera in whole major
+/// cells so it looks endless.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Grid {
+    /// World units between lines.
+    pub spacing: f32,
+    /// Cells on each side of the centre.
+    pub cells: u32,
+    /// Height of the plane.
+    pub height: f32,
+    pub color: [f32; 4],
+    pub major_every: u32,
+    pub major_color: [f32; 4],
+    pub style: LineStyle,
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Self {
+            spacing: GRID_SPACING,
+            cells: GRID_CELLS,
+            height: 0.0,
+            color: GRID_COLOR,
+            major_every: GRID_MAJOR_EVERY,
+            major_color: GRID_MAJOR_COLOR,
+            style: LineStyle::default().with_width(1.5),
+        }
+    }
+}
+
+impl Grid {
+    /// Set the spacing between lines and the cells on each side.
+    pub fn with_spacing(mut self, spacing: f32, cells: u32) -> Self {
+        self.spacing = spacing;
+        self.cells = cells;
+        self
+    }
+
+    /// Set the height of the plane.
+    pub fn with_height(mut self, height: f32) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// Set the minor and major line colours.
+    pub fn with_colors(mut self, color: [f32; 4], major_color: [f32; 4]) -> Self {
+        self.color = color;
+        self.major_color = major_color;
+        self
+    }
+
+    /// Set how often a major line is drawn; `0` draws none.
+    pub fn with_major_every(mut self, major_every: u32) -> Self {
+        self.major_every = major_every;
+        self
+    }
+
+    /// Set the line style.
+    pub fn with_style(mut self, style: LineStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// The grid lines around `center`, snapped to whole major cells.
+    /// Nothing without cells or with a spacing of zero or less.
+    pub fn lines(&self, center: [f32; 3]) -> Lines {
+        let mut lines = Lines::new();
+        if self.cells == 0 || self.spacing <= 0.0 {
+            return lines;
+        }
+        let period = self.spacing * self.major_every.max(1) as f32;
+        let origin = [center[0], center[2]].map(|v| (v / period).round() * period);
+        let reach = self.cells as f32 * self.spacing;
+        let cells = self.cells as i64;
+        for i in -cells..=cells {
+            let offset = i as f32 * self.spacing;
+            // Major lines are counted from the world origin.
+            let major = |base: f32| {
+                self.major_every > 0 && ((base / self.spacing).round() as i64 + i).rem_euclid(i64::from(self.major_every)) == 0
+            };
+            let x = origin[0] + offset;
+            let color = if major(origin[0]) { self.major_color } else { self.color };
+            lines.push_segment([x, self.height, origin[1] - reach], [x, self.height, origin[1] + reach], color, self.style);
+            let z = origin[1] + offset;
+            let color = if major(origin[1]) { self.major_color } else { self.color };
+            lines.push_segment([origin[0] - reach, self.height, z], [origin[0] + reach, self.height, z], color, self.style);
+        }
+        lines
+    }
+}
+
+/// GPU state of the line renderer: the shader, one pipeline per target
+/// format and depth mode, the viewport uniform and the segment buffer.
+pub(crate) struct LinePass {
+    shader: wgpu::ShaderModule,
+    pipeline_layout: wgpu::PipelineLayout,
+    pipelines: Mutex<Vec<((wgpu::TextureFormat, bool), wgpu::RenderPipeline)>>,
+    buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    instances: wgpu::Buffer,
+    /// Segments `instances` holds.
+    capacity: u64,
+    /// Depth-tested segments at the start of `instances`, followed by
+    /// those drawn on top.
+    tested: u32,
+    on_top: u32,
+}
+
+impl LinePass {
+    /// Compile the line shader for passes whose group 0 holds the scene
+    /// camera, laid out by `camera_layout`.
+    pub(crate) fn new(device: &Device, camera_layout: &wgpu::BindGroupLayout) -> Self {
+        let source = ShaderPreprocessor::new()
+            .process("lines.wgsl", include_str!("lines.wgsl"))
+            .expect("built-in shader preprocesses");
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("lines.wgsl"),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        });
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+            label: Some("line_bind_group_layout"),
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Line Pipeline Layout"),
+            bind_group_layouts: &[Some(camera_layout), Some(&layout)],
+            immediate_size: 0,
+        });
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Line Uniform Buffer"),
+            size: size_of::<[f32; 4]>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &layout,
+            entries: &[wgpu::BindGroupEntry { binding: 0, resource: buffer.as_entire_binding() }],
+            label: Some("line_bind_group"),
+        });
+        Self {
+            shader,
+            pipeline_layout,
+            pipelines: Mutex::new(Vec::new()),
+            buffer,
+            bind_group,
+            instances: create_instance_buffer(device, INITIAL_CAPACITY),
+            capacity: INITIAL_CAPACITY,
+            tested: 0,
+            on_top: 0,
+        }
+    }
+
+    /// Replace the segments with those of `parts`.  Returns `(bytes
+    /// written, buffers allocated)`.
+    pub(crate) fn upload(&mut self, device: &Device, queue: &wgpu::Queue, parts: &[&Lines]) -> (u64, u32) {
+        let segments: Vec<LineSegment> = parts.iter().flat_map(|lines| &lines.tested)
+            .chain(parts.iter().flat_map(|lines| &lines.on_top))
+            .copied()
+            .collect();
+        self.tested = parts.iter().map(|lines| lines.tested.len() as u32).sum();
+        self.on_top = segments.len() as u32 - self.tested;
+        let mut allocated = 0;
+        if segments.len() as u64 > self.capacity {
+            self.capacity = (segments.len() as u64).next_power_of_two();
+            self.instances = create_instance_buffer(device, self.capacity);
+            allocated += 1;
+        }
+        if !segments.is_empty() {
+            queue.write_buffer(&self.instances, 0, bytemuck::cast_slice(&segments));
+        }
+        (size_of_val(segments.as_slice()) as u64, allocated)
+    }
+
+    /// Whether there is nothing to draw.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.tested + self.on_top == 0
+    }
+
+    /// Upload this frame's uniform for a viewport of `size` pixels.
+    /// Returns the bytes written.
+    pub(crate) fn prepare(&self, queue: &wgpu::Queue, size: [f32; 2]) -> u64 {
+        let params = [size[0], size[1], MITER_LIMIT, 0.0];
+        queue.write_buffer(&self.buffer, 0, bytemuck::bytes_of(&params));
+        size_of_val(&params) as u64
+    }
+
+    /// Draw the depth-tested segments, or those on top, into `rp`, whose
+    /// group 0 holds the scene camera.
+    pub(crate) fn draw(
+        &self,
+        device: &Device,
+        rp: &mut wgpu::RenderPass<'_>,
+        format: wgpu::TextureFormat,
+        on_top: bool,
+        stats: &mut RenderStats,
+    ) {
+        let range = if on_top { self.tested..self.tested + self.on_top } else { 0..self.tested };
+        if range.is_empty() {
+            return;
+        }
+        rp.set_pipeline(&self.pipeline(device, format, on_top));
+        rp.set_bind_group(1, &self.bind_group, &[]);
+        rp.set_vertex_buffer(0, self.instances.slice(..));
+        rp.draw(0..9, range.clone());
+        stats.record_draw(9, range.len() as u32);
+    }
+
+    /// The segment buffer and uniform.
+    pub(crate) fn gpu_memory(&self) -> GpuMemory {
+        GpuMemory { vertex_bytes: self.instances.size(), uniform_bytes: self.buffer.size(), ..GpuMemory::default() }
+    }
+
+    /// The pipeline drawing into `format`, created on first use.
+    fn pipeline(&self, device: &Device, format: wgpu::TextureFormat, on_top: bool) -> wgpu::RenderPipeline {
+        let mut pipelines = self.pipelines.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some((_, pipeline)) = pipelines.iter().find(|(key, _)| *key == (format, on_top)) {
+            return pipeline.clone();
+        }
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Line Pipeline"),
+            layout: Some(&self.pipeline_layout),
+            cache: None, multiview_mask: None,
+            vertex: wgpu::VertexState {
+                module: &self.shader, entry_point: Some("vs_main"),
+                compilation_options: PipelineCompilationOptions::default(),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: size_of::<LineSegment>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Instance,
+                    attributes: &wgpu::vertex_attr_array![
+                        0 => Float32x4, 1 => Float32x4, 2 => Float32x4, 3 => Float32x4, 4 => Float32x4,
+                    ],
+                }],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &self.shader, entry_point: Some("fs_main"),
+                compilation_options: PipelineCompilationOptions::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            // Quads face either way depending on the segment's direction.
+            primitive: wgpu::PrimitiveState { cull_mode: None, ..Default::default() },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_write_enabled: Some(false),
+                depth_compare: Some(if on_top { wgpu::CompareFunction::Always } else { wgpu::CompareFunction::LessEqual }),
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+        });
+        pipelines.push(((format, on_top), pipeline.clone()));
+        pipeline
+    }
+}
+
+fn create_instance_buffer(device: &Device, capacity: u64) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Line Instance Buffer"),
+        size: capacity * size_of::<LineSegment>() as u64,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
@@ -0,0 +1,141 @@
+// Thick screen-space lines (see `lines.rs`).  Each instance is one segment
+// with its neighbours: the vertex shader expands it into a quad `width`
+// pixels wide facing the camera (vertices 0-5), plus a bevel triangle
+// filling the outside of the turn at its start (vertices 6-8).
+#include "vertra/camera.wgsl"
+
+struct LineUniform {
+    // xy viewport size in pixels, z miter limit.
+    params: vec4<f32>,
+};
+@group(1) @binding(0)
+var<uniform> line: LineUniform;
+
+struct SegmentInput {
+    // xyz previous point, w 1 when there is one.
+    @location(0) prev: vec4<f32>,
+    // xyz start, w width in pixels.
+    @location(1) start: vec4<f32>,
+    // xyz end, w 1 for bevel joins.
+    @location(2) end: vec4<f32>,
+    // xyz next point, w 1 when there is one.
+    @location(3) next: vec4<f32>,
+    @location(4) color: vec4<f32>,
+};
+
+struct LineOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+};
+
+// `p` moved towards `q` onto the near plane when it lies behind it.
+fn clip_near(p: vec4<f32>, q: vec4<f32>) -> vec4<f32> {
+    if p.z >= 0.0 {
+        return p;
+    }
+    return mix(p, q, p.z / (p.z - q.z));
+}
+
+// Clip-space position in pixels from the viewport centre.
+fn to_pixels(clip: vec4<f32>) -> vec2<f32> {
+    return clip.xy / clip.w * 0.5 * line.params.xy;
+}
+
+fn left(d: vec2<f32>) -> vec2<f32> {
+    return vec2<f32>(-d.y, d.x);
+}
+
+// Unit direction from `p` to `q`, or `fallback` when they coincide.
+fn direction(p: vec2<f32>, q: vec2<f32>, fallback: vec2<f32>) -> vec2<f32> {
+    let d = q - p;
+    let len = length(d);
+    if len < 1e-4 {
+        return fallback;
+    }
+    return d / len;
+}
+
+// Miter normal where a segment running along `d0` meets one along `d1`
+// (xy), and the factor that keeps the line's width across the joint (z).
+// Both segments compute the same miter, so their quads share the corner.
+fn miter(d0: vec2<f32>, d1: vec2<f32>) -> vec3<f32> {
+    let tangent = d0 + d1;
+    if length(tangent) < 1e-4 {
+        // A full turn back: no miter fits.
+        return vec3<f32>(left(d1), 1e9);
+    }
+    let m = left(normalize(tangent));
+    return vec3<f32>(m, 1.0 / max(dot(m, left(d1)), 1e-4));
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32, in: SegmentInput) -> LineOutput {
+    var out: LineOutput;
+    out.color = in.color;
+    // Placed behind the near plane, so clipping discards the triangle.
+    out.clip_position = vec4<f32>(0.0, 0.0, -1.0, 1.0);
+    let a_clip = camera.view_proj * vec4<f32>(in.start.xyz, 1.0);
+    let b_clip = camera.view_proj * vec4<f32>(in.end.xyz, 1.0);
+    if a_clip.z < 0.0 && b_clip.z < 0.0 {
+        return out;
+    }
+    let a = clip_near(a_clip, b_clip);
+    let b = clip_near(b_clip, a_clip);
+    let pa = to_pixels(a);
+    let pb = to_pixels(b);
+    let d = direction(pa, pb, vec2<f32>(1.0, 0.0));
+    let n = left(d);
+    let half = in.start.w * 0.5;
+    let bevel = in.end.w > 0.5;
+    let limit = line.params.z;
+
+    // Neighbours only join when they and the shared end are in front of
+    // the near plane.
+    let prev_clip = camera.view_proj * vec4<f32>(in.prev.xyz, 1.0);
+    let next_clip = camera.view_proj * vec4<f32>(in.next.xyz, 1.0);
+    let has_prev = in.prev.w > 0.5 && prev_clip.z >= 0.0 && a_clip.z >= 0.0;
+    let has_next = in.next.w > 0.5 && next_clip.z >= 0.0 && b_clip.z >= 0.0;
+    let dp = direction(to_pixels(prev_clip), pa, d);
+    let dn = direction(pb, to_pixels(next_clip), d);
+    let ma = miter(dp, d);
+    let mb = miter(d, dn);
+    // Miters longer than the limit fall back to a bevel.
+    let miter_a = has_prev && !bevel && ma.z <= limit;
+    let miter_b = has_next && !bevel && mb.z <= limit;
+
+    // Straight joints and miters leave the bevel triangle out.
+    let outside = -sign(dp.x * d.y - dp.y * d.x);
+    if index >= 6u && (!has_prev || miter_a || outside == 0.0) {
+        return out;
+    }
+
+    var clip = a;
+    var pixel = pa;
+    if index < 6u {
+        let at_end = index == 1u || index == 2u || index == 4u;
+        let side = select(-1.0, 1.0, index == 2u || index == 4u || index == 5u);
+        var offset = n;
+        if at_end && miter_b {
+            offset = mb.xy * mb.z;
+        } else if !at_end && miter_a {
+            offset = ma.xy * ma.z;
+        }
+        clip = select(a, b, at_end);
+        pixel = select(pa, pb, at_end) + offset * half * side;
+    } else {
+        // Between the previous segment's end and this one's start, on the
+        // outside of the turn.
+        if index == 7u {
+            pixel = pa + left(dp) * half * outside;
+        } else if index == 8u {
+            pixel = pa + n * half * outside;
+        }
+    }
+    out.clip_position = vec4<f32>(pixel / (0.5 * line.params.xy) * clip.w, clip.z, clip.w);
+    return out;
+}
+
+@fragment
+fn fs_main(in: LineOutput) -> @location(0) vec4<f32> {
+    return in.color;
+}
@@ -0,0 +1,320 @@
+//! Per-object render state: face culling, depth writes, depth bias,
+//! emission and vertex displacement.
+//!
+//! Every [`crate::objects::Object`] carries a [`Material`].  Objects sharing
+//! a texture and material are batched together, and the material picks the
+//! [`PipelineKey`] their batch is drawn with.  The default culls back faces
+//! and writes depth like any opaque surface.
+//!
+//! ```
+//! use vertra::geometry::Geometry;
+//! use vertra::material::{CullMode, Material};
+//! use vertra::objects::Object;
+//! use vertra::pipeline_cache::DepthBias;
+//!
+//! // A decal: visible from both sides, drawn over the wall it lies on and
+//! // hiding nothing behind it.
+//! let decal = Material::default()
+//!     .with_cull_mode(CullMode::None)
+//!     .with_depth_write(false)
+//!     .with_depth_bias(DepthBias { constant: -4, slope_scale: -1.0 });
+//! let object = Object::builder()
+//!     .geometry(Geometry::Sprite { width: 1.0, height: 1.0, uv: [0.0, 0.0, 1.0, 1.0] })
+//!     .material(decal)
+//!     .build();
+//! assert_eq!(object.material.cull_mode, CullMode::None);
+//! ```
+//!
+//! An [`Emission`] adds light of the object's own on top of its shaded
+//! colour.  Rendered with [`crate::post::PostSettings::hdr`], strengths
+//! past `1.0` go over the bloom threshold, so signs and lasers glow:
+//!
+//! ```
+//! use vertra::material::Material;
+//! use vertra::post::{Bloom, PostSettings};
+//!
+//! let neon = Material::default().with_emission([1.0, 0.2, 0.6], 4.0);
+//! let post = PostSettings::default().with_hdr(true).with_bloom(Some(Bloom::default()));
+//! assert_eq!(neon.emission.radiance(), [4.0, 0.8, 2.4]);
+//! # let _ = post;
+//! ```
+//!
+//! A [`Displacement`] sways the object's vertices in the vertex shader with
+//! a travelling sine wave, driven by [`crate::scene::Scene::time`]: flags,
+//! water and foliage animate without touching their meshes on the CPU.
+//!
+//! ```
+//! use vertra::material::Material;
+//!
+//! // Grass blades swaying 5 cm along X, in waves 4 units long rolling
+//! // across the field along +Z once a second.
+//! let tau = std::f32::consts::TAU;
+//! let grass = Material::default().with_displacement([0.05, 0.0, 0.0], [0.0, 0.0, tau / 4.0], tau);
+//! assert!(!grass.displacement.is_none());
+//! ```
+//!
+//! [`crate::geometry::Geometry::Plane`] and
+//! [`crate::geometry::Geometry::Sprite`] already emit a back face, so they
+//! show both sides with any cull mode.
+
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+
+use serde::{Deserialize, Serialize};
+use crate::pipeline_cache::{DepthBias, DepthMode, PipelineKey};
+
+/// Faces discarded before shading.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum CullMode {
+    /// Hide faces pointing away from the camera.
+    #[default]
+    Back,
+    /// Hide faces pointing towards the camera, e.g. to see the inside of a
+    /// closed shape.
+    Front,
+    /// Draw both sides of every face.
+    None,
+}
+
+impl CullMode {
+    /// The face wgpu culls, `None` for double-sided.
+    pub fn face(self) -> Option<wgpu::Face> {
+        match self {
+            Self::Back => Some(wgpu::Face::Back),
+            Self::Front => Some(wgpu::Face::Front),
+            Self::None => None,
+        }
+    }
+}
+
+/// Light an object gives off whatever the scene lighting, added to its
+/// shaded colour and tinted by its texture.  Without HDR the sum is clamped
+/// to white.
+///
+/// Compared and hashed by the bits of its fields, so it can be part of a
+/// [`Material`].
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Emission {
+    /// Linear RGB colour.
+    pub color: [f32; 3],
+    /// Multiplier on `color`; above `1.0` it overshoots the display range
+    /// and feeds [`crate::post::PostSettings::bloom`].
+    pub strength: f32,
+}
+
+impl Emission {
+    /// No emitted light.
+    pub const NONE: Self = Self { color: [0.0; 3], strength: 0.0 };
+
+    /// `color` given off at `strength`.
+    pub fn new(color: [f32; 3], strength: f32) -> Self {
+        Self { color, strength }
+    }
+
+    /// `color` scaled by `strength`, as the shaders add it.
+    pub fn radiance(self) -> [f32; 3] {
+        self.color.map(|c| c * self.strength)
+    }
+
+    /// Whether no light is given off.
+    pub fn is_none(self) -> bool {
+        self.radiance() == [0.0; 3]
+    }
+
+    fn bits(self) -> [u32; 4] {
+        let [r, g, b] = self.color;
+        [r, g, b, self.strength].map(f32::to_bits)
+    }
+}
+
+impl PartialEq for Emission {
+    fn eq(&self, other: &Self) -> bool {
+        self.bits() == other.bits()
+    }
+}
+
+impl Eq for Emission {}
+
+impl Hash for Emission {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.bits().hash(state);
+    }
+}
+
+impl PartialOrd for Emission {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Emission {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.bits().cmp(&other.bits())
+    }
+}
+
+/// A travelling sine wave moving vertices in the vertex shader: each vertex
+/// at world position `p` is offset by
+/// `amplitude * sin(dot(wave_vector, p) - speed * time)`, with `time` from
+/// [`crate::scene::Scene::time`].  Objects sharing a displacement sway as
+/// one field.  Culling and occlusion bounds grow by [`Self::reach`].
+///
+/// Compared and hashed by the bits of its fields, so it can be part of a
+/// [`Material`].
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Displacement {
+    /// Offset at the wave's crest, in world units.
+    pub amplitude: [f32; 3],
+    /// Direction the wave travels in, scaled by its angular wavenumber:
+    /// one wave per `2π / |wave_vector|` world units.  Zero moves every
+    /// vertex together.
+    pub wave_vector: [f32; 3],
+    /// Angular speed in radians per second.
+    pub speed: f32,
+}
+
+impl Displacement {
+    /// No movement.
+    pub const NONE: Self = Self { amplitude: [0.0; 3], wave_vector: [0.0; 3], speed: 0.0 };
+
+    /// A wave of `amplitude` travelling along `wave_vector` at `speed`.
+    pub fn new(amplitude: [f32; 3], wave_vector: [f32; 3], speed: f32) -> Self {
+        Self { amplitude, wave_vector, speed }
+    }
+
+    /// Whether vertices stay in place.
+    pub fn is_none(self) -> bool {
+        self.amplitude == [0.0; 3]
+    }
+
+    /// Furthest any vertex moves: the length of the amplitude.
+    pub fn reach(self) -> f32 {
+        let [x, y, z] = self.amplitude;
+        (x * x + y * y + z * z).sqrt()
+    }
+
+    /// The wave as the shaders take it at `time` seconds: amplitude and
+    /// phase, then the wave vector.  The phase is reduced to one period in
+    /// double precision so the wave stays smooth however long the scene
+    /// runs.
+    pub(crate) fn uniform(self, time: f64) -> [[f32; 4]; 2] {
+        let ([ax, ay, az], [kx, ky, kz]) = (self.amplitude, self.wave_vector);
+        let phase = (self.speed as f64 * time).rem_euclid(std::f64::consts::TAU) as f32;
+        [[ax, ay, az, phase], [kx, ky, kz, 0.0]]
+    }
+
+    fn bits(self) -> [u32; 7] {
+        let ([ax, ay, az], [kx, ky, kz]) = (self.amplitude, self.wave_vector);
+        [ax, ay, az, kx, ky, kz, self.speed].map(f32::to_bits)
+    }
+}
+
+impl PartialEq for Displacement {
+    fn eq(&self, other: &Self) -> bool {
+        self.bits() == other.bits()
+    }
+}
+
+impl Eq for Displacement {}
+
+impl Hash for Displacement {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.bits().hash(state);
+    }
+}
+
+impl PartialOrd for Displacement {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Displacement {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.bits().cmp(&other.bits())
+    }
+}
+
+/// How an object's triangles are culled, depth-tested, lit and moved.  See
+/// the [module docs](self).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Material {
+    /// Faces left out.
+    pub cull_mode: CullMode,
+    /// Whether the object hides what is drawn behind it later.  Off for
+    /// decals and see-through surfaces.
+    pub depth_write: bool,
+    /// Offset applied to the object's depth before testing.
+    pub depth_bias: DepthBias,
+    /// Light given off by the object.
+    #[serde(default)]
+    pub emission: Emission,
+    /// Vertex animation applied on the GPU.
+    #[serde(default)]
+    pub displacement: Displacement,
+}
+
+impl Material {
+    /// Back-face culled, depth-writing, unbiased, not emissive and still.
+    pub const OPAQUE: Self = Self {
+        cull_mode: CullMode::Back,
+        depth_write: true,
+        depth_bias: DepthBias::NONE,
+        emission: Emission::NONE,
+        displacement: Displacement::NONE,
+    };
+
+    /// Set the culled faces.
+    pub fn with_cull_mode(mut self, cull_mode: CullMode) -> Self {
+        self.cull_mode = cull_mode;
+        self
+    }
+
+    /// Set whether depth is written.
+    pub fn with_depth_write(mut self, depth_write: bool) -> Self {
+        self.depth_write = depth_write;
+        self
+    }
+
+    /// Set the depth bias.
+    pub fn with_depth_bias(mut self, depth_bias: DepthBias) -> Self {
+        self.depth_bias = depth_bias;
+        self
+    }
+
+    /// Set the emitted light to `color` at `strength`.
+    pub fn with_emission(mut self, color: [f32; 3], strength: f32) -> Self {
+        self.emission = Emission::new(color, strength);
+        self
+    }
+
+    /// Set the vertex displacement to a wave of `amplitude` travelling along
+    /// `wave_vector` at `speed` (see [`Displacement`]).
+    pub fn with_displacement(mut self, amplitude: [f32; 3], wave_vector: [f32; 3], speed: f32) -> Self {
+        self.displacement = Displacement::new(amplitude, wave_vector, speed);
+        self
+    }
+
+    /// Whether batches drawn with this material need a model uniform of
+    /// their own rather than the shared identity one.
+    pub(crate) fn has_model_uniform(&self) -> bool {
+        !self.emission.is_none() || !self.displacement.is_none()
+    }
+
+    /// `key` adjusted to draw with this material.  Depth writes are only
+    /// turned off for [`DepthMode::Test`], so keys that ignore depth, such
+    /// as [`crate::pipeline_cache::DebugView::Overdraw`], keep doing so.
+    pub fn apply(self, key: PipelineKey) -> PipelineKey {
+        let depth = match key.depth {
+            DepthMode::Test if !self.depth_write => DepthMode::TestReadOnly,
+            depth => depth,
+        };
+        key.with_cull_mode(self.cull_mode.face()).with_depth(depth).with_depth_bias(self.depth_bias)
+    }
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Self::OPAQUE
+    }
+}
@@ -9,6 +9,9 @@ use std::ops::Mul;
 /// * [`Matrix4::identity`] - multiplicative identity.
 /// * [`Matrix4::look_at`] - camera view matrix.
 /// * [`Matrix4::perspective`] - WGPU-compatible perspective projection.
+/// * [`Matrix4::orthographic`] - WGPU-compatible orthographic projection.
+/// * [`Matrix4::frustum`] - off-axis perspective projection.
+/// * [`Matrix4::inverse`] - inverse of an existing matrix.
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct Matrix4 {
@@ -33,8 +36,8 @@ impl Matrix4 {
     /// result as `[f32; 4]`.
     pub fn mul_vec4(&self, v: [f32; 4]) -> [f32; 4] {
         let mut res = [0.0; 4];
-        for row in 0..4 {
-            res[row] = self.data[0][row] * v[0] +
+        for (row, out) in res.iter_mut().enumerate() {
+            *out = self.data[0][row] * v[0] +
                 self.data[1][row] * v[1] +
                 self.data[2][row] * v[2] +
                 self.data[3][row] * v[3];
@@ -76,6 +79,58 @@ impl Matrix4 {
         Self { data }
     }
 
+    /// Build a **WGPU-compatible** orthographic projection matrix mapping the
+    /// box `left..right` × `bottom..top` × `near..far` to NDC, with depth
+    /// range `[0.0, 1.0]`.
+    ///
+    /// Passing `top < bottom` flips Y, e.g. for pixel coordinates with the
+    /// origin at the top-left.
+    pub fn orthographic(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Self {
+        let mut data = Self::identity().data;
+        data[0][0] = 2.0 / (right - left);
+        data[1][1] = 2.0 / (top - bottom);
+        data[2][2] = 1.0 / (far - near);
+        data[3][0] = -(right + left) / (right - left);
+        data[3][1] = -(top + bottom) / (top - bottom);
+        data[3][2] = -near / (far - near);
+        Self { data }
+    }
+
+    /// Build a **WGPU-compatible** off-axis perspective projection whose
+    /// near plane spans `left..right` × `bottom..top` in view space, with
+    /// depth range `[0.0, 1.0]`.  Used for views through a rectangle that is
+    /// not centred in front of the eye, such as a mirror.
+    pub fn frustum(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Self {
+        let mut data = [[0.0; 4]; 4];
+        data[0][0] = 2.0 * near / (right - left);
+        data[1][1] = 2.0 * near / (top - bottom);
+        data[2][0] = -(right + left) / (right - left);
+        data[2][1] = -(top + bottom) / (top - bottom);
+        data[2][2] = far / (far - near);
+        data[2][3] = 1.0;
+        data[3][2] = far * near / (near - far);
+        Self { data }
+    }
+
+    /// This projection with its near plane replaced by the view-space
+    /// `plane` `[a, b, c, d]` (points with `ax + by + cz + d < 0` are
+    /// clipped), using Lengyel's oblique near-plane clipping.  The eye must
+    /// be on the clipped side.  The far plane tilts to keep depth in
+    /// `[0.0, 1.0]`, which costs some depth precision.
+    ///
+    /// Returns the projection unchanged if it cannot be inverted.
+    pub fn oblique_near_plane(&self, plane: [f32; 4]) -> Self {
+        let Some(inverse) = self.inverse() else { return *self };
+        // The far corner of the frustum on the plane's side.
+        let q = inverse.mul_vec4([plane[0].signum(), plane[1].signum(), 1.0, 1.0]);
+        let dot = plane[0] * q[0] + plane[1] * q[1] + plane[2] * q[2] + plane[3] * q[3];
+        let mut res = *self;
+        for (col, value) in plane.iter().enumerate() {
+            res.data[col][2] = value / dot;
+        }
+        res
+    }
+
     /// Build a look-at **view** matrix.
     ///
     /// Transforms world space into camera (view) space such that:
@@ -137,6 +192,41 @@ impl Matrix4 {
         // Perspective Divide: [x/w, y/w, z/w]
         [v[0] / v[3], v[1] / v[3], v[2] / v[3]]
     }
+
+    /// Return the inverse of this matrix, or `None` when it is singular
+    /// (determinant near zero).
+    ///
+    /// Undoes a view-projection matrix to map NDC points back to world
+    /// space; see [`crate::camera::Camera::screen_to_world`].
+    pub fn inverse(&self) -> Option<Self> {
+        // Cofactor expansion on the flattened matrix.  Inverting the
+        // transpose gives the transposed inverse, so the result is correct
+        // for column-major storage as well.
+        let m: [f32; 16] = bytemuck::cast(self.data);
+        let mut inv = [0.0f32; 16];
+        inv[0] = m[5]*m[10]*m[15] - m[5]*m[11]*m[14] - m[9]*m[6]*m[15] + m[9]*m[7]*m[14] + m[13]*m[6]*m[11] - m[13]*m[7]*m[10];
+        inv[4] = -m[4]*m[10]*m[15] + m[4]*m[11]*m[14] + m[8]*m[6]*m[15] - m[8]*m[7]*m[14] - m[12]*m[6]*m[11] + m[12]*m[7]*m[10];
+        inv[8] = m[4]*m[9]*m[15] - m[4]*m[11]*m[13] - m[8]*m[5]*m[15] + m[8]*m[7]*m[13] + m[12]*m[5]*m[11] - m[12]*m[7]*m[9];
+        inv[12] = -m[4]*m[9]*m[14] + m[4]*m[10]*m[13] + m[8]*m[5]*m[14] - m[8]*m[6]*m[13] - m[12]*m[5]*m[10] + m[12]*m[6]*m[9];
+        inv[1] = -m[1]*m[10]*m[15] + m[1]*m[11]*m[14] + m[9]*m[2]*m[15] - m[9]*m[3]*m[14] - m[13]*m[2]*m[11] + m[13]*m[3]*m[10];
+        inv[5] = m[0]*m[10]*m[15] - m[0]*m[11]*m[14] - m[8]*m[2]*m[15] + m[8]*m[3]*m[14] + m[12]*m[2]*m[11] - m[12]*m[3]*m[10];
+        inv[9] = -m[0]*m[9]*m[15] + m[0]*m[11]*m[13] + m[8]*m[1]*m[15] - m[8]*m[3]*m[13] - m[12]*m[1]*m[11] + m[12]*m[3]*m[9];
+        inv[13] = m[0]*m[9]*m[14] - m[0]*m[10]*m[13] - m[8]*m[1]*m[14] + m[8]*m[2]*m[13] + m[12]*m[1]*m[10] - m[12]*m[2]*m[9];
+        inv[2] = m[1]*m[6]*m[15] - m[1]*m[7]*m[14] - m[5]*m[2]*m[15] + m[5]*m[3]*m[14] + m[13]*m[2]*m[7] - m[13]*m[3]*m[6];
+        inv[6] = -m[0]*m[6]*m[15] + m[0]*m[7]*m[14] + m[4]*m[2]*m[15] - m[4]*m[3]*m[14] - m[12]*m[2]*m[7] + m[12]*m[3]*m[6];
+        inv[10] = m[0]*m[5]*m[15] - m[0]*m[7]*m[13] - m[4]*m[1]*m[15] + m[4]*m[3]*m[13] + m[12]*m[1]*m[7] - m[12]*m[3]*m[5];
+        inv[14] = -m[0]*m[5]*m[14] + m[0]*m[6]*m[13] + m[4]*m[1]*m[14] - m[4]*m[2]*m[13] - m[12]*m[1]*m[6] + m[12]*m[2]*m[5];
+        inv[3] = -m[1]*m[6]*m[11] + m[1]*m[7]*m[10] + m[5]*m[2]*m[11] - m[5]*m[3]*m[10] - m[9]*m[2]*m[7] + m[9]*m[3]*m[6];
+        inv[7] = m[0]*m[6]*m[11] - m[0]*m[7]*m[10] - m[4]*m[2]*m[11] + m[4]*m[3]*m[10] + m[8]*m[2]*m[7] - m[8]*m[3]*m[6];
+        inv[11] = -m[0]*m[5]*m[11] + m[0]*m[7]*m[9] + m[4]*m[1]*m[11] - m[4]*m[3]*m[9] - m[8]*m[1]*m[7] + m[8]*m[3]*m[5];
+        inv[15] = m[0]*m[5]*m[10] - m[0]*m[6]*m[9] - m[4]*m[1]*m[10] + m[4]*m[2]*m[9] + m[8]*m[1]*m[6] - m[8]*m[2]*m[5];
+
+        let det = m[0]*inv[0] + m[1]*inv[4] + m[2]*inv[8] + m[3]*inv[12];
+        if det.abs() < f32::EPSILON * f32::EPSILON {
+            return None;
+        }
+        Some(Self { data: bytemuck::cast(inv.map(|v| v / det)) })
+    }
 }
 
 
@@ -144,9 +234,9 @@ impl Mul for Matrix4 {
     type Output = Self;
     fn mul(self, other: Self) -> Self {
         let mut res = [[0.0; 4]; 4];
-        for col in 0..4 {
-            for row in 0..4 {
-                res[col][row] = (0..4).map(|i| self.data[i][row] * other.data[col][i]).sum();
+        for (col, res_col) in res.iter_mut().enumerate() {
+            for (row, cell) in res_col.iter_mut().enumerate() {
+                *cell = (0..4).map(|i| self.data[i][row] * other.data[col][i]).sum();
             }
         }
         Self { data: res }
@@ -1,5 +1,11 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Write};
+use crate::constants::layers;
+use crate::geometry::GeometryId;
+use crate::material::Material;
 use crate::pipeline::Pipeline;
 use crate::transform::Transform;
+use crate::world::World;
 
 /// A single GPU-ready vertex.
 ///
@@ -16,6 +22,17 @@ pub struct Vertex {
     pub uv: [f32; 2],
 }
 
+/// Mirror an engine position into the right-handed, Y-up space of glTF, OBJ
+/// and PLY.
+///
+/// Negating Z also reverses the handedness of every triangle: engine faces,
+/// clockwise under the right-hand rule, come out counter-clockwise seen from
+/// outside, so exporters keep the index order as is.
+pub(crate) fn to_right_handed([x, y, z]: [f32; 3]) -> [f32; 3] {
+    // `0.0 - z` rather than `-z` so a zero stays `0` instead of printing `-0`.
+    [x, y, 0.0 - z]
+}
+
 /// A fully uploaded mesh living in GPU (VRAM) memory.
 ///
 /// Created by [`MeshData::bake`] or
@@ -28,6 +45,197 @@ pub struct BakedMesh {
     pub index_buffer: wgpu::Buffer,
     /// Number of indices; used as the `index_count` argument in draw calls.
     pub index_count: u32,
+    /// Sphere `[x, y, z, radius]` enclosing every vertex, in the mesh's own
+    /// space; used by [`crate::culling`].
+    pub bounding_sphere: [f32; 4],
+    /// How the indices are assembled into primitives.
+    pub topology: Topology,
+}
+
+/// How a mesh's indices are assembled into primitives.
+///
+/// Only list topologies are offered, so meshes can be merged with
+/// [`MeshData::append`].  Lines and points are drawn unlit, one pixel wide.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Topology {
+    /// Every three indices form a triangle.
+    #[default]
+    TriangleList,
+    /// Every two indices form a line segment.
+    LineList,
+    /// Every index is a point.
+    PointList,
+}
+
+impl Topology {
+    /// The matching [`crate::pipeline_cache::PipelineKey::topology`].
+    pub fn primitive(self) -> wgpu::PrimitiveTopology {
+        match self {
+            Self::TriangleList => wgpu::PrimitiveTopology::TriangleList,
+            Self::LineList => wgpu::PrimitiveTopology::LineList,
+            Self::PointList => wgpu::PrimitiveTopology::PointList,
+        }
+    }
+
+    /// Indices per primitive.
+    pub fn vertices_per_primitive(self) -> usize {
+        match self {
+            Self::TriangleList => 3,
+            Self::LineList => 2,
+            Self::PointList => 1,
+        }
+    }
+}
+
+/// Sphere `[x, y, z, radius]` around the axis-aligned bounds of `vertices`,
+/// or all zeros when empty.
+pub(crate) fn bounding_sphere(vertices: &[Vertex]) -> [f32; 4] {
+    let Some(first) = vertices.first() else { return [0.0; 4] };
+    let (lo, hi) = vertices.iter().fold((first.position, first.position), |(lo, hi), v| {
+        ([0, 1, 2].map(|k| lo[k].min(v.position[k])), [0, 1, 2].map(|k| hi[k].max(v.position[k])))
+    });
+    let half = [0, 1, 2].map(|k| (hi[k] - lo[k]) * 0.5);
+    [lo[0] + half[0], lo[1] + half[1], lo[2] + half[2], half.iter().map(|h| h * h).sum::<f32>().sqrt()]
+}
+
+/// How often an object's geometry is expected to change.
+///
+/// Passed to [`crate::scene::Scene::spawn_with_usage`].  The hint controls the
+/// GPU buffer usage flags and whether the object is merged into the cached
+/// static batches or re-flattened every frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MeshUsage {
+    /// The object never moves after spawn.  Its geometry is merged into
+    /// per-texture static batches that are uploaded once into immutable
+    /// buffers and reused until [`crate::scene::Scene::invalidate_static_batches`]
+    /// is called (or a static object is added, removed, or re-hinted).
+    Static,
+    /// The object may move or change every frame.  Its geometry is rebuilt
+    /// each frame into buffers that allow in-place updates.
+    #[default]
+    Dynamic,
+}
+
+impl MeshUsage {
+    /// Buffer usage flags for a vertex or index buffer with this hint, in
+    /// addition to `VERTEX` / `INDEX`.  Dynamic buffers may be rewritten with
+    /// `Queue::write_buffer`, so they also carry `COPY_DST`.
+    pub fn extra_buffer_usages(self) -> wgpu::BufferUsages {
+        match self {
+            MeshUsage::Static  => wgpu::BufferUsages::empty(),
+            MeshUsage::Dynamic => wgpu::BufferUsages::COPY_DST,
+        }
+    }
+}
+
+/// Texture and material shared by every object merged into one batch.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BatchKey {
+    /// Texture image path, or `None` for untextured objects.
+    pub texture_path: Option<String>,
+    /// Culling and depth state the batch is drawn with.
+    pub material: Material,
+}
+
+/// Cache of merged geometry for objects spawned with [`MeshUsage::Static`].
+///
+/// Tracks the set of static object IDs and the baked per-texture and
+/// per-material batches
+/// built from them.  The batches are rebuilt lazily by
+/// [`crate::scene::Scene::draw_world`] whenever the cache is dirty.
+///
+/// Generic over the stored mesh for the same reason as [`MeshRegistry`].
+pub struct StaticBatches<M = BakedMesh> {
+    ids:     HashSet<usize>,
+    batches: Vec<(BatchKey, M)>,
+    dirty:   bool,
+    /// Camera layer mask the batches were built for.
+    layers_mask: u32,
+}
+
+impl<M> Default for StaticBatches<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<M> StaticBatches<M> {
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        Self { ids: HashSet::new(), batches: Vec::new(), dirty: false, layers_mask: layers::ALL }
+    }
+
+    /// Record object `id` as static or dynamic.
+    ///
+    /// Marks the cache dirty when the static set actually changes.
+    pub fn set_usage(&mut self, id: usize, usage: MeshUsage) {
+        let changed = match usage {
+            MeshUsage::Static  => self.ids.insert(id),
+            MeshUsage::Dynamic => self.ids.remove(&id),
+        };
+        self.dirty |= changed;
+    }
+
+    /// Usage hint recorded for object `id` ([`MeshUsage::Dynamic`] if unknown).
+    pub fn usage(&self, id: usize) -> MeshUsage {
+        if self.ids.contains(&id) { MeshUsage::Static } else { MeshUsage::Dynamic }
+    }
+
+    /// IDs of all objects currently marked static.
+    pub fn ids(&self) -> &HashSet<usize> {
+        &self.ids
+    }
+
+    /// Force the batches to be rebuilt on the next frame.
+    pub fn invalidate(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Record the camera layer mask the batches must be built for, marking
+    /// the cache dirty when it changes.
+    pub fn set_layers_mask(&mut self, mask: u32) {
+        self.dirty |= self.layers_mask != mask;
+        self.layers_mask = mask;
+    }
+
+    /// The camera layer mask last passed to [`Self::set_layers_mask`].
+    pub fn layers_mask(&self) -> u32 {
+        self.layers_mask
+    }
+
+    /// `true` when the cached batches no longer match the static set.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Forget static IDs whose objects no longer exist in `world`, marking
+    /// the cache dirty if any were removed.
+    pub fn prune(&mut self, world: &World) {
+        let before = self.ids.len();
+        self.ids.retain(|id| world.objects.contains_key(id));
+        self.dirty |= self.ids.len() != before;
+    }
+
+    /// Replace the cached batches and clear the dirty flag.
+    pub fn set_batches(&mut self, batches: Vec<(BatchKey, M)>) {
+        self.batches = batches;
+        self.dirty = false;
+    }
+
+    /// The cached `(key, mesh)` batches.
+    pub fn batches(&self) -> &[(BatchKey, M)] {
+        &self.batches
+    }
+
+    /// Number of objects marked static.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// `true` when no object is marked static.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
 }
 
 /// CPU-side mesh builder that accumulates vertices and indices before uploading
@@ -36,29 +244,156 @@ pub struct BakedMesh {
 /// This is the primary way to assemble geometry during the render phase.  The
 /// scene renderer creates one `MeshData` per texture group each frame,
 /// populates it by walking the scene graph, then bakes the result once.
+///
+/// Meshes hold triangles unless created with [`Self::lines`] or
+/// [`Self::points`]; see [`Self::push_polyline`] and [`Self::push_points`].
 pub struct MeshData {
     /// Accumulated vertex list.
     pub vertices: Vec<Vertex>,
-    /// Accumulated index list (triangle list topology).
+    /// Accumulated index list, grouped by [`Self::topology`].
     pub indices: Vec<u32>,
+    /// How [`Self::indices`] form primitives.
+    pub topology: Topology,
 }
 
-/// Registry that keeps track of the current world mesh inside a [`crate::scene::Scene`].
-pub struct MeshRegistry {
+/// A registered mesh together with the number of objects referencing it.
+struct MeshEntry<M> {
+    mesh:      M,
+    ref_count: usize,
+}
+
+/// Reference-counted store of GPU meshes inside a [`crate::scene::Scene`].
+///
+/// Meshes are inserted once and addressed by a [`GeometryId`]; objects refer
+/// to them through [`crate::objects::Object::mesh`].  Each [`World`] counts
+/// the objects referencing every mesh as they are spawned, deleted or
+/// re-pointed with [`World::set_mesh`]; every entry here additionally
+/// carries a manual reference count ([`Self::retain`] / [`Self::release`])
+/// for holders outside any world.  Meshes referenced by neither can be freed
+/// with [`MeshRegistry::collect_garbage`] instead of accumulating forever in
+/// long-running applications that spawn and despawn objects constantly.
+///
+/// IDs are never reused: once a mesh is removed its [`GeometryId`] stays
+/// invalid and [`MeshRegistry::get`] returns `None` for it.
+///
+/// The stored mesh type defaults to [`BakedMesh`]; the parameter exists so
+/// the bookkeeping can be exercised without a GPU device.
+pub struct MeshRegistry<M = BakedMesh> {
     /// The most recently baked world geometry, or `None` before the first frame.
-    pub world_mesh: Option<BakedMesh>,
+    pub world_mesh: Option<M>,
+    meshes:  HashMap<GeometryId, MeshEntry<M>>,
+    next_id: usize,
+}
+
+impl<M> Default for MeshRegistry<M> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-impl MeshRegistry {
+impl<M> MeshRegistry<M> {
     /// Create an empty registry.
     pub fn new() -> Self {
-        Self { world_mesh: None }
+        Self { world_mesh: None, meshes: HashMap::new(), next_id: 0 }
     }
 
     /// Replace the stored world mesh with a freshly baked one.
-    pub fn update_world_mesh(&mut self, baked: BakedMesh) {
+    pub fn update_world_mesh(&mut self, baked: M) {
         self.world_mesh = Some(baked);
     }
+
+    /// Store `mesh` and return the [`GeometryId`] that refers to it.
+    ///
+    /// The new entry starts with a reference count of zero; it survives the
+    /// next [`Self::collect_garbage`] pass only if something retains it or an
+    /// object uses it.
+    pub fn insert(&mut self, mesh: M) -> GeometryId {
+        let id = GeometryId(self.next_id);
+        self.next_id += 1;
+        self.meshes.insert(id, MeshEntry { mesh, ref_count: 0 });
+        id
+    }
+
+    /// Borrow the mesh registered under `id`, if it is still alive.
+    pub fn get(&self, id: GeometryId) -> Option<&M> {
+        self.meshes.get(&id).map(|e| &e.mesh)
+    }
+
+    /// Returns `true` when `id` refers to a live mesh.
+    pub fn contains(&self, id: GeometryId) -> bool {
+        self.meshes.contains_key(&id)
+    }
+
+    /// Manual reference count of `id`, not including references from world
+    /// objects, or `None` if it is not registered.
+    pub fn ref_count(&self, id: GeometryId) -> Option<usize> {
+        self.meshes.get(&id).map(|e| e.ref_count)
+    }
+
+    /// Increment the reference count of `id`.
+    ///
+    /// Returns `false` if `id` is not registered.
+    pub fn retain(&mut self, id: GeometryId) -> bool {
+        match self.meshes.get_mut(&id) {
+            Some(entry) => { entry.ref_count += 1; true }
+            None => false,
+        }
+    }
+
+    /// Decrement the reference count of `id` (saturating at zero).
+    ///
+    /// The mesh is **not** freed immediately; unreferenced entries are dropped
+    /// by the next [`Self::collect_garbage`] pass.  Returns `false` if `id`
+    /// is not registered.
+    pub fn release(&mut self, id: GeometryId) -> bool {
+        match self.meshes.get_mut(&id) {
+            Some(entry) => { entry.ref_count = entry.ref_count.saturating_sub(1); true }
+            None => false,
+        }
+    }
+
+    /// Remove `id` immediately regardless of its reference count and return
+    /// the mesh so its GPU buffers can be dropped.
+    ///
+    /// Objects that still reference `id` simply stop rendering.
+    pub fn remove(&mut self, id: GeometryId) -> Option<M> {
+        self.meshes.remove(&id).map(|e| e.mesh)
+    }
+
+    /// Drop every mesh that has a zero reference count and is not used by
+    /// any object in `worlds`.
+    ///
+    /// Pass every world drawn with this registry; a mesh used only by a
+    /// world left out is freed.  Returns the number of meshes that were
+    /// freed.
+    pub fn collect_garbage<'a>(&mut self, worlds: impl IntoIterator<Item = &'a World>) -> usize {
+        let worlds: Vec<&World> = worlds.into_iter().collect();
+        let before = self.meshes.len();
+        self.meshes.retain(|&id, e| e.ref_count > 0 || worlds.iter().any(|world| world.mesh_ref_count(id) > 0));
+        before - self.meshes.len()
+    }
+
+    /// Every live mesh, plus the world mesh if one is stored, in no
+    /// particular order.
+    pub fn meshes(&self) -> impl Iterator<Item = &M> {
+        self.world_mesh.iter().chain(self.meshes.values().map(|e| &e.mesh))
+    }
+
+    /// Number of registered meshes (excluding the world mesh).
+    pub fn len(&self) -> usize {
+        self.meshes.len()
+    }
+
+    /// `true` when no meshes are registered.
+    pub fn is_empty(&self) -> bool {
+        self.meshes.is_empty()
+    }
+}
+
+impl Default for MeshData {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl MeshData {
@@ -67,15 +402,43 @@ impl MeshData {
         Self {
             vertices: Vec::new(),
             indices: Vec::new(),
+            topology: Topology::TriangleList,
         }
     }
 
+    /// Create an empty builder for line segments, e.g. graphs and
+    /// trajectories.
+    ///
+    /// ```
+    /// use vertra::mesh::{MeshData, Topology};
+    ///
+    /// let mut path = MeshData::lines();
+    /// path.push_polyline(&[[0.0, 0.0, 0.0], [1.0, 1.0, 0.0], [2.0, 0.5, 0.0]], [1.0, 0.8, 0.2, 1.0]);
+    /// assert_eq!(path.topology, Topology::LineList);
+    /// assert_eq!(path.indices, [0, 1, 1, 2]);
+    /// ```
+    pub fn lines() -> Self {
+        Self { topology: Topology::LineList, ..Self::new() }
+    }
+
+    /// Create an empty builder for single points, e.g. scan data.
+    pub fn points() -> Self {
+        Self { topology: Topology::PointList, ..Self::new() }
+    }
+
     /// Upload the accumulated CPU data to GPU memory and return a [`BakedMesh`].
     ///
     /// The `MeshData` itself is left unchanged; you can bake multiple times if
     /// needed (though typically you bake once and discard).
     pub fn bake(&self, pipeline: &Pipeline) -> BakedMesh {
-        pipeline.create_baked_mesh(&self.vertices, &self.indices)
+        self.bake_with_usage(pipeline, MeshUsage::Static)
+    }
+
+    /// Like [`Self::bake`], but with buffer usage flags chosen by `usage`.
+    pub fn bake_with_usage(&self, pipeline: &Pipeline, usage: MeshUsage) -> BakedMesh {
+        let mut baked = pipeline.create_baked_mesh_with_usage(&self.vertices, &self.indices, usage);
+        baked.topology = self.topology;
+        baked
     }
 
     /// Recursively add an object and all its descendants to this mesh builder.
@@ -114,6 +477,43 @@ impl MeshData {
         self.push_quad(transformed, color);
     }
 
+    /// Append a quad split into a `segments[0]` × `segments[1]` grid of
+    /// cells, counted along the `points[0] → points[1]` and
+    /// `points[0] → points[3]` edges (`0` counts as `1`).  Grid vertices are
+    /// shared between cells, UVs span the whole quad as in
+    /// [`Self::push_quad`], and every cell keeps the quad's winding.
+    pub fn add_transformed_grid(
+        &mut self,
+        points: [[f32; 3]; 4],
+        segments: [usize; 2],
+        transform: &Transform,
+        color: [f32; 4],
+    ) {
+        let [a, b, c, d] = transform.apply(points);
+        let [n, m] = segments.map(|s| s.max(1));
+        let start_index = self.vertices.len() as u32;
+        let color = [color[0], color[1], color[2]];
+        for j in 0..=m {
+            let t = j as f32 / m as f32;
+            for i in 0..=n {
+                let s = i as f32 / n as f32;
+                // Bilinear interpolation of the four corners.
+                let position = [0, 1, 2].map(|k| {
+                    a[k] + s * (b[k] - a[k]) + t * (d[k] - a[k]) + s * t * (a[k] - b[k] + c[k] - d[k])
+                });
+                self.vertices.push(Vertex { position, color, uv: [s, 1.0 - t] });
+            }
+        }
+        let row = n as u32 + 1;
+        for j in 0..m as u32 {
+            for i in 0..n as u32 {
+                let i0 = start_index + j * row + i;
+                let (i1, i2, i3) = (i0 + 1, i0 + row + 1, i0 + row);
+                self.indices.extend_from_slice(&[i0, i1, i2, i0, i2, i3]);
+            }
+        }
+    }
+
     /// Append a planar quad (four points → two triangles) with the given color.
     ///
     /// UV coordinates are assigned in bottom-left → bottom-right → top-right →
@@ -147,9 +547,213 @@ impl MeshData {
         self.indices.extend_from_slice(&[start_index, start_index + 1, start_index + 2]);
     }
 
+    /// Append one line segment per consecutive pair of `points`.  Meant for
+    /// builders made with [`Self::lines`].
+    pub fn push_polyline(&mut self, points: &[[f32; 3]], color: [f32; 4]) {
+        debug_assert_eq!(self.topology, Topology::LineList);
+        let start_index = self.vertices.len() as u32;
+        self.push_vertices(points, color);
+        for i in 1..points.len() as u32 {
+            self.indices.extend_from_slice(&[start_index + i - 1, start_index + i]);
+        }
+    }
+
+    /// Like [`Self::push_polyline`], with a last segment joining the final
+    /// point back to the first.
+    pub fn push_closed_polyline(&mut self, points: &[[f32; 3]], color: [f32; 4]) {
+        let start_index = self.vertices.len() as u32;
+        self.push_polyline(points, color);
+        if points.len() > 2 {
+            self.indices.extend_from_slice(&[start_index + points.len() as u32 - 1, start_index]);
+        }
+    }
+
+    /// Append one point per entry of `points`.  Meant for builders made with
+    /// [`Self::points`].
+    pub fn push_points(&mut self, points: &[[f32; 3]], color: [f32; 4]) {
+        debug_assert_eq!(self.topology, Topology::PointList);
+        let start_index = self.vertices.len() as u32;
+        self.push_vertices(points, color);
+        self.indices.extend(start_index..start_index + points.len() as u32);
+    }
+
+    fn push_vertices(&mut self, points: &[[f32; 3]], color: [f32; 4]) {
+        let color = [color[0], color[1], color[2]];
+        self.vertices.extend(points.iter().map(|&position| Vertex { position, color, uv: [0.0, 0.0] }));
+    }
+
+    /// Move all of `other`'s geometry to the end of this builder, offsetting
+    /// its indices past the existing vertices.  Both must share a
+    /// [`Topology`].
+    pub fn append(&mut self, mut other: MeshData) {
+        debug_assert!(
+            self.topology == other.topology || other.indices.is_empty(),
+            "appending {:?} to {:?}", other.topology, self.topology,
+        );
+        let offset = self.vertices.len() as u32;
+        self.vertices.append(&mut other.vertices);
+        self.indices.extend(other.indices.iter().map(|i| i + offset));
+    }
+
     /// Clear all accumulated vertices and indices, resetting the builder.
     pub fn clear(&mut self) {
         self.vertices.clear();
         self.indices.clear();
     }
+
+    /// Merge the procedural geometry of every object in `world` into one
+    /// mesh in world space, e.g. for [`Self::export_obj`].  Objects that
+    /// only reference a registry [`crate::objects::Object::mesh`] are
+    /// skipped: their vertices live on the GPU.
+    pub fn from_world(world: &World) -> Self {
+        let mut mesh = Self::new();
+        let mut roots: Vec<usize> = world.objects.iter()
+            .filter(|(_, object)| object.parent.is_none())
+            .map(|(&id, _)| id)
+            .collect();
+        roots.sort_unstable();
+        for id in roots {
+            mesh.add_object(world, id, &Transform::default());
+        }
+        mesh
+    }
+
+    /// Write the mesh as Wavefront OBJ: positions with vertex colours
+    /// (`v x y z r g b`, read by Blender and MeshLab), texture coordinates
+    /// with V flipped to OBJ's bottom-left origin, and one `f` line per
+    /// triangle (`l` per segment and `p` per point for line and point
+    /// meshes).  Positions are mirrored into OBJ's right-handed space by
+    /// negating Z, so faces wind counter-clockwise from outside.
+    ///
+    /// ```no_run
+    /// # use vertra::mesh::MeshData;
+    /// # fn demo(scene: &vertra::scene::Scene) -> std::io::Result<()> {
+    /// let file = std::io::BufWriter::new(std::fs::File::create("world.obj")?);
+    /// MeshData::from_world(&scene.world).export_obj(file)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn export_obj(&self, mut writer: impl Write) -> io::Result<()> {
+        let primitive = match self.topology {
+            Topology::TriangleList => "triangles",
+            Topology::LineList => "lines",
+            Topology::PointList => "points",
+        };
+        let count = self.indices.len() / self.topology.vertices_per_primitive();
+        writeln!(writer, "# vertra mesh: {} vertices, {count} {primitive}", self.vertices.len())?;
+        for v in &self.vertices {
+            let [x, y, z] = to_right_handed(v.position);
+            let [r, g, b] = v.color;
+            writeln!(writer, "v {x} {y} {z} {r} {g} {b}")?;
+        }
+        for v in &self.vertices {
+            writeln!(writer, "vt {} {}", v.uv[0], 1.0 - v.uv[1])?;
+        }
+        for primitive in self.indices.chunks_exact(self.topology.vertices_per_primitive()) {
+            match *primitive {
+                [a, b, c] => writeln!(writer, "f {0}/{0} {1}/{1} {2}/{2}", a + 1, b + 1, c + 1)?,
+                [a, b] => writeln!(writer, "l {} {}", a + 1, b + 1)?,
+                [a] => writeln!(writer, "p {}", a + 1)?,
+                _ => unreachable!("primitives have one to three vertices"),
+            }
+        }
+        writer.flush()
+    }
+
+    /// Write the mesh as ASCII PLY with per-vertex 8-bit colours and
+    /// texture coordinates (`s`, `t`), one face per triangle or one edge
+    /// per line segment.  Point meshes are written as bare vertices.
+    /// Positions are mirrored into right-handed space like
+    /// [`export_obj`](Self::export_obj).
+    pub fn export_ply(&self, mut writer: impl Write) -> io::Result<()> {
+        writeln!(writer, "ply")?;
+        writeln!(writer, "format ascii 1.0")?;
+        writeln!(writer, "comment vertra mesh")?;
+        writeln!(writer, "element vertex {}", self.vertices.len())?;
+        for property in ["float x", "float y", "float z", "uchar red", "uchar green", "uchar blue", "float s", "float t"] {
+            writeln!(writer, "property {property}")?;
+        }
+        let per_primitive = self.topology.vertices_per_primitive();
+        let count = self.indices.len() / per_primitive;
+        match self.topology {
+            Topology::TriangleList => {
+                writeln!(writer, "element face {count}")?;
+                writeln!(writer, "property list uchar uint vertex_indices")?;
+            }
+            Topology::LineList => {
+                writeln!(writer, "element edge {count}")?;
+                writeln!(writer, "property int vertex1")?;
+                writeln!(writer, "property int vertex2")?;
+            }
+            Topology::PointList => {}
+        }
+        writeln!(writer, "end_header")?;
+        for v in &self.vertices {
+            let [x, y, z] = to_right_handed(v.position);
+            let [r, g, b] = v.color.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+            writeln!(writer, "{x} {y} {z} {r} {g} {b} {} {}", v.uv[0], 1.0 - v.uv[1])?;
+        }
+        for primitive in self.indices.chunks_exact(per_primitive) {
+            match *primitive {
+                [a, b, c] => writeln!(writer, "3 {a} {b} {c}")?,
+                [a, b] => writeln!(writer, "{a} {b}")?,
+                _ => break,
+            }
+        }
+        writer.flush()
+    }
+
+    /// Axis-aligned bounds `(min, max)` of all vertices, or `None` when
+    /// empty.
+    pub fn bounds(&self) -> Option<([f32; 3], [f32; 3])> {
+        let first = self.vertices.first()?.position;
+        Some(self.vertices.iter().fold((first, first), |(lo, hi), v| {
+            (
+                [0, 1, 2].map(|k| lo[k].min(v.position[k])),
+                [0, 1, 2].map(|k| hi[k].max(v.position[k])),
+            )
+        }))
+    }
+
+    /// Recolour every vertex from its position: `f(position, color)` returns
+    /// the new RGB colour.  Positions are in the mesh's own space (world
+    /// space for batched geometry, local space for registry meshes).
+    ///
+    /// ```rust,ignore
+    /// // Checkerboard by 1-unit cells.
+    /// mesh.color_by_position(|[x, _, z], color| {
+    ///     if (x.floor() + z.floor()) as i32 % 2 == 0 { color } else { [0.2; 3] }
+    /// });
+    /// ```
+    pub fn color_by_position(&mut self, mut f: impl FnMut([f32; 3], [f32; 3]) -> [f32; 3]) {
+        for vertex in &mut self.vertices {
+            vertex.color = f(vertex.position, vertex.color);
+        }
+    }
+
+    /// Multiply vertex colours by a vertical gradient: `bottom` at the lowest
+    /// vertex, `top` at the highest.  Existing colours act as a tint.
+    pub fn height_gradient(&mut self, bottom: [f32; 3], top: [f32; 3]) {
+        let Some((lo, hi)) = self.bounds() else { return };
+        let span = (hi[1] - lo[1]).max(f32::EPSILON);
+        self.color_by_position(|p, color| mul(color, lerp(bottom, top, (p[1] - lo[1]) / span)));
+    }
+
+    /// Multiply vertex colours by a radial gradient: `inner` at `center`,
+    /// `outer` at `radius` and beyond.  Existing colours act as a tint.
+    pub fn radial_gradient(&mut self, center: [f32; 3], radius: f32, inner: [f32; 3], outer: [f32; 3]) {
+        let radius = radius.max(f32::EPSILON);
+        self.color_by_position(|p, color| {
+            let distance = (0..3).map(|k| (p[k] - center[k]).powi(2)).sum::<f32>().sqrt();
+            mul(color, lerp(inner, outer, (distance / radius).min(1.0)))
+        });
+    }
+}
+
+fn lerp(a: [f32; 3], b: [f32; 3], t: f32) -> [f32; 3] {
+    [0, 1, 2].map(|k| a[k] + (b[k] - a[k]) * t)
+}
+
+fn mul(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [0, 1, 2].map(|k| a[k] * b[k])
 }
\ No newline at end of file
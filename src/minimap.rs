@@ -0,0 +1,233 @@
+//! Top-down minimap drawn in a screen corner.
+//!
+//! A [`Minimap`] renders the scene every frame from an orthographic camera
+//! high above it, looking straight down, into a texture registered in
+//! [`crate::scene::Scene::textures`] under [`TEXTURE_KEY`].  The texture is
+//! shown on a quad in the [`crate::overlay`], with a marker for the scene
+//! camera's position and heading on top.
+//!
+//! The map follows the scene camera unless [`Minimap::center`] pins it.  By
+//! default `+Z` points up the map; with [`Minimap::rotate`] the camera's
+//! heading does.  [`Minimap::span`] sets how much of the world fits across
+//! the map, and [`Minimap::zoom`] changes it by a factor.
+//!
+//! The map pass skips post-processing, render hooks, water and the screen
+//! overlay, and it renders at the map's on-screen pixel size.  Objects
+//! textured with [`TEXTURE_KEY`] must not be visible to the map camera, as
+//! the pass would then read the texture it is drawing into.
+//!
+//! ```no_run
+//! # fn demo(scene: &mut vertra::scene::Scene) {
+//! use vertra::minimap::Minimap;
+//! use vertra::overlay::{Anchor, Layout};
+//! scene.overlay.minimap = Some(Minimap::default()
+//!     .with_layout(Layout::new(Anchor::BottomLeft, [240.0, 160.0]))
+//!     .with_span(120.0));
+//! scene.overlay.minimap.as_mut().unwrap().zoom(2.0);
+//! # }
+//! ```
+
+use crate::camera::{Camera, Viewport};
+use crate::constants::layers;
+use crate::constants::minimap::{
+    ALTITUDE, DEPTH, MARGIN, MARKER_COLOR, MARKER_SIZE, MAX_SPAN, MIN_SPAN, SIZE, SPAN,
+};
+use crate::mesh::MeshData;
+use crate::overlay::{Anchor, Layout, Margins, OverlayCamera};
+
+/// Key of the map texture in [`crate::scene::Scene::textures`].
+pub const TEXTURE_KEY: &str = "vertra://minimap";
+
+/// Format of the map texture.
+pub const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+/// Minimap settings.  Install on [`crate::overlay::Overlay::minimap`]; see
+/// the [module docs](self).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Minimap {
+    /// Placement of the map; only the anchor, margins and size are used.
+    pub layout: Layout,
+    /// World units shown across the map's height; the width follows the
+    /// layout's aspect ratio.
+    pub span: f32,
+    /// World `[x, z]` at the middle of the map, or `None` to follow the
+    /// scene camera.
+    pub center: Option<[f32; 2]>,
+    /// World height of the map camera.  Geometry above it is not drawn.
+    pub altitude: f32,
+    /// How far below [`Self::altitude`] the map camera sees.
+    pub depth: f32,
+    /// Turn the map so the scene camera's heading points up, instead of
+    /// `+Z`.
+    pub rotate: bool,
+    /// Render layers drawn on the map.  Defaults to every layer except
+    /// [`layers::UI`] and [`layers::GIZMO`].
+    pub layers_mask: u32,
+    /// Colour of the scene-camera marker, or `None` to hide it.
+    pub marker_color: Option<[f32; 4]>,
+}
+
+impl Default for Minimap {
+    fn default() -> Self {
+        Self {
+            layout: Layout::new(Anchor::BottomRight, [SIZE; 2]).with_margins(Margins::all(MARGIN)),
+            span: SPAN,
+            center: None,
+            altitude: ALTITUDE,
+            depth: DEPTH,
+            rotate: false,
+            layers_mask: layers::ALL & !(layers::UI | layers::GIZMO),
+            marker_color: Some(MARKER_COLOR),
+        }
+    }
+}
+
+impl Minimap {
+    /// Place the map by `layout`.
+    pub fn with_layout(mut self, layout: Layout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Show `span` world units across the map's height.
+    pub fn with_span(mut self, span: f32) -> Self {
+        self.span = span;
+        self
+    }
+
+    /// Pin the middle of the map to world `[x, z]`, or follow the camera
+    /// with `None`.
+    pub fn with_center(mut self, center: Option<[f32; 2]>) -> Self {
+        self.center = center;
+        self
+    }
+
+    /// Set the map camera's height and how far down it sees.
+    pub fn with_altitude(mut self, altitude: f32, depth: f32) -> Self {
+        self.altitude = altitude;
+        self.depth = depth;
+        self
+    }
+
+    /// Turn the map with the scene camera's heading.
+    pub fn with_rotate(mut self, rotate: bool) -> Self {
+        self.rotate = rotate;
+        self
+    }
+
+    /// Set the marker colour, or hide the marker with `None`.
+    pub fn with_marker(mut self, color: Option<[f32; 4]>) -> Self {
+        self.marker_color = color;
+        self
+    }
+
+    /// Zoom in by `factor` (below `1.0` zooms out), keeping the span within
+    /// [`MIN_SPAN`] and [`MAX_SPAN`].
+    pub fn zoom(&mut self, factor: f32) {
+        if factor > 0.0 {
+            self.span = (self.span / factor).clamp(MIN_SPAN, MAX_SPAN);
+        }
+    }
+
+    /// The map's top-left corner and size in an overlay of `viewport`
+    /// logical pixels.
+    pub fn rect(&self, viewport: [f32; 2]) -> ([f32; 2], [f32; 2]) {
+        self.layout.rect(viewport)
+    }
+
+    /// The orthographic camera the map is rendered from, for a map
+    /// `aspect` wide (`width / height`) and the scene camera `scene_camera`.
+    pub fn camera(&self, scene_camera: &Camera, aspect: f32) -> Camera {
+        let [x, z] = self.center.unwrap_or([scene_camera.eye[0], scene_camera.eye[2]]);
+        let up = match heading(scene_camera) {
+            Some([hx, hz]) if self.rotate => [hx, 0.0, hz],
+            _ => [0.0, 0.0, 1.0],
+        };
+        Camera {
+            eye: [x, self.altitude, z],
+            target: [x, self.altitude - 1.0, z],
+            up,
+            aspect,
+            znear: 0.0,
+            zfar: self.depth,
+            layers_mask: self.layers_mask,
+            ortho_height: Some(self.span),
+            ..*scene_camera
+        }
+    }
+
+    /// Where the world `point` appears on the map in an overlay of
+    /// `viewport` logical pixels.  Points off the map land outside
+    /// [`Self::rect`].
+    pub fn world_to_map(&self, scene_camera: &Camera, viewport: [f32; 2], point: [f32; 3]) -> Option<[f32; 2]> {
+        let (min, size) = self.rect(viewport);
+        if size[0] <= 0.0 || size[1] <= 0.0 {
+            return None;
+        }
+        let map_viewport = Viewport::new(size[0], size[1]).with_origin(min[0], min[1]);
+        let [x, y, _] = self.camera(scene_camera, size[0] / size[1]).world_to_screen(point, map_viewport)?;
+        Some([x, y])
+    }
+
+    /// The quad showing the map texture, in overlay pixels.
+    pub fn quad(&self, viewport: [f32; 2]) -> MeshData {
+        let (min, size) = self.rect(viewport);
+        let [x0, y0, x1, y1] = [min[0], min[1], min[0] + size[0], min[1] + size[1]];
+        let mut mesh = MeshData::new();
+        mesh.push_quad([[x0, y1, 0.0], [x1, y1, 0.0], [x1, y0, 0.0], [x0, y0, 0.0]], [1.0; 4]);
+        mesh
+    }
+
+    /// The scene-camera marker in overlay pixels: a triangle pointing along
+    /// the camera's heading.  Empty when the marker is hidden or the camera
+    /// is off the map.
+    pub fn marker(&self, scene_camera: &Camera, viewport: [f32; 2]) -> MeshData {
+        let mut mesh = MeshData::new();
+        let Some(color) = self.marker_color else { return mesh };
+        let eye = scene_camera.eye;
+        let Some(p) = self.world_to_map(scene_camera, viewport, eye) else { return mesh };
+        let (min, size) = self.rect(viewport);
+        if p[0] < min[0] || p[1] < min[1] || p[0] > min[0] + size[0] || p[1] > min[1] + size[1] {
+            return mesh;
+        }
+        let ahead = heading(scene_camera)
+            .and_then(|[hx, hz]| self.world_to_map(scene_camera, viewport, [eye[0] + hx, eye[1], eye[2] + hz]))
+            .map(|q| [q[0] - p[0], q[1] - p[1]]);
+        let d = match ahead {
+            Some([dx, dy]) if dx * dx + dy * dy > 1e-8 => {
+                let length = (dx * dx + dy * dy).sqrt();
+                [dx / length, dy / length]
+            }
+            _ => [0.0, -1.0],
+        };
+        let n = [-d[1], d[0]];
+        let s = MARKER_SIZE;
+        let tip = [p[0] + d[0] * s * 0.6, p[1] + d[1] * s * 0.6];
+        let base = [p[0] - d[0] * s * 0.4, p[1] - d[1] * s * 0.4];
+        mesh.push_triangle(
+            [
+                [tip[0], tip[1], 0.0],
+                [base[0] + n[0] * s * 0.35, base[1] + n[1] * s * 0.35, 0.0],
+                [base[0] - n[0] * s * 0.35, base[1] - n[1] * s * 0.35, 0.0],
+            ],
+            color,
+        );
+        mesh
+    }
+
+    /// Size of the map texture for `overlay`: the map's size in physical
+    /// pixels, at least 1 × 1.
+    pub(crate) fn target_size(&self, overlay: &OverlayCamera) -> [u32; 2] {
+        let scale = if overlay.scale_factor > 0.0 { overlay.scale_factor } else { 1.0 };
+        let (_, size) = self.rect(overlay.size());
+        size.map(|side| ((side * scale).round() as u32).max(1))
+    }
+}
+
+/// The scene camera's unit horizontal forward as `[x, z]`, or `None` when
+/// it looks straight up or down.
+fn heading(camera: &Camera) -> Option<[f32; 2]> {
+    let [x, z] = [camera.target[0] - camera.eye[0], camera.target[2] - camera.eye[2]];
+    let length = (x * x + z * z).sqrt();
+    (length > 1e-4).then(|| [x / length, z / length])
+}
@@ -0,0 +1,208 @@
+//! Planar mirrors and portals rendered to textures.
+//!
+//! A mirror is a [`Geometry::Sprite`] object on [`layers::MIRROR`] whose
+//! texture is rendered every frame from a second camera; spawn one with
+//! [`crate::scene::Scene::spawn_mirror`].  For a plain mirror that camera is
+//! the scene camera reflected in the sprite's plane.  With [`Mirror::exit`]
+//! set the sprite is a portal instead: the camera is carried from the
+//! mirror's frame to the exit object's frame, turned to look out of the
+//! exit's front (its -Z side), so the portal shows what lies in front of
+//! the exit.
+//!
+//! Each pass uses an off-axis projection fitted to the surface rectangle,
+//! so the texture lines up with the sprite's UVs, and replaces its near
+//! plane with the surface plane through
+//! [`Matrix4::oblique_near_plane`]: geometry behind a mirror is never
+//! reflected.  Surfaces on [`layers::MIRROR`] are left out of every mirror
+//! pass, so mirrors do not show each other.  A surface seen from behind
+//! keeps its last image.
+//!
+//! Passes skip post-processing, render hooks, water and the screen
+//! overlay, and cost one extra scene render per visible mirror.
+//!
+//! ```no_run
+//! # fn demo(scene: &mut vertra::scene::Scene) {
+//! use vertra::mirror::Mirror;
+//! use vertra::transform::Transform;
+//! // A 2 × 3 mirror standing on the origin, facing -Z.
+//! let mirror = scene.spawn_mirror(Mirror::default(), [2.0, 3.0], Transform::from_position(0.0, 1.5, 0.0), None);
+//! # }
+//! ```
+
+use crate::camera::Camera;
+use crate::constants::layers;
+use crate::constants::mirror::{DEFAULT_RESOLUTION, MAX_RESOLUTION};
+use crate::geometry::Geometry;
+use crate::math::Matrix4;
+use crate::objects::Object;
+use crate::transform::Transform;
+
+/// Format of mirror textures.
+pub const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+/// Key of mirror `id`'s texture in [`crate::scene::Scene::textures`].
+pub fn texture_key(id: usize) -> String {
+    format!("vertra://mirror/{id}")
+}
+
+/// Width and height of a mirror object's surface: its
+/// [`Geometry::Sprite`] size.
+pub fn surface_size(object: &Object) -> Option<[f32; 2]> {
+    match object.geometry {
+        Some(Geometry::Sprite { width, height, .. }) => Some([width, height]),
+        _ => None,
+    }
+}
+
+/// Settings of a mirror or portal surface.  See the [module docs](self).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mirror {
+    /// Height of the render texture in pixels; the width follows the
+    /// surface's aspect ratio.
+    pub resolution: u32,
+    /// Object whose frame the view comes out of, making this a portal, or
+    /// `None` for a mirror.  The exit is a rectangle of the portal's own
+    /// size in that object's frame; its geometry is not used.
+    pub exit: Option<usize>,
+    /// Render layers seen in the surface, on top of the scene camera's
+    /// own mask.  [`layers::MIRROR`] is always left out.
+    pub layers_mask: u32,
+}
+
+impl Default for Mirror {
+    fn default() -> Self {
+        Self { resolution: DEFAULT_RESOLUTION, exit: None, layers_mask: layers::ALL }
+    }
+}
+
+/// The camera a mirror pass renders from.
+#[derive(Debug, Clone, Copy)]
+pub struct MirrorView {
+    /// The virtual camera: its eye, direction and layers.  Its own
+    /// projection is not used.
+    pub camera: Camera,
+    /// Off-axis projection through the surface with the surface as near
+    /// plane, times the view.
+    pub view_projection: Matrix4,
+}
+
+impl Mirror {
+    /// Render `resolution` pixels tall.
+    pub fn with_resolution(mut self, resolution: u32) -> Self {
+        self.resolution = resolution;
+        self
+    }
+
+    /// Make the surface a portal out of object `exit`, or a mirror with
+    /// `None`.
+    pub fn with_exit(mut self, exit: Option<usize>) -> Self {
+        self.exit = exit;
+        self
+    }
+
+    /// Only show the render layers in `mask`.
+    pub fn with_layers_mask(mut self, mask: u32) -> Self {
+        self.layers_mask = mask;
+        self
+    }
+
+    /// The sprite UV region that maps the texture onto the surface:
+    /// flipped horizontally for mirrors, whose view comes from behind the
+    /// surface.
+    pub fn uv(&self) -> [f32; 4] {
+        match self.exit {
+            Some(_) => [0.0, 0.0, 1.0, 1.0],
+            None => [1.0, 0.0, 0.0, 1.0],
+        }
+    }
+
+    /// Render texture size for a surface of `size` world units.
+    pub fn texture_size(&self, size: [f32; 2]) -> [u32; 2] {
+        let height = self.resolution.clamp(1, MAX_RESOLUTION);
+        let aspect = if size[1] > 0.0 { size[0] / size[1] } else { 1.0 };
+        let width = ((height as f32 * aspect).round() as u32).clamp(1, MAX_RESOLUTION);
+        [width, height]
+    }
+
+    /// The view of a `size` surface at `surface` (world transform) seen by
+    /// `camera`, through the portal exit at `exit` when this is a portal.
+    ///
+    /// `None` when the camera is behind or level with the surface, or the
+    /// transforms are degenerate.
+    pub fn view(&self, camera: &Camera, surface: &Transform, size: [f32; 2], exit: Option<&Transform>) -> Option<MirrorView> {
+        let [pa, pb, pc] = corners(surface, size);
+        let normal = normalize(cross(sub(pc, pa), sub(pb, pa)))?;
+        let side = dot(normal, sub(camera.eye, pa));
+        if side <= f32::EPSILON {
+            return None;
+        }
+        let (eye, [qa, qb, qc]) = match exit {
+            None => ([0, 1, 2].map(|k| camera.eye[k] - 2.0 * side * normal[k]), [pa, pb, pc]),
+            Some(exit) => {
+                let [x, y, z, _] = surface.to_matrix().inverse()?.mul_vec4([camera.eye[0], camera.eye[1], camera.eye[2], 1.0]);
+                let [ex, ey, ez, _] = exit.to_matrix().mul_vec4([-x, y, -z, 1.0]);
+                ([ex, ey, ez], corners(exit, size))
+            }
+        };
+
+        // Look straight through the destination surface, towards its front.
+        let forward = normalize(cross(sub(qc, qa), sub(qb, qa)))?;
+        let up = sub(qc, qa);
+        let view = Matrix4::look_at(eye, [0, 1, 2].map(|k| eye[k] + forward[k]), up);
+        let [a, b, c] = [qa, qb, qc].map(|p| view.mul_vec4([p[0], p[1], p[2], 1.0]));
+        let distance = a[2];
+        if distance <= f32::EPSILON {
+            return None;
+        }
+        let scale = camera.znear / distance;
+        let projection = Matrix4::frustum(
+            a[0].min(b[0]) * scale,
+            a[0].max(b[0]) * scale,
+            a[1].min(c[1]) * scale,
+            a[1].max(c[1]) * scale,
+            camera.znear,
+            camera.zfar,
+        )
+        .oblique_near_plane([0.0, 0.0, 1.0, -distance]);
+
+        Some(MirrorView {
+            camera: Camera {
+                eye,
+                target: [0, 1, 2].map(|k| eye[k] + forward[k]),
+                up,
+                layers_mask: camera.layers_mask & self.layers_mask & !layers::MIRROR,
+                ortho_height: None,
+                ..*camera
+            },
+            view_projection: projection * view,
+        })
+    }
+}
+
+/// World positions of the bottom-left, bottom-right and top-left corners
+/// of a `size` sprite at `transform`.
+fn corners(transform: &Transform, size: [f32; 2]) -> [[f32; 3]; 3] {
+    let matrix = transform.to_matrix();
+    let [w, h] = [size[0] * 0.5, size[1] * 0.5];
+    [[-w, -h], [w, -h], [-w, h]].map(|[x, y]| {
+        let p = matrix.mul_vec4([x, y, 0.0, 1.0]);
+        [p[0], p[1], p[2]]
+    })
+}
+
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+fn normalize(v: [f32; 3]) -> Option<[f32; 3]> {
+    let length = dot(v, v).sqrt();
+    (length > f32::EPSILON).then(|| v.map(|c| c / length))
+}
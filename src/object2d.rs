@@ -0,0 +1,306 @@
+//! 2D shape generators.
+//!
+//! [`Shape2D`] describes a flat primitive in the XY plane, centred at the
+//! origin, and turns it into [`MeshData`] either filled or as an outline of a
+//! given thickness ([`Style2D`]).  The result can be registered with
+//! [`crate::scene::Scene::register_mesh`] and placed on any object — in the
+//! [`crate::overlay`] for HUD elements, or in the world for signs and markers:
+//!
+//! ```no_run
+//! # use vertra::object2d::{Shape2D, Style2D};
+//! # use vertra::mesh::MeshData;
+//! # fn demo(scene: &mut vertra::scene::Scene) {
+//! let badge = Shape2D::RoundedRect { width: 2.0, height: 1.0, radius: 0.2, segments: 6 };
+//! let (vertices, indices) = badge.build(Style2D::Outline { thickness: 0.05 });
+//! let mesh = scene.register_mesh(&MeshData { vertices, indices, ..MeshData::new() });
+//! # }
+//! ```
+//!
+//! Triangles wind counter-clockwise seen from -Z, so shapes face a camera
+//! looking along +Z.  Fill UVs span the shape's bounding box with `v`
+//! pointing down, matching [`MeshData::push_quad`].
+//!
+//! [`NineSlice`] builds textured panels whose borders keep their size when
+//! the panel is resized.
+
+use crate::mesh::{MeshData, Vertex};
+use crate::transform::Transform;
+
+/// A flat shape in the XY plane, centred at the origin.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shape2D {
+    /// An axis-aligned rectangle with full extents `width` × `height`.
+    Rect { width: f32, height: f32 },
+    /// A circle approximated by `segments` edges.
+    Circle { radius: f32, segments: usize },
+    /// A regular polygon with `sides` corners on a circle of `radius`, the
+    /// first at +Y.
+    RegularPolygon { radius: f32, sides: usize },
+    /// A rectangle whose corners are quarter circles of `radius` (clamped to
+    /// half the smaller side), each made of `segments` edges.
+    RoundedRect { width: f32, height: f32, radius: f32, segments: usize },
+    /// A circular band between `inner_radius` and `outer_radius`.
+    Ring { inner_radius: f32, outer_radius: f32, segments: usize },
+}
+
+/// How a [`Shape2D`] is turned into triangles.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Style2D {
+    /// Cover the whole shape.
+    #[default]
+    Fill,
+    /// A border `thickness` wide drawn inside the shape's edge, so the
+    /// outline has the same extents as the filled shape.  A ring gets a
+    /// border along both of its edges.
+    Outline { thickness: f32 },
+}
+
+impl Shape2D {
+    /// The shape's outer edge as counter-clockwise points.
+    pub fn perimeter(&self) -> Vec<[f32; 2]> {
+        match *self {
+            Shape2D::Rect { width, height } => {
+                let (w, h) = (width * 0.5, height * 0.5);
+                vec![[-w, -h], [w, -h], [w, h], [-w, h]]
+            }
+            Shape2D::Circle { radius, segments } => circle(radius, segments.max(3), 0.0),
+            Shape2D::RegularPolygon { radius, sides } => {
+                circle(radius, sides.max(3), std::f32::consts::FRAC_PI_2)
+            }
+            Shape2D::RoundedRect { width, height, radius, segments } => {
+                let (w, h) = (width * 0.5, height * 0.5);
+                let r = radius.clamp(0.0, w.min(h));
+                if r <= 0.0 {
+                    return Shape2D::Rect { width, height }.perimeter();
+                }
+                let n = segments.max(1);
+                let corners = [[w - r, h - r], [r - w, h - r], [r - w, r - h], [w - r, r - h]];
+                let mut points = Vec::with_capacity(4 * (n + 1));
+                for (k, [cx, cy]) in corners.into_iter().enumerate() {
+                    for i in 0..=n {
+                        let t = (k * n + i) as f32 * std::f32::consts::FRAC_PI_2 / n as f32;
+                        points.push([cx + r * t.cos(), cy + r * t.sin()]);
+                    }
+                }
+                // A fully rounded side leaves nothing between its corners.
+                let same = |a: &[f32; 2], b: &[f32; 2]| (a[0] - b[0]).abs() < 1e-5 && (a[1] - b[1]).abs() < 1e-5;
+                points.dedup_by(|a, b| same(a, b));
+                if points.len() > 1 && same(&points[0], &points[points.len() - 1]) {
+                    points.pop();
+                }
+                points
+            }
+            Shape2D::Ring { outer_radius, segments, .. } => circle(outer_radius, segments.max(3), 0.0),
+        }
+    }
+
+    /// Build the shape at the origin in white.  Returns `(vertices, indices)`
+    /// like [`crate::geometry::Geometry::build`].
+    pub fn build(&self, style: Style2D) -> (Vec<Vertex>, Vec<u32>) {
+        let mut mesh = MeshData::new();
+        self.generate_mesh_data(&mut mesh, style, &Transform::default(), [1.0; 4]);
+        (mesh.vertices, mesh.indices)
+    }
+
+    /// Append the shape's triangles into `mesh_data`, applying `transform`
+    /// and `color` to every vertex.
+    pub fn generate_mesh_data(&self, mesh_data: &mut MeshData, style: Style2D, transform: &Transform, color: [f32; 4]) {
+        let outer = self.perimeter();
+        let (min, max) = bounds(&outer);
+        let mut emit = Emitter { mesh: mesh_data, transform, color: [color[0], color[1], color[2]], min, max };
+
+        match (*self, style) {
+            (Shape2D::Ring { inner_radius, segments, .. }, Style2D::Fill) => {
+                let inner = circle(inner_radius, segments.max(3), 0.0);
+                emit.band(&outer, &inner);
+            }
+            (Shape2D::Ring { inner_radius, segments, .. }, Style2D::Outline { thickness }) => {
+                let inner = circle(inner_radius, segments.max(3), 0.0);
+                emit.band(&outer, &offset(&outer, thickness));
+                emit.band(&offset(&inner, -thickness), &inner);
+            }
+            (_, Style2D::Fill) => emit.fan(&outer),
+            (_, Style2D::Outline { thickness }) => emit.band(&outer, &offset(&outer, thickness)),
+        }
+    }
+}
+
+/// A textured rectangle split into a 3 × 3 grid: the corners keep their
+/// size, the edges stretch along their length and the centre stretches both
+/// ways, so panel and button borders do not smear when resized.
+///
+/// ```no_run
+/// # use vertra::object2d::NineSlice;
+/// // A 300 × 120 panel from a texture whose 16 px frame is 1/8 of its width.
+/// let (vertices, indices) = NineSlice::new(300.0, 120.0, 16.0, 0.125).build();
+/// ```
+///
+/// Borders are given left, top, right, bottom.  Like [`Shape2D`], the panel
+/// lies in the XY plane centred at the origin, with the texture's top row
+/// at +Y.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NineSlice {
+    /// Full extents of the panel.
+    pub width: f32,
+    pub height: f32,
+    /// Border sizes in mesh units.  Scaled down proportionally when two
+    /// opposite borders do not fit the panel.
+    pub border: [f32; 4],
+    /// Border sizes as fractions of the texture, `0.0..=1.0`.
+    pub uv_border: [f32; 4],
+}
+
+impl NineSlice {
+    /// A `width` × `height` panel with the same `border` on every side,
+    /// covering `uv_border` of the texture.
+    pub fn new(width: f32, height: f32, border: f32, uv_border: f32) -> Self {
+        Self { width, height, border: [border; 4], uv_border: [uv_border; 4] }
+    }
+
+    /// Use separate left, top, right and bottom border sizes.
+    pub fn with_border(mut self, border: [f32; 4]) -> Self {
+        self.border = border;
+        self
+    }
+
+    /// Use separate left, top, right and bottom texture borders.
+    pub fn with_uv_border(mut self, uv_border: [f32; 4]) -> Self {
+        self.uv_border = uv_border;
+        self
+    }
+
+    /// Build the panel at the origin in white: 16 vertices, 9 quads.
+    pub fn build(&self) -> (Vec<Vertex>, Vec<u32>) {
+        let mut mesh = MeshData::new();
+        self.generate_mesh_data(&mut mesh, &Transform::default(), [1.0; 4]);
+        (mesh.vertices, mesh.indices)
+    }
+
+    /// Append the panel's triangles into `mesh_data`, applying `transform`
+    /// and `color` to every vertex.
+    pub fn generate_mesh_data(&self, mesh_data: &mut MeshData, transform: &Transform, color: [f32; 4]) {
+        let [left, top, right, bottom] = self.border.map(|b| b.max(0.0));
+        let fit = |a: f32, b: f32, size: f32| {
+            let scale = if a + b > size { size.max(0.0) / (a + b) } else { 1.0 };
+            (a * scale, b * scale)
+        };
+        let (left, right) = fit(left, right, self.width);
+        let (top, bottom) = fit(top, bottom, self.height);
+        let (w, h) = (self.width * 0.5, self.height * 0.5);
+        let xs = [-w, left - w, w - right, w];
+        let ys = [h, h - top, bottom - h, -h];
+        let [ul, vt, ur, vb] = self.uv_border;
+        let us = [0.0, ul, 1.0 - ur, 1.0];
+        let vs = [0.0, vt, 1.0 - vb, 1.0];
+
+        let matrix = transform.to_matrix();
+        let start = mesh_data.vertices.len() as u32;
+        for (&y, &v) in ys.iter().zip(&vs) {
+            for (&x, &u) in xs.iter().zip(&us) {
+                let p = matrix.mul_vec4([x, y, 0.0, 1.0]);
+                mesh_data.vertices.push(Vertex {
+                    position: [p[0], p[1], p[2]],
+                    color: [color[0], color[1], color[2]],
+                    uv: [u, v],
+                });
+            }
+        }
+        for row in 0..3 {
+            for col in 0..3 {
+                let top_left = start + row * 4 + col;
+                let bottom_left = top_left + 4;
+                mesh_data.indices.extend_from_slice(&[
+                    bottom_left, bottom_left + 1, top_left + 1,
+                    bottom_left, top_left + 1, top_left,
+                ]);
+            }
+        }
+    }
+}
+
+/// Writes transformed vertices with bounding-box UVs.
+struct Emitter<'a> {
+    mesh: &'a mut MeshData,
+    transform: &'a Transform,
+    color: [f32; 3],
+    min: [f32; 2],
+    max: [f32; 2],
+}
+
+impl Emitter<'_> {
+    fn push(&mut self, points: &[[f32; 2]]) -> u32 {
+        let start = self.mesh.vertices.len() as u32;
+        let matrix = self.transform.to_matrix();
+        let size = [(self.max[0] - self.min[0]).max(f32::EPSILON), (self.max[1] - self.min[1]).max(f32::EPSILON)];
+        for &[x, y] in points {
+            let p = matrix.mul_vec4([x, y, 0.0, 1.0]);
+            self.mesh.vertices.push(Vertex {
+                position: [p[0], p[1], p[2]],
+                color: self.color,
+                uv: [(x - self.min[0]) / size[0], 1.0 - (y - self.min[1]) / size[1]],
+            });
+        }
+        start
+    }
+
+    /// Fill a convex counter-clockwise polygon as a fan around its centre.
+    fn fan(&mut self, points: &[[f32; 2]]) {
+        let centre = points.iter().fold([0.0, 0.0], |c, p| [c[0] + p[0], c[1] + p[1]])
+            .map(|c| c / points.len() as f32);
+        let start = self.push(&[centre]);
+        self.push(points);
+        let n = points.len() as u32;
+        for i in 0..n {
+            self.mesh.indices.extend_from_slice(&[start, start + 1 + i, start + 1 + (i + 1) % n]);
+        }
+    }
+
+    /// Fill between two counter-clockwise loops with matching point counts.
+    fn band(&mut self, outer: &[[f32; 2]], inner: &[[f32; 2]]) {
+        let o = self.push(outer);
+        let i = self.push(inner);
+        let n = outer.len() as u32;
+        for k in 0..n {
+            let next = (k + 1) % n;
+            self.mesh.indices.extend_from_slice(&[o + k, o + next, i + next, o + k, i + next, i + k]);
+        }
+    }
+}
+
+/// `n` counter-clockwise points on a circle, the first at angle `start`.
+fn circle(radius: f32, n: usize, start: f32) -> Vec<[f32; 2]> {
+    (0..n)
+        .map(|i| {
+            let t = start + i as f32 * std::f32::consts::TAU / n as f32;
+            [radius * t.cos(), radius * t.sin()]
+        })
+        .collect()
+}
+
+/// Move every edge of a convex counter-clockwise loop `distance` inwards
+/// (outwards when negative), mitring the corners.
+fn offset(points: &[[f32; 2]], distance: f32) -> Vec<[f32; 2]> {
+    let n = points.len();
+    let inward = |a: [f32; 2], b: [f32; 2]| {
+        let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+        let len = (dx * dx + dy * dy).sqrt();
+        [-dy / len, dx / len]
+    };
+    (0..n)
+        .map(|k| {
+            let p = points[k];
+            let n0 = inward(points[(k + n - 1) % n], p);
+            let n1 = inward(p, points[(k + 1) % n]);
+            let m = [n0[0] + n1[0], n0[1] + n1[1]];
+            // Scale the bisector so both adjoining edges move by `distance`.
+            let scale = distance / (1.0 + n0[0] * n1[0] + n0[1] * n1[1]);
+            [p[0] + m[0] * scale, p[1] + m[1] * scale]
+        })
+        .collect()
+}
+
+fn bounds(points: &[[f32; 2]]) -> ([f32; 2], [f32; 2]) {
+    points.iter().fold(([f32::MAX; 2], [f32::MIN; 2]), |(lo, hi), p| {
+        ([lo[0].min(p[0]), lo[1].min(p[1])], [hi[0].max(p[0]), hi[1].max(p[1])])
+    })
+}
@@ -1,4 +1,7 @@
-use crate::geometry::Geometry;
+use crate::constants::layers;
+use crate::geometry::{Geometry, GeometryId};
+use crate::material::Material;
+use crate::mesh::MeshUsage;
 use crate::transform::Transform;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -47,6 +50,28 @@ pub struct Object {
     pub str_id: String,
     /// Path to a texture image applied to this object's surface.
     pub texture_path: Option<String>,
+    /// Optional shared mesh from [`crate::mesh::MeshRegistry`], rendered with
+    /// this object's world transform and color in addition to `geometry`
+    /// (except meshes baked from it by [`crate::scene::Scene::spawn_batch`]).
+    ///
+    /// Registry handles are only valid for the running session, so this field
+    /// is not serialised (VTR files and serde output omit it).  Once the
+    /// object is in a world, change it with [`crate::world::World::set_mesh`]
+    /// so the world's mesh reference counts stay current.
+    #[serde(skip)]
+    pub mesh: Option<GeometryId>,
+    /// Render-layer bits.  The object is drawn by a camera only when
+    /// `layer & camera.layers_mask != 0`; see [`crate::constants::layers`].
+    /// Defaults to [`layers::DEFAULT`].  Children do not inherit it.
+    #[serde(default = "default_layer")]
+    pub layer: u32,
+    /// Face culling and depth state; see [`crate::material`].
+    #[serde(default)]
+    pub material: Material,
+}
+
+fn default_layer() -> u32 {
+    layers::DEFAULT
 }
 
 /// Configuration bundle passed to [`Object::new`].
@@ -89,6 +114,8 @@ impl Object {
     /// * `str_id` -> random UUID
     /// * `color` -> opaque white
     /// * `geometry` -> `None` (invisible)
+    /// * `layer` -> [`layers::DEFAULT`]
+    /// * `material` -> [`Material::OPAQUE`]
     pub fn new(config: ObjectConstructor) -> Self {
         Self {
             name: config.name,
@@ -99,9 +126,30 @@ impl Object {
             children: Vec::new(),
             parent: None,
             texture_path: config.texture_path,
+            mesh: None,
+            layer: layers::DEFAULT,
+            material: Material::default(),
         }
     }
 
+    /// Start an [`ObjectBuilder`] from [`Object::default`].
+    ///
+    /// ```
+    /// use vertra::geometry::Geometry;
+    /// use vertra::objects::Object;
+    ///
+    /// let object = Object::builder()
+    ///     .name("crate")
+    ///     .geometry(Geometry::Cube { size: 1.0 })
+    ///     .color([0.6, 0.4, 0.2, 1.0])
+    ///     .at(0.0, 0.5, 0.0)
+    ///     .build();
+    /// assert_eq!(object.transform.position, [0.0, 0.5, 0.0]);
+    /// ```
+    pub fn builder() -> ObjectBuilder {
+        ObjectBuilder::default()
+    }
+
     /// Convenience constructor for an object with a known geometry, transform,
     /// and color.
     ///
@@ -124,8 +172,122 @@ impl Object {
             color,
             children: Vec::new(),
             parent: None,
-            str_id: str_id.unwrap_or_else(|| Uuid::new_v4().to_string()).into(),
+            str_id: str_id.unwrap_or_else(|| Uuid::new_v4().to_string()),
             texture_path: None,
+            mesh: None,
+            layer: layers::DEFAULT,
+            material: Material::default(),
         }
     }
-}
\ No newline at end of file
+}
+/// Fluent alternative to [`ObjectConstructor`], started with
+/// [`Object::builder`].
+///
+/// Unset fields keep the [`Object::default`] values.  Finish with
+/// [`Self::build`], or hand the builder to
+/// [`crate::scene::Scene::spawn_built`] to spawn it with its
+/// [`Self::usage`] hint.
+#[derive(Clone, Debug, Default)]
+pub struct ObjectBuilder {
+    object: Object,
+    usage: MeshUsage,
+}
+
+impl ObjectBuilder {
+    /// Set the display name.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.object.name = name.into();
+        self
+    }
+
+    /// Set the stable string identifier instead of a random UUID.
+    pub fn str_id(mut self, str_id: impl Into<String>) -> Self {
+        self.object.str_id = str_id.into();
+        self
+    }
+
+    /// Attach procedural geometry.
+    pub fn geometry(mut self, geometry: Geometry) -> Self {
+        self.object.geometry = Some(geometry);
+        self
+    }
+
+    /// Attach a shared mesh from [`crate::mesh::MeshRegistry`].
+    pub fn mesh(mut self, mesh: GeometryId) -> Self {
+        self.object.mesh = Some(mesh);
+        self
+    }
+
+    /// Set the RGBA base color.
+    pub fn color(mut self, color: [f32; 4]) -> Self {
+        self.object.color = color;
+        self
+    }
+
+    /// Set the texture image path.
+    pub fn texture(mut self, path: impl Into<String>) -> Self {
+        self.object.texture_path = Some(path.into());
+        self
+    }
+
+    /// Replace the whole local transform.
+    pub fn transform(mut self, transform: Transform) -> Self {
+        self.object.transform = transform;
+        self
+    }
+
+    /// Set the local position.
+    pub fn at(mut self, x: f32, y: f32, z: f32) -> Self {
+        self.object.transform.position = [x, y, z];
+        self
+    }
+
+    /// Set the local Euler rotation in degrees (see [`Transform::rotation`]).
+    pub fn rotated(mut self, rotation: [f32; 3]) -> Self {
+        self.object.transform.rotation = rotation;
+        self
+    }
+
+    /// Set the local per-axis scale.
+    pub fn scaled(mut self, scale: [f32; 3]) -> Self {
+        self.object.transform.scale = scale;
+        self
+    }
+
+    /// Set the render-layer bits (see [`Object::layer`]).
+    pub fn layer(mut self, layer: u32) -> Self {
+        self.object.layer = layer;
+        self
+    }
+
+    /// Set the culling and depth state (see [`Object::material`]).
+    pub fn material(mut self, material: Material) -> Self {
+        self.object.material = material;
+        self
+    }
+
+    /// Set the mesh usage hint applied by
+    /// [`crate::scene::Scene::spawn_built`].  Defaults to
+    /// [`MeshUsage::Dynamic`].
+    pub fn usage(mut self, usage: MeshUsage) -> Self {
+        self.usage = usage;
+        self
+    }
+
+    /// The configured usage hint.
+    pub fn mesh_usage(&self) -> MeshUsage {
+        self.usage
+    }
+
+    /// Finish the object.  The usage hint is dropped; spawn with
+    /// [`crate::scene::Scene::spawn_built`] to keep it.
+    pub fn build(self) -> Object {
+        self.object
+    }
+}
+
+impl From<ObjectBuilder> for Object {
+    fn from(builder: ObjectBuilder) -> Self {
+        builder.build()
+    }
+}
@@ -0,0 +1,421 @@
+//! Hardware occlusion queries for expensive objects.
+//!
+//! Objects flagged with [`crate::scene::Scene::set_occlusion_query`] are
+//! tested every frame: once the opaque layers are drawn, their world-space
+//! bounding boxes are rasterised against the depth buffer in a depth-only
+//! pass that writes nothing, and an occlusion query counts the samples that
+//! pass.  [`OcclusionQueries`] reads the counts back without stalling the
+//! frame, and an object whose box had no visible samples is left out of the
+//! next frame's draws.  Its box is still queried, so the object comes back
+//! one frame after it is uncovered.
+//!
+//! Results arrive a frame late, or later when the GPU is slow to hand them
+//! back, so a fast camera move can show a flagged object popping in.  Flag
+//! objects that are expensive to draw and often hidden behind walls or
+//! floors; for cheap ones the query costs more than it saves.
+//!
+//! Boxes cover the object's geometry and registry mesh, padded by
+//! [`BOX_MARGIN`]; children are only queried when flagged themselves.
+//! Objects whose box holds the camera are always drawn, as are objects in
+//! static batches and everything in capture passes (mirrors, the minimap,
+//! cubemaps), whose cameras the results do not apply to.
+//!
+//! ```no_run
+//! # fn demo(scene: &mut vertra::scene::Scene, statue: usize) {
+//! scene.set_occlusion_query(statue, true);
+//! // ... a few frames later:
+//! if scene.is_occluded(statue) {
+//!     println!("the statue is behind a wall");
+//! }
+//! # }
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, PoisonError};
+use wgpu::{Device, Queue};
+use crate::camera::Viewport;
+use crate::constants::occlusion::{BOX_MARGIN, INITIAL_CAPACITY, MAX_QUERIES};
+use crate::geometry::Geometry;
+use crate::math::Matrix4;
+use crate::mesh::MeshData;
+use crate::transform::Transform;
+
+/// Axis-aligned bounds as `(min, max)`.
+pub type Bounds = ([f32; 3], [f32; 3]);
+
+/// Triangles over the corners of [`OcclusionBox::corners`].
+const BOX_INDICES: [u32; 36] = [
+    0, 2, 6, 0, 6, 4, // -X
+    1, 5, 7, 1, 7, 3, // +X
+    0, 4, 5, 0, 5, 1, // -Y
+    2, 3, 7, 2, 7, 6, // +Y
+    0, 1, 3, 0, 3, 2, // -Z
+    4, 6, 7, 4, 7, 5, // +Z
+];
+
+/// A world-space box queried for one object.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OcclusionBox {
+    /// Handed back with the result; the object ID for scenes.
+    pub key: usize,
+    pub min: [f32; 3],
+    pub max: [f32; 3],
+}
+
+impl OcclusionBox {
+    /// The world box around local `bounds` placed by `model`, padded by
+    /// [`BOX_MARGIN`] of its largest side.
+    pub fn from_local(key: usize, bounds: Bounds, model: &Matrix4) -> Self {
+        let (lo, hi) = bounds;
+        let corner = |i: usize| {
+            let pick = |k: usize| if i >> k & 1 == 0 { lo[k] } else { hi[k] };
+            let p = model.mul_vec4([pick(0), pick(1), pick(2), 1.0]);
+            [p[0], p[1], p[2]]
+        };
+        let (mut min, mut max) = (corner(0), corner(0));
+        for p in (1..8).map(corner) {
+            min = [0, 1, 2].map(|k| min[k].min(p[k]));
+            max = [0, 1, 2].map(|k| max[k].max(p[k]));
+        }
+        let pad = (0..3).map(|k| max[k] - min[k]).fold(0.0, f32::max) * BOX_MARGIN;
+        Self { key, min: min.map(|v| v - pad), max: max.map(|v| v + pad) }
+    }
+
+    /// The box grown by `by` on every side, e.g. by
+    /// [`crate::material::Displacement::reach`].
+    pub fn grown(self, by: f32) -> Self {
+        Self { min: self.min.map(|v| v - by), max: self.max.map(|v| v + by), ..self }
+    }
+
+    /// Whether `point` lies inside the box or on its surface.
+    pub fn contains(&self, point: [f32; 3]) -> bool {
+        (0..3).all(|k| point[k] >= self.min[k] && point[k] <= self.max[k])
+    }
+
+    /// The eight corners; bit 0, 1 and 2 of the index pick the max side on
+    /// X, Y and Z.
+    pub fn corners(&self) -> [[f32; 3]; 8] {
+        std::array::from_fn(|i| {
+            std::array::from_fn(|k| if i >> k & 1 == 0 { self.min[k] } else { self.max[k] })
+        })
+    }
+}
+
+/// Union of two optional bounds.
+pub fn union(a: Option<Bounds>, b: Option<Bounds>) -> Option<Bounds> {
+    match (a, b) {
+        (Some((alo, ahi)), Some((blo, bhi))) => {
+            Some(([0, 1, 2].map(|k| alo[k].min(blo[k])), [0, 1, 2].map(|k| ahi[k].max(bhi[k]))))
+        }
+        (a, b) => a.or(b),
+    }
+}
+
+/// Flagged objects and what their last queries said.  Owned by the scene.
+#[derive(Debug, Default)]
+pub(crate) struct QueriedObjects {
+    /// IDs of flagged objects.
+    pub(crate) ids: HashSet<usize>,
+    /// Flagged objects whose box had no visible samples in the last results.
+    pub(crate) occluded: HashSet<usize>,
+    /// Local bounds of each flagged object's geometry, with the geometry
+    /// they were measured from.
+    geometry_bounds: HashMap<usize, (Geometry, Option<Bounds>)>,
+}
+
+impl QueriedObjects {
+    /// Flag or unflag `id`.
+    pub(crate) fn set(&mut self, id: usize, enabled: bool) {
+        if enabled {
+            self.ids.insert(id);
+        } else {
+            self.ids.remove(&id);
+            self.occluded.remove(&id);
+            self.geometry_bounds.remove(&id);
+        }
+    }
+
+    /// Forget objects for which `exists` is false.
+    pub(crate) fn prune(&mut self, exists: impl Fn(usize) -> bool) {
+        self.ids.retain(|&id| exists(id));
+        self.occluded.retain(|id| self.ids.contains(id));
+        self.geometry_bounds.retain(|id, _| self.ids.contains(id));
+    }
+
+    /// Take in query results: `(id, visible)` pairs.
+    pub(crate) fn apply(&mut self, results: &[(usize, bool)]) {
+        for &(id, visible) in results {
+            if visible || !self.ids.contains(&id) {
+                self.occluded.remove(&id);
+            } else {
+                self.occluded.insert(id);
+            }
+        }
+    }
+
+    /// Local bounds of `geometry` for object `id`, measured once per
+    /// geometry value.
+    pub(crate) fn geometry_bounds(&mut self, id: usize, geometry: &Geometry) -> Option<Bounds> {
+        match self.geometry_bounds.get(&id) {
+            Some((measured, bounds)) if measured == geometry => *bounds,
+            _ => {
+                let mut mesh = MeshData::new();
+                geometry.generate_mesh_data(&mut mesh, &Transform::default(), [1.0; 4]);
+                let bounds = mesh.bounds();
+                self.geometry_bounds.insert(id, (geometry.clone(), bounds));
+                bounds
+            }
+        }
+    }
+}
+
+/// The box pass, its query set and the readback of its results.
+pub struct OcclusionQueries {
+    pipeline: wgpu::RenderPipeline,
+    /// The camera's view-projection.
+    params: wgpu::Buffer,
+    /// Eight corners per box.
+    vertices: wgpu::Buffer,
+    /// [`BOX_INDICES`], shared by every box through its base vertex.
+    indices: wgpu::Buffer,
+    query_set: wgpu::QuerySet,
+    /// Sample counts resolved from `query_set`.
+    resolve: wgpu::Buffer,
+    /// Copy of `resolve` mapped for reading.
+    readback: wgpu::Buffer,
+    capacity: u32,
+    /// Boxes for the next recorded pass.
+    pending: Vec<OcclusionBox>,
+    /// Keys of the boxes in flight, in query order; empty when idle.
+    in_flight: Vec<usize>,
+    /// Set by the readback's map callback: `Some(mapped)` once it ran.
+    mapped: Option<Arc<Mutex<Option<bool>>>>,
+}
+
+impl OcclusionQueries {
+    /// Bindings of `occlusion.wgsl`: the view-projection uniform.
+    pub const LAYOUT_ENTRIES: [wgpu::BindGroupLayoutEntry; 1] = [wgpu::BindGroupLayoutEntry {
+        binding: 0,
+        visibility: wgpu::ShaderStages::VERTEX,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    }];
+
+    /// Compile the box pass for bind groups of `layout`, created from
+    /// [`Self::LAYOUT_ENTRIES`], against a `depth_format` depth buffer.
+    pub fn new(device: &Device, layout: &wgpu::BindGroupLayout, depth_format: wgpu::TextureFormat) -> Self {
+        let shader = device.create_shader_module(wgpu::include_wgsl!("occlusion.wgsl"));
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Occlusion Pipeline Layout"),
+            bind_group_layouts: &[Some(layout)],
+            immediate_size: 0,
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Occlusion Pipeline"),
+            layout: Some(&pipeline_layout),
+            cache: None, multiview_mask: None,
+            vertex: wgpu::VertexState {
+                module: &shader, entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: size_of::<[f32; 3]>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &wgpu::vertex_attr_array![0 => Float32x3],
+                }],
+            },
+            fragment: None,
+            // Both sides, so a box cut by the near plane still shows its
+            // far faces.
+            primitive: wgpu::PrimitiveState { cull_mode: None, ..Default::default() },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: depth_format,
+                depth_write_enabled: Some(false),
+                depth_compare: Some(wgpu::CompareFunction::LessEqual),
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+        });
+        let params = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Occlusion Params Buffer"),
+            size: size_of::<[[f32; 4]; 4]>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let indices = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Occlusion Index Buffer"),
+            size: size_of_val(&BOX_INDICES) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::INDEX,
+            mapped_at_creation: true,
+        });
+        indices.slice(..).get_mapped_range_mut().copy_from_slice(bytemuck::cast_slice(&BOX_INDICES));
+        indices.unmap();
+        let (vertices, query_set, resolve, readback) = create_query_buffers(device, INITIAL_CAPACITY);
+        Self {
+            pipeline,
+            params,
+            vertices,
+            indices,
+            query_set,
+            resolve,
+            readback,
+            capacity: INITIAL_CAPACITY,
+            pending: Vec::new(),
+            in_flight: Vec::new(),
+            mapped: None,
+        }
+    }
+
+    /// Query `boxes` in the next recorded pass, replacing boxes not yet
+    /// recorded.  Boxes beyond [`MAX_QUERIES`] are dropped.
+    pub fn queue(&mut self, mut boxes: Vec<OcclusionBox>) {
+        boxes.truncate(MAX_QUERIES as usize);
+        self.pending = boxes;
+    }
+
+    /// Whether boxes are waiting for the next recorded pass.
+    pub fn has_queued(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Whether a pass has been recorded whose results have not been taken
+    /// yet.  No new pass is recorded meanwhile.
+    pub fn is_busy(&self) -> bool {
+        !self.in_flight.is_empty()
+    }
+
+    /// The bind group entries of the box pass, for a bind group of
+    /// [`Self::LAYOUT_ENTRIES`].
+    pub fn bind_group_entries(&self) -> [wgpu::BindGroupEntry<'_>; 1] {
+        [wgpu::BindGroupEntry { binding: 0, resource: self.params.as_entire_binding() }]
+    }
+
+    /// Record the queued boxes into `encoder` against `depth_view`, seen
+    /// through `view_proj` in `viewport` (or the whole target), followed by
+    /// the copy of their results.  Does nothing while idle or busy.
+    /// `bind_group` binds [`Self::bind_group_entries`].  Returns `(bytes
+    /// written, buffers allocated)`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn record(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        bind_group: &wgpu::BindGroup,
+        depth_view: &wgpu::TextureView,
+        view_proj: &Matrix4,
+        viewport: Option<Viewport>,
+    ) -> (u64, u32) {
+        if self.pending.is_empty() || self.is_busy() {
+            return (0, 0);
+        }
+        let boxes = std::mem::take(&mut self.pending);
+        let count = boxes.len() as u32;
+        let mut allocated = 0;
+        if count > self.capacity {
+            self.capacity = count.next_power_of_two().min(MAX_QUERIES);
+            (self.vertices, self.query_set, self.resolve, self.readback) = create_query_buffers(device, self.capacity);
+            allocated += 4;
+        }
+        let corners: Vec<[f32; 3]> = boxes.iter().flat_map(OcclusionBox::corners).collect();
+        queue.write_buffer(&self.params, 0, bytemuck::bytes_of(&view_proj.data));
+        queue.write_buffer(&self.vertices, 0, bytemuck::cast_slice(&corners));
+        {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Occlusion Pass"),
+                color_attachments: &[],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: depth_view,
+                    depth_ops: Some(wgpu::Operations { load: wgpu::LoadOp::Load, store: wgpu::StoreOp::Store }),
+                    stencil_ops: None,
+                }),
+                occlusion_query_set: Some(&self.query_set),
+                ..Default::default()
+            });
+            if let Some(viewport) = viewport {
+                pass.set_viewport(viewport.x, viewport.y, viewport.width, viewport.height, 0.0, 1.0);
+            }
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, bind_group, &[]);
+            pass.set_vertex_buffer(0, self.vertices.slice(..));
+            pass.set_index_buffer(self.indices.slice(..), wgpu::IndexFormat::Uint32);
+            for index in 0..count {
+                pass.begin_occlusion_query(index);
+                pass.draw_indexed(0..BOX_INDICES.len() as u32, index as i32 * 8, 0..1);
+                pass.end_occlusion_query();
+            }
+        }
+        let result_bytes = u64::from(count) * size_of::<u64>() as u64;
+        encoder.resolve_query_set(&self.query_set, 0..count, &self.resolve, 0);
+        encoder.copy_buffer_to_buffer(&self.resolve, 0, &self.readback, 0, result_bytes);
+        self.in_flight = boxes.iter().map(|b| b.key).collect();
+        ((size_of_val(&view_proj.data) + size_of_val(corners.as_slice())) as u64, allocated)
+    }
+
+    /// Start reading back the results of the recorded pass.  Call once the
+    /// encoder holding it has been submitted.
+    pub fn map(&mut self) {
+        if !self.is_busy() || self.mapped.is_some() {
+            return;
+        }
+        let state = Arc::new(Mutex::new(None));
+        let callback_state = state.clone();
+        let bytes = self.in_flight.len() as u64 * size_of::<u64>() as u64;
+        self.readback.slice(..bytes).map_async(wgpu::MapMode::Read, move |result| {
+            *callback_state.lock().unwrap_or_else(PoisonError::into_inner) = Some(result.is_ok());
+        });
+        self.mapped = Some(state);
+    }
+
+    /// The results of the last pass as `(key, visible)` pairs, once they
+    /// have been read back.  The device must be polled for them to arrive.
+    /// Frees the queries for the next pass.
+    pub fn take_results(&mut self) -> Option<Vec<(usize, bool)>> {
+        let mapped = (*self.mapped.as_ref()?.lock().unwrap_or_else(PoisonError::into_inner))?;
+        self.mapped = None;
+        let keys = std::mem::take(&mut self.in_flight);
+        if !mapped {
+            return None;
+        }
+        let bytes = keys.len() as u64 * size_of::<u64>() as u64;
+        let results = {
+            let data = self.readback.slice(..bytes).get_mapped_range();
+            let samples: &[u64] = bytemuck::cast_slice(&data);
+            keys.into_iter().zip(samples).map(|(key, &samples)| (key, samples > 0)).collect()
+        };
+        self.readback.unmap();
+        Some(results)
+    }
+}
+
+fn create_query_buffers(device: &Device, capacity: u32) -> (wgpu::Buffer, wgpu::QuerySet, wgpu::Buffer, wgpu::Buffer) {
+    let vertices = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Occlusion Vertex Buffer"),
+        size: (size_of::<[[f32; 3]; 8]>() * capacity as usize) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let query_set = device.create_query_set(&wgpu::QuerySetDescriptor {
+        label: Some("Occlusion Query Set"),
+        ty: wgpu::QueryType::Occlusion,
+        count: capacity,
+    });
+    let result_bytes = (size_of::<u64>() * capacity as usize) as wgpu::BufferAddress;
+    let resolve = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Occlusion Resolve Buffer"),
+        size: result_bytes,
+        usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+        mapped_at_creation: false,
+    });
+    let readback = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Occlusion Readback Buffer"),
+        size: result_bytes,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    (vertices, query_set, resolve, readback)
+}
@@ -0,0 +1,11 @@
+// World-space bounding boxes for occlusion queries.  Depth-tested against
+// the scene but never written, and without a fragment stage: only the
+// samples that pass are counted.
+
+@group(0) @binding(0)
+var<uniform> view_proj: mat4x4<f32>;
+
+@vertex
+fn vs_main(@location(0) position: vec3<f32>) -> @builtin(position) vec4<f32> {
+    return view_proj * vec4<f32>(position, 1.0);
+}
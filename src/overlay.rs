@@ -0,0 +1,364 @@
+//! Pixel-space 2D overlay for HUDs.
+//!
+//! [`Overlay`] holds a second [`World`] whose objects are drawn after the 3D
+//! scene and all post-processing, without depth testing or lighting, through
+//! an [`OverlayCamera`] whose units are logical pixels with `(0, 0)` at the
+//! top-left of the window.  One unit is [`OverlayCamera::scale_factor`]
+//! physical pixels, so a HUD keeps its apparent size on high-DPI displays
+//! while still being rasterised at full resolution; at integer scale factors
+//! elements land on exact pixels:
+//!
+//! ```no_run
+//! # use vertra::mesh::MeshData;
+//! # use vertra::object2d::{Shape2D, Style2D};
+//! # use vertra::objects::Object;
+//! # use vertra::transform::Transform;
+//! # fn demo(scene: &mut vertra::scene::Scene) {
+//! // A 200 × 40 health bar whose top-left corner is 16 px from the window's.
+//! let (vertices, indices) = Shape2D::Rect { width: 200.0, height: 40.0 }.build(Style2D::Fill);
+//! let bar = scene.register_mesh(&MeshData { vertices, indices, ..MeshData::new() });
+//! scene.overlay.world.spawn_object(Object {
+//!     mesh: Some(bar),
+//!     transform: Transform::from_position(116.0, 36.0, 0.0),
+//!     color: [0.8, 0.1, 0.1, 1.0],
+//!     ..Default::default()
+//! }, None);
+//! # }
+//! ```
+//!
+//! Elements are painted in scene-graph order — roots in spawn order, each
+//! parent before its children — so later elements cover earlier ones; `z`
+//! does not affect the order (see [`crate::constants::overlay::DEPTH_RANGE`]).
+//!
+//! Y points down, so shapes from [`crate::object2d`], which are built Y-up,
+//! appear mirrored vertically.  That is invisible for untextured symmetric
+//! shapes; give textured elements such as a [`crate::object2d::NineSlice`]
+//! a negative Y scale to show the texture upright.  The overlay is not saved
+//! to `.vtr` files and ignores render layers.
+//!
+//! # Layout
+//! Instead of positioning an element by hand after every resize, give it a
+//! [`Layout`]: an [`Anchor`] on the window plus [`Margins`].  The element's
+//! transform is then recomputed each frame from the current window size:
+//!
+//! ```no_run
+//! # use vertra::overlay::{Anchor, Layout, Margins};
+//! # fn demo(scene: &mut vertra::scene::Scene, minimap: usize, bar: usize) {
+//! // 160 × 160 in the bottom-right corner, 16 px from both edges.
+//! scene.overlay.set_layout(minimap, Layout::new(Anchor::BottomRight, [160.0, 160.0])
+//!     .with_margins(Margins::all(16.0)));
+//! // Full width along the top, 24 px tall (built at 100 × 24 and scaled).
+//! scene.overlay.set_layout(bar, Layout::new(Anchor::TopStretch, [100.0, 24.0]));
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use crate::constants::overlay::DEPTH_RANGE;
+use crate::math::Matrix4;
+use crate::mesh::MeshData;
+use crate::minimap::Minimap;
+use crate::transform::Transform;
+use crate::view_axes::ViewAxes;
+use crate::world::World;
+
+/// Orthographic camera mapping logical pixels to the window, origin at the
+/// top-left, Y down.  Kept in sync with the window size by
+/// [`crate::scene::Scene::resize`] and with the DPI by
+/// [`crate::scene::Scene::set_scale_factor`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OverlayCamera {
+    /// Viewport width in physical pixels.
+    pub width: f32,
+    /// Viewport height in physical pixels.
+    pub height: f32,
+    /// Physical pixels per overlay unit.  Defaults to `1.0`.
+    pub scale_factor: f32,
+}
+
+impl OverlayCamera {
+    /// A camera for a `width` × `height` physical pixel viewport, at scale
+    /// factor 1.
+    pub fn new(width: f32, height: f32) -> Self {
+        Self { width, height, scale_factor: 1.0 }
+    }
+
+    /// Use `scale_factor` physical pixels per overlay unit.
+    pub fn with_scale_factor(mut self, scale_factor: f32) -> Self {
+        self.scale_factor = scale_factor;
+        self
+    }
+
+    /// Viewport size in overlay units (logical pixels).
+    pub fn size(&self) -> [f32; 2] {
+        let scale = if self.scale_factor > 0.0 { self.scale_factor } else { 1.0 };
+        [self.width / scale, self.height / scale]
+    }
+
+    /// The window in overlay units, for placing overlay items over 3D
+    /// points with [`crate::camera::Camera::world_to_screen`].
+    pub fn viewport(&self) -> crate::camera::Viewport {
+        let [width, height] = self.size();
+        crate::camera::Viewport::new(width, height)
+    }
+
+    /// Convert a point in physical window pixels, such as
+    /// [`crate::scene::Scene::cursor_position`], to overlay units.
+    pub fn from_physical(&self, point: [f32; 2]) -> [f32; 2] {
+        let scale = if self.scale_factor > 0.0 { self.scale_factor } else { 1.0 };
+        [point[0] / scale, point[1] / scale]
+    }
+
+    /// Projection from overlay coordinates to clip space.
+    pub fn view_projection(&self) -> Matrix4 {
+        let [width, height] = self.size();
+        Matrix4::orthographic(0.0, width, height, 0.0, -DEPTH_RANGE, DEPTH_RANGE)
+    }
+}
+
+/// The 2D overlay drawn on top of the scene.  Stored on
+/// [`crate::scene::Scene::overlay`].
+pub struct Overlay {
+    /// Pixel camera; resized with the window.
+    pub camera: OverlayCamera,
+    /// Overlay elements, positioned in logical pixels.
+    pub world: World,
+    /// Orientation widget drawn over the elements; `None` (the default)
+    /// hides it.
+    pub view_axes: Option<ViewAxes>,
+    /// Top-down minimap drawn under the orientation widget; `None` (the
+    /// default) hides it.
+    pub minimap: Option<Minimap>,
+    /// Layouts keyed by element ID.  Kept out of [`World`] like scripts.
+    layouts: HashMap<usize, Layout>,
+}
+
+impl Overlay {
+    /// An empty overlay for a `width` × `height` physical pixel viewport.
+    pub fn new(width: f32, height: f32) -> Self {
+        Self {
+            camera: OverlayCamera::new(width, height),
+            world: World::new(),
+            view_axes: None,
+            minimap: None,
+            layouts: HashMap::new(),
+        }
+    }
+
+    /// Place element `id` by `layout` from now on, replacing any previous
+    /// layout.  Its position (and, when stretched, the magnitude of its
+    /// scale) is overwritten every frame; rotation and `z` are left alone.
+    ///
+    /// Layouts are computed in window pixels, so use them on root elements;
+    /// children move with their parent.
+    pub fn set_layout(&mut self, id: usize, layout: Layout) {
+        self.layouts.insert(id, layout);
+    }
+
+    /// The layout of element `id`, if any.
+    pub fn layout(&self, id: usize) -> Option<&Layout> {
+        self.layouts.get(&id)
+    }
+
+    /// Stop laying out element `id`; it keeps its last transform.  Returns
+    /// `true` if it had a layout.
+    pub fn remove_layout(&mut self, id: usize) -> bool {
+        self.layouts.remove(&id).is_some()
+    }
+
+    /// Reposition every laid-out element for the current camera size and
+    /// forget layouts of deleted elements.  Called by
+    /// [`crate::scene::Scene::draw_world`] before drawing.
+    pub fn apply_layout(&mut self) {
+        let viewport = self.camera.size();
+        let world = &mut self.world;
+        self.layouts.retain(|&id, layout| {
+            let Some(obj) = world.objects.get_mut(&id) else { return false };
+            let (min, size) = layout.rect(viewport);
+            let t = &mut obj.transform;
+            t.position[0] = min[0] + size[0] * 0.5;
+            t.position[1] = min[1] + size[1] * 0.5;
+            for (axis, align) in layout.anchor.alignment().into_iter().enumerate() {
+                if align == Align::Stretch && layout.size[axis] > 0.0 {
+                    // Keep the sign so flipped elements stay flipped.
+                    t.scale[axis] = t.scale[axis].signum() * size[axis] / layout.size[axis];
+                }
+            }
+            true
+        });
+    }
+}
+
+/// Where a laid-out element sits in the window.
+///
+/// The nine point anchors keep the element at its natural size, aligned to
+/// a corner, edge midpoint or the centre of the window inset by the
+/// [`Margins`].  The stretch anchors scale it to span the inset window along
+/// one or both axes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Anchor {
+    #[default]
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+    /// Fill the whole inset window.
+    Stretch,
+    /// Full width along the top edge.
+    TopStretch,
+    /// Full width along the bottom edge.
+    BottomStretch,
+    /// Full height along the left edge.
+    LeftStretch,
+    /// Full height along the right edge.
+    RightStretch,
+}
+
+/// Placement along one axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Align {
+    Start,
+    Center,
+    End,
+    Stretch,
+}
+
+impl Anchor {
+    /// Horizontal and vertical alignment.
+    fn alignment(self) -> [Align; 2] {
+        use Align::*;
+        match self {
+            Anchor::TopLeft => [Start, Start],
+            Anchor::Top => [Center, Start],
+            Anchor::TopRight => [End, Start],
+            Anchor::Left => [Start, Center],
+            Anchor::Center => [Center, Center],
+            Anchor::Right => [End, Center],
+            Anchor::BottomLeft => [Start, End],
+            Anchor::Bottom => [Center, End],
+            Anchor::BottomRight => [End, End],
+            Anchor::Stretch => [Stretch, Stretch],
+            Anchor::TopStretch => [Stretch, Start],
+            Anchor::BottomStretch => [Stretch, End],
+            Anchor::LeftStretch => [Start, Stretch],
+            Anchor::RightStretch => [End, Stretch],
+        }
+    }
+}
+
+/// Distances in pixels between the window edges and a laid-out element.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Margins {
+    pub left: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+}
+
+impl Margins {
+    /// The same margin on every side.
+    pub fn all(margin: f32) -> Self {
+        Self { left: margin, top: margin, right: margin, bottom: margin }
+    }
+
+    /// `horizontal` on the left and right, `vertical` on the top and bottom.
+    pub fn symmetric(horizontal: f32, vertical: f32) -> Self {
+        Self { left: horizontal, top: vertical, right: horizontal, bottom: vertical }
+    }
+}
+
+/// Anchor, margins and natural size of an overlay element.  See
+/// [`Overlay::set_layout`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Layout {
+    pub anchor: Anchor,
+    pub margins: Margins,
+    /// The element's size in pixels at scale 1, e.g. the `width` and
+    /// `height` of the [`crate::object2d::Shape2D`] it was built from.
+    /// Stretched axes are scaled relative to it.
+    pub size: [f32; 2],
+}
+
+impl Layout {
+    /// An element of natural `size` at `anchor`, without margins.
+    pub fn new(anchor: Anchor, size: [f32; 2]) -> Self {
+        Self { anchor, margins: Margins::default(), size }
+    }
+
+    /// Keep `margins` between the element and the window edges.
+    pub fn with_margins(mut self, margins: Margins) -> Self {
+        self.margins = margins;
+        self
+    }
+
+    /// The element's top-left corner and size in a `viewport` of
+    /// `[width, height]` pixels.
+    pub fn rect(&self, viewport: [f32; 2]) -> ([f32; 2], [f32; 2]) {
+        let m = self.margins;
+        let start = [m.left, m.top];
+        let space = [viewport[0] - m.left - m.right, viewport[1] - m.top - m.bottom];
+        let mut min = [0.0; 2];
+        let mut size = self.size;
+        for (axis, align) in self.anchor.alignment().into_iter().enumerate() {
+            min[axis] = match align {
+                Align::Start => start[axis],
+                Align::Center => start[axis] + (space[axis] - size[axis]) * 0.5,
+                Align::End => start[axis] + space[axis] - size[axis],
+                Align::Stretch => {
+                    size[axis] = space[axis].max(0.0);
+                    start[axis]
+                }
+            };
+        }
+        (min, size)
+    }
+}
+
+/// One overlay draw, in paint order.
+pub(crate) enum OverlayItem {
+    /// Consecutive procedural-geometry elements sharing a texture, flattened
+    /// into one mesh.
+    Batch(Option<String>, MeshData),
+    /// An element referencing a registry mesh, with its world transform.
+    Mesh(usize, Transform),
+}
+
+/// Flatten `world` into draws in paint order.
+pub(crate) fn collect(world: &World) -> Vec<OverlayItem> {
+    let mut items = Vec::new();
+    let identity = Transform::default();
+    for &root_id in &world.roots {
+        collect_object(world, root_id, &identity, &mut items);
+    }
+    items
+}
+
+fn collect_object(world: &World, object_id: usize, parent_transform: &Transform, items: &mut Vec<OverlayItem>) {
+    let Some(obj) = world.objects.get(&object_id) else { return };
+    let world_transform = parent_transform.combine(&obj.transform);
+
+    if let Some(geo) = &obj.geometry {
+        // Extend the previous batch only if nothing else was painted since.
+        match items.last_mut() {
+            Some(OverlayItem::Batch(key, mesh)) if *key == obj.texture_path => {
+                geo.generate_mesh_data(mesh, &world_transform, obj.color);
+            }
+            _ => {
+                let mut mesh = MeshData::new();
+                geo.generate_mesh_data(&mut mesh, &world_transform, obj.color);
+                items.push(OverlayItem::Batch(obj.texture_path.clone(), mesh));
+            }
+        }
+    }
+    if obj.mesh.is_some() {
+        items.push(OverlayItem::Mesh(object_id, world_transform.clone()));
+    }
+
+    for &child_id in &obj.children {
+        collect_object(world, child_id, &world_transform, items);
+    }
+}
@@ -1,76 +1,874 @@
-use std::sync::Arc;
-use wgpu::{Device, PipelineCompilationOptions, Queue, Surface};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use wgpu::{Device, Queue, Surface};
 use wgpu::util::DeviceExt;
-use crate::camera::Camera;
-use crate::mesh::{BakedMesh, Vertex};
+use crate::bind_cache::BindGroupCache;
+use crate::camera::{Camera, Viewport};
+use crate::lighting::Lighting;
+use crate::math::matrix4::Matrix4;
+use crate::mesh::{BakedMesh, MeshUsage, Topology, Vertex};
+use crate::material::Material;
+use crate::pipeline_cache::{self, DebugView, PipelineCache, PipelineKey, ShaderId, ShaderVariant};
+use crate::post::{FadePass, PostChain, PostSettings};
+use crate::uniforms::UniformAllocator;
+use crate::instances::InstanceBuffer;
+use crate::culling::{CullGroup, GpuCuller};
+use crate::occlusion::{OcclusionBox, OcclusionQueries};
+use crate::water::WaterPass;
+use crate::lines::{LinePass, Lines};
+use crate::shader::{self, ShaderPreprocessor};
+use crate::constants::pipeline::LETTERBOX_COLOR;
+use crate::readback::{DepthProbe, PixelRect, Readback};
+use crate::blit::Blitter;
+use crate::render_hook::{RenderContext, RenderHooks, RenderStage};
+use crate::resources::Resources;
+
+/// Re-export of the `raw-window-handle` crate version used by
+/// [`Pipeline::from_window_handle`] and [`Pipeline::from_raw_handles`].
+pub use wgpu::rwh;
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
 struct ModelUniform {
     model: [[f32; 4]; 4],
     color: [f32; 4],
+    /// x = 1 when scene lighting applies, 0 for unlit draws.
+    flags: [f32; 4],
+    /// rgb = [`crate::material::Emission::radiance`].
+    emissive: [f32; 4],
+    /// [`crate::material::Displacement`] at the current time, as the
+    /// shader's `wave` and `wave_vector`.
+    displacement: [[f32; 4]; 2],
+}
+
+/// Identity transform, white tint: used for every draw whose vertices are
+/// already in world space (flattened batches).
+const IDENTITY_MODEL: ModelUniform = ModelUniform {
+    model: [
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ],
+    color: [1.0, 1.0, 1.0, 1.0],
+    flags: [1.0, 0.0, 0.0, 0.0],
+    emissive: [0.0; 4],
+    displacement: [[0.0; 4]; 2],
+};
+
+/// [`IDENTITY_MODEL`] without lighting, for the skybox and overlay.
+const UNLIT_MODEL: ModelUniform = ModelUniform { flags: [0.0; 4], ..IDENTITY_MODEL };
+
+/// The `emissive` uniform of draws with `material`.
+fn emissive(material: &Material) -> [f32; 4] {
+    let [r, g, b] = material.emission.radiance();
+    [r, g, b, 0.0]
+}
+
+/// Uniform slots written before the first [`MeshDraw`]: scene camera,
+/// screen camera, [`IDENTITY_MODEL`] and [`UNLIT_MODEL`].
+const RESERVED_UNIFORM_SLOTS: usize = 4;
+
+/// Size of the view-projection matrix bound at `@group(0) @binding(0)`.
+const CAMERA_UNIFORM_SIZE: u64 = size_of::<[[f32; 4]; 4]>() as u64;
+
+/// Size of the `frame` uniform bound at `@group(0) @binding(2)`.
+const FRAME_UNIFORM_SIZE: u64 = size_of::<[f32; 4]>() as u64;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct LightUniform {
+    sky: [f32; 4],
+    ground: [f32; 4],
+}
+
+impl From<&Lighting> for LightUniform {
+    fn from(lighting: &Lighting) -> Self {
+        let ([sr, sg, sb], [gr, gg, gb]) = lighting.ambient.sky_and_ground();
+        Self { sky: [sr, sg, sb, 1.0], ground: [gr, gg, gb, 1.0] }
+    }
+}
+
+/// Number of uniform slots allocated up front (including the
+/// [`RESERVED_UNIFORM_SLOTS`]).
+const INITIAL_UNIFORM_SLOTS: usize = 64;
+
+/// A local-space mesh drawn with its own model transform and tint.
+///
+/// Produced by [`crate::scene::Scene::draw_world`] for every object that
+/// references a mesh in [`crate::mesh::MeshRegistry`].
+pub struct MeshDraw<'a> {
+    /// The shared GPU mesh.
+    pub mesh: &'a BakedMesh,
+    /// Texture bind group for `@group(1)`.
+    pub texture_bind_group: &'a wgpu::BindGroup,
+    /// Object-to-world transform.
+    pub model: Matrix4,
+    /// RGBA tint multiplied with the vertex colours.
+    pub color: [f32; 4],
+    /// Shader permutation, e.g. untextured for draws without a texture.
+    pub variant: ShaderVariant,
+    /// Culling and depth state.
+    pub material: Material,
+}
+
+/// Unlit draws painted in order on top of the finished frame, after all
+/// post-processing and without depth testing.  Built from
+/// [`crate::overlay::Overlay`] by [`crate::scene::Scene::draw_world`].
+pub struct ScreenDraws<'a> {
+    /// Projection used instead of the scene camera, usually
+    /// [`crate::overlay::OverlayCamera::view_projection`].
+    pub view_projection: Matrix4,
+    /// Draws in paint order.  Their model slots follow the scene's
+    /// [`MeshDraw`]s, so reserve room for both.
+    pub draws: Vec<MeshDraw<'a>>,
+}
+
+/// Registry meshes drawn with one instanced call per mesh, reading their
+/// transforms from an [`InstanceBuffer`].  Built by
+/// [`crate::scene::Scene::draw_world`] when
+/// [`crate::scene::Scene::instanced_rendering`] is on.
+pub struct InstancedDraws<'a> {
+    /// Instance data, uploaded with [`Pipeline::upload_instances`].
+    pub instances: &'a InstanceBuffer,
+    pub draws: Vec<InstancedDraw<'a>>,
+    /// Frustum-cull the instances in a compute pass and draw the survivors
+    /// indirectly; see [`crate::culling`].  Needs
+    /// [`Pipeline::supports_gpu_culling`].  [`RenderStats`] then counts
+    /// instances and triangles before culling.
+    pub gpu_culling: bool,
+    /// Cull against this view-projection instead of the camera's, e.g. a
+    /// frozen [`crate::frustum::CullingDebug`] camera.
+    pub cull_view_projection: Option<Matrix4>,
+}
+
+/// Everything [`Pipeline::render_scene`] draws in one frame.
+///
+/// Start from [`Self::new`] and set the layers the frame uses; the rest stay
+/// empty.  New layers are added here as fields, so callers written against
+/// earlier versions keep compiling.
+pub struct FrameInputs<'a> {
+    pub camera: &'a Camera,
+    /// Ambient term applied to world batches and mesh draws.
+    pub lighting: &'a Lighting,
+    /// `(mesh, texture_bind_group, material)` for scene objects.  Each may
+    /// carry a different texture; batches bound to
+    /// [`Pipeline::default_texture_bind_group`] use the untextured variant.
+    pub world_batches: &'a [(&'a BakedMesh, &'a wgpu::BindGroup, Material)],
+    /// Registry meshes drawn with their own model transform.  Call
+    /// [`Pipeline::reserve_mesh_draws`] beforehand, counting emissive and
+    /// displaced world batches too; draws beyond the reserved capacity are
+    /// skipped, and such batches drawn without emission.
+    pub mesh_draws: &'a [MeshDraw<'a>],
+    /// Registry meshes drawn from an [`InstanceBuffer`]; needs
+    /// [`Pipeline::supports_instancing`].
+    pub instanced: Option<&'a InstancedDraws<'a>>,
+    /// Rendered first with the overlay pipeline (depth=Always, no depth-write).
+    pub skybox: Option<&'a BakedMesh>,
+    /// Rendered last with the overlay pipeline (gizmos, always on top).
+    pub overlay: Option<&'a BakedMesh>,
+    /// 2D draws painted over the post-processed frame in a second pass.
+    pub screen: Option<&'a ScreenDraws<'a>>,
+    /// Run at each [`RenderStage`] with a [`RenderContext`] lending out the
+    /// given resources; not run while capturing.
+    pub hooks: Option<(&'a mut RenderHooks, &'a Resources)>,
+}
+
+impl<'a> FrameInputs<'a> {
+    /// A frame seen from `camera` under `lighting`, with every layer empty.
+    pub fn new(camera: &'a Camera, lighting: &'a Lighting) -> Self {
+        Self {
+            camera,
+            lighting,
+            world_batches: &[],
+            mesh_draws: &[],
+            instanced: None,
+            skybox: None,
+            overlay: None,
+            screen: None,
+            hooks: None,
+        }
+    }
+}
+
+/// Consecutive instances of one mesh; see [`InstancedDraws`].
+pub struct InstancedDraw<'a> {
+    pub mesh: &'a BakedMesh,
+    /// Texture bind group for `@group(1)`.
+    pub texture_bind_group: &'a wgpu::BindGroup,
+    /// Shader permutation shared by the instances.
+    pub variant: ShaderVariant,
+    /// Culling, depth, emission and displacement shared by the instances.
+    pub material: Material,
+    /// Instance indices returned by [`InstanceBuffer::push`].
+    pub instances: std::ops::Range<u32>,
+}
+
+/// Storage bindings of the instanced shader (group 2): instance data and
+/// draw order.
+const INSTANCE_LAYOUT_ENTRIES: [wgpu::BindGroupLayoutEntry; 2] = [
+    storage_entry(0),
+    storage_entry(1),
+];
+
+const fn storage_entry(binding: u32) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::VERTEX,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    }
 }
 
+/// Colour format of the offscreen target used by [`Pipeline::headless`].
+pub const HEADLESS_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+/// Colour format of the cube textures made by
+/// [`crate::scene::Scene::capture_cubemap`].
+pub const CUBEMAP_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+/// Views [`Pipeline::render_scene`] draws the 3D layers into instead of the
+/// frame, e.g. the faces of [`crate::scene::Scene::capture_cubemap`].
+/// Post-processing, letterboxing and the screen overlay are skipped.
+pub(crate) struct CaptureTarget {
+    pub(crate) color: wgpu::TextureView,
+    pub(crate) depth: wgpu::TextureView,
+    /// Replaces the camera's view-projection, e.g. for the off-axis,
+    /// obliquely clipped views of [`crate::mirror`].
+    pub(crate) view_projection: Option<Matrix4>,
+}
+
+/// Adapter and backend selection used when creating a [`Pipeline`].
+///
+/// Every constructor that does not take an explicit config uses
+/// [`PipelineConfig::from_env`], so the adapter can be steered without code
+/// changes:
+///
+/// | Variable                          | Effect                                                     |
+/// |-----------------------------------|------------------------------------------------------------|
+/// | `VERTRA_BACKEND`                  | Comma list of `vulkan`, `metal`, `dx12`, `gl` (e.g. `gl`)   |
+/// | `VERTRA_FORCE_FALLBACK_ADAPTER`   | `1` / `true` selects a software rasterizer                 |
+/// | `VERTRA_VSYNC`                    | `0` / `false` presents without waiting for vertical sync   |
+///
+/// # Software rendering (CI, VMs)
+/// With `force_fallback_adapter` set, wgpu picks a CPU implementation —
+/// lavapipe on Vulkan or llvmpipe through Mesa's GL driver.  Combined with
+/// [`Pipeline::headless`] this renders real frames on machines without a GPU
+/// or display:
+///
+/// ```no_run
+/// use vertra::pipeline::{Pipeline, PipelineConfig};
+/// let config = PipelineConfig::default().with_force_fallback_adapter(true);
+/// let pipeline = pollster::block_on(Pipeline::headless(&config, 256, 256))
+///     .expect("no software adapter (install mesa-vulkan-drivers or libgl1-mesa-dri)");
+/// ```
+///
+/// On wasm32 `backends` is ignored; see the `webgpu` crate feature instead.
+#[derive(Debug, Clone, PartialEq)]
 pub struct PipelineConfig {
     pub initial_vertex_buffer_size: usize,
+    /// Backends wgpu may choose from.  Defaults to [`wgpu::Backends::all`].
+    pub backends: wgpu::Backends,
+    /// Only consider software (fallback) adapters such as lavapipe / llvmpipe.
+    pub force_fallback_adapter: bool,
+    /// Preferred adapter class when several are available.
+    pub power_preference: wgpu::PowerPreference,
+    /// Wait for vertical sync when presenting.  When off, frames are
+    /// presented immediately if the surface supports it (tearing possible).
+    pub vsync: bool,
+    /// Exact present mode to request, overriding [`Self::vsync`].  Modes the
+    /// surface lacks fall back along the chain documented at
+    /// [`resolve_present_mode`].
+    pub present_mode: Option<wgpu::PresentMode>,
+}
+
+impl Default for PipelineConfig {
+    fn default() -> Self {
+        Self {
+            initial_vertex_buffer_size: crate::constants::pipeline::INITIAL_VERTEX_LIMIT as usize,
+            backends: wgpu::Backends::all(),
+            force_fallback_adapter: false,
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            vsync: true,
+            present_mode: None,
+        }
+    }
 }
 
+impl PipelineConfig {
+    /// The default configuration with environment overrides applied.
+    pub fn from_env() -> Self {
+        Self::default().with_env_overrides()
+    }
+
+    /// Apply `VERTRA_BACKEND` / `VERTRA_FORCE_FALLBACK_ADAPTER` /
+    /// `VERTRA_VSYNC` on top of this configuration.  Unset or empty
+    /// variables leave fields untouched.
+    pub fn with_env_overrides(mut self) -> Self {
+        use crate::constants::pipeline::{BACKEND_ENV, FORCE_FALLBACK_ADAPTER_ENV, VSYNC_ENV};
+        if let Some(backends) = std::env::var(BACKEND_ENV).ok().and_then(|v| parse_backends(&v)) {
+            self.backends = backends;
+        }
+        if let Some(force) = std::env::var(FORCE_FALLBACK_ADAPTER_ENV).ok().and_then(|v| parse_flag(&v)) {
+            self.force_fallback_adapter = force;
+        }
+        if let Some(vsync) = std::env::var(VSYNC_ENV).ok().and_then(|v| parse_flag(&v)) {
+            self.vsync = vsync;
+        }
+        self
+    }
+
+    /// Restrict adapter selection to `backends`.
+    pub fn with_backends(mut self, backends: wgpu::Backends) -> Self {
+        self.backends = backends;
+        self
+    }
+
+    /// Only consider software (fallback) adapters.
+    pub fn with_force_fallback_adapter(mut self, force: bool) -> Self {
+        self.force_fallback_adapter = force;
+        self
+    }
+
+    /// Set the preferred adapter class.
+    pub fn with_power_preference(mut self, preference: wgpu::PowerPreference) -> Self {
+        self.power_preference = preference;
+        self
+    }
+
+    /// Wait for vertical sync (`true`, the default) or present immediately.
+    pub fn with_vsync(mut self, vsync: bool) -> Self {
+        self.vsync = vsync;
+        self
+    }
+
+    /// Request an exact present mode instead of deriving one from
+    /// [`Self::vsync`].
+    pub fn with_present_mode(mut self, mode: wgpu::PresentMode) -> Self {
+        self.present_mode = Some(mode);
+        self
+    }
+
+    /// The present mode to request: [`Self::present_mode`] if set, else the
+    /// automatic mode matching [`Self::vsync`].
+    pub fn requested_present_mode(&self) -> wgpu::PresentMode {
+        self.present_mode.unwrap_or(if self.vsync {
+            wgpu::PresentMode::AutoVsync
+        } else {
+            wgpu::PresentMode::AutoNoVsync
+        })
+    }
+}
+
+/// Pick the present mode to configure for `requested` given the modes a
+/// surface `supported`.
+///
+/// A supported request is used as is.  Otherwise the first supported mode of
+/// its fallback chain is, ending in `Fifo`, which every surface supports:
+///
+/// | Requested     | Fallback chain                 |
+/// |---------------|--------------------------------|
+/// | `Immediate`   | `Mailbox` → `Fifo`             |
+/// | `Mailbox`     | `Immediate` → `Fifo`           |
+/// | `FifoRelaxed` | `Fifo`                         |
+/// | `AutoNoVsync` | `Immediate` → `Mailbox` → `Fifo` |
+/// | `AutoVsync`   | `FifoRelaxed` → `Fifo`         |
+///
+/// The automatic modes always resolve to a concrete mode, so the
+/// configured [`wgpu::SurfaceConfiguration::present_mode`] says what is
+/// actually used.  An empty `supported` list (no surface) returns
+/// `requested` unchanged.
+pub fn resolve_present_mode(requested: wgpu::PresentMode, supported: &[wgpu::PresentMode]) -> wgpu::PresentMode {
+    use wgpu::PresentMode::*;
+    if supported.is_empty() || supported.contains(&requested) {
+        return requested;
+    }
+    let chain: &[wgpu::PresentMode] = match requested {
+        Immediate => &[Mailbox],
+        Mailbox => &[Immediate],
+        FifoRelaxed | Fifo => &[],
+        AutoNoVsync => &[Immediate, Mailbox],
+        AutoVsync => &[FifoRelaxed],
+    };
+    chain.iter().copied().find(|mode| supported.contains(mode)).unwrap_or(Fifo)
+}
+
+/// Resolve `requested` against what `surface` supports on `adapter`, with
+/// the event to report if that is a fallback.
+fn surface_present_mode(
+    surface: &Surface<'_>,
+    adapter: &wgpu::Adapter,
+    requested: wgpu::PresentMode,
+) -> (wgpu::PresentMode, Option<SurfaceEvent>) {
+    let used = resolve_present_mode(requested, &surface.get_capabilities(adapter).present_modes);
+    let fallback = match requested {
+        wgpu::PresentMode::AutoVsync => false,
+        wgpu::PresentMode::AutoNoVsync => used == wgpu::PresentMode::Fifo,
+        _ => used != requested,
+    };
+    (used, fallback.then_some(SurfaceEvent::PresentModeFallback { requested, used }))
+}
+
+/// Why acquiring a surface texture did not yield a regular frame; mirrors
+/// the non-success cases of [`wgpu::CurrentSurfaceTexture`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SurfaceStatus {
+    /// The frame was acquired but no longer matches the surface.
+    Suboptimal,
+    /// Acquiring the frame timed out.
+    Timeout,
+    /// The window is minimised or hidden.
+    Occluded,
+    /// The surface configuration is out of date, usually after a resize.
+    Outdated,
+    /// The surface was lost and must be re-created.
+    Lost,
+    /// A validation error was raised while acquiring the frame.
+    Validation,
+}
+
+/// Something happened to the presentation surface that the application may
+/// want to surface to the user (e.g. in a settings menu or a log).  Frames
+/// that could not be rendered are reported as a [`RenderError`] instead.
+///
+/// Queued by the [`Pipeline`] and collected with
+/// [`Pipeline::drain_surface_events`]; a [`crate::window::Window`] delivers
+/// them to [`crate::window::Window::on_surface_event`] after every frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SurfaceEvent {
+    /// The requested present mode is unsupported; `used` was configured
+    /// instead (see [`resolve_present_mode`]).  `AutoVsync` never falls
+    /// back, and `AutoNoVsync` only when it ends at `Fifo`.
+    PresentModeFallback { requested: wgpu::PresentMode, used: wgpu::PresentMode },
+    /// The surface was reconfigured to recover from `reason`.
+    Reconfigured { reason: SurfaceStatus },
+    /// The adapter cannot present to the surface re-created by
+    /// [`Pipeline::resume`]; rendering stays off until the next resume.
+    Unsupported,
+    /// [`Pipeline::resume`] could not re-create the surface for the window;
+    /// rendering stays off until the next resume.
+    CreateFailed,
+}
+
+/// Parse a comma-separated backend list (`"vulkan,gl"`).  `None` when empty
+/// or when no name is recognised.
+pub(crate) fn parse_backends(value: &str) -> Option<wgpu::Backends> {
+    if value.trim().is_empty() {
+        return None;
+    }
+    let backends = wgpu::Backends::from_comma_list(value);
+    (!backends.is_empty()).then_some(backends)
+}
+
+/// Parse a boolean environment flag (`1/0`, `true/false`, `yes/no`, `on/off`).
+pub(crate) fn parse_flag(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// Why [`Pipeline::render_scene`] (and so
+/// [`crate::scene::Scene::draw_world`]) dropped a frame.
+///
+/// Minimised or occluded windows and suspended pipelines are not errors:
+/// they render nothing and return empty [`RenderStats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderError {
+    /// The surface is lost, or still outdated or invalid after being
+    /// reconfigured.  Re-create it with [`Pipeline::suspend`] followed by
+    /// [`Pipeline::resume`].
+    SurfaceLost,
+    /// The device ran out of memory while recording or submitting the frame.
+    OutOfMemory,
+    /// Acquiring the next surface texture timed out.  Try again later.
+    Timeout,
+}
+
+impl std::fmt::Display for RenderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RenderError::SurfaceLost => write!(f, "Surface lost"),
+            RenderError::OutOfMemory => write!(f, "Out of GPU memory"),
+            RenderError::Timeout => write!(f, "Timed out acquiring the next frame"),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+/// Errors that can occur while creating a [`Pipeline`].
+#[derive(Debug)]
+pub enum PipelineError {
+    /// The window surface could not be created.
+    CreateSurface(wgpu::CreateSurfaceError),
+    /// No adapter matched the configured backends / fallback setting.
+    NoAdapter(wgpu::RequestAdapterError),
+    /// The adapter refused to create a device.
+    RequestDevice(wgpu::RequestDeviceError),
+    /// The selected adapter cannot present to the surface.
+    SurfaceUnsupported,
+}
+
+impl std::fmt::Display for PipelineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PipelineError::CreateSurface(e) => write!(f, "Failed to create surface: {e}"),
+            PipelineError::NoAdapter(e) => write!(f, "Failed to find an appropriate adapter: {e}"),
+            PipelineError::RequestDevice(e) => write!(f, "Failed to create device: {e}"),
+            PipelineError::SurfaceUnsupported => write!(f, "Surface not supported by adapter"),
+        }
+    }
+}
+
+impl std::error::Error for PipelineError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PipelineError::CreateSurface(e) => Some(e),
+            PipelineError::NoAdapter(e) => Some(e),
+            PipelineError::RequestDevice(e) => Some(e),
+            PipelineError::SurfaceUnsupported => None,
+        }
+    }
+}
+
+/// Counters describing the GPU work of one rendered frame.
+///
+/// Returned by [`Pipeline::render_scene`] (and so by
+/// [`crate::scene::Scene::draw_world`]) and handed to callbacks as
+/// [`crate::window::FrameContext::render_stats`].  Upload counters cover
+/// everything since the previous frame, so meshes and textures created
+/// between frames are attributed to the next one.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub struct RenderStats {
+    /// Draw calls issued.
     pub draw_calls: u32,
+    /// Instances drawn, summed over all draw calls.
+    pub instance_count: u32,
+    /// Triangles rendered, summed over all instances.
     pub triangle_count: u32,
+    /// Bytes written to GPU buffers and textures.
+    pub bytes_uploaded: u64,
+    /// GPU buffers created, including per-frame dynamic batches and growth
+    /// of persistent buffers.
+    pub buffer_reallocations: u32,
+    /// Bind groups created rather than reused from the bind group cache.
+    /// Zero in steady state.
+    pub bind_groups_created: u32,
+    /// GPU memory held once the frame was drawn.  Filled in by
+    /// [`crate::scene::Scene::draw_world`]; [`Pipeline::render_scene`] alone
+    /// leaves it zero.
+    pub memory: GpuMemory,
+}
+
+impl RenderStats {
+    /// Count one draw of `index_count` indices with `instances` instances.
+    pub(crate) fn record_draw(&mut self, index_count: u32, instances: u32) {
+        self.draw_calls += 1;
+        self.instance_count += instances;
+        self.triangle_count += index_count / 3 * instances;
+    }
+}
+
+/// Bytes of GPU memory held by persistent resources, by category.
+///
+/// Sizes are those requested from wgpu; drivers may pad them.  Geometry
+/// baked for a single frame is not counted, so a total that keeps growing
+/// while nothing new is loaded points at a leak, e.g. registry meshes that
+/// are never collected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GpuMemory {
+    /// Vertex buffers of registry meshes and static batches.
+    pub vertex_bytes: u64,
+    /// Index buffers of registry meshes and static batches.
+    pub index_bytes: u64,
+    /// Uniform buffers: per-frame camera and model slots, lighting and post
+    /// settings.
+    pub uniform_bytes: u64,
+    /// Storage buffers of instanced rendering.
+    pub storage_bytes: u64,
+    /// Textures registered with the scene, including their mip levels.
+    pub texture_bytes: u64,
+    /// Depth, offscreen, post-processing and readback targets, which scale
+    /// with the frame size.
+    pub render_target_bytes: u64,
+}
+
+impl GpuMemory {
+    /// Bytes across all categories.
+    pub fn total(&self) -> u64 {
+        self.vertex_bytes
+            + self.index_bytes
+            + self.uniform_bytes
+            + self.storage_bytes
+            + self.texture_bytes
+            + self.render_target_bytes
+    }
+
+    /// Count the vertex and index buffers of `mesh`.
+    pub fn add_mesh(&mut self, mesh: &BakedMesh) {
+        self.vertex_bytes += mesh.vertex_buffer.size();
+        self.index_bytes += mesh.index_buffer.size();
+    }
+}
+
+impl std::ops::Add for GpuMemory {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            vertex_bytes: self.vertex_bytes + other.vertex_bytes,
+            index_bytes: self.index_bytes + other.index_bytes,
+            uniform_bytes: self.uniform_bytes + other.uniform_bytes,
+            storage_bytes: self.storage_bytes + other.storage_bytes,
+            texture_bytes: self.texture_bytes + other.texture_bytes,
+            render_target_bytes: self.render_target_bytes + other.render_target_bytes,
+        }
+    }
+}
+
+/// Bytes of every mip level, layer and sample of `texture`.  Formats without
+/// a single block size (combined depth-stencil) count 4 bytes per texel.
+pub fn texture_bytes(texture: &wgpu::Texture) -> u64 {
+    let format = texture.format();
+    let block_bytes = u64::from(format.block_copy_size(None).unwrap_or(4));
+    let (block_width, block_height) = format.block_dimensions();
+    let size = texture.size();
+    let layers = u64::from(size.depth_or_array_layers);
+    (0..texture.mip_level_count())
+        .map(|level| {
+            let width = (size.width >> level).max(1).div_ceil(block_width);
+            let height = (size.height >> level).max(1).div_ceil(block_height);
+            u64::from(width) * u64::from(height) * block_bytes
+        })
+        .sum::<u64>()
+        * layers
+        * u64::from(texture.sample_count())
 }
 
 pub struct Pipeline {
-    pub render_pipeline: wgpu::RenderPipeline,
-    /// Depth = Always, no culling, no depth-write.
-    /// Used for both the skybox (layer 1) and gizmo overlays (layer 3).
-    overlay_pipeline: wgpu::RenderPipeline,
+    /// Render pipelines by state; see [`Self::render_pipeline`].
+    pipelines: Mutex<PipelineCache>,
+    /// Pipelines of the instanced shader, created on first use; see
+    /// [`Self::instanced_pipeline`].
+    instanced_pipelines: Mutex<Option<PipelineCache>>,
+    /// Compute culling for instanced draws, created on first use.
+    culler: Mutex<Option<GpuCuller>>,
+    /// Bounding-box occlusion queries, created on first use; see
+    /// [`crate::occlusion`].
+    occlusion: Mutex<Option<OcclusionQueries>>,
+    /// Reads single depth texels for [`Self::read_depth`], created on first
+    /// use.
+    depth_probe: Mutex<Option<DepthProbe>>,
+    /// Texture blits and mip generation, created on first use.
+    blitter: Mutex<Option<Blitter>>,
+    /// Shared by every render pipeline; kept to validate shader reloads.
+    pipeline_layout: wgpu::PipelineLayout,
+    /// HDR target, tonemapping and the scene-transition fade.
+    post: PostChain,
+    /// Target aspect ratio of the scene viewport; see
+    /// [`Self::set_fixed_aspect`].
+    fixed_aspect: Option<f32>,
+    /// Paints the bars around a fixed-aspect viewport, created on first
+    /// use.
+    letterbox_bars: Option<FadePass>,
+    /// Water surface drawn after the opaque layers; see
+    /// [`crate::scene::Scene::set_water`].
+    pub(crate) water: Option<WaterPass>,
+    /// Thick lines drawn with the scene, created on first use; see
+    /// [`Self::set_lines`].
+    lines: Option<LinePass>,
+    /// Visualisation replacing scene shading; see [`Self::set_debug_view`].
+    debug_view: DebugView,
+    /// Present mode asked for, re-resolved whenever the surface is
+    /// re-created; see [`Self::set_present_mode`].
+    requested_present_mode: wgpu::PresentMode,
+    /// Undrained events; see [`Self::drain_surface_events`].
+    surface_events: Mutex<Vec<SurfaceEvent>>,
+    /// Suboptimal frames presented in a row.
+    suboptimal_frames: AtomicU32,
     pub shader: wgpu::ShaderModule,
     pub device: Device,
     pub queue: Queue,
-    pub surface: Surface<'static>,
+    /// The presentation surface, or `None` while the application is
+    /// suspended (mobile platforms destroy the native window on suspend).
+    pub surface: Option<Surface<'static>>,
     pub surface_config: wgpu::SurfaceConfiguration,
-    camera_buffer: wgpu::Buffer,
-    camera_bind_group: wgpu::BindGroup,
-    depth_view: wgpu::TextureView,
+    /// Render target used instead of a surface by [`Pipeline::headless`].
+    offscreen_target: Option<wgpu::Texture>,
+    /// Copy of the last presented frame, kept while
+    /// [`Self::set_frame_readback`] is on.
+    retained_frame: Option<wgpu::Texture>,
+    /// Replaces the frame while set; see [`Self::set_capture_target`].
+    capture_target: Option<CaptureTarget>,
+    /// Kept alive so the surface can be re-created on resume.
+    instance: wgpu::Instance,
+    adapter: wgpu::Adapter,
+    light_buffer: wgpu::Buffer,
+    /// `frame` uniform (`@group(0) @binding(2)`): [`Self::time`].
+    frame_buffer: wgpu::Buffer,
+    /// Seconds passed to the shaders; see [`Self::set_time`].
+    time: f64,
+    /// Bind group layout for `@group(0)` (camera at a dynamic offset,
+    /// lighting, frame time).
+    pub(crate) camera_bind_group_layout: wgpu::BindGroupLayout,
+    /// Camera slot of `uniforms` plus `light_buffer` and `frame_buffer`; the
+    /// scene and [`ScreenDraws`] projections differ only in offset.
+    pub(crate) camera_bind_group: wgpu::BindGroup,
+    pub(crate) depth_view: wgpu::TextureView,
     /// Bind group layout for `@group(1)` (texture + sampler).
     pub texture_bind_group_layout: wgpu::BindGroupLayout,
     /// Default 1×1 white texture bind group used for untextured objects.
     pub default_texture_bind_group: wgpu::BindGroup,
     /// Shared linear sampler reused when creating per-object texture bind groups.
     pub default_sampler: wgpu::Sampler,
+    /// Bind group layout for `@group(2)` (per-draw model uniform, dynamic offset).
+    model_bind_group_layout: wgpu::BindGroupLayout,
+    /// Model slot of `uniforms`.
+    model_bind_group: wgpu::BindGroup,
+    /// Per-frame camera and model uniforms, bound at dynamic offsets.
+    uniforms: UniformAllocator,
+    /// Reused bind groups and layouts; see [`Self::bind_group`].
+    bind_cache: Mutex<BindGroupCache>,
+    /// Bytes uploaded since the last rendered frame (see [`RenderStats`]).
+    bytes_uploaded: AtomicU64,
+    /// Buffers created since the last rendered frame (see [`RenderStats`]).
+    buffers_allocated: AtomicU32,
+    /// Time base for the frame delta fed to auto exposure.
+    clock: web_time::Instant,
+    /// Microseconds since `clock` at the last rendered frame.
+    last_frame_micros: AtomicU64,
 }
 
 // Shared vertex buffer layout: position(3) + color(3) + uv(2)
-const VERTEX_ATTRS: [wgpu::VertexAttribute; 3] = [
+pub(crate) const VERTEX_ATTRS: [wgpu::VertexAttribute; 3] = [
     wgpu::VertexAttribute { offset: 0,  shader_location: 0, format: wgpu::VertexFormat::Float32x3 },
     wgpu::VertexAttribute { offset: 12, shader_location: 1, format: wgpu::VertexFormat::Float32x3 },
     wgpu::VertexAttribute { offset: 24, shader_location: 2, format: wgpu::VertexFormat::Float32x2 },
 ];
 
 impl Pipeline {
-    pub async fn initialize(window: Arc<winit::window::Window>) -> Self {
-        // On WASM inside any bundled environment the WebGPU
-        // backend's instanceof GPUCanvasContext check fails due to a JS
-        // realm mismatch, causing a panic. Force WebGL2 on wasm32 to avoid
-        // this until wgpu ships a proper fix.
-        let mut desc = wgpu::InstanceDescriptor::new_without_display_handle();
-        desc.backends = wgpu::Backends::GL;
+    /// Create a pipeline that renders into a winit `window`, configured by
+    /// [`PipelineConfig::from_env`].
+    pub async fn initialize(window: Arc<winit::window::Window>) -> Result<Self, PipelineError> {
+        Self::initialize_with_config(window, &PipelineConfig::from_env()).await
+    }
+
+    /// Create a pipeline that renders into a winit `window` using `config`.
+    pub async fn initialize_with_config(
+        window: Arc<winit::window::Window>,
+        config: &PipelineConfig,
+    ) -> Result<Self, PipelineError> {
+        let size = window.inner_size();
+        Self::from_window_handle(config, window, size.width, size.height).await
+    }
+
+    /// Create a pipeline that renders into any window the caller owns.
+    ///
+    /// `target` is anything wgpu can build a surface from — typically a type
+    /// implementing [`rwh::HasWindowHandle`] + [`rwh::HasDisplayHandle`]
+    /// (winit, SDL, Tao/Tauri windows, …).  Use this to embed vertra inside a
+    /// host application that runs its own event loop; build a
+    /// [`crate::scene::Scene`] with [`crate::scene::Scene::new`] and call
+    /// [`crate::scene::Scene::draw_world`] / [`crate::scene::Scene::resize`]
+    /// from the host's callbacks.
+    ///
+    /// `width` / `height` are the initial drawable size in physical pixels;
+    /// zero falls back to the engine defaults.  Fails if no surface,
+    /// adapter, or device can be created.
+    pub async fn from_window_handle(
+        config: &PipelineConfig,
+        target: impl Into<wgpu::SurfaceTarget<'static>>,
+        width: u32,
+        height: u32,
+    ) -> Result<Self, PipelineError> {
+        let instance = Self::create_instance(config).await;
+        let surface = instance.create_surface(target).map_err(PipelineError::CreateSurface)?;
+        Self::build(instance, Some(surface), config, width, height).await
+    }
+
+    /// Create a pipeline from raw platform handles, e.g. a native widget
+    /// exported by Qt (`QWidget::winId`) or another toolkit.
+    ///
+    /// # Safety
+    /// `raw_window_handle` and `raw_display_handle` must be valid handles to
+    /// create a surface upon, and must remain valid until the returned
+    /// pipeline (and therefore its surface) is dropped.
+    pub async unsafe fn from_raw_handles(
+        config: &PipelineConfig,
+        raw_display_handle: Option<rwh::RawDisplayHandle>,
+        raw_window_handle: rwh::RawWindowHandle,
+        width: u32,
+        height: u32,
+    ) -> Result<Self, PipelineError> {
+        let instance = Self::create_instance(config).await;
+        // SAFETY: upheld by the caller (see `# Safety`).
+        let surface = unsafe {
+            instance.create_surface_unsafe(wgpu::SurfaceTargetUnsafe::RawHandle {
+                raw_display_handle,
+                raw_window_handle,
+            })
+        }.map_err(PipelineError::CreateSurface)?;
+        Self::build(instance, Some(surface), config, width, height).await
+    }
+
+    /// Create a pipeline without any window, rendering into an offscreen
+    /// `width` × `height` texture in [`HEADLESS_FORMAT`].
+    ///
+    /// Intended for tests, CI, and servers: together with
+    /// [`PipelineConfig::force_fallback_adapter`] it runs on lavapipe /
+    /// llvmpipe.  Frames rendered with [`Self::render_scene`] land in
+    /// [`Self::offscreen_texture`].
+    pub async fn headless(config: &PipelineConfig, width: u32, height: u32) -> Result<Self, PipelineError> {
+        let instance = Self::create_instance(config).await;
+        Self::build(instance, None, config, width, height).await
+    }
 
+    #[cfg_attr(target_arch = "wasm32", allow(unused_variables))]
+    async fn create_instance(config: &PipelineConfig) -> wgpu::Instance {
+        // On WASM inside some bundled environments the WebGPU backend's
+        // instanceof GPUCanvasContext check fails due to a JS realm mismatch,
+        // causing a panic.  WebGPU is therefore opt-in through the `webgpu`
+        // feature; WebGL2 is used otherwise, and as the fallback when the
+        // browser does not expose WebGPU.
         #[cfg(target_arch = "wasm32")]
-        let instance = wgpu::Instance::new(desc);
+        {
+            let mut desc = wgpu::InstanceDescriptor::new_without_display_handle();
+            desc.backends = if cfg!(feature = "webgpu") {
+                wgpu::Backends::BROWSER_WEBGPU | wgpu::Backends::GL
+            } else {
+                wgpu::Backends::GL
+            };
+            wgpu::util::new_instance_with_webgpu_detection(desc).await
+        }
 
         #[cfg(not(target_arch = "wasm32"))]
-        let instance = wgpu::Instance::default();
-        let surface = instance.create_surface(Arc::clone(&window)).unwrap();
+        {
+            let mut desc = wgpu::InstanceDescriptor::new_without_display_handle();
+            desc.backends = config.backends;
+            wgpu::Instance::new(desc)
+        }
+    }
+
+    async fn build(
+        instance: wgpu::Instance,
+        surface: Option<Surface<'static>>,
+        config: &PipelineConfig,
+        width: u32,
+        height: u32,
+    ) -> Result<Self, PipelineError> {
         let adapter = instance.request_adapter(
             &wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::HighPerformance,
-                compatible_surface: Some(&surface),
-                force_fallback_adapter: false,
+                power_preference: config.power_preference,
+                compatible_surface: surface.as_ref(),
+                force_fallback_adapter: config.force_fallback_adapter,
             },
-        ).await.expect("Failed to find an appropriate adapter");
+        ).await.map_err(PipelineError::NoAdapter)?;
 
         // Get the limits actually supported by this specific hardware
         let adapter_limits = adapter.limits();
@@ -81,75 +879,109 @@ impl Pipeline {
                 required_limits: wgpu::Limits {
                     ..adapter_limits
                 },
-                required_features: wgpu::Features::empty(),
+                // Wireframe permutations fall back to fill without line
+                // mode; GPU culling is unavailable without indirect first
+                // instances.
+                required_features: adapter.features()
+                    & (wgpu::Features::POLYGON_MODE_LINE | wgpu::Features::INDIRECT_FIRST_INSTANCE),
                 memory_hints: Default::default(),
                 trace: wgpu::Trace::Off,
                 experimental_features: wgpu::ExperimentalFeatures::default(),
             },
-        ).await.expect("Failed to create device");
+        ).await.map_err(PipelineError::RequestDevice)?;
 
-        let size = window.inner_size();
-        let width = if size.width > 0 { size.width } else { crate::constants::window::DEFAULT_WIDTH };
-        let height = if size.height > 0 { size.height } else { crate::constants::window::DEFAULT_HEIGHT };
-        let surface_config = surface
-            .get_default_config(&adapter, width, height)
-            .expect("Surface not supported by adapter");
-
-        surface.configure(&device, &surface_config);
-        let shader = device.create_shader_module(wgpu::include_wgsl!("shader.wgsl"));
-
-        let camera_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Camera Uniform Buffer"),
-            size: size_of::<[[f32; 4]; 4]>() as wgpu::BufferAddress,
+        let width = if width > 0 { width } else { crate::constants::window::DEFAULT_WIDTH };
+        let height = if height > 0 { height } else { crate::constants::window::DEFAULT_HEIGHT };
+        let requested_present_mode = config.requested_present_mode();
+        let mut surface_events = Vec::new();
+        let surface_config = match &surface {
+            Some(surface) => {
+                let mut surface_config = surface
+                    .get_default_config(&adapter, width, height)
+                    .ok_or(PipelineError::SurfaceUnsupported)?;
+                let (present_mode, fallback) = surface_present_mode(surface, &adapter, requested_present_mode);
+                surface_config.present_mode = present_mode;
+                surface_events.extend(fallback);
+                surface.configure(&device, &surface_config);
+                surface_config
+            }
+            // Headless: no surface to configure, but the config still records
+            // the target size and format every pipeline is built against.
+            None => wgpu::SurfaceConfiguration {
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                format: HEADLESS_FORMAT,
+                width,
+                height,
+                present_mode: wgpu::PresentMode::Fifo,
+                desired_maximum_frame_latency: 2,
+                alpha_mode: wgpu::CompositeAlphaMode::Auto,
+                view_formats: Vec::new(),
+            },
+        };
+        let offscreen_target = surface.is_none()
+            .then(|| create_offscreen_target(&device, width, height));
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("shader.wgsl"),
+            source: wgpu::ShaderSource::Wgsl(
+                ShaderPreprocessor::new()
+                    .process("shader.wgsl", shader::SCENE_SHADER)
+                    .expect("built-in shader preprocesses")
+                    .into(),
+            ),
+        });
+
+        let light_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Light Uniform Buffer"),
+            size: size_of::<LightUniform>() as wgpu::BufferAddress,
             usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
             mapped_at_creation: false,
         });
-
-        let camera_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-            entries: &[wgpu::BindGroupLayoutEntry {
-                binding: 0,
-                visibility: wgpu::ShaderStages::VERTEX,
-                ty: wgpu::BindingType::Buffer {
-                    ty: wgpu::BufferBindingType::Uniform,
-                    has_dynamic_offset: false,
-                    min_binding_size: None,
-                },
-                count: None,
-            }],
-            label: Some("camera_bind_group_layout"),
+        let frame_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Frame Uniform Buffer"),
+            size: FRAME_UNIFORM_SIZE,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
         });
 
-        let camera_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &camera_bind_group_layout,
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: camera_buffer.as_entire_binding(),
-            }],
-            label: Some("camera_bind_group"),
-        });
+        // Camera (binding 0, a slot of the frame uniforms), scene lighting
+        // (binding 1) and the frame time (binding 2) share group 0.
+        let uniform_entry = |binding, visibility, min_binding_size: Option<wgpu::BufferSize>| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: min_binding_size.is_some(),
+                min_binding_size,
+            },
+            count: None,
+        };
+        let mut bind_cache = BindGroupCache::new();
+        let camera_bind_group_layout = bind_cache.layout(&device, Some("camera_bind_group_layout"), &[
+            // The depth debug view reads the camera per fragment.
+            uniform_entry(0, wgpu::ShaderStages::VERTEX_FRAGMENT, wgpu::BufferSize::new(CAMERA_UNIFORM_SIZE)),
+            uniform_entry(1, wgpu::ShaderStages::FRAGMENT, None),
+            uniform_entry(2, wgpu::ShaderStages::VERTEX_FRAGMENT, None),
+        ]);
 
         // Texture bind group layout (group 1)
-        let texture_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-            label: Some("texture_bind_group_layout"),
-            entries: &[
-                wgpu::BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Texture {
-                        multisampled: false,
-                        view_dimension: wgpu::TextureViewDimension::D2,
-                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
-                    },
-                    count: None,
-                },
-                wgpu::BindGroupLayoutEntry {
-                    binding: 1,
-                    visibility: wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
-                    count: None,
+        let texture_bind_group_layout = bind_cache.layout(&device, Some("texture_bind_group_layout"), &[
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    multisampled: false,
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
                 },
-            ],
-        });
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            },
+        ]);
 
         // Shared sampler
         let default_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
@@ -180,18 +1012,44 @@ impl Pipeline {
             &[255u8, 255, 255, 255],
         );
         let white_view = white_texture.create_view(&wgpu::TextureViewDescriptor::default());
-        let default_texture_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("default_texture_bind_group"),
-            layout: &texture_bind_group_layout,
-            entries: &[
+        let default_texture_bind_group = bind_cache.bind_group(
+            &device,
+            Some("default_texture_bind_group"),
+            &texture_bind_group_layout,
+            &[
                 wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&white_view) },
                 wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&default_sampler) },
             ],
-        });
+        );
+
+        // Per-draw model uniform (group 2): one slot per draw, selected with a
+        // dynamic offset so all draws share a single buffer and bind group.
+        let model_bind_group_layout = bind_cache.layout(&device, Some("model_bind_group_layout"), &[uniform_entry(
+            0,
+            wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+            wgpu::BufferSize::new(size_of::<ModelUniform>() as u64),
+        )]);
+        let uniforms = UniformAllocator::new(
+            &device,
+            CAMERA_UNIFORM_SIZE.max(size_of::<ModelUniform>() as u64),
+            INITIAL_UNIFORM_SLOTS,
+        );
+        let (camera_bind_group, model_bind_group) = create_uniform_bind_groups(
+            &device,
+            &mut bind_cache,
+            &uniforms,
+            [&light_buffer, &frame_buffer],
+            &camera_bind_group_layout,
+            &model_bind_group_layout,
+        );
 
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: Some("Render Pipeline Layout"),
-            bind_group_layouts: &[Some(&camera_bind_group_layout), Some(&texture_bind_group_layout)],
+            bind_group_layouts: &[
+                Some(&camera_bind_group_layout),
+                Some(&texture_bind_group_layout),
+                Some(&model_bind_group_layout),
+            ],
             immediate_size: 0,
         });
 
@@ -201,231 +1059,995 @@ impl Pipeline {
             mip_level_count: 1, sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
             format: wgpu::TextureFormat::Depth32Float,
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
             view_formats: &[],
         });
         let depth_view = depth_texture.create_view(&wgpu::TextureViewDescriptor::default());
 
-        let vertex_buf_layout = wgpu::VertexBufferLayout {
-            array_stride: size_of::<Vertex>() as wgpu::BufferAddress,
-            step_mode: wgpu::VertexStepMode::Vertex,
-            attributes: &VERTEX_ATTRS,
-        };
-
-        // Main pipeline (normal depth, back-face culled)
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Render Pipeline"),
-            layout: Some(&pipeline_layout),
-            cache: None, multiview_mask: None,
-            vertex: wgpu::VertexState {
-                module: &shader, entry_point: Some("vs_main"),
-                compilation_options: PipelineCompilationOptions::default(),
-                buffers: &[vertex_buf_layout.clone()],
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader, entry_point: Some("fs_main"),
-                compilation_options: PipelineCompilationOptions::default(),
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: surface_config.format,
-                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: Some(wgpu::Face::Back),
-                unclipped_depth: false,
-                polygon_mode: wgpu::PolygonMode::Fill,
-                conservative: false,
-            },
-            depth_stencil: Some(wgpu::DepthStencilState {
-                format: wgpu::TextureFormat::Depth32Float,
-                depth_write_enabled: Some(true),
-                depth_compare: Some(wgpu::CompareFunction::Less),
-                stencil: wgpu::StencilState::default(),
-                bias: wgpu::DepthBiasState::default(),
-            }),
-            multisample: wgpu::MultisampleState::default(),
-        });
-
-        // Used for both the skybox (rendered first) and gizmo overlays (rendered last).
-        let overlay_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Overlay Pipeline"),
-            layout: Some(&pipeline_layout),
-            cache: None, multiview_mask: None,
-            vertex: wgpu::VertexState {
-                module: &shader, entry_point: Some("vs_main"),
-                compilation_options: PipelineCompilationOptions::default(),
-                buffers: &[vertex_buf_layout],
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader, entry_point: Some("fs_main"),
-                compilation_options: PipelineCompilationOptions::default(),
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: surface_config.format,
-                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-            }),
-            primitive: wgpu::PrimitiveState { cull_mode: None, ..Default::default() },
-            depth_stencil: Some(wgpu::DepthStencilState {
-                format: wgpu::TextureFormat::Depth32Float,
-                depth_write_enabled: Some(false),
-                depth_compare: Some(wgpu::CompareFunction::Always),
-                stencil: wgpu::StencilState::default(),
-                bias: wgpu::DepthBiasState::default(),
-            }),
-            multisample: wgpu::MultisampleState::default(),
-        });
+        // Create the built-in permutations up front so the first frame
+        // does not stall on pipeline compilation.
+        let post = PostChain::new(&device, &adapter, surface_config.format);
+        let mut pipelines = PipelineCache::new(pipeline_layout.clone(), shader.clone());
+        for key in builtin_pipeline_keys(post.scene_format(surface_config.format), surface_config.format) {
+            pipelines.get(&device, key);
+        }
 
-        Self {
-            render_pipeline,
-            overlay_pipeline,
+        Ok(Self {
+            pipelines: Mutex::new(pipelines),
+            instanced_pipelines: Mutex::new(None),
+            culler: Mutex::new(None),
+            occlusion: Mutex::new(None),
+            depth_probe: Mutex::new(None),
+            blitter: Mutex::new(None),
+            pipeline_layout,
+            post,
+            fixed_aspect: None,
+            letterbox_bars: None,
+            water: None,
+            lines: None,
+            debug_view: DebugView::Off,
+            requested_present_mode,
+            surface_events: Mutex::new(surface_events),
+            suboptimal_frames: AtomicU32::new(0),
             shader,
             device,
             queue,
             surface,
             surface_config,
-            camera_buffer,
+            offscreen_target,
+            retained_frame: None,
+            capture_target: None,
+            instance,
+            adapter,
+            light_buffer,
+            frame_buffer,
+            time: 0.0,
+            camera_bind_group_layout,
             camera_bind_group,
             depth_view,
             texture_bind_group_layout,
             default_texture_bind_group,
             default_sampler,
-        }
+            model_bind_group_layout,
+            model_bind_group,
+            uniforms,
+            bind_cache: Mutex::new(bind_cache),
+            bytes_uploaded: AtomicU64::new(0),
+            buffers_allocated: AtomicU32::new(0),
+            clock: web_time::Instant::now(),
+            last_frame_micros: AtomicU64::new(0),
+        })
     }
 
-    /// Render in three layers within a single render pass.
+    /// Render the 3D layers of `frame` in a single render pass, then its 2D
+    /// `screen` layer over the post-processed result.
     ///
-    /// * `world_batches` - slice of `(mesh, texture_bind_group)` pairs for scene objects.
-    ///   Each pair may carry a different texture; they are all rendered with the main pipeline.
-    /// * `skybox`  - rendered first with the overlay pipeline (depth=Always, no depth-write).
-    /// * `overlay` - rendered last with the overlay pipeline (gizmos, always on top).
-    pub fn render_scene(
-        &self,
-        camera: &Camera,
-        world_batches: &[(&BakedMesh, &wgpu::BindGroup)],
-        skybox: Option<&BakedMesh>,
-        overlay: Option<&BakedMesh>,
-    ) -> RenderStats {
-        let frame = match self.surface.get_current_texture() {
-            wgpu::CurrentSurfaceTexture::Success(f)    => f,
-            wgpu::CurrentSurfaceTexture::Suboptimal(f) => f,
-            _ => return RenderStats::default(),
+    /// Returns the frame's counters, or why it was dropped (see
+    /// [`RenderError`]).  Outdated surfaces are reconfigured and retried once
+    /// before [`RenderError::SurfaceLost`] is reported.
+    pub fn render_scene(&self, frame: FrameInputs<'_>) -> Result<RenderStats, RenderError> {
+        profile_scope!("Pipeline::render_scene");
+        let FrameInputs { camera, lighting, world_batches, mesh_draws, instanced, skybox, overlay, screen, mut hooks } = frame;
+        // Present to the surface if there is one, otherwise render into the
+        // headless target.  Suspended pipelines have neither.
+        let capture = self.capture_target.as_ref();
+        let (frame, view) = if let Some(capture) = capture {
+            (None, capture.color.clone())
+        } else if let Some(surface) = &self.surface {
+            let Some(frame) = self.acquire_frame(surface)? else {
+                return Ok(RenderStats::default());
+            };
+            let view = frame.texture.create_view(&wgpu::TextureViewDescriptor::default());
+            (Some(frame), view)
+        } else if let Some(target) = &self.offscreen_target {
+            (None, target.create_view(&wgpu::TextureViewDescriptor::default()))
+        } else {
+            return Ok(RenderStats::default());
         };
-        let view = frame.texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let memory_scope = self.device.push_error_scope(wgpu::ErrorFilter::OutOfMemory);
 
-        let cam_mat = camera.build_view_projection_matrix();
-        self.queue.write_buffer(&self.camera_buffer, 0, bytemuck::cast_slice(&[cam_mat.data]));
+        let lights = LightUniform::from(lighting);
+        self.queue.write_buffer(&self.light_buffer, 0, bytemuck::bytes_of(&lights));
+        self.record_upload(size_of::<LightUniform>() as u64, 0);
+
+        // The reserved slots always fit: the allocator never holds fewer than
+        // INITIAL_UNIFORM_SLOTS.  Draws beyond the capacity are dropped.
+        let cam_mat = capture
+            .and_then(|capture| capture.view_projection)
+            .unwrap_or_else(|| camera.build_view_projection_matrix());
+        let screen_mat = screen.map_or(cam_mat.data, |s| s.view_projection.data);
+        let mut uniforms = self.uniforms.begin_frame();
+        let camera_offset = uniforms.push(&cam_mat.data).unwrap_or_default();
+        let screen_camera_offset = uniforms.push(&screen_mat).unwrap_or_default();
+        let identity_offset = uniforms.push(&IDENTITY_MODEL).unwrap_or_default();
+        let unlit_offset = uniforms.push(&UNLIT_MODEL).unwrap_or_default();
+        // Emissive and displaced world batches take a slot each; the rest
+        // share the identity slot.
+        let batch_offsets: Vec<u32> = world_batches
+            .iter()
+            .map(|(_, _, material)| {
+                if !material.has_model_uniform() {
+                    return identity_offset;
+                }
+                let model = ModelUniform {
+                    emissive: emissive(material),
+                    displacement: material.displacement.uniform(self.time),
+                    ..IDENTITY_MODEL
+                };
+                uniforms.push(&model).unwrap_or(identity_offset)
+            })
+            .collect();
+        let mut push_draws = |draws: &[MeshDraw], flags| -> Vec<u32> {
+            draws.iter()
+                .map_while(|draw| uniforms.push(&ModelUniform {
+                    model: draw.model.data,
+                    color: draw.color,
+                    flags,
+                    emissive: emissive(&draw.material),
+                    displacement: draw.material.displacement.uniform(self.time),
+                }))
+                .collect()
+        };
+        let mesh_offsets = push_draws(mesh_draws, IDENTITY_MODEL.flags);
+        let screen_offsets = push_draws(screen.map_or(&[][..], |s| &s.draws), UNLIT_MODEL.flags);
+        // The instances of a draw share its material, so emission and
+        // displacement come from a slot per draw (group 3), not per instance.
+        let instanced_offsets: Vec<u32> = instanced.map_or(&[][..], |instanced| &instanced.draws)
+            .iter()
+            .map(|draw| {
+                if !draw.material.has_model_uniform() {
+                    return identity_offset;
+                }
+                let model = ModelUniform {
+                    emissive: emissive(&draw.material),
+                    displacement: draw.material.displacement.uniform(self.time),
+                    ..IDENTITY_MODEL
+                };
+                uniforms.push(&model).unwrap_or(identity_offset)
+            })
+            .collect();
+        self.record_upload(uniforms.finish(&self.queue), 0);
+        let screen_draws = screen.map_or(&[][..], |s| &s.draws[..screen_offsets.len()]);
+
+        // Capture passes skip post and run just before the main pass; letting
+        // them tick the clock would leave the main pass only the capture time
+        // to adapt over.
+        if capture.is_none() {
+            let now = self.clock.elapsed().as_micros() as u64;
+            let dt = now.saturating_sub(self.last_frame_micros.swap(now, Ordering::Relaxed)) as f32 * 1e-6;
+            self.record_upload(self.post.prepare(&self.queue, camera, dt), 0);
+        }
+        let water = self.water.as_ref().filter(|_| capture.is_none());
+        if let Some(water) = water {
+            self.record_upload(water.prepare(&self.queue, camera, self.viewport()), 0);
+        }
+        let lines = self.lines.as_ref().filter(|lines| !lines.is_empty());
+        if let Some(lines) = lines {
+            self.record_upload(lines.prepare(&self.queue, self.target_size()), 0);
+        }
+        // With post passes on, the scene goes to an intermediate target and
+        // is resolved into `view` afterwards.
+        let scene_view = match capture {
+            Some(_) => &view,
+            None => self.post.scene_view().unwrap_or(&view),
+        };
+        let depth_view = capture.map_or(&self.depth_view, |capture| &capture.depth);
+        let [scene_key, overlay_key, screen_key] = match capture {
+            Some(capture) => {
+                let format = capture.color.texture().format();
+                builtin_pipeline_keys(format, format)
+            }
+            None => self.builtin_pipeline_keys(),
+        };
+        let scene_key = self.debug_view.apply(scene_key);
+        let clear = match self.debug_view {
+            DebugView::Overdraw => wgpu::Color::BLACK,
+            _ => wgpu::Color { r: 0.05, g: 0.07, b: 0.12, a: 1.0 },
+        };
+        let overlay_pipeline = self.render_pipeline(overlay_key);
+        let mut bound_key = None;
 
         let mut enc = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        let instanced = instanced.map(|instanced| {
+            let (bind_group, indirect_args) = self.prepare_instanced(&mut enc, instanced.cull_view_projection.as_ref().unwrap_or(&cam_mat), instanced);
+            (instanced, bind_group, indirect_args)
+        });
+        let mut run_hooks = |stage, enc: &mut wgpu::CommandEncoder, view: &wgpu::TextureView| {
+            if let Some((hooks, resources)) = &mut hooks && capture.is_none() && !hooks.is_empty() {
+                hooks.run(enc, &RenderContext { pipeline: self, stage, view, camera_offset, resources });
+            }
+        };
+        run_hooks(RenderStage::BeforeScene, &mut enc, scene_view);
         let mut stats = RenderStats::default();
         {
             let mut rp = enc.begin_render_pass(&wgpu::RenderPassDescriptor {
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
+                    view: scene_view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color { r: 0.05, g: 0.07, b: 0.12, a: 1.0 }),
+                        load: wgpu::LoadOp::Clear(clear),
                         store: wgpu::StoreOp::Store,
                     },
                     depth_slice: None,
                 })],
                 depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
-                    view: &self.depth_view,
+                    view: depth_view,
                     depth_ops: Some(wgpu::Operations { load: wgpu::LoadOp::Clear(1.0), store: wgpu::StoreOp::Store }),
                     stencil_ops: None,
                 }),
                 ..Default::default()
             });
 
-            rp.set_bind_group(0, &self.camera_bind_group, &[]);
+            if self.fixed_aspect.is_some() && capture.is_none() {
+                let viewport = self.viewport();
+                rp.set_viewport(viewport.x, viewport.y, viewport.width, viewport.height, 0.0, 1.0);
+                let [x, y, width, height] = [viewport.x, viewport.y, viewport.width, viewport.height].map(|v| v as u32);
+                rp.set_scissor_rect(x, y, width, height);
+            }
+            rp.set_bind_group(0, &self.camera_bind_group, &[camera_offset]);
 
             // Layer 1: Skybox (overlay pipeline → depth=Always, no depth write)
-            if let Some(sky) = skybox {
-                if sky.index_count > 0 {
-                    rp.set_pipeline(&self.overlay_pipeline);
-                    rp.set_bind_group(1, &self.default_texture_bind_group, &[]);
-                    rp.set_vertex_buffer(0, sky.vertex_buffer.slice(..));
-                    rp.set_index_buffer(sky.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
-                    rp.draw_indexed(0..sky.index_count, 0, 0..1);
-                    stats.draw_calls += 1;
-                    stats.triangle_count += sky.index_count / 3;
-                }
+            if let Some(sky) = skybox && sky.index_count > 0 {
+                rp.set_bind_group(2, &self.model_bind_group, &[unlit_offset]);
+                rp.set_pipeline(&overlay_pipeline);
+                rp.set_bind_group(1, &self.default_texture_bind_group, &[]);
+                rp.set_vertex_buffer(0, sky.vertex_buffer.slice(..));
+                rp.set_index_buffer(sky.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+                rp.draw_indexed(0..sky.index_count, 0, 0..1);
+                stats.record_draw(sky.index_count, 1);
             }
 
             // Layer 2: World batches (main pipeline, per-texture)
-            rp.set_pipeline(&self.render_pipeline);
-            for (mesh, tex_bg) in world_batches {
+            for ((mesh, tex_bg, material), &offset) in world_batches.iter().zip(&batch_offsets) {
                 if mesh.index_count > 0 {
+                    rp.set_bind_group(2, &self.model_bind_group, &[offset]);
+                    let variant = ShaderVariant::DEFAULT.with_textured(*tex_bg != &self.default_texture_bind_group);
+                    self.set_variant(&mut rp, &mut bound_key, material.apply(scene_key.with_variant(variant)));
                     rp.set_bind_group(1, *tex_bg, &[]);
                     rp.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
                     rp.set_index_buffer(mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
                     rp.draw_indexed(0..mesh.index_count, 0, 0..1);
-                    stats.draw_calls += 1;
-                    stats.triangle_count += mesh.index_count / 3;
+                    stats.record_draw(mesh.index_count, 1);
+                }
+            }
+
+            // Layer 2b: Registry meshes (main pipeline, per-draw model slot)
+            for (draw, &offset) in mesh_draws.iter().zip(&mesh_offsets) {
+                if draw.mesh.index_count > 0 {
+                    self.set_variant(&mut rp, &mut bound_key, draw.material.apply(mesh_key(scene_key.with_variant(draw.variant), draw.mesh)));
+                    rp.set_bind_group(1, draw.texture_bind_group, &[]);
+                    rp.set_bind_group(2, &self.model_bind_group, &[offset]);
+                    rp.set_vertex_buffer(0, draw.mesh.vertex_buffer.slice(..));
+                    rp.set_index_buffer(draw.mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+                    rp.draw_indexed(0..draw.mesh.index_count, 0, 0..1);
+                    stats.record_draw(draw.mesh.index_count, 1);
+                }
+            }
+
+            // Layer 2c: Instanced registry meshes (storage buffer in group 2,
+            // per-draw material slot in group 3)
+            if let Some((instanced, bind_group, indirect_args)) = &instanced {
+                rp.set_bind_group(2, bind_group, &[]);
+                let mut bound_instanced = None;
+                for ((index, draw), &offset) in instanced.draws.iter().enumerate().zip(&instanced_offsets) {
+                    if draw.mesh.index_count > 0 && !draw.instances.is_empty() {
+                        let key = draw.material.apply(mesh_key(scene_key.with_variant(draw.variant), draw.mesh));
+                        if bound_instanced != Some(key) {
+                            rp.set_pipeline(&self.instanced_pipeline(key));
+                            bound_instanced = Some(key);
+                        }
+                        rp.set_bind_group(1, draw.texture_bind_group, &[]);
+                        rp.set_bind_group(3, &self.model_bind_group, &[offset]);
+                        rp.set_vertex_buffer(0, draw.mesh.vertex_buffer.slice(..));
+                        rp.set_index_buffer(draw.mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+                        match indirect_args {
+                            Some(args) => rp.draw_indexed_indirect(args, GpuCuller::args_offset(index)),
+                            None => rp.draw_indexed(0..draw.mesh.index_count, 0, draw.instances.clone()),
+                        }
+                        stats.record_draw(draw.mesh.index_count, draw.instances.len() as u32);
+                    }
                 }
             }
+            rp.set_bind_group(2, &self.model_bind_group, &[unlit_offset]);
+
+            // Layer 2d: Water (translucent, depth-tested; skipped while
+            // capturing, including its own reflection)
+            if let Some(water) = water {
+                water.draw(&self.device, &mut rp, scene_key.format, &mut stats);
+            }
+
+            // Layer 2e: Depth-tested thick lines (debug drawing, grid)
+            if let Some(lines) = lines {
+                lines.draw(&self.device, &mut rp, scene_key.format, false, &mut stats);
+            }
 
             // Layer 3: Overlay / gizmos (overlay pipeline -> always on top)
-            if let Some(ov) = overlay {
-                if ov.index_count > 0 {
-                    rp.set_pipeline(&self.overlay_pipeline);
-                    rp.set_bind_group(1, &self.default_texture_bind_group, &[]);
-                    rp.set_vertex_buffer(0, ov.vertex_buffer.slice(..));
-                    rp.set_index_buffer(ov.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
-                    rp.draw_indexed(0..ov.index_count, 0, 0..1);
-                    stats.draw_calls += 1;
-                    stats.triangle_count += ov.index_count / 3;
+            if let Some(ov) = overlay && ov.index_count > 0 {
+                rp.set_pipeline(&overlay_pipeline);
+                rp.set_bind_group(1, &self.default_texture_bind_group, &[]);
+                rp.set_vertex_buffer(0, ov.vertex_buffer.slice(..));
+                rp.set_index_buffer(ov.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+                rp.draw_indexed(0..ov.index_count, 0, 0..1);
+                stats.record_draw(ov.index_count, 1);
+            }
+            if let Some(lines) = lines {
+                lines.draw(&self.device, &mut rp, scene_key.format, true, &mut stats);
+            }
+
+            // Layer 4: Full-screen fade (scene transitions).  With HDR on
+            // it is drawn after tonemapping instead.
+            if self.post.scene_view().is_none() && capture.is_none() {
+                self.post.fade.draw(&mut rp, &mut stats);
+            }
+        }
+        if capture.is_none() {
+            self.record_occlusion_queries(&mut enc, &cam_mat);
+        }
+        run_hooks(RenderStage::AfterScene, &mut enc, scene_view);
+        if capture.is_none() {
+            self.post.resolve(&mut enc, &view, &self.depth_view, &mut stats);
+            self.draw_letterbox_bars(&mut enc, &view, &mut stats);
+        }
+        run_hooks(RenderStage::AfterPost, &mut enc, &view);
+
+        // Layer 5: Screen-space overlay, after tonemapping and the fade so
+        // HUD colours are exact.
+        if !screen_draws.is_empty() && capture.is_none() {
+            let mut rp = enc.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Screen Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations { load: wgpu::LoadOp::Load, store: wgpu::StoreOp::Store },
+                    depth_slice: None,
+                })],
+                ..Default::default()
+            });
+            bound_key = None;
+            rp.set_bind_group(0, &self.camera_bind_group, &[screen_camera_offset]);
+            for (draw, &offset) in screen_draws.iter().zip(&screen_offsets) {
+                if draw.mesh.index_count > 0 {
+                    self.set_variant(&mut rp, &mut bound_key, mesh_key(screen_key.with_variant(draw.variant), draw.mesh));
+                    rp.set_bind_group(1, draw.texture_bind_group, &[]);
+                    rp.set_bind_group(2, &self.model_bind_group, &[offset]);
+                    rp.set_vertex_buffer(0, draw.mesh.vertex_buffer.slice(..));
+                    rp.set_index_buffer(draw.mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+                    rp.draw_indexed(0..draw.mesh.index_count, 0, 0..1);
+                    stats.record_draw(draw.mesh.index_count, 1);
                 }
             }
         }
+        run_hooks(RenderStage::AfterOverlay, &mut enc, &view);
 
+        if let (Some(frame), Some(retained)) = (&frame, &self.retained_frame) {
+            enc.copy_texture_to_texture(
+                frame.texture.as_image_copy(),
+                retained.as_image_copy(),
+                retained.size(),
+            );
+        }
         self.queue.submit(std::iter::once(enc.finish()));
-        frame.present();
-        stats
+        if let Some(queries) = self.occlusion.lock().unwrap_or_else(PoisonError::into_inner).as_mut() {
+            queries.map();
+        }
+        if let Some(frame) = frame {
+            frame.present();
+        }
+        stats.bytes_uploaded = self.bytes_uploaded.swap(0, Ordering::Relaxed);
+        stats.buffer_reallocations = self.buffers_allocated.swap(0, Ordering::Relaxed);
+        let mut bind_cache = self.bind_cache();
+        bind_cache.end_frame();
+        stats.bind_groups_created = bind_cache.take_created();
+        // Native error scopes resolve immediately; on WebGPU the result
+        // arrives later and the frame is assumed fine.
+        let mut popped = std::pin::pin!(memory_scope.pop());
+        let poll = popped.as_mut().poll(&mut std::task::Context::from_waker(std::task::Waker::noop()));
+        if let std::task::Poll::Ready(Some(wgpu::Error::OutOfMemory { .. })) = poll {
+            return Err(RenderError::OutOfMemory);
+        }
+        Ok(stats)
+    }
+
+    pub fn render_baked_mesh(&self, mesh: &BakedMesh, camera: &Camera) -> Result<RenderStats, RenderError> {
+        let lighting = Lighting::default();
+        let batches = [(mesh, &self.default_texture_bind_group, Material::default())];
+        self.render_scene(FrameInputs { world_batches: &batches, ..FrameInputs::new(camera, &lighting) })
+    }
+
+    /// Make sure the model uniform buffer can hold `count` [`MeshDraw`]s
+    /// (scene and screen draws combined).
+    ///
+    /// Grows the buffer (to the next power of two) when needed; cheap no-op
+    /// otherwise.  Called by [`crate::scene::Scene::draw_world`] each frame.
+    pub fn reserve_mesh_draws(&mut self, count: usize) {
+        if !self.uniforms.reserve(&self.device, count + RESERVED_UNIFORM_SLOTS) {
+            return;
+        }
+        (self.camera_bind_group, self.model_bind_group) = create_uniform_bind_groups(
+            &self.device,
+            self.bind_cache.get_mut().unwrap_or_else(PoisonError::into_inner),
+            &self.uniforms,
+            [&self.light_buffer, &self.frame_buffer],
+            &self.camera_bind_group_layout,
+            &self.model_bind_group_layout,
+        );
+        self.record_upload(0, 1);
+    }
+
+    /// Paint [`LETTERBOX_COLOR`] outside the fixed-aspect viewport, over the
+    /// post-processed frame but under the screen-space overlay.  No-op
+    /// without a fixed aspect ratio.
+    fn draw_letterbox_bars(&self, enc: &mut wgpu::CommandEncoder, view: &wgpu::TextureView, stats: &mut RenderStats) {
+        let (Some(_), Some(bars)) = (self.fixed_aspect, &self.letterbox_bars) else { return };
+        let (width, height) = (self.surface_config.width, self.surface_config.height);
+        let viewport = self.viewport();
+        let [x, y, w, h] = [viewport.x, viewport.y, viewport.width, viewport.height].map(|v| v as u32);
+        let rects = [
+            [0, 0, x, height],
+            [x + w, 0, width - x - w, height],
+            [0, 0, width, y],
+            [0, y + h, width, height - y - h],
+        ];
+        let mut rp = enc.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Letterbox Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations { load: wgpu::LoadOp::Load, store: wgpu::StoreOp::Store },
+                depth_slice: None,
+            })],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &self.depth_view,
+                depth_ops: Some(wgpu::Operations { load: wgpu::LoadOp::Load, store: wgpu::StoreOp::Store }),
+                stencil_ops: None,
+            }),
+            ..Default::default()
+        });
+        for [x, y, w, h] in rects {
+            if w > 0 && h > 0 {
+                rp.set_scissor_rect(x, y, w, h);
+                bars.draw(&mut rp, stats);
+            }
+        }
     }
 
-    pub fn render_baked_mesh(&self, mesh: &BakedMesh, camera: &Camera) {
-        self.render_scene(camera, &[(mesh, &self.default_texture_bind_group)], None, None);
+    /// Add to the upload counters reported by the next [`RenderStats`].
+    fn record_upload(&self, bytes: u64, buffers: u32) {
+        self.bytes_uploaded.fetch_add(bytes, Ordering::Relaxed);
+        self.buffers_allocated.fetch_add(buffers, Ordering::Relaxed);
     }
 
+    /// Reconfigure the surface and depth buffer for a new window / canvas size.
+    ///
+    /// Zero-sized requests (minimised window, hidden canvas) are ignored, and
+    /// sizes are clamped to the device's maximum texture dimension — browsers
+    /// frequently report canvases larger than WebGL2 allows on high-DPI screens.
     pub fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
+        let max = self.device.limits().max_texture_dimension_2d;
+        let new_size = winit::dpi::PhysicalSize::new(new_size.width.min(max), new_size.height.min(max));
         if new_size.width > 0 && new_size.height > 0 {
             self.surface_config.width = new_size.width;
             self.surface_config.height = new_size.height;
-            self.surface.configure(&self.device, &self.surface_config);
+            if let Some(surface) = &self.surface {
+                surface.configure(&self.device, &self.surface_config);
+            }
+            if self.offscreen_target.is_some() {
+                self.offscreen_target = Some(create_offscreen_target(&self.device, new_size.width, new_size.height));
+            }
+            if self.retained_frame.is_some() {
+                self.retained_frame = Some(self.create_retained_frame());
+            }
             self.depth_view = self.create_depth_view(new_size);
+            self.post.resize(&self.device, new_size.width, new_size.height, &self.depth_view);
+        }
+    }
+
+    /// Replace the scene shader with WGSL `source` and rebuild the render
+    /// pipelines, e.g. for live shader editing.
+    ///
+    /// The source must keep the bind group layout and vertex interface of
+    /// the built-in `shader.wgsl` (`vs_main` / `fs_main`).  On any compile or
+    /// validation error the current shader stays active.
+    ///
+    /// `source` is plain WGSL; run it through
+    /// [`crate::shader::ShaderPreprocessor`] first if it uses `#include` or
+    /// `#define`.
+    pub async fn reload_shader(&mut self, source: &str) -> Result<(), wgpu::Error> {
+        let scope = self.device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let shader = self.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("shader.wgsl (reloaded)"),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        });
+        let keys = self.builtin_pipeline_keys();
+        let built = keys.map(|key| pipeline_cache::create_pipeline(&self.device, &self.pipeline_layout, &shader, key));
+        if let Some(error) = scope.pop().await {
+            return Err(error);
         }
+        let pipelines = self.pipelines.get_mut().unwrap_or_else(PoisonError::into_inner);
+        pipelines.replace_shader(ShaderId::SCENE, shader.clone());
+        for (key, pipeline) in keys.into_iter().zip(built) {
+            pipelines.insert(key, pipeline);
+        }
+        self.shader = shader;
+        Ok(())
+    }
+
+    /// Cover the whole frame with `color` (straight alpha), drawn on top of
+    /// everything including gizmos.  Alpha `0.0` disables the pass.
+    ///
+    /// Driven by scene transitions (see
+    /// [`crate::scene::Scene::switch_to_with_fade`]); also usable directly
+    /// for flashes or screen dimming.
+    pub fn set_fade(&mut self, color: [f32; 4]) {
+        let bytes = self.post.fade.set_color(&self.queue, color);
+        self.record_upload(bytes, 0);
+    }
+
+    /// The colour last passed to [`Self::set_fade`].
+    pub fn fade(&self) -> [f32; 4] {
+        self.post.fade.color
+    }
+
+    /// Set the time in seconds that drives material displacement and the
+    /// `frame` uniform of `vertra/camera.wgsl`.  Set every frame from
+    /// [`crate::scene::Scene::time`].
+    pub fn set_time(&mut self, seconds: f64) {
+        if seconds == self.time {
+            return;
+        }
+        self.time = seconds;
+        self.queue.write_buffer(&self.frame_buffer, 0, bytemuck::bytes_of(&[seconds as f32, 0.0, 0.0, 0.0]));
+        self.record_upload(FRAME_UNIFORM_SIZE, 0);
+    }
+
+    /// The seconds last passed to [`Self::set_time`].
+    pub fn time(&self) -> f64 {
+        self.time
     }
 
-    fn create_depth_view(&self, size: winit::dpi::PhysicalSize<u32>) -> wgpu::TextureView {
+    /// Render the 3D scene into the largest centred rectangle of aspect
+    /// ratio `aspect` (`width / height`), recomputed on every
+    /// [`Self::resize`], and fill the rest of the frame with
+    /// [`LETTERBOX_COLOR`] bars.  `None` (the default) uses the whole frame.
+    ///
+    /// The screen-space overlay still spans the whole frame.
+    /// [`crate::scene::Scene::set_fixed_aspect`] also matches the camera.
+    pub fn set_fixed_aspect(&mut self, aspect: Option<f32>) {
+        let aspect = aspect.filter(|a| a.is_finite() && *a > 0.0);
+        if aspect.is_some() && self.letterbox_bars.is_none() {
+            let mut bars = FadePass::new(&self.device, self.surface_config.format);
+            let bytes = bars.set_color(&self.queue, LETTERBOX_COLOR);
+            self.record_upload(bytes, 0);
+            self.letterbox_bars = Some(bars);
+        }
+        self.fixed_aspect = aspect;
+    }
+
+    /// The aspect ratio last passed to [`Self::set_fixed_aspect`].
+    pub fn fixed_aspect(&self) -> Option<f32> {
+        self.fixed_aspect
+    }
+
+    /// Draw scene geometry with `view` instead of regular shading, or
+    /// [`DebugView::Off`] to go back.  See [`DebugView`].
+    pub fn set_debug_view(&mut self, view: DebugView) {
+        self.debug_view = view;
+    }
+
+    /// The view last passed to [`Self::set_debug_view`].
+    pub fn debug_view(&self) -> DebugView {
+        self.debug_view
+    }
+
+    /// GPU memory the pipeline itself holds: uniform buffers and render
+    /// targets.  [`crate::scene::Scene::gpu_memory`] adds meshes and
+    /// textures.
+    pub fn gpu_memory(&self) -> GpuMemory {
+        let targets = [self.depth_view.texture()]
+            .into_iter()
+            .chain(self.offscreen_target.as_ref())
+            .chain(self.retained_frame.as_ref());
+        let own = GpuMemory {
+            uniform_bytes: self.uniforms.buffer().size() + self.light_buffer.size() + self.frame_buffer.size(),
+            render_target_bytes: targets.map(texture_bytes).sum(),
+            ..GpuMemory::default()
+        };
+        own + self.post.gpu_memory()
+            + self.water.as_ref().map(WaterPass::gpu_memory).unwrap_or_default()
+            + self.lines.as_ref().map(LinePass::gpu_memory).unwrap_or_default()
+    }
+
+    /// The pixel rectangle the 3D scene is rendered into: the whole frame,
+    /// or the letterboxed area with a fixed aspect ratio.
+    pub fn viewport(&self) -> Viewport {
+        let (width, height) = (self.surface_config.width as f32, self.surface_config.height as f32);
+        match self.fixed_aspect {
+            Some(aspect) => Viewport::letterbox(width, height, aspect),
+            None => Viewport::new(width, height),
+        }
+    }
+
+    /// Choose the post-processing passes; see [`crate::post`].
+    ///
+    /// Toggling HDR rebuilds the scene pipelines for the new target format,
+    /// so do it at setup or on a settings change, not every frame.
+    pub fn set_post_settings(&mut self, settings: PostSettings) {
+        let hdr_changed = settings.hdr != self.post.settings().hdr;
+        let (width, height) = (self.surface_config.width, self.surface_config.height);
+        self.post.set_settings(&self.device, settings, width, height, &self.depth_view);
+        if hdr_changed {
+            // Compile the permutations for the new scene format now rather
+            // than on the next frame.
+            for key in self.builtin_pipeline_keys() {
+                self.render_pipeline(key);
+            }
+        }
+    }
+
+    /// The settings last passed to [`Self::set_post_settings`].
+    pub fn post_settings(&self) -> &PostSettings {
+        self.post.settings()
+    }
+
+    /// `false` when the device lacks compute shaders (WebGL2); auto
+    /// exposure is then skipped and the camera exposure used as-is.
+    pub fn supports_auto_exposure(&self) -> bool {
+        self.post.compute_supported()
+    }
+
+    /// The texture rendered into by a [`Self::headless`] pipeline, or `None`
+    /// when presenting to a window surface.
+    pub fn offscreen_texture(&self) -> Option<&wgpu::Texture> {
+        self.offscreen_target.as_ref()
+    }
+
+    /// Draw the 3D layers of [`Self::render_scene`] into `target` instead
+    /// of the frame until reset to `None`.
+    pub(crate) fn set_capture_target(&mut self, target: Option<CaptureTarget>) {
+        self.capture_target = target;
+    }
+
+    /// Whether a capture target is set.
+    pub(crate) fn is_capturing(&self) -> bool {
+        self.capture_target.is_some()
+    }
+
+    /// Size in pixels of what the 3D layers are drawn into: the capture
+    /// target while one is set, else the viewport.
+    pub(crate) fn target_size(&self) -> [f32; 2] {
+        match &self.capture_target {
+            Some(capture) => {
+                let texture = capture.color.texture();
+                [texture.width() as f32, texture.height() as f32]
+            }
+            None => {
+                let viewport = self.viewport();
+                [viewport.width, viewport.height]
+            }
+        }
+    }
+
+    /// Replace the thick lines drawn with the next frames by those of
+    /// `parts`; see [`crate::lines`].
+    pub(crate) fn set_lines(&mut self, parts: &[&Lines]) {
+        if self.lines.is_none() && parts.iter().all(|lines| lines.is_empty()) {
+            return;
+        }
+        let pass = self.lines.get_or_insert_with(|| LinePass::new(&self.device, &self.camera_bind_group_layout));
+        let (bytes, allocated) = pass.upload(&self.device, &self.queue, parts);
+        self.record_upload(bytes, allocated);
+    }
+
+    /// Keep a copy of every presented frame so [`Self::read_pixels`] works
+    /// with a window surface, at the cost of one full-frame copy per frame.
+    /// Headless pipelines can always be read and ignore this.
+    ///
+    /// Returns whether readback is now on: surfaces that cannot be copied
+    /// from refuse it.
+    pub fn set_frame_readback(&mut self, enabled: bool) -> bool {
+        let Some(surface) = &self.surface else { return self.offscreen_target.is_some() };
+        if enabled && !surface.get_capabilities(&self.adapter).usages.contains(wgpu::TextureUsages::COPY_SRC) {
+            return false;
+        }
+        self.surface_config.usage.set(wgpu::TextureUsages::COPY_SRC, enabled);
+        surface.configure(&self.device, &self.surface_config);
+        self.retained_frame = enabled.then(|| self.create_retained_frame());
+        enabled
+    }
+
+    fn create_retained_frame(&self) -> wgpu::Texture {
+        self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Retained Frame"),
+            size: wgpu::Extent3d {
+                width: self.surface_config.width,
+                height: self.surface_config.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1, sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: self.surface_config.format,
+            usage: wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        })
+    }
+
+    /// RGBA8 pixels of `rect` in the last rendered frame, row by row from
+    /// the top with no padding.  Works on headless pipelines, and on window
+    /// surfaces while [`Self::set_frame_readback`] is on.
+    ///
+    /// Returns `None` if `rect` does not fit the frame, the frame cannot be
+    /// read, the target is not an 8-bit RGBA or BGRA format, or the readback
+    /// fails.  Waits for the GPU to finish the frame.
+    pub async fn read_pixels(&self, rect: PixelRect) -> Option<Vec<u8>> {
+        let texture = self.offscreen_target.as_ref().or(self.retained_frame.as_ref())?;
+        let swap_red_blue = match texture.format() {
+            wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb => false,
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb => true,
+            _ => return None,
+        };
+        if !rect.fits(texture.width(), texture.height()) {
+            return None;
+        }
+        let mut enc = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("Pixel Readback") });
+        let source = wgpu::TexelCopyTextureInfo {
+            origin: wgpu::Origin3d { x: rect.x, y: rect.y, z: 0 },
+            ..texture.as_image_copy()
+        };
+        let readback = Readback::copy(&self.device, &mut enc, source, [rect.width, rect.height], 4);
+        self.queue.submit(std::iter::once(enc.finish()));
+        let mut pixels = readback.read(&self.device).await?;
+        if swap_red_blue {
+            pixels.chunks_exact_mut(4).for_each(|pixel| pixel.swap(0, 2));
+        }
+        Some(pixels)
+    }
+
+    /// Depth of the last rendered frame at physical pixel `(x, y)`, in
+    /// `[0, 1]` like [`Camera::world_to_screen`]'s third component; `1.0`
+    /// where nothing was drawn.  Feed it to [`Camera::screen_to_world`] to
+    /// find the world position under the cursor.
+    ///
+    /// Returns `None` outside the frame or if the readback fails.  Waits
+    /// for the GPU to finish the frame, so use it for clicks rather than
+    /// every frame.
+    pub async fn read_depth(&self, x: u32, y: u32) -> Option<f32> {
+        let depth = self.depth_view.texture();
+        if x >= depth.width() || y >= depth.height() {
+            return None;
+        }
+        let layout = self.bind_group_layout(Some("depth_probe_bind_group_layout"), &DepthProbe::LAYOUT_ENTRIES);
+        let mut enc = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("Depth Readback") });
+        let readback = {
+            let mut probe = self.depth_probe.lock().unwrap_or_else(PoisonError::into_inner);
+            let probe = probe.get_or_insert_with(|| DepthProbe::new(&self.device, &layout));
+            let bind_group =
+                self.bind_group(Some("depth_probe_bind_group"), &layout, &probe.bind_group_entries(&self.depth_view));
+            let (bytes, readback) = probe.probe(&self.device, &self.queue, &mut enc, &bind_group, x, y);
+            self.record_upload(bytes, 0);
+            readback
+        };
+        self.queue.submit(std::iter::once(enc.finish()));
+        let bits = readback.read(&self.device).await?;
+        Some(f32::from_bits(bytemuck::pod_read_unaligned(bits.get(..4)?)))
+    }
+
+    /// A `width` × `height` texture of `format` with `mip_level_count`
+    /// levels that can be rendered to, sampled, blitted and read back, e.g.
+    /// for thumbnails or an off-screen view.
+    pub fn create_render_target(
+        &self,
+        width: u32,
+        height: u32,
+        format: wgpu::TextureFormat,
+        mip_level_count: u32,
+    ) -> wgpu::Texture {
+        self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Render Target"),
+            size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+            mip_level_count, sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC
+                | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        })
+    }
+
+    /// Copy level 0 of `source` into level 0 of `target`, scaling and
+    /// converting the format with a fullscreen pass where a plain copy is
+    /// not possible.  See [`crate::blit`] for the usages each side needs.
+    pub fn copy_texture(&self, source: &wgpu::Texture, target: &wgpu::Texture) {
+        self.with_blitter(|blitter, enc| blitter.copy(&self.device, enc, source, target));
+    }
+
+    /// Draw `source` over the whole of `target` with linear filtering.
+    pub fn blit(&self, source: &wgpu::TextureView, target: &wgpu::TextureView) {
+        let format = target.texture().format();
+        self.with_blitter(|blitter, enc| blitter.blit(&self.device, enc, source, target, format));
+    }
+
+    /// Fill mip levels `1..` of `texture` from level 0, each a filtered
+    /// half-size copy of the one above.
+    pub fn generate_mipmaps(&self, texture: &wgpu::Texture) {
+        self.with_blitter(|blitter, enc| blitter.generate_mipmaps(&self.device, enc, texture));
+    }
+
+    /// Record `f` with the shared [`Blitter`] and submit it.
+    fn with_blitter(&self, f: impl FnOnce(&mut Blitter, &mut wgpu::CommandEncoder)) {
+        let mut enc = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("Blit") });
+        {
+            let mut blitter = self.blitter.lock().unwrap_or_else(PoisonError::into_inner);
+            f(blitter.get_or_insert_with(|| Blitter::new(&self.device)), &mut enc);
+        }
+        self.queue.submit(std::iter::once(enc.finish()));
+    }
+
+    /// Drop the presentation surface.
+    ///
+    /// Call on winit's `Suspended` event: Android destroys the native window
+    /// when the app goes to the background, and any surface still referencing
+    /// it becomes invalid.  Rendering is a no-op until [`Self::resume`].
+    pub fn suspend(&mut self) {
+        self.surface = None;
+    }
+
+    /// Re-create the presentation surface for `window` after a suspend.
+    ///
+    /// No-op if a surface already exists.  The device, pipelines, and all
+    /// uploaded meshes and textures survive the suspend/resume cycle.  The
+    /// requested present mode is resolved again for the new surface.  If the
+    /// adapter cannot present to it, [`SurfaceEvent::Unsupported`] is queued
+    /// and rendering stays off; likewise [`SurfaceEvent::CreateFailed`] when
+    /// the surface cannot be created at all.
+    pub fn resume(&mut self, window: Arc<winit::window::Window>) {
+        if self.surface.is_some() {
+            return;
+        }
+        let size = window.inner_size();
+        let Ok(surface) = self.instance.create_surface(window) else {
+            self.push_surface_event(SurfaceEvent::CreateFailed);
+            return;
+        };
+        if !self.adapter.is_surface_supported(&surface) {
+            self.push_surface_event(SurfaceEvent::Unsupported);
+            return;
+        }
+        let (present_mode, fallback) = surface_present_mode(&surface, &self.adapter, self.requested_present_mode);
+        self.surface_config.present_mode = present_mode;
+        if let Some(event) = fallback {
+            self.push_surface_event(event);
+        }
+        surface.configure(&self.device, &self.surface_config);
+        self.surface = Some(surface);
+        // The native window may come back with a different size.
+        self.resize(size);
+    }
+
+    /// Request a present mode and reconfigure the surface, falling back as
+    /// documented at [`resolve_present_mode`].  Returns the mode in use,
+    /// which headless pipelines simply record.
+    pub fn set_present_mode(&mut self, mode: wgpu::PresentMode) -> wgpu::PresentMode {
+        self.requested_present_mode = mode;
+        let Some(surface) = &self.surface else {
+            self.surface_config.present_mode = mode;
+            return mode;
+        };
+        let (present_mode, fallback) = surface_present_mode(surface, &self.adapter, mode);
+        self.surface_config.present_mode = present_mode;
+        surface.configure(&self.device, &self.surface_config);
+        if let Some(event) = fallback {
+            self.push_surface_event(event);
+        }
+        present_mode
+    }
+
+    /// The configured present mode, after any fallback.
+    pub fn present_mode(&self) -> wgpu::PresentMode {
+        self.surface_config.present_mode
+    }
+
+    /// Take the surface events queued since the last call, oldest first.
+    ///
+    /// At most [`crate::constants::pipeline::MAX_SURFACE_EVENTS`] are kept
+    /// between calls.
+    pub fn drain_surface_events(&self) -> Vec<SurfaceEvent> {
+        std::mem::take(&mut *self.surface_events.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    pub(crate) fn push_surface_event(&self, event: SurfaceEvent) {
+        let mut events = self.surface_events.lock().unwrap_or_else(PoisonError::into_inner);
+        if events.len() >= crate::constants::pipeline::MAX_SURFACE_EVENTS {
+            events.remove(0);
+        }
+        events.push(event);
+    }
+
+    /// Acquire the next surface texture, reconfiguring the surface once when
+    /// it is outdated, lost, or has been suboptimal for
+    /// [`crate::constants::pipeline::SUBOPTIMAL_RECONFIGURE_FRAMES`] frames
+    /// in a row.  `Ok(None)` skips the frame while the window is occluded.
+    fn acquire_frame(&self, surface: &Surface<'static>) -> Result<Option<wgpu::SurfaceTexture>, RenderError> {
+        use crate::constants::pipeline::SUBOPTIMAL_RECONFIGURE_FRAMES;
+        use wgpu::CurrentSurfaceTexture as Current;
+        let mut reconfigured = false;
+        loop {
+            let reason = match surface.get_current_texture() {
+                Current::Success(frame) => {
+                    self.suboptimal_frames.store(0, Ordering::Relaxed);
+                    return Ok(Some(frame));
+                }
+                Current::Suboptimal(frame) => {
+                    let streak = self.suboptimal_frames.fetch_add(1, Ordering::Relaxed) + 1;
+                    if reconfigured || streak < SUBOPTIMAL_RECONFIGURE_FRAMES {
+                        return Ok(Some(frame));
+                    }
+                    // The frame must be released before reconfiguring.
+                    drop(frame);
+                    SurfaceStatus::Suboptimal
+                }
+                Current::Occluded => return Ok(None),
+                Current::Timeout => return Err(RenderError::Timeout),
+                Current::Validation => return Err(RenderError::SurfaceLost),
+                Current::Outdated => SurfaceStatus::Outdated,
+                Current::Lost => SurfaceStatus::Lost,
+            };
+            if reconfigured {
+                return Err(RenderError::SurfaceLost);
+            }
+            surface.configure(&self.device, &self.surface_config);
+            self.suboptimal_frames.store(0, Ordering::Relaxed);
+            self.push_surface_event(SurfaceEvent::Reconfigured { reason });
+            reconfigured = true;
+        }
+    }
+
+    pub(crate) fn create_depth_view(&self, size: winit::dpi::PhysicalSize<u32>) -> wgpu::TextureView {
         let depth_texture = self.device.create_texture(&wgpu::TextureDescriptor {
             label: Some("Depth Texture"),
             size: wgpu::Extent3d { width: size.width, height: size.height, depth_or_array_layers: 1 },
             mip_level_count: 1, sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
             format: wgpu::TextureFormat::Depth32Float,
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
             view_formats: &[],
         });
         depth_texture.create_view(&wgpu::TextureViewDescriptor::default())
     }
 
     pub fn create_baked_mesh(&self, vertices: &[Vertex], indices: &[u32]) -> BakedMesh {
+        self.create_baked_mesh_with_usage(vertices, indices, MeshUsage::Static)
+    }
+
+    /// Upload `vertices` / `indices` with buffer flags chosen by `usage`
+    /// (see [`MeshUsage::extra_buffer_usages`]).
+    pub fn create_baked_mesh_with_usage(
+        &self,
+        vertices: &[Vertex],
+        indices: &[u32],
+        usage: MeshUsage,
+    ) -> BakedMesh {
+        profile_scope!("bake");
+        let extra = usage.extra_buffer_usages();
         let vertex_buffer = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Baked Vertex Buffer"),
             contents: bytemuck::cast_slice(vertices),
-            usage: wgpu::BufferUsages::VERTEX,
+            usage: wgpu::BufferUsages::VERTEX | extra,
         });
         let index_buffer = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Baked Index Buffer"),
             contents: bytemuck::cast_slice(indices),
-            usage: wgpu::BufferUsages::INDEX,
+            usage: wgpu::BufferUsages::INDEX | extra,
         });
-        BakedMesh { vertex_buffer, index_buffer, index_count: indices.len() as u32 }
+        self.record_upload(size_of_val(vertices) as u64 + size_of_val(indices) as u64, 2);
+        BakedMesh {
+            vertex_buffer,
+            index_buffer,
+            index_count: indices.len() as u32,
+            bounding_sphere: crate::mesh::bounding_sphere(vertices),
+            topology: Topology::TriangleList,
+        }
     }
 
     /// Upload raw RGBA8 pixel data and return a texture bind group for use with
@@ -452,15 +2074,257 @@ impl Pipeline {
             wgpu::util::TextureDataOrder::default(),
             rgba_data,
         );
+        self.record_upload(rgba_data.len() as u64, 0);
         let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+        let bind_group = self.bind_group(Some(label), &self.texture_bind_group_layout, &[
+            wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&view) },
+            wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&self.default_sampler) },
+        ]);
+        (texture, bind_group)
+    }
+
+    /// A bind group of `layout` binding `entries`, reused from the
+    /// pipeline's [`BindGroupCache`] when the same resources were bound
+    /// before.  `label` is only used when the bind group is created.
+    pub fn bind_group(
+        &self,
+        label: Option<&str>,
+        layout: &wgpu::BindGroupLayout,
+        entries: &[wgpu::BindGroupEntry],
+    ) -> wgpu::BindGroup {
+        self.bind_cache().bind_group(&self.device, label, layout, entries)
+    }
+
+    /// The bind group layout with exactly `entries`, shared with every
+    /// earlier request for the same entries.
+    pub fn bind_group_layout(&self, label: Option<&str>, entries: &[wgpu::BindGroupLayoutEntry]) -> wgpu::BindGroupLayout {
+        self.bind_cache().layout(&self.device, label, entries)
+    }
+
+    /// The render pipeline for `key`, compiled on first request and reused
+    /// after.  See [`crate::pipeline_cache`].
+    pub fn render_pipeline(&self, key: PipelineKey) -> wgpu::RenderPipeline {
+        self.pipelines.lock().unwrap_or_else(PoisonError::into_inner).get(&self.device, key)
+    }
+
+    /// Returns `true` if the device can read storage buffers in vertex
+    /// shaders, which instanced rendering needs.  `false` on WebGL2.
+    pub fn supports_instancing(&self) -> bool {
+        self.adapter.get_downlevel_capabilities().flags.contains(wgpu::DownlevelFlags::VERTEX_STORAGE)
+            && self.device.limits().max_storage_buffers_per_shader_stage >= INSTANCE_LAYOUT_ENTRIES.len() as u32
+    }
+
+    /// Returns `true` if instanced draws can be culled on the GPU: compute
+    /// shaders, indirect draws and `INDIRECT_FIRST_INSTANCE` on top of
+    /// [`Self::supports_instancing`].
+    pub fn supports_gpu_culling(&self) -> bool {
+        let flags = self.adapter.get_downlevel_capabilities().flags;
+        self.supports_instancing()
+            && flags.contains(wgpu::DownlevelFlags::COMPUTE_SHADERS | wgpu::DownlevelFlags::INDIRECT_EXECUTION)
+            && self.device.features().contains(wgpu::Features::INDIRECT_FIRST_INSTANCE)
+    }
+
+    /// Bind group for group 2 of the instanced pass and, when culling on
+    /// the GPU, the indirect arguments written by the cull pass recorded
+    /// into `enc`.
+    fn prepare_instanced(
+        &self,
+        enc: &mut wgpu::CommandEncoder,
+        view_proj: &Matrix4,
+        instanced: &InstancedDraws,
+    ) -> (wgpu::BindGroup, Option<wgpu::Buffer>) {
+        if !instanced.gpu_culling {
+            return (self.instance_bind_group(instanced.instances), None);
+        }
+        let layout = self.bind_group_layout(Some("cull_bind_group_layout"), &GpuCuller::LAYOUT_ENTRIES);
+        let mut culler = self.culler.lock().unwrap_or_else(PoisonError::into_inner);
+        let culler = culler.get_or_insert_with(|| GpuCuller::new(&self.device, &layout));
+        let groups: Vec<CullGroup> = instanced.draws
+            .iter()
+            .map(|draw| {
+                let reach = draw.material.displacement.reach();
+                CullGroup::new(draw.mesh.bounding_sphere, draw.instances.clone(), draw.mesh.index_count, reach)
+            })
+            .collect();
+        let instance_count = instanced.instances.instance_count();
+        let (bytes, buffers) = culler.prepare(&self.device, &self.queue, view_proj, instance_count, &groups);
+        self.record_upload(bytes, buffers);
+        let cull_bind_group =
+            self.bind_group(Some("cull_bind_group"), &layout, &culler.bind_group_entries(instanced.instances));
+        culler.dispatch(enc, &cull_bind_group);
+
+        // The vertex shader reads the survivors in place of the draw order.
+        let instance_layout = self.bind_group_layout(Some("instance_bind_group_layout"), &INSTANCE_LAYOUT_ENTRIES);
+        let bind_group = self.bind_group(Some("culled_instance_bind_group"), &instance_layout, &[
+            wgpu::BindGroupEntry { binding: 0, resource: instanced.instances.data_buffer().as_entire_binding() },
+            wgpu::BindGroupEntry { binding: 1, resource: culler.visible_buffer().as_entire_binding() },
+        ]);
+        (bind_group, Some(culler.args_buffer().clone()))
+    }
+
+    /// Query `boxes` against the depth of the next frame rendered outside a
+    /// capture pass; see [`crate::occlusion`].
+    pub(crate) fn queue_occlusion_queries(&self, boxes: Vec<OcclusionBox>) {
+        let mut queries = self.occlusion.lock().unwrap_or_else(PoisonError::into_inner);
+        if boxes.is_empty() && queries.is_none() {
+            return;
+        }
+        let layout = self.bind_group_layout(Some("occlusion_bind_group_layout"), &OcclusionQueries::LAYOUT_ENTRIES);
+        let depth_format = self.depth_view.texture().format();
+        queries.get_or_insert_with(|| OcclusionQueries::new(&self.device, &layout, depth_format)).queue(boxes);
+    }
+
+    /// Results of the last occlusion pass as `(key, visible)` pairs, once
+    /// the GPU has handed them back.  Never waits.
+    pub(crate) fn occlusion_results(&self) -> Option<Vec<(usize, bool)>> {
+        let mut queries = self.occlusion.lock().unwrap_or_else(PoisonError::into_inner);
+        let queries = queries.as_mut().filter(|queries| queries.is_busy())?;
+        let _ = self.device.poll(wgpu::PollType::Poll);
+        queries.take_results()
+    }
+
+    /// Record the queued occlusion boxes against the frame's depth buffer.
+    fn record_occlusion_queries(&self, enc: &mut wgpu::CommandEncoder, view_proj: &Matrix4) {
+        let mut queries = self.occlusion.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(queries) = queries.as_mut().filter(|queries| queries.has_queued() && !queries.is_busy()) else {
+            return;
+        };
+        let layout = self.bind_group_layout(Some("occlusion_bind_group_layout"), &OcclusionQueries::LAYOUT_ENTRIES);
+        let bind_group = self.bind_group(Some("occlusion_bind_group"), &layout, &queries.bind_group_entries());
+        let viewport = self.fixed_aspect.is_some().then(|| self.viewport());
+        let (bytes, buffers) =
+            queries.record(&self.device, &self.queue, enc, &bind_group, &self.depth_view, view_proj, viewport);
+        self.record_upload(bytes, buffers);
+    }
+
+    /// The pipeline for `key` built from the instanced scene shader
+    /// (`instanced.wgsl`), whose group 2 is the storage buffers of an
+    /// [`InstanceBuffer`].  `key.shader` is ignored.  Needs
+    /// [`Self::supports_instancing`].
+    pub fn instanced_pipeline(&self, key: PipelineKey) -> wgpu::RenderPipeline {
+        let mut pipelines = self.instanced_pipelines.lock().unwrap_or_else(PoisonError::into_inner);
+        let pipelines = pipelines.get_or_insert_with(|| {
+            let layout = self.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Instanced Pipeline Layout"),
+                bind_group_layouts: &[
+                    Some(&self.camera_bind_group_layout),
+                    Some(&self.texture_bind_group_layout),
+                    Some(&self.bind_group_layout(Some("instance_bind_group_layout"), &INSTANCE_LAYOUT_ENTRIES)),
+                    Some(&self.model_bind_group_layout),
+                ],
+                immediate_size: 0,
+            });
+            let source = ShaderPreprocessor::new()
+                .process("instanced.wgsl", include_str!("instanced.wgsl"))
+                .expect("built-in shader preprocesses");
+            let shader = self.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("instanced.wgsl"),
+                source: wgpu::ShaderSource::Wgsl(source.into()),
+            });
+            PipelineCache::new(layout, shader)
+        });
+        pipelines.get(&self.device, key.with_shader(ShaderId::SCENE))
+    }
+
+    /// Upload the changes in `instances` and count them in the next
+    /// [`RenderStats`].
+    pub fn upload_instances(&self, instances: &mut InstanceBuffer) {
+        let (bytes, buffers) = instances.upload(&self.device, &self.queue);
+        self.record_upload(bytes, buffers);
+    }
+
+    /// The group 2 bind group of the instanced shader over `instances`,
+    /// cached until its buffers are reallocated.
+    pub fn instance_bind_group(&self, instances: &InstanceBuffer) -> wgpu::BindGroup {
+        let layout = self.bind_group_layout(Some("instance_bind_group_layout"), &INSTANCE_LAYOUT_ENTRIES);
+        self.bind_group(Some("instance_bind_group"), &layout, &[
+            wgpu::BindGroupEntry { binding: 0, resource: instances.data_buffer().as_entire_binding() },
+            wgpu::BindGroupEntry { binding: 1, resource: instances.order_buffer().as_entire_binding() },
+        ])
+    }
+
+    /// Compile WGSL `source` and register it for use in
+    /// [`PipelineKey::shader`].  Like [`Self::reload_shader`], the source
+    /// must keep the interface of the built-in `shader.wgsl` and be
+    /// preprocessed already.
+    pub fn add_shader(&self, label: &str, source: &str) -> ShaderId {
+        let module = self.device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some(label),
-            layout: &self.texture_bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&view) },
-                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&self.default_sampler) },
-            ],
+            source: wgpu::ShaderSource::Wgsl(source.into()),
         });
-        (texture, bind_group)
+        self.pipelines.lock().unwrap_or_else(PoisonError::into_inner).add_shader(module)
+    }
+
+    /// Bind the pipeline for `key` unless `bound` already holds it, so
+    /// consecutive draws of one variant switch pipelines once.
+    fn set_variant(&self, rp: &mut wgpu::RenderPass, bound: &mut Option<PipelineKey>, key: PipelineKey) {
+        if *bound != Some(key) {
+            rp.set_pipeline(&self.render_pipeline(key));
+            *bound = Some(key);
+        }
     }
+
+    /// [`builtin_pipeline_keys`] for the current surface and post settings.
+    fn builtin_pipeline_keys(&self) -> [PipelineKey; 3] {
+        builtin_pipeline_keys(self.post.scene_format(self.surface_config.format), self.surface_config.format)
+    }
+
+    fn bind_cache(&self) -> MutexGuard<'_, BindGroupCache> {
+        self.bind_cache.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Keys of the scene, overlay (skybox and gizmos) and screen pipelines
+/// `render_scene` draws with.  Scene layers target `scene_format`, which
+/// differs from the surface `output_format` while HDR is on.
+fn builtin_pipeline_keys(scene_format: wgpu::TextureFormat, output_format: wgpu::TextureFormat) -> [PipelineKey; 3] {
+    [PipelineKey::scene(scene_format), PipelineKey::overlay(scene_format), PipelineKey::screen(output_format)]
+}
+
+/// Create the colour target used by headless pipelines.  `COPY_SRC` allows
+/// reading frames back to the CPU.
+fn create_offscreen_target(device: &Device, width: u32, height: u32) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Offscreen Target"),
+        size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: HEADLESS_FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+            | wgpu::TextureUsages::COPY_SRC
+            | wgpu::TextureUsages::TEXTURE_BINDING,
+        view_formats: &[],
+    })
+}
+
+/// `key` for drawing `mesh`: its topology, and unlit for lines and points,
+/// which have no surface to light.
+fn mesh_key(key: PipelineKey, mesh: &BakedMesh) -> PipelineKey {
+    match mesh.topology {
+        Topology::TriangleList => key,
+        topology => key.with_topology(topology.primitive()).with_variant(key.variant.with_lit(false)),
+    }
+}
+
+/// Bind groups over the frame `uniforms`: the camera slot with the scene
+/// lighting and frame time for `@group(0)`, and the model slot for
+/// `@group(2)`.
+fn create_uniform_bind_groups(
+    device: &Device,
+    cache: &mut BindGroupCache,
+    uniforms: &UniformAllocator,
+    [light_buffer, frame_buffer]: [&wgpu::Buffer; 2],
+    camera_layout: &wgpu::BindGroupLayout,
+    model_layout: &wgpu::BindGroupLayout,
+) -> (wgpu::BindGroup, wgpu::BindGroup) {
+    let camera = cache.bind_group(device, Some("camera_bind_group"), camera_layout, &[
+        wgpu::BindGroupEntry { binding: 0, resource: uniforms.binding(CAMERA_UNIFORM_SIZE) },
+        wgpu::BindGroupEntry { binding: 1, resource: light_buffer.as_entire_binding() },
+        wgpu::BindGroupEntry { binding: 2, resource: frame_buffer.as_entire_binding() },
+    ]);
+    let model = cache.bind_group(device, Some("model_bind_group"), model_layout, &[
+        wgpu::BindGroupEntry { binding: 0, resource: uniforms.binding(size_of::<ModelUniform>() as u64) },
+    ]);
+    (camera, model)
 }
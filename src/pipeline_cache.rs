@@ -0,0 +1,495 @@
+//! Render pipeline permutations, created lazily and reused.
+//!
+//! Every combination of render state the engine draws with — target format,
+//! depth mode and bias, culling, blending, polygon mode, topology, sample
+//! count and shader — is a [`PipelineKey`].  [`PipelineCache::get`] creates the
+//! pipeline for a key the first time it is requested and returns the same
+//! handle afterwards, so new options only add keys instead of hand-built
+//! pipeline fields.
+//!
+//! ```no_run
+//! # use vertra::pipeline::{Pipeline, PipelineConfig};
+//! # use vertra::pipeline_cache::PipelineKey;
+//! # let pipeline = pollster::block_on(Pipeline::headless(&PipelineConfig::default(), 64, 64)).unwrap();
+//! let wireframe = PipelineKey::scene(pipeline.surface_config.format)
+//!     .with_polygon_mode(wgpu::PolygonMode::Line)
+//!     .with_cull_mode(None);
+//! let render_pipeline = pipeline.render_pipeline(wireframe);
+//! ```
+//!
+//! All pipelines share the scene bind group layout (camera, texture, model)
+//! and [`crate::mesh::Vertex`] layout, and use the `vs_main` / `fs_main`
+//! entry points of their shader.
+//!
+//! ## Shader variants
+//!
+//! [`PipelineKey::variant`] specialises one shader source into lit/unlit
+//! and textured/untextured permutations through WGSL pipeline-overridable
+//! constants, resolved when the pipeline is created:
+//!
+//! ```wgsl
+//! override LIT: bool = true;
+//! override TEXTURED: bool = true;
+//! ```
+//!
+//! Only constants that differ from these defaults are passed, so shaders
+//! used solely with [`ShaderVariant::DEFAULT`] need not declare them.
+//!
+//! [`PipelineKey::debug_view`] likewise sets `DEBUG_VIEW`, declared by the
+//! `vertra/debug.wgsl` chunk; see [`DebugView`].
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use serde::{Deserialize, Serialize};
+use wgpu::{Device, PipelineCompilationOptions};
+use crate::mesh::Vertex;
+use crate::pipeline::VERTEX_ATTRS;
+
+/// Format of the scene depth buffer.
+const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+
+/// A shader module registered with a [`PipelineCache`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ShaderId(usize);
+
+impl ShaderId {
+    /// The built-in `shader.wgsl`, or its replacement after
+    /// [`crate::pipeline::Pipeline::reload_shader`].
+    pub const SCENE: Self = Self(0);
+}
+
+/// Depth testing and writing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DepthMode {
+    /// Nearer fragments win and write depth (opaque scene geometry).
+    Test,
+    /// Nearer fragments win but leave depth untouched (decals, glass).
+    TestReadOnly,
+    /// Every fragment passes and depth is left untouched (skybox, gizmos).
+    Always,
+    /// No depth attachment (screen overlay).
+    Disabled,
+}
+
+/// Offset added to fragment depth before the depth test, as in
+/// [`wgpu::DepthBiasState`].  Negative values pull surfaces towards the
+/// camera, e.g. decals over the wall they lie on.
+///
+/// Compared and hashed by the bits of `slope_scale`, so it can be part of
+/// a [`PipelineKey`].
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct DepthBias {
+    /// Constant offset in units of the smallest depth step.
+    pub constant: i32,
+    /// Offset scaled by the fragment's depth slope.
+    pub slope_scale: f32,
+}
+
+impl DepthBias {
+    /// No offset.
+    pub const NONE: Self = Self { constant: 0, slope_scale: 0.0 };
+
+    fn bits(self) -> (i32, u32) {
+        (self.constant, self.slope_scale.to_bits())
+    }
+
+    fn state(self) -> wgpu::DepthBiasState {
+        wgpu::DepthBiasState { constant: self.constant, slope_scale: self.slope_scale, clamp: 0.0 }
+    }
+}
+
+impl PartialEq for DepthBias {
+    fn eq(&self, other: &Self) -> bool {
+        self.bits() == other.bits()
+    }
+}
+
+impl Eq for DepthBias {}
+
+impl Hash for DepthBias {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.bits().hash(state);
+    }
+}
+
+impl PartialOrd for DepthBias {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DepthBias {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.bits().cmp(&other.bits())
+    }
+}
+
+/// How fragments combine with the colour target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlendMode {
+    /// Replace the target colour.
+    Opaque,
+    /// Straight-alpha blending.
+    Alpha,
+    /// Add the fragment colour, weighted by its alpha.
+    Additive,
+}
+
+impl BlendMode {
+    fn state(self) -> Option<wgpu::BlendState> {
+        match self {
+            Self::Opaque => None,
+            Self::Alpha => Some(wgpu::BlendState::ALPHA_BLENDING),
+            Self::Additive => Some(wgpu::BlendState {
+                color: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::SrcAlpha,
+                    dst_factor: wgpu::BlendFactor::One,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha: wgpu::BlendComponent::OVER,
+            }),
+        }
+    }
+}
+
+/// Feature toggles baked into a pipeline through the shader's `override`
+/// constants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ShaderVariant {
+    /// Apply scene lighting (`LIT`).  Per-draw model flags can still switch
+    /// it off in a lit pipeline.
+    pub lit: bool,
+    /// Sample the bound texture (`TEXTURED`); untextured pipelines use white.
+    pub textured: bool,
+}
+
+impl ShaderVariant {
+    /// Lit and textured: the shader's own defaults.
+    pub const DEFAULT: Self = Self { lit: true, textured: true };
+
+    /// Set whether scene lighting applies.
+    pub fn with_lit(mut self, lit: bool) -> Self {
+        self.lit = lit;
+        self
+    }
+
+    /// Set whether the bound texture is sampled.
+    pub fn with_textured(mut self, textured: bool) -> Self {
+        self.textured = textured;
+        self
+    }
+
+    /// Override constants differing from [`Self::DEFAULT`], as passed to
+    /// pipeline creation.
+    pub fn constants(self) -> Vec<(&'static str, f64)> {
+        [("LIT", self.lit, Self::DEFAULT.lit), ("TEXTURED", self.textured, Self::DEFAULT.textured)]
+            .into_iter()
+            .filter(|&(_, value, default)| value != default)
+            .map(|(name, value, _)| (name, f64::from(u8::from(value))))
+            .collect()
+    }
+}
+
+impl Default for ShaderVariant {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// A visualisation replacing the shaded colour of scene geometry, selected
+/// with [`crate::scene::Scene::set_debug_view`].
+///
+/// Shaders opt in by including `vertra/debug.wgsl` and returning
+/// `debug_color(in)` from `fs_main` while `DEBUG_VIEW != 0u`, as the
+/// built-in scene shaders do.  The skybox, gizmos and screen overlay are
+/// drawn normally, and post-processing still applies.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DebugView {
+    /// Regular shading.
+    #[default]
+    Off,
+    /// Linear view depth: black at the eye, mid-grey 10 units away and
+    /// approaching white in the distance.
+    Depth,
+    /// World-space face normals, each axis mapped from `-1..1` to `0..1`.
+    Normals,
+    /// Every fragment adds a dim orange without depth testing, so areas
+    /// drawn over many times glow.  The frame is cleared to black.
+    Overdraw,
+    /// An 8×8 checkerboard per UV unit, tinted red by `u` and green by `v`.
+    UvChecker,
+}
+
+impl DebugView {
+    /// Every view, in `DEBUG_VIEW` order.
+    pub const ALL: [Self; 5] = [Self::Off, Self::Depth, Self::Normals, Self::Overdraw, Self::UvChecker];
+
+    /// `key` adjusted to draw this view: [`Self::Overdraw`] blends
+    /// additively and ignores depth.
+    pub fn apply(self, key: PipelineKey) -> PipelineKey {
+        let key = PipelineKey { debug_view: self, ..key };
+        match self {
+            Self::Overdraw => key.with_blend(BlendMode::Additive).with_depth(DepthMode::Always),
+            _ => key,
+        }
+    }
+
+    /// The `DEBUG_VIEW` override constant; `None` for [`Self::Off`].
+    pub fn constant(self) -> Option<(&'static str, f64)> {
+        let index = Self::ALL.iter().position(|&view| view == self).unwrap_or(0);
+        (index != 0).then_some(("DEBUG_VIEW", index as f64))
+    }
+}
+
+/// The render state identifying one pipeline permutation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PipelineKey {
+    /// Shader providing `vs_main` and `fs_main`.
+    pub shader: ShaderId,
+    /// Lit/unlit and textured/untextured specialisation of `shader`.
+    pub variant: ShaderVariant,
+    /// Debug visualisation replacing the shaded colour.
+    pub debug_view: DebugView,
+    /// Colour target format.
+    pub format: wgpu::TextureFormat,
+    /// Depth testing and writing.
+    pub depth: DepthMode,
+    /// Offset applied before the depth test; ignored without a depth
+    /// attachment.
+    pub depth_bias: DepthBias,
+    /// Faces discarded by culling, `None` for double-sided.
+    pub cull_mode: Option<wgpu::Face>,
+    /// Colour blending.
+    pub blend: BlendMode,
+    /// Fill or wireframe.  Falls back to `Fill` on devices without
+    /// `POLYGON_MODE_LINE` / `POLYGON_MODE_POINT`.
+    pub polygon_mode: wgpu::PolygonMode,
+    /// Primitive assembly; strip topologies restart on `u32::MAX`.
+    pub topology: wgpu::PrimitiveTopology,
+    /// MSAA samples; the colour and depth targets must match.
+    pub sample_count: u32,
+}
+
+impl PipelineKey {
+    /// Depth-tested, back-face culled, alpha-blended triangles: the scene's
+    /// world batches and registry meshes.
+    pub fn scene(format: wgpu::TextureFormat) -> Self {
+        Self {
+            shader: ShaderId::SCENE,
+            variant: ShaderVariant::DEFAULT,
+            debug_view: DebugView::Off,
+            format,
+            depth: DepthMode::Test,
+            depth_bias: DepthBias::NONE,
+            cull_mode: Some(wgpu::Face::Back),
+            blend: BlendMode::Alpha,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            sample_count: 1,
+        }
+    }
+
+    /// Double-sided triangles that ignore depth: the skybox and gizmos.
+    pub fn overlay(format: wgpu::TextureFormat) -> Self {
+        Self { depth: DepthMode::Always, cull_mode: None, ..Self::scene(format) }
+    }
+
+    /// Double-sided triangles without a depth attachment: the screen overlay.
+    pub fn screen(format: wgpu::TextureFormat) -> Self {
+        Self { depth: DepthMode::Disabled, cull_mode: None, ..Self::scene(format) }
+    }
+
+    /// Draw with `shader` instead of the scene shader.
+    pub fn with_shader(mut self, shader: ShaderId) -> Self {
+        self.shader = shader;
+        self
+    }
+
+    /// Specialise the shader with `variant`.
+    pub fn with_variant(mut self, variant: ShaderVariant) -> Self {
+        self.variant = variant;
+        self
+    }
+
+    /// Replace the shaded colour with `debug_view`.  Unlike
+    /// [`DebugView::apply`], the rest of the render state is kept.
+    pub fn with_debug_view(mut self, debug_view: DebugView) -> Self {
+        self.debug_view = debug_view;
+        self
+    }
+
+    /// Set the depth mode.
+    pub fn with_depth(mut self, depth: DepthMode) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Set the depth bias.
+    pub fn with_depth_bias(mut self, depth_bias: DepthBias) -> Self {
+        self.depth_bias = depth_bias;
+        self
+    }
+
+    /// Set the culled faces; `None` draws both sides.
+    pub fn with_cull_mode(mut self, cull_mode: Option<wgpu::Face>) -> Self {
+        self.cull_mode = cull_mode;
+        self
+    }
+
+    /// Set the blend mode.
+    pub fn with_blend(mut self, blend: BlendMode) -> Self {
+        self.blend = blend;
+        self
+    }
+
+    /// Set the polygon mode, e.g. `Line` for wireframe.
+    pub fn with_polygon_mode(mut self, polygon_mode: wgpu::PolygonMode) -> Self {
+        self.polygon_mode = polygon_mode;
+        self
+    }
+
+    /// Set the primitive topology.
+    pub fn with_topology(mut self, topology: wgpu::PrimitiveTopology) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    /// Set the MSAA sample count.
+    pub fn with_sample_count(mut self, sample_count: u32) -> Self {
+        self.sample_count = sample_count;
+        self
+    }
+}
+
+/// Render pipelines by [`PipelineKey`], sharing one pipeline layout.
+pub struct PipelineCache {
+    layout:    wgpu::PipelineLayout,
+    shaders:   Vec<wgpu::ShaderModule>,
+    pipelines: HashMap<PipelineKey, wgpu::RenderPipeline>,
+}
+
+impl PipelineCache {
+    /// An empty cache whose [`ShaderId::SCENE`] is `scene_shader`.
+    pub fn new(layout: wgpu::PipelineLayout, scene_shader: wgpu::ShaderModule) -> Self {
+        Self { layout, shaders: vec![scene_shader], pipelines: HashMap::new() }
+    }
+
+    /// Register a shader for use in [`PipelineKey::shader`].
+    pub fn add_shader(&mut self, module: wgpu::ShaderModule) -> ShaderId {
+        self.shaders.push(module);
+        ShaderId(self.shaders.len() - 1)
+    }
+
+    /// Swap the module behind `id` and drop every pipeline built from the
+    /// old one.  Returns `false` if `id` is unknown.
+    pub fn replace_shader(&mut self, id: ShaderId, module: wgpu::ShaderModule) -> bool {
+        let Some(slot) = self.shaders.get_mut(id.0) else { return false };
+        *slot = module;
+        self.pipelines.retain(|key, _| key.shader != id);
+        true
+    }
+
+    /// The pipeline for `key`, created on first request.
+    ///
+    /// # Panics
+    /// If `key.shader` was not registered with this cache.
+    pub fn get(&mut self, device: &Device, key: PipelineKey) -> wgpu::RenderPipeline {
+        if let Some(pipeline) = self.pipelines.get(&key) {
+            return pipeline.clone();
+        }
+        let pipeline = create_pipeline(device, &self.layout, &self.shaders[key.shader.0], key);
+        self.pipelines.insert(key, pipeline.clone());
+        pipeline
+    }
+
+    /// Store `pipeline` for `key`, replacing any cached one.
+    pub(crate) fn insert(&mut self, key: PipelineKey, pipeline: wgpu::RenderPipeline) {
+        self.pipelines.insert(key, pipeline);
+    }
+
+    /// Returns `true` if the pipeline for `key` has been created.
+    pub fn contains(&self, key: &PipelineKey) -> bool {
+        self.pipelines.contains_key(key)
+    }
+
+    /// Number of cached pipelines.
+    pub fn len(&self) -> usize {
+        self.pipelines.len()
+    }
+
+    /// Returns `true` if no pipelines are cached.
+    pub fn is_empty(&self) -> bool {
+        self.pipelines.is_empty()
+    }
+}
+
+/// Build the pipeline for `key` from `shader`.
+///
+/// A variant or debug view whose constants the shader does not declare
+/// fails validation.
+pub(crate) fn create_pipeline(
+    device: &Device,
+    layout: &wgpu::PipelineLayout,
+    shader: &wgpu::ShaderModule,
+    key: PipelineKey,
+) -> wgpu::RenderPipeline {
+    let polygon_mode = match key.polygon_mode {
+        wgpu::PolygonMode::Line if !device.features().contains(wgpu::Features::POLYGON_MODE_LINE) => {
+            wgpu::PolygonMode::Fill
+        }
+        wgpu::PolygonMode::Point if !device.features().contains(wgpu::Features::POLYGON_MODE_POINT) => {
+            wgpu::PolygonMode::Fill
+        }
+        mode => mode,
+    };
+    let depth_stencil = |write: bool, compare| wgpu::DepthStencilState {
+        format: DEPTH_FORMAT,
+        depth_write_enabled: Some(write),
+        depth_compare: Some(compare),
+        stencil: wgpu::StencilState::default(),
+        bias: key.depth_bias.state(),
+    };
+    let mut constants = key.variant.constants();
+    constants.extend(key.debug_view.constant());
+    let compilation_options = PipelineCompilationOptions { constants: &constants, ..Default::default() };
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some(&format!("{key:?}")),
+        layout: Some(layout),
+        cache: None, multiview_mask: None,
+        vertex: wgpu::VertexState {
+            module: shader, entry_point: Some("vs_main"),
+            compilation_options: compilation_options.clone(),
+            buffers: &[wgpu::VertexBufferLayout {
+                array_stride: size_of::<Vertex>() as wgpu::BufferAddress,
+                step_mode: wgpu::VertexStepMode::Vertex,
+                attributes: &VERTEX_ATTRS,
+            }],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader, entry_point: Some("fs_main"),
+            compilation_options,
+            targets: &[Some(wgpu::ColorTargetState {
+                format: key.format,
+                blend: key.blend.state(),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: key.topology,
+            strip_index_format: key.topology.is_strip().then_some(wgpu::IndexFormat::Uint32),
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: key.cull_mode,
+            unclipped_depth: false,
+            polygon_mode,
+            conservative: false,
+        },
+        depth_stencil: match key.depth {
+            DepthMode::Test => Some(depth_stencil(true, wgpu::CompareFunction::Less)),
+            DepthMode::TestReadOnly => Some(depth_stencil(false, wgpu::CompareFunction::Less)),
+            DepthMode::Always => Some(depth_stencil(false, wgpu::CompareFunction::Always)),
+            DepthMode::Disabled => None,
+        },
+        multisample: wgpu::MultisampleState { count: key.sample_count, ..Default::default() },
+    })
+}
@@ -0,0 +1,988 @@
+//! Post-processing: HDR rendering, depth of field, bloom, exposure and
+//! tonemapping, FXAA, and the full-screen fade used by scene transitions.
+//!
+//! With every pass off (the default) the scene is drawn straight into the
+//! surface and only the fade runs on top.  With [`PostSettings::hdr`] on, the
+//! scene is drawn into an [`HDR_FORMAT`] target so lighting may exceed
+//! `1.0`; a tonemap pass then scales it by the exposure and maps it to the
+//! display range.  [`PostSettings::fxaa`] adds an anti-aliasing pass over the
+//! display-range image, reading it from an intermediate target in the
+//! surface format.  [`PostSettings::depth_of_field`] blurs the scene target
+//! by distance from [`crate::camera::Camera::focus_distance`] before any of
+//! that:
+//!
+//! ```text
+//! scene ──▶ [DoF] ──▶ HDR target ──▶ [bloom] ──▶ [auto exposure] ──▶ tonemap ──┐
+//!             └─────────(HDR off)─────────────────────────────────────────────┴──▶ [FXAA] ──▶ surface ──▶ fade
+//! ```
+//!
+//! [`PostSettings::bloom`] makes light past the display range glow: a pass
+//! keeps whatever the HDR target holds above [`Bloom::threshold`] in a
+//! half-resolution target, two passes blur it, and the tonemapper adds it
+//! back.  Lit surfaces seldom get that bright, so the glow mostly comes
+//! from [`crate::material::Emission`].
+//!
+//! The exposure is [`crate::camera::Camera::exposure`].  With
+//! [`PostSettings::auto_exposure`] set, a compute pass builds a luminance
+//! histogram of every frame and adapts the exposure towards middle grey over
+//! time; the camera exposure then acts as compensation on top.  Auto
+//! exposure needs compute shaders, so it is skipped on WebGL2 (see
+//! [`crate::pipeline::Pipeline::supports_auto_exposure`]).
+//!
+//! ```no_run
+//! # use vertra::post::{AutoExposure, PostSettings};
+//! # fn demo(scene: &mut vertra::scene::Scene) {
+//! scene.pipeline.set_post_settings(
+//!     PostSettings::default().with_hdr(true).with_auto_exposure(Some(AutoExposure::default())),
+//! );
+//! scene.camera.exposure = 1.5; // one half stop brighter than auto
+//! # }
+//! ```
+
+use wgpu::{Device, PipelineCompilationOptions};
+
+use crate::camera::Camera;
+use crate::constants::post;
+use crate::pipeline::{self, GpuMemory, RenderStats};
+
+/// Colour format of the intermediate scene target when HDR is enabled.
+pub const HDR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+/// Workgroup edge length of the histogram compute pass (`exposure.wgsl`).
+const HISTOGRAM_WORKGROUP: u32 = 16;
+/// Number of histogram bins (`exposure.wgsl`).
+const HISTOGRAM_BINS: u64 = 256;
+
+/// Auto-exposure tuning.  See the [module docs](self).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AutoExposure {
+    /// Darkest luminance considered, in log2 units.  Darker pixels count as
+    /// the darkest histogram bin.
+    pub min_log_luminance: f32,
+    /// Brightest luminance considered, in log2 units.
+    pub max_log_luminance: f32,
+    /// Adaptation rate in 1/s; higher values react faster.
+    pub speed: f32,
+    /// Average scene luminance the exposure maps to.
+    pub key: f32,
+}
+
+impl Default for AutoExposure {
+    fn default() -> Self {
+        Self {
+            min_log_luminance: post::MIN_LOG_LUMINANCE,
+            max_log_luminance: post::MAX_LOG_LUMINANCE,
+            speed: post::ADAPTATION_SPEED,
+            key: post::EXPOSURE_KEY,
+        }
+    }
+}
+
+/// Bloom tuning.  See the [module docs](self).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bloom {
+    /// Brightness (largest colour channel, before exposure) above which
+    /// pixels glow.
+    pub threshold: f32,
+    /// How strongly the glow is added back.
+    pub intensity: f32,
+    /// Blur reach, in pixels of the half-resolution bloom target.
+    pub radius: f32,
+}
+
+impl Default for Bloom {
+    fn default() -> Self {
+        Self { threshold: post::BLOOM_THRESHOLD, intensity: post::BLOOM_INTENSITY, radius: post::BLOOM_RADIUS }
+    }
+}
+
+/// Which post-processing passes run after the scene is drawn.
+///
+/// Apply with [`crate::pipeline::Pipeline::set_post_settings`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PostSettings {
+    /// Render into an [`HDR_FORMAT`] target and tonemap into the surface.
+    pub hdr: bool,
+    /// Adapt the exposure to the frame's brightness.  Only used with
+    /// [`Self::hdr`].
+    pub auto_exposure: Option<AutoExposure>,
+    /// Smooth jagged edges with FXAA: one cheap full-screen pass instead of
+    /// multisampled targets, which suits WebGL2 and low-end GPUs.  Slightly
+    /// softens texture detail.
+    pub fxaa: bool,
+    /// Blur the image by distance from the camera's focus plane, using
+    /// [`crate::camera::Camera::focus_distance`] and
+    /// [`crate::camera::Camera::aperture`].
+    pub depth_of_field: bool,
+    /// Make the brightest parts of the frame glow.  Only used with
+    /// [`Self::hdr`].
+    pub bloom: Option<Bloom>,
+}
+
+impl PostSettings {
+    /// Enable or disable the HDR target and tonemapper.
+    pub fn with_hdr(mut self, hdr: bool) -> Self {
+        self.hdr = hdr;
+        self
+    }
+
+    /// Enable (`Some`) or disable (`None`) auto exposure.
+    pub fn with_auto_exposure(mut self, auto_exposure: Option<AutoExposure>) -> Self {
+        self.auto_exposure = auto_exposure;
+        self
+    }
+
+    /// Enable or disable the FXAA pass.
+    pub fn with_fxaa(mut self, fxaa: bool) -> Self {
+        self.fxaa = fxaa;
+        self
+    }
+
+    /// Enable or disable the depth-of-field pass.
+    pub fn with_depth_of_field(mut self, depth_of_field: bool) -> Self {
+        self.depth_of_field = depth_of_field;
+        self
+    }
+
+    /// Enable (`Some`) or disable (`None`) bloom.
+    pub fn with_bloom(mut self, bloom: Option<Bloom>) -> Self {
+        self.bloom = bloom;
+        self
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct ExposureParams {
+    min_log_luminance: f32,
+    log_luminance_range: f32,
+    adaptation: f32,
+    key: f32,
+    compensation: f32,
+    pixel_count: f32,
+    _pad: [f32; 2],
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct DofParams {
+    focus_distance: f32,
+    aperture: f32,
+    znear: f32,
+    zfar: f32,
+    direction: [f32; 2],
+    _pad: [f32; 2],
+}
+
+/// Full-screen colour fade drawn after all other layers (see
+/// [`crate::pipeline::Pipeline::set_fade`]).
+pub(crate) struct FadePass {
+    pipeline: wgpu::RenderPipeline,
+    buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    pub(crate) color: [f32; 4],
+}
+
+impl FadePass {
+    pub(crate) fn new(device: &Device, format: wgpu::TextureFormat) -> Self {
+        let shader = device.create_shader_module(wgpu::include_wgsl!("fade.wgsl"));
+        let buffer = create_uniform_buffer(device, "Fade Uniform Buffer", size_of::<[f32; 4]>());
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[uniform_entry(0, wgpu::ShaderStages::FRAGMENT)],
+            label: Some("fade_bind_group_layout"),
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &layout,
+            entries: &[wgpu::BindGroupEntry { binding: 0, resource: buffer.as_entire_binding() }],
+            label: Some("fade_bind_group"),
+        });
+        let pipeline = create_fullscreen_pipeline(
+            device,
+            "Fade Pipeline",
+            &layout,
+            &shader,
+            format,
+            Some(wgpu::BlendState::ALPHA_BLENDING),
+            true,
+        );
+        Self { pipeline, buffer, bind_group, color: [0.0; 4] }
+    }
+
+    /// Set the colour, uploading it only if it changed.  Returns the bytes
+    /// written.
+    pub(crate) fn set_color(&mut self, queue: &wgpu::Queue, color: [f32; 4]) -> u64 {
+        if self.color == color {
+            return 0;
+        }
+        self.color = color;
+        queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(&color));
+        size_of_val(&color) as u64
+    }
+
+    /// Draw the fade into `rp` if it is visible.
+    pub(crate) fn draw(&self, rp: &mut wgpu::RenderPass<'_>, stats: &mut RenderStats) {
+        if self.color[3] > 0.0 {
+            rp.set_pipeline(&self.pipeline);
+            rp.set_bind_group(0, &self.bind_group, &[]);
+            rp.draw(0..3, 0..1);
+            stats.draw_calls += 1;
+            stats.instance_count += 1;
+        }
+    }
+}
+
+/// Histogram buffers and pipelines for auto exposure.  Kept across resizes
+/// so the adapted exposure survives them.
+struct ExposurePass {
+    layout: wgpu::BindGroupLayout,
+    histogram_pipeline: wgpu::ComputePipeline,
+    average_pipeline: wgpu::ComputePipeline,
+    params_buffer: wgpu::Buffer,
+    histogram_buffer: wgpu::Buffer,
+    state_buffer: wgpu::Buffer,
+}
+
+impl ExposurePass {
+    fn new(device: &Device) -> Self {
+        let shader = device.create_shader_module(wgpu::include_wgsl!("exposure.wgsl"));
+        let storage = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only: false },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                uniform_entry(0, wgpu::ShaderStages::COMPUTE),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                    },
+                    count: None,
+                },
+                storage(2),
+                storage(3),
+            ],
+            label: Some("exposure_bind_group_layout"),
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Exposure Pipeline Layout"),
+            bind_group_layouts: &[Some(&layout)],
+            immediate_size: 0,
+        });
+        let compute = |label, entry_point| {
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some(label),
+                layout: Some(&pipeline_layout),
+                module: &shader,
+                entry_point: Some(entry_point),
+                compilation_options: PipelineCompilationOptions::default(),
+                cache: None,
+            })
+        };
+        let histogram_pipeline = compute("Exposure Histogram Pipeline", "build_histogram");
+        let average_pipeline = compute("Exposure Average Pipeline", "average");
+
+        let params_buffer = create_uniform_buffer(device, "Exposure Params Buffer", size_of::<ExposureParams>());
+        let histogram_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Exposure Histogram Buffer"),
+            size: HISTOGRAM_BINS * 4,
+            usage: wgpu::BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+        // adapted, output, average luminance, padding; zero = not adapted yet.
+        let state_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Exposure State Buffer"),
+            size: 16,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        Self { layout, histogram_pipeline, average_pipeline, params_buffer, histogram_buffer, state_buffer }
+    }
+
+    fn bind_group(&self, device: &Device, hdr_view: &wgpu::TextureView) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: self.params_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(hdr_view) },
+                wgpu::BindGroupEntry { binding: 2, resource: self.histogram_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 3, resource: self.state_buffer.as_entire_binding() },
+            ],
+            label: Some("exposure_bind_group"),
+        })
+    }
+}
+
+/// Separable depth-of-field blur: a horizontal pass into a scratch target,
+/// then a vertical one back out.  Built for one scene format.
+struct DofPass {
+    format: wgpu::TextureFormat,
+    layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::RenderPipeline,
+    /// Uniforms of the horizontal and vertical pass.
+    buffers: [wgpu::Buffer; 2],
+}
+
+impl DofPass {
+    fn new(device: &Device, format: wgpu::TextureFormat) -> Self {
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                uniform_entry(0, wgpu::ShaderStages::FRAGMENT),
+                texture_entry(1),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                    },
+                    count: None,
+                },
+            ],
+            label: Some("dof_bind_group_layout"),
+        });
+        let shader = device.create_shader_module(wgpu::include_wgsl!("dof.wgsl"));
+        let pipeline = create_fullscreen_pipeline(device, "DoF Pipeline", &layout, &shader, format, None, false);
+        let buffers = [
+            create_uniform_buffer(device, "DoF Horizontal Uniform Buffer", size_of::<DofParams>()),
+            create_uniform_buffer(device, "DoF Vertical Uniform Buffer", size_of::<DofParams>()),
+        ];
+        Self { format, layout, pipeline, buffers }
+    }
+
+    fn bind_group(
+        &self,
+        device: &Device,
+        pass: usize,
+        color: &wgpu::TextureView,
+        depth: &wgpu::TextureView,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: self.buffers[pass].as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(color) },
+                wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::TextureView(depth) },
+            ],
+            label: Some("dof_bind_group"),
+        })
+    }
+}
+
+/// Scratch target between the two blur passes, and the bind groups of both.
+struct DofTarget {
+    view: wgpu::TextureView,
+    bind_groups: [wgpu::BindGroup; 2],
+}
+
+/// Bloom threshold and blur passes, all drawn by one pipeline into
+/// [`HDR_FORMAT`] targets.
+struct BloomPass {
+    layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::RenderPipeline,
+    /// Uniforms of the threshold, horizontal and vertical pass.
+    buffers: [wgpu::Buffer; 3],
+}
+
+impl BloomPass {
+    fn new(device: &Device) -> Self {
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[uniform_entry(0, wgpu::ShaderStages::FRAGMENT), texture_entry(1), sampler_entry(2)],
+            label: Some("bloom_bind_group_layout"),
+        });
+        let shader = device.create_shader_module(wgpu::include_wgsl!("bloom.wgsl"));
+        let pipeline = create_fullscreen_pipeline(device, "Bloom Pipeline", &layout, &shader, HDR_FORMAT, None, false);
+        let buffers = [
+            create_uniform_buffer(device, "Bloom Threshold Uniform Buffer", size_of::<[f32; 4]>()),
+            create_uniform_buffer(device, "Bloom Horizontal Uniform Buffer", size_of::<[f32; 4]>()),
+            create_uniform_buffer(device, "Bloom Vertical Uniform Buffer", size_of::<[f32; 4]>()),
+        ];
+        Self { layout, pipeline, buffers }
+    }
+
+    fn bind_group(
+        &self,
+        device: &Device,
+        pass: usize,
+        source: &wgpu::TextureView,
+        sampler: &wgpu::Sampler,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: self.buffers[pass].as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(source) },
+                wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::Sampler(sampler) },
+            ],
+            label: Some("bloom_bind_group"),
+        })
+    }
+}
+
+/// Half-resolution bloom targets: the thresholded image, blurred into the
+/// second and back into the first, and the bind groups of the three passes.
+struct BloomTarget {
+    views: [wgpu::TextureView; 2],
+    bind_groups: [wgpu::BindGroup; 3],
+}
+
+/// The HDR scene target and the bind groups that read it.
+struct HdrTarget {
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    tonemap_bind_group: wgpu::BindGroup,
+    exposure_bind_group: Option<wgpu::BindGroup>,
+    bloom: Option<BloomTarget>,
+}
+
+/// Display-range target in the surface format, read by FXAA.  Also the
+/// scene target when depth of field runs without HDR.
+struct LdrTarget {
+    view: wgpu::TextureView,
+    fxaa_bind_group: wgpu::BindGroup,
+}
+
+/// GPU side of [`PostSettings`], owned by the pipeline.
+pub(crate) struct PostChain {
+    settings: PostSettings,
+    /// Surface format: the output of every pass, and of the LDR target.
+    format: wgpu::TextureFormat,
+    compute_supported: bool,
+    sampler: wgpu::Sampler,
+    tonemap_layout: wgpu::BindGroupLayout,
+    tonemap_pipeline: wgpu::RenderPipeline,
+    tonemap_buffer: wgpu::Buffer,
+    fxaa_layout: wgpu::BindGroupLayout,
+    fxaa_pipeline: wgpu::RenderPipeline,
+    exposure: Option<ExposurePass>,
+    dof: Option<DofPass>,
+    bloom: Option<BloomPass>,
+    hdr_target: Option<HdrTarget>,
+    ldr_target: Option<LdrTarget>,
+    dof_target: Option<DofTarget>,
+    pub(crate) fade: FadePass,
+}
+
+impl PostChain {
+    pub(crate) fn new(device: &Device, adapter: &wgpu::Adapter, format: wgpu::TextureFormat) -> Self {
+        let compute_supported = adapter
+            .get_downlevel_capabilities()
+            .flags
+            .contains(wgpu::DownlevelFlags::COMPUTE_SHADERS)
+            && device.limits().max_storage_buffers_per_shader_stage >= 2;
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("post_sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let tonemap_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                uniform_entry(0, wgpu::ShaderStages::FRAGMENT),
+                texture_entry(1),
+                sampler_entry(2),
+                texture_entry(3),
+            ],
+            label: Some("tonemap_bind_group_layout"),
+        });
+        let shader = device.create_shader_module(wgpu::include_wgsl!("tonemap.wgsl"));
+        let tonemap_pipeline =
+            create_fullscreen_pipeline(device, "Tonemap Pipeline", &tonemap_layout, &shader, format, None, true);
+        let tonemap_buffer = create_uniform_buffer(device, "Tonemap Uniform Buffer", size_of::<[f32; 4]>());
+
+        let fxaa_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[texture_entry(0), sampler_entry(1)],
+            label: Some("fxaa_bind_group_layout"),
+        });
+        let shader = device.create_shader_module(wgpu::include_wgsl!("fxaa.wgsl"));
+        let fxaa_pipeline =
+            create_fullscreen_pipeline(device, "FXAA Pipeline", &fxaa_layout, &shader, format, None, true);
+
+        Self {
+            settings: PostSettings::default(),
+            format,
+            compute_supported,
+            sampler,
+            tonemap_layout,
+            tonemap_pipeline,
+            tonemap_buffer,
+            fxaa_layout,
+            fxaa_pipeline,
+            exposure: None,
+            dof: None,
+            bloom: None,
+            hdr_target: None,
+            ldr_target: None,
+            dof_target: None,
+            fade: FadePass::new(device, format),
+        }
+    }
+
+    pub(crate) fn settings(&self) -> &PostSettings {
+        &self.settings
+    }
+
+    pub(crate) fn compute_supported(&self) -> bool {
+        self.compute_supported
+    }
+
+    /// Whether the auto-exposure pass runs this frame.
+    fn auto_exposure(&self) -> Option<&AutoExposure> {
+        self.settings.auto_exposure.as_ref().filter(|_| self.settings.hdr && self.compute_supported)
+    }
+
+    /// Whether the bloom passes run this frame.
+    fn bloom_settings(&self) -> Option<&Bloom> {
+        self.settings.bloom.as_ref().filter(|_| self.settings.hdr)
+    }
+
+    /// Colour format the scene pipelines must target.
+    pub(crate) fn scene_format(&self, surface_format: wgpu::TextureFormat) -> wgpu::TextureFormat {
+        if self.settings.hdr { HDR_FORMAT } else { surface_format }
+    }
+
+    /// Uniform and storage buffers and intermediate targets held by the
+    /// passes, including the fade.
+    pub(crate) fn gpu_memory(&self) -> GpuMemory {
+        let mut uniforms = vec![&self.tonemap_buffer, &self.fade.buffer];
+        let mut storage = Vec::new();
+        if let Some(exposure) = &self.exposure {
+            uniforms.push(&exposure.params_buffer);
+            storage.extend([&exposure.histogram_buffer, &exposure.state_buffer]);
+        }
+        if let Some(dof) = &self.dof {
+            uniforms.extend(&dof.buffers);
+        }
+        if let Some(bloom) = &self.bloom {
+            uniforms.extend(&bloom.buffers);
+        }
+        let bloom_views = self.hdr_target.as_ref().and_then(|t| t.bloom.as_ref()).map_or(&[][..], |b| &b.views);
+        let targets = [
+            self.hdr_target.as_ref().map(|t| &t.view),
+            self.ldr_target.as_ref().map(|t| &t.view),
+            self.dof_target.as_ref().map(|t| &t.view),
+        ];
+        GpuMemory {
+            uniform_bytes: uniforms.iter().map(|b| b.size()).sum(),
+            storage_bytes: storage.iter().map(|b| b.size()).sum(),
+            render_target_bytes: targets.into_iter().flatten().chain(bloom_views)
+                .map(|v| pipeline::texture_bytes(v.texture()))
+                .sum(),
+            ..GpuMemory::default()
+        }
+    }
+
+    /// The view the scene is drawn into, or `None` to draw into the surface.
+    pub(crate) fn scene_view(&self) -> Option<&wgpu::TextureView> {
+        self.hdr_target.as_ref().map(|t| &t.view).or(self.ldr_target.as_ref().map(|t| &t.view))
+    }
+
+    /// Apply `settings`, creating or dropping GPU resources as needed.
+    pub(crate) fn set_settings(
+        &mut self,
+        device: &Device,
+        settings: PostSettings,
+        width: u32,
+        height: u32,
+        depth_view: &wgpu::TextureView,
+    ) {
+        self.settings = settings;
+        if self.auto_exposure().is_some() && self.exposure.is_none() {
+            self.exposure = Some(ExposurePass::new(device));
+        }
+        if self.bloom_settings().is_some() && self.bloom.is_none() {
+            self.bloom = Some(BloomPass::new(device));
+        }
+        let format = self.scene_format(self.format);
+        self.dof = match self.dof.take() {
+            Some(dof) if self.settings.depth_of_field && dof.format == format => Some(dof),
+            _ => self.settings.depth_of_field.then(|| DofPass::new(device, format)),
+        };
+        self.resize(device, width, height, depth_view);
+    }
+
+    /// Re-create the intermediate targets for a new surface size.
+    /// `depth_view` is the scene depth buffer, read by depth of field.
+    pub(crate) fn resize(&mut self, device: &Device, width: u32, height: u32, depth_view: &wgpu::TextureView) {
+        self.hdr_target = self.settings.hdr.then(|| {
+            let texture = create_target(device, "HDR Scene Target", HDR_FORMAT, width, height);
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            let bloom = self.bloom.as_ref().filter(|_| self.bloom_settings().is_some()).map(|pass| {
+                let views = ["Bloom Target", "Bloom Scratch Target"].map(|label| {
+                    create_target(device, label, HDR_FORMAT, (width / 2).max(1), (height / 2).max(1))
+                        .create_view(&wgpu::TextureViewDescriptor::default())
+                });
+                let sources = [&view, &views[0], &views[1]];
+                let bind_groups = [0, 1, 2].map(|i| pass.bind_group(device, i, sources[i], &self.sampler));
+                BloomTarget { views, bind_groups }
+            });
+            // With bloom off the HDR target stands in for it, at zero
+            // intensity.
+            let glow = bloom.as_ref().map_or(&view, |b| &b.views[0]);
+            let tonemap_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: &self.tonemap_layout,
+                entries: &[
+                    wgpu::BindGroupEntry { binding: 0, resource: self.tonemap_buffer.as_entire_binding() },
+                    wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(&view) },
+                    wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::Sampler(&self.sampler) },
+                    wgpu::BindGroupEntry { binding: 3, resource: wgpu::BindingResource::TextureView(glow) },
+                ],
+                label: Some("tonemap_bind_group"),
+            });
+            let exposure_bind_group = self.exposure.as_ref().map(|e| e.bind_group(device, &view));
+            HdrTarget { texture, view, tonemap_bind_group, exposure_bind_group, bloom }
+        });
+        let ldr_scene = self.settings.depth_of_field && !self.settings.hdr;
+        self.ldr_target = (self.settings.fxaa || ldr_scene).then(|| {
+            let texture = create_target(device, "LDR Post Target", self.format, width, height);
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            let fxaa_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: &self.fxaa_layout,
+                entries: &[
+                    wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&view) },
+                    wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&self.sampler) },
+                ],
+                label: Some("fxaa_bind_group"),
+            });
+            LdrTarget { view, fxaa_bind_group }
+        });
+        self.dof_target = self.dof.as_ref().zip(self.scene_view()).map(|(dof, scene_view)| {
+            let texture = create_target(device, "DoF Scratch Target", dof.format, width, height);
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            let bind_groups =
+                [dof.bind_group(device, 0, scene_view, depth_view), dof.bind_group(device, 1, &view, depth_view)];
+            DofTarget { view, bind_groups }
+        });
+    }
+
+    /// Upload this frame's uniforms.  Returns the bytes written.
+    pub(crate) fn prepare(&self, queue: &wgpu::Queue, camera: &Camera, dt: f32) -> u64 {
+        let mut bytes = 0;
+        if let Some(dof) = &self.dof {
+            for (buffer, direction) in dof.buffers.iter().zip([[1.0, 0.0], [0.0, 1.0]]) {
+                let params = DofParams {
+                    focus_distance: camera.focus_distance,
+                    aperture: camera.aperture,
+                    znear: camera.znear,
+                    zfar: camera.zfar,
+                    direction,
+                    _pad: [0.0; 2],
+                };
+                queue.write_buffer(buffer, 0, bytemuck::bytes_of(&params));
+                bytes += size_of::<DofParams>() as u64;
+            }
+        }
+
+        let Some(target) = &self.hdr_target else { return bytes };
+        let bloom = self.bloom_settings().filter(|_| target.bloom.is_some());
+        if let (Some(settings), Some(pass)) = (bloom, &self.bloom) {
+            for (buffer, [dx, dy]) in pass.buffers.iter().zip([[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]]) {
+                let params = [settings.threshold, settings.radius, dx, dy];
+                queue.write_buffer(buffer, 0, bytemuck::cast_slice(&params));
+                bytes += size_of_val(&params) as u64;
+            }
+        }
+        let exposure = camera.exposure;
+        let tonemap = [exposure, bloom.map_or(0.0, |b| b.intensity), 0.0, 0.0];
+        queue.write_buffer(&self.tonemap_buffer, 0, bytemuck::cast_slice(&tonemap));
+        bytes += size_of_val(&tonemap) as u64;
+
+        if let (Some(auto), Some(pass)) = (self.auto_exposure(), &self.exposure) {
+            let size = target.texture.size();
+            let params = ExposureParams {
+                min_log_luminance: auto.min_log_luminance,
+                log_luminance_range: (auto.max_log_luminance - auto.min_log_luminance).max(f32::EPSILON),
+                adaptation: 1.0 - (-dt * auto.speed).exp(),
+                key: auto.key,
+                compensation: exposure,
+                pixel_count: (size.width * size.height) as f32,
+                _pad: [0.0; 2],
+            };
+            queue.write_buffer(&pass.params_buffer, 0, bytemuck::bytes_of(&params));
+            bytes += size_of::<ExposureParams>() as u64;
+        }
+        bytes
+    }
+
+    /// Run the enabled passes over the scene target, ending in `output`,
+    /// and draw the fade last.  `depth_view` is attached without depth
+    /// testing so the fullscreen pipelines match the scene pass.  No-op when
+    /// the scene was drawn straight into `output`.
+    pub(crate) fn resolve(
+        &self,
+        enc: &mut wgpu::CommandEncoder,
+        output: &wgpu::TextureView,
+        depth_view: &wgpu::TextureView,
+        stats: &mut RenderStats,
+    ) {
+        if let (Some(dof), Some(target), Some(scene_view)) = (&self.dof, &self.dof_target, self.scene_view()) {
+            // Without HDR or FXAA nothing else reads the blurred image, so
+            // the vertical pass writes it straight out.
+            let direct = !self.settings.hdr && !self.settings.fxaa;
+            let dest = if direct { output } else { scene_view };
+            let passes = [
+                ("DoF Horizontal Pass", &target.view, &target.bind_groups[0]),
+                ("DoF Vertical Pass", dest, &target.bind_groups[1]),
+            ];
+            for (label, view, bind_group) in passes {
+                let mut rp = enc.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some(label),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view,
+                        resolve_target: None,
+                        ops: wgpu::Operations { load: wgpu::LoadOp::Clear(wgpu::Color::BLACK), store: wgpu::StoreOp::Store },
+                        depth_slice: None,
+                    })],
+                    ..Default::default()
+                });
+                rp.set_pipeline(&dof.pipeline);
+                rp.set_bind_group(0, bind_group, &[]);
+                rp.draw(0..3, 0..1);
+                stats.draw_calls += 1;
+                stats.instance_count += 1;
+            }
+            if direct && self.fade.color[3] > 0.0 {
+                let mut rp = enc.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("Fade Pass"),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: output,
+                        resolve_target: None,
+                        ops: wgpu::Operations { load: wgpu::LoadOp::Load, store: wgpu::StoreOp::Store },
+                        depth_slice: None,
+                    })],
+                    depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                        view: depth_view,
+                        depth_ops: Some(wgpu::Operations { load: wgpu::LoadOp::Load, store: wgpu::StoreOp::Store }),
+                        stencil_ops: None,
+                    }),
+                    ..Default::default()
+                });
+                self.fade.draw(&mut rp, stats);
+            }
+        }
+        if let Some(target) = &self.hdr_target {
+            self.tonemap(enc, target, output, depth_view, stats);
+        }
+        if let Some(target) = self.ldr_target.as_ref().filter(|_| self.settings.fxaa) {
+            let mut rp = begin_fullscreen_pass(enc, "FXAA Pass", output, depth_view);
+            rp.set_pipeline(&self.fxaa_pipeline);
+            rp.set_bind_group(0, &target.fxaa_bind_group, &[]);
+            rp.draw(0..3, 0..1);
+            stats.draw_calls += 1;
+            stats.instance_count += 1;
+            self.fade.draw(&mut rp, stats);
+        }
+    }
+
+    /// Bloom and auto exposure, then tonemap into the LDR target (FXAA on)
+    /// or `output`.
+    fn tonemap(
+        &self,
+        enc: &mut wgpu::CommandEncoder,
+        target: &HdrTarget,
+        output: &wgpu::TextureView,
+        depth_view: &wgpu::TextureView,
+        stats: &mut RenderStats,
+    ) {
+        if let (Some(pass), Some(bloom)) = (&self.bloom, &target.bloom) {
+            let passes = [
+                ("Bloom Threshold Pass", &bloom.views[0]),
+                ("Bloom Horizontal Pass", &bloom.views[1]),
+                ("Bloom Vertical Pass", &bloom.views[0]),
+            ];
+            for ((label, view), bind_group) in passes.into_iter().zip(&bloom.bind_groups) {
+                let mut rp = enc.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some(label),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view,
+                        resolve_target: None,
+                        ops: wgpu::Operations { load: wgpu::LoadOp::Clear(wgpu::Color::BLACK), store: wgpu::StoreOp::Store },
+                        depth_slice: None,
+                    })],
+                    ..Default::default()
+                });
+                rp.set_pipeline(&pass.pipeline);
+                rp.set_bind_group(0, bind_group, &[]);
+                rp.draw(0..3, 0..1);
+                stats.draw_calls += 1;
+                stats.instance_count += 1;
+            }
+        }
+        if self.auto_exposure().is_some()
+            && let (Some(pass), Some(bind_group)) = (&self.exposure, &target.exposure_bind_group)
+        {
+            let size = target.texture.size();
+            {
+                let mut cp = enc.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                    label: Some("Auto Exposure"),
+                    timestamp_writes: None,
+                });
+                cp.set_bind_group(0, bind_group, &[]);
+                cp.set_pipeline(&pass.histogram_pipeline);
+                cp.dispatch_workgroups(
+                    size.width.div_ceil(HISTOGRAM_WORKGROUP),
+                    size.height.div_ceil(HISTOGRAM_WORKGROUP),
+                    1,
+                );
+                cp.set_pipeline(&pass.average_pipeline);
+                cp.dispatch_workgroups(1, 1, 1);
+            }
+            // state.output -> tonemap.params.x
+            enc.copy_buffer_to_buffer(&pass.state_buffer, 4, &self.tonemap_buffer, 0, 4);
+        }
+
+        let dest = self.ldr_target.as_ref().map_or(output, |t| &t.view);
+        let mut rp = begin_fullscreen_pass(enc, "Tonemap Pass", dest, depth_view);
+        rp.set_pipeline(&self.tonemap_pipeline);
+        rp.set_bind_group(0, &target.tonemap_bind_group, &[]);
+        rp.draw(0..3, 0..1);
+        stats.draw_calls += 1;
+        stats.instance_count += 1;
+        if self.ldr_target.is_none() {
+            self.fade.draw(&mut rp, stats);
+        }
+    }
+}
+
+/// Begin a pass that overwrites `view` with a fullscreen draw.
+fn begin_fullscreen_pass<'e>(
+    enc: &'e mut wgpu::CommandEncoder,
+    label: &str,
+    view: &wgpu::TextureView,
+    depth_view: &wgpu::TextureView,
+) -> wgpu::RenderPass<'e> {
+    enc.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some(label),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view,
+            resolve_target: None,
+            ops: wgpu::Operations { load: wgpu::LoadOp::Clear(wgpu::Color::BLACK), store: wgpu::StoreOp::Store },
+            depth_slice: None,
+        })],
+        depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+            view: depth_view,
+            depth_ops: Some(wgpu::Operations { load: wgpu::LoadOp::Load, store: wgpu::StoreOp::Store }),
+            stencil_ops: None,
+        }),
+        ..Default::default()
+    })
+}
+
+/// A colour target that passes render into and later passes sample.
+fn create_target(
+    device: &Device,
+    label: &str,
+    format: wgpu::TextureFormat,
+    width: u32,
+    height: u32,
+) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some(label),
+        size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+        view_formats: &[],
+    })
+}
+
+fn texture_entry(binding: u32) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Texture {
+            multisampled: false,
+            view_dimension: wgpu::TextureViewDimension::D2,
+            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+        },
+        count: None,
+    }
+}
+
+fn sampler_entry(binding: u32) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+        count: None,
+    }
+}
+
+fn uniform_entry(binding: u32, visibility: wgpu::ShaderStages) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    }
+}
+
+fn create_uniform_buffer(device: &Device, label: &str, size: usize) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some(label),
+        size: size as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
+
+/// A pipeline drawing one oversized triangle (`vs_main` / `fs_main` in
+/// `shader`) over the whole target.  With `depth_attached` the pass shares
+/// the scene's depth buffer but never tests or writes it; passes that sample
+/// depth leave it detached.
+fn create_fullscreen_pipeline(
+    device: &Device,
+    label: &str,
+    bind_group_layout: &wgpu::BindGroupLayout,
+    shader: &wgpu::ShaderModule,
+    format: wgpu::TextureFormat,
+    blend: Option<wgpu::BlendState>,
+    depth_attached: bool,
+) -> wgpu::RenderPipeline {
+    let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some(label),
+        bind_group_layouts: &[Some(bind_group_layout)],
+        immediate_size: 0,
+    });
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some(label),
+        layout: Some(&layout),
+        cache: None, multiview_mask: None,
+        vertex: wgpu::VertexState {
+            module: shader, entry_point: Some("vs_main"),
+            compilation_options: PipelineCompilationOptions::default(),
+            buffers: &[],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader, entry_point: Some("fs_main"),
+            compilation_options: PipelineCompilationOptions::default(),
+            targets: &[Some(wgpu::ColorTargetState { format, blend, write_mask: wgpu::ColorWrites::ALL })],
+        }),
+        primitive: wgpu::PrimitiveState { cull_mode: None, ..Default::default() },
+        depth_stencil: depth_attached.then(|| wgpu::DepthStencilState {
+            format: wgpu::TextureFormat::Depth32Float,
+            depth_write_enabled: Some(false),
+            depth_compare: Some(wgpu::CompareFunction::Always),
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        }),
+        multisample: wgpu::MultisampleState::default(),
+    })
+}
@@ -0,0 +1,61 @@
+//! Ready-made objects for getting a scene on screen quickly.
+//!
+//! Each preset is an [`ObjectBuilder`] or [`MeshData`] with sensible
+//! defaults from [`crate::constants::presets`], so it can still be tweaked
+//! before spawning.  The [`crate::scene::Scene`] `spawn_*` helpers wrap
+//! them:
+//!
+//! ```no_run
+//! # fn demo(scene: &mut vertra::scene::Scene) {
+//! scene.spawn_ground_plane(20.0);
+//! scene.spawn_debug_axes(1.0, None);
+//! scene.spawn_skybox();
+//! # }
+//! ```
+
+use crate::constants::presets;
+use crate::geometry::{Geometry, SphereTessellation};
+use crate::mesh::{MeshData, MeshUsage};
+use crate::objects::{Object, ObjectBuilder};
+
+/// A `size` × `size` static floor at `y = 0`, centred on the origin.
+pub fn ground_plane(size: f32) -> ObjectBuilder {
+    Object::builder()
+        .name("Ground")
+        .geometry(Geometry::Plane { size, subdivisions: presets::GROUND_SUBDIVISIONS })
+        .color(presets::GROUND_COLOR)
+        .usage(MeshUsage::Static)
+}
+
+/// A small sphere marking where a point light sits, for seeing light
+/// placement while authoring.  It is only a marker: lighting is ambient.
+pub fn point_light_marker(position: [f32; 3]) -> ObjectBuilder {
+    Object::builder()
+        .name("Point Light")
+        .geometry(Geometry::Sphere { radius: presets::LIGHT_MARKER_RADIUS, subdivisions: 8, tessellation: SphereTessellation::Uv })
+        .color(presets::LIGHT_MARKER_COLOR)
+        .at(position[0], position[1], position[2])
+}
+
+/// Red, green and blue arrows of `length` along +X, +Y and +Z (see
+/// [`Geometry::build_axes`]).
+pub fn debug_axes_mesh(length: f32) -> MeshData {
+    let (vertices, indices) = Geometry::build_axes(length);
+    MeshData { vertices, indices, ..MeshData::new() }
+}
+
+/// A box of half-extent [`presets::SKYBOX_HALF_EXTENT`] around the origin,
+/// seen from inside: `top` overhead, `bottom` underfoot and `sides` on the
+/// four walls.  Drawn double-sided and behind everything else by
+/// [`crate::scene::Scene::set_skybox`].
+pub fn skybox_mesh(top: [f32; 4], sides: [f32; 4], bottom: [f32; 4]) -> MeshData {
+    let mut mesh = MeshData::new();
+    let s = presets::SKYBOX_HALF_EXTENT;
+    mesh.push_quad([[-s, s, -s], [s, s, -s], [s, s, s], [-s, s, s]], top);
+    mesh.push_quad([[-s, -s, s], [s, -s, s], [s, -s, -s], [-s, -s, -s]], bottom);
+    mesh.push_quad([[-s, s, -s], [-s, -s, -s], [s, -s, -s], [s, s, -s]], sides);
+    mesh.push_quad([[s, s, s], [s, -s, s], [-s, -s, s], [-s, s, s]], sides);
+    mesh.push_quad([[s, s, -s], [s, -s, -s], [s, -s, s], [s, s, s]], sides);
+    mesh.push_quad([[-s, s, s], [-s, -s, s], [-s, -s, -s], [-s, s, -s]], sides);
+    mesh
+}
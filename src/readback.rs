@@ -0,0 +1,280 @@
+//! Copying texture regions back to the CPU.
+//!
+//! Backs [`crate::pipeline::Pipeline::read_pixels`] and
+//! [`crate::pipeline::Pipeline::read_depth`].  A region is copied into
+//! a `MAP_READ` buffer with rows padded to
+//! [`wgpu::COPY_BYTES_PER_ROW_ALIGNMENT`], and [`Readback::read`] maps it and
+//! strips the padding.  Native targets block on the device until the copy
+//! lands; on the web the returned future resolves once the browser has
+//! mapped the buffer.
+//!
+//! Depth textures go through [`DepthProbe`] first, since GLES and WebGL2
+//! cannot copy them to buffers directly.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll, Waker};
+use wgpu::Device;
+
+/// A rectangle of whole pixels, measured from the top-left corner of the
+/// frame.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PixelRect {
+    /// Left edge in pixels.
+    pub x: u32,
+    /// Top edge in pixels.
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl PixelRect {
+    /// A `width` × `height` rectangle with its top-left corner at `(x, y)`.
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self { x, y, width, height }
+    }
+
+    /// The whole of a `width` × `height` frame.
+    pub fn full(width: u32, height: u32) -> Self {
+        Self::new(0, 0, width, height)
+    }
+
+    /// Whether the rectangle is non-empty and lies inside a `width` ×
+    /// `height` frame.
+    pub fn fits(self, width: u32, height: u32) -> bool {
+        self.width > 0
+            && self.height > 0
+            && self.x.checked_add(self.width).is_some_and(|right| right <= width)
+            && self.y.checked_add(self.height).is_some_and(|bottom| bottom <= height)
+    }
+}
+
+/// A texture region copied into a mappable buffer, waiting to be read.
+pub(crate) struct Readback {
+    buffer: wgpu::Buffer,
+    /// Bytes of one row without padding.
+    row_bytes: u32,
+    /// Bytes of one row in `buffer`.
+    padded_row_bytes: u32,
+}
+
+impl Readback {
+    /// Record a copy of the `size` texels at `source` into a new buffer.
+    /// `texel_bytes` is the size of one texel of the copied aspect.
+    pub(crate) fn copy(
+        device: &Device,
+        enc: &mut wgpu::CommandEncoder,
+        source: wgpu::TexelCopyTextureInfo<'_>,
+        size: [u32; 2],
+        texel_bytes: u32,
+    ) -> Self {
+        let row_bytes = size[0] * texel_bytes;
+        let padded_row_bytes = row_bytes.div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT) * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Readback Buffer"),
+            size: u64::from(padded_row_bytes) * u64::from(size[1]),
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        enc.copy_texture_to_buffer(
+            source,
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row_bytes),
+                    rows_per_image: Some(size[1]),
+                },
+            },
+            wgpu::Extent3d { width: size[0], height: size[1], depth_or_array_layers: 1 },
+        );
+        Self { buffer, row_bytes, padded_row_bytes }
+    }
+
+    /// Map the buffer and return its rows without padding, or `None` if
+    /// mapping failed (e.g. the device was lost).  The copy must already be
+    /// submitted.
+    pub(crate) async fn read(self, device: &Device) -> Option<Vec<u8>> {
+        let state = Arc::new(Mutex::new(MapState::default()));
+        let callback_state = state.clone();
+        self.buffer.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+            let mut state = callback_state.lock().unwrap_or_else(PoisonError::into_inner);
+            state.result = Some(result.is_ok());
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        });
+        #[cfg(not(target_arch = "wasm32"))]
+        device.poll(wgpu::PollType::wait_indefinitely()).ok()?;
+        #[cfg(target_arch = "wasm32")]
+        let _ = device;
+        let mapped = MapFuture { state }.await;
+        if !mapped {
+            return None;
+        }
+
+        let mapped = self.buffer.slice(..).get_mapped_range();
+        let mut bytes = Vec::with_capacity(mapped.len() / self.padded_row_bytes as usize * self.row_bytes as usize);
+        for row in mapped.chunks(self.padded_row_bytes as usize) {
+            bytes.extend_from_slice(&row[..self.row_bytes as usize]);
+        }
+        Some(bytes)
+    }
+}
+
+/// Completion of a `map_async` call, shared with its callback.
+#[derive(Default)]
+struct MapState {
+    /// `Some(mapped)` once the callback has run.
+    result: Option<bool>,
+    waker: Option<Waker>,
+}
+
+/// Resolves to whether the buffer was mapped.
+struct MapFuture {
+    state: Arc<Mutex<MapState>>,
+}
+
+impl Future for MapFuture {
+    type Output = bool;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<bool> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        match state.result {
+            Some(mapped) => Poll::Ready(mapped),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Draws one texel of a `Depth32Float` texture into a 1×1 `R32Uint` target
+/// with its bits intact, so it can be copied like any colour texture.
+pub(crate) struct DepthProbe {
+    pipeline: wgpu::RenderPipeline,
+    /// The texel to read, as `vec4<u32>(x, y, 0, 0)`.
+    texel: wgpu::Buffer,
+    target: wgpu::Texture,
+    target_view: wgpu::TextureView,
+}
+
+impl DepthProbe {
+    /// Bind group layout: the depth texture, then the texel uniform.
+    pub(crate) const LAYOUT_ENTRIES: [wgpu::BindGroupLayoutEntry; 2] = [
+        wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        },
+        wgpu::BindGroupLayoutEntry {
+            binding: 1,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        },
+    ];
+
+    /// Compile the probe for bind groups of `layout`, created from
+    /// [`Self::LAYOUT_ENTRIES`].
+    pub(crate) fn new(device: &Device, layout: &wgpu::BindGroupLayout) -> Self {
+        let shader = device.create_shader_module(wgpu::include_wgsl!("depth_probe.wgsl"));
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Depth Probe Pipeline Layout"),
+            bind_group_layouts: &[Some(layout)],
+            immediate_size: 0,
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Depth Probe Pipeline"),
+            layout: Some(&pipeline_layout),
+            cache: None, multiview_mask: None,
+            vertex: wgpu::VertexState {
+                module: &shader, entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader, entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: wgpu::TextureFormat::R32Uint,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+        });
+        let texel = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Depth Probe Texel"),
+            size: size_of::<[u32; 4]>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let target = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Depth Probe Target"),
+            size: wgpu::Extent3d { width: 1, height: 1, depth_or_array_layers: 1 },
+            mip_level_count: 1, sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::R32Uint,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let target_view = target.create_view(&wgpu::TextureViewDescriptor::default());
+        Self { pipeline, texel, target, target_view }
+    }
+
+    /// Entries binding `depth_view` and the texel uniform into a bind group
+    /// of [`Self::LAYOUT_ENTRIES`].
+    pub(crate) fn bind_group_entries<'a>(&'a self, depth_view: &'a wgpu::TextureView) -> [wgpu::BindGroupEntry<'a>; 2] {
+        [
+            wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(depth_view) },
+            wgpu::BindGroupEntry { binding: 1, resource: self.texel.as_entire_binding() },
+        ]
+    }
+
+    /// Record reading texel `(x, y)` of the depth texture bound in
+    /// `bind_group`.  Returns the bytes uploaded and the pending readback of
+    /// the texel's bits.
+    pub(crate) fn probe(
+        &self,
+        device: &Device,
+        queue: &wgpu::Queue,
+        enc: &mut wgpu::CommandEncoder,
+        bind_group: &wgpu::BindGroup,
+        x: u32,
+        y: u32,
+    ) -> (u64, Readback) {
+        let texel = [x, y, 0, 0];
+        queue.write_buffer(&self.texel, 0, bytemuck::cast_slice(&texel));
+        {
+            let mut rp = enc.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Depth Probe Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &self.target_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations { load: wgpu::LoadOp::Clear(wgpu::Color::BLACK), store: wgpu::StoreOp::Store },
+                    depth_slice: None,
+                })],
+                ..Default::default()
+            });
+            rp.set_pipeline(&self.pipeline);
+            rp.set_bind_group(0, bind_group, &[]);
+            rp.draw(0..3, 0..1);
+        }
+        let readback = Readback::copy(device, enc, self.target.as_image_copy(), [1, 1], 4);
+        (size_of_val(&texel) as u64, readback)
+    }
+}
@@ -0,0 +1,198 @@
+//! User render passes recorded into the engine's frame.
+//!
+//! [`crate::scene::Scene::add_render_hook`] registers a callback for a
+//! [`RenderStage`]; each frame [`crate::scene::Scene::draw_world`] calls it
+//! with the frame's command encoder and a [`RenderContext`], so it can
+//! record its own passes (custom effects, an external UI library) between
+//! the built-in ones:
+//!
+//! ```ignore
+//! scene.add_render_hook(RenderStage::AfterPost, |encoder, ctx| {
+//!     let pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+//!         color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+//!             view: ctx.view(),
+//!             resolve_target: None,
+//!             ops: wgpu::Operations { load: wgpu::LoadOp::Load, store: wgpu::StoreOp::Store },
+//!             depth_slice: None,
+//!         })],
+//!         ..Default::default()
+//!     });
+//!     // draw with your own pipeline…
+//! });
+//! ```
+//!
+//! Hooks do not run for [`crate::scene::Scene::capture_cubemap`], and
+//! their draws are not counted in [`crate::pipeline::RenderStats`].
+//!
+//! # Raw wgpu access
+//!
+//! [`RenderContext`] lends out the device, queue, frame targets and the
+//! engine's camera bind group for the duration of one hook call, so
+//! third-party wgpu crates can be driven without reaching into
+//! [`Pipeline`]'s private state.  Outside hooks, [`Pipeline::device`] and
+//! [`Pipeline::queue`] are public for creating resources up front; keep
+//! them in [`Resources`] to use them from a hook.
+
+use crate::pipeline::Pipeline;
+use crate::resources::Resources;
+
+/// Point in the frame at which a hook is recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RenderStage {
+    /// Before the scene pass, which then clears the frame: for compute work
+    /// or rendering into your own textures.
+    BeforeScene,
+    /// After the 3D scene, before post-processing.  The view is the scene
+    /// target, an HDR intermediate when post-processing is enabled.
+    AfterScene,
+    /// After post-processing and letterboxing, before the screen-space
+    /// overlay.  The view is the final frame.
+    AfterPost,
+    /// Last, after the screen-space overlay.  The view is the final frame.
+    AfterOverlay,
+}
+
+/// Handle to a registered hook, returned by
+/// [`crate::scene::Scene::add_render_hook`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RenderHookId(u64);
+
+/// Callback recording passes into `encoder` for the frame described by the
+/// [`RenderContext`].
+pub type RenderHook = Box<dyn FnMut(&mut wgpu::CommandEncoder, &RenderContext)>;
+
+/// Borrowed view of the renderer during one hook call.
+///
+/// Everything here is valid until the hook returns; the views change from
+/// frame to frame (and on resize), so never keep them.
+pub struct RenderContext<'a> {
+    pub(crate) pipeline: &'a Pipeline,
+    pub(crate) stage: RenderStage,
+    pub(crate) view: &'a wgpu::TextureView,
+    pub(crate) camera_offset: u32,
+    pub(crate) resources: &'a Resources,
+}
+
+impl RenderContext<'_> {
+    /// Stage the hook was registered for.
+    pub fn stage(&self) -> RenderStage {
+        self.stage
+    }
+
+    /// Device the engine renders with.
+    pub fn device(&self) -> &wgpu::Device {
+        &self.pipeline.device
+    }
+
+    /// Queue the frame is submitted on; writes land before the frame's
+    /// commands run.
+    pub fn queue(&self) -> &wgpu::Queue {
+        &self.pipeline.queue
+    }
+
+    /// Colour target of this stage (see [`RenderStage`]).
+    pub fn view(&self) -> &wgpu::TextureView {
+        self.view
+    }
+
+    /// Format of [`Self::view`], for building compatible pipelines.  It is
+    /// an HDR format at [`RenderStage::AfterScene`] when post-processing is
+    /// on.
+    pub fn format(&self) -> wgpu::TextureFormat {
+        self.view.texture().format()
+    }
+
+    /// Size of [`Self::view`] in physical pixels.
+    pub fn size(&self) -> (u32, u32) {
+        let size = self.view.texture().size();
+        (size.width, size.height)
+    }
+
+    /// The scene's depth buffer (`Depth32Float`, one sample), holding the
+    /// frame's depth from [`RenderStage::AfterScene`] on.
+    pub fn depth_view(&self) -> &wgpu::TextureView {
+        &self.pipeline.depth_view
+    }
+
+    /// Layout of bind group 0 in the built-in shaders: the camera uniform
+    /// (dynamic offset) and ambient lighting.
+    pub fn camera_bind_group_layout(&self) -> &wgpu::BindGroupLayout {
+        &self.pipeline.camera_bind_group_layout
+    }
+
+    /// This frame's camera bind group; bind it with
+    /// `pass.set_bind_group(0, ctx.camera_bind_group(), &[ctx.camera_offset()])`.
+    pub fn camera_bind_group(&self) -> &wgpu::BindGroup {
+        &self.pipeline.camera_bind_group
+    }
+
+    /// Dynamic offset of the 3D camera's view-projection matrix in
+    /// [`Self::camera_bind_group`].
+    pub fn camera_offset(&self) -> u32 {
+        self.camera_offset
+    }
+
+    /// Layout of bind group 1 (texture and sampler) in the built-in shaders.
+    pub fn texture_bind_group_layout(&self) -> &wgpu::BindGroupLayout {
+        &self.pipeline.texture_bind_group_layout
+    }
+
+    /// The scene's shared state.
+    pub fn resources(&self) -> &Resources {
+        self.resources
+    }
+}
+
+/// Registered hooks, run per stage in the order they were added.
+#[derive(Default)]
+pub struct RenderHooks {
+    hooks: Vec<(RenderHookId, RenderStage, RenderHook)>,
+    next_id: u64,
+}
+
+impl std::fmt::Debug for RenderHooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.hooks.iter().map(|(id, stage, _)| (id, stage))).finish()
+    }
+}
+
+impl RenderHooks {
+    /// Create an empty set of hooks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `hook` to run at `stage`.
+    pub fn add(&mut self, stage: RenderStage, hook: RenderHook) -> RenderHookId {
+        let id = RenderHookId(self.next_id);
+        self.next_id += 1;
+        self.hooks.push((id, stage, hook));
+        id
+    }
+
+    /// Unregister hook `id`.  Returns `false` if it was already removed.
+    pub fn remove(&mut self, id: RenderHookId) -> bool {
+        let before = self.hooks.len();
+        self.hooks.retain(|(hook_id, ..)| *hook_id != id);
+        self.hooks.len() != before
+    }
+
+    /// Number of registered hooks.
+    pub fn len(&self) -> usize {
+        self.hooks.len()
+    }
+
+    /// `true` when no hooks are registered.
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+
+    /// Run every hook registered for `ctx`'s stage.
+    pub(crate) fn run(&mut self, encoder: &mut wgpu::CommandEncoder, ctx: &RenderContext) {
+        for (_, stage, hook) in &mut self.hooks {
+            if *stage == ctx.stage {
+                hook(encoder, ctx);
+            }
+        }
+    }
+}
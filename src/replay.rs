@@ -0,0 +1,319 @@
+//! Input recording and deterministic replay.
+//!
+//! [`crate::window::Window::with_recording`] writes every pass of the event
+//! loop — its delta time plus the input event that triggered it — to a
+//! [`Recording`] file.  [`crate::window::Window::with_replay`] plays such a
+//! file back: live input is ignored and each recorded step runs through the
+//! same callbacks with its recorded `dt`, so `on_update`, `on_fixed_update`
+//! and scripts see exactly the sequence they saw while recording.  Use it to
+//! reproduce a bug report or to drive an end-to-end regression test:
+//!
+//! ```no_run
+//! # use vertra::window::Window;
+//! // Record a session...
+//! Window::new(()).with_recording("bug-1234.vrec.json").create()?;
+//! # Ok::<(), vertra::window::WindowError>(())
+//! ```
+//! ```no_run
+//! # use vertra::window::Window;
+//! // ...and replay it, exiting when the recording ends.
+//! Window::new(()).with_replay("bug-1234.vrec.json", true).create()?;
+//! # Ok::<(), vertra::window::WindowError>(())
+//! ```
+//!
+//! winit does not allow constructing keyboard events, so replayed key
+//! presses reach [`crate::window::Window::on_input`] and the editor but not
+//! the raw event handler.  `on_input` receives every [`InputEvent`] live and
+//! replayed alike; handle input there for replays to be faithful.  Mouse,
+//! focus and input-method events also reach the raw event handler.  Typed
+//! text is recorded as its own [`InputEvent::Text`] step right after the key
+//! press that produced it.  The window size stays live: resizes during a
+//! replay come from the real window, not the recording.
+//!
+//! Recordings are JSON, so they can be inspected and trimmed by hand.
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use winit::dpi::PhysicalPosition;
+use winit::event::{DeviceId, Ime, KeyEvent, MouseScrollDelta, Touch, TouchPhase};
+use winit::keyboard::{KeyCode, PhysicalKey};
+use winit::window::WindowId;
+use crate::event::{DeviceEvent, ElementState, Event, MouseButton, WindowEvent};
+
+/// Recording layout version written to every file.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// A user input, independent of the winit event that carried it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum InputEvent {
+    /// A key identified by its physical position changed state.
+    Key { code: KeyCode, pressed: bool, repeat: bool },
+    /// A mouse button changed state.
+    MouseButton { button: MouseButton, pressed: bool },
+    /// The cursor moved to `(x, y)` physical pixels in the window.
+    CursorMoved { x: f64, y: f64 },
+    /// Scroll wheel or touchpad scroll; `pixels` distinguishes pixel deltas
+    /// from line deltas.
+    MouseWheel { dx: f32, dy: f32, pixels: bool },
+    /// A finger touched, moved on, left or was cancelled from a touch
+    /// screen at `(x, y)` physical pixels.  `id` stays the same for one
+    /// finger from `Started` to `Ended`.  See [`crate::touch`] for gestures.
+    Touch { id: u64, phase: TouchPhase, x: f64, y: f64 },
+    /// Touchpad pinch; positive when the fingers move apart (zoom in).
+    Pinch { delta: f64 },
+    /// Raw, unaccelerated mouse motion (for mouse-look).  Not bounded by
+    /// the window edge; delivered while the cursor is grabbed, see
+    /// [`crate::window::Window::with_mouse_motion_always`].
+    MouseMotion { dx: f64, dy: f64 },
+    /// The window gained (`true`) or lost focus.
+    Focused(bool),
+    /// Text entered by the user: the printable characters of a key press,
+    /// or text committed by an input method.  Control characters (Enter,
+    /// Backspace, Tab…) are left out; handle those through [`Self::Key`].
+    Text(String),
+    /// An input method is composing text (e.g. a CJK reading before
+    /// conversion); show `text` at the caret, underlined, until the
+    /// matching [`Self::Text`] arrives.  Empty `text` clears it.  `cursor`
+    /// is the byte range of the caret or selection within `text`.
+    ImePreedit { text: String, cursor: Option<(usize, usize)> },
+    /// The input method was enabled (`true`) or disabled for the window.
+    /// Allow it with [`AppCommands::set_ime_allowed`](crate::window::AppCommands::set_ime_allowed).
+    ImeEnabled(bool),
+}
+
+impl InputEvent {
+    /// The input carried by `event`, if it is one this module records.
+    pub fn from_event(event: &Event<()>) -> Option<Self> {
+        match event {
+            Event::WindowEvent { event, .. } => match event {
+                WindowEvent::KeyboardInput { event: KeyEvent { physical_key: PhysicalKey::Code(code), state, repeat, .. }, .. } => {
+                    Some(Self::Key { code: *code, pressed: *state == ElementState::Pressed, repeat: *repeat })
+                }
+                WindowEvent::MouseInput { button, state, .. } => {
+                    Some(Self::MouseButton { button: *button, pressed: *state == ElementState::Pressed })
+                }
+                WindowEvent::CursorMoved { position, .. } => Some(Self::CursorMoved { x: position.x, y: position.y }),
+                WindowEvent::MouseWheel { delta, .. } => Some(match *delta {
+                    MouseScrollDelta::LineDelta(dx, dy) => Self::MouseWheel { dx, dy, pixels: false },
+                    MouseScrollDelta::PixelDelta(d) => Self::MouseWheel { dx: d.x as f32, dy: d.y as f32, pixels: true },
+                }),
+                WindowEvent::Touch(touch) => Some(Self::Touch {
+                    id: touch.id,
+                    phase: touch.phase,
+                    x: touch.location.x,
+                    y: touch.location.y,
+                }),
+                WindowEvent::PinchGesture { delta, .. } => Some(Self::Pinch { delta: *delta }),
+                WindowEvent::Focused(focused) => Some(Self::Focused(*focused)),
+                WindowEvent::Ime(ime) => Some(match ime {
+                    Ime::Enabled => Self::ImeEnabled(true),
+                    Ime::Disabled => Self::ImeEnabled(false),
+                    Ime::Preedit(text, cursor) => Self::ImePreedit { text: text.clone(), cursor: *cursor },
+                    Ime::Commit(text) => Self::Text(text.clone()),
+                }),
+                _ => None,
+            },
+            Event::DeviceEvent { event: DeviceEvent::MouseMotion { delta }, .. } => {
+                Some(Self::MouseMotion { dx: delta.0, dy: delta.1 })
+            }
+            _ => None,
+        }
+    }
+
+    /// Zoom input in wheel steps, positive to zoom in: mouse-wheel lines,
+    /// touchpad scroll in units of
+    /// [`PIXELS_PER_SCROLL_STEP`](crate::constants::camera::PIXELS_PER_SCROLL_STEP)
+    /// and pinches scaled by
+    /// [`PINCH_SCROLL_STEPS`](crate::constants::camera::PINCH_SCROLL_STEPS).
+    /// `None` for other inputs.
+    ///
+    /// Pass it to [`Camera::dolly`](crate::camera::Camera::dolly) or
+    /// [`Camera::zoom_fov`](crate::camera::Camera::zoom_fov), or scale a
+    /// fly speed with it:
+    ///
+    /// ```
+    /// # use vertra::replay::InputEvent;
+    /// let mut speed = 5.0_f32;
+    /// let input = InputEvent::MouseWheel { dx: 0.0, dy: 1.0, pixels: false };
+    /// if let Some(steps) = input.zoom_steps() {
+    ///     speed *= 1.2_f32.powf(steps);
+    /// }
+    /// assert!(speed > 5.0);
+    /// ```
+    pub fn zoom_steps(&self) -> Option<f32> {
+        use crate::constants::camera::{PINCH_SCROLL_STEPS, PIXELS_PER_SCROLL_STEP};
+        match *self {
+            Self::MouseWheel { dy, pixels: false, .. } => Some(dy),
+            Self::MouseWheel { dy, pixels: true, .. } => Some(dy / PIXELS_PER_SCROLL_STEP),
+            Self::Pinch { delta } => Some(delta as f32 * PINCH_SCROLL_STEPS),
+            _ => None,
+        }
+    }
+
+    /// The [`Self::Text`] typed by a key press in `event`, which also
+    /// yields a [`Self::Key`] through [`Self::from_event`].
+    pub fn text_from_event(event: &Event<()>) -> Option<Self> {
+        let Event::WindowEvent { event: WindowEvent::KeyboardInput { event, .. }, .. } = event else {
+            return None;
+        };
+        let text: String = event.text.as_deref()?.chars().filter(|c| !c.is_control()).collect();
+        (event.state == ElementState::Pressed && !text.is_empty()).then_some(Self::Text(text))
+    }
+
+    /// Rebuild the winit event for window `window_id`.  `None` for
+    /// [`Self::Key`] and [`Self::Text`], which may come from keyboard
+    /// events winit cannot construct.
+    pub fn to_event(&self, window_id: WindowId) -> Option<Event<()>> {
+        // The dummy id never matches a real device, which is exactly what a
+        // replayed event should carry.
+        let device_id = DeviceId::dummy();
+        let state = |pressed| if pressed { ElementState::Pressed } else { ElementState::Released };
+        let event = match *self {
+            Self::Key { .. } | Self::Text(_) => return None,
+            Self::MouseMotion { dx, dy } => {
+                return Some(Event::DeviceEvent { device_id, event: DeviceEvent::MouseMotion { delta: (dx, dy) } });
+            }
+            Self::MouseButton { button, pressed } => WindowEvent::MouseInput { device_id, state: state(pressed), button },
+            Self::CursorMoved { x, y } => WindowEvent::CursorMoved { device_id, position: PhysicalPosition::new(x, y) },
+            Self::MouseWheel { dx, dy, pixels } => WindowEvent::MouseWheel {
+                device_id,
+                delta: if pixels {
+                    MouseScrollDelta::PixelDelta(PhysicalPosition::new(dx as f64, dy as f64))
+                } else {
+                    MouseScrollDelta::LineDelta(dx, dy)
+                },
+                phase: TouchPhase::Moved,
+            },
+            Self::Touch { id, phase, x, y } => WindowEvent::Touch(Touch {
+                device_id,
+                phase,
+                location: PhysicalPosition::new(x, y),
+                force: None,
+                id,
+            }),
+            Self::Pinch { delta } => WindowEvent::PinchGesture { device_id, delta, phase: TouchPhase::Moved },
+            Self::Focused(focused) => WindowEvent::Focused(focused),
+            Self::ImePreedit { ref text, cursor } => WindowEvent::Ime(Ime::Preedit(text.clone(), cursor)),
+            Self::ImeEnabled(enabled) => WindowEvent::Ime(if enabled { Ime::Enabled } else { Ime::Disabled }),
+        };
+        Some(Event::WindowEvent { window_id, event })
+    }
+}
+
+/// What triggered one pass of the event loop.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum StepKind {
+    /// A user input.
+    Input(InputEvent),
+    /// A frame was rendered.
+    Redraw,
+    /// The end of a batch of events, where fixed updates run.
+    FrameEnd,
+    /// Any other event; replayed as a bare update.
+    Other,
+}
+
+impl StepKind {
+    /// Classify a live event.
+    pub fn from_event(event: &Event<()>) -> Self {
+        match event {
+            Event::AboutToWait => Self::FrameEnd,
+            Event::WindowEvent { event: WindowEvent::RedrawRequested, .. } => Self::Redraw,
+            _ => InputEvent::from_event(event).map_or(Self::Other, Self::Input),
+        }
+    }
+}
+
+/// One pass of the event loop.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Step {
+    /// Seconds since the previous pass.
+    pub dt: f32,
+    /// What triggered the pass.
+    pub kind: StepKind,
+}
+
+/// A recorded session: every event-loop pass in order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Recording {
+    /// Layout version; see [`FORMAT_VERSION`].
+    pub version: u32,
+    /// Passes in the order they ran.
+    pub steps: Vec<Step>,
+}
+
+impl Default for Recording {
+    fn default() -> Self {
+        Self { version: FORMAT_VERSION, steps: Vec::new() }
+    }
+}
+
+impl Recording {
+    /// An empty recording.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a pass.
+    pub fn push(&mut self, dt: f32, kind: StepKind) {
+        self.steps.push(Step { dt, kind });
+    }
+
+    /// Total recorded time in seconds.
+    pub fn duration(&self) -> f32 {
+        self.steps.iter().map(|step| step.dt).sum()
+    }
+
+    /// Write the recording to `path`, creating or truncating it.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(&mut writer, self)?;
+        writer.flush()
+    }
+
+    /// Read a recording written by [`Self::save`].
+    ///
+    /// # Errors
+    /// Fails on I/O errors, malformed JSON, and recordings from a newer
+    /// [`FORMAT_VERSION`].
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let recording: Self = serde_json::from_reader(BufReader::new(File::open(path)?))?;
+        if recording.version > FORMAT_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("recording version {} is newer than {FORMAT_VERSION}", recording.version),
+            ));
+        }
+        Ok(recording)
+    }
+}
+
+/// Hands out the steps of a [`Recording`] one frame at a time.
+#[derive(Debug, Clone)]
+pub struct Player {
+    steps: VecDeque<Step>,
+}
+
+impl Player {
+    /// Play `recording` from the start.
+    pub fn new(recording: Recording) -> Self {
+        Self { steps: recording.steps.into() }
+    }
+
+    /// The steps up to and including the next [`StepKind::FrameEnd`], or
+    /// all remaining steps if there is none.
+    pub fn next_frame(&mut self) -> Vec<Step> {
+        let end = self.steps.iter()
+            .position(|step| step.kind == StepKind::FrameEnd)
+            .map_or(self.steps.len(), |i| i + 1);
+        self.steps.drain(..end).collect()
+    }
+
+    /// Returns `true` once every step has been handed out.
+    pub fn is_finished(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
@@ -0,0 +1,92 @@
+//! Type-keyed store for state shared between callbacks.
+//!
+//! [`crate::window::Window`] hands every callback one user state `S`; state
+//! that several systems need (configuration, score, asset handles, …) can
+//! instead live on the [`crate::scene::Scene`] as a resource, one value per
+//! type:
+//!
+//! ```
+//! use vertra::resources::Resources;
+//!
+//! struct Score(u32);
+//!
+//! let mut resources = Resources::new();
+//! resources.insert(Score(0));
+//! resources.get_mut::<Score>().unwrap().0 += 10;
+//! assert_eq!(resources.get::<Score>().map(|s| s.0), Some(10));
+//! ```
+//!
+//! Resources belong to the [`crate::scene::Scene`], not its world, so they
+//! survive scene switches and are never serialised.
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+/// At most one value of each type, looked up by type.
+#[derive(Default)]
+pub struct Resources {
+    values: HashMap<TypeId, Box<dyn Any>>,
+}
+
+impl std::fmt::Debug for Resources {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Resources").field("len", &self.values.len()).finish()
+    }
+}
+
+impl Resources {
+    /// Create an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store `value`, returning the previous resource of the same type.
+    pub fn insert<T: 'static>(&mut self, value: T) -> Option<T> {
+        self.values.insert(TypeId::of::<T>(), Box::new(value)).map(|old| *downcast(old))
+    }
+
+    /// The resource of type `T`, if one is stored.
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.values.get(&TypeId::of::<T>()).and_then(|value| value.downcast_ref())
+    }
+
+    /// Mutable access to the resource of type `T`, if one is stored.
+    pub fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.values.get_mut(&TypeId::of::<T>()).and_then(|value| value.downcast_mut())
+    }
+
+    /// The resource of type `T`, inserting `init()` first if there is none.
+    pub fn get_or_insert_with<T: 'static>(&mut self, init: impl FnOnce() -> T) -> &mut T {
+        let value = self.values.entry(TypeId::of::<T>()).or_insert_with(|| Box::new(init()));
+        value.downcast_mut().expect("resources are keyed by their own type")
+    }
+
+    /// Take the resource of type `T` out of the store.
+    pub fn remove<T: 'static>(&mut self) -> Option<T> {
+        self.values.remove(&TypeId::of::<T>()).map(|value| *downcast(value))
+    }
+
+    /// Whether a resource of type `T` is stored.
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.values.contains_key(&TypeId::of::<T>())
+    }
+
+    /// Number of stored resources.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// `true` when no resources are stored.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Drop every resource.
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+}
+
+fn downcast<T: 'static>(value: Box<dyn Any>) -> Box<T> {
+    value.downcast().unwrap_or_else(|_| unreachable!("resources are keyed by their own type"))
+}
@@ -0,0 +1,152 @@
+//! Seeded random numbers for reproducible scene generation.
+//!
+//! [`SceneRng`] is a small SplitMix64 generator: the same seed yields the
+//! same sequence on every platform and run, so procedurally placed objects
+//! land in the same spots each time.  Every [`crate::scene::Scene`] owns one
+//! as [`Scene::rng`](crate::scene::Scene::rng), seeded with
+//! [`crate::constants::rng::DEFAULT_SEED`].
+//!
+//! ```
+//! use vertra::rng::SceneRng;
+//! let mut a = SceneRng::new(7);
+//! let mut b = SceneRng::new(7);
+//! let p = a.point_in_aabb([-10.0, 0.0, -10.0], [10.0, 5.0, 10.0]);
+//! assert_eq!(p, b.point_in_aabb([-10.0, 0.0, -10.0], [10.0, 5.0, 10.0]));
+//! ```
+//!
+//! Not suitable for cryptography.
+
+use std::ops::Range;
+use crate::color;
+use crate::constants::rng::{DEFAULT_SEED, RANDOM_COLOR_SATURATION, RANDOM_COLOR_VALUE};
+
+/// Deterministic pseudo-random generator (SplitMix64).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SceneRng {
+    seed:  u64,
+    state: u64,
+}
+
+impl Default for SceneRng {
+    fn default() -> Self {
+        Self::new(DEFAULT_SEED)
+    }
+}
+
+impl SceneRng {
+    /// Generator producing the sequence for `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self { seed, state: seed }
+    }
+
+    /// The seed this generator was created or last reseeded with.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Restart the sequence from `seed`.
+    pub fn reseed(&mut self, seed: u64) {
+        *self = Self::new(seed);
+    }
+
+    /// Independent generator seeded from this one, e.g. one per generated
+    /// chunk so adding objects to one chunk does not shift the others.
+    pub fn fork(&mut self) -> Self {
+        Self::new(self.next_u64())
+    }
+
+    /// Next 64 random bits.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Next 32 random bits.
+    pub fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    /// Uniform float in `[0, 1)`.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Uniform float in `[min, max)`.
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+
+    /// Uniform integer in `range`.  Panics when `range` is empty.
+    pub fn range_i32(&mut self, range: Range<i32>) -> i32 {
+        assert!(range.start < range.end, "empty range {range:?}");
+        let span = (range.end as i64 - range.start as i64) as u64;
+        (range.start as i64 + (self.next_u64() % span) as i64) as i32
+    }
+
+    /// Uniform index in `0..len`.  Panics when `len` is zero.
+    pub fn index(&mut self, len: usize) -> usize {
+        assert!(len > 0, "index into an empty range");
+        (self.next_u64() % len as u64) as usize
+    }
+
+    /// `true` with probability `p`.
+    pub fn chance(&mut self, p: f32) -> bool {
+        self.next_f32() < p
+    }
+
+    /// A random element of `items`, or `None` when empty.
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            return None;
+        }
+        Some(&items[self.index(items.len())])
+    }
+
+    /// Shuffle `items` in place (Fisher–Yates).
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.index(i + 1));
+        }
+    }
+
+    /// Uniform point inside the box `min`..`max`.
+    pub fn point_in_aabb(&mut self, min: [f32; 3], max: [f32; 3]) -> [f32; 3] {
+        [0, 1, 2].map(|k| self.range(min[k], max[k]))
+    }
+
+    /// Uniformly distributed unit vector.
+    pub fn direction(&mut self) -> [f32; 3] {
+        let z = self.range(-1.0, 1.0);
+        let angle = self.range(0.0, std::f32::consts::TAU);
+        let r = (1.0 - z * z).max(0.0).sqrt();
+        [r * angle.cos(), r * angle.sin(), z]
+    }
+
+    /// Uniform point on the surface of the sphere at `center` with `radius`.
+    pub fn point_on_sphere(&mut self, center: [f32; 3], radius: f32) -> [f32; 3] {
+        let d = self.direction();
+        [0, 1, 2].map(|k| center[k] + d[k] * radius)
+    }
+
+    /// Uniform point inside the sphere at `center` with `radius`.
+    pub fn point_in_sphere(&mut self, center: [f32; 3], radius: f32) -> [f32; 3] {
+        let d = self.direction();
+        let r = radius * self.next_f32().cbrt();
+        [0, 1, 2].map(|k| center[k] + d[k] * r)
+    }
+
+    /// Opaque colour with a random hue at
+    /// [`RANDOM_COLOR_SATURATION`] / [`RANDOM_COLOR_VALUE`], so random
+    /// objects stay vivid instead of drifting to greys and browns.
+    pub fn color(&mut self) -> [f32; 4] {
+        color::hsv(self.range(0.0, 360.0), RANDOM_COLOR_SATURATION, RANDOM_COLOR_VALUE)
+    }
+
+    /// Opaque colour with every channel uniform in `[0, 1)`.
+    pub fn color_rgb(&mut self) -> [f32; 4] {
+        [self.next_f32(), self.next_f32(), self.next_f32(), 1.0]
+    }
+}
@@ -1,13 +1,51 @@
-use std::collections::HashMap;
-use crate::camera::Camera;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use crate::camera::{Camera, Viewport};
+use crate::constants::layers;
 use crate::editor::{EditorEvent, EditorState, InspectorData};
-use crate::mesh::{MeshData, MeshRegistry};
-use crate::pipeline::{Pipeline, RenderStats};
-use crate::world::World;
-use crate::objects::Object;
+use crate::mesh::{BakedMesh, BatchKey, MeshData, MeshRegistry, MeshUsage, StaticBatches};
+use crate::material::Material;
+use crate::geometry::{Geometry, GeometryId};
+use crate::overlay::{self, Overlay, OverlayItem};
+use crate::instances::{InstanceBuffer, InstanceData};
+use crate::pipeline::{self, CaptureTarget, CUBEMAP_FORMAT, FrameInputs, GpuMemory, InstancedDraw, InstancedDraws, MeshDraw, Pipeline, RenderError, RenderStats, ScreenDraws};
+use crate::pipeline_cache::{DebugView, ShaderVariant};
+use crate::world::{RayHit, World};
+use crate::objects::{Object, ObjectBuilder};
 use crate::transform::Transform;
 use crate::vtr::{self, VtrError};
+use crate::vtm::MeshBundle;
+use crate::watch::{AssetKind, AssetReload, FileWatcher};
 use crate::script::{ObjectScript, ScriptRegistry};
+use crate::resources::Resources;
+use crate::render_hook::{RenderContext, RenderHookId, RenderHooks, RenderStage};
+use crate::scene_manager::{SceneManager, SceneSlot};
+use crate::lighting::Lighting;
+use crate::ao::{self, AoSettings, Occluders};
+use crate::presets;
+use crate::rng::SceneRng;
+use crate::minimap;
+use crate::mirror::{self, Mirror};
+use crate::occlusion::{self, OcclusionBox, QueriedObjects};
+use crate::frustum::{CullingDebug, DebugFrame, Frustum};
+use crate::lines::{Grid, Lines};
+use crate::voxel::{Chunk, ChunkKey, VoxelGrid};
+use crate::streaming::{ObjectGroup, StreamStats, Streamer};
+use crate::view_axes;
+use crate::water::{Water, WaterPass};
+
+/// View direction and up vector of each cube face, in layer order
+/// (+X, −X, +Y, −Y, +Z, −Z).  With the engine's left-handed cameras these
+/// match how WebGPU samples cube textures, so faces need no flipping.
+const CUBE_FACES: [([f32; 3], [f32; 3]); 6] = [
+    ([1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+    ([-1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+    ([0.0, 1.0, 0.0], [0.0, 0.0, -1.0]),
+    ([0.0, -1.0, 0.0], [0.0, 0.0, 1.0]),
+    ([0.0, 0.0, 1.0], [0.0, 1.0, 0.0]),
+    ([0.0, 0.0, -1.0], [0.0, 1.0, 0.0]),
+];
 
 /// A loaded GPU texture paired with its bind group.
 ///
@@ -15,17 +53,68 @@ use crate::script::{ObjectScript, ScriptRegistry};
 /// objects.  The `texture` field is kept alive so the GPU memory is not freed
 /// while the bind group is in use.
 pub struct TextureEntry {
-    #[allow(dead_code)]
     pub texture: wgpu::Texture,
     /// Bind group that wires the texture to the shader's texture slot.
     pub bind_group: wgpu::BindGroup,
 }
 
+/// Depth buffer of a render-to-texture pass whose colour texture is a
+/// [`TextureEntry`] in [`Scene::textures`], so objects and overlay items
+/// can show it by key.
+pub(crate) struct TextureTarget {
+    pub(crate) size: [u32; 2],
+    pub(crate) depth: wgpu::Texture,
+}
+
+impl TextureTarget {
+    /// A `size` target in `format` and the texture entry to register for it.
+    pub(crate) fn new(pipeline: &Pipeline, label: &str, size: [u32; 2], format: wgpu::TextureFormat) -> (Self, TextureEntry) {
+        let extent = wgpu::Extent3d { width: size[0], height: size[1], depth_or_array_layers: 1 };
+        let texture = pipeline.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size: extent,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let depth = pipeline.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size: extent,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Depth32Float,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let bind_group = pipeline.bind_group(Some(label), &pipeline.texture_bind_group_layout, &[
+            wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&view) },
+            wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&pipeline.default_sampler) },
+        ]);
+        (Self { size, depth }, TextureEntry { texture, bind_group })
+    }
+
+    /// The capture target drawing into `entry`'s texture, optionally with
+    /// its own view-projection.
+    pub(crate) fn capture(&self, entry: &TextureEntry, view_projection: Option<crate::math::Matrix4>) -> CaptureTarget {
+        CaptureTarget {
+            color: entry.texture.create_view(&wgpu::TextureViewDescriptor::default()),
+            depth: self.depth.create_view(&wgpu::TextureViewDescriptor::default()),
+            view_projection,
+        }
+    }
+}
+
 /// The root container for a 3D scene.
 ///
 /// `Scene` owns all engine subsystems for a single viewport:
 /// * [`Scene::world`]  - the scene-graph (objects, hierarchy).
 /// * [`Scene::camera`] - the viewport camera.
+/// * [`Scene::overlay`] - 2D HUD elements in pixel coordinates.
 /// * [`Scene::pipeline`] - the wgpu render pipeline.
 /// * [`Scene::editor`] - optional built-in editor overlay.
 /// * [`Scene::textures`] - loaded GPU textures keyed by path.
@@ -35,7 +124,8 @@ pub struct TextureEntry {
 pub struct Scene {
     /// The wgpu render pipeline, surface, and device context.
     pub pipeline:       Pipeline,
-    /// Registry tracking the world mesh (primarily used internally).
+    /// Reference-counted store of shared GPU meshes, addressed by
+    /// [`crate::objects::Object::mesh`].  See [`Scene::register_mesh`].
     pub mesh_registry:  MeshRegistry,
     /// Active viewport camera.
     pub camera:         Camera,
@@ -52,12 +142,290 @@ pub struct Scene {
     /// any mutations that occurred during play (object movement, etc.) are
     /// reverted to the exact state the editor saved.
     pub(crate) snapshot: Option<Vec<u8>>,
+    /// Registry mesh handles captured alongside [`Self::snapshot`], keyed by
+    /// object ID.  VTR does not store them, so they are re-applied on restore.
+    pub(crate) snapshot_meshes: HashMap<usize, GeometryId>,
+    /// Registry meshes [`Self::spawn_batch`] baked from a geometry.  An
+    /// object pointing at one of these draws it in place of the same
+    /// geometry, which it keeps for picking and saving.
+    batch_shapes: HashMap<GeometryId, Geometry>,
     /// Per-object script registry.  Kept separate from `World` so scripts
     /// never affect serialisation.
     pub script_registry: ScriptRegistry,
+    /// State shared between callbacks, one value per type.  See
+    /// [`Scene::insert_resource`].
+    pub resources: Resources,
+    /// User passes recorded into each frame.  See
+    /// [`Scene::add_render_hook`].
+    pub render_hooks: RenderHooks,
+    /// Objects spawned with [`MeshUsage::Static`] and their cached merged
+    /// geometry.  See [`Scene::spawn_with_usage`].
+    pub static_batches: StaticBatches,
+    /// Polls watched files for hot reloading.
+    pub(crate) file_watcher: FileWatcher,
+    /// Files written by [`Scene::save_vtr_file`] since the last poll; their
+    /// new timestamps become the watch baseline so our own saves are not
+    /// reloaded.
+    saved_files: Mutex<Vec<PathBuf>>,
+    /// Watched files and what they reload as.  See [`Scene::watch_vtr_file`],
+    /// [`Scene::watch_texture`] and [`Scene::watch_shader`].
+    pub(crate) watched_assets: HashMap<PathBuf, AssetKind>,
+    /// Parked scenes and the running fade transition.  See
+    /// [`Scene::add_scene`] and [`Scene::switch_to`].
+    pub scenes: SceneManager,
+    /// Ambient lighting uploaded with every frame.  See [`crate::lighting`].
+    pub lighting: Lighting,
+    /// Seconds of animation time: drives material
+    /// [`crate::material::Displacement`].  Advanced by
+    /// [`Self::update_time`]; set it directly to pause or scrub.  Double
+    /// precision, so waves stay smooth in sessions of any length.
+    pub time: f64,
+    /// Pixel-space 2D elements drawn over the frame.  See [`crate::overlay`].
+    pub overlay: Overlay,
+    /// Sound playback, opened by [`Scene::enable_audio`].
+    #[cfg(all(feature = "audio", not(target_arch = "wasm32")))]
+    pub audio: Option<crate::audio::Audio>,
+    /// Worker threads [`Self::draw_world`] splits draw-list preparation
+    /// across: `0` (the default) uses one per CPU core, `1` prepares on the
+    /// calling thread.  The workers are started on first use and kept for
+    /// later frames.  Worlds smaller than
+    /// [`crate::constants::scene::PARALLEL_PREP_MIN_OBJECTS`] and wasm builds
+    /// always prepare on the calling thread.
+    pub prep_threads: usize,
+    /// Draw registry-mesh objects with one instanced call per mesh and
+    /// texture, their transforms kept in a storage buffer that is updated
+    /// only where objects changed.  Pays off from tens of thousands of
+    /// objects.  Ignored where [`Pipeline::supports_instancing`] is `false`.
+    pub instanced_rendering: bool,
+    /// Frustum-cull instanced objects in a compute pass instead of drawing
+    /// every instance; see [`crate::culling`].  Ignored unless
+    /// [`Self::instanced_rendering`] is active and
+    /// [`Pipeline::supports_gpu_culling`] is `true`.
+    pub gpu_culling: bool,
+    /// Frustum outlines and tinting of culled objects; see
+    /// [`crate::frustum`].
+    pub culling_debug: Option<CullingDebug>,
+    /// Thick lines drawn every frame until cleared, for debug drawing; see
+    /// [`crate::lines`].
+    pub lines: Lines,
+    /// Ground grid drawn around the camera.
+    pub grid: Option<Grid>,
+    /// [`Self::culling_debug`] for the frame being drawn.
+    culling_frame: Option<DebugFrame>,
+    /// Backing storage while [`Self::instanced_rendering`] is active.
+    pub(crate) instances: Option<InstanceBuffer>,
+    /// Occlusion baked into the static batches, if any.  See
+    /// [`Scene::set_ambient_occlusion`].
+    ambient_occlusion: Option<AoSettings>,
+    /// Backdrop drawn behind everything outside editor mode.  See
+    /// [`Scene::set_skybox`].
+    skybox: Option<BakedMesh>,
+    /// Last cursor position over the window.  See [`Scene::cursor_position`].
+    cursor: Option<[f32; 2]>,
+    /// Depth buffer of the [`Overlay::minimap`] pass while one is shown.
+    minimap_target: Option<TextureTarget>,
+    /// Mirror and portal settings keyed by object ID.  See
+    /// [`Scene::spawn_mirror`].
+    mirrors: BTreeMap<usize, Mirror>,
+    /// Depth buffers of the mirror passes, keyed like [`Self::mirrors`].
+    mirror_targets: HashMap<usize, TextureTarget>,
+    /// Objects tested with occlusion queries.  See
+    /// [`Scene::set_occlusion_query`].
+    occlusion: QueriedObjects,
+    /// Workers kept across frames for draw-list preparation.
+    prep_pool: PrepPool,
+    /// Seeded random numbers for procedural generation; reseed with
+    /// [`SceneRng::reseed`] for a different but reproducible layout.
+    pub rng: SceneRng,
 }
 
 impl Scene {
+    /// Create an empty scene that renders through `pipeline` from `camera`.
+    ///
+    /// [`crate::window::Window`] does this for you.  Call it directly when
+    /// embedding vertra in a host application that owns the window and event
+    /// loop (see [`Pipeline::from_window_handle`]); the host then calls
+    /// [`Self::draw_world`] each frame and [`Self::resize`] on size changes.
+    pub fn new(pipeline: Pipeline, camera: Camera) -> Self {
+        let (width, height) = (pipeline.surface_config.width, pipeline.surface_config.height);
+        Self {
+            pipeline,
+            mesh_registry: MeshRegistry::new(),
+            camera,
+            world: World::new(),
+            editor: None,
+            textures: HashMap::new(),
+            snapshot: None,
+            snapshot_meshes: HashMap::new(),
+            batch_shapes: HashMap::new(),
+            script_registry: ScriptRegistry::new(),
+            resources: Resources::new(),
+            render_hooks: RenderHooks::new(),
+            static_batches: StaticBatches::new(),
+            file_watcher: FileWatcher::new(),
+            saved_files: Mutex::new(Vec::new()),
+            watched_assets: HashMap::new(),
+            scenes: SceneManager::new(),
+            lighting: Lighting::default(),
+            time: 0.0,
+            overlay: Overlay::new(width as f32, height as f32),
+            #[cfg(all(feature = "audio", not(target_arch = "wasm32")))]
+            audio: None,
+            prep_threads: 0,
+            instanced_rendering: false,
+            gpu_culling: false,
+            culling_debug: None,
+            lines: Lines::new(),
+            grid: None,
+            culling_frame: None,
+            instances: None,
+            ambient_occlusion: None,
+            skybox: None,
+            cursor: None,
+            minimap_target: None,
+            mirrors: BTreeMap::new(),
+            mirror_targets: HashMap::new(),
+            occlusion: QueriedObjects::default(),
+            prep_pool: PrepPool::default(),
+            rng: SceneRng::default(),
+        }
+    }
+
+    /// Resize the render target to `width` × `height` physical pixels and
+    /// update the camera aspect ratio, overlay camera and editor viewport to
+    /// match.
+    ///
+    /// Zero-sized requests (e.g. a minimised window) are ignored.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        if width == 0 || height == 0 {
+            return;
+        }
+        self.pipeline.resize(winit::dpi::PhysicalSize::new(width, height));
+        // The pipeline may clamp to the device's maximum texture size.
+        let (w, h) = (self.pipeline.surface_config.width, self.pipeline.surface_config.height);
+        self.overlay.camera = overlay::OverlayCamera::new(w as f32, h as f32)
+            .with_scale_factor(self.overlay.camera.scale_factor);
+        self.apply_viewport();
+    }
+
+    /// Keep the 3D view at aspect ratio `aspect` (`width / height`) whatever
+    /// the window's shape, with black bars filling the rest; `None` fills
+    /// the window again.  See [`Pipeline::set_fixed_aspect`].
+    ///
+    /// The camera and editor follow the letterboxed [`Self::viewport`].
+    pub fn set_fixed_aspect(&mut self, aspect: Option<f32>) {
+        self.pipeline.set_fixed_aspect(aspect);
+        self.apply_viewport();
+    }
+
+    /// The pixel rectangle the 3D scene is rendered into, in physical
+    /// pixels.  Pass it to [`Camera::screen_ray`] and friends.
+    pub fn viewport(&self) -> Viewport {
+        self.pipeline.viewport()
+    }
+
+    /// Show depth, normals, overdraw or a UV checkerboard in place of
+    /// regular shading, e.g. bound to a debug key; [`DebugView::Off`]
+    /// restores it.  See [`Pipeline::set_debug_view`].
+    pub fn set_debug_view(&mut self, view: DebugView) {
+        self.pipeline.set_debug_view(view);
+    }
+
+    /// The active debug view.
+    pub fn debug_view(&self) -> DebugView {
+        self.pipeline.debug_view()
+    }
+
+    /// Match the camera aspect ratio and editor viewport to
+    /// [`Self::viewport`].
+    fn apply_viewport(&mut self) {
+        let viewport = self.viewport();
+        self.camera.aspect = viewport.aspect_ratio();
+        if let Some(ed) = &mut self.editor {
+            ed.set_viewport(viewport);
+        }
+    }
+
+    /// The cursor position in physical window pixels, or `None` while it
+    /// is outside the window.  Kept up to date by [`crate::window::Window`],
+    /// including during replays.
+    pub fn cursor_position(&self) -> Option<[f32; 2]> {
+        self.cursor
+    }
+
+    /// Record the cursor position; for hosts that run their own event loop.
+    pub fn set_cursor_position(&mut self, position: Option<[f32; 2]>) {
+        self.cursor = position;
+    }
+
+    /// Snap the camera to the axis-aligned view under the cursor if it is
+    /// over a disc of [`Overlay::view_axes`] with
+    /// [`ViewAxes::snap_on_click`](crate::view_axes::ViewAxes::snap_on_click)
+    /// on.  The camera orbits the editor pivot in editor mode and its own
+    /// target otherwise.  Returns `true` if the click was used.
+    ///
+    /// Called by [`crate::window::Window`] on every left click, before the
+    /// editor sees it.
+    pub fn click_view_axes(&mut self) -> bool {
+        let (Some(axes), Some(cursor)) = (&self.overlay.view_axes, self.cursor) else { return false };
+        if !axes.snap_on_click {
+            return false;
+        }
+        let point = self.overlay.camera.from_physical(cursor);
+        let Some(axis) = axes.hit(&self.camera, self.overlay.camera.size(), point) else { return false };
+        let pivot = self.editor.as_ref().map_or(self.camera.target, |ed| ed.pivot);
+        view_axes::snap_camera(&mut self.camera, axis, pivot);
+        true
+    }
+
+    /// The world-space ray under the cursor, as `(origin, direction)`; see
+    /// [`Camera::screen_ray`].
+    pub fn cursor_ray(&self) -> Option<([f32; 3], [f32; 3])> {
+        self.camera.screen_ray(self.cursor?, self.viewport())
+    }
+
+    /// Where the cursor ray meets the horizontal plane at height `y_plane`,
+    /// e.g. `0.0` for the ground.  `None` without a cursor, or when the
+    /// ray runs parallel to or away from the plane.
+    ///
+    /// ```rust,ignore
+    /// // Click to drop a cube.
+    /// if let Some([x, _, z]) = scene.cursor_world_point(0.0) {
+    ///     scene.spawn(Object::builder().geometry(Geometry::Cube { size: 1.0 }).at(x, 0.5, z).build(), None);
+    /// }
+    /// ```
+    pub fn cursor_world_point(&self, y_plane: f32) -> Option<[f32; 3]> {
+        let (origin, direction) = self.cursor_ray()?;
+        let t = (y_plane - origin[1]) / direction[1];
+        (t.is_finite() && t >= 0.0).then(|| [origin[0] + direction[0] * t, y_plane, origin[2] + direction[2] * t])
+    }
+
+    /// The nearest object under the cursor that the camera renders; see
+    /// [`World::raycast`].  Editor gizmos are never hit.
+    pub fn cursor_hit(&self) -> Option<RayHit> {
+        let (origin, direction) = self.cursor_ray()?;
+        let gizmos = self.editor.as_ref().map(|editor| &editor.gizmo_ids);
+        self.world.raycast(origin, direction, |id, object| {
+            self.camera.sees_layer(object.layer) && !gizmos.is_some_and(|ids| ids.contains(&id))
+        })
+    }
+
+    /// Display scale factor (physical pixels per logical pixel), as last
+    /// set by [`Self::set_scale_factor`].
+    pub fn scale_factor(&self) -> f32 {
+        self.overlay.camera.scale_factor
+    }
+
+    /// Record the display scale factor, e.g. `2.0` on a high-DPI panel.
+    /// The overlay is laid out in logical pixels from then on.
+    /// [`crate::window::Window`] calls this on start-up and whenever the
+    /// window moves to a display with a different DPI.
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        if scale_factor > 0.0 {
+            self.overlay.camera.scale_factor = scale_factor;
+        }
+    }
+
     /// Spawn `object` into the scene, optionally as a child of `parent_id`.
     ///
     /// This is a thin convenience wrapper around
@@ -70,6 +438,180 @@ impl Scene {
         self.world.spawn_object(object, parent_id)
     }
 
+    /// Spawn `object` as a child of `parent_id`, its transform relative to
+    /// the parent's (so it follows the parent when that moves).
+    ///
+    /// Unlike [`Self::spawn`], a missing parent is an error: returns `None`
+    /// and spawns nothing.
+    pub fn spawn_child(&mut self, parent_id: usize, object: Object) -> Option<usize> {
+        self.world.objects.contains_key(&parent_id).then(|| self.world.spawn_object(object, Some(parent_id)))
+    }
+
+    /// Like [`Self::spawn_child`], but `object.transform` is given in world
+    /// space and converted to the parent's space (see
+    /// [`Transform::relative_to`]), so the object appears exactly there.
+    pub fn spawn_child_at_world(&mut self, parent_id: usize, mut object: Object) -> Option<usize> {
+        let parent = self.world.world_transform(parent_id)?;
+        object.transform = object.transform.relative_to(&parent);
+        Some(self.world.spawn_object(object, Some(parent_id)))
+    }
+
+    /// Like [`Self::spawn`], with a [`MeshUsage`] hint for the renderer.
+    ///
+    /// [`MeshUsage::Static`] objects are merged into cached per-texture
+    /// batches that are uploaded once into immutable buffers, so thousands of
+    /// never-moving objects cost nothing to re-flatten each frame.  If a static
+    /// object is moved anyway, call [`Self::invalidate_static_batches`] (or
+    /// re-hint it as dynamic with [`Self::set_mesh_usage`]) to see the change.
+    ///
+    /// Static batching is bypassed while editor mode is active so gizmo edits
+    /// stay visible; the cache is rebuilt when play mode is entered.
+    pub fn spawn_with_usage(&mut self, object: Object, parent_id: Option<usize>, usage: MeshUsage) -> usize {
+        let id = self.world.spawn_object(object, parent_id);
+        self.static_batches.set_usage(id, usage);
+        id
+    }
+
+    /// Spawn every object of `objects` under `parent_id` (or at the root)
+    /// with one [`MeshUsage`] hint, returning their IDs in order.
+    ///
+    /// The world is grown once up front.  Dynamic objects sharing the same
+    /// [`crate::geometry::Geometry`] (and no [`Object::mesh`]) point at one
+    /// registry mesh per distinct shape, baked once here and drawn with
+    /// each object's transform and colour (instanced under
+    /// [`Self::instanced_rendering`]) instead of re-flattening every copy
+    /// each frame.  They keep their geometry for picking, VTR and
+    /// snapshots; once it changes, the geometry is drawn instead.  Shapes
+    /// appearing once are left alone, and static objects are merged into
+    /// one batch rebuild as usual.
+    pub fn spawn_batch(
+        &mut self,
+        objects: impl IntoIterator<Item = Object>,
+        parent_id: Option<usize>,
+        usage: MeshUsage,
+    ) -> Vec<usize> {
+        let mut objects: Vec<Object> = objects.into_iter().collect();
+        if usage == MeshUsage::Dynamic {
+            self.share_batch_geometry(&mut objects);
+        }
+        self.world.reserve(objects.len());
+        objects
+            .into_iter()
+            .map(|object| self.spawn_with_usage(object, parent_id, usage))
+            .collect()
+    }
+
+    /// Bake each geometry used by more than one mesh-less object in
+    /// `objects` into the registry once, and point those objects at it.
+    fn share_batch_geometry(&mut self, objects: &mut [Object]) {
+        let mut shapes: HashMap<[u64; 7], (&Geometry, usize)> = HashMap::new();
+        for geometry in objects.iter().filter(|obj| obj.mesh.is_none()).filter_map(|obj| obj.geometry.as_ref()) {
+            shapes.entry(geometry.bits()).or_insert((geometry, 0)).1 += 1;
+        }
+        let shared: HashMap<[u64; 7], GeometryId> = shapes
+            .into_iter()
+            .filter(|&(_, (_, count))| count > 1)
+            .map(|(key, (shape, _))| {
+                let mut mesh = MeshData::new();
+                shape.generate_mesh_data(&mut mesh, &Transform::default(), [1.0; 4]);
+                let id = self.register_mesh(&mesh);
+                self.batch_shapes.insert(id, shape.clone());
+                (key, id)
+            })
+            .collect();
+        for obj in objects.iter_mut().filter(|obj| obj.mesh.is_none()) {
+            let Some(geometry) = &obj.geometry else { continue };
+            if let Some(&id) = shared.get(&geometry.bits()) {
+                obj.mesh = Some(id);
+            }
+        }
+    }
+
+    /// Spawn the object configured by `builder` under `parent_id` (or at
+    /// the root), applying its [`ObjectBuilder::usage`] hint.
+    ///
+    /// Returns the integer ID assigned by [`crate::world::World`].
+    pub fn spawn_built(&mut self, builder: ObjectBuilder, parent_id: Option<usize>) -> usize {
+        let usage = builder.mesh_usage();
+        self.spawn_with_usage(builder.build(), parent_id, usage)
+    }
+
+    /// Spawn a static `size` × `size` floor at `y = 0` (see
+    /// [`presets::ground_plane`]).
+    pub fn spawn_ground_plane(&mut self, size: f32) -> usize {
+        self.spawn_built(presets::ground_plane(size), None)
+    }
+
+    /// Spawn red, green and blue arrows of `length` along the X, Y and Z
+    /// axes of `parent_id`, or of the world at the root.  The mesh is
+    /// registered in [`Self::mesh_registry`].
+    pub fn spawn_debug_axes(&mut self, length: f32, parent_id: Option<usize>) -> usize {
+        let mesh = self.register_mesh(&presets::debug_axes_mesh(length));
+        self.spawn_built(Object::builder().name("Axes").mesh(mesh), parent_id)
+    }
+
+    /// Spawn a small sphere marking a point light at `position` (see
+    /// [`presets::point_light_marker`]).
+    pub fn spawn_point_light_marker(&mut self, position: [f32; 3]) -> usize {
+        self.spawn_built(presets::point_light_marker(position), None)
+    }
+
+    /// Show a sky-blue backdrop; see [`Self::set_skybox`] for custom
+    /// colours.
+    pub fn spawn_skybox(&mut self) {
+        use crate::constants::presets::{SKY_BOTTOM, SKY_HORIZON, SKY_TOP};
+        self.set_skybox(Some(&presets::skybox_mesh(SKY_TOP, SKY_HORIZON, SKY_BOTTOM)));
+    }
+
+    /// Draw `mesh` unlit and behind everything else, or remove the backdrop
+    /// with `None`.  [`presets::skybox_mesh`] builds a suitable box.
+    ///
+    /// The mesh is not an object: it is not picked, saved or culled.  Editor
+    /// mode shows its own backdrop instead.
+    pub fn set_skybox(&mut self, mesh: Option<&MeshData>) {
+        self.skybox = mesh.map(|mesh| self.pipeline.create_baked_mesh(&mesh.vertices, &mesh.indices));
+    }
+
+    /// Change the usage hint of an existing object.
+    ///
+    /// Returns `false` if no object with `id` exists.
+    pub fn set_mesh_usage(&mut self, id: usize, usage: MeshUsage) -> bool {
+        if !self.world.objects.contains_key(&id) {
+            return false;
+        }
+        self.static_batches.set_usage(id, usage);
+        true
+    }
+
+    /// Usage hint of object `id` ([`MeshUsage::Dynamic`] unless it was
+    /// spawned or re-hinted as static).
+    pub fn mesh_usage(&self, id: usize) -> MeshUsage {
+        self.static_batches.usage(id)
+    }
+
+    /// Rebuild the static batches on the next frame, e.g. after moving or
+    /// recoloring a static object.
+    pub fn invalidate_static_batches(&mut self) {
+        self.static_batches.invalidate();
+    }
+
+    /// Bake ambient occlusion into the static batches with `settings`, or
+    /// stop with `None`.  The batches are rebuilt on the next frame.
+    ///
+    /// Every static object occludes every other, so contact shadows appear
+    /// where static objects meet.  Dynamic and registry-mesh objects neither
+    /// receive nor cast occlusion.  Baking runs on the CPU whenever the
+    /// batches are rebuilt, which takes noticeable time for large scenes.
+    pub fn set_ambient_occlusion(&mut self, settings: Option<AoSettings>) {
+        self.ambient_occlusion = settings;
+        self.static_batches.invalidate();
+    }
+
+    /// The settings static batches are baked with, if any.
+    pub fn ambient_occlusion(&self) -> Option<AoSettings> {
+        self.ambient_occlusion
+    }
+
     /// Upload raw RGBA pixel data and register it under `path_key`.
     ///
     /// After this call any object whose `texture_path` equals `path_key` will
@@ -116,6 +658,166 @@ impl Scene {
         self.textures.contains_key(path_key)
     }
 
+    /// Upload `mesh` once and register it in [`Scene::mesh_registry`].
+    ///
+    /// Assign the returned [`GeometryId`] to
+    /// [`Object::mesh`](crate::objects::Object::mesh) on any number of
+    /// objects; each is drawn with its own world transform and color while
+    /// sharing the same GPU buffers.  Vertices are interpreted in the
+    /// object's local space.
+    pub fn register_mesh(&mut self, mesh: &MeshData) -> GeometryId {
+        let baked = mesh.bake(&self.pipeline);
+        self.mesh_registry.insert(baked)
+    }
+
+    /// Register every mesh of `bundle` (see [`Self::register_mesh`]),
+    /// e.g. one loaded with [`crate::vtm::cached_bundle`].
+    ///
+    /// Returns the IDs by mesh name; of meshes sharing a name, the last
+    /// one's ID is kept, but all are registered.
+    pub fn register_mesh_bundle(&mut self, bundle: &MeshBundle) -> HashMap<String, GeometryId> {
+        bundle.meshes.iter().map(|(name, mesh)| (name.clone(), self.register_mesh(mesh))).collect()
+    }
+
+    /// Free the mesh registered under `id` immediately, even if objects still
+    /// reference it (they simply stop rendering it).
+    ///
+    /// Returns `true` if the mesh existed and was removed.
+    pub fn remove_mesh(&mut self, id: GeometryId) -> bool {
+        self.batch_shapes.remove(&id);
+        self.mesh_registry.remove(id).is_some()
+    }
+
+    /// Free every registered mesh that no object uses any more and that is
+    /// not held with [`MeshRegistry::retain`].  Objects of
+    /// [`Self::overlay`] and of parked scenes in [`Self::scenes`] count as
+    /// users.
+    ///
+    /// Call periodically (e.g. after despawning a wave of objects) in
+    /// applications that spawn and despawn constantly.  Meshes registered but
+    /// not yet assigned to an object are freed too, so assign them first.
+    ///
+    /// Returns the number of meshes freed.
+    pub fn collect_mesh_garbage(&mut self) -> usize {
+        let worlds = [&self.world, &self.overlay.world].into_iter().chain(self.scenes.parked_worlds());
+        let freed = self.mesh_registry.collect_garbage(worlds);
+        let registry = &self.mesh_registry;
+        self.batch_shapes.retain(|&id, _| registry.contains(id));
+        freed
+    }
+
+    /// Rebake every dirty chunk of `voxels` into the scene.
+    ///
+    /// Each chunk with visible faces is one object named `voxel chunk
+    /// [x, y, z]` at [`VoxelGrid::chunk_origin`], drawing a registered mesh
+    /// from [`VoxelGrid::mesh_chunk`].  A rebaked chunk swaps its object's
+    /// mesh and frees the old one; chunks left without faces lose their
+    /// object.  Untouched chunks cost nothing, so call this after every
+    /// batch of edits.
+    ///
+    /// Returns the number of chunks rebaked.
+    pub fn sync_voxels(&mut self, voxels: &mut VoxelGrid) -> usize {
+        let dirty = voxels.take_dirty();
+        self.rebake_voxel_chunks(voxels, &dirty);
+        dirty.len()
+    }
+
+    /// Rebake chunks `keys` of `voxels` (see [`Self::sync_voxels`]).
+    fn rebake_voxel_chunks(&mut self, voxels: &mut VoxelGrid, keys: &[ChunkKey]) {
+        for &key in keys {
+            let mesh = voxels.mesh_chunk(key);
+            let previous = voxels.baked.remove(&key);
+            if let Some((_, old)) = previous {
+                self.remove_mesh(old);
+            }
+            let live = previous.map(|(object, _)| object).filter(|&id| self.world.objects.contains_key(&id));
+            if mesh.indices.is_empty() {
+                if let Some(object) = live {
+                    self.world.delete(object);
+                }
+                continue;
+            }
+            let geometry = self.register_mesh(&mesh);
+            let object = match live {
+                Some(id) => {
+                    self.world.set_mesh(id, Some(geometry));
+                    id
+                }
+                None => {
+                    let [x, y, z] = VoxelGrid::chunk_origin(key);
+                    let chunk = Object::builder()
+                        .name(format!("voxel chunk {key:?}"))
+                        .mesh(geometry)
+                        .transform(Transform::from_position(x, y, z))
+                        .build();
+                    self.spawn(chunk, None)
+                }
+            };
+            voxels.baked.insert(key, (object, geometry));
+        }
+    }
+
+    /// Advance `streamer` to the camera: despawn the objects of cells that
+    /// went out of range and spawn those of up to
+    /// [`Streamer::bake_budget`] generated cells, registering their meshes.
+    /// Cells whose generator panicked are reported in
+    /// [`StreamStats::failed`].  Call once per frame.
+    pub fn stream_objects(&mut self, streamer: &mut Streamer<ObjectGroup>) -> StreamStats {
+        profile_scope!("Scene::stream_objects");
+        let update = streamer.update(self.camera.eye);
+        for key in &update.unload {
+            let Some((objects, meshes)) = streamer.spawned.remove(key) else { continue };
+            for id in objects {
+                self.world.delete(id);
+            }
+            for mesh in meshes {
+                self.remove_mesh(mesh);
+            }
+        }
+        let loaded = update.load.len();
+        for (key, group) in update.load {
+            let mut spawned = (Vec::with_capacity(group.objects.len()), Vec::new());
+            for (mut object, mesh) in group.objects {
+                if let Some(mesh) = mesh {
+                    let id = self.register_mesh(&mesh);
+                    object.mesh = Some(id);
+                    spawned.1.push(id);
+                }
+                spawned.0.push(self.spawn(object, None));
+            }
+            streamer.spawned.insert(key, spawned);
+        }
+        StreamStats { loaded, unloaded: update.unload.len(), pending: streamer.pending(), failed: update.failed }
+    }
+
+    /// Advance `streamer` to the camera: take chunks that went out of range
+    /// out of `voxels`, put in up to [`Streamer::bake_budget`] generated
+    /// ones, and rebake as many dirty chunks, nearest to the camera first.
+    /// Call once per frame; [`StreamStats::pending`] counts the chunks
+    /// still waiting for a rebake too, and [`StreamStats::failed`] reports
+    /// chunks whose generator panicked.
+    pub fn stream_voxels(&mut self, streamer: &mut Streamer<Chunk>, voxels: &mut VoxelGrid) -> StreamStats {
+        profile_scope!("Scene::stream_voxels");
+        let update = streamer.update(self.camera.eye);
+        for &key in &update.unload {
+            voxels.remove_chunk(key);
+        }
+        let loaded = update.load.len();
+        for (key, chunk) in update.load {
+            voxels.insert_chunk(key, chunk);
+        }
+        let (center, _) = VoxelGrid::locate(self.camera.eye.map(|v| v.floor() as i32));
+        let dirty = voxels.take_dirty_nearest(center, streamer.bake_budget);
+        self.rebake_voxel_chunks(voxels, &dirty);
+        let waiting = voxels.dirty_count();
+        StreamStats {
+            loaded,
+            unloaded: update.unload.len(),
+            pending: streamer.pending() + waiting,
+            failed: update.failed,
+        }
+    }
+
     /// Traverse the entire scene graph and issue a single batched draw call
     /// per texture group.
     ///
@@ -123,44 +825,541 @@ impl Scene {
     /// bind-group switches is minimised.  The editor gizmo overlay (if any) is
     /// rendered as a separate pass on top.
     ///
+    /// The CPU half of the frame — visibility, grouping and model matrices —
+    /// runs first without touching the GPU, split across worker threads in
+    /// large worlds (see [`Self::prep_threads`]).  With
+    /// [`Self::instanced_rendering`] on, registry-mesh objects are drawn
+    /// instanced instead of one draw call each.
+    ///
+    /// Returns the frame's counters, or why the frame was dropped (see
+    /// [`RenderError`]).  Ends the world's frame first, publishing
+    /// [`World::changes`].
+    ///
     /// Called automatically by [`crate::window::Window`] every frame on
     /// `RedrawRequested`.  You do not normally need to call this manually.
-    pub fn draw_world(&mut self) -> RenderStats {
-        // Group object geometry by texture_path so we minimise bind-group switches.
-        let mut groups: HashMap<Option<String>, MeshData> = HashMap::new();
-        let identity = Transform::default();
-        for &root_id in &self.world.roots {
-            collect_by_texture(&self.world, root_id, &identity, &mut groups);
+    pub fn draw_world(&mut self) -> Result<RenderStats, RenderError> {
+        profile_scope!("Scene::draw_world");
+        self.world.end_frame();
+        self.culling_frame = self.culling_debug.as_ref().map(|debug| debug.frame(&self.camera));
+        self.prepare_water()?;
+        self.prepare_minimap()?;
+        self.prepare_mirrors()?;
+        self.render_frame()
+    }
+
+    /// Render the world from [`Self::camera`] into the frame, or into the
+    /// pipeline's capture target while one is set.
+    fn render_frame(&mut self) -> Result<RenderStats, RenderError> {
+        // Static batching is bypassed in editor mode so gizmo edits show up,
+        // and in capture passes that see other layers than the batches, so
+        // minimap and mirror views do not rebuild them every frame.
+        let capturing = self.pipeline.is_capturing();
+        let use_static = self.editor.is_none()
+            && (!capturing || self.static_batches.layers_mask() == self.camera.layers_mask);
+        if use_static {
+            self.static_batches.prune(&self.world);
+            self.static_batches.set_layers_mask(self.camera.layers_mask);
+            if self.static_batches.is_dirty() {
+                self.rebuild_static_batches();
+            }
+        }
+        // Occlusion results only apply to the scene camera.
+        if !capturing {
+            let objects = &self.world.objects;
+            self.occlusion.prune(|id| objects.contains_key(&id));
+            if let Some(results) = self.pipeline.occlusion_results() {
+                self.occlusion.apply(&results);
+            }
+        }
+        // The overlay, grid and line work runs on this thread while the
+        // workers walk the world.
+        let threads = self.prep_thread_count();
+        let (DrawList { groups, mesh_objects, queried }, overlay_items) = {
+            profile_scope!("prepare");
+            let Self {
+                world, overlay, pipeline, textures, camera, grid, lines, culling_debug, culling_frame, time,
+                static_batches, occlusion, prep_pool, batch_shapes, ..
+            } = self;
+            let filter = DrawFilter {
+                layers_mask: camera.layers_mask,
+                static_ids: use_static.then(|| static_batches.ids()),
+                batch_shapes,
+                occlusion: (!capturing).then_some(&*occlusion),
+                tint: culling_debug.as_ref().zip(culling_frame.as_ref().map(|frame| &frame.culling)),
+            };
+            prep_pool.prepare(threads, &world.objects, &world.roots, &filter, || {
+                overlay.apply_layout();
+                let mut overlay_items = overlay::collect(&overlay.world);
+                if let Some(map) = &overlay.minimap && textures.contains_key(minimap::TEXTURE_KEY) {
+                    let size = overlay.camera.size();
+                    overlay_items.push(OverlayItem::Batch(Some(minimap::TEXTURE_KEY.to_string()), map.quad(size)));
+                    overlay_items.push(OverlayItem::Batch(None, map.marker(camera, size)));
+                }
+                if let Some(axes) = &overlay.view_axes {
+                    overlay_items.push(OverlayItem::Batch(None, axes.build(camera, overlay.camera.size())));
+                }
+                pipeline.set_time(*time);
+
+                // Thick lines: the debug list, the grid and any frustum outlines.
+                let grid = grid.map(|grid| grid.lines(camera.eye)).unwrap_or_default();
+                let outlines = culling_debug.as_ref().zip(culling_frame.as_ref())
+                    .map(|(debug, frame)| debug.outline_lines(frame, !capturing))
+                    .unwrap_or_default();
+                pipeline.set_lines(&[lines, &grid, &outlines]);
+                overlay_items
+            })
+        };
+        if !capturing {
+            let boxes = self.occlusion_boxes(&queried);
+            self.pipeline.queue_occlusion_queries(boxes);
         }
+        let instanced_groups = if self.instanced_rendering && self.pipeline.supports_instancing() {
+            self.prepare_instances(&mesh_objects)
+        } else {
+            self.instances = None;
+            Vec::new()
+        };
+        let mesh_objects = if self.instances.is_some() { Vec::new() } else { mesh_objects };
+        let static_keys = if use_static { self.static_batches.batches() } else { &[] }.iter().map(|(key, _)| key);
+        let uniform_batches =
+            groups.iter().map(|(key, _)| key).chain(static_keys).filter(|key| key.material.has_model_uniform()).count();
+        let uniform_instanced = instanced_groups.iter().filter(|(_, _, material, _)| material.has_model_uniform()).count();
+        self.pipeline.reserve_mesh_draws(mesh_objects.len() + overlay_items.len() + uniform_batches + uniform_instanced);
 
         // Bake each group - collect into Vec so we own the BakedMeshes before
         // taking any references out of `self.pipeline`.
-        let baked_groups: Vec<(Option<String>, crate::mesh::BakedMesh)> = groups
+        let baked_groups: Vec<(BatchKey, crate::mesh::BakedMesh)> = groups
             .into_iter()
-            .map(|(key, mesh_data)| (key, mesh_data.bake(&self.pipeline)))
+            .map(|(key, mesh_data)| (key, mesh_data.bake_with_usage(&self.pipeline, MeshUsage::Dynamic)))
             .collect();
+        let static_groups = if use_static { self.static_batches.batches() } else { &[] };
 
         // Pair each baked mesh with the matching bind group (or default white).
-        let world_batches: Vec<(&crate::mesh::BakedMesh, &wgpu::BindGroup)> = baked_groups
+        let world_batches: Vec<(&crate::mesh::BakedMesh, &wgpu::BindGroup, Material)> = static_groups
             .iter()
+            .chain(baked_groups.iter())
             .map(|(key, baked)| {
                 let bg: &wgpu::BindGroup = key
+                    .texture_path
                     .as_ref()
                     .and_then(|p| self.textures.get(p))
                     .map(|e| &e.bind_group)
                     .unwrap_or(&self.pipeline.default_texture_bind_group);
-                (baked, bg)
+                (baked, bg, key.material)
+            })
+            .collect();
+
+        // Objects referencing a registry mesh are drawn individually with
+        // their world transform; stale IDs are skipped.
+        let tint = self.culling_debug.as_ref().zip(self.culling_frame.as_ref());
+        let mesh_draws: Vec<MeshDraw> = mesh_objects
+            .iter()
+            .filter_map(|&(object_id, model)| {
+                let obj = self.world.objects.get(&object_id)?;
+                let mesh = self.mesh_registry.get(obj.mesh?)?;
+                let texture = obj.texture_path.as_ref().and_then(|p| self.textures.get(p));
+                Some(MeshDraw {
+                    mesh,
+                    texture_bind_group: texture.map_or(&self.pipeline.default_texture_bind_group, |e| &e.bind_group),
+                    model,
+                    color: tint.map_or(obj.color, |(debug, frame)| debug.mesh_color(frame, mesh, &model, &obj.material, obj.color)),
+                    variant: ShaderVariant::DEFAULT.with_textured(texture.is_some()),
+                    material: obj.material,
+                })
             })
             .collect();
 
-        // Build gizmo overlay for the selected object (if editor is active).
-        let overlay_baked = self.editor.as_ref()
+        // Build gizmo overlay for the selected object (if editor is active
+        // and the camera renders the gizmo layer).
+        let mut overlay_mesh = MeshData::new();
+        if let Some((vertices, indices)) = self.editor.as_ref()
+            .filter(|_| self.camera.sees_layer(layers::GIZMO))
             .and_then(|ed| ed.gizmo_overlay_for_selection(&self.world, &self.camera))
-            .map(|(v, i)| self.pipeline.create_baked_mesh(&v, &i));
+        {
+            overlay_mesh.append(MeshData { vertices, indices, ..MeshData::new() });
+        }
+        let overlay_baked = (!overlay_mesh.indices.is_empty())
+            .then(|| self.pipeline.create_baked_mesh(&overlay_mesh.vertices, &overlay_mesh.indices));
+
+        // Overlay batches are flattened in pixel space; the camera alone
+        // places them.
+        let overlay_batches: Vec<Option<crate::mesh::BakedMesh>> = overlay_items
+            .iter()
+            .map(|item| match item {
+                OverlayItem::Batch(_, mesh_data) => Some(mesh_data.bake_with_usage(&self.pipeline, MeshUsage::Dynamic)),
+                OverlayItem::Mesh(..) => None,
+            })
+            .collect();
+        let screen_draws: Vec<MeshDraw> = overlay_items
+            .iter()
+            .zip(&overlay_batches)
+            .filter_map(|(item, baked)| {
+                let (texture_path, mesh, model, color) = match item {
+                    OverlayItem::Batch(key, _) => {
+                        (key.as_ref(), baked.as_ref()?, crate::math::Matrix4::identity(), [1.0; 4])
+                    }
+                    OverlayItem::Mesh(object_id, world_transform) => {
+                        let obj = self.overlay.world.objects.get(object_id)?;
+                        let mesh = self.mesh_registry.get(obj.mesh?)?;
+                        (obj.texture_path.as_ref(), mesh, world_transform.to_matrix(), obj.color)
+                    }
+                };
+                let texture = texture_path.and_then(|p| self.textures.get(p));
+                Some(MeshDraw {
+                    mesh,
+                    texture_bind_group: texture.map_or(&self.pipeline.default_texture_bind_group, |e| &e.bind_group),
+                    model,
+                    color,
+                    variant: ShaderVariant { lit: false, textured: texture.is_some() },
+                    material: Material::default(),
+                })
+            })
+            .collect();
+        let screen = ScreenDraws { view_projection: self.overlay.camera.view_projection(), draws: screen_draws };
+        let instanced = self.instances.as_ref().map(|instances| InstancedDraws {
+            instances,
+            gpu_culling: self.gpu_culling && self.pipeline.supports_gpu_culling(),
+            cull_view_projection: self.culling_frame.as_ref()
+                .filter(|_| !capturing)
+                .and_then(|frame| frame.frozen_view_projection),
+            draws: instanced_groups
+                .iter()
+                .filter_map(|(mesh, texture_path, material, instances)| {
+                    let texture = texture_path.as_ref().and_then(|p| self.textures.get(p));
+                    Some(InstancedDraw {
+                        mesh: self.mesh_registry.get(*mesh)?,
+                        texture_bind_group: texture.map_or(&self.pipeline.default_texture_bind_group, |e| &e.bind_group),
+                        variant: ShaderVariant::DEFAULT.with_textured(texture.is_some()),
+                        material: *material,
+                        instances: instances.clone(),
+                    })
+                })
+                .collect(),
+        });
 
         let camera = &self.camera;
-        let skybox = self.editor.as_ref().and_then(|ed| ed.skybox.as_ref());
-        self.pipeline.render_scene(camera, &world_batches, skybox, overlay_baked.as_ref())
+        let skybox = match &self.editor {
+            Some(editor) => editor.skybox.as_ref(),
+            None => self.skybox.as_ref(),
+        };
+        let mut stats = self.pipeline.render_scene(FrameInputs {
+            world_batches: &world_batches,
+            mesh_draws: &mesh_draws,
+            instanced: instanced.as_ref(),
+            skybox,
+            overlay: overlay_baked.as_ref(),
+            screen: Some(&screen),
+            hooks: Some((&mut self.render_hooks, &self.resources)),
+            ..FrameInputs::new(camera, &self.lighting)
+        })?;
+        stats.memory = self.gpu_memory();
+        Ok(stats)
+    }
+
+    /// GPU memory held by the scene and its pipeline: registry meshes,
+    /// static batches, the editor skybox, textures, instance buffers,
+    /// uniforms and render targets.  Also reported per frame as
+    /// [`RenderStats::memory`].
+    ///
+    /// Parked scenes in [`Self::scenes`] are not included.
+    pub fn gpu_memory(&self) -> GpuMemory {
+        let mut memory = self.pipeline.gpu_memory();
+        let skybox = self.editor.as_ref().and_then(|ed| ed.skybox.as_ref()).into_iter().chain(&self.skybox);
+        let static_meshes = self.static_batches.batches().iter().map(|(_, mesh)| mesh);
+        for mesh in self.mesh_registry.meshes().chain(static_meshes).chain(skybox) {
+            memory.add_mesh(mesh);
+        }
+        memory.texture_bytes += self.textures.values().map(|entry| pipeline::texture_bytes(&entry.texture)).sum::<u64>();
+        for target in self.minimap_target.iter().chain(self.mirror_targets.values()) {
+            memory.render_target_bytes += pipeline::texture_bytes(&target.depth);
+        }
+        if let Some(instances) = &self.instances {
+            memory.storage_bytes += instances.data_buffer().size() + instances.order_buffer().size();
+        }
+        memory
+    }
+
+    /// Render the world from `position` into the six layers of a new
+    /// `resolution` × `resolution` cube texture in [`CUBEMAP_FORMAT`], for
+    /// use as a reflection environment map or a baked skybox.  Bind it with
+    /// a [`wgpu::TextureViewDimension::Cube`] view.
+    ///
+    /// Each face sees the layers and clip planes of [`Self::camera`] with a
+    /// 90° field of view.  Post-processing and the screen overlay are not
+    /// applied.  Costs six full renders, so capture once or rarely.  Fails
+    /// only if the device runs out of memory.
+    pub fn capture_cubemap(&mut self, position: [f32; 3], resolution: u32) -> Result<wgpu::Texture, RenderError> {
+        let resolution = resolution.clamp(1, self.pipeline.device.limits().max_texture_dimension_2d);
+        let texture = self.pipeline.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Captured Cubemap"),
+            size: wgpu::Extent3d { width: resolution, height: resolution, depth_or_array_layers: 6 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: CUBEMAP_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let depth = self.pipeline.create_depth_view(winit::dpi::PhysicalSize::new(resolution, resolution));
+        let main_camera = self.camera;
+        let mut result = Ok(());
+        for (layer, (forward, up)) in (0..).zip(CUBE_FACES) {
+            let color = texture.create_view(&wgpu::TextureViewDescriptor {
+                label: Some("cubemap_face_view"),
+                dimension: Some(wgpu::TextureViewDimension::D2),
+                base_array_layer: layer,
+                array_layer_count: Some(1),
+                ..Default::default()
+            });
+            self.pipeline.set_capture_target(Some(CaptureTarget { color, depth: depth.clone(), view_projection: None }));
+            self.camera = Camera {
+                eye: position,
+                target: [position[0] + forward[0], position[1] + forward[1], position[2] + forward[2]],
+                up,
+                fov: 90.0,
+                aspect: 1.0,
+                ..main_camera
+            };
+            if let Err(e) = self.render_frame() {
+                result = Err(e);
+                break;
+            }
+        }
+        self.pipeline.set_capture_target(None);
+        self.camera = main_camera;
+        result.map(|()| texture)
+    }
+
+    /// Rebuild the water plane if its shape changed and render its
+    /// reflection from the mirrored camera.
+    fn prepare_water(&mut self) -> Result<(), RenderError> {
+        let Some(mut water) = self.pipeline.water.take() else { return Ok(()) };
+        water.update_mesh(&self.pipeline);
+        let target = water.reflection_target(&self.pipeline.device, self.pipeline.viewport());
+        let camera = water.settings.reflection_camera(&self.camera);
+        self.pipeline.water = Some(water);
+        let Some(target) = target else { return Ok(()) };
+        profile_scope!("water_reflection");
+        self.pipeline.set_capture_target(Some(target));
+        let main_camera = std::mem::replace(&mut self.camera, camera);
+        let result = self.render_frame();
+        self.pipeline.set_capture_target(None);
+        self.camera = main_camera;
+        result.map(drop)
+    }
+
+    /// Render the [`Overlay::minimap`] into its texture from the map
+    /// camera, or free the texture once the minimap is removed.
+    fn prepare_minimap(&mut self) -> Result<(), RenderError> {
+        let Some(map) = &self.overlay.minimap else {
+            if self.minimap_target.take().is_some() {
+                self.textures.remove(minimap::TEXTURE_KEY);
+            }
+            return Ok(());
+        };
+        let size = map.target_size(&self.overlay.camera);
+        let camera = map.camera(&self.camera, size[0] as f32 / size[1] as f32);
+        if self.minimap_target.as_ref().is_none_or(|target| target.size != size)
+            || !self.textures.contains_key(minimap::TEXTURE_KEY)
+        {
+            let (target, entry) = TextureTarget::new(&self.pipeline, "Minimap", size, minimap::FORMAT);
+            self.textures.insert(minimap::TEXTURE_KEY.to_string(), entry);
+            self.minimap_target = Some(target);
+        }
+        let (Some(target), Some(entry)) = (&self.minimap_target, self.textures.get(minimap::TEXTURE_KEY)) else {
+            return Ok(());
+        };
+        profile_scope!("minimap");
+        self.pipeline.set_capture_target(Some(target.capture(entry, None)));
+        let main_camera = std::mem::replace(&mut self.camera, camera);
+        let result = self.render_frame();
+        self.pipeline.set_capture_target(None);
+        self.camera = main_camera;
+        result.map(drop)
+    }
+
+    /// Render every mirror and portal facing the camera into its texture,
+    /// and forget mirrors whose object was despawned.
+    fn prepare_mirrors(&mut self) -> Result<(), RenderError> {
+        let despawned: Vec<usize> = self.mirrors.keys().copied().filter(|id| !self.world.objects.contains_key(id)).collect();
+        for id in despawned {
+            self.remove_mirror(id);
+        }
+        if self.mirrors.is_empty() {
+            return Ok(());
+        }
+        profile_scope!("mirrors");
+        let main_camera = self.camera;
+        let ids: Vec<usize> = self.mirrors.keys().copied().collect();
+        let mut result = Ok(());
+        for id in ids {
+            let settings = self.mirrors[&id];
+            let Some(object) = self.world.objects.get(&id) else { continue };
+            let Some(size) = mirror::surface_size(object) else { continue };
+            if !main_camera.sees_layer(object.layer) {
+                continue;
+            }
+            // Keep the sprite showing the texture the right way round.
+            if let Some(crate::geometry::Geometry::Sprite { uv, .. }) = &object.geometry
+                && *uv != settings.uv()
+                && let Some(crate::geometry::Geometry::Sprite { uv, .. }) =
+                    self.world.get_mut(id).and_then(|object| object.geometry.as_mut())
+            {
+                *uv = settings.uv();
+            }
+            let Some(surface) = self.world.world_transform(id) else { continue };
+            let exit = match settings.exit {
+                Some(exit) => match self.world.world_transform(exit) {
+                    Some(transform) => Some(transform),
+                    None => continue,
+                },
+                None => None,
+            };
+            let Some(view) = settings.view(&main_camera, &surface, size, exit.as_ref()) else { continue };
+
+            let key = mirror::texture_key(id);
+            let texture_size = settings.texture_size(size);
+            if self.mirror_targets.get(&id).is_none_or(|target| target.size != texture_size) || !self.textures.contains_key(&key) {
+                let (target, entry) = TextureTarget::new(&self.pipeline, "Mirror", texture_size, mirror::FORMAT);
+                self.textures.insert(key.clone(), entry);
+                self.mirror_targets.insert(id, target);
+            }
+            let (Some(target), Some(entry)) = (self.mirror_targets.get(&id), self.textures.get(&key)) else { continue };
+            self.pipeline.set_capture_target(Some(target.capture(entry, Some(view.view_projection))));
+            self.camera = view.camera;
+            result = self.render_frame().map(drop);
+            if result.is_err() {
+                break;
+            }
+        }
+        self.pipeline.set_capture_target(None);
+        self.camera = main_camera;
+        result
+    }
+
+    /// Push `mesh_objects` into [`Self::instances`] grouped by mesh and
+    /// texture, upload the changes, and return each group's instance range.
+    fn prepare_instances(
+        &mut self,
+        mesh_objects: &[(usize, crate::math::Matrix4)],
+    ) -> Vec<(GeometryId, Option<String>, Material, std::ops::Range<u32>)> {
+        profile_scope!("instances");
+        let mut objects: Vec<_> = mesh_objects
+            .iter()
+            .filter_map(|&(object_id, model)| {
+                let obj = self.world.objects.get(&object_id)?;
+                let data = InstanceData { model: model.data, color: obj.color };
+                Some((obj.mesh?, obj.texture_path.as_ref(), obj.material, object_id, data))
+            })
+            .collect();
+        objects.sort_by(|a, b| (a.0.0, a.1, a.2).cmp(&(b.0.0, b.1, b.2)));
+
+        let instances = self.instances.get_or_insert_with(|| InstanceBuffer::new(&self.pipeline.device));
+        instances.begin_frame();
+        let mut groups: Vec<(GeometryId, Option<String>, Material, std::ops::Range<u32>)> = Vec::new();
+        for (mesh, texture_path, material, object_id, data) in objects {
+            let index = instances.push(object_id, data);
+            match groups.last_mut() {
+                Some((m, t, mat, range)) if *m == mesh && t.as_ref() == texture_path && *mat == material => {
+                    range.end = index + 1
+                }
+                _ => groups.push((mesh, texture_path.cloned(), material, index..index + 1)),
+            }
+        }
+        instances.end_frame();
+        self.pipeline.upload_instances(instances);
+        groups
+    }
+
+    /// Build this frame's [`DrawList`] from [`Self::world`] without touching
+    /// the GPU.
+    ///
+    /// Only reads the scene graph, so the root subtrees are split across
+    /// [`Self::prep_threads`] pooled workers whose partial lists are merged
+    /// in root order; the result is identical to a single-threaded walk.
+    /// Objects in `static_batches` are skipped when `use_static` is set, and
+    /// objects found occluded outside capture passes.  [`Self::draw_world`]
+    /// does the same walk, with the overlay work alongside it.
+    #[cfg(test)]
+    pub(crate) fn prepare_draw_list(&mut self, use_static: bool) -> DrawList {
+        let threads = self.prep_thread_count();
+        let filter = DrawFilter {
+            layers_mask: self.camera.layers_mask,
+            static_ids: use_static.then(|| self.static_batches.ids()),
+            batch_shapes: &self.batch_shapes,
+            occlusion: (!self.pipeline.is_capturing()).then_some(&self.occlusion),
+            tint: self.culling_debug.as_ref().zip(self.culling_frame.as_ref().map(|frame| &frame.culling)),
+        };
+        self.prep_pool.prepare(threads, &self.world.objects, &self.world.roots, &filter, || ()).0
+    }
+
+    /// World boxes of the flagged objects in `queried`.  Objects whose box
+    /// holds the camera are marked visible instead of queried.
+    fn occlusion_boxes(&mut self, queried: &[(usize, crate::math::Matrix4)]) -> Vec<OcclusionBox> {
+        let mut boxes = Vec::with_capacity(queried.len());
+        let mut inside = Vec::new();
+        for &(object_id, model) in queried {
+            let Some(obj) = self.world.objects.get(&object_id) else { continue };
+            let geometry = obj.geometry.as_ref().and_then(|geo| self.occlusion.geometry_bounds(object_id, geo));
+            let mesh = obj.mesh.and_then(|id| self.mesh_registry.get(id)).map(|mesh| {
+                let [x, y, z, r] = mesh.bounding_sphere;
+                ([x - r, y - r, z - r], [x + r, y + r, z + r])
+            });
+            let Some(bounds) = occlusion::union(geometry, mesh) else { continue };
+            let bbox = OcclusionBox::from_local(object_id, bounds, &model).grown(obj.material.displacement.reach());
+            if bbox.contains(self.camera.eye) {
+                inside.push((object_id, true));
+            } else {
+                boxes.push(bbox);
+            }
+        }
+        self.occlusion.apply(&inside);
+        boxes
+    }
+
+    /// Workers draw-list preparation uses this frame: at most one per
+    /// root and never more than one on wasm or below
+    /// [`crate::constants::scene::PARALLEL_PREP_MIN_OBJECTS`].
+    fn prep_thread_count(&self) -> usize {
+        if cfg!(target_arch = "wasm32")
+            || self.prep_threads == 1
+            || self.world.objects.len() < crate::constants::scene::PARALLEL_PREP_MIN_OBJECTS
+        {
+            return 1;
+        }
+        let threads = match self.prep_threads {
+            0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
+        };
+        threads.min(self.world.roots.len())
+    }
+
+    /// Flatten every static object into per-texture groups and upload them
+    /// into immutable buffers.
+    fn rebuild_static_batches(&mut self) {
+        profile_scope!("rebuild_static_batches");
+        let mut groups: HashMap<BatchKey, MeshData> = HashMap::new();
+        let identity = Transform::default();
+        for &root_id in &self.world.roots {
+            collect_static(
+                &self.world, root_id, &identity, self.camera.layers_mask, self.static_batches.ids(), &self.batch_shapes,
+                &mut groups,
+            );
+        }
+        if let Some(settings) = &self.ambient_occlusion {
+            profile_scope!("bake_ambient_occlusion");
+            let occluders = Occluders::new(groups.values());
+            for mesh_data in groups.values_mut() {
+                ao::bake_mesh(mesh_data, &occluders, settings);
+            }
+        }
+        let batches = groups
+            .into_iter()
+            .map(|(key, mesh_data)| (key, mesh_data.bake_with_usage(&self.pipeline, MeshUsage::Static)))
+            .collect();
+        self.static_batches.set_batches(batches);
     }
 
     /// Switch into static editor mode.
@@ -179,14 +1378,20 @@ impl Scene {
                 Ok(data) => {
                     self.camera = data.camera;
                     self.world  = data.world;
+                    for (&id, &mesh) in &self.snapshot_meshes {
+                        self.world.set_mesh(id, Some(mesh));
+                    }
                 }
                 Err(e) => eprintln!("enable_editor_mode: failed to restore snapshot: {e}"),
             }
+            for (_, mesh) in self.snapshot_meshes.drain() {
+                self.mesh_registry.release(mesh);
+            }
         }
 
-        let w = self.pipeline.surface_config.width  as f32;
-        let h = self.pipeline.surface_config.height as f32;
-        let mut ed = EditorState::new(w, h);
+        let viewport = self.viewport();
+        let mut ed = EditorState::new(viewport.width, viewport.height);
+        ed.set_viewport(viewport);
         ed.spawn_gizmos(&mut self.world);
 
         // Bake the skybox once and store it
@@ -210,10 +1415,25 @@ impl Scene {
         // Snapshot current state so we can roll back when returning to editor.
         let mut buf = Vec::new();
         match vtr::write(&mut buf, &self.camera, &self.world) {
-            Ok(()) => self.snapshot = Some(buf),
+            Ok(()) => {
+                self.snapshot = Some(buf);
+                // Hold the snapshot's meshes so garbage collection during
+                // play cannot free them before they are restored.
+                for (_, mesh) in self.snapshot_meshes.drain() {
+                    self.mesh_registry.release(mesh);
+                }
+                self.snapshot_meshes = self.world.objects.iter()
+                    .filter_map(|(&id, obj)| obj.mesh.map(|m| (id, m)))
+                    .collect();
+                for &mesh in self.snapshot_meshes.values() {
+                    self.mesh_registry.retain(mesh);
+                }
+            }
             Err(e) => eprintln!("disable_editor_mode: failed to capture snapshot: {e}"),
         }
         self.editor = None;
+        // Objects may have been edited while static batching was bypassed.
+        self.static_batches.invalidate();
         // Reset all scripts so on_start re-runs against the fresh world that
         // will be restored when the user returns to editor mode.  Without this,
         // cached IDs / base transforms from a previous play session would be
@@ -278,6 +1498,45 @@ impl Scene {
         self.script_registry.has(id)
     }
 
+    /// Store `value` as the scene's resource of type `T`, returning the
+    /// previous one.  Resources outlive [`Self::switch_to`] and are not saved
+    /// with the world.
+    pub fn insert_resource<T: 'static>(&mut self, value: T) -> Option<T> {
+        self.resources.insert(value)
+    }
+
+    /// The resource of type `T`, if one was inserted.
+    pub fn resource<T: 'static>(&self) -> Option<&T> {
+        self.resources.get()
+    }
+
+    /// Mutable access to the resource of type `T`, if one was inserted.
+    pub fn resource_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.resources.get_mut()
+    }
+
+    /// Take the resource of type `T` out of the scene.
+    pub fn remove_resource<T: 'static>(&mut self) -> Option<T> {
+        self.resources.remove()
+    }
+
+    /// Record `hook` into every frame at `stage`, after the hooks already
+    /// registered there.  It receives the frame's command encoder and a
+    /// [`RenderContext`] with the stage's targets, the device and queue, and
+    /// [`Self::resources`]; see [`crate::render_hook`].
+    pub fn add_render_hook(
+        &mut self,
+        stage: RenderStage,
+        hook: impl FnMut(&mut wgpu::CommandEncoder, &RenderContext) + 'static,
+    ) -> RenderHookId {
+        self.render_hooks.add(stage, Box::new(hook))
+    }
+
+    /// Stop running hook `id`.  Returns `false` if it was already removed.
+    pub fn remove_render_hook(&mut self, id: RenderHookId) -> bool {
+        self.render_hooks.remove(id)
+    }
+
     /// Run `on_start` (first call only) + `on_update` for all attached scripts.
     ///
     /// Called automatically by the window loop every frame when not in editor
@@ -300,8 +1559,11 @@ impl Scene {
     ///
     /// # Errors
     /// Returns a [`VtrError`] on I/O failure or serialization problems.
-    pub fn save_vtr_file(&self, path: &std::path::Path) -> Result<(), VtrError> {
-        vtr::write_to_file(path, &self.camera, &self.world)
+    pub fn save_vtr_file(&self, path: &Path) -> Result<(), VtrError> {
+        vtr::write_to_file(path, &self.camera, &self.world)?;
+        // Our own save must not trigger a hot reload of the same file.
+        self.saved_files.lock().unwrap_or_else(PoisonError::into_inner).push(path.to_path_buf());
+        Ok(())
     }
 
     /// Replace the current camera and world with the contents of a `.vtr` file.
@@ -312,44 +1574,565 @@ impl Scene {
     /// # Errors
     /// Returns a [`VtrError`] on I/O failure, bad magic bytes, unsupported
     /// format version, or any other parse error.
-    pub fn load_vtr_file(&mut self, path: &std::path::Path) -> Result<(), VtrError> {
+    pub fn load_vtr_file(&mut self, path: &Path) -> Result<(), VtrError> {
         let data = vtr::read_from_file(path)?;
         self.camera = data.camera;
         self.world  = data.world;
+        self.static_batches.invalidate();
         // World has changed, cached script state (IDs, transforms, etc.) is
         // no longer valid for the new world, so force on_start to re-run.
         self.script_registry.reset_started();
         Ok(())
     }
+
+    /// Bookmark the current viewpoint: write only the camera to `path`,
+    /// as a `.vtr` file without objects.
+    ///
+    /// # Errors
+    /// Returns a [`VtrError`] on I/O failure.
+    pub fn save_camera(&self, path: &Path) -> Result<(), VtrError> {
+        vtr::write_camera_to_file(path, &self.camera)
+    }
+
+    /// Restore a viewpoint saved with [`Self::save_camera`] (or the camera
+    /// of any `.vtr` scene file), leaving the world untouched.
+    ///
+    /// The camera keeps the current aspect ratio so the window's shape wins
+    /// over the one the bookmark was taken in.
+    ///
+    /// # Errors
+    /// Returns a [`VtrError`] on I/O failure or any parse error.
+    pub fn load_camera(&mut self, path: &Path) -> Result<(), VtrError> {
+        let aspect = self.camera.aspect;
+        self.camera = vtr::read_camera_from_file(path)?;
+        self.camera.aspect = aspect;
+        Ok(())
+    }
+
+    /// Load a `.vtr` file and reload its objects whenever the file changes
+    /// on disk, for near-instant level iteration with an external editor.
+    ///
+    /// Changes are picked up by [`Self::poll_hot_reload`], which
+    /// [`crate::window::Window`] calls once per frame.  Reloads replace the
+    /// [`World`] only; the camera keeps its current position.  Replaces any
+    /// previously watched scene file.
+    ///
+    /// # Errors
+    /// Returns the error of the initial load; nothing is watched then.
+    pub fn watch_vtr_file(&mut self, path: impl Into<PathBuf>) -> Result<(), VtrError> {
+        let path = path.into();
+        self.load_vtr_file(&path)?;
+        self.unwatch_vtr_file();
+        self.watch_asset(path, AssetKind::Scene);
+        Ok(())
+    }
+
+    /// Stop hot reloading the file passed to [`Self::watch_vtr_file`].
+    pub fn unwatch_vtr_file(&mut self) {
+        if let Some(path) = self.watched_vtr_file().map(Path::to_path_buf) {
+            self.unwatch_asset(&path);
+        }
+    }
+
+    /// The scene file currently hot reloaded, if any.
+    pub fn watched_vtr_file(&self) -> Option<&Path> {
+        self.watched_assets
+            .iter()
+            .find(|(_, kind)| **kind == AssetKind::Scene)
+            .map(|(path, _)| path.as_path())
+    }
+
+    /// Load a texture like [`Self::load_texture`] and re-upload it whenever
+    /// the image changes on disk.
+    ///
+    /// The new texture replaces the old entry under the same key in one
+    /// step, so objects switch to it on the next frame without flicker.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn watch_texture(&mut self, path: &str) -> Result<(), String> {
+        self.load_texture(path)?;
+        self.watch_asset(PathBuf::from(path), AssetKind::Texture);
+        Ok(())
+    }
+
+    /// Replace the scene shader with the WGSL file at `path` and rebuild the
+    /// render pipelines whenever it changes.
+    ///
+    /// See [`Pipeline::reload_shader`] for the interface the shader must
+    /// keep.  The file is run through [`crate::shader::ShaderPreprocessor`],
+    /// with includes resolved relative to its directory; only the watched
+    /// file itself triggers reloads.  A shader that fails to preprocess or
+    /// compile leaves the previous one active.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn watch_shader(&mut self, path: impl Into<PathBuf>) -> Result<(), String> {
+        let path = path.into();
+        self.reload_shader_file(&path)?;
+        self.watch_asset(path, AssetKind::Shader);
+        Ok(())
+    }
+
+    /// Set how often [`Self::poll_hot_reload`] checks watched files, in
+    /// seconds (default [`crate::constants::watch::DEFAULT_POLL_INTERVAL_SECS`]).
+    pub fn set_hot_reload_interval(&mut self, secs: f32) {
+        self.file_watcher.set_interval(secs);
+    }
+
+    /// Stop hot reloading `path`.  Returns `false` if it was not watched.
+    pub fn unwatch_asset(&mut self, path: &Path) -> bool {
+        self.file_watcher.unwatch(path);
+        self.watched_assets.remove(path).is_some()
+    }
+
+    fn watch_asset(&mut self, path: PathBuf, kind: AssetKind) {
+        self.file_watcher.watch(path.clone());
+        self.watched_assets.insert(path, kind);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn reload_shader_file(&mut self, path: &Path) -> Result<(), String> {
+        let source = crate::shader::ShaderPreprocessor::new()
+            .process_file(path)
+            .map_err(|e| format!("watch_shader(\"{}\"): {e}", path.display()))?;
+        pollster::block_on(self.pipeline.reload_shader(&source))
+            .map_err(|e| format!("watch_shader(\"{}\"): {e}", path.display()))
+    }
+
+    /// Replace the world with the objects of a `.vtr` file, keeping the
+    /// current camera.
+    ///
+    /// # Errors
+    /// Same as [`Self::load_vtr_file`]; the world is untouched on error.
+    pub fn reload_world_from_vtr(&mut self, path: &Path) -> Result<(), VtrError> {
+        let data = vtr::read_from_file(path)?;
+        self.world = data.world;
+        self.static_batches.invalidate();
+        self.script_registry.reset_started();
+        Ok(())
+    }
+
+    /// Reload every watched scene file, texture, and shader that changed
+    /// since the last poll and report the outcome of each.
+    ///
+    /// Called once per frame by [`crate::window::Window`], which hands each
+    /// outcome to [`crate::window::Window::on_hot_reload`].  A failed reload (e.g. a file caught mid-write, a shader
+    /// with a typo) keeps the previous version; the next save triggers
+    /// another attempt.
+    pub fn poll_hot_reload(&mut self) -> Vec<AssetReload> {
+        let saved = self.saved_files.get_mut().unwrap_or_else(PoisonError::into_inner);
+        for path in saved.drain(..) {
+            if self.file_watcher.is_watching(&path) {
+                self.file_watcher.watch(path);
+            }
+        }
+        let changed = self.file_watcher.poll();
+        let mut reloads = Vec::with_capacity(changed.len());
+        for path in changed {
+            let Some(&kind) = self.watched_assets.get(&path) else { continue };
+            let result = match kind {
+                AssetKind::Scene => self.reload_world_from_vtr(&path).map_err(|e| e.to_string()),
+                #[cfg(not(target_arch = "wasm32"))]
+                AssetKind::Texture => self.load_texture(&path.to_string_lossy()),
+                #[cfg(not(target_arch = "wasm32"))]
+                AssetKind::Shader => self.reload_shader_file(&path),
+                #[cfg(target_arch = "wasm32")]
+                AssetKind::Texture | AssetKind::Shader => Ok(()),
+            };
+            reloads.push(AssetReload { path, kind, result });
+        }
+        reloads
+    }
+
+    /// Park `world` under `id` so it can be switched to later.  The camera
+    /// is adopted as-is; its aspect ratio is matched to the viewport when
+    /// the scene becomes active.
+    ///
+    /// # Panics
+    /// Panics if `id` is the active scene (see [`Self::active_scene_id`]).
+    pub fn add_scene(&mut self, id: impl Into<String>, world: World, camera: Camera) {
+        self.scenes.insert(id, SceneSlot::new(world, camera));
+    }
+
+    /// ID of the scene whose world is currently in [`Self::world`].
+    pub fn active_scene_id(&self) -> &str {
+        self.scenes.active()
+    }
+
+    /// Switch to the parked scene `id` immediately, parking the current one
+    /// under its ID.  Scripts keep their state, so switching back resumes
+    /// where the scene left off.
+    ///
+    /// Returns `false` if `id` is not a parked scene.
+    pub fn switch_to(&mut self, id: &str) -> bool {
+        self.scenes.cancel_transition();
+        let switched = self.with_active_slot(|scenes, slot| scenes.switch(id, slot));
+        self.pipeline.set_fade([0.0; 4]);
+        switched
+    }
+
+    /// Like [`Self::switch_to`], but fade out to
+    /// [`SceneManager::fade_color`] and back in over `duration_secs`.  The
+    /// swap happens at the midpoint; see [`Self::update_transition`].
+    ///
+    /// Returns `false` if `id` is not a parked scene.
+    pub fn switch_to_with_fade(&mut self, id: &str, duration_secs: f32) -> bool {
+        self.scenes.begin_transition(id, duration_secs)
+    }
+
+    /// Open the default audio device (once) and return the scene's
+    /// [`crate::audio::Audio`].
+    #[cfg(all(feature = "audio", not(target_arch = "wasm32")))]
+    pub fn enable_audio(&mut self) -> Result<&mut crate::audio::Audio, crate::audio::AudioError> {
+        let audio = match self.audio.take() {
+            Some(audio) => audio,
+            None => crate::audio::Audio::new()?,
+        };
+        Ok(self.audio.insert(audio))
+    }
+
+    /// Install a water surface drawn with every frame, or remove it with
+    /// `None`.  See [`crate::water`].
+    pub fn set_water(&mut self, water: Option<Water>) {
+        self.pipeline.water = match (water, self.pipeline.water.take()) {
+            (Some(settings), Some(mut pass)) => {
+                pass.settings = settings;
+                Some(pass)
+            }
+            (Some(settings), None) => Some(WaterPass::new(&self.pipeline, settings)),
+            (None, _) => None,
+        };
+    }
+
+    /// The installed water surface, if any.
+    pub fn water(&self) -> Option<&Water> {
+        self.pipeline.water.as_ref().map(|pass| &pass.settings)
+    }
+
+    /// Mutable access to the installed water surface; changes show up on
+    /// the next frame.
+    pub fn water_mut(&mut self) -> Option<&mut Water> {
+        self.pipeline.water.as_mut().map(|pass| &mut pass.settings)
+    }
+
+    /// Spawn a `size` mirror, or a portal when [`Mirror::exit`] is set,
+    /// placed by `transform` under `parent_id` (or at the root).  See
+    /// [`crate::mirror`].
+    ///
+    /// The object is a [`Geometry::Sprite`](crate::geometry::Geometry::Sprite)
+    /// facing -Z on [`layers::MIRROR`], textured with
+    /// [`mirror::texture_key`]; its colour tints the image.  Resize it by
+    /// changing the sprite's width and height.  Returns the object's ID.
+    pub fn spawn_mirror(&mut self, settings: Mirror, size: [f32; 2], transform: Transform, parent_id: Option<usize>) -> usize {
+        let id = self.spawn(Object {
+            name: "Mirror".to_string(),
+            transform,
+            geometry: Some(crate::geometry::Geometry::Sprite { width: size[0], height: size[1], uv: settings.uv() }),
+            layer: layers::MIRROR,
+            ..Default::default()
+        }, parent_id);
+        if let Some(object) = self.world.get_mut(id) {
+            object.texture_path = Some(mirror::texture_key(id));
+        }
+        self.mirrors.insert(id, settings);
+        id
+    }
+
+    /// Settings of mirror `id`.
+    pub fn mirror(&self, id: usize) -> Option<&Mirror> {
+        self.mirrors.get(&id)
+    }
+
+    /// Mutable settings of mirror `id`; changes show up on the next frame.
+    pub fn mirror_mut(&mut self, id: usize) -> Option<&mut Mirror> {
+        self.mirrors.get_mut(&id)
+    }
+
+    /// Stop rendering mirror `id` and free its texture.  The object stays
+    /// in the world, showing its vertex colour.  Despawned mirrors are
+    /// removed automatically.
+    pub fn remove_mirror(&mut self, id: usize) -> Option<Mirror> {
+        self.mirror_targets.remove(&id);
+        self.textures.remove(&mirror::texture_key(id));
+        self.mirrors.remove(&id)
+    }
+
+    /// Test object `id` with an occlusion query every frame and skip its
+    /// draws while it is hidden, or stop with `enabled` false; see
+    /// [`crate::occlusion`].  Returns `false` if no such object exists.
+    pub fn set_occlusion_query(&mut self, id: usize, enabled: bool) -> bool {
+        if !self.world.objects.contains_key(&id) {
+            return false;
+        }
+        self.occlusion.set(id, enabled);
+        true
+    }
+
+    /// Whether object `id` is tested with an occlusion query.
+    pub fn has_occlusion_query(&self, id: usize) -> bool {
+        self.occlusion.ids.contains(&id)
+    }
+
+    /// Whether object `id`'s last occlusion query found it hidden, so it is
+    /// left out of the frame.
+    pub fn is_occluded(&self, id: usize) -> bool {
+        self.occlusion.occluded.contains(&id)
+    }
+
+    /// Advance [`Self::time`] by `dt` seconds.  Called every frame by
+    /// [`crate::window::Window`].
+    pub fn update_time(&mut self, dt: f32) {
+        self.time += dt as f64;
+    }
+
+    /// Advance the water animation by `dt` seconds.  Called every frame by
+    /// [`crate::window::Window`]; does nothing without water.
+    pub fn update_water(&mut self, dt: f32) {
+        if let Some(water) = self.water_mut() {
+            water.time += dt;
+        }
+    }
+
+    /// Attenuate positioned sounds for the current camera and forget
+    /// finished ones.  Called every frame by [`crate::window::Window`].
+    #[cfg(all(feature = "audio", not(target_arch = "wasm32")))]
+    pub fn update_audio(&mut self) {
+        if let Some(audio) = &mut self.audio {
+            audio.update(&self.camera, &self.world);
+        }
+    }
+
+    /// Advance a running fade transition by `dt` seconds and update the
+    /// pipeline's fade overlay.  Called every frame by
+    /// [`crate::window::Window`]; does nothing while no transition runs.
+    pub fn update_transition(&mut self, dt: f32) {
+        if self.scenes.transition().is_none() {
+            return;
+        }
+        let opacity = self.with_active_slot(|scenes, slot| scenes.update(dt, slot));
+        let [r, g, b] = self.scenes.fade_color;
+        self.pipeline.set_fade([r, g, b, opacity]);
+    }
+
+    /// Move the active scene's state into a [`SceneSlot`], run `f`, and move
+    /// whatever `f` left in the slot back.
+    fn with_active_slot<R>(&mut self, f: impl FnOnce(&mut SceneManager, &mut SceneSlot) -> R) -> R {
+        let active = self.scenes.active().to_string();
+        let mut slot = SceneSlot {
+            world: std::mem::take(&mut self.world),
+            camera: std::mem::take(&mut self.camera),
+            static_batches: std::mem::take(&mut self.static_batches),
+            script_registry: std::mem::take(&mut self.script_registry),
+        };
+        let result = f(&mut self.scenes, &mut slot);
+        self.world = slot.world;
+        self.static_batches = slot.static_batches;
+        self.script_registry = slot.script_registry;
+        if self.scenes.active() != active {
+            // The incoming camera may have been parked at another size.
+            slot.camera.aspect = self.viewport().aspect_ratio();
+        }
+        self.camera = slot.camera;
+        result
+    }
+}
+
+/// The CPU-side work of one frame: world geometry flattened into
+/// per-texture and per-material groups, plus the registry-mesh objects drawn individually.
+#[derive(Default)]
+pub(crate) struct DrawList {
+    /// Flattened geometry keyed by texture path and material, in
+    /// first-seen order.
+    pub(crate) groups: Vec<(BatchKey, MeshData)>,
+    /// Objects referencing a registry mesh, with their model matrix.
+    pub(crate) mesh_objects: Vec<(usize, crate::math::Matrix4)>,
+    /// Objects to test with occlusion queries, drawn or not, with their
+    /// model matrix.
+    pub(crate) queried: Vec<(usize, crate::math::Matrix4)>,
+}
+
+/// What [`DrawListBuilder::collect`] leaves out.
+struct DrawFilter<'a> {
+    /// Objects on no layer in the mask are skipped.
+    layers_mask: u32,
+    /// Objects drawn by the static batches, when those are used.
+    static_ids: Option<&'a HashSet<usize>>,
+    /// Meshes baked by [`Scene::spawn_batch`]; see [`batch_shape`].
+    batch_shapes: &'a HashMap<GeometryId, Geometry>,
+    /// Objects listed in [`DrawList::queried`] and those left out as
+    /// occluded; `None` in capture passes.
+    occlusion: Option<&'a QueriedObjects>,
+    /// Tints geometry outside the frustum.
+    tint: Option<(&'a CullingDebug, &'a Frustum)>,
+}
+
+/// Accumulates a [`DrawList`], indexing groups by texture path.
+#[derive(Default)]
+struct DrawListBuilder {
+    list:  DrawList,
+    index: HashMap<Option<String>, Vec<usize>>,
+}
+
+impl DrawListBuilder {
+    /// The group for `texture_path` and `material`, created on first use
+    /// so the path is cloned once per frame rather than once per object.
+    fn group(&mut self, texture_path: &Option<String>, material: Material) -> &mut MeshData {
+        let groups = &self.list.groups;
+        let found = self.index
+            .get(texture_path)
+            .and_then(|indices| indices.iter().copied().find(|&i| groups[i].0.material == material));
+        let i = match found {
+            Some(i) => i,
+            None => {
+                let i = self.list.groups.len();
+                self.index.entry(texture_path.clone()).or_default().push(i);
+                self.list.groups.push((BatchKey { texture_path: texture_path.clone(), material }, MeshData::new()));
+                i
+            }
+        };
+        &mut self.list.groups[i].1
+    }
+
+    /// Recursively flatten `object_id` and its descendants, skipping what
+    /// `filter` leaves out.
+    fn collect(
+        &mut self,
+        objects: &HashMap<usize, Object>,
+        object_id: usize,
+        parent_transform: &Transform,
+        filter: &DrawFilter,
+    ) {
+        if let Some(obj) = objects.get(&object_id) {
+            let world_transform = parent_transform.combine(&obj.transform);
+
+            let in_layer = obj.layer & filter.layers_mask != 0;
+            let is_static = filter.static_ids.is_some_and(|ids| ids.contains(&object_id));
+            let occlusion = filter.occlusion;
+            if in_layer && !is_static && occlusion.is_some_and(|o| o.ids.contains(&object_id)) {
+                self.list.queried.push((object_id, world_transform.to_matrix()));
+            }
+            let visible = in_layer && !occlusion.is_some_and(|o| o.occluded.contains(&object_id));
+
+            let batched = batch_shape(obj, filter.batch_shapes);
+            if let Some(geo) = &obj.geometry && visible && !is_static && batched != Some(true) {
+                let group = self.group(&obj.texture_path, obj.material);
+                let start = group.vertices.len();
+                geo.generate_mesh_data(group, &world_transform, obj.color);
+                if let Some((debug, frustum)) = filter.tint {
+                    debug.tint_culled(&mut group.vertices[start..], obj.material.displacement.reach(), frustum);
+                }
+            }
+
+            if obj.mesh.is_some() && visible && batched != Some(false) {
+                self.list.mesh_objects.push((object_id, world_transform.to_matrix()));
+            }
+
+            for &child_id in &obj.children {
+                self.collect(objects, child_id, &world_transform, filter);
+            }
+        }
+    }
+
+    /// Append `other`, which covers later roots, keeping first-seen order.
+    fn merge(&mut self, other: DrawListBuilder) {
+        for (key, mesh_data) in other.list.groups {
+            self.group(&key.texture_path, key.material).append(mesh_data);
+        }
+        self.list.mesh_objects.extend(other.list.mesh_objects);
+        self.list.queried.extend(other.list.queried);
+    }
+}
+
+/// Worker threads for draw-list preparation, started on first use
+/// and kept across frames.
+#[derive(Default)]
+struct PrepPool {
+    #[cfg(not(target_arch = "wasm32"))]
+    pool: Option<rayon::ThreadPool>,
+}
+
+impl PrepPool {
+    /// Walk the trees under `roots` into a [`DrawList`] on `threads` workers while
+    /// `alongside` runs on the calling thread, and return both results.
+    #[cfg_attr(target_arch = "wasm32", allow(unused_variables))]
+    fn prepare<R>(
+        &mut self,
+        threads: usize,
+        objects: &HashMap<usize, Object>,
+        roots: &[usize],
+        filter: &DrawFilter,
+        alongside: impl FnOnce() -> R,
+    ) -> (DrawList, R) {
+        let prepare = |roots: &[usize]| {
+            let mut builder = DrawListBuilder::default();
+            for &root_id in roots {
+                builder.collect(objects, root_id, &Transform::default(), filter);
+            }
+            builder
+        };
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if threads > 1 {
+            let pool = match &mut self.pool {
+                Some(pool) if pool.current_num_threads() >= threads => pool,
+                slot => match rayon::ThreadPoolBuilder::new().num_threads(threads).build() {
+                    Ok(pool) => slot.insert(pool),
+                    // No threads to be had: prepare on this thread instead.
+                    Err(_) => return (prepare(roots).list, alongside()),
+                },
+            };
+            let chunk_len = roots.len().div_ceil(threads);
+            let mut parts: Vec<Option<DrawListBuilder>> = roots.chunks(chunk_len).map(|_| None).collect();
+            let result = pool.in_place_scope(|s| {
+                for (part, chunk) in parts.iter_mut().zip(roots.chunks(chunk_len)) {
+                    let prepare = &prepare;
+                    s.spawn(move |_| *part = Some(prepare(chunk)));
+                }
+                alongside()
+            });
+            let mut parts = parts.into_iter().flatten();
+            let mut merged = parts.next().unwrap_or_default();
+            for part in parts {
+                merged.merge(part);
+            }
+            return (merged.list, result);
+        }
+        (prepare(roots).list, alongside())
+    }
 }
 
-/// Traverse the object hierarchy and accumulate each object's mesh geometry
-/// into a bucket keyed by `texture_path`.  Objects with no geometry are skipped.
-fn collect_by_texture(
+/// Like [`DrawListBuilder::collect`], but emits geometry only for objects in
+/// `static_ids`.  The whole hierarchy is walked so static children of dynamic
+/// parents get the correct world transform at bake time.
+fn collect_static(
     world: &World,
     object_id: usize,
     parent_transform: &Transform,
-    groups: &mut HashMap<Option<String>, MeshData>,
+    layers_mask: u32,
+    static_ids: &HashSet<usize>,
+    batch_shapes: &HashMap<GeometryId, Geometry>,
+    groups: &mut HashMap<BatchKey, MeshData>,
 ) {
-    // `collect_by_texture` uses `groups.entry(obj.texture_path.clone())`,
-    // cloning the (potentially long) texture path string for every object
-    // on every frame. This can become a noticeable per-frame allocation cost
-    // in large scenes.
-    // TODO: Consider grouping by a borrowed key (e.g. Option<&str> via
-    //  a two-pass approach) or storing an interned/shared key on Object
-    //  (e.g. Arc<str>), so we can hash without allocating each frame.
     if let Some(obj) = world.objects.get(&object_id) {
         let world_transform = parent_transform.combine(&obj.transform);
 
-        if let Some(geo) = &obj.geometry {
+        let visible = obj.layer & layers_mask != 0;
+        let batched = batch_shape(obj, batch_shapes) == Some(true);
+        if let Some(geo) = &obj.geometry && visible && static_ids.contains(&object_id) && !batched {
             let entry = groups
-                .entry(obj.texture_path.clone())
-                .or_insert_with(MeshData::new);
+                .entry(BatchKey { texture_path: obj.texture_path.clone(), material: obj.material })
+                .or_default();
             geo.generate_mesh_data(entry, &world_transform, obj.color);
         }
 
         for &child_id in &obj.children {
-            collect_by_texture(world, child_id, &world_transform, groups);
+            collect_static(world, child_id, &world_transform, layers_mask, static_ids, batch_shapes, groups);
         }
     }
 }
+
+/// Whether `obj` points at a mesh [`Scene::spawn_batch`] baked from a
+/// geometry, and if so whether it still matches the object's own.  A
+/// matching mesh is drawn in place of the geometry; a stale one (the
+/// geometry changed since, e.g. a sprite frame) is not drawn at all.
+fn batch_shape(obj: &Object, batch_shapes: &HashMap<GeometryId, Geometry>) -> Option<bool> {
+    let shape = batch_shapes.get(&obj.mesh?)?;
+    Some(obj.geometry.as_ref() == Some(shape))
+}
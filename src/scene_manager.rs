@@ -0,0 +1,218 @@
+//! Multiple named scenes sharing one [`crate::pipeline::Pipeline`].
+//!
+//! A game usually has several independent worlds — a menu, a level, a pause
+//! screen — but only one GPU context.  [`SceneManager`] keeps the inactive
+//! ones *parked* (world, camera, static-batch hints and scripts) while the
+//! active one lives in the [`crate::scene::Scene`] fields the renderer and
+//! callbacks already use.  Switching swaps the two in place, so nothing is
+//! re-uploaded except the per-frame geometry.
+//!
+//! ```no_run
+//! # use vertra::scene::Scene;
+//! # use vertra::world::World;
+//! # use vertra::camera::Camera;
+//! # fn demo(scene: &mut Scene) {
+//! // The world the scene started with is "main".
+//! scene.add_scene("pause", World::new(), Camera::new());
+//! scene.switch_to_with_fade("pause", 0.5);
+//! # }
+//! ```
+//!
+//! Transitions fade to [`SceneManager::fade_color`] over half the duration,
+//! swap, then fade back in.  [`crate::window::Window`] advances them every
+//! frame; embedders call [`crate::scene::Scene::update_transition`].
+
+use std::collections::HashMap;
+
+use crate::camera::Camera;
+use crate::constants::scene::MAIN_SCENE_ID;
+use crate::mesh::StaticBatches;
+use crate::script::ScriptRegistry;
+use crate::world::World;
+
+/// Everything that belongs to one scene and is swapped on a switch.
+pub struct SceneSlot {
+    pub world: World,
+    pub camera: Camera,
+    pub static_batches: StaticBatches,
+    pub script_registry: ScriptRegistry,
+}
+
+impl SceneSlot {
+    pub fn new(world: World, camera: Camera) -> Self {
+        Self {
+            world,
+            camera,
+            static_batches: StaticBatches::new(),
+            script_registry: ScriptRegistry::new(),
+        }
+    }
+}
+
+/// Direction of an in-progress fade.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FadePhase {
+    /// Fading the old scene out; the swap happens at the end.
+    Out,
+    /// Fading the new scene in.
+    In,
+}
+
+/// A fade transition towards `target`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transition {
+    pub target: String,
+    pub phase: FadePhase,
+    /// Seconds per phase (half the requested duration).
+    pub phase_secs: f32,
+    /// Seconds elapsed in the current phase.
+    pub elapsed: f32,
+}
+
+impl Transition {
+    /// Fade opacity in `[0, 1]`: rises during [`FadePhase::Out`], falls
+    /// during [`FadePhase::In`].
+    pub fn opacity(&self) -> f32 {
+        let t = if self.phase_secs > 0.0 { (self.elapsed / self.phase_secs).clamp(0.0, 1.0) } else { 1.0 };
+        match self.phase {
+            FadePhase::Out => t,
+            FadePhase::In => 1.0 - t,
+        }
+    }
+}
+
+/// Bookkeeping for parked scenes and the active transition.
+pub struct SceneManager {
+    parked: HashMap<String, SceneSlot>,
+    active: String,
+    transition: Option<Transition>,
+    /// RGB colour faded through during transitions.  Defaults to black.
+    pub fade_color: [f32; 3],
+}
+
+impl Default for SceneManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SceneManager {
+    pub fn new() -> Self {
+        Self {
+            parked: HashMap::new(),
+            active: MAIN_SCENE_ID.to_string(),
+            transition: None,
+            fade_color: [0.0; 3],
+        }
+    }
+
+    /// ID of the scene currently in use.
+    pub fn active(&self) -> &str {
+        &self.active
+    }
+
+    /// Whether `id` is the active scene or a parked one.
+    pub fn contains(&self, id: &str) -> bool {
+        self.active == id || self.parked.contains_key(id)
+    }
+
+    /// IDs of all parked (inactive) scenes, in no particular order.
+    pub fn parked_ids(&self) -> impl Iterator<Item = &str> {
+        self.parked.keys().map(String::as_str)
+    }
+
+    /// Worlds of all parked scenes, in no particular order.  They share the
+    /// mesh registry with the active scene, so garbage collection must count
+    /// their references too.
+    pub fn parked_worlds(&self) -> impl Iterator<Item = &World> {
+        self.parked.values().map(|slot| &slot.world)
+    }
+
+    /// Park `slot` under `id`, returning the slot it replaced.
+    ///
+    /// # Panics
+    /// Panics if `id` is the active scene; replace its fields directly.
+    pub fn insert(&mut self, id: impl Into<String>, slot: SceneSlot) -> Option<SceneSlot> {
+        let id = id.into();
+        assert!(id != self.active, "cannot replace the active scene '{id}' by inserting");
+        self.parked.insert(id, slot)
+    }
+
+    /// Remove a parked scene.  The active scene cannot be removed.
+    pub fn remove(&mut self, id: &str) -> Option<SceneSlot> {
+        if self.transition.as_ref().is_some_and(|t| t.target == id) {
+            self.transition = None;
+        }
+        self.parked.remove(id)
+    }
+
+    /// Borrow a parked scene, e.g. to populate it before switching.
+    pub fn get_mut(&mut self, id: &str) -> Option<&mut SceneSlot> {
+        self.parked.get_mut(id)
+    }
+
+    /// Swap `active` (the live scene) with the parked scene `id`.  The live
+    /// scene is parked under the previous active ID.
+    ///
+    /// Returns `false` (and changes nothing) if `id` is not parked.
+    pub fn switch(&mut self, id: &str, active: &mut SceneSlot) -> bool {
+        let Some(mut slot) = self.parked.remove(id) else { return false };
+        std::mem::swap(active, &mut slot);
+        let previous = std::mem::replace(&mut self.active, id.to_string());
+        self.parked.insert(previous, slot);
+        true
+    }
+
+    /// Start a fade transition to the parked scene `id` lasting
+    /// `duration_secs` in total.  Replaces any running transition.
+    ///
+    /// Returns `false` if `id` is not parked.
+    pub fn begin_transition(&mut self, id: &str, duration_secs: f32) -> bool {
+        if !self.parked.contains_key(id) {
+            return false;
+        }
+        self.transition = Some(Transition {
+            target: id.to_string(),
+            phase: FadePhase::Out,
+            phase_secs: duration_secs.max(0.0) * 0.5,
+            elapsed: 0.0,
+        });
+        true
+    }
+
+    /// Abandon the running transition, if any, without switching.
+    pub fn cancel_transition(&mut self) {
+        self.transition = None;
+    }
+
+    /// The running transition, if any.
+    pub fn transition(&self) -> Option<&Transition> {
+        self.transition.as_ref()
+    }
+
+    /// Advance the running transition by `dt`, swapping into the target
+    /// scene when the fade-out completes.
+    ///
+    /// Returns the fade opacity to draw this frame (`0.0` when idle).
+    pub fn update(&mut self, dt: f32, active: &mut SceneSlot) -> f32 {
+        let Some(transition) = &mut self.transition else { return 0.0 };
+        transition.elapsed += dt;
+        if transition.elapsed >= transition.phase_secs {
+            match transition.phase {
+                FadePhase::Out => {
+                    let target = transition.target.clone();
+                    transition.phase = FadePhase::In;
+                    transition.elapsed = 0.0;
+                    self.switch(&target, active);
+                    // Hold full opacity for the swap frame.
+                    return 1.0;
+                }
+                FadePhase::In => {
+                    self.transition = None;
+                    return 0.0;
+                }
+            }
+        }
+        transition.opacity()
+    }
+}
@@ -0,0 +1,269 @@
+//! WGSL preprocessing: `#include` and `#define`.
+//!
+//! WGSL has no module system, so [`ShaderPreprocessor`] stitches shaders
+//! together from named chunks before they reach wgpu.  Directives sit on
+//! their own line:
+//!
+//! | Directive            | Effect                                                        |
+//! |----------------------|---------------------------------------------------------------|
+//! | `#include "name"`    | Paste a chunk (at most once per shader)                       |
+//! | `#define NAME value` | Define `NAME`; later whole-word uses are replaced by `value`  |
+//! | `#define NAME`       | Define `NAME` as a flag for `#ifdef`                          |
+//! | `#undef NAME`        | Remove a definition                                           |
+//! | `#ifdef` / `#ifndef` | Keep the following lines only if `NAME` is (not) defined      |
+//! | `#else` / `#endif`   | Close or flip the innermost `#ifdef` / `#ifndef`              |
+//!
+//! The engine registers its shared chunks under `vertra/`:
+//!
+//! | Chunk                      | Provides                                                           |
+//! |----------------------------|--------------------------------------------------------------------|
+//! | `vertra/camera.wgsl`       | `camera` (view-projection), `lights` and `frame` uniforms, group 0 |
+//! | `vertra/model.wgsl`        | `model_data` per-draw uniform, group 2                             |
+//! | `vertra/vertex.wgsl`       | `VertexInput` / `VertexOutput` structs                             |
+//! | `vertra/lighting.wgsl`     | `flat_normal` and `ambient_light` helpers                          |
+//! | `vertra/displacement.wgsl` | `displace`, the material vertex wave                               |
+//! | `vertra/debug.wgsl`        | `DEBUG_VIEW` override and `debug_color` for debug views            |
+//!
+//! Chunks not registered with [`ShaderPreprocessor::with_chunk`] are looked
+//! up as files in the include directories, and
+//! [`ShaderPreprocessor::process_file`] adds the file's own directory.
+//!
+//! ```
+//! use vertra::shader::ShaderPreprocessor;
+//! let wgsl = ShaderPreprocessor::new()
+//!     .with_define("TINT", "vec3<f32>(1.0, 0.5, 0.5)")
+//!     .process("custom.wgsl", "#include \"vertra/camera.wgsl\"\nconst tint = TINT;")
+//!     .unwrap();
+//! assert!(wgsl.contains("var<uniform> camera"));
+//! assert!(wgsl.contains("const tint = vec3<f32>(1.0, 0.5, 0.5);"));
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// The engine's shared chunks, by include name.
+const BUILTIN_CHUNKS: [(&str, &str); 6] = [
+    ("vertra/camera.wgsl", include_str!("shaders/camera.wgsl")),
+    ("vertra/model.wgsl", include_str!("shaders/model.wgsl")),
+    ("vertra/vertex.wgsl", include_str!("shaders/vertex.wgsl")),
+    ("vertra/lighting.wgsl", include_str!("shaders/lighting.wgsl")),
+    ("vertra/displacement.wgsl", include_str!("shaders/displacement.wgsl")),
+    ("vertra/debug.wgsl", include_str!("shaders/debug.wgsl")),
+];
+
+/// The built-in scene shader, before preprocessing.
+pub const SCENE_SHADER: &str = include_str!("shader.wgsl");
+
+/// A preprocessing failure, located by file name and 1-based line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PreprocessError {
+    /// `#include` named a chunk that is neither registered nor a file in an
+    /// include directory.
+    MissingInclude { file: String, line: usize, name: String },
+    /// An unknown directive, or one missing its argument.
+    InvalidDirective { file: String, line: usize, directive: String },
+    /// `#else` or `#endif` without an open `#ifdef` / `#ifndef`.
+    UnmatchedDirective { file: String, line: usize, directive: String },
+    /// An `#ifdef` / `#ifndef` still open at the end of its file.
+    UnterminatedConditional { file: String, line: usize },
+}
+
+impl std::fmt::Display for PreprocessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingInclude { file, line, name } => write!(f, "{file}:{line}: cannot find include \"{name}\""),
+            Self::InvalidDirective { file, line, directive } => write!(f, "{file}:{line}: invalid directive `{directive}`"),
+            Self::UnmatchedDirective { file, line, directive } => {
+                write!(f, "{file}:{line}: `{directive}` without a matching #ifdef or #ifndef")
+            }
+            Self::UnterminatedConditional { file, line } => write!(f, "{file}:{line}: conditional is never closed"),
+        }
+    }
+}
+
+impl std::error::Error for PreprocessError {}
+
+/// Expands `#include`, `#define` and conditionals in WGSL sources.
+#[derive(Debug, Clone)]
+pub struct ShaderPreprocessor {
+    chunks:       HashMap<String, String>,
+    defines:      HashMap<String, String>,
+    include_dirs: Vec<PathBuf>,
+}
+
+impl Default for ShaderPreprocessor {
+    fn default() -> Self {
+        Self {
+            chunks: BUILTIN_CHUNKS.iter().map(|&(name, source)| (name.to_owned(), source.to_owned())).collect(),
+            defines: HashMap::new(),
+            include_dirs: Vec::new(),
+        }
+    }
+}
+
+/// Per-shader expansion state.
+struct Expansion {
+    defines:  HashMap<String, String>,
+    included: HashSet<String>,
+    output:   String,
+}
+
+impl ShaderPreprocessor {
+    /// A preprocessor with the built-in `vertra/` chunks and no defines.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `source` under the include name `name`, replacing any chunk
+    /// of that name.
+    pub fn with_chunk(mut self, name: impl Into<String>, source: impl Into<String>) -> Self {
+        self.chunks.insert(name.into(), source.into());
+        self
+    }
+
+    /// Define `name` before processing, as if by `#define name value`.  Use
+    /// an empty `value` for a plain flag.
+    pub fn with_define(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.defines.insert(name.into(), value.into());
+        self
+    }
+
+    /// Look up unregistered includes as files relative to `dir`.
+    pub fn with_include_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.include_dirs.push(dir.into());
+        self
+    }
+
+    /// Expand `source`.  `name` labels errors and counts as already
+    /// included.
+    pub fn process(&self, name: &str, source: &str) -> Result<String, PreprocessError> {
+        let mut expansion = Expansion {
+            defines: self.defines.clone(),
+            included: HashSet::from([name.to_owned()]),
+            output: String::with_capacity(source.len()),
+        };
+        self.expand(name, source, &mut expansion)?;
+        Ok(expansion.output)
+    }
+
+    /// Read and expand the file at `path`, resolving includes against its
+    /// directory before the configured include directories.
+    ///
+    /// # Errors
+    /// Read failures, and [`PreprocessError`]s wrapped as
+    /// [`std::io::ErrorKind::InvalidData`].
+    pub fn process_file(&self, path: &Path) -> std::io::Result<String> {
+        let source = std::fs::read_to_string(path)?;
+        let mut preprocessor = self.clone();
+        if let Some(dir) = path.parent() {
+            preprocessor.include_dirs.insert(0, dir.to_path_buf());
+        }
+        preprocessor
+            .process(&path.display().to_string(), &source)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    fn expand(&self, file: &str, source: &str, expansion: &mut Expansion) -> Result<(), PreprocessError> {
+        // Open conditionals: (line opened, currently emitting, an enclosing
+        // block was inactive).
+        let mut conditionals: Vec<(usize, bool, bool)> = Vec::new();
+        for (index, text) in source.lines().enumerate() {
+            let line = index + 1;
+            let active = conditionals.last().is_none_or(|&(_, emitting, _)| emitting);
+            let trimmed = text.trim();
+            let Some(directive) = trimmed.strip_prefix('#') else {
+                if active {
+                    expansion.output.push_str(&substitute(text, &expansion.defines));
+                }
+                expansion.output.push('\n');
+                continue;
+            };
+            let invalid = || PreprocessError::InvalidDirective {
+                file: file.to_owned(),
+                line,
+                directive: trimmed.to_owned(),
+            };
+            let (keyword, argument) = directive.split_once(char::is_whitespace).unwrap_or((directive, ""));
+            let argument = argument.trim();
+            match keyword {
+                "ifdef" | "ifndef" => {
+                    if argument.is_empty() {
+                        return Err(invalid());
+                    }
+                    let defined = expansion.defines.contains_key(argument);
+                    conditionals.push((line, active && defined == (keyword == "ifdef"), !active));
+                }
+                "else" | "endif" => {
+                    let Some((opened, emitting, outer_inactive)) = conditionals.pop() else {
+                        return Err(PreprocessError::UnmatchedDirective {
+                            file: file.to_owned(),
+                            line,
+                            directive: trimmed.to_owned(),
+                        });
+                    };
+                    if keyword == "else" {
+                        conditionals.push((opened, !emitting && !outer_inactive, outer_inactive));
+                    }
+                }
+                _ if !active => {}
+                "include" => {
+                    let name = argument.strip_prefix('"').and_then(|a| a.strip_suffix('"')).ok_or_else(invalid)?;
+                    if expansion.included.insert(name.to_owned()) {
+                        let source = self.resolve(name).ok_or_else(|| PreprocessError::MissingInclude {
+                            file: file.to_owned(),
+                            line,
+                            name: name.to_owned(),
+                        })?;
+                        self.expand(name, &source, expansion)?;
+                    }
+                }
+                "define" => {
+                    let (name, value) = argument.split_once(char::is_whitespace).unwrap_or((argument, ""));
+                    if name.is_empty() {
+                        return Err(invalid());
+                    }
+                    expansion.defines.insert(name.to_owned(), value.trim().to_owned());
+                }
+                "undef" => {
+                    expansion.defines.remove(argument);
+                }
+                _ => return Err(invalid()),
+            }
+            expansion.output.push('\n');
+        }
+        match conditionals.first() {
+            Some(&(line, ..)) => Err(PreprocessError::UnterminatedConditional { file: file.to_owned(), line }),
+            None => Ok(()),
+        }
+    }
+
+    /// Source of the chunk `name`: registered chunks first, then files in
+    /// the include directories.
+    fn resolve(&self, name: &str) -> Option<String> {
+        self.chunks.get(name).cloned().or_else(|| {
+            self.include_dirs.iter().find_map(|dir| std::fs::read_to_string(dir.join(name)).ok())
+        })
+    }
+}
+
+/// Replace whole-word identifiers in `line` that have a non-empty define.
+fn substitute<'a>(line: &'a str, defines: &HashMap<String, String>) -> std::borrow::Cow<'a, str> {
+    if defines.values().all(String::is_empty) {
+        return line.into();
+    }
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let mut out = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(start) = rest.find(is_ident) {
+        out.push_str(&rest[..start]);
+        let word_len = rest[start..].find(|c: char| !is_ident(c)).unwrap_or(rest.len() - start);
+        let word = &rest[start..start + word_len];
+        match defines.get(word) {
+            Some(value) if !value.is_empty() => out.push_str(value),
+            _ => out.push_str(word),
+        }
+        rest = &rest[start + word_len..];
+    }
+    out.push_str(rest);
+    out.into()
+}
@@ -1,36 +1,47 @@
-struct CameraUniform {
-    view_proj: mat4x4<f32>,
-};
-@group(0) @binding(0)
-var<uniform> camera: CameraUniform;
+// Built-in scene shader.  Preprocessed by `shader::ShaderPreprocessor`,
+// which provides the "vertra/..." chunks.
+#include "vertra/camera.wgsl"
+#include "vertra/model.wgsl"
+#include "vertra/vertex.wgsl"
+#include "vertra/lighting.wgsl"
+#include "vertra/displacement.wgsl"
+#include "vertra/debug.wgsl"
+
+// Variant toggles, set per pipeline; see `pipeline_cache::ShaderVariant`.
+override LIT: bool = true;
+override TEXTURED: bool = true;
 
 @group(1) @binding(0) var t_diffuse: texture_2d<f32>;
 @group(1) @binding(1) var s_diffuse: sampler;
 
-struct VertexInput {
-    @location(0) position: vec3<f32>,
-    @location(1) color: vec3<f32>,
-    @location(2) uv: vec2<f32>,
-};
-
-struct VertexOutput {
-    @builtin(position) clip_position: vec4<f32>,
-    @location(0) color: vec3<f32>,
-    @location(1) uv: vec2<f32>,
-};
-
 @vertex
 fn vs_main(model: VertexInput) -> VertexOutput {
     var out: VertexOutput;
-    // The 'position' is already in World Space because of the CPU flattening logic
-    out.clip_position = camera.view_proj * vec4<f32>(model.position, 1.0);
-    out.color = model.color;
+    // Flattened batches are already in World Space (model_data.model = identity);
+    // registry meshes are local-space and transformed here.
+    let world = (model_data.model * vec4<f32>(model.position, 1.0)).xyz;
+    let world_position = displace(world, model_data.wave, model_data.wave_vector);
+    out.clip_position = camera.view_proj * vec4<f32>(world_position, 1.0);
+    out.world_position = world_position;
+    out.color = model.color * model_data.color.rgb;
+    out.emissive = model_data.emissive.rgb;
     out.uv = model.uv;
     return out;
 }
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    let tex = textureSample(t_diffuse, s_diffuse, in.uv);
-    return vec4<f32>(in.color * tex.rgb, tex.a);
+    if DEBUG_VIEW != 0u {
+        return debug_color(in);
+    }
+    var tex = vec4<f32>(1.0);
+    if TEXTURED {
+        tex = textureSample(t_diffuse, s_diffuse, in.uv);
+    }
+    var light = vec3<f32>(1.0);
+    if LIT {
+        let ambient = ambient_light(flat_normal(in.world_position));
+        light = mix(vec3<f32>(1.0), ambient, model_data.flags.x);
+    }
+    return vec4<f32>((in.color * light + in.emissive) * tex.rgb, tex.a);
 }
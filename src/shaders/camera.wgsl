@@ -0,0 +1,22 @@
+// Scene camera and ambient lighting (group 0).
+struct CameraUniform {
+    view_proj: mat4x4<f32>,
+};
+@group(0) @binding(0)
+var<uniform> camera: CameraUniform;
+
+// Ambient light: `sky` on surfaces facing +Y, `ground` facing -Y.
+struct LightUniform {
+    sky: vec4<f32>,
+    ground: vec4<f32>,
+};
+@group(0) @binding(1)
+var<uniform> lights: LightUniform;
+
+// Per-frame values: x = `Scene::time` in seconds, yzw unused.  Precision
+// drops as the time grows; the built-in waves take per-draw phases instead.
+struct FrameUniform {
+    time: vec4<f32>,
+};
+@group(0) @binding(2)
+var<uniform> frame: FrameUniform;
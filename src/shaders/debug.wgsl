@@ -0,0 +1,34 @@
+// Debug views replacing the shaded colour of scene geometry; see
+// `pipeline_cache::DebugView`.
+#include "vertra/camera.wgsl"
+#include "vertra/vertex.wgsl"
+#include "vertra/lighting.wgsl"
+
+// The active view: 0 off, 1 depth, 2 normals, 3 overdraw, 4 UV checker.
+override DEBUG_VIEW: u32 = 0u;
+
+// View depth, in world units, shown as mid-grey by the depth view.
+const DEPTH_MIDPOINT: f32 = 10.0;
+// Colour every fragment adds in the overdraw view.
+const OVERDRAW_STEP: vec3<f32> = vec3<f32>(0.2, 0.08, 0.02);
+// Checker cells per UV unit.
+const CHECKER_CELLS: f32 = 8.0;
+
+// Colour of `in` under the active view.  Fragment stage only.
+fn debug_color(in: VertexOutput) -> vec4<f32> {
+    if DEBUG_VIEW == 1u {
+        // Clip-space w is the view depth under a perspective projection.
+        let depth = (camera.view_proj * vec4<f32>(in.world_position, 1.0)).w;
+        return vec4<f32>(vec3<f32>(depth / (depth + DEPTH_MIDPOINT)), 1.0);
+    }
+    if DEBUG_VIEW == 2u {
+        return vec4<f32>(flat_normal(in.world_position) * 0.5 + 0.5, 1.0);
+    }
+    if DEBUG_VIEW == 3u {
+        return vec4<f32>(OVERDRAW_STEP, 1.0);
+    }
+    let cell = floor(in.uv * CHECKER_CELLS);
+    let odd = (cell.x + cell.y) - 2.0 * floor((cell.x + cell.y) * 0.5);
+    let tint = vec3<f32>(fract(in.uv), 0.5);
+    return vec4<f32>(tint * mix(1.0, 0.4, odd), 1.0);
+}
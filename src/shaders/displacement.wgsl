@@ -0,0 +1,9 @@
+// Vertex displacement (`material::Displacement`).
+
+// `position` moved by a travelling sine wave: `wave` holds the amplitude
+// and this frame's phase (w, speed times time reduced to one period on the
+// CPU), `wave_vector` the direction scaled by the wavenumber.
+fn displace(position: vec3<f32>, wave: vec4<f32>, wave_vector: vec4<f32>) -> vec3<f32> {
+    let phase = dot(wave_vector.xyz, position) - wave.w;
+    return position + wave.xyz * sin(phase);
+}
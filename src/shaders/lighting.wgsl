@@ -0,0 +1,13 @@
+// Lighting helpers.  Needs the `lights` uniform from "vertra/camera.wgsl".
+#include "vertra/camera.wgsl"
+
+// Flat face normal from screen-space derivatives of the world position.
+// Fragment stage only.
+fn flat_normal(world_position: vec3<f32>) -> vec3<f32> {
+    return normalize(cross(dpdx(world_position), dpdy(world_position)));
+}
+
+// Hemispheric ambient term for a surface facing `normal`.
+fn ambient_light(normal: vec3<f32>) -> vec3<f32> {
+    return mix(lights.ground.rgb, lights.sky.rgb, normal.y * 0.5 + 0.5);
+}
@@ -0,0 +1,17 @@
+// Per-draw model transform, tint, emission and displacement (group 2).  Identity / white
+// for CPU-flattened batches, the object's world transform for registry
+// meshes.
+struct ModelUniform {
+    model: mat4x4<f32>,
+    color: vec4<f32>,
+    // x = 1 when scene lighting applies (0 for the skybox and gizmos).
+    flags: vec4<f32>,
+    // rgb = emitted light (`material::Emission::radiance`), w unused.
+    emissive: vec4<f32>,
+    // `material::Displacement`: amplitude and this frame's phase (w), then
+    // the wave vector.  Applied in world space.
+    wave: vec4<f32>,
+    wave_vector: vec4<f32>,
+};
+@group(2) @binding(0)
+var<uniform> model_data: ModelUniform;
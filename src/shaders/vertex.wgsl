@@ -0,0 +1,15 @@
+// Vertex layout of `mesh::Vertex` and the interface between the stages.
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) color: vec3<f32>,
+    @location(2) uv: vec2<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec3<f32>,
+    @location(1) uv: vec2<f32>,
+    @location(2) world_position: vec3<f32>,
+    // Light given off, added after shading.
+    @location(3) emissive: vec3<f32>,
+};
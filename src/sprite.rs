@@ -0,0 +1,234 @@
+//! Sprite sheets and frame animation.
+//!
+//! An [`Atlas`] divides one texture into regions — a uniform grid of frames,
+//! named rectangles, or both.  An [`AnimatedSprite`] steps through a list of
+//! those regions at a fixed frame rate and writes the current one into its
+//! object's [`Geometry::Sprite`].  It is an [`ObjectScript`], so attaching it
+//! is all it takes to have it ticked every frame:
+//!
+//! ```no_run
+//! # use vertra::objects::Object;
+//! # use vertra::sprite::{AnimatedSprite, Atlas};
+//! # fn demo(scene: &mut vertra::scene::Scene) {
+//! // An 8 × 4 sheet; the explosion is the second row.
+//! let explosion = AnimatedSprite::new(Atlas::grid(8, 4), [2.0, 2.0])
+//!     .with_frames((8..16).collect())
+//!     .with_fps(24.0)
+//!     .with_looping(false);
+//! let id = scene.spawn(Object {
+//!     geometry: Some(explosion.geometry()),
+//!     texture_path: Some("explosion.png".into()),
+//!     ..Default::default()
+//! }, None);
+//! scene.attach_script(id, Box::new(explosion));
+//! # }
+//! ```
+//!
+//! Scripts only run for [`crate::scene::Scene::world`]; for overlay elements
+//! call [`AnimatedSprite::update`] and [`AnimatedSprite::apply`] yourself.
+
+use std::collections::HashMap;
+use crate::constants::sprite::DEFAULT_FPS;
+use crate::geometry::Geometry;
+use crate::objects::Object;
+use crate::script::ObjectScript;
+use crate::world::World;
+
+/// UV regions of a texture, addressed by index or by name.
+///
+/// Regions are `[u0, v0, u1, v1]` with `v0` at the top, as taken by
+/// [`Geometry::Sprite`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Atlas {
+    regions: Vec<[f32; 4]>,
+    names: HashMap<String, usize>,
+}
+
+impl Atlas {
+    /// An atlas without regions; add them with [`Self::with_region`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A sheet of `columns` × `rows` equal frames, numbered left to right,
+    /// top to bottom from 0.
+    pub fn grid(columns: usize, rows: usize) -> Self {
+        let (columns, rows) = (columns.max(1), rows.max(1));
+        let (du, dv) = (1.0 / columns as f32, 1.0 / rows as f32);
+        let regions = (0..rows)
+            .flat_map(|row| (0..columns).map(move |col| (col, row)))
+            .map(|(col, row)| {
+                let (u, v) = (col as f32 * du, row as f32 * dv);
+                [u, v, u + du, v + dv]
+            })
+            .collect();
+        Self { regions, names: HashMap::new() }
+    }
+
+    /// Append `region` under `name`; it gets the next index.  Reusing a
+    /// name points it at the new region.
+    pub fn with_region(mut self, name: impl Into<String>, region: [f32; 4]) -> Self {
+        self.names.insert(name.into(), self.regions.len());
+        self.regions.push(region);
+        self
+    }
+
+    /// Name an existing region, e.g. a frame of a [`Self::grid`].
+    pub fn with_name(mut self, name: impl Into<String>, index: usize) -> Self {
+        if index < self.regions.len() {
+            self.names.insert(name.into(), index);
+        }
+        self
+    }
+
+    /// The region at `index`.
+    pub fn region(&self, index: usize) -> Option<[f32; 4]> {
+        self.regions.get(index).copied()
+    }
+
+    /// The region registered as `name`.
+    pub fn named(&self, name: &str) -> Option<[f32; 4]> {
+        self.index_of(name).and_then(|i| self.region(i))
+    }
+
+    /// Index of the region registered as `name`.
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.names.get(name).copied()
+    }
+
+    /// Number of regions.
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    /// Returns `true` when the atlas has no regions.
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+}
+
+/// Plays a sequence of [`Atlas`] regions on a [`Geometry::Sprite`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimatedSprite {
+    atlas: Atlas,
+    /// Atlas indices played in order.
+    frames: Vec<usize>,
+    /// Frames per second.
+    pub fps: f32,
+    /// Restart after the last frame instead of holding it.
+    pub looping: bool,
+    /// Whether [`Self::update`] advances the animation.
+    pub playing: bool,
+    /// Sprite size in world units (pixels in the overlay).
+    pub size: [f32; 2],
+    elapsed: f32,
+}
+
+impl AnimatedSprite {
+    /// Play every region of `atlas` in order, looping at
+    /// [`DEFAULT_FPS`], on a sprite of `size`.
+    pub fn new(atlas: Atlas, size: [f32; 2]) -> Self {
+        let frames = (0..atlas.len()).collect();
+        Self { atlas, frames, fps: DEFAULT_FPS, looping: true, playing: true, size, elapsed: 0.0 }
+    }
+
+    /// Play these atlas indices instead of all of them.  Indices outside the
+    /// atlas show the whole texture.
+    pub fn with_frames(mut self, frames: Vec<usize>) -> Self {
+        self.frames = frames;
+        self.elapsed = 0.0;
+        self
+    }
+
+    /// Play the regions registered under `names`; unknown names are skipped.
+    pub fn with_named_frames(self, names: &[&str]) -> Self {
+        let frames = names.iter().filter_map(|name| self.atlas.index_of(name)).collect();
+        self.with_frames(frames)
+    }
+
+    /// Play at `fps` frames per second; `0.0` holds the first frame.
+    pub fn with_fps(mut self, fps: f32) -> Self {
+        self.fps = fps;
+        self
+    }
+
+    /// Restart after the last frame (`true`) or hold it.
+    pub fn with_looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    /// The atlas frames are taken from.
+    pub fn atlas(&self) -> &Atlas {
+        &self.atlas
+    }
+
+    /// Advance the animation by `dt` seconds (no-op while paused).
+    pub fn update(&mut self, dt: f32) {
+        if !self.playing || self.fps <= 0.0 {
+            return;
+        }
+        // Keep `elapsed` within one cycle so long-running loops stay exact.
+        let duration = self.frames.len() as f32 / self.fps;
+        self.elapsed += dt;
+        if self.looping {
+            self.elapsed %= duration.max(f32::MIN_POSITIVE);
+        } else {
+            self.elapsed = self.elapsed.min(duration);
+        }
+    }
+
+    /// Position in the frame list, `0..frames.len()`.
+    pub fn frame(&self) -> usize {
+        let count = self.frames.len();
+        if count == 0 || self.fps <= 0.0 {
+            return 0;
+        }
+        let step = (self.elapsed * self.fps) as usize;
+        if self.looping { step % count } else { step.min(count - 1) }
+    }
+
+    /// Jump to position `frame` in the frame list.
+    pub fn set_frame(&mut self, frame: usize) {
+        self.elapsed = if self.fps > 0.0 { frame as f32 / self.fps } else { 0.0 };
+    }
+
+    /// `true` once a non-looping animation has shown its last frame for a
+    /// full frame duration.  Always `false` while looping.
+    pub fn is_finished(&self) -> bool {
+        !self.looping && self.fps > 0.0 && self.elapsed * self.fps >= self.frames.len() as f32
+    }
+
+    /// UV region of the current frame.
+    pub fn region(&self) -> [f32; 4] {
+        self.frames
+            .get(self.frame())
+            .and_then(|&i| self.atlas.region(i))
+            .unwrap_or([0.0, 0.0, 1.0, 1.0])
+    }
+
+    /// The sprite geometry showing the current frame.
+    pub fn geometry(&self) -> Geometry {
+        Geometry::Sprite { width: self.size[0], height: self.size[1], uv: self.region() }
+    }
+
+    /// Show the current frame on `object`.
+    pub fn apply(&self, object: &mut Object) {
+        object.geometry = Some(self.geometry());
+    }
+}
+
+impl ObjectScript for AnimatedSprite {
+    fn on_start(&mut self, id: usize, world: &mut World) {
+        if let Some(obj) = world.get_mut(id) {
+            self.apply(obj);
+        }
+    }
+
+    fn on_update(&mut self, id: usize, world: &mut World, dt: f32) {
+        self.update(dt);
+        if let Some(obj) = world.get_mut(id) {
+            self.apply(obj);
+        }
+    }
+}
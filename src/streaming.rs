@@ -0,0 +1,395 @@
+//! Streaming worlds: content loaded around the camera and unloaded behind
+//! it.
+//!
+//! A [`Streamer`] splits space into cubic cells and keeps the cells within
+//! [`Streamer::radius`] of a centre point loaded.  Missing cells are handed
+//! to a generator function on background threads, nearest first; finished
+//! cells are baked into the scene at most [`Streamer::bake_budget`] per
+//! update so a burst of them cannot cause a hitch.  Cells farther than the
+//! radius plus [`Streamer::unload_margin`] are unloaded, and results for
+//! cells that left the radius while generating are dropped.  A generator
+//! that panics, on a worker or inline, fails only that cell: it is reported
+//! in [`StreamUpdate::failed`] and not retried until it leaves the range.
+//!
+//! The scene streams two kinds of content, each updated once per frame:
+//!
+//! - [`ObjectGroup`]s through [`crate::scene::Scene::stream_objects`],
+//!   spawned as root objects with optional per-object meshes and despawned
+//!   on unload.
+//! - Voxel [`Chunk`]s through [`crate::scene::Scene::stream_voxels`], put
+//!   into a [`crate::voxel::VoxelGrid`] and rebaked nearest first within
+//!   the same budget.
+//!
+//! ```no_run
+//! # fn demo(scene: &mut vertra::scene::Scene, grid: &mut vertra::voxel::VoxelGrid) {
+//! use vertra::constants::voxel::CHUNK_SIZE;
+//! use vertra::streaming::Streamer;
+//! use vertra::voxel::Chunk;
+//!
+//! // Rolling ground four blocks deep, in chunk rows -1 and 0.
+//! let mut terrain = Streamer::voxel_chunks(96.0, |[cx, cy, cz]| {
+//!     let mut chunk = Chunk::new();
+//!     for z in 0..CHUNK_SIZE {
+//!         for x in 0..CHUNK_SIZE {
+//!             let wx = (cx * CHUNK_SIZE as i32 + x as i32) as f32;
+//!             let wz = (cz * CHUNK_SIZE as i32 + z as i32) as f32;
+//!             let top = ((wx * 0.1).sin() * (wz * 0.1).cos() * 4.0) as i32;
+//!             for y in 0..CHUNK_SIZE {
+//!                 let wy = cy * CHUNK_SIZE as i32 + y as i32;
+//!                 if wy <= top && wy > top - 4 {
+//!                     chunk.set([x, y, z], 1);
+//!                 }
+//!             }
+//!         }
+//!     }
+//!     chunk
+//! })
+//! .with_vertical_range(-1, 0);
+//!
+//! // Every frame:
+//! scene.stream_voxels(&mut terrain, grid);
+//! # }
+//! ```
+//!
+//! On `wasm32` there are no threads: cells are generated on the calling
+//! thread, [`Streamer::bake_budget`] per update.  [`Streamer::with_workers`]
+//! with `0` does the same on native targets.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex, PoisonError};
+use crate::constants::streaming;
+use crate::constants::voxel::CHUNK_SIZE;
+use crate::geometry::GeometryId;
+use crate::mesh::MeshData;
+use crate::objects::Object;
+use crate::voxel::Chunk;
+
+/// Cell coordinates: position divided by the cell size, rounded down.
+pub type CellKey = [i32; 3];
+
+type Generator<T> = Arc<dyn Fn(CellKey) -> T + Send + Sync>;
+
+/// Objects generated for one cell, in world space.
+#[derive(Default)]
+pub struct ObjectGroup {
+    /// Each object with the mesh to register for it, if any.
+    pub objects: Vec<(Object, Option<MeshData>)>,
+}
+
+impl ObjectGroup {
+    /// An empty group.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an object drawn from its own geometry or an already registered
+    /// mesh.
+    pub fn push(&mut self, object: Object) {
+        self.objects.push((object, None));
+    }
+
+    /// Add an object drawing `mesh`, registered when the cell is baked and
+    /// freed when it unloads.
+    pub fn push_with_mesh(&mut self, object: Object, mesh: MeshData) {
+        self.objects.push((object, Some(mesh)));
+    }
+}
+
+/// Cells that finished or left during one [`Streamer::update`].
+#[derive(Debug)]
+pub struct StreamUpdate<T> {
+    /// Generated cells to bake, nearest first, at most
+    /// [`Streamer::bake_budget`].
+    pub load: Vec<(CellKey, T)>,
+    /// Loaded cells now out of range.
+    pub unload: Vec<CellKey>,
+    /// Cells whose generator panicked, with the panic message.
+    pub failed: Vec<(CellKey, String)>,
+}
+
+/// Counters from one scene streaming update.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StreamStats {
+    /// Cells baked into the scene.
+    pub loaded: usize,
+    /// Cells removed from the scene.
+    pub unloaded: usize,
+    /// Cells in range still generating or waiting for the bake budget.
+    pub pending: usize,
+    /// Cells whose generator panicked during this update, with the panic
+    /// message.  They are not retried until they leave the range.
+    pub failed: Vec<(CellKey, String)>,
+}
+
+/// Background threads running the generator.
+struct Workers<T> {
+    jobs: Sender<CellKey>,
+    results: Receiver<(CellKey, Result<T, String>)>,
+}
+
+/// Loads the cells around a moving centre.  See the
+/// [module docs](self).
+pub struct Streamer<T> {
+    cell_size: f32,
+    /// Cells whose centre is within this distance of the centre are loaded.
+    pub radius: f32,
+    /// Distance past [`Self::radius`] a loaded cell may drift before it
+    /// unloads, so cells at the edge do not flicker in and out.
+    pub unload_margin: f32,
+    /// Generated cells baked per update.
+    pub bake_budget: usize,
+    /// Lowest and highest row of cells; distances are then horizontal.
+    vertical: Option<[i32; 2]>,
+    generate: Generator<T>,
+    worker_count: usize,
+    workers: Option<Workers<T>>,
+    in_flight: HashSet<CellKey>,
+    /// Cells whose generator panicked, skipped until they leave the range.
+    failed: HashSet<CellKey>,
+    ready: Vec<(CellKey, T)>,
+    loaded: HashSet<CellKey>,
+    /// Objects and meshes each loaded cell added to the scene.
+    pub(crate) spawned: HashMap<CellKey, (Vec<usize>, Vec<GeometryId>)>,
+}
+
+impl<T> std::fmt::Debug for Streamer<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Streamer")
+            .field("cell_size", &self.cell_size)
+            .field("radius", &self.radius)
+            .field("loaded", &self.loaded.len())
+            .field("in_flight", &self.in_flight.len())
+            .field("ready", &self.ready.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Send + 'static> Streamer<T> {
+    /// Stream cells `cell_size` units wide within `radius` of the centre,
+    /// each produced by `generate` on a background thread.
+    pub fn new(cell_size: f32, radius: f32, generate: impl Fn(CellKey) -> T + Send + Sync + 'static) -> Self {
+        Self {
+            cell_size: cell_size.max(f32::EPSILON),
+            radius,
+            unload_margin: streaming::UNLOAD_MARGIN * cell_size,
+            bake_budget: streaming::BAKE_BUDGET,
+            vertical: None,
+            generate: Arc::new(generate),
+            worker_count: if cfg!(target_arch = "wasm32") { 0 } else { streaming::WORKERS },
+            workers: None,
+            in_flight: HashSet::new(),
+            failed: HashSet::new(),
+            ready: Vec::new(),
+            loaded: HashSet::new(),
+            spawned: HashMap::new(),
+        }
+    }
+
+    /// Set the number of generator threads; `0` generates on the calling
+    /// thread.  Ignored on `wasm32`, which always does.
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        if !cfg!(target_arch = "wasm32") {
+            self.worker_count = workers;
+        }
+        self
+    }
+
+    /// Set how many generated cells are baked per update.
+    pub fn with_bake_budget(mut self, budget: usize) -> Self {
+        self.bake_budget = budget;
+        self
+    }
+
+    /// Set how far past the radius loaded cells stay loaded.
+    pub fn with_unload_margin(mut self, margin: f32) -> Self {
+        self.unload_margin = margin;
+        self
+    }
+
+    /// Only stream the rows of cells from `min` to `max`, both inclusive,
+    /// and measure distances horizontally; suits terrain.
+    pub fn with_vertical_range(mut self, min: i32, max: i32) -> Self {
+        self.vertical = Some([min.min(max), min.max(max)]);
+        self
+    }
+
+    /// Width of a cell in world units.
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// The cell holding `position`.
+    pub fn cell_at(&self, position: [f32; 3]) -> CellKey {
+        position.map(|v| (v / self.cell_size).floor() as i32)
+    }
+
+    /// World position of the minimum corner of cell `key`.
+    pub fn cell_origin(&self, key: CellKey) -> [f32; 3] {
+        key.map(|v| v as f32 * self.cell_size)
+    }
+
+    /// Whether cell `key` has been handed out in [`StreamUpdate::load`]
+    /// and not unloaded since.
+    pub fn is_loaded(&self, key: CellKey) -> bool {
+        self.loaded.contains(&key)
+    }
+
+    /// The loaded cells, in no particular order.
+    pub fn loaded(&self) -> impl Iterator<Item = CellKey> + '_ {
+        self.loaded.iter().copied()
+    }
+
+    /// Cells generating or generated but not yet handed out.
+    pub fn pending(&self) -> usize {
+        self.in_flight.len() + self.ready.len()
+    }
+
+    /// Distance from `center` to the centre of cell `key`.
+    fn distance(&self, key: CellKey, center: [f32; 3]) -> f32 {
+        let mid = self.cell_origin(key).map(|v| v + self.cell_size * 0.5);
+        let d = [0, 1, 2].map(|k| mid[k] - center[k]);
+        let vertical = if self.vertical.is_some() { 0.0 } else { d[1] * d[1] };
+        (d[0] * d[0] + vertical + d[2] * d[2]).sqrt()
+    }
+
+    /// Cells within the radius of `center`, nearest first.
+    fn wanted(&self, center: [f32; 3]) -> Vec<CellKey> {
+        let reach = (self.radius / self.cell_size).ceil() as i32 + 1;
+        let [cx, cy, cz] = self.cell_at(center);
+        let [y0, y1] = self.vertical.unwrap_or([cy - reach, cy + reach]);
+        let mut cells: Vec<(f32, CellKey)> = Vec::new();
+        for z in cz - reach..=cz + reach {
+            for y in y0..=y1 {
+                for x in cx - reach..=cx + reach {
+                    let d = self.distance([x, y, z], center);
+                    if d <= self.radius {
+                        cells.push((d, [x, y, z]));
+                    }
+                }
+            }
+        }
+        cells.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+        cells.into_iter().map(|(_, key)| key).collect()
+    }
+
+    /// Start the worker threads on first use.
+    fn workers(&mut self) -> &Workers<T> {
+        self.workers.get_or_insert_with(|| {
+            let (jobs, queue) = mpsc::channel::<CellKey>();
+            let (done, results) = mpsc::channel();
+            let queue = Arc::new(Mutex::new(queue));
+            for _ in 0..self.worker_count {
+                let (queue, done, generate) = (queue.clone(), done.clone(), self.generate.clone());
+                // Workers exit once the streamer, and with it `jobs`, drops.
+                std::thread::spawn(move || loop {
+                    let Ok(key) = queue.lock().unwrap_or_else(PoisonError::into_inner).recv() else { break };
+                    let result = panic::catch_unwind(AssertUnwindSafe(|| generate(key))).map_err(panic_message);
+                    if done.send((key, result)).is_err() {
+                        break;
+                    }
+                });
+            }
+            Workers { jobs, results }
+        })
+    }
+
+    /// Move the centre to `center`: request generation of missing cells in
+    /// range, collect finished ones and report which cells to bake and to
+    /// remove.
+    pub fn update(&mut self, center: [f32; 3]) -> StreamUpdate<T> {
+        let wanted = self.wanted(center);
+        let keep = self.radius + self.unload_margin;
+        let mut unload: Vec<CellKey> = self.loaded.iter().copied().filter(|&key| self.distance(key, center) > keep).collect();
+        unload.sort_unstable();
+        for key in &unload {
+            self.loaded.remove(key);
+        }
+        let mut failed = std::mem::take(&mut self.failed);
+        failed.retain(|&key| self.distance(key, center) <= keep);
+        self.failed = failed;
+
+        let mut failed = Vec::new();
+        if self.worker_count == 0 {
+            // Generate inline, no more than can be baked this update.
+            let missing: Vec<CellKey> = wanted.iter().copied()
+                .filter(|key| {
+                    !self.loaded.contains(key) && !self.failed.contains(key) && !self.ready.iter().any(|(k, _)| k == key)
+                })
+                .take(self.bake_budget.saturating_sub(self.ready.len()))
+                .collect();
+            for key in missing {
+                match panic::catch_unwind(AssertUnwindSafe(|| (self.generate)(key))) {
+                    Ok(value) => self.ready.push((key, value)),
+                    Err(payload) => {
+                        self.failed.insert(key);
+                        failed.push((key, panic_message(payload)));
+                    }
+                }
+            }
+        } else {
+            let finished: Vec<_> = self.workers().results.try_iter().collect();
+            for (key, result) in finished {
+                self.in_flight.remove(&key);
+                match result {
+                    Ok(value) => self.ready.push((key, value)),
+                    Err(message) => {
+                        self.failed.insert(key);
+                        failed.push((key, message));
+                    }
+                }
+            }
+            let capacity = self.worker_count * streaming::JOBS_PER_WORKER;
+            for &key in &wanted {
+                if self.in_flight.len() >= capacity {
+                    break;
+                }
+                if self.loaded.contains(&key)
+                    || self.in_flight.contains(&key)
+                    || self.failed.contains(&key)
+                    || self.ready.iter().any(|(k, _)| *k == key)
+                {
+                    continue;
+                }
+                if self.workers().jobs.send(key).is_ok() {
+                    self.in_flight.insert(key);
+                }
+            }
+        }
+
+        // Results for cells that left the radius are dropped; the rest wait
+        // their turn, nearest first.
+        let radius = self.radius;
+        let mut ready: Vec<(f32, (CellKey, T))> = std::mem::take(&mut self.ready).into_iter()
+            .map(|cell| (self.distance(cell.0, center), cell))
+            .filter(|(d, _)| *d <= radius)
+            .collect();
+        ready.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.0.cmp(&b.1.0)));
+        let mut load = Vec::new();
+        for (_, (key, value)) in ready {
+            if load.len() < self.bake_budget {
+                self.loaded.insert(key);
+                load.push((key, value));
+            } else {
+                self.ready.push((key, value));
+            }
+        }
+        StreamUpdate { load, unload, failed }
+    }
+}
+
+/// The message a generator panicked with.
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    payload.downcast_ref::<&str>().map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "generator panicked".to_string())
+}
+
+impl Streamer<Chunk> {
+    /// Stream voxel chunks: cells are [`CHUNK_SIZE`] blocks wide, so cell
+    /// keys are [`crate::voxel::ChunkKey`]s.
+    pub fn voxel_chunks(radius: f32, generate: impl Fn(CellKey) -> Chunk + Send + Sync + 'static) -> Self {
+        Self::new(CHUNK_SIZE as f32, radius, generate)
+    }
+}
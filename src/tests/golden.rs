@@ -0,0 +1,166 @@
+//! Golden-image harness shared by the rendering tests.
+//!
+//! A scene is rendered into a headless [`Pipeline`] on a software adapter, the
+//! frame is read back to RGBA8, and compared against a reference PNG under
+//! `src/tests/golden/` with a per-channel tolerance.
+//!
+//! * A missing reference fails the test, so a lost or mistyped golden file
+//!   cannot pass CI unnoticed.
+//! * `VERTRA_UPDATE_GOLDEN=1` writes missing references and rewrites every
+//!   existing one after an intentional rendering change; new cases are added
+//!   by running the suite once with it and committing the PNG.
+//! * On mismatch the rendered frame is saved next to the reference as
+//!   `<name>.actual.png` for inspection.
+//!
+//! Without a fallback adapter [`headless_pipeline`] and [`headless_scene`]
+//! return `None` and callers skip.  Tests that only need a device use
+//! [`headless_pipeline`] as well, and tests that touch the file system take
+//! a scratch path from [`TempFile`].
+
+use std::path::PathBuf;
+
+use crate::camera::Camera;
+use crate::pipeline::{Pipeline, PipelineConfig};
+use crate::readback::PixelRect;
+use crate::scene::Scene;
+
+/// Environment variable that forces references to be rewritten.
+const UPDATE_ENV: &str = "VERTRA_UPDATE_GOLDEN";
+
+/// A read-back RGBA8 frame.
+pub struct Frame {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+/// Result of comparing two frames of equal size.
+#[derive(Debug, PartialEq)]
+pub struct ImageDiff {
+    /// Largest absolute difference of any single channel.
+    pub max_channel_delta: u8,
+    /// Number of pixels with any channel differing by more than the tolerance.
+    pub mismatched_pixels: usize,
+}
+
+/// Create a headless [`Pipeline`] on a fallback adapter, or `None` when no
+/// software adapter is installed.
+pub fn headless_pipeline(width: u32, height: u32) -> Option<Pipeline> {
+    let config = PipelineConfig::default().with_force_fallback_adapter(true);
+    match pollster::block_on(Pipeline::headless(&config, width, height)) {
+        Ok(pipeline) => Some(pipeline),
+        Err(e) => {
+            eprintln!("skipping GPU test: {e}");
+            None
+        }
+    }
+}
+
+/// Build a [`Scene`] around [`headless_pipeline`], or `None` when no
+/// software adapter is installed.
+pub fn headless_scene(width: u32, height: u32, camera: Camera) -> Option<Scene> {
+    headless_pipeline(width, height).map(|pipeline| Scene::new(pipeline, camera))
+}
+
+/// A unique path in a fresh temp directory; the directory and everything
+/// written under it are removed on drop.
+pub struct TempFile {
+    pub path: PathBuf,
+    dir: PathBuf,
+}
+
+impl TempFile {
+    /// `name`, which may include subdirectories that do not exist yet, inside
+    /// a newly created directory.
+    pub fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("vertra-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        Self { path: dir.join(name), dir }
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Draw one frame of `scene` and read the offscreen target back.
+pub fn render(scene: &mut Scene) -> Frame {
+    scene.draw_world().unwrap();
+    read_back(&scene.pipeline)
+}
+
+/// Copy the headless target of `pipeline` into CPU memory.
+pub fn read_back(pipeline: &Pipeline) -> Frame {
+    let texture = pipeline.offscreen_texture().expect("golden tests require a headless pipeline");
+    let (width, height) = (texture.width(), texture.height());
+    let pixels = pollster::block_on(pipeline.read_pixels(PixelRect::full(width, height))).expect("readback failed");
+    Frame { width, height, pixels }
+}
+
+/// Compare two equally sized RGBA8 buffers.
+pub fn compare(actual: &[u8], expected: &[u8], tolerance: u8) -> ImageDiff {
+    assert_eq!(actual.len(), expected.len(), "frames must have the same size");
+    let mut diff = ImageDiff { max_channel_delta: 0, mismatched_pixels: 0 };
+    for (a, e) in actual.chunks(4).zip(expected.chunks(4)) {
+        let delta = a.iter().zip(e).map(|(a, e)| a.abs_diff(*e)).max().unwrap_or(0);
+        diff.max_channel_delta = diff.max_channel_delta.max(delta);
+        if delta > tolerance {
+            diff.mismatched_pixels += 1;
+        }
+    }
+    diff
+}
+
+fn golden_path(name: &str, suffix: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src/tests/golden")
+        .join(format!("{name}{suffix}.png"))
+}
+
+fn save(frame: &Frame, path: &PathBuf) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    image::save_buffer(path, &frame.pixels, frame.width, frame.height, image::ColorType::Rgba8)
+        .unwrap_or_else(|e| panic!("failed to write {}: {e}", path.display()));
+}
+
+/// Compare `frame` against the reference image `name`.
+///
+/// Passes when at most `max_mismatched` pixels differ by more than
+/// `tolerance` in any channel; small allowances absorb rasterizer
+/// differences between lavapipe and llvmpipe.
+pub fn assert_golden(name: &str, frame: &Frame, tolerance: u8, max_mismatched: usize) {
+    let path = golden_path(name, "");
+    let update = std::env::var(UPDATE_ENV).is_ok_and(|v| v == "1");
+    if update {
+        save(frame, &path);
+        return;
+    }
+    assert!(
+        path.exists(),
+        "golden '{name}' has no reference at {}; run with {UPDATE_ENV}=1 to create it",
+        path.display(),
+    );
+
+    let expected = image::open(&path)
+        .unwrap_or_else(|e| panic!("failed to read {}: {e}", path.display()))
+        .to_rgba8();
+    assert_eq!(
+        (expected.width(), expected.height()),
+        (frame.width, frame.height),
+        "golden '{name}' has a different size"
+    );
+    let diff = compare(&frame.pixels, expected.as_raw(), tolerance);
+    if diff.mismatched_pixels > max_mismatched {
+        let actual_path = golden_path(name, ".actual");
+        save(frame, &actual_path);
+        panic!(
+            "golden '{name}' mismatch: {} pixels beyond tolerance {tolerance} (max delta {}); \
+             actual frame written to {}",
+            diff.mismatched_pixels,
+            diff.max_channel_delta,
+            actual_path.display(),
+        );
+    }
+}
@@ -0,0 +1,134 @@
+//! Structural invariants for generated meshes, shared by geometry tests.
+//!
+//! [`check_mesh`] reports every violation it finds instead of stopping at the
+//! first, so a failing shape shows the whole picture:
+//!
+//! * every index is in bounds and the index count is a multiple of three
+//! * every triangle has a non-zero area
+//! * closed shapes are watertight: after welding coincident positions each
+//!   edge is shared by exactly two triangles
+//! * winding is consistent: those two triangles traverse the edge in
+//!   opposite directions
+//! * closed shapes wind outward
+//!
+//! Generators emit separate vertices per face, so positions are welded on a
+//! small grid before topology is inspected.
+//!
+//! Vertra's world space is left-handed (the default camera looks down +Z), so
+//! outward-facing triangles are clockwise under the right-hand rule and a
+//! closed mesh has a *negative* right-handed signed volume.
+
+use std::collections::HashMap;
+
+use crate::mesh::Vertex;
+
+/// Grid used to weld coincident positions.
+const WELD_EPSILON: f32 = 1e-4;
+/// Triangles with a smaller area are reported as degenerate.
+const MIN_AREA: f32 = 1e-7;
+
+/// Expected topology of the mesh under test.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Topology {
+    /// A closed, outward-facing solid.
+    Closed,
+    /// A double-sided surface: edges pair up, but it encloses no volume.
+    DoubleSided,
+}
+
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn weld_key(p: [f32; 3]) -> [i64; 3] {
+    p.map(|c| (c / WELD_EPSILON).round() as i64)
+}
+
+/// Validate `vertices` / `indices` and return a list of violations (empty
+/// when the mesh is well formed).
+pub fn check_mesh(vertices: &[Vertex], indices: &[u32], topology: Topology) -> Vec<String> {
+    let mut errors = Vec::new();
+    if indices.is_empty() {
+        errors.push("mesh has no triangles".into());
+        return errors;
+    }
+    if !indices.len().is_multiple_of(3) {
+        errors.push(format!("index count {} is not a multiple of 3", indices.len()));
+        return errors;
+    }
+    if let Some(&bad) = indices.iter().find(|&&i| i as usize >= vertices.len()) {
+        errors.push(format!("index {bad} out of bounds for {} vertices", vertices.len()));
+        return errors;
+    }
+
+    // Weld positions so faces that share a corner share an ID.
+    let mut welded: HashMap<[i64; 3], u32> = HashMap::new();
+    let ids: Vec<u32> = vertices
+        .iter()
+        .map(|v| {
+            let next = welded.len() as u32;
+            *welded.entry(weld_key(v.position)).or_insert(next)
+        })
+        .collect();
+
+    // Directed edge -> number of triangles traversing it in that direction.
+    let mut edges: HashMap<(u32, u32), u32> = HashMap::new();
+    let mut volume = 0.0_f32;
+    for (t, tri) in indices.chunks(3).enumerate() {
+        let [a, b, c] = [tri[0], tri[1], tri[2]].map(|i| vertices[i as usize].position);
+        let area = 0.5 * dot(cross(sub(b, a), sub(c, a)), cross(sub(b, a), sub(c, a))).sqrt();
+        if area.is_nan() || area < MIN_AREA {
+            errors.push(format!("triangle {t} is degenerate (area {area})"));
+            continue;
+        }
+        volume += dot(a, cross(b, c)) / 6.0;
+        let [ia, ib, ic] = [tri[0], tri[1], tri[2]].map(|i| ids[i as usize]);
+        for edge in [(ia, ib), (ib, ic), (ic, ia)] {
+            *edges.entry(edge).or_default() += 1;
+        }
+    }
+
+    // Welding lays both sides of a double-sided surface on top of each other,
+    // so each interior edge is crossed once per side in each direction.
+    let max_count = if topology == Topology::DoubleSided { 2 } else { 1 };
+    for (&(a, b), &count) in &edges {
+        if count > max_count {
+            errors.push(format!("edge {a}->{b} traversed {count} times in the same direction (inconsistent winding)"));
+        }
+        if !edges.contains_key(&(b, a)) {
+            errors.push(format!("edge {a}->{b} has no opposite (mesh is not watertight)"));
+        }
+    }
+
+    match topology {
+        Topology::Closed if volume >= 0.0 => {
+            errors.push(format!("signed volume {volume} is not negative (faces wind inward)"));
+        }
+        Topology::DoubleSided if volume.abs() > 1e-4 => {
+            errors.push(format!("double-sided surface encloses volume {volume}"));
+        }
+        _ => {}
+    }
+    errors
+}
+
+/// Axis-aligned bounds `(min, max)` of `vertices`.
+pub fn bounds(vertices: &[Vertex]) -> ([f32; 3], [f32; 3]) {
+    let mut min = [f32::INFINITY; 3];
+    let mut max = [f32::NEG_INFINITY; 3];
+    for v in vertices {
+        for k in 0..3 {
+            min[k] = min[k].min(v.position[k]);
+            max[k] = max[k].max(v.position[k]);
+        }
+    }
+    (min, max)
+}
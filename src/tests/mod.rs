@@ -4,3 +4,70 @@ mod test_scene_graph_events;
 mod test_snapshot;
 mod test_scripts;
 mod test_frame_stats;
+mod test_mesh_registry;
+mod test_mesh_usage;
+mod test_headless;
+mod golden;
+mod test_golden;
+mod mesh_check;
+mod test_geometry;
+mod test_watch;
+mod test_scene_manager;
+mod test_layers;
+mod test_post;
+mod test_lighting;
+mod test_mesh_gradients;
+mod test_object2d;
+mod test_overlay;
+mod test_sprite;
+#[cfg(all(feature = "audio", not(target_arch = "wasm32")))]
+mod test_audio;
+#[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+mod test_clipboard;
+mod test_window;
+mod test_replay;
+mod test_draw_list;
+mod test_uniforms;
+mod test_bind_cache;
+mod test_pipeline_cache;
+mod test_shader;
+mod test_instances;
+mod test_culling;
+mod test_camera;
+mod test_readback;
+mod test_blit;
+mod test_capture;
+mod test_ao;
+mod test_debug_view;
+mod test_gpu_memory;
+mod test_present_mode;
+mod test_object_builder;
+mod test_presets;
+mod test_world_lookup;
+mod test_world_changes;
+#[cfg(feature = "ecs-mirror")]
+mod test_ecs;
+mod test_resources;
+mod test_spawn_child;
+mod test_spawn_batch;
+mod test_render_hook;
+mod test_app_commands;
+mod test_touch;
+mod test_cursor_pick;
+mod test_mesh_export;
+mod test_gltf;
+mod test_color;
+mod test_rng;
+mod test_snap;
+mod test_water;
+mod test_view_axes;
+mod test_minimap;
+mod test_mirror;
+mod test_occlusion;
+mod test_frustum;
+mod test_material;
+mod test_topology;
+mod test_lines;
+mod test_voxel;
+mod test_streaming;
+mod test_vtm;
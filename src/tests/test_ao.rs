@@ -0,0 +1,134 @@
+//! Tests for baked per-vertex ambient occlusion.
+//!
+//! Coverage:
+//!   - settings defaults and builders
+//!   - rays hit either face of a triangle, within range only
+//!   - convex shapes and open planes are not occluded
+//!   - the floor darkens towards a cube standing on it, and more with
+//!     higher strength
+//!   - static batches render darker with occlusion enabled and recover when
+//!     it is turned off (skipped without a software adapter)
+
+use crate::ao::{self, AoSettings, Occluders};
+use crate::camera::Camera;
+use crate::constants;
+use crate::geometry::{Geometry, SphereTessellation};
+use crate::mesh::{MeshData, MeshUsage};
+use crate::objects::Object;
+use crate::tests::golden;
+use crate::transform::Transform;
+
+const WHITE: [f32; 4] = [1.0; 4];
+
+fn mesh(geometry: Geometry, transform: Transform) -> MeshData {
+    let mut mesh = MeshData::new();
+    geometry.generate_mesh_data(&mut mesh, &transform, WHITE);
+    mesh
+}
+
+/// A 4×4 floor at `y = 0` in 8×8 cells, and a unit cube standing on its
+/// centre.
+fn floor_and_cube() -> (MeshData, MeshData) {
+    let floor = mesh(Geometry::Plane { size: 4.0, subdivisions: 8 }, Transform::default());
+    let cube = mesh(Geometry::Cube { size: 1.0 }, Transform::from_position(0.0, 0.5, 0.0));
+    (floor, cube)
+}
+
+/// Shade of the floor at `(x, 0, z)`.  The plane is double-sided; its
+/// downward-facing vertices are never occluded, so take the darker one.
+fn floor_shade(floor: &MeshData, x: f32, z: f32) -> f32 {
+    floor
+        .vertices
+        .iter()
+        .filter(|v| (v.position[0] - x).abs() < 1e-4 && (v.position[2] - z).abs() < 1e-4)
+        .map(|v| v.color[0])
+        .reduce(f32::min)
+        .expect("grid vertex")
+}
+
+#[test]
+fn settings_defaults_and_builders() {
+    let settings = AoSettings::default();
+    assert_eq!(settings.samples, constants::ao::DEFAULT_SAMPLES);
+    assert_eq!(settings.max_distance, constants::ao::DEFAULT_MAX_DISTANCE);
+    assert_eq!(settings.strength, constants::ao::DEFAULT_STRENGTH);
+
+    let custom = settings.with_samples(8).with_max_distance(3.0).with_strength(0.5);
+    assert_eq!((custom.samples, custom.max_distance, custom.strength), (8, 3.0, 0.5));
+}
+
+#[test]
+fn rays_hit_either_face_within_range() {
+    let mut triangle = MeshData::new();
+    triangle.push_triangle([[-1.0, 1.0, -1.0], [1.0, 1.0, -1.0], [0.0, 1.0, 1.0]], WHITE);
+    let occluders = Occluders::new([&triangle]);
+    assert_eq!(occluders.len(), 1);
+
+    assert!(occluders.hits([0.0, 0.0, 0.0], [0.0, 1.0, 0.0], 2.0));
+    assert!(occluders.hits([0.0, 2.0, 0.0], [0.0, -1.0, 0.0], 2.0), "back face occludes too");
+    assert!(!occluders.hits([0.0, 0.0, 0.0], [0.0, 1.0, 0.0], 0.5), "beyond max distance");
+    assert!(!occluders.hits([0.0, 0.0, 0.0], [0.0, -1.0, 0.0], 2.0), "pointing away");
+    assert!(!occluders.hits([5.0, 0.0, 0.0], [0.0, 1.0, 0.0], 2.0), "beside the triangle");
+    assert!(!Occluders::new([]).hits([0.0; 3], [0.0, 1.0, 0.0], 2.0));
+}
+
+#[test]
+fn convex_shapes_are_unoccluded() {
+    let settings = AoSettings::default().with_strength(1.0);
+    for geometry in [
+        Geometry::Cube { size: 1.0 },
+        Geometry::Sphere { radius: 1.0, subdivisions: 16, tessellation: SphereTessellation::Uv },
+        Geometry::Plane { size: 2.0, subdivisions: 4 },
+    ] {
+        let mut shape = mesh(geometry.clone(), Transform::default());
+        let occluders = Occluders::new([&shape]);
+        ao::bake_mesh(&mut shape, &occluders, &settings);
+        assert!(shape.vertices.iter().all(|v| v.color == [1.0; 3]), "{geometry:?} occludes itself");
+    }
+}
+
+#[test]
+fn floor_darkens_towards_contact() {
+    let (mut floor, cube) = floor_and_cube();
+    let settings = AoSettings::default().with_samples(64).with_max_distance(1.5).with_strength(1.0);
+    let occluders = Occluders::new([&floor, &cube]);
+    ao::bake_mesh(&mut floor, &occluders, &settings);
+
+    let corner = floor_shade(&floor, 1.0, 1.0);
+    let near = floor_shade(&floor, 1.0, 0.0);
+    let far = floor_shade(&floor, 2.0, 2.0);
+    assert!(near < 0.9, "beside a face, got {near}");
+    assert!(corner > near, "a corner sees more sky than a face centre");
+    assert!(far > corner && far > 0.95, "far from the cube, got {far}");
+
+    let (mut weak, _) = floor_and_cube();
+    ao::bake_mesh(&mut weak, &occluders, &settings.with_strength(0.5));
+    let weak_near = floor_shade(&weak, 1.0, 0.0);
+    assert!((1.0 - weak_near - (1.0 - near) * 0.5).abs() < 1e-5);
+}
+
+#[test]
+fn static_batches_bake_occlusion() {
+    // Looking down at the floor beside the cube's +x face.
+    let mut camera = Camera::new().with_position([1.25, 3.0, -0.5]);
+    camera.target = [1.25, 0.0, 0.0];
+    let Some(mut scene) = golden::headless_scene(32, 32, camera) else { return };
+    let floor = Object { geometry: Some(Geometry::Plane { size: 4.0, subdivisions: 8 }), ..Default::default() };
+    let cube = Object {
+        geometry: Some(Geometry::Cube { size: 1.0 }),
+        transform: Transform::from_position(0.0, 0.5, 0.0),
+        ..Default::default()
+    };
+    scene.spawn_with_usage(floor, None, MeshUsage::Static);
+    scene.spawn_with_usage(cube, None, MeshUsage::Static);
+    let plain = golden::render(&mut scene);
+
+    scene.set_ambient_occlusion(Some(AoSettings::default().with_strength(1.0)));
+    assert!(scene.static_batches.is_dirty());
+    let baked = golden::render(&mut scene);
+    let luma = |frame: &golden::Frame| frame.pixels.chunks(4).map(|p| u32::from(p[0])).sum::<u32>();
+    assert!(luma(&baked) < luma(&plain), "occlusion darkens the floor");
+
+    scene.set_ambient_occlusion(None);
+    assert_eq!(golden::render(&mut scene).pixels, plain.pixels);
+}
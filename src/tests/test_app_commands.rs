@@ -0,0 +1,45 @@
+//! Unit tests for `AppCommands`.
+//!
+//! These tests exercise:
+//! - the helpers queue the matching `AppCommand`, in call order
+//! - `drain` empties the queue
+//! - which cursor modes grab the cursor (gating raw mouse motion)
+//!
+//! Applying commands to a window needs a display and is not covered.
+
+use crate::window::{AppCommand, AppCommands, CursorMode};
+
+#[test]
+fn helpers_queue_in_order() {
+    let mut commands = AppCommands::default();
+    assert!(commands.is_empty());
+    commands.set_title("demo");
+    commands.toggle_fullscreen();
+    commands.set_fullscreen(false);
+    commands.set_cursor_mode(CursorMode::Locked);
+    commands.push(AppCommand::Exit);
+    assert_eq!(commands.len(), 5);
+
+    let queued: Vec<_> = commands.drain().collect();
+    assert_eq!(queued, vec![
+        AppCommand::SetTitle("demo".into()),
+        AppCommand::ToggleFullscreen,
+        AppCommand::SetFullscreen(false),
+        AppCommand::SetCursorMode(CursorMode::Locked),
+        AppCommand::Exit,
+    ]);
+    assert!(commands.is_empty());
+}
+
+#[test]
+fn cursor_mode_defaults_to_normal() {
+    assert_eq!(CursorMode::default(), CursorMode::Normal);
+}
+
+#[test]
+fn only_confined_and_locked_grab_the_cursor() {
+    assert!(!CursorMode::Normal.is_grabbed());
+    assert!(!CursorMode::Hidden.is_grabbed());
+    assert!(CursorMode::Confined.is_grabbed());
+    assert!(CursorMode::Locked.is_grabbed());
+}
@@ -0,0 +1,26 @@
+//! Tests for the device-independent parts of audio.rs: distance
+//! attenuation and play options.  Only built with the `audio` feature.
+
+use crate::audio::{Attenuation, PlayOptions};
+
+#[test]
+fn attenuation_follows_inverse_distance() {
+    let falloff = Attenuation { reference_distance: 2.0, max_distance: 20.0, rolloff: 1.0 };
+    assert_eq!(falloff.gain(0.0), 1.0);
+    assert_eq!(falloff.gain(2.0), 1.0);
+    assert_eq!(falloff.gain(4.0), 0.5);
+    assert_eq!(falloff.gain(8.0), 0.25);
+    assert_eq!(falloff.gain(1000.0), falloff.gain(20.0), "no quieter past max_distance");
+
+    let flat = Attenuation { rolloff: 0.0, ..falloff };
+    assert_eq!(flat.gain(15.0), 1.0);
+    assert!(Attenuation::default().gain(10.0) < 1.0);
+}
+
+#[test]
+fn play_options_builders() {
+    let options = PlayOptions::default();
+    assert_eq!((options.volume, options.looping, options.emitter), (1.0, false, None));
+    let options = options.with_volume(0.3).with_looping(true).with_emitter(7);
+    assert_eq!((options.volume, options.looping, options.emitter), (0.3, true, Some(7)));
+}
@@ -0,0 +1,109 @@
+//! Tests for the bind group and layout cache.
+//!
+//! Coverage (skipped without a software adapter):
+//!   - identical layout entries share one layout
+//!   - the same layout and resources return the cached bind group; a
+//!     different resource or binding range creates a new one
+//!   - unused bind groups are evicted after `BIND_GROUP_MAX_UNUSED_FRAMES`
+//!   - rendering a settled scene creates no bind groups
+
+use crate::bind_cache::BindGroupCache;
+use crate::camera::Camera;
+use crate::constants::pipeline::BIND_GROUP_MAX_UNUSED_FRAMES;
+use crate::geometry::Geometry;
+use crate::objects::Object;
+use crate::pipeline::Pipeline;
+use crate::tests::golden;
+
+fn uniform_layout_entry() -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding: 0,
+        visibility: wgpu::ShaderStages::VERTEX,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    }
+}
+
+fn uniform_buffer(pipeline: &Pipeline) -> wgpu::Buffer {
+    pipeline.device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: 512,
+        usage: wgpu::BufferUsages::UNIFORM,
+        mapped_at_creation: false,
+    })
+}
+
+fn buffer_entry(buffer: &wgpu::Buffer, offset: u64) -> [wgpu::BindGroupEntry<'_>; 1] {
+    [wgpu::BindGroupEntry {
+        binding: 0,
+        resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+            buffer,
+            offset,
+            size: wgpu::BufferSize::new(64),
+        }),
+    }]
+}
+
+#[test]
+fn identical_layouts_are_shared() {
+    let Some(pipeline) = golden::headless_pipeline(8, 8) else { return };
+    let mut cache = BindGroupCache::new();
+    let a = cache.layout(&pipeline.device, Some("a"), &[uniform_layout_entry()]);
+    let b = cache.layout(&pipeline.device, Some("b"), &[uniform_layout_entry()]);
+    assert_eq!(a, b);
+    assert_eq!(cache.layout_count(), 1);
+    let from_pipeline = pipeline.bind_group_layout(None, &[uniform_layout_entry()]);
+    assert_eq!(from_pipeline, pipeline.bind_group_layout(None, &[uniform_layout_entry()]));
+}
+
+#[test]
+fn bind_groups_are_keyed_on_resources() {
+    let Some(pipeline) = golden::headless_pipeline(8, 8) else { return };
+    let mut cache = BindGroupCache::new();
+    let layout = cache.layout(&pipeline.device, None, &[uniform_layout_entry()]);
+    let (buffer, other) = (uniform_buffer(&pipeline), uniform_buffer(&pipeline));
+
+    let first = cache.bind_group(&pipeline.device, None, &layout, &buffer_entry(&buffer, 0));
+    let again = cache.bind_group(&pipeline.device, None, &layout, &buffer_entry(&buffer, 0));
+    assert_eq!(first, again);
+    assert_eq!(cache.take_created(), 1);
+
+    let shifted = cache.bind_group(&pipeline.device, None, &layout, &buffer_entry(&buffer, 256));
+    let swapped = cache.bind_group(&pipeline.device, None, &layout, &buffer_entry(&other, 0));
+    assert_ne!(first, shifted);
+    assert_ne!(first, swapped);
+    assert_eq!(cache.take_created(), 2);
+    assert_eq!(cache.len(), 3);
+}
+
+#[test]
+fn unused_bind_groups_are_evicted() {
+    let Some(pipeline) = golden::headless_pipeline(8, 8) else { return };
+    let mut cache = BindGroupCache::new();
+    let layout = cache.layout(&pipeline.device, None, &[uniform_layout_entry()]);
+    let (kept, dropped) = (uniform_buffer(&pipeline), uniform_buffer(&pipeline));
+    cache.bind_group(&pipeline.device, None, &layout, &buffer_entry(&kept, 0));
+    cache.bind_group(&pipeline.device, None, &layout, &buffer_entry(&dropped, 0));
+
+    for _ in 0..=BIND_GROUP_MAX_UNUSED_FRAMES {
+        cache.bind_group(&pipeline.device, None, &layout, &buffer_entry(&kept, 0));
+        cache.end_frame();
+    }
+    assert_eq!(cache.len(), 1, "only the bind group requested every frame survives");
+    assert_eq!(cache.layout_count(), 1, "layouts are never evicted");
+    cache.clear();
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn settled_scene_creates_no_bind_groups() {
+    let Some(mut scene) = golden::headless_scene(16, 16, Camera::new()) else { return };
+    scene.world.spawn_object(Object { geometry: Some(Geometry::Cube { size: 1.0 }), ..Default::default() }, None);
+    scene.draw_world().unwrap();
+    let stats = scene.draw_world().unwrap();
+    assert_eq!(stats.bind_groups_created, 0);
+}
@@ -0,0 +1,77 @@
+//! Tests for texture copies, blits and mipmap generation.
+//!
+//! Coverage (skipped without a software adapter):
+//!   - same-format copies are exact; a copy into a smaller target filters
+//!   - blits convert between colour formats
+//!   - each generated mip level averages the one above
+
+use crate::pipeline::Pipeline;
+use crate::readback::Readback;
+use crate::tests::golden;
+
+const RGBA8: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+
+/// A 4×4 render target whose left half is red and right half blue.
+fn split_target(pipeline: &Pipeline, mip_level_count: u32) -> wgpu::Texture {
+    let texture = pipeline.create_render_target(4, 4, RGBA8, mip_level_count);
+    let row = [[255, 0, 0, 255], [255, 0, 0, 255], [0, 0, 255, 255], [0, 0, 255, 255]];
+    let pixels: Vec<u8> = row.iter().flatten().copied().cycle().take(4 * 4 * 4).collect();
+    pipeline.queue.write_texture(
+        texture.as_image_copy(),
+        &pixels,
+        wgpu::TexelCopyBufferLayout { offset: 0, bytes_per_row: Some(16), rows_per_image: Some(4) },
+        texture.size(),
+    );
+    texture
+}
+
+/// Level `mip` of an 8-bit RGBA `texture`.
+fn read(pipeline: &Pipeline, texture: &wgpu::Texture, mip: u32) -> Vec<u8> {
+    let size = texture.size().mip_level_size(mip, wgpu::TextureDimension::D2);
+    let mut enc = pipeline.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+    let source = wgpu::TexelCopyTextureInfo { mip_level: mip, ..texture.as_image_copy() };
+    let readback = Readback::copy(&pipeline.device, &mut enc, source, [size.width, size.height], 4);
+    pipeline.queue.submit(std::iter::once(enc.finish()));
+    pollster::block_on(readback.read(&pipeline.device)).unwrap()
+}
+
+fn near(actual: &[u8], expected: [u8; 4]) -> bool {
+    actual.iter().zip(expected).all(|(&a, e)| a.abs_diff(e) <= 2)
+}
+
+#[test]
+fn copies_are_exact_or_filtered() {
+    let Some(pipeline) = golden::headless_pipeline(8, 8) else { return };
+    let source = split_target(&pipeline, 1);
+    let same = pipeline.create_render_target(4, 4, wgpu::TextureFormat::Rgba8UnormSrgb, 1);
+    pipeline.copy_texture(&source, &same);
+    assert_eq!(read(&pipeline, &same, 0), read(&pipeline, &source, 0));
+
+    let thumbnail = pipeline.create_render_target(2, 1, RGBA8, 1);
+    pipeline.copy_texture(&source, &thumbnail);
+    let pixels = read(&pipeline, &thumbnail, 0);
+    assert!(near(&pixels[..4], [255, 0, 0, 255]), "{pixels:?}");
+    assert!(near(&pixels[4..], [0, 0, 255, 255]), "{pixels:?}");
+}
+
+#[test]
+fn blits_convert_formats() {
+    let Some(pipeline) = golden::headless_pipeline(8, 8) else { return };
+    let source = split_target(&pipeline, 1);
+    let hdr = pipeline.create_render_target(4, 4, wgpu::TextureFormat::Rgba16Float, 1);
+    pipeline.copy_texture(&source, &hdr);
+    let back = pipeline.create_render_target(4, 4, RGBA8, 1);
+    let view = |texture: &wgpu::Texture| texture.create_view(&wgpu::TextureViewDescriptor::default());
+    pipeline.blit(&view(&hdr), &view(&back));
+    assert_eq!(read(&pipeline, &back, 0), read(&pipeline, &source, 0));
+}
+
+#[test]
+fn mipmaps_average_the_level_above() {
+    let Some(pipeline) = golden::headless_pipeline(8, 8) else { return };
+    let texture = split_target(&pipeline, 3);
+    pipeline.generate_mipmaps(&texture);
+    let level1 = read(&pipeline, &texture, 1);
+    assert!(near(&level1[..4], [255, 0, 0, 255]) && near(&level1[4..8], [0, 0, 255, 255]), "{level1:?}");
+    assert!(near(&read(&pipeline, &texture, 2), [128, 0, 128, 255]));
+}
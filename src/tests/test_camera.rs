@@ -0,0 +1,165 @@
+//! Tests for camera projection helpers.
+//!
+//! Coverage:
+//!   - `Matrix4::inverse` undoes a view-projection and rejects singular input
+//!   - `world_to_screen` puts the look-at target at the viewport centre,
+//!     honours the viewport origin and rejects points behind the camera
+//!   - `screen_to_world` and `screen_ray` invert `world_to_screen`
+//!   - `Viewport::letterbox` centres the largest fitting rectangle and
+//!     `aspect_ratio` divides in floating point
+//!   - `dolly` keeps the look direction and stops short of the target;
+//!     `zoom_fov` clamps; `InputEvent::zoom_steps` normalises wheel and pinch
+//!   - `pixel_size_at` matches one pixel of `world_to_screen`, growing with
+//!     distance in perspective and fixed in orthographic views
+//!   - a fixed aspect ratio matches the camera and leaves black bars
+//!     (skipped without a software adapter)
+
+use crate::camera::{Camera, Viewport};
+use crate::geometry::Geometry;
+use crate::math::Matrix4;
+use crate::objects::Object;
+use crate::tests::golden;
+
+fn camera() -> Camera {
+    Camera::new().with_aspect(2.0).with_position([1.0, 2.0, -8.0]).with_rotation(80.0, -10.0)
+}
+
+fn assert_near(actual: [f32; 3], expected: [f32; 3]) {
+    for (a, e) in actual.iter().zip(expected) {
+        assert!((a - e).abs() < 1e-3, "{actual:?} != {expected:?}");
+    }
+}
+
+#[test]
+fn inverse_undoes_view_projection() {
+    let m = camera().build_view_projection_matrix();
+    let product = m * m.inverse().unwrap();
+    for (col, column) in product.data.iter().enumerate() {
+        for (row, &value) in column.iter().enumerate() {
+            let expected = if col == row { 1.0 } else { 0.0 };
+            assert!((value - expected).abs() < 1e-4, "[{col}][{row}] = {value}");
+        }
+    }
+    assert!(Matrix4 { data: [[0.0; 4]; 4] }.inverse().is_none());
+}
+
+#[test]
+fn world_to_screen_maps_target_to_centre() {
+    let camera = camera();
+    let viewport = Viewport::new(800.0, 400.0);
+    let [x, y, depth] = camera.world_to_screen(camera.target, viewport).unwrap();
+    assert_near([x, y, 0.0], [400.0, 200.0, 0.0]);
+    assert!((0.0..1.0).contains(&depth));
+
+    let shifted = camera.world_to_screen(camera.target, viewport.with_origin(100.0, 50.0)).unwrap();
+    assert_near(shifted, [500.0, 250.0, depth]);
+
+    // A point above the target appears higher on screen (smaller y).
+    let above = [camera.target[0], camera.target[1] + 0.5, camera.target[2]];
+    assert!(camera.world_to_screen(above, viewport).unwrap()[1] < y);
+
+    let (forward, _) = camera.get_directions();
+    let behind = [camera.eye[0] - forward[0], camera.eye[1] - forward[1], camera.eye[2] - forward[2]];
+    assert_eq!(camera.world_to_screen(behind, viewport), None);
+}
+
+#[test]
+fn screen_to_world_round_trips() {
+    let camera = camera();
+    let viewport = Viewport::new(640.0, 320.0).with_origin(10.0, 20.0);
+    let point = [2.0, 1.0, 3.0];
+    let [x, y, depth] = camera.world_to_screen(point, viewport).unwrap();
+    assert_near(camera.screen_to_world([x, y], depth, viewport).unwrap(), point);
+
+    // The click ray passes through the point.
+    let (origin, dir) = camera.screen_ray([x, y], viewport).unwrap();
+    let to_point = [point[0] - origin[0], point[1] - origin[1], point[2] - origin[2]];
+    let t = to_point[0] * dir[0] + to_point[1] * dir[1] + to_point[2] * dir[2];
+    assert_near([origin[0] + dir[0] * t, origin[1] + dir[1] * t, origin[2] + dir[2] * t], point);
+}
+
+#[test]
+fn letterbox_centres_fitting_rectangle() {
+    // Wider window than target: pillarbox bars left and right.
+    assert_eq!(Viewport::letterbox(1920.0, 1080.0, 4.0 / 3.0), Viewport::new(1440.0, 1080.0).with_origin(240.0, 0.0));
+    // Taller window than target: letterbox bars top and bottom.
+    assert_eq!(Viewport::letterbox(800.0, 800.0, 2.0), Viewport::new(800.0, 400.0).with_origin(0.0, 200.0));
+    assert_eq!(Viewport::letterbox(640.0, 480.0, 0.0), Viewport::new(640.0, 480.0));
+
+    assert_eq!(Viewport::new(3.0, 2.0).aspect_ratio(), 1.5);
+    assert_eq!(Viewport::new(5.0, 0.0).aspect_ratio(), 1.0);
+}
+
+#[test]
+fn scroll_zoom_dollies_and_narrows_fov() {
+    use crate::constants::camera::{MAX_FOV, MIN_DOLLY_DISTANCE, PIXELS_PER_SCROLL_STEP};
+    use crate::replay::InputEvent;
+
+    let mut cam = Camera::new().with_position([0.0, 0.0, 10.0]);
+    cam.target = [0.0, 0.0, 0.0];
+    cam.dolly(1.0, 0.5);
+    assert_near(cam.eye, [0.0, 0.0, 5.0]);
+    cam.dolly(-1.0, 0.5);
+    assert_near(cam.eye, [0.0, 0.0, 10.0]);
+    cam.dolly(100.0, 0.5);
+    assert_near(cam.eye, [0.0, 0.0, MIN_DOLLY_DISTANCE]);
+    assert_eq!(cam.target, [0.0, 0.0, 0.0]);
+
+    let fov = cam.fov;
+    cam.zoom_fov(2.0, 5.0);
+    assert_eq!(cam.fov, fov - 10.0);
+    cam.zoom_fov(-100.0, 5.0);
+    assert_eq!(cam.fov, MAX_FOV);
+
+    let wheel = InputEvent::MouseWheel { dx: 0.0, dy: 2.0, pixels: false };
+    let touchpad = InputEvent::MouseWheel { dx: 0.0, dy: PIXELS_PER_SCROLL_STEP, pixels: true };
+    assert_eq!(wheel.zoom_steps(), Some(2.0));
+    assert_eq!(touchpad.zoom_steps(), Some(1.0));
+    assert!(InputEvent::Pinch { delta: -0.1 }.zoom_steps().unwrap() < 0.0);
+    assert_eq!(InputEvent::Focused(true).zoom_steps(), None);
+}
+
+#[test]
+fn pixel_size_matches_projection() {
+    let viewport = Viewport::new(200.0, 100.0);
+    for ortho_height in [None, Some(6.0)] {
+        let camera = Camera { eye: [0.0, 0.0, -10.0], target: [0.0, 0.0, 0.0], ..Camera::new() }
+            .with_aspect(2.0)
+            .with_ortho_height(ortho_height);
+        for distance in [2.0, 10.0] {
+            let size = camera.pixel_size_at(distance, viewport.height);
+            let z = distance - 10.0;
+            let a = camera.world_to_screen([0.0, 0.0, z], viewport).unwrap();
+            let b = camera.world_to_screen([0.0, size, z], viewport).unwrap();
+            assert!((a[1] - b[1] - 1.0).abs() < 1e-3, "{ortho_height:?} at {distance}: {a:?} {b:?}");
+        }
+    }
+    let perspective = Camera::new();
+    assert!((perspective.pixel_size_at(20.0, 100.0) - 2.0 * perspective.pixel_size_at(10.0, 100.0)).abs() < 1e-6);
+    let ortho = Camera::new().with_ortho_height(Some(6.0));
+    assert_eq!(ortho.pixel_size_at(1.0, 100.0), ortho.pixel_size_at(50.0, 100.0));
+}
+
+#[test]
+fn fixed_aspect_leaves_black_bars() {
+    let camera = Camera::new().with_position([0.0, 0.0, -3.0]);
+    let Some(mut scene) = golden::headless_scene(32, 16, camera) else { return };
+    scene.spawn(Object { geometry: Some(Geometry::Cube { size: 4.0 }), ..Default::default() }, None);
+    scene.set_fixed_aspect(Some(1.0));
+    assert_eq!(scene.viewport(), Viewport::new(16.0, 16.0).with_origin(8.0, 0.0));
+    assert_eq!(scene.camera.aspect, 1.0);
+
+    let frame = golden::render(&mut scene);
+    let pixel = |x: u32, y: u32| {
+        let i = ((y * frame.width + x) * 4) as usize;
+        [frame.pixels[i], frame.pixels[i + 1], frame.pixels[i + 2]]
+    };
+    assert_eq!(pixel(2, 8), [0, 0, 0]);
+    assert_eq!(pixel(29, 8), [0, 0, 0]);
+    assert!(pixel(16, 8).iter().all(|&c| c > 100), "the cube fills the viewport");
+
+    scene.set_fixed_aspect(None);
+    assert_eq!(scene.camera.aspect, 2.0);
+    let frame = golden::render(&mut scene);
+    assert_ne!(frame.pixels[8..11], [0, 0, 0]);
+}
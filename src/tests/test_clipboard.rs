@@ -0,0 +1,40 @@
+//! Tests for the clipboard encodings in clipboard.rs: hex colours and
+//! tagged object JSON.  Only built with the `clipboard` feature; the system
+//! clipboard itself is not touched.
+
+use crate::clipboard::{decode_object, encode_object, format_color, parse_color};
+use crate::geometry::Geometry;
+use crate::objects::Object;
+
+#[test]
+fn colours_round_trip_through_hex() {
+    assert_eq!(format_color([1.0, 0.0, 0.5, 1.0]), "#FF0080FF");
+    assert_eq!(format_color([2.0, -1.0, 0.0, 0.0]), "#FF000000", "channels clamp");
+    assert_eq!(parse_color(" #FF0080FF\n"), Some([1.0, 0.0, 128.0 / 255.0, 1.0]));
+    assert_eq!(parse_color("00ff00"), Some([0.0, 1.0, 0.0, 1.0]), "alpha defaults to opaque");
+    assert_eq!(parse_color("#12345"), None);
+    assert_eq!(parse_color("#GG0000"), None);
+    assert_eq!(parse_color("#ÿÿÿ"), None);
+}
+
+#[test]
+fn objects_round_trip_without_links() {
+    let mut object = Object::builder()
+        .name("crate")
+        .geometry(Geometry::Cube { size: 2.0 })
+        .color([0.2, 0.4, 0.6, 1.0])
+        .at(1.0, 2.0, 3.0)
+        .build();
+    object.children = vec![4, 5];
+    object.parent = Some(1);
+
+    let pasted = decode_object(&encode_object(&object)).unwrap();
+    assert_eq!(pasted.name, "crate");
+    assert_eq!(pasted.geometry, object.geometry);
+    assert_eq!(pasted.transform, object.transform);
+    assert!(pasted.children.is_empty() && pasted.parent.is_none());
+    assert_ne!(pasted.str_id, object.str_id, "pasted copies get a fresh id");
+
+    assert!(decode_object(r#"{"name": "not tagged"}"#).is_err());
+    assert!(decode_object("plain text").is_err());
+}
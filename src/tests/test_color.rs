@@ -0,0 +1,104 @@
+//! Unit tests for the `color` module.
+//!
+//! These tests exercise:
+//! - HSV and HSL round trips and reference values
+//! - OKLab round trips and the white / black reference points
+//! - `lerp_oklab` / `gradient` endpoints and alpha
+//! - Hue rotation, analogous, complementary and distinct palettes
+
+use crate::color::*;
+
+fn assert_close(actual: [f32; 3], expected: [f32; 3]) {
+    for k in 0..3 {
+        assert!((actual[k] - expected[k]).abs() < 1e-4, "{actual:?} vs {expected:?}");
+    }
+}
+
+#[test]
+fn hsv_reference_values() {
+    assert_close(hsv_to_rgb([0.0, 1.0, 1.0]), [1.0, 0.0, 0.0]);
+    assert_close(hsv_to_rgb([120.0, 1.0, 1.0]), [0.0, 1.0, 0.0]);
+    assert_close(hsv_to_rgb([240.0, 1.0, 0.5]), [0.0, 0.0, 0.5]);
+    assert_close(hsv_to_rgb([-60.0, 1.0, 1.0]), [1.0, 0.0, 1.0]);
+    assert_close(rgb_to_hsv([1.0, 1.0, 0.0]), [60.0, 1.0, 1.0]);
+    assert_close(rgb_to_hsv([0.5, 0.5, 0.5]), [0.0, 0.0, 0.5]);
+}
+
+#[test]
+fn hsv_and_hsl_round_trip() {
+    for rgb in [[0.2, 0.4, 0.9], [0.9, 0.1, 0.3], [0.05, 0.7, 0.05], [1.0, 1.0, 1.0], [0.0, 0.0, 0.0]] {
+        assert_close(hsv_to_rgb(rgb_to_hsv(rgb)), rgb);
+        assert_close(hsl_to_rgb(rgb_to_hsl(rgb)), rgb);
+    }
+}
+
+#[test]
+fn hsl_reference_values() {
+    assert_close(hsl_to_rgb([0.0, 1.0, 0.5]), [1.0, 0.0, 0.0]);
+    assert_close(hsl_to_rgb([180.0, 1.0, 0.75]), [0.5, 1.0, 1.0]);
+    assert_close(rgb_to_hsl([0.0, 0.0, 0.5]), [240.0, 1.0, 0.25]);
+    assert_eq!(hsl(0.0, 0.0, 1.0), [1.0, 1.0, 1.0, 1.0]);
+}
+
+#[test]
+fn oklab_reference_and_round_trip() {
+    assert_close(rgb_to_oklab([1.0, 1.0, 1.0]), [1.0, 0.0, 0.0]);
+    assert_close(rgb_to_oklab([0.0, 0.0, 0.0]), [0.0, 0.0, 0.0]);
+    for rgb in [[0.2, 0.4, 0.9], [0.9, 0.1, 0.3], [0.05, 0.7, 0.05]] {
+        assert_close(oklab_to_rgb(rgb_to_oklab(rgb)), rgb);
+    }
+}
+
+#[test]
+fn lerp_oklab_hits_endpoints_and_blends_alpha() {
+    let a = [1.0, 0.0, 0.0, 1.0];
+    let b = [0.0, 0.0, 1.0, 0.0];
+    let start = lerp_oklab(a, b, 0.0);
+    let end = lerp_oklab(a, b, 1.0);
+    assert_close([start[0], start[1], start[2]], [1.0, 0.0, 0.0]);
+    assert_close([end[0], end[1], end[2]], [0.0, 0.0, 1.0]);
+    assert_eq!(lerp_oklab(a, b, 0.25)[3], 0.75);
+
+    let mid = lerp_oklab([0.0, 0.0, 0.0, 1.0], [1.0, 1.0, 1.0, 1.0], 0.5);
+    // Perceptual mid-grey is L = 0.5, i.e. 0.125 linear, not 0.5.
+    assert_close([mid[0], mid[1], mid[2]], [0.125, 0.125, 0.125]);
+}
+
+#[test]
+fn gradient_includes_both_ends() {
+    let a = [0.1, 0.2, 0.3, 1.0];
+    let b = [0.9, 0.8, 0.1, 1.0];
+    let colors = gradient(a, b, 4);
+    assert_eq!(colors.len(), 4);
+    assert_close([colors[3][0], colors[3][1], colors[3][2]], [0.9, 0.8, 0.1]);
+    assert_eq!(gradient(a, b, 1), vec![a]);
+    assert!(gradient(a, b, 0).is_empty());
+}
+
+#[test]
+fn hue_palettes() {
+    let red = [1.0, 0.0, 0.0, 0.5];
+    let green = rotate_hue(red, 120.0);
+    assert_close([green[0], green[1], green[2]], [0.0, 1.0, 0.0]);
+    assert_eq!(green[3], 0.5);
+    let cyan = complementary(red);
+    assert_close([cyan[0], cyan[1], cyan[2]], [0.0, 1.0, 1.0]);
+
+    let palette = analogous(red, 3, 60.0);
+    assert_close([palette[0][0], palette[0][1], palette[0][2]], hsv_to_rgb([-30.0, 1.0, 1.0]));
+    assert_close([palette[1][0], palette[1][1], palette[1][2]], [1.0, 0.0, 0.0]);
+    assert_close([palette[2][0], palette[2][1], palette[2][2]], hsv_to_rgb([30.0, 1.0, 1.0]));
+    assert_eq!(analogous(red, 1, 60.0), vec![red]);
+}
+
+#[test]
+fn distinct_colors_differ() {
+    let colors = distinct(8, 0.8, 0.9);
+    assert_eq!(colors.len(), 8);
+    for pair in colors.windows(2) {
+        let [h0, ..] = rgb_to_hsv([pair[0][0], pair[0][1], pair[0][2]]);
+        let [h1, ..] = rgb_to_hsv([pair[1][0], pair[1][1], pair[1][2]]);
+        let gap = (h1 - h0).rem_euclid(360.0);
+        assert!(gap.min(360.0 - gap) > 90.0);
+    }
+}
@@ -0,0 +1,131 @@
+//! Tests for GPU frustum culling.
+//!
+//! Coverage (skipped without a software adapter with compute support):
+//!   - the cull pass counts only instances inside the frustum into the
+//!     indirect arguments and lists their slots as visible
+//!   - a culled instanced scene renders like an unculled one
+//!   - displaced instances whose mesh lies outside the frustum are kept
+
+use crate::camera::Camera;
+use crate::culling::{CullGroup, GpuCuller};
+use crate::geometry::Geometry;
+use crate::instances::{InstanceBuffer, InstanceData};
+use crate::material::Material;
+use crate::math::matrix4::Matrix4;
+use crate::mesh::MeshData;
+use crate::objects::Object;
+use crate::pipeline::Pipeline;
+use crate::tests::golden;
+use crate::transform::Transform;
+
+fn camera() -> Camera {
+    Camera::new().with_aspect(1.0).with_position([0.0, 0.0, -6.0]).with_rotation(90.0, 0.0)
+}
+
+fn translation(x: f32, y: f32, z: f32) -> Matrix4 {
+    let mut m = Matrix4::identity();
+    m.data[3] = [x, y, z, 1.0];
+    m
+}
+
+fn read_u32s(pipeline: &Pipeline, source: &wgpu::Buffer, count: usize) -> Vec<u32> {
+    let size = (count * size_of::<u32>()) as u64;
+    let buffer = pipeline.device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    let mut enc = pipeline.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+    enc.copy_buffer_to_buffer(source, 0, &buffer, 0, size);
+    pipeline.queue.submit(std::iter::once(enc.finish()));
+    buffer.slice(..).map_async(wgpu::MapMode::Read, |r| r.unwrap());
+    pipeline.device.poll(wgpu::PollType::wait_indefinitely()).unwrap();
+    bytemuck::cast_slice(&buffer.slice(..).get_mapped_range()).to_vec()
+}
+
+#[test]
+fn cull_pass_counts_visible_instances() {
+    let Some(pipeline) = golden::headless_pipeline(8, 8).filter(Pipeline::supports_gpu_culling) else { return };
+    let mut instances = InstanceBuffer::new(&pipeline.device);
+    instances.begin_frame();
+    let positions = [(0.0, 0.0, 0.0), (500.0, 0.0, 0.0), (0.0, 0.0, -50.0), (1.0, 1.0, 2.0)];
+    for (id, &(x, y, z)) in positions.iter().enumerate() {
+        instances.push(id, InstanceData { model: translation(x, y, z).data, color: [1.0; 4] });
+    }
+    instances.end_frame();
+    pipeline.upload_instances(&mut instances);
+
+    let layout = pipeline.bind_group_layout(None, &GpuCuller::LAYOUT_ENTRIES);
+    let mut culler = GpuCuller::new(&pipeline.device, &layout);
+    let groups = [CullGroup::new([0.0, 0.0, 0.0, 0.5], 0..1, 36, 0.0), CullGroup::new([0.0, 0.0, 0.0, 0.5], 1..4, 36, 0.0)];
+    let view_proj = camera().build_view_projection_matrix();
+    culler.prepare(&pipeline.device, &pipeline.queue, &view_proj, instances.instance_count(), &groups);
+    let bind_group = pipeline.bind_group(None, &layout, &culler.bind_group_entries(&instances));
+    let mut enc = pipeline.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+    culler.dispatch(&mut enc, &bind_group);
+    pipeline.queue.submit(std::iter::once(enc.finish()));
+
+    // Two groups of five u32 arguments: index count, instance count, first
+    // index, base vertex, first instance.
+    let args = read_u32s(&pipeline, culler.args_buffer(), 10);
+    assert_eq!(args, [36, 1, 0, 0, 0, 36, 1, 0, 0, 1]);
+    let visible = read_u32s(&pipeline, culler.visible_buffer(), 2);
+    assert_eq!(visible, [0, 3], "slots of the two instances in view, at their groups' first index");
+}
+
+#[test]
+fn culled_scene_matches_unculled() {
+    let render = |gpu_culling: bool| {
+        let mut scene = golden::headless_scene(48, 48, camera())?;
+        if !scene.pipeline.supports_gpu_culling() {
+            return None;
+        }
+        scene.instanced_rendering = true;
+        scene.gpu_culling = gpu_culling;
+        let (vertices, indices) = Geometry::Cube { size: 0.5 }.build();
+        let mesh = scene.register_mesh(&MeshData { vertices, indices, ..MeshData::new() });
+        for i in 0..12 {
+            // Every other cube is far outside the view.
+            let x = if i % 2 == 0 { (i / 2) as f32 - 2.5 } else { 200.0 + i as f32 };
+            scene.spawn(
+                Object { mesh: Some(mesh), transform: Transform::from_position(x, 0.0, 0.0), ..Default::default() },
+                None,
+            );
+        }
+        scene.draw_world().unwrap();
+        let stats = scene.draw_world().unwrap();
+        Some((golden::read_back(&scene.pipeline), stats))
+    };
+    let (Some((culled, stats)), Some((unculled, _))) = (render(true), render(false)) else { return };
+    let diff = golden::compare(&culled.pixels, &unculled.pixels, 2);
+    assert_eq!(diff.mismatched_pixels, 0, "{diff:?}");
+    assert_eq!(stats.draw_calls, 1);
+}
+
+#[test]
+fn displaced_instances_outside_the_frustum_are_kept() {
+    let render = |gpu_culling: bool| {
+        let mut scene = golden::headless_scene(32, 32, camera())?;
+        if !scene.pipeline.supports_gpu_culling() {
+            return None;
+        }
+        scene.instanced_rendering = true;
+        scene.gpu_culling = gpu_culling;
+        let (vertices, indices) = Geometry::Cube { size: 0.5 }.build();
+        let mesh = scene.register_mesh(&MeshData { vertices, indices, ..MeshData::new() });
+        // Far off to the side, swung into the middle of the view at 3π/2.
+        let material = Material::default().with_displacement([20.0, 0.0, 0.0], [0.0; 3], 1.0);
+        scene.spawn(
+            Object { mesh: Some(mesh), transform: Transform::from_position(-20.0, 0.0, 0.0), material, ..Default::default() },
+            None,
+        );
+        scene.time = 1.5 * std::f64::consts::PI;
+        Some(golden::render(&mut scene))
+    };
+    let (Some(culled), Some(unculled)) = (render(true), render(false)) else { return };
+    let center = (16 * 32 + 16) * 4;
+    assert_ne!(unculled.pixels[center..center + 4], unculled.pixels[..4], "the cube moved into view");
+    let diff = golden::compare(&culled.pixels, &unculled.pixels, 2);
+    assert_eq!(diff.mismatched_pixels, 0, "{diff:?}");
+}
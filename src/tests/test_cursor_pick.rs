@@ -0,0 +1,55 @@
+//! Tests for cursor picking (`World::raycast`, `Scene::cursor_*`).
+//!
+//! Coverage:
+//!   - `raycast` returns the nearest accepted object with its entry point,
+//!     and skips filtered and mesh-only objects
+//!   - the centre of the viewport looks through the camera target onto the
+//!     ground plane; no cursor or a ray pointing away yields `None`
+//!   - `cursor_hit` respects the camera's layer mask (skipped without a
+//!     software adapter)
+
+use crate::camera::Camera;
+use crate::constants::layers;
+use crate::geometry::Geometry;
+use crate::objects::Object;
+use crate::tests::golden;
+use crate::world::World;
+
+fn cube_at(z: f32) -> Object {
+    Object::builder().geometry(Geometry::Cube { size: 2.0 }).at(0.0, 0.0, z).build()
+}
+
+#[test]
+fn raycast_hits_nearest_accepted_object() {
+    let mut world = World::new();
+    let far = world.spawn_object(cube_at(-10.0), None);
+    let near = world.spawn_object(cube_at(-5.0), None);
+    world.spawn_object(Object { geometry: None, ..cube_at(-2.0) }, None);
+
+    let hit = world.raycast([0.0, 0.0, 0.0], [0.0, 0.0, -1.0], |_, _| true).expect("hit");
+    assert_eq!(hit.id, near);
+    assert!((hit.distance - 4.0).abs() < 1e-5);
+    assert_eq!(hit.point, [0.0, 0.0, -4.0]);
+
+    let hit = world.raycast([0.0, 0.0, 0.0], [0.0, 0.0, -1.0], |id, _| id != near).expect("hit");
+    assert_eq!(hit.id, far);
+    assert!(world.raycast([0.0, 0.0, 0.0], [0.0, 0.0, 1.0], |_, _| true).is_none());
+}
+
+#[test]
+fn cursor_meets_ground_plane() {
+    let camera = Camera::new().with_position([0.0, 5.0, 5.0]);
+    let Some(mut scene) = golden::headless_scene(64, 64, camera) else { return };
+    scene.camera.target = [0.0, 0.0, 0.0];
+    assert_eq!(scene.cursor_world_point(0.0), None, "no cursor yet");
+
+    scene.set_cursor_position(Some([32.0, 32.0]));
+    let [x, y, z] = scene.cursor_world_point(0.0).expect("ground below");
+    assert!(x.abs() < 1e-3 && y == 0.0 && z.abs() < 1e-3, "{x} {y} {z}");
+    assert_eq!(scene.cursor_world_point(10.0), None, "plane behind the ray");
+
+    let ui = scene.spawn(Object { layer: layers::UI, ..cube_at(0.0) }, None);
+    assert_eq!(scene.cursor_hit().map(|hit| hit.id), Some(ui));
+    scene.camera.layers_mask = layers::ALL & !layers::UI;
+    assert_eq!(scene.cursor_hit(), None, "hidden layers are not hit");
+}
@@ -0,0 +1,121 @@
+//! Tests for debug visualisation modes.
+//!
+//! Coverage:
+//!   - only active views pass `DEBUG_VIEW`, and overdraw blends additively
+//!     without depth testing
+//!
+//! GPU tests (skipped without a software adapter):
+//!   - every view compiles for the scene and instanced shaders
+//!   - normals, depth, overdraw and UV checker colours, and `Off` restoring
+//!     regular shading
+
+use crate::camera::Camera;
+use crate::geometry::Geometry;
+use crate::objects::Object;
+use crate::pipeline_cache::{BlendMode, DebugView, DepthMode, PipelineKey, ShaderVariant};
+use crate::scene::Scene;
+use crate::tests::golden::{self, Frame};
+use crate::transform::Transform;
+
+const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+/// A unit cube at `z`, seen head-on from `z = -3`.
+fn cube_at(z: f32) -> Object {
+    Object {
+        geometry: Some(Geometry::Cube { size: 1.0 }),
+        transform: Transform::from_position(0.0, 0.0, z),
+        ..Default::default()
+    }
+}
+
+fn cube_scene() -> Option<Scene> {
+    let mut scene = golden::headless_scene(32, 32, Camera::new().with_position([0.0, 0.0, -3.0]))?;
+    scene.spawn(cube_at(0.0), None);
+    Some(scene)
+}
+
+fn pixel(frame: &Frame, x: u32, y: u32) -> [u8; 4] {
+    let i = ((y * frame.width + x) * 4) as usize;
+    frame.pixels[i..i + 4].try_into().unwrap()
+}
+
+#[test]
+fn views_set_constants_and_state() {
+    assert_eq!(DebugView::default(), DebugView::Off);
+    assert_eq!(DebugView::Off.constant(), None);
+    assert_eq!(DebugView::Depth.constant(), Some(("DEBUG_VIEW", 1.0)));
+    assert_eq!(DebugView::UvChecker.constant(), Some(("DEBUG_VIEW", 4.0)));
+
+    let scene = PipelineKey::scene(FORMAT);
+    assert_eq!(DebugView::Off.apply(scene), scene);
+    let normals = DebugView::Normals.apply(scene);
+    assert_eq!(normals, scene.with_debug_view(DebugView::Normals));
+    assert_eq!(normals.depth, DepthMode::Test);
+
+    let overdraw = DebugView::Overdraw.apply(scene);
+    assert_eq!(overdraw.blend, BlendMode::Additive);
+    assert_eq!(overdraw.depth, DepthMode::Always);
+}
+
+#[test]
+fn every_view_compiles() {
+    let Some(scene) = cube_scene() else { return };
+    let pipeline = &scene.pipeline;
+    let scope = pipeline.device.push_error_scope(wgpu::ErrorFilter::Validation);
+    for view in DebugView::ALL {
+        for textured in [true, false] {
+            let key = view.apply(PipelineKey::scene(FORMAT)).with_variant(ShaderVariant::DEFAULT.with_textured(textured));
+            pipeline.render_pipeline(key);
+            if pipeline.supports_instancing() {
+                pipeline.instanced_pipeline(key);
+            }
+        }
+    }
+    assert!(pollster::block_on(scope.pop()).is_none(), "every debug view compiles");
+}
+
+#[test]
+fn normals_and_depth() {
+    let Some(mut scene) = cube_scene() else { return };
+    let shaded = golden::render(&mut scene);
+
+    // The front face points at -z: (0, 0, -1) maps to (0.5, 0.5, 0).
+    scene.set_debug_view(DebugView::Normals);
+    assert_eq!(scene.debug_view(), DebugView::Normals);
+    let [r, g, b, _] = pixel(&golden::render(&mut scene), 16, 16);
+    assert!(r > 150 && r.abs_diff(g) <= 2 && b < 10, "got {:?}", [r, g, b]);
+
+    scene.set_debug_view(DebugView::Depth);
+    let near = pixel(&golden::render(&mut scene), 16, 16);
+    assert!(near[0] == near[1] && near[1] == near[2], "depth is grey, got {near:?}");
+    scene.world.objects.values_mut().for_each(|obj| obj.transform.position[2] = 5.0);
+    let far = pixel(&golden::render(&mut scene), 16, 16);
+    assert!(far[0] > near[0], "farther is brighter: {far:?} vs {near:?}");
+
+    scene.world.objects.values_mut().for_each(|obj| obj.transform.position[2] = 0.0);
+    scene.set_debug_view(DebugView::Off);
+    assert_eq!(golden::render(&mut scene).pixels, shaded.pixels);
+}
+
+#[test]
+fn overdraw_counts_hidden_layers() {
+    let Some(mut scene) = cube_scene() else { return };
+    // Hidden behind the first cube, only its front face faces the camera.
+    scene.spawn(cube_at(2.0), None);
+    scene.set_debug_view(DebugView::Overdraw);
+    let frame = golden::render(&mut scene);
+    let (two, one, none) = (pixel(&frame, 16, 16), pixel(&frame, 22, 16), pixel(&frame, 1, 1));
+    assert_eq!(none[..3], [0, 0, 0], "cleared to black");
+    assert!(one[0] > 0 && two[0] > one[0], "two layers outshine one: {two:?} vs {one:?}");
+}
+
+#[test]
+fn uv_checker_alternates() {
+    let Some(mut scene) = cube_scene() else { return };
+    scene.set_debug_view(DebugView::UvChecker);
+    let frame = golden::render(&mut scene);
+    // Blue is a constant 0.5 tint, dimmed in the dark cells.
+    let blues: Vec<u8> = (9..23).map(|x| pixel(&frame, x, 16)[2]).collect();
+    assert!(blues.iter().any(|&b| b > 170), "light cells, got {blues:?}");
+    assert!(blues.iter().any(|&b| b < 140), "dark cells, got {blues:?}");
+}
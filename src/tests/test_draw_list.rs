@@ -0,0 +1,67 @@
+//! Tests for draw-list preparation.
+//!
+//! Coverage:
+//!   - `MeshData::append` offsets the appended indices
+//!   - preparing on worker threads gives the same groups, geometry order and
+//!     mesh objects as preparing on one thread (skipped without a software
+//!     adapter)
+
+use crate::camera::Camera;
+use crate::constants::scene::PARALLEL_PREP_MIN_OBJECTS;
+use crate::geometry::{Geometry, GeometryId};
+use crate::mesh::MeshData;
+use crate::objects::Object;
+use crate::tests::golden;
+use crate::transform::Transform;
+
+#[test]
+fn append_offsets_indices() {
+    let mut a = MeshData::new();
+    a.push_triangle([[0.0; 3], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]], [1.0; 4]);
+    let mut b = MeshData::new();
+    b.push_quad([[0.0; 3], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]], [1.0; 4]);
+    let b_indices = b.indices.clone();
+
+    a.append(b);
+    assert_eq!(a.vertices.len(), 7);
+    assert_eq!(a.indices[..3], [0, 1, 2]);
+    assert_eq!(a.indices[3..], b_indices.iter().map(|i| i + 3).collect::<Vec<_>>()[..]);
+}
+
+#[test]
+fn parallel_prepare_matches_sequential() {
+    let Some(mut scene) = golden::headless_scene(8, 8, Camera::new()) else { return };
+    // Enough roots, each with a child, to cross the parallel threshold.
+    for i in 0..PARALLEL_PREP_MIN_OBJECTS / 2 {
+        let texture_path = (i % 3 != 0).then(|| format!("tex{}.png", i % 3));
+        let root = scene.world.spawn_object(Object {
+            geometry: Some(Geometry::Cube { size: 1.0 }),
+            transform: Transform::from_position(i as f32, 0.0, 0.0),
+            texture_path,
+            ..Default::default()
+        }, None);
+        scene.world.spawn_object(Object {
+            geometry: (i % 2 == 0).then_some(Geometry::Cube { size: 0.5 }),
+            mesh: (i % 2 == 1).then_some(GeometryId(0)),
+            transform: Transform::from_position(0.0, 1.0, 0.0),
+            ..Default::default()
+        }, Some(root));
+    }
+
+    scene.prep_threads = 1;
+    let sequential = scene.prepare_draw_list(false);
+    scene.prep_threads = 4;
+    let parallel = scene.prepare_draw_list(false);
+
+    assert_eq!(sequential.groups.len(), 3);
+    let keys = |list: &crate::scene::DrawList| list.groups.iter().map(|(k, _)| k.clone()).collect::<Vec<_>>();
+    assert_eq!(keys(&sequential), keys(&parallel), "groups keep first-seen order");
+    for ((_, seq), (_, par)) in sequential.groups.iter().zip(&parallel.groups) {
+        assert_eq!(seq.indices, par.indices);
+        let positions = |m: &MeshData| m.vertices.iter().map(|v| v.position).collect::<Vec<_>>();
+        assert_eq!(positions(seq), positions(par));
+    }
+    assert_eq!(sequential.mesh_objects.len(), PARALLEL_PREP_MIN_OBJECTS / 4);
+    let ids = |list: &crate::scene::DrawList| list.mesh_objects.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+    assert_eq!(ids(&sequential), ids(&parallel));
+}
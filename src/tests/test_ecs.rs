@@ -0,0 +1,79 @@
+//! Unit tests for the `ecs` mirror of `World`.
+//!
+//! These tests exercise:
+//! - spawned objects get entities with their components, optional ones only
+//!   when present
+//! - deleting a subtree despawns its entities, reparenting updates `Parent`
+//! - `get_mut` edits reach the mirror at `end_frame`
+//! - transform edits made through `World` reach the mirror at `end_frame`
+
+use crate::ecs::{Color, Layer, Name, ObjectId, Parent};
+use crate::geometry::Geometry;
+use crate::objects::Object;
+use crate::transform::Transform;
+use crate::world::World;
+
+#[test]
+fn spawned_objects_become_entities() {
+    let mut world = World::new();
+    let root = world.spawn_object(Object::builder().name("root").color([1.0, 0.0, 0.0, 1.0]).layer(2).build(), None);
+    let cube = world.spawn_object(Object::builder().geometry(Geometry::Cube { size: 1.0 }).build(), Some(root));
+
+    let ecs = world.ecs();
+    assert_eq!(ecs.len(), 2);
+    let entity = world.entity(root).expect("root entity");
+    assert_eq!(*ecs.get::<&ObjectId>(entity).unwrap(), ObjectId(root));
+    assert_eq!(*ecs.get::<&Name>(entity).unwrap(), Name("root".into()));
+    assert_eq!(*ecs.get::<&Color>(entity).unwrap(), Color([1.0, 0.0, 0.0, 1.0]));
+    assert_eq!(*ecs.get::<&Layer>(entity).unwrap(), Layer(2));
+    assert!(!ecs.satisfies::<&Parent>(entity));
+    assert!(!ecs.satisfies::<&Geometry>(entity));
+
+    let entity = world.entity(cube).unwrap();
+    assert_eq!(*ecs.get::<&Parent>(entity).unwrap(), Parent(root));
+    assert!(ecs.satisfies::<&Geometry>(entity));
+    assert_eq!(ecs.query::<&Geometry>().iter().count(), 1);
+}
+
+#[test]
+fn deletes_and_reparents_update_the_mirror() {
+    let mut world = World::new();
+    let a = world.spawn_object(Object::default(), None);
+    let b = world.spawn_object(Object::default(), Some(a));
+    let c = world.spawn_object(Object::default(), None);
+
+    world.reparent(b, Some(c));
+    assert_eq!(*world.ecs().get::<&Parent>(world.entity(b).unwrap()).unwrap(), Parent(c));
+
+    world.delete(c);
+    assert_eq!(world.entity(b), None);
+    assert_eq!(world.entity(c), None);
+    assert_eq!(world.ecs().len(), 1);
+    assert!(world.ecs().contains(world.entity(a).unwrap()));
+}
+
+#[test]
+fn edits_sync_at_end_frame() {
+    let mut world = World::new();
+    let id = world.spawn_object(Object::default(), None);
+    world.get_mut(id).unwrap().color = [0.0, 1.0, 0.0, 1.0];
+    let entity = world.entity(id).unwrap();
+    assert_eq!(*world.ecs().get::<&Color>(entity).unwrap(), Color(Object::default().color));
+
+    world.end_frame();
+    assert_eq!(*world.ecs().get::<&Color>(entity).unwrap(), Color([0.0, 1.0, 0.0, 1.0]));
+}
+
+#[test]
+fn world_transforms_sync_at_end_frame() {
+    let mut world = World::new();
+    let moved = world.spawn_object(Object::default(), None);
+    let still = world.spawn_object(Object::default(), None);
+    world.end_frame();
+
+    world.get_mut(moved).unwrap().transform.position = [1.0, 2.0, 3.0];
+    world.end_frame();
+    let position = |id| world.ecs().get::<&Transform>(world.entity(id).unwrap()).unwrap().position;
+    assert_eq!(position(moved), [1.0, 2.0, 3.0]);
+    assert_eq!(position(still), [0.0; 3]);
+}
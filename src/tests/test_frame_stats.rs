@@ -1,6 +1,7 @@
 use std::thread::sleep;
 use std::time::{Duration, Instant};
 use crate::frame_stats::FrameStats;
+use crate::pipeline::RenderStats;
 use crate::constants::frame_stats::DEFAULT_SAMPLE_WINDOW_SECS;
 use crate::window::Window;
 
@@ -85,6 +86,26 @@ fn gpu_stats_update_immediately() {
     assert_eq!(stats.triangle_count, 8_000);
 }
 
+#[test]
+fn render_stats_are_stored_and_mirror_gpu_stats() {
+    let mut stats = make_stats();
+    let render = RenderStats {
+        draw_calls: 3,
+        instance_count: 5,
+        triangle_count: 120,
+        bytes_uploaded: 4096,
+        buffer_reallocations: 2,
+        bind_groups_created: 1,
+        ..Default::default()
+    };
+
+    stats.set_render_stats(render);
+
+    assert_eq!(stats.render, render);
+    assert_eq!(stats.draw_calls, 3);
+    assert_eq!(stats.triangle_count, 120);
+}
+
 #[test]
 fn custom_sample_window_commits_early() {
     let custom_window = 0.1; // Much shorter than the default 0.5s
@@ -0,0 +1,100 @@
+//! Unit tests for view frustums and the culling debug view.
+//!
+//! These tests exercise:
+//! - Plane extraction keeps points, spheres and boxes inside the frustum
+//! - Corners land on the near and far rectangles in index order
+//! - Tinting mixes towards the tint colour by its alpha
+//! - A frozen camera tints objects outside its frustum and its outline is
+//!   drawn on top of the frame
+
+use crate::camera::Camera;
+use crate::frustum::{CullingDebug, Frustum};
+use crate::geometry::Geometry;
+use crate::objects::Object;
+use crate::tests::golden;
+use crate::transform::Transform;
+
+fn camera() -> Camera {
+    Camera { eye: [0.0, 0.0, -10.0], target: [0.0, 0.0, 0.0], aspect: 1.0, fov: 90.0, znear: 1.0, zfar: 20.0, ..Camera::new() }
+}
+
+#[test]
+fn planes_bound_spheres_and_boxes() {
+    let frustum = Frustum::from_camera(&camera());
+    assert!(frustum.intersects_sphere([0.0, 0.0, 0.0], 0.1));
+    assert!(!frustum.intersects_sphere([0.0, 0.0, -12.0], 1.0), "behind the eye");
+    assert!(!frustum.intersects_sphere([0.0, 0.0, 11.0], 0.5), "past the far plane");
+    // 10 units in front the half-width is 10: a sphere beyond it only
+    // counts once its radius reaches back in.
+    assert!(!frustum.intersects_sphere([12.0, 0.0, 0.0], 1.0));
+    assert!(frustum.intersects_sphere([12.0, 0.0, 0.0], 2.0));
+
+    assert!(frustum.intersects_box([-1.0; 3], [1.0; 3]));
+    assert!(frustum.intersects_box([9.0, -1.0, -1.0], [11.0, 1.0, 1.0]), "straddling the right plane");
+    assert!(!frustum.intersects_box([12.0, -1.0, -1.0], [14.0, 1.0, 1.0]));
+    assert!(!frustum.intersects_box([-1.0, -1.0, -15.0], [1.0, 1.0, -12.0]));
+}
+
+#[test]
+fn corners_follow_index_bits() {
+    let corners = Frustum::corners(&camera().build_view_projection_matrix()).unwrap();
+    let close = |a: [f32; 3], b: [f32; 3]| (0..3).all(|k| (a[k] - b[k]).abs() < 1e-3);
+    // Left-handed, looking down +Z: right is +X, top is +Y.
+    assert!(close(corners[0], [-1.0, -1.0, -9.0]), "{:?}", corners[0]);
+    assert!(close(corners[3], [1.0, 1.0, -9.0]), "{:?}", corners[3]);
+    assert!(close(corners[4], [-20.0, -20.0, 10.0]), "{:?}", corners[4]);
+    assert!(close(corners[7], [20.0, 20.0, 10.0]), "{:?}", corners[7]);
+}
+
+#[test]
+fn tint_mixes_by_alpha() {
+    let debug = CullingDebug::default().with_culled_tint([1.0, 0.0, 0.0, 0.5]);
+    assert_eq!(debug.tinted([0.0, 1.0, 0.0, 0.25]), [0.5, 0.5, 0.0, 0.25]);
+    assert_eq!(debug.with_culled_tint([1.0, 0.0, 0.0, 0.0]).tinted([0.0, 1.0, 0.0, 1.0]), [0.0, 1.0, 0.0, 1.0]);
+
+    let mut debug = CullingDebug::default();
+    let scene_camera = camera();
+    let frozen = Camera { eye: [5.0, 0.0, 0.0], ..scene_camera };
+    debug.freeze(&frozen);
+    assert_eq!(debug.culling_camera(&scene_camera).eye, frozen.eye);
+    debug.unfreeze();
+    assert_eq!(debug.culling_camera(&scene_camera).eye, scene_camera.eye);
+}
+
+#[test]
+fn frozen_camera_tints_and_outlines() {
+    let Some(mut scene) = golden::headless_scene(48, 48, camera()) else { return };
+    let cube = |x: f32| Object {
+        geometry: Some(Geometry::Cube { size: 2.0 }),
+        color: [0.1, 1.0, 0.1, 1.0],
+        transform: Transform::from_position(x, 0.0, 0.0),
+        ..Default::default()
+    };
+    scene.spawn(cube(-4.0), None);
+    scene.spawn(cube(4.0), None);
+    let green = |frame: &golden::Frame, x: usize, y: usize| {
+        let at = (y * 48 + x) * 4;
+        let [r, g, b] = [0, 1, 2].map(|k| u32::from(frame.pixels[at + k]));
+        g > 100 && g > r * 2 && g > b * 2
+    };
+    let plain = golden::render(&mut scene);
+    assert!(green(&plain, 14, 24) && green(&plain, 34, 24));
+
+    // Nothing changes while culling follows the scene camera and the cubes
+    // are in view: its own outline is not drawn from where it stands.
+    scene.culling_debug = Some(CullingDebug::default());
+    assert_eq!(golden::render(&mut scene).pixels, plain.pixels);
+
+    // Frozen on the left cube, the right one is outside and tinted.
+    let mut debug = CullingDebug::default().with_colors(None, Some([1.0, 1.0, 1.0, 1.0]));
+    debug.freeze(&Camera { eye: [-4.0, 0.0, -6.0], target: [-4.0, 0.0, 0.0], fov: 30.0, ..camera() });
+    scene.culling_debug = Some(debug);
+    let frozen = golden::render(&mut scene);
+    assert!(green(&frozen, 14, 24));
+    assert!(!green(&frozen, 34, 24));
+    let white = frozen.pixels.chunks(4).filter(|p| p[..3].iter().all(|&c| c > 240)).count();
+    assert!(white > 0, "the frozen frustum is outlined");
+
+    scene.culling_debug = None;
+    assert_eq!(golden::render(&mut scene).pixels, plain.pixels);
+}
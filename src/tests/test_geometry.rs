@@ -0,0 +1,424 @@
+//! Invariant and snapshot tests for procedural geometry.
+//!
+//! Every `Geometry` variant is generated across a range of parameters and
+//! validated with `mesh_check::check_mesh` (index bounds, non-degenerate
+//! triangles, watertightness, consistent outward winding).  Vertex / index
+//! counts and bounds are pinned as snapshots so a generator refactor cannot
+//! silently change a shape.  Per-face colouring is checked against the
+//! documented face order.  `with_accuracy` is checked against the edge
+//! lengths and sagging it promises.
+
+use super::mesh_check::{bounds, check_mesh, Topology};
+use crate::constants::geometry::{MAX_ADAPTIVE_SUBDIVISIONS, MIN_ADAPTIVE_SUBDIVISIONS};
+use crate::geometry::{Accuracy, Geometry, SphereTessellation};
+
+const EPS: f32 = 1e-4;
+
+fn topology(geometry: &Geometry) -> Topology {
+    match geometry {
+        Geometry::Plane { .. } | Geometry::Sprite { .. } => Topology::DoubleSided,
+        _ => Topology::Closed,
+    }
+}
+
+fn assert_valid(geometry: Geometry) {
+    let (vertices, indices) = geometry.build();
+    let errors = check_mesh(&vertices, &indices, topology(&geometry));
+    assert!(errors.is_empty(), "{geometry:?}:\n  {}", errors.join("\n  "));
+}
+
+fn assert_bounds(geometry: Geometry, min: [f32; 3], max: [f32; 3]) {
+    let (vertices, _) = geometry.build();
+    let (lo, hi) = bounds(&vertices);
+    for k in 0..3 {
+        assert!((lo[k] - min[k]).abs() < EPS, "{geometry:?}: min {lo:?} != {min:?}");
+        assert!((hi[k] - max[k]).abs() < EPS, "{geometry:?}: max {hi:?} != {max:?}");
+    }
+}
+
+fn counts(geometry: Geometry) -> (usize, usize) {
+    let (vertices, indices) = geometry.build();
+    (vertices.len(), indices.len())
+}
+
+// -- invariants across parameter ranges ------------------------------------
+
+#[test]
+fn cube_and_box_are_valid() {
+    for size in [0.01, 0.5, 1.0, 7.5] {
+        assert_valid(Geometry::Cube { size });
+    }
+    for (w, h, d) in [(1.0, 2.0, 3.0), (0.1, 5.0, 0.1), (10.0, 0.05, 4.0)] {
+        assert_valid(Geometry::Box { width: w, height: h, depth: d, segments: [1; 3] });
+    }
+    for segments in [[2, 1, 1], [1, 3, 2], [4, 4, 4], [0, 0, 0]] {
+        assert_valid(Geometry::Box { width: 1.0, height: 2.0, depth: 3.0, segments });
+    }
+}
+
+#[test]
+fn rounded_box_is_valid() {
+    for radius in [0.0, 0.1, 0.25, 0.5, 2.0] {
+        for subdivisions in [0, 1, 3, 8] {
+            assert_valid(Geometry::RoundedBox { width: 1.0, height: 2.0, depth: 3.0, radius, subdivisions });
+        }
+    }
+}
+
+#[test]
+fn rounded_box_tolerates_degenerate_extents() {
+    let mirrored = Geometry::RoundedBox { width: -1.0, height: 2.0, depth: -3.0, radius: 0.25, subdivisions: 2 };
+    assert_bounds(mirrored, [-0.5, -1.0, -1.5], [0.5, 1.0, 1.5]);
+
+    for (width, radius) in [(0.0, 0.25), (f32::NAN, 0.25), (1.0, f32::NAN), (-0.0, -1.0)] {
+        let geometry = Geometry::RoundedBox { width, height: 2.0, depth: 3.0, radius, subdivisions: 3 };
+        let (vertices, _) = geometry.build();
+        assert!(!vertices.is_empty());
+        assert!(
+            vertices.iter().all(|v| v.position.iter().all(|c| c.is_finite())),
+            "{geometry:?} produced non-finite vertices",
+        );
+    }
+}
+
+#[test]
+fn rounded_box_bevels_are_round() {
+    let (w, h, d, r) = (1.0_f32, 2.0_f32, 3.0_f32, 0.25_f32);
+    let inner = [w * 0.5 - r, h * 0.5 - r, d * 0.5 - r];
+    let (vertices, _) = Geometry::RoundedBox { width: w, height: h, depth: d, radius: r, subdivisions: 4 }.build();
+    for v in &vertices {
+        let p = v.position;
+        let dist = (0..3)
+            .map(|k| (p[k].abs() - inner[k]).max(0.0).powi(2))
+            .sum::<f32>()
+            .sqrt();
+        assert!((dist - r).abs() < EPS, "{p:?} is {dist} from the inner box, not {r}");
+    }
+}
+
+#[test]
+fn arrow_is_valid() {
+    // Heads wider than, narrower than and as long as the whole arrow.
+    for (length, shaft_radius, head_size) in [(1.0, 0.05, 0.3), (2.0, 0.3, 0.2), (0.5, 0.1, 0.5), (1.0, 0.1, 3.0)] {
+        assert_valid(Geometry::Arrow { length, shaft_radius, head_size });
+    }
+}
+
+#[test]
+fn axes_point_along_x_y_and_z() {
+    let (vertices, indices) = Geometry::build_axes(2.0);
+    assert_eq!(indices.len(), 3 * counts(Geometry::Arrow { length: 2.0, shaft_radius: 0.04, head_size: 0.4 }).1);
+    // One arrow per axis, in X, Y, Z order; each one's furthest point is its tip.
+    let arrow = vertices.len() / 3;
+    for (axis, chunk) in vertices.chunks(arrow).enumerate() {
+        let tip = chunk
+            .iter()
+            .map(|v| v.position)
+            .max_by(|a, b| a[axis].total_cmp(&b[axis]))
+            .unwrap();
+        let mut expected = [0.0; 3];
+        expected[axis] = 2.0;
+        for k in 0..3 {
+            assert!((tip[k] - expected[k]).abs() < EPS, "axis {axis}: tip {tip:?}");
+        }
+    }
+}
+
+#[test]
+fn plane_is_valid() {
+    for size in [0.1, 1.0, 100.0] {
+        assert_valid(Geometry::Plane { size, subdivisions: 1 });
+    }
+    for subdivisions in [0, 2, 7, 32] {
+        assert_valid(Geometry::Plane { size: 4.0, subdivisions });
+    }
+}
+
+#[test]
+fn sprite_is_valid_and_maps_its_uv_region() {
+    let sprite = Geometry::Sprite { width: 2.0, height: 1.0, uv: [0.25, 0.5, 0.5, 0.75] };
+    assert_valid(sprite.clone());
+    assert_bounds(sprite.clone(), [-1.0, -0.5, 0.0], [1.0, 0.5, 0.0]);
+    assert_eq!(counts(sprite.clone()), (8, 12));
+    let (vertices, _) = sprite.build();
+    for v in &vertices {
+        let u = if v.position[0] < 0.0 { 0.25 } else { 0.5 };
+        let v_top = if v.position[1] > 0.0 { 0.5 } else { 0.75 };
+        assert_eq!(v.uv, [u, v_top], "at {:?}", v.position);
+    }
+}
+
+#[test]
+fn pyramid_is_valid() {
+    for (base_size, height) in [(1.0, 1.0), (0.2, 3.0), (4.0, 0.1)] {
+        assert_valid(Geometry::Pyramid { base_size, height });
+    }
+}
+
+#[test]
+fn sphere_is_valid_across_subdivisions() {
+    for subdivisions in [0, 3, 4, 8, 9, 16, 33, 64] {
+        for radius in [0.1, 1.0, 25.0] {
+            for tessellation in [SphereTessellation::Uv, SphereTessellation::Icosphere] {
+                assert_valid(Geometry::Sphere { radius, subdivisions, tessellation });
+            }
+        }
+    }
+}
+
+#[test]
+fn sphere_vertices_are_shared_and_on_the_surface() {
+    for tessellation in [SphereTessellation::Uv, SphereTessellation::Icosphere] {
+        let (vertices, indices) = Geometry::Sphere { radius: 2.0, subdivisions: 16, tessellation }.build();
+        for v in &vertices {
+            let r = v.position.iter().map(|c| c * c).sum::<f32>().sqrt();
+            assert!((r - 2.0).abs() < EPS, "{tessellation:?}: {:?}", v.position);
+        }
+        // Only seam vertices repeat a position, with different UVs.
+        let mut seen = std::collections::HashMap::new();
+        for v in &vertices {
+            if let Some(uv) = seen.insert(v.position.map(f32::to_bits), v.uv) {
+                assert!(((uv[0] - v.uv[0]).abs() - 1.0).abs() < EPS && uv[1] == v.uv[1], "{tessellation:?}: {:?}", v.position);
+            }
+        }
+        assert!(indices.len() / 3 > vertices.len(), "{tessellation:?}: triangles share vertices");
+    }
+
+    // Each pole is a single vertex of the UV sphere.
+    let (vertices, _) = Geometry::Sphere { radius: 1.0, subdivisions: 16, tessellation: SphereTessellation::Uv }.build();
+    assert_eq!(vertices.iter().filter(|v| v.position[1].abs() > 1.0 - EPS).count(), 2);
+}
+
+#[test]
+fn sphere_uvs_wrap_without_crossing_the_seam() {
+    for tessellation in [SphereTessellation::Uv, SphereTessellation::Icosphere] {
+        let (vertices, indices) = Geometry::Sphere { radius: 1.0, subdivisions: 16, tessellation }.build();
+        for v in &vertices {
+            let expected_v = 0.5 - v.position[1].asin() / std::f32::consts::PI;
+            assert!((v.uv[1] - expected_v).abs() < EPS, "{tessellation:?}: {:?} {:?}", v.position, v.uv);
+        }
+        for tri in indices.chunks(3) {
+            // Pole vertices sit at u = 0.5 and are left out.
+            let us: Vec<f32> = tri.iter()
+                .map(|&i| vertices[i as usize])
+                .filter(|v| v.position[1].abs() < 1.0 - EPS)
+                .map(|v| v.uv[0])
+                .collect();
+            let span = us.iter().fold(0.0f32, |m, a| us.iter().fold(m, |m, b| m.max((a - b).abs())));
+            assert!(span < 0.5, "{tessellation:?}: triangle {tri:?} spans u {us:?}");
+        }
+    }
+}
+
+#[test]
+fn capsule_is_valid_across_subdivisions() {
+    for subdivisions in [3, 4, 8, 9, 16, 33, 64] {
+        for (radius, height) in [(0.5, 1.0), (1.0, 0.1), (0.2, 5.0)] {
+            assert_valid(Geometry::Capsule { radius, height, subdivisions });
+        }
+    }
+}
+
+// -- bounds -----------------------------------------------------------------
+
+#[test]
+fn shapes_match_documented_extents() {
+    assert_bounds(Geometry::Cube { size: 2.0 }, [-1.0; 3], [1.0; 3]);
+    assert_bounds(Geometry::Box { width: 2.0, height: 4.0, depth: 6.0, segments: [1; 3] }, [-1.0, -2.0, -3.0], [1.0, 2.0, 3.0]);
+    assert_bounds(Geometry::Plane { size: 4.0, subdivisions: 1 }, [-2.0, 0.0, -2.0], [2.0, 0.0, 2.0]);
+    assert_bounds(Geometry::Box { width: 2.0, height: 4.0, depth: 6.0, segments: [3, 2, 5] }, [-1.0, -2.0, -3.0], [1.0, 2.0, 3.0]);
+    assert_bounds(Geometry::Plane { size: 4.0, subdivisions: 6 }, [-2.0, 0.0, -2.0], [2.0, 0.0, 2.0]);
+    assert_bounds(
+        Geometry::Arrow { length: 2.0, shaft_radius: 0.1, head_size: 0.6 },
+        [-0.3, 0.0, -0.3],
+        [0.3, 2.0, 0.3],
+    );
+    assert_bounds(
+        Geometry::RoundedBox { width: 2.0, height: 4.0, depth: 6.0, radius: 0.5, subdivisions: 4 },
+        [-1.0, -2.0, -3.0],
+        [1.0, 2.0, 3.0],
+    );
+    assert_bounds(Geometry::Pyramid { base_size: 2.0, height: 3.0 }, [-1.0, -1.5, -1.0], [1.0, 1.5, 1.0]);
+    assert_bounds(Geometry::Sphere { radius: 2.0, subdivisions: 16, tessellation: SphereTessellation::Uv }, [-2.0; 3], [2.0; 3]);
+    assert_bounds(
+        Geometry::Capsule { radius: 1.0, height: 2.0, subdivisions: 16 },
+        [-1.0, -2.0, -1.0],
+        [1.0, 2.0, 1.0],
+    );
+}
+
+// -- count snapshots ----------------------------------------------------------
+
+#[test]
+fn vertex_and_index_count_snapshots() {
+    assert_eq!(counts(Geometry::Cube { size: 1.0 }), (24, 36));
+    assert_eq!(counts(Geometry::Box { width: 1.0, height: 2.0, depth: 3.0, segments: [1; 3] }), (24, 36));
+    assert_eq!(counts(Geometry::Plane { size: 1.0, subdivisions: 1 }), (8, 12));
+    assert_eq!(counts(Geometry::Plane { size: 1.0, subdivisions: 4 }), (2 * 25, 2 * 16 * 6));
+    // Front/back 3×2 cells, left/right 3×4, top/bottom 4×2.
+    assert_eq!(
+        counts(Geometry::Box { width: 1.0, height: 1.0, depth: 1.0, segments: [2, 3, 4] }),
+        (2 * (12 + 20 + 15), 2 * (6 + 12 + 8) * 6),
+    );
+    // 5 × 5 cells per face: two per half-bevel at each end, one flat.
+    assert_eq!(
+        counts(Geometry::RoundedBox { width: 1.0, height: 1.0, depth: 1.0, radius: 0.2, subdivisions: 2 }),
+        (6 * 25 * 4, 6 * 25 * 6),
+    );
+    assert_eq!(counts(Geometry::Arrow { length: 1.0, shaft_radius: 0.05, head_size: 0.2 }), (16 * 14, 16 * 18));
+    assert_eq!(counts(Geometry::Pyramid { base_size: 1.0, height: 1.0 }), (16, 18));
+    // Two poles and 7 rings of 17, the seam column repeated.
+    assert_eq!(counts(Geometry::Sphere { radius: 1.0, subdivisions: 16, tessellation: SphereTessellation::Uv }), (121, 672));
+    // Edges split in four: 10 · 4² + 2 points, plus copies along the seam.
+    let (vertices, indices) = counts(Geometry::Sphere { radius: 1.0, subdivisions: 16, tessellation: SphereTessellation::Icosphere });
+    assert_eq!(indices, 20 * 16 * 3);
+    assert!((162..200).contains(&vertices), "{vertices}");
+    assert_eq!(counts(Geometry::Capsule { radius: 1.0, height: 1.0, subdivisions: 16 }), (1056, 1536));
+}
+
+// -- per-face colours ---------------------------------------------------------
+
+/// Mean vertex position of each colour in `colors`, in order.
+fn face_centres(geometry: &Geometry, colors: &[[f32; 4]]) -> Vec<[f32; 3]> {
+    let (vertices, _) = geometry.build_with_face_colors(colors);
+    colors
+        .iter()
+        .map(|c| {
+            let face: Vec<_> = vertices.iter().filter(|v| v.color == [c[0], c[1], c[2]]).collect();
+            assert!(!face.is_empty(), "{geometry:?}: no vertices coloured {c:?}");
+            let n = face.len() as f32;
+            [0, 1, 2].map(|k| face.iter().map(|v| v.position[k]).sum::<f32>() / n)
+        })
+        .collect()
+}
+
+fn palette(n: usize) -> Vec<[f32; 4]> {
+    (0..n).map(|i| [i as f32 / n as f32, 0.5, 1.0, 1.0]).collect()
+}
+
+fn assert_close(a: [f32; 3], b: [f32; 3]) {
+    assert!((0..3).all(|k| (a[k] - b[k]).abs() < EPS), "{a:?} != {b:?}");
+}
+
+#[test]
+fn box_faces_follow_documented_order() {
+    let geometry = Geometry::Box { width: 2.0, height: 4.0, depth: 6.0, segments: [1; 3] };
+    assert_eq!(geometry.face_count(), 6);
+    let centres = face_centres(&geometry, &palette(6));
+    let expected = [
+        [0.0, 0.0, 3.0],  // front
+        [0.0, 0.0, -3.0], // back
+        [-1.0, 0.0, 0.0], // left
+        [1.0, 0.0, 0.0],  // right
+        [0.0, 2.0, 0.0],  // top
+        [0.0, -2.0, 0.0], // bottom
+    ];
+    for (centre, expected) in centres.into_iter().zip(expected) {
+        assert_close(centre, expected);
+    }
+    // Colouring does not change the shape.
+    let (colored, indices) = geometry.build_with_face_colors(&palette(6));
+    let (plain, plain_indices) = geometry.build();
+    assert_eq!(indices, plain_indices);
+    assert!(colored.iter().zip(&plain).all(|(a, b)| a.position == b.position));
+}
+
+#[test]
+fn pyramid_and_plane_faces_follow_documented_order() {
+    let pyramid = Geometry::Pyramid { base_size: 2.0, height: 2.0 };
+    assert_eq!(pyramid.face_count(), 5);
+    let centres = face_centres(&pyramid, &palette(5));
+    assert!(centres[0][2] > 0.0 && centres[1][0] > 0.0 && centres[2][2] < 0.0 && centres[3][0] < 0.0);
+    assert_close(centres[4], [0.0, -1.0, 0.0]);
+
+    let plane = Geometry::Plane { size: 1.0, subdivisions: 1 };
+    assert_eq!(plane.face_count(), 2);
+    let (vertices, _) = plane.build_with_face_colors(&palette(2));
+    assert!(vertices[..4].iter().all(|v| v.color[0] == 0.0));
+    assert!(vertices[4..].iter().all(|v| v.color[0] == 0.5));
+}
+
+#[test]
+fn face_colors_cycle_and_curved_shapes_take_the_first() {
+    let (vertices, _) = Geometry::Cube { size: 1.0 }.build_with_face_colors(&palette(2));
+    let first = vertices.iter().filter(|v| v.color[0] == 0.0).count();
+    assert_eq!(first, 12, "faces 0, 2 and 4 take the first colour");
+
+    let sphere = Geometry::Sphere { radius: 1.0, subdivisions: 8, tessellation: SphereTessellation::Uv };
+    assert_eq!(sphere.face_count(), 1);
+    let (vertices, _) = sphere.build_with_face_colors(&palette(3));
+    assert!(vertices.iter().all(|v| v.color == [0.0, 0.5, 1.0]));
+}
+
+fn subdivisions(geometry: &Geometry) -> usize {
+    match geometry {
+        Geometry::Sphere { subdivisions, .. } | Geometry::Capsule { subdivisions, .. } => *subdivisions,
+        _ => unreachable!(),
+    }
+}
+
+fn longest_edge(geometry: &Geometry) -> f32 {
+    let (vertices, indices) = geometry.build();
+    let length = |a: u32, b: u32| {
+        let [a, b] = [a, b].map(|i| vertices[i as usize].position);
+        ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+    };
+    indices.chunks(3).flat_map(|t| [length(t[0], t[1]), length(t[1], t[2]), length(t[2], t[0])]).fold(0.0, f32::max)
+}
+
+fn sphere(radius: f32, tessellation: SphereTessellation) -> Geometry {
+    Geometry::Sphere { radius, subdivisions: 0, tessellation }
+}
+
+#[test]
+fn edge_length_target_bounds_every_edge() {
+    for radius in [0.5, 2.0, 10.0] {
+        for geometry in [
+            sphere(radius, SphereTessellation::Uv),
+            sphere(radius, SphereTessellation::Icosphere),
+        ] {
+            let edge = radius * 0.3;
+            let adapted = geometry.with_accuracy(Accuracy::EdgeLength(edge));
+            let longest = longest_edge(&adapted);
+            assert!(longest <= edge * (1.0 + EPS), "{adapted:?}: {longest} > {edge}");
+            assert_valid(adapted);
+        }
+    }
+}
+
+#[test]
+fn bigger_shapes_get_more_subdivisions() {
+    for tessellation in [SphereTessellation::Uv, SphereTessellation::Icosphere] {
+        let count = |radius| subdivisions(&sphere(radius, tessellation).with_accuracy(Accuracy::EdgeLength(0.25)));
+        assert!(count(1.0) < count(4.0), "{tessellation:?}");
+        assert!(count(4.0) < count(16.0), "{tessellation:?}");
+    }
+    let capsule = Geometry::Capsule { radius: 3.0, height: 2.0, subdivisions: 0 }.with_accuracy(Accuracy::EdgeLength(0.25));
+    assert_eq!(subdivisions(&capsule), subdivisions(&sphere(3.0, SphereTessellation::Uv).with_accuracy(Accuracy::EdgeLength(0.25))));
+}
+
+#[test]
+fn max_error_bounds_edge_sagging() {
+    for (radius, error) in [(1.0, 0.01), (5.0, 0.002), (50.0, 0.05)] {
+        let adapted = sphere(radius, SphereTessellation::Uv).with_accuracy(Accuracy::MaxError(error));
+        let (vertices, indices) = adapted.build();
+        for t in indices.chunks(3) {
+            for (a, b) in [(t[0], t[1]), (t[1], t[2]), (t[2], t[0])] {
+                let [a, b] = [a, b].map(|i| vertices[i as usize].position);
+                let mid = [0, 1, 2].map(|k| (a[k] + b[k]) * 0.5);
+                let sag = radius - (mid[0] * mid[0] + mid[1] * mid[1] + mid[2] * mid[2]).sqrt();
+                assert!(sag <= error * 1.01 + EPS, "r {radius}: {sag} > {error}");
+            }
+        }
+    }
+}
+
+#[test]
+fn accuracy_is_clamped_and_ignores_flat_shapes() {
+    let fine = sphere(100.0, SphereTessellation::Uv).with_accuracy(Accuracy::MaxError(0.0));
+    assert_eq!(subdivisions(&fine), MAX_ADAPTIVE_SUBDIVISIONS);
+    let coarse = sphere(0.1, SphereTessellation::Icosphere).with_accuracy(Accuracy::EdgeLength(10.0));
+    assert_eq!(subdivisions(&coarse), MIN_ADAPTIVE_SUBDIVISIONS);
+    let cube = Geometry::Cube { size: 3.0 };
+    assert_eq!(cube.clone().with_accuracy(Accuracy::EdgeLength(0.1)), cube);
+}
@@ -0,0 +1,197 @@
+//! Unit tests for glTF scene export.
+//!
+//! These tests exercise:
+//! - Euler rotations convert to quaternions in `Transform::to_matrix` order
+//! - Nodes keep names, transforms, `str_id` extras and the hierarchy, with
+//!   translation and rotation mirrored into glTF's right-handed space
+//! - Exported triangles wind counter-clockwise seen from outside
+//! - Meshes and materials are shared between identical objects
+//! - GLB framing: header, 4-byte aligned JSON and BIN chunks
+//! - `.gltf` output embeds the buffer as a base64 data URI
+
+use serde_json::Value;
+use crate::geometry::Geometry;
+use crate::gltf::{euler_to_quaternion, export_glb, export_gltf};
+use crate::objects::Object;
+use crate::transform::Transform;
+use crate::world::World;
+
+/// Rotate `v` by the unit quaternion `q` (`[x, y, z, w]`).
+fn rotate(q: [f32; 4], v: [f32; 3]) -> [f32; 3] {
+    let [x, y, z, w] = q;
+    let u = [x, y, z];
+    let cross = |a: [f32; 3], b: [f32; 3]| [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]];
+    let t = cross(u, v).map(|c| 2.0 * c);
+    let ut = cross(u, t);
+    [0, 1, 2].map(|k| v[k] + w * t[k] + ut[k])
+}
+
+fn sample_world() -> (World, usize, usize) {
+    let mut world = World::new();
+    let cube = || Object::builder().geometry(Geometry::Cube { size: 1.0 });
+    let parent = world.spawn_object(
+        cube().name("base").str_id("base").at(1.0, 2.0, 3.0).rotated([0.0, 90.0, 0.0]).build(),
+        None,
+    );
+    let child = world.spawn_object(
+        cube().name("arm").color([1.0, 0.0, 0.0, 0.5]).scaled([2.0, 1.0, 1.0]).build(),
+        Some(parent),
+    );
+    world.spawn_object(cube().name("twin").at(-4.0, 0.0, 0.0).build(), None);
+    world.spawn_object(Object::builder().name("pivot").build(), None);
+    (world, parent, child)
+}
+
+fn gltf_json(world: &World) -> Value {
+    let mut out = Vec::new();
+    export_gltf(world, &mut out).unwrap();
+    serde_json::from_slice(&out).unwrap()
+}
+
+#[test]
+fn quaternion_matches_transform_matrix() {
+    let rotation = [30.0, -70.0, 145.0];
+    let q = euler_to_quaternion(rotation);
+    let transform = Transform { rotation, ..Transform::default() };
+    let points = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.3, -2.0, 5.0]];
+    for (point, expected) in points.iter().zip(transform.apply(points)) {
+        let got = rotate(q, *point);
+        for k in 0..3 {
+            assert!((got[k] - expected[k]).abs() < 1e-4, "{got:?} vs {expected:?}");
+        }
+    }
+    assert_eq!(euler_to_quaternion([0.0; 3]), [0.0, 0.0, 0.0, 1.0]);
+}
+
+#[test]
+fn nodes_keep_hierarchy_and_transforms() {
+    let (world, parent, child) = sample_world();
+    let doc = gltf_json(&world);
+    let nodes = doc["nodes"].as_array().unwrap();
+    assert_eq!(nodes.len(), 4);
+    assert_eq!(doc["scenes"][0]["nodes"], serde_json::json!([0, 2, 3]));
+
+    let base = &nodes[parent];
+    assert_eq!(base["name"], "base");
+    assert_eq!(base["extras"]["str_id"], "base");
+    assert_eq!(base["translation"], serde_json::json!([1.0, 2.0, -3.0]));
+    assert_eq!(base["children"], serde_json::json!([child]));
+    let half = std::f64::consts::FRAC_1_SQRT_2;
+    let rotation: Vec<f64> = base["rotation"].as_array().unwrap().iter().map(|v| v.as_f64().unwrap()).collect();
+    for (got, want) in rotation.iter().zip([0.0, -half, 0.0, half]) {
+        assert!((got - want).abs() < 1e-6);
+    }
+    assert_eq!(nodes[child]["scale"], serde_json::json!([2.0, 1.0, 1.0]));
+    assert!(nodes[3].get("mesh").is_none(), "objects without geometry have no mesh");
+}
+
+#[test]
+fn cube_winds_counter_clockwise_from_outside() {
+    let mut world = World::new();
+    world.spawn_object(Object::builder().geometry(Geometry::Cube { size: 1.0 }).build(), None);
+    let mut out = Vec::new();
+    export_glb(&world, &mut out).unwrap();
+    let u32_at = |i: usize| u32::from_le_bytes(out[i..i + 4].try_into().unwrap());
+    let json_len = u32_at(12) as usize;
+    let doc: Value = serde_json::from_slice(&out[20..20 + json_len]).unwrap();
+    let bin = 20 + json_len + 8;
+
+    // Start of the data behind an accessor, relative to the whole file.
+    let start = |accessor: &Value| {
+        let view = &doc["bufferViews"][accessor["bufferView"].as_u64().unwrap() as usize];
+        bin + view["byteOffset"].as_u64().unwrap() as usize
+    };
+    let primitive = &doc["meshes"][0]["primitives"][0];
+    let position = &doc["accessors"][primitive["attributes"]["POSITION"].as_u64().unwrap() as usize];
+    let indices = &doc["accessors"][primitive["indices"].as_u64().unwrap() as usize];
+    let vertex = |i: u32| {
+        let at = start(position) + 12 * i as usize;
+        [0, 1, 2].map(|k| f32::from_bits(u32_at(at + 4 * k)))
+    };
+
+    let count = indices["count"].as_u64().unwrap() as usize;
+    assert_eq!(count, 36);
+    // Faces are two triangles each; check the first of every face.
+    for triangle in (0..count / 3).step_by(2) {
+        let [a, b, c] = [0, 1, 2].map(|k| vertex(u32_at(start(indices) + 4 * (3 * triangle + k))));
+        let (u, v) = ([0, 1, 2].map(|k| b[k] - a[k]), [0, 1, 2].map(|k| c[k] - a[k]));
+        let normal = [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]];
+        // The cube is centred on the origin, so the centroid points outward.
+        let outward: f32 = (0..3).map(|k| normal[k] * (a[k] + b[k] + c[k])).sum();
+        assert!(outward > 0.0, "triangle {triangle} {a:?} {b:?} {c:?} winds clockwise from outside");
+    }
+}
+
+#[test]
+fn meshes_and_materials_are_shared() {
+    let (world, parent, child) = sample_world();
+    let doc = gltf_json(&world);
+    let nodes = doc["nodes"].as_array().unwrap();
+    assert_eq!(doc["meshes"].as_array().unwrap().len(), 2);
+    assert_eq!(nodes[parent]["mesh"], nodes[2]["mesh"]);
+    assert_ne!(nodes[parent]["mesh"], nodes[child]["mesh"]);
+
+    let materials = doc["materials"].as_array().unwrap();
+    assert_eq!(materials.len(), 2);
+    let mesh = &doc["meshes"][nodes[child]["mesh"].as_u64().unwrap() as usize];
+    let material = &materials[mesh["primitives"][0]["material"].as_u64().unwrap() as usize];
+    assert_eq!(material["pbrMetallicRoughness"]["baseColorFactor"], serde_json::json!([1.0, 0.0, 0.0, 0.5]));
+    assert_eq!(material["alphaMode"], "BLEND");
+
+    let position = &doc["accessors"][mesh["primitives"][0]["attributes"]["POSITION"].as_u64().unwrap() as usize];
+    assert_eq!(position["min"], serde_json::json!([-0.5, -0.5, -0.5]));
+    assert_eq!(position["max"], serde_json::json!([0.5, 0.5, 0.5]));
+}
+
+#[test]
+fn textures_become_images() {
+    let mut world = World::new();
+    world.spawn_object(Object::builder().geometry(Geometry::Cube { size: 1.0 }).texture("crate.png").build(), None);
+    let doc = gltf_json(&world);
+    assert_eq!(doc["images"][0]["uri"], "crate.png");
+    assert_eq!(doc["textures"][0]["source"], 0);
+    assert_eq!(doc["materials"][0]["pbrMetallicRoughness"]["baseColorTexture"]["index"], 0);
+}
+
+#[test]
+fn glb_chunks_are_aligned() {
+    let (world, ..) = sample_world();
+    let mut out = Vec::new();
+    export_glb(&world, &mut out).unwrap();
+    let u32_at = |i: usize| u32::from_le_bytes(out[i..i + 4].try_into().unwrap()) as usize;
+
+    assert_eq!(&out[0..4], b"glTF");
+    assert_eq!(u32_at(4), 2);
+    assert_eq!(u32_at(8), out.len());
+    let json_len = u32_at(12);
+    assert_eq!(&out[16..20], b"JSON");
+    assert_eq!(json_len % 4, 0);
+    let doc: Value = serde_json::from_slice(&out[20..20 + json_len]).unwrap();
+
+    let bin = 20 + json_len;
+    let bin_len = u32_at(bin);
+    assert_eq!(&out[bin + 4..bin + 8], b"BIN\0");
+    assert_eq!(bin_len % 4, 0);
+    assert_eq!(bin + 8 + bin_len, out.len());
+    assert!(doc["buffers"][0]["byteLength"].as_u64().unwrap() as usize <= bin_len);
+    assert!(doc["buffers"][0].get("uri").is_none());
+}
+
+#[test]
+fn gltf_embeds_base64_buffer() {
+    let (world, ..) = sample_world();
+    let doc = gltf_json(&world);
+    let buffer = &doc["buffers"][0];
+    let uri = buffer["uri"].as_str().unwrap();
+    let data = uri.strip_prefix("data:application/octet-stream;base64,").unwrap();
+    let len = buffer["byteLength"].as_u64().unwrap() as usize;
+    assert_eq!(data.len(), len.div_ceil(3) * 4);
+    assert_eq!(doc["asset"]["version"], "2.0");
+}
+
+#[test]
+fn empty_world_has_no_buffers() {
+    let doc = gltf_json(&World::new());
+    assert!(doc.get("buffers").is_none());
+    assert_eq!(doc["nodes"], serde_json::json!([]));
+}
@@ -0,0 +1,116 @@
+//! Golden-image rendering tests.
+//!
+//! Each test builds a small scene, renders it headlessly, and compares the
+//! frame with a reference PNG in `src/tests/golden/` (see `golden.rs` for the
+//! harness and how to update references).
+//!
+//! Coverage:
+//!   - the image comparison itself (tolerance, mismatch counting)
+//!   - an empty scene (clear colour only)
+//!   - a single coloured cube
+//!   - several primitives side by side
+//!   - a parent/child hierarchy with inherited rotation
+//!   - a mesh drawn through the registry (per-object model uniform)
+
+use super::golden::{assert_golden, compare, headless_scene, render};
+use crate::camera::Camera;
+use crate::geometry::{Geometry, SphereTessellation};
+use crate::mesh::MeshData;
+use crate::objects::Object;
+use crate::transform::Transform;
+
+const SIZE: u32 = 96;
+/// Per-channel slack for rasterizer differences between software adapters.
+const TOLERANCE: u8 = 8;
+/// Edge pixels allowed to differ beyond `TOLERANCE`.
+const MAX_MISMATCHED: usize = 48;
+
+fn camera() -> Camera {
+    Camera::new()
+        .with_aspect(1.0)
+        .with_position([0.0, 2.0, -5.0])
+        .with_rotation(90.0, -20.0)
+}
+
+fn object(name: &str, geometry: Geometry, position: [f32; 3], color: [f32; 4]) -> Object {
+    Object {
+        name: name.into(),
+        geometry: Some(geometry),
+        color,
+        transform: Transform { position, ..Default::default() },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn compare_identical_frames() {
+    let a = vec![10, 20, 30, 255, 40, 50, 60, 255];
+    let diff = compare(&a, &a, 0);
+    assert_eq!(diff.max_channel_delta, 0);
+    assert_eq!(diff.mismatched_pixels, 0);
+}
+
+#[test]
+fn compare_counts_pixels_beyond_tolerance() {
+    let a = vec![10, 20, 30, 255, 40, 50, 60, 255, 0, 0, 0, 255];
+    let b = vec![12, 20, 30, 255, 40, 90, 60, 255, 0, 0, 0, 250];
+    let diff = compare(&a, &b, 4);
+    assert_eq!(diff.max_channel_delta, 40);
+    assert_eq!(diff.mismatched_pixels, 2, "only the 40 and 5 deltas exceed 4");
+}
+
+#[test]
+fn golden_empty_scene() {
+    let Some(mut scene) = headless_scene(SIZE, SIZE, camera()) else { return };
+    let frame = render(&mut scene);
+    assert_golden("empty_scene", &frame, TOLERANCE, 0);
+}
+
+#[test]
+fn golden_single_cube() {
+    let Some(mut scene) = headless_scene(SIZE, SIZE, camera()) else { return };
+    scene.spawn(object("cube", Geometry::Cube { size: 1.5 }, [0.0; 3], [0.2, 0.6, 0.9, 1.0]), None);
+    let frame = render(&mut scene);
+    assert_golden("single_cube", &frame, TOLERANCE, MAX_MISMATCHED);
+}
+
+#[test]
+fn golden_primitives() {
+    let Some(mut scene) = headless_scene(SIZE, SIZE, camera()) else { return };
+    scene.spawn(object("box", Geometry::Box { width: 0.8, height: 1.2, depth: 0.8, segments: [1; 3] }, [-1.6, 0.0, 0.0], [0.9, 0.3, 0.2, 1.0]), None);
+    scene.spawn(object("sphere", Geometry::Sphere { radius: 0.6, subdivisions: 16, tessellation: SphereTessellation::Uv }, [0.0, 0.0, 0.0], [0.3, 0.9, 0.3, 1.0]), None);
+    scene.spawn(object("pyramid", Geometry::Pyramid { base_size: 1.0, height: 1.2 }, [1.6, -0.5, 0.0], [0.9, 0.8, 0.2, 1.0]), None);
+    scene.spawn(object("ground", Geometry::Plane { size: 6.0, subdivisions: 1 }, [0.0, -0.7, 0.0], [0.4, 0.4, 0.45, 1.0]), None);
+    let frame = render(&mut scene);
+    assert_golden("primitives", &frame, TOLERANCE, MAX_MISMATCHED * 2);
+}
+
+#[test]
+fn golden_hierarchy() {
+    let Some(mut scene) = headless_scene(SIZE, SIZE, camera()) else { return };
+    let mut parent = object("parent", Geometry::Cube { size: 1.0 }, [0.0; 3], [0.8, 0.2, 0.8, 1.0]);
+    parent.transform.rotation = [0.0, 45.0, 0.0];
+    let parent_id = scene.spawn(parent, None);
+    scene.spawn(object("child", Geometry::Cube { size: 0.5 }, [1.5, 0.0, 0.0], [0.2, 0.8, 0.8, 1.0]), Some(parent_id));
+    let frame = render(&mut scene);
+    assert_golden("hierarchy", &frame, TOLERANCE, MAX_MISMATCHED);
+}
+
+#[test]
+fn golden_registry_mesh() {
+    let Some(mut scene) = headless_scene(SIZE, SIZE, camera()) else { return };
+    let (vertices, indices) = Geometry::Pyramid { base_size: 1.5, height: 1.5 }.build();
+    let mesh = scene.register_mesh(&MeshData { vertices, indices, ..MeshData::new() });
+    scene.spawn(
+        Object {
+            name: "mesh".into(),
+            mesh: Some(mesh),
+            color: [1.0, 0.5, 0.1, 1.0],
+            transform: Transform { position: [0.0, -0.5, 0.0], ..Default::default() },
+            ..Default::default()
+        },
+        None,
+    );
+    let frame = render(&mut scene);
+    assert_golden("registry_mesh", &frame, TOLERANCE, MAX_MISMATCHED);
+}
@@ -0,0 +1,98 @@
+//! Tests for GPU memory reporting.
+//!
+//! Coverage:
+//!   - totals and sums over categories
+//!
+//! GPU tests (skipped without a software adapter):
+//!   - texture sizes count every mip level, layer and sample
+//!   - registry meshes and textures are counted until freed, and
+//!     `draw_world` reports the same totals as `Scene::gpu_memory`
+//!   - respawning dynamic objects every frame does not grow the total
+
+use crate::camera::Camera;
+use crate::geometry::Geometry;
+use crate::mesh::{MeshData, Vertex};
+use crate::objects::Object;
+use crate::pipeline::{self, GpuMemory};
+use crate::tests::golden;
+
+#[test]
+fn totals_sum_categories() {
+    let memory = GpuMemory {
+        vertex_bytes: 1,
+        index_bytes: 2,
+        uniform_bytes: 4,
+        storage_bytes: 8,
+        texture_bytes: 16,
+        render_target_bytes: 32,
+    };
+    assert_eq!(memory.total(), 63);
+    assert_eq!((memory + memory).total(), 126);
+    assert_eq!(GpuMemory::default().total(), 0);
+}
+
+#[test]
+fn texture_bytes_count_mips_layers_and_samples() {
+    let Some(scene) = golden::headless_scene(8, 8, Camera::new()) else { return };
+    let device = &scene.pipeline.device;
+    let texture = |layers, mips, samples, format| {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d { width: 8, height: 8, depth_or_array_layers: layers },
+            mip_level_count: mips,
+            sample_count: samples,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        })
+    };
+    let rgba = wgpu::TextureFormat::Rgba8Unorm;
+    assert_eq!(pipeline::texture_bytes(&texture(1, 1, 1, rgba)), 256);
+    assert_eq!(pipeline::texture_bytes(&texture(1, 4, 1, rgba)), (64 + 16 + 4 + 1) * 4);
+    assert_eq!(pipeline::texture_bytes(&texture(6, 1, 1, rgba)), 6 * 256);
+    assert_eq!(pipeline::texture_bytes(&texture(1, 1, 4, rgba)), 4 * 256);
+    assert_eq!(pipeline::texture_bytes(&texture(1, 1, 1, wgpu::TextureFormat::Depth32Float)), 256);
+}
+
+#[test]
+fn registered_resources_are_counted_until_freed() {
+    let Some(mut scene) = golden::headless_scene(16, 16, Camera::new()) else { return };
+    let empty = scene.gpu_memory();
+    assert_eq!((empty.vertex_bytes, empty.index_bytes, empty.texture_bytes), (0, 0, 0));
+    assert!(empty.uniform_bytes > 0);
+    assert!(empty.render_target_bytes >= 16 * 16 * 8, "colour and depth targets");
+
+    let (vertices, indices) = Geometry::Cube { size: 1.0 }.build();
+    let mesh = MeshData { vertices, indices, ..MeshData::new() };
+    let id = scene.register_mesh(&mesh);
+    scene.load_texture_from_rgba("checker", 4, 4, &[255; 64]);
+    let loaded = scene.gpu_memory();
+    assert!(loaded.vertex_bytes >= (mesh.vertices.len() * size_of::<Vertex>()) as u64);
+    assert!(loaded.index_bytes >= (mesh.indices.len() * size_of::<u32>()) as u64);
+    assert_eq!(loaded.texture_bytes, 64);
+    assert_eq!(loaded.uniform_bytes, empty.uniform_bytes);
+
+    scene.spawn(Object { mesh: Some(id), ..Default::default() }, None);
+    let stats = scene.draw_world().unwrap();
+    assert_eq!(stats.memory, scene.gpu_memory());
+
+    scene.world = crate::world::World::new();
+    scene.unload_texture("checker");
+    assert_eq!(scene.collect_mesh_garbage(), 1);
+    let freed = scene.gpu_memory();
+    assert_eq!(freed.vertex_bytes + freed.index_bytes + freed.texture_bytes, 0);
+}
+
+#[test]
+fn dynamic_geometry_does_not_accumulate() {
+    let Some(mut scene) = golden::headless_scene(16, 16, Camera::new()) else { return };
+    let cube = Object { geometry: Some(Geometry::Cube { size: 1.0 }), ..Default::default() };
+    let mut totals = Vec::new();
+    for _ in 0..3 {
+        scene.world = crate::world::World::new();
+        scene.spawn(cube.clone(), None);
+        totals.push(scene.draw_world().unwrap().memory.total());
+    }
+    assert!(totals.windows(2).all(|pair| pair[0] == pair[1]), "got {totals:?}");
+}
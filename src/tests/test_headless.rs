@@ -0,0 +1,141 @@
+//! Tests for adapter selection and the headless (software-rasterizer) path.
+//!
+//! Coverage:
+//!   - `VERTRA_BACKEND` / `VERTRA_FORCE_FALLBACK_ADAPTER` / `VERTRA_SIZE` value
+//!     parsing
+//!   - `PipelineConfig` defaults and builders
+//!   - a full frame rendered through `Pipeline::headless` on a fallback adapter
+//!   - `RenderStats` upload / allocation counters across frames
+//!
+//! The rendering tests need a software adapter (lavapipe or llvmpipe).  When
+//! none is installed they print a note and return instead of failing, so the
+//! suite still passes on minimal machines.
+
+use crate::camera::Camera;
+use crate::geometry::Geometry;
+use crate::mesh::MeshUsage;
+use crate::objects::Object;
+use crate::pipeline::{parse_backends, parse_flag, PipelineConfig, HEADLESS_FORMAT};
+use crate::scene::Scene;
+use crate::tests::golden;
+use crate::window::parse_size;
+
+#[test]
+fn parse_backends_accepts_comma_lists() {
+    assert_eq!(parse_backends("gl"), Some(wgpu::Backends::GL));
+    assert_eq!(
+        parse_backends("vulkan, gl"),
+        Some(wgpu::Backends::VULKAN | wgpu::Backends::GL)
+    );
+}
+
+#[test]
+fn parse_backends_rejects_empty_and_unknown() {
+    assert_eq!(parse_backends(""), None);
+    assert_eq!(parse_backends("   "), None);
+    assert_eq!(parse_backends("glide"), None);
+}
+
+#[test]
+fn parse_flag_values() {
+    for v in ["1", "true", "YES", " on "] {
+        assert_eq!(parse_flag(v), Some(true), "{v:?}");
+    }
+    for v in ["0", "false", "No", "off"] {
+        assert_eq!(parse_flag(v), Some(false), "{v:?}");
+    }
+    assert_eq!(parse_flag("maybe"), None);
+}
+
+#[test]
+fn parse_size_values() {
+    assert_eq!(parse_size("1920x1080"), Some((1920, 1080)));
+    assert_eq!(parse_size(" 640 X 480 "), Some((640, 480)));
+    for v in ["", "1920", "1920x", "0x600", "800x-1", "wide x tall"] {
+        assert_eq!(parse_size(v), None, "{v:?}");
+    }
+}
+
+#[test]
+fn config_defaults_and_builders() {
+    let config = PipelineConfig::default();
+    assert_eq!(config.backends, wgpu::Backends::all());
+    assert!(!config.force_fallback_adapter);
+    assert!(config.vsync);
+
+    let config = config
+        .with_backends(wgpu::Backends::GL)
+        .with_force_fallback_adapter(true)
+        .with_power_preference(wgpu::PowerPreference::LowPower)
+        .with_vsync(false);
+    assert_eq!(config.backends, wgpu::Backends::GL);
+    assert!(config.force_fallback_adapter);
+    assert_eq!(config.power_preference, wgpu::PowerPreference::LowPower);
+    assert!(!config.vsync);
+}
+
+#[test]
+fn headless_pipeline_has_offscreen_target() {
+    let Some(pipeline) = golden::headless_pipeline(64, 32) else { return };
+    assert!(pipeline.surface.is_none());
+    let target = pipeline.offscreen_texture().expect("headless pipeline must own a target");
+    assert_eq!((target.width(), target.height()), (64, 32));
+    assert_eq!(target.format(), HEADLESS_FORMAT);
+}
+
+#[test]
+fn headless_resize_recreates_target() {
+    let Some(mut pipeline) = golden::headless_pipeline(64, 32) else { return };
+    pipeline.resize(winit::dpi::PhysicalSize::new(48, 80));
+    let target = pipeline.offscreen_texture().unwrap();
+    assert_eq!((target.width(), target.height()), (48, 80));
+}
+
+#[test]
+fn headless_scene_renders_frames() {
+    let Some(pipeline) = golden::headless_pipeline(64, 64) else { return };
+    let mut scene = Scene::new(pipeline, Camera::new().with_position([0.0, 0.0, -5.0]));
+    scene.spawn(
+        Object {
+            name: "Cube".into(),
+            geometry: Some(Geometry::Cube { size: 1.0 }),
+            ..Default::default()
+        },
+        None,
+    );
+    let stats = scene.draw_world().unwrap();
+    assert!(stats.draw_calls > 0, "a frame must be issued without a surface");
+    assert!(stats.triangle_count >= 12);
+}
+
+#[test]
+fn render_stats_count_instances_and_uploads() {
+    let Some(pipeline) = golden::headless_pipeline(32, 32) else { return };
+    let mut scene = Scene::new(pipeline, Camera::new().with_position([0.0, 0.0, -5.0]));
+    scene.spawn(
+        Object { geometry: Some(Geometry::Cube { size: 1.0 }), ..Default::default() },
+        None,
+    );
+    let stats = scene.draw_world().unwrap();
+    assert_eq!(stats.instance_count, stats.draw_calls);
+    assert_eq!(stats.triangle_count, 12);
+    assert!(stats.bytes_uploaded > 0);
+    assert_eq!(stats.buffer_reallocations, 2, "one dynamic batch: vertex + index buffer");
+}
+
+#[test]
+fn static_batches_stop_reallocating_after_first_frame() {
+    let Some(pipeline) = golden::headless_pipeline(32, 32) else { return };
+    let mut scene = Scene::new(pipeline, Camera::new().with_position([0.0, 0.0, -5.0]));
+    scene.spawn_with_usage(
+        Object { geometry: Some(Geometry::Cube { size: 1.0 }), ..Default::default() },
+        None,
+        MeshUsage::Static,
+    );
+    let first = scene.draw_world().unwrap();
+    let second = scene.draw_world().unwrap();
+    assert!(first.buffer_reallocations > 0);
+    assert_eq!(second.buffer_reallocations, 0);
+    assert!(second.bytes_uploaded < first.bytes_uploaded, "only uniforms are rewritten");
+    assert_eq!(second.triangle_count, 12);
+}
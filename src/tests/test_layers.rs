@@ -0,0 +1,66 @@
+//! Tests for render layers and camera layer masks.
+//!
+//! Coverage:
+//!   - defaults: objects on `layers::DEFAULT`, cameras see `layers::ALL`
+//!   - `Camera::sees_layer` / `with_layers_mask`
+//!   - static batches are rebuilt when the camera mask changes
+//!   - masked-out objects (dynamic and static) are not drawn, while their
+//!     children still are (skipped without a software adapter)
+
+use crate::camera::Camera;
+use crate::constants::layers;
+use crate::geometry::Geometry;
+use crate::mesh::{MeshUsage, StaticBatches};
+use crate::objects::Object;
+use crate::tests::golden;
+
+fn cube(layer: u32) -> Object {
+    Object { geometry: Some(Geometry::Cube { size: 1.0 }), layer, ..Default::default() }
+}
+
+#[test]
+fn defaults_render_everything() {
+    assert_eq!(Object::default().layer, layers::DEFAULT);
+    let camera = Camera::new();
+    assert_eq!(camera.layers_mask, layers::ALL);
+    assert!(camera.sees_layer(layers::DEFAULT));
+    assert!(camera.sees_layer(layers::GIZMO));
+}
+
+#[test]
+fn mask_filters_layers() {
+    let camera = Camera::new().with_layers_mask(layers::DEFAULT | layers::UI);
+    assert!(camera.sees_layer(layers::UI));
+    assert!(camera.sees_layer(layers::UI | layers::GIZMO), "any shared bit is enough");
+    assert!(!camera.sees_layer(layers::GIZMO));
+    assert!(!camera.sees_layer(0));
+}
+
+#[test]
+fn static_batches_dirty_on_mask_change() {
+    let mut batches: StaticBatches<u32> = StaticBatches::new();
+    batches.set_batches(Vec::new());
+    batches.set_layers_mask(layers::ALL);
+    assert!(!batches.is_dirty(), "unchanged mask is a no-op");
+    batches.set_layers_mask(layers::DEFAULT);
+    assert!(batches.is_dirty());
+    assert_eq!(batches.layers_mask(), layers::DEFAULT);
+}
+
+#[test]
+fn masked_objects_are_not_drawn() {
+    let camera = Camera::new().with_position([0.0, 0.0, -5.0]);
+    let Some(mut scene) = golden::headless_scene(32, 32, camera) else { return };
+    let parent = scene.spawn(cube(layers::UI), None);
+    scene.spawn(cube(layers::DEFAULT), Some(parent));
+    scene.spawn_with_usage(cube(layers::UI), None, MeshUsage::Static);
+
+    let all = scene.draw_world().unwrap().triangle_count;
+    assert_eq!(all, 36);
+
+    scene.camera.layers_mask = layers::DEFAULT;
+    assert_eq!(scene.draw_world().unwrap().triangle_count, 12, "only the child cube stays visible");
+
+    scene.camera.layers_mask = layers::UI;
+    assert_eq!(scene.draw_world().unwrap().triangle_count, 24);
+}
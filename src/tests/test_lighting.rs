@@ -0,0 +1,85 @@
+//! Tests for scene lighting (flat and hemispheric ambient).
+//!
+//! Coverage:
+//!   - the default ambient is flat white and leaves colours unchanged
+//!   - a flat ambient scales every lit surface, static and registry meshes
+//!   - a hemisphere lights upward faces with the sky colour and downward
+//!     faces with the ground colour
+//!
+//! GPU tests are skipped without a software adapter.
+
+use crate::camera::Camera;
+use crate::geometry::Geometry;
+use crate::lighting::{AmbientLight, Lighting};
+use crate::mesh::{MeshData, MeshUsage};
+use crate::objects::Object;
+use crate::tests::golden::{self, Frame};
+
+fn cube() -> Object {
+    Object { geometry: Some(Geometry::Cube { size: 1.0 }), ..Default::default() }
+}
+
+fn pixels(frame: &Frame) -> impl Iterator<Item = &[u8]> {
+    frame.pixels.chunks(4)
+}
+
+#[test]
+fn default_ambient_is_flat_white() {
+    let lighting = Lighting::default();
+    assert_eq!(lighting.ambient, AmbientLight::Flat([1.0; 3]));
+    assert_eq!(lighting.ambient.sky_and_ground(), ([1.0; 3], [1.0; 3]));
+
+    let hemisphere = AmbientLight::Hemisphere { sky: [0.0, 0.0, 1.0], ground: [1.0, 0.0, 0.0] };
+    assert_eq!(Lighting::default().with_ambient(hemisphere).ambient.sky_and_ground().1, [1.0, 0.0, 0.0]);
+}
+
+#[test]
+fn flat_ambient_scales_lit_surfaces() {
+    let camera = Camera::new().with_position([0.0, 0.0, -3.0]);
+    let Some(mut scene) = golden::headless_scene(32, 32, camera) else { return };
+    let id = scene.spawn(Object { geometry: Some(Geometry::Cube { size: 4.0 }), ..Default::default() }, None);
+    let full = golden::render(&mut scene);
+    assert!(pixels(&full).all(|p| p[..3] == [255, 255, 255]));
+
+    scene.lighting.ambient = AmbientLight::Flat([0.0, 0.5, 1.0]);
+    let frame = golden::render(&mut scene);
+    assert!(pixels(&frame).all(|p| p[0] == 0 && (180..=195).contains(&p[1]) && p[2] == 255));
+
+    // Static batches and registry meshes are lit the same way.
+    scene.world.objects.get_mut(&id).unwrap().geometry = None;
+    let mut wall = Object { geometry: Some(Geometry::Cube { size: 4.0 }), ..Default::default() };
+    scene.spawn_with_usage(wall.clone(), None, MeshUsage::Static);
+    assert_eq!(golden::render(&mut scene).pixels, frame.pixels);
+
+    scene.world = crate::world::World::new();
+    scene.invalidate_static_batches();
+    let (vertices, indices) = Geometry::Cube { size: 4.0 }.build();
+    wall.mesh = Some(scene.register_mesh(&MeshData { vertices, indices, ..MeshData::new() }));
+    wall.geometry = None;
+    scene.spawn(wall, None);
+    assert_eq!(golden::render(&mut scene).pixels, frame.pixels);
+}
+
+#[test]
+fn hemisphere_lights_faces_by_orientation() {
+    let sky_and_ground = AmbientLight::Hemisphere { sky: [0.0, 0.0, 1.0], ground: [1.0, 0.0, 0.0] };
+    let blue = |p: &[u8]| p[2] > 200 && p[0] < 30;
+    let red = |p: &[u8]| p[0] > 200 && p[2] < 30;
+
+    // From above, the top face shows the sky colour.
+    let camera = Camera::new().with_position([0.0, 3.0, -3.0]);
+    let Some(mut scene) = golden::headless_scene(48, 48, camera) else { return };
+    scene.spawn(cube(), None);
+    scene.lighting.ambient = sky_and_ground;
+    let above = golden::render(&mut scene);
+    assert!(pixels(&above).filter(|p| blue(p)).count() > 50);
+    assert!(!pixels(&above).any(red));
+    // Side faces sit halfway between.
+    assert!(pixels(&above).any(|p| p[0] > 100 && p[0].abs_diff(p[2]) < 8));
+
+    // From below, the bottom face shows the ground colour.
+    scene.camera = Camera::new().with_position([0.0, -3.0, -3.0]).with_aspect(1.0);
+    let below = golden::render(&mut scene);
+    assert!(pixels(&below).filter(|p| red(p)).count() > 50);
+    assert!(!pixels(&below).any(blue));
+}
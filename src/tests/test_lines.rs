@@ -0,0 +1,111 @@
+//! Unit tests for thick lines and the ground grid.
+//!
+//! These tests exercise:
+//! - Polylines flag the neighbours their joints are mitered with, closed
+//!   ones around the loop, and lines on top are kept apart
+//! - The grid snaps to whole major cells and colours every major line
+//! - Lines are drawn the requested number of pixels wide, miters fill the
+//!   outer corner that bevels cut off, and only lines drawn on top show
+//!   through geometry
+
+use crate::camera::Camera;
+use crate::geometry::Geometry;
+use crate::lines::{Grid, LineJoin, LineStyle, Lines};
+use crate::objects::Object;
+use crate::tests::golden;
+
+const RED: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
+
+#[test]
+fn polylines_link_neighbours() {
+    let points = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0]];
+    let mut lines = Lines::new();
+    lines.push_polyline(&points, RED, LineStyle::default());
+    assert_eq!(lines.len(), 2);
+    let [first, second] = [lines.tested[0], lines.tested[1]];
+    assert_eq!((first.prev[3], first.next[3]), (0.0, 1.0));
+    assert_eq!((second.prev[3], second.next[3]), (1.0, 0.0));
+    assert_eq!(second.prev[..3], points[0]);
+    assert_eq!(first.start[3], LineStyle::default().width);
+
+    let mut closed = Lines::new();
+    closed.push_closed_polyline(&points, RED, LineStyle::default().with_join(LineJoin::Bevel).with_depth_test(false));
+    assert!(closed.tested.is_empty());
+    assert_eq!(closed.on_top.len(), 3);
+    assert!(closed.on_top.iter().all(|s| s.prev[3] == 1.0 && s.next[3] == 1.0 && s.end[3] == 1.0));
+    assert_eq!(closed.on_top[0].prev[..3], points[2], "the first joint closes the loop");
+    assert_eq!(closed.on_top[2].end[..3], points[0]);
+
+    lines.push_polyline(&points[..1], RED, LineStyle::default());
+    lines.append(&mut closed);
+    assert_eq!(lines.len(), 5);
+    assert!(closed.is_empty());
+    lines.clear();
+    assert!(lines.is_empty());
+}
+
+#[test]
+fn grid_snaps_to_major_cells() {
+    let grid = Grid::default().with_spacing(1.0, 10).with_major_every(5).with_height(-1.0);
+    let lines = grid.lines([13.0, 4.0, -7.0]);
+    assert_eq!(lines.len(), 2 * 21);
+    // Centred on (15, -5), reaching 10 units either way.
+    let along_z = lines.tested.iter().step_by(2);
+    let xs: Vec<f32> = along_z.clone().map(|s| s.start[0]).collect();
+    assert_eq!((xs[0], xs[20]), (5.0, 25.0));
+    assert!(along_z.clone().all(|s| s.start[1] == -1.0 && s.start[2] == -15.0 && s.end[2] == 5.0));
+    for segment in along_z {
+        let major = segment.start[0].rem_euclid(5.0) == 0.0;
+        assert_eq!(segment.color, if major { grid.major_color } else { grid.color }, "{}", segment.start[0]);
+    }
+    assert!(Grid::default().with_spacing(0.0, 10).lines([0.0; 3]).is_empty());
+}
+
+/// An orthographic camera showing 1 world unit per pixel.
+fn pixel_camera() -> Camera {
+    Camera { eye: [0.0, 0.0, -30.0], target: [0.0, 0.0, 0.0], aspect: 1.0, ortho_height: Some(64.0), ..Camera::new() }
+}
+
+fn red_pixels(frame: &golden::Frame) -> usize {
+    frame.pixels.chunks(4).filter(|p| p[0] > 200 && p[1] < 60 && p[2] < 60).count()
+}
+
+#[test]
+fn lines_have_pixel_width_and_joins() {
+    let Some(mut scene) = golden::headless_scene(64, 64, pixel_camera()) else { return };
+    // 40 pixels long and 6 wide.
+    scene.lines.push_segment([-20.0, 0.0, 0.0], [20.0, 0.0, 0.0], RED, LineStyle::default().with_width(6.0));
+    let straight = red_pixels(&golden::render(&mut scene));
+    assert!((200..=280).contains(&straight), "{straight}");
+
+    // An L turning at a right angle: the miter fills the outer square
+    // corner of 3 × 3 pixels the bevel halves.
+    let corner = [[-20.0, 0.0, 0.0], [0.0, 0.0, 0.0], [0.0, 20.0, 0.0]];
+    let mut count = |join| {
+        scene.lines.clear();
+        scene.lines.push_polyline(&corner, RED, LineStyle::default().with_width(6.0).with_join(join));
+        red_pixels(&golden::render(&mut scene))
+    };
+    let (miter, bevel) = (count(LineJoin::Miter), count(LineJoin::Bevel));
+    assert!(miter > bevel && miter - bevel <= 9, "{miter} {bevel}");
+    scene.lines.clear();
+    assert_eq!(red_pixels(&golden::render(&mut scene)), 0);
+}
+
+#[test]
+fn depth_testing_hides_lines_behind_geometry() {
+    let Some(mut scene) = golden::headless_scene(64, 64, pixel_camera()) else { return };
+    scene.spawn(Object {
+        geometry: Some(Geometry::Cube { size: 20.0 }),
+        color: [0.1, 0.1, 1.0, 1.0],
+        ..Default::default()
+    }, None);
+    let style = LineStyle::default().with_width(4.0);
+    scene.lines.push_segment([-30.0, 0.0, 15.0], [30.0, 0.0, 15.0], RED, style);
+    let tested = red_pixels(&golden::render(&mut scene));
+    scene.lines.clear();
+    scene.lines.push_segment([-30.0, 0.0, 15.0], [30.0, 0.0, 15.0], RED, style.with_depth_test(false));
+    let on_top = red_pixels(&golden::render(&mut scene));
+    // The cube covers 20 of the 60 pixels.
+    assert!((140..=180).contains(&tested) && on_top >= tested + 60, "{tested} {on_top}");
+}
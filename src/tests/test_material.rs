@@ -0,0 +1,210 @@
+//! Unit tests for per-object materials.
+//!
+//! These tests exercise:
+//! - Materials set culling, depth bias and read-only depth on a key, and
+//!   leave keys that ignore depth alone
+//! - Depth biases compare by value and make distinct pipeline keys
+//! - Objects sharing a texture but not a material get separate groups
+//! - Front-face culling shows the inside of a box around the camera, and
+//!   a material without depth writes hides nothing drawn after it
+//! - Emission compares by bits and lights black objects in batches,
+//!   registry mesh draws and instanced draws alike
+//! - Displacement compares by bits, and moves vertices with `Scene::time`
+//!   exactly like the equivalent transform on every draw path
+//! - The displacement phase sent to the GPU stays within one period however
+//!   large the time, and `reach` is the amplitude's length
+
+use crate::camera::Camera;
+use crate::geometry::Geometry;
+use crate::material::{CullMode, Displacement, Emission, Material};
+use crate::mesh::MeshData;
+use crate::objects::Object;
+use crate::pipeline_cache::{DebugView, DepthBias, DepthMode, PipelineKey};
+use crate::tests::golden;
+use crate::transform::Transform;
+
+const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+#[test]
+fn materials_adjust_pipeline_keys() {
+    let scene = PipelineKey::scene(FORMAT);
+    assert_eq!(Material::default().apply(scene), scene);
+    assert_eq!(Material::OPAQUE, Material::default());
+
+    let bias = DepthBias { constant: -4, slope_scale: -1.0 };
+    let decal = Material::default().with_cull_mode(CullMode::None).with_depth_write(false).with_depth_bias(bias);
+    let key = decal.apply(scene);
+    assert_eq!(key.cull_mode, None);
+    assert_eq!(key.depth, DepthMode::TestReadOnly);
+    assert_eq!(key.depth_bias, bias);
+    assert_eq!(Material::default().with_cull_mode(CullMode::Front).apply(scene).cull_mode, Some(wgpu::Face::Front));
+
+    // Overdraw ignores depth, so there is nothing to make read-only.
+    let overdraw = DebugView::Overdraw.apply(scene);
+    assert_eq!(decal.apply(overdraw).depth, DepthMode::Always);
+}
+
+#[test]
+fn depth_bias_keys_by_value() {
+    let a = DepthBias { constant: 1, slope_scale: 0.5 };
+    assert_eq!(a, DepthBias { constant: 1, slope_scale: 0.5 });
+    assert_ne!(a, DepthBias { constant: 1, slope_scale: 0.25 });
+    assert_eq!(DepthBias::default(), DepthBias::NONE);
+    let scene = PipelineKey::scene(FORMAT);
+    let keys: std::collections::HashSet<_> = [scene, scene.with_depth_bias(a), scene.with_depth_bias(a)].into();
+    assert_eq!(keys.len(), 2);
+}
+
+#[test]
+fn materials_split_draw_groups() {
+    let Some(mut scene) = golden::headless_scene(8, 8, Camera::new()) else { return };
+    let cube = |material| Object {
+        geometry: Some(Geometry::Cube { size: 1.0 }),
+        material,
+        ..Default::default()
+    };
+    scene.spawn(cube(Material::default()), None);
+    scene.spawn(cube(Material::default().with_cull_mode(CullMode::None)), None);
+    scene.spawn(cube(Material::default()), None);
+
+    let list = scene.prepare_draw_list(false);
+    assert_eq!(list.groups.len(), 2);
+    assert_eq!(list.groups[0].0.material, Material::default());
+    assert_eq!(list.groups[1].0.material.cull_mode, CullMode::None);
+    assert_eq!(list.groups[0].1.vertices.len(), 2 * list.groups[1].1.vertices.len());
+}
+
+#[test]
+fn cull_mode_and_depth_write_render() {
+    let camera = Camera { eye: [0.0, 0.0, -5.0], target: [0.0, 0.0, 0.0], ..Camera::new() };
+    let Some(mut scene) = golden::headless_scene(32, 32, camera) else { return };
+    let center = |frame: &golden::Frame| {
+        let at = (16 * 32 + 16) * 4;
+        [0, 1, 2].map(|k| frame.pixels[at + k])
+    };
+    let clear = center(&golden::render(&mut scene));
+
+    // A room around the camera: its walls face outwards, away from the eye.
+    let room = scene.spawn(Object {
+        geometry: Some(Geometry::Cube { size: 40.0 }),
+        color: [1.0, 0.1, 0.1, 1.0],
+        ..Default::default()
+    }, None);
+    assert_eq!(center(&golden::render(&mut scene)), clear);
+    scene.world.objects.get_mut(&room).unwrap().material.cull_mode = CullMode::Front;
+    let [r, g, b] = center(&golden::render(&mut scene)).map(u32::from);
+    assert!(r > 40 && r > g * 2 && r > b * 2, "{r} {g} {b}");
+
+    // A green cube in front of a blue one: only hides it while it writes
+    // depth.  The blue cube is spawned last, so it is drawn after.
+    scene.world.delete(room);
+    let cube = |color, z, material| Object {
+        geometry: Some(Geometry::Cube { size: 1.0 }),
+        color,
+        material,
+        transform: Transform::from_position(0.0, 0.0, z),
+        ..Default::default()
+    };
+    let front = scene.spawn(cube([0.1, 1.0, 0.1, 1.0], -1.0, Material::default().with_depth_write(false)), None);
+    scene.spawn(cube([0.1, 0.1, 1.0, 1.0], 1.0, Material::default()), None);
+    let [r, g, b] = center(&golden::render(&mut scene)).map(u32::from);
+    assert!(b > 40 && b > r * 2 && b > g * 2, "{r} {g} {b}");
+    scene.world.objects.get_mut(&front).unwrap().material.depth_write = true;
+    let [r, g, b] = center(&golden::render(&mut scene)).map(u32::from);
+    assert!(g > 40 && g > r * 2 && g > b * 2, "{r} {g} {b}");
+}
+
+#[test]
+fn emission_keys_by_value() {
+    let glow = Material::default().with_emission([1.0, 0.5, 0.0], 2.0);
+    assert_eq!(glow.emission.radiance(), [2.0, 1.0, 0.0]);
+    assert_eq!(glow, Material::default().with_emission([1.0, 0.5, 0.0], 2.0));
+    assert_ne!(glow, Material::default());
+    assert!(Material::default().emission.is_none());
+    assert!(Emission::new([1.0; 3], 0.0).is_none());
+    assert!(!glow.emission.is_none());
+    // Emission changes no pipeline state.
+    let scene = PipelineKey::scene(FORMAT);
+    assert_eq!(glow.apply(scene), scene);
+}
+
+#[test]
+fn emission_lights_every_draw_path() {
+    let camera = Camera { eye: [0.0, 0.0, -5.0], target: [0.0, 0.0, 0.0], ..Camera::new() };
+    let Some(mut scene) = golden::headless_scene(32, 32, camera) else { return };
+    let center = |frame: &golden::Frame| {
+        let at = (16 * 32 + 16) * 4;
+        [0, 1, 2].map(|k| frame.pixels[at + k])
+    };
+    let (vertices, indices) = Geometry::Cube { size: 1.0 }.build();
+    let mesh = scene.register_mesh(&MeshData { vertices, indices, ..MeshData::new() });
+    let glow = Material::default().with_emission([1.0, 0.0, 0.0], 1.0);
+    let batched = Object { geometry: Some(Geometry::Cube { size: 1.0 }), color: [0.0, 0.0, 0.0, 1.0], ..Default::default() };
+    let registry = Object { geometry: None, mesh: Some(mesh), ..batched.clone() };
+
+    for (object, instanced) in [(batched, false), (registry.clone(), false), (registry, true)] {
+        if instanced && !scene.pipeline.supports_instancing() {
+            continue;
+        }
+        scene.instanced_rendering = instanced;
+        let id = scene.spawn(object.clone(), None);
+        assert_eq!(center(&golden::render(&mut scene)), [0, 0, 0], "{instanced}");
+        scene.world.objects.get_mut(&id).unwrap().material = glow;
+        assert_eq!(center(&golden::render(&mut scene)), [255, 0, 0], "{instanced}");
+        scene.world.delete(id);
+    }
+}
+
+#[test]
+fn displacement_keys_by_value() {
+    let sway = Material::default().with_displacement([0.1, 0.0, 0.0], [0.0, 0.0, 1.0], 2.0);
+    assert_eq!(sway, Material::default().with_displacement([0.1, 0.0, 0.0], [0.0, 0.0, 1.0], 2.0));
+    assert_ne!(sway, Material::default().with_displacement([0.1, 0.0, 0.0], [0.0, 0.0, 1.0], 3.0));
+    assert!(Material::default().displacement.is_none());
+    assert!(Displacement::new([0.0; 3], [1.0; 3], 1.0).is_none());
+    assert!(!sway.displacement.is_none());
+    let scene = PipelineKey::scene(FORMAT);
+    assert_eq!(sway.apply(scene), scene);
+}
+
+#[test]
+fn displacement_follows_scene_time_on_every_draw_path() {
+    let camera = Camera { eye: [0.0, 0.0, -5.0], target: [0.0, 0.0, 0.0], ..Camera::new() };
+    let Some(mut scene) = golden::headless_scene(32, 32, camera) else { return };
+    let (vertices, indices) = Geometry::Cube { size: 1.0 }.build();
+    let mesh = scene.register_mesh(&MeshData { vertices, indices, ..MeshData::new() });
+    // A uniform wave: at time π/2 every vertex sits at -amplitude.
+    let sway = Material::default().with_displacement([1.2, 0.0, 0.0], [0.0; 3], 1.0);
+    let batched = Object { geometry: Some(Geometry::Cube { size: 1.0 }), ..Default::default() };
+    let registry = Object { geometry: None, mesh: Some(mesh), ..batched.clone() };
+
+    for (object, instanced) in [(batched, false), (registry.clone(), false), (registry, true)] {
+        if instanced && !scene.pipeline.supports_instancing() {
+            continue;
+        }
+        scene.instanced_rendering = instanced;
+        scene.time = 0.0;
+        let moved = scene.spawn(Object { transform: Transform::from_position(-1.2, 0.0, 0.0), ..object.clone() }, None);
+        let expected = golden::render(&mut scene);
+        scene.world.delete(moved);
+
+        let id = scene.spawn(Object { material: sway, ..object.clone() }, None);
+        let still = golden::render(&mut scene);
+        scene.time = std::f64::consts::FRAC_PI_2;
+        let swayed = golden::render(&mut scene);
+        assert!(golden::compare(&still.pixels, &expected.pixels, 0).mismatched_pixels > 0, "{instanced}");
+        assert_eq!(golden::compare(&swayed.pixels, &expected.pixels, 2).mismatched_pixels, 0, "{instanced}");
+        scene.world.delete(id);
+    }
+}
+
+#[test]
+fn displacement_phase_is_period_reduced() {
+    let sway = Displacement::new([0.0, 3.0, 4.0], [0.0; 3], 1.0);
+    assert_eq!(sway.reach(), 5.0);
+    let tau = std::f64::consts::TAU;
+    // A year in: an f32 time would be a whole number of seconds apart.
+    let [[.., phase], _] = sway.uniform(365.0 * 86_400.0 * tau + std::f64::consts::FRAC_PI_2);
+    assert!((phase - std::f32::consts::FRAC_PI_2).abs() < 1e-4, "{phase}");
+    assert!((0.0..std::f32::consts::TAU).contains(&sway.uniform(-1.0)[0][3]));
+}
@@ -0,0 +1,141 @@
+//! Unit tests for OBJ / PLY export on `MeshData`.
+//!
+//! These tests exercise:
+//! - OBJ: coloured vertices, flipped texture coordinates, 1-based faces
+//! - PLY: header counts match the body, colours become bytes
+//! - Both mirror Z into right-handed space: an exported cube winds
+//!   counter-clockwise seen from outside
+//! - Line and point meshes export as OBJ `l` / `p` lines and PLY edges
+//! - `from_world` merges every object's geometry in world space
+
+use crate::geometry::Geometry;
+use crate::mesh::{MeshData, Topology, Vertex};
+use crate::objects::Object;
+use crate::world::World;
+
+fn triangle() -> MeshData {
+    let vertex = |position, uv| Vertex { position, color: [1.0, 0.5, 0.0], uv };
+    MeshData {
+        vertices: vec![
+            vertex([0.0, 0.0, 0.0], [0.0, 0.0]),
+            vertex([1.0, 0.0, 0.0], [1.0, 0.0]),
+            vertex([0.0, 1.0, 2.0], [0.0, 1.0]),
+        ],
+        indices: vec![0, 1, 2],
+        topology: Topology::TriangleList,
+    }
+}
+
+fn export(f: impl FnOnce(&mut Vec<u8>) -> std::io::Result<()>) -> Vec<String> {
+    let mut out = Vec::new();
+    f(&mut out).unwrap();
+    String::from_utf8(out).unwrap().lines().map(str::to_owned).collect()
+}
+
+#[test]
+fn obj_lists_vertices_uvs_and_faces() {
+    let mesh = triangle();
+    let lines = export(|out| mesh.export_obj(out));
+    assert!(lines[0].starts_with('#'));
+    assert_eq!(lines[1..4], ["v 0 0 0 1 0.5 0", "v 1 0 0 1 0.5 0", "v 0 1 -2 1 0.5 0"]);
+    assert_eq!(lines[4..7], ["vt 0 1", "vt 1 1", "vt 0 0"]);
+    assert_eq!(lines[7..], ["f 1/1 2/2 3/3"]);
+}
+
+#[test]
+fn ply_header_matches_body() {
+    let mesh = triangle();
+    let lines = export(|out| mesh.export_ply(out));
+    assert_eq!(lines[..2], ["ply", "format ascii 1.0"]);
+    assert!(lines.contains(&"element vertex 3".to_owned()));
+    assert!(lines.contains(&"element face 1".to_owned()));
+    let body = lines.iter().position(|l| l == "end_header").unwrap() + 1;
+    assert_eq!(lines[body], "0 0 0 255 128 0 0 1");
+    assert_eq!(lines[body + 3..], ["3 0 1 2"]);
+}
+
+fn cube() -> MeshData {
+    let mut mesh = MeshData::new();
+    Geometry::Cube { size: 1.0 }.generate_mesh_data(&mut mesh, &Default::default(), [1.0; 4]);
+    mesh
+}
+
+/// Assert the first triangle of each face of a cube centred on the origin
+/// winds counter-clockwise seen from outside under the right-hand rule.
+fn assert_outward(positions: &[[f32; 3]], triangles: &[[usize; 3]]) {
+    assert_eq!(triangles.len(), 12);
+    for (i, &[a, b, c]) in triangles.iter().enumerate().step_by(2) {
+        let [a, b, c] = [positions[a], positions[b], positions[c]];
+        let (u, v) = ([0, 1, 2].map(|k| b[k] - a[k]), [0, 1, 2].map(|k| c[k] - a[k]));
+        let normal = [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]];
+        let outward: f32 = (0..3).map(|k| normal[k] * (a[k] + b[k] + c[k])).sum();
+        assert!(outward > 0.0, "triangle {i} {a:?} {b:?} {c:?} winds clockwise from outside");
+    }
+}
+
+fn numbers<T: std::str::FromStr>(line: &str) -> Vec<T> {
+    line.split([' ', '/']).filter_map(|n| n.parse().ok()).collect()
+}
+
+#[test]
+fn obj_cube_winds_counter_clockwise() {
+    let lines = export(|out| cube().export_obj(out));
+    let positions: Vec<[f32; 3]> = lines.iter()
+        .filter_map(|l| l.strip_prefix("v "))
+        .map(|l| numbers(l)[..3].try_into().unwrap())
+        .collect();
+    let triangles: Vec<[usize; 3]> = lines.iter()
+        .filter_map(|l| l.strip_prefix("f "))
+        .map(|l| {
+            let n: Vec<usize> = numbers(l);
+            [n[0] - 1, n[2] - 1, n[4] - 1]
+        })
+        .collect();
+    assert_outward(&positions, &triangles);
+}
+
+#[test]
+fn ply_cube_winds_counter_clockwise() {
+    let mesh = cube();
+    let lines = export(|out| mesh.export_ply(out));
+    let body = lines.iter().position(|l| l == "end_header").unwrap() + 1;
+    let (vertices, faces) = lines[body..].split_at(mesh.vertices.len());
+    let positions: Vec<[f32; 3]> = vertices.iter().map(|l| numbers(l)[..3].try_into().unwrap()).collect();
+    let triangles: Vec<[usize; 3]> = faces.iter().map(|l| numbers(l)[1..].try_into().unwrap()).collect();
+    assert_outward(&positions, &triangles);
+}
+
+#[test]
+fn lines_and_points_export() {
+    let mut path = MeshData::lines();
+    path.push_polyline(&[[0.0; 3], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0]], [1.0; 4]);
+    let obj = export(|out| path.export_obj(out));
+    assert_eq!(obj[0], "# vertra mesh: 3 vertices, 2 lines");
+    assert_eq!(obj[7..], ["l 1 2", "l 2 3"]);
+    let ply = export(|out| path.export_ply(out));
+    assert!(ply.contains(&"element edge 2".to_owned()));
+    assert!(!ply.iter().any(|l| l.starts_with("element face")));
+    assert_eq!(ply[ply.len() - 2..], ["0 1", "1 2"]);
+
+    let mut scan = MeshData::points();
+    scan.push_points(&[[0.0; 3], [2.0, 0.0, 0.0]], [1.0; 4]);
+    assert_eq!(export(|out| scan.export_obj(out))[5..], ["p 1", "p 2"]);
+    let ply = export(|out| scan.export_ply(out));
+    assert_eq!(ply.last().map(String::as_str), Some("2 0 0 255 255 255 0 1"));
+}
+
+#[test]
+fn from_world_merges_hierarchy_in_world_space() {
+    let mut world = World::new();
+    let cube = |x| Object::builder().geometry(Geometry::Cube { size: 1.0 }).at(x, 0.0, 0.0).build();
+    let parent = world.spawn_object(cube(10.0), None);
+    world.spawn_object(cube(5.0), Some(parent));
+    world.spawn_object(Object::builder().at(-50.0, 0.0, 0.0).build(), None);
+
+    let merged = MeshData::from_world(&world);
+    let mut single = MeshData::new();
+    Geometry::Cube { size: 1.0 }.generate_mesh_data(&mut single, &Default::default(), [1.0; 4]);
+    assert_eq!(merged.indices.len(), 2 * single.indices.len(), "the empty pivot adds nothing");
+    let (lo, hi) = merged.bounds().unwrap();
+    assert_eq!((lo[0], hi[0]), (9.5, 15.5), "the child sits at 10 + 5");
+}
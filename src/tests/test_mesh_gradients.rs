@@ -0,0 +1,74 @@
+//! Unit tests for position-based vertex colouring on `MeshData`.
+//!
+//! These tests exercise:
+//! - `bounds` on empty and populated meshes
+//! - `color_by_position` sees every vertex with its current colour
+//! - `height_gradient` spans the mesh's vertical extent and keeps tints
+//! - `radial_gradient` saturates at the radius
+
+use crate::geometry::Geometry;
+use crate::mesh::MeshData;
+use crate::transform::Transform;
+
+const EPS: f32 = 1e-5;
+
+fn mesh(geometry: Geometry, color: [f32; 4]) -> MeshData {
+    let mut mesh = MeshData::new();
+    geometry.generate_mesh_data(&mut mesh, &Transform::default(), color);
+    mesh
+}
+
+fn assert_color(actual: [f32; 3], expected: [f32; 3]) {
+    assert!((0..3).all(|k| (actual[k] - expected[k]).abs() < EPS), "{actual:?} != {expected:?}");
+}
+
+#[test]
+fn bounds_cover_all_vertices() {
+    assert_eq!(MeshData::new().bounds(), None);
+    let box_mesh = mesh(Geometry::Box { width: 2.0, height: 4.0, depth: 6.0, segments: [1; 3] }, [1.0; 4]);
+    assert_eq!(box_mesh.bounds(), Some(([-1.0, -2.0, -3.0], [1.0, 2.0, 3.0])));
+}
+
+#[test]
+fn color_by_position_maps_every_vertex() {
+    let mut cube = mesh(Geometry::Cube { size: 2.0 }, [0.5, 0.5, 0.5, 1.0]);
+    let mut seen = 0;
+    cube.color_by_position(|[x, _, _], color| {
+        seen += 1;
+        assert_eq!(color, [0.5; 3]);
+        if x > 0.0 { [1.0, 0.0, 0.0] } else { color }
+    });
+    assert_eq!(seen, cube.vertices.len());
+    for v in &cube.vertices {
+        let expected = if v.position[0] > 0.0 { [1.0, 0.0, 0.0] } else { [0.5; 3] };
+        assert_eq!(v.color, expected);
+    }
+}
+
+#[test]
+fn height_gradient_spans_vertical_extent() {
+    let mut column = mesh(Geometry::Box { width: 1.0, height: 4.0, depth: 1.0, segments: [1; 3] }, [1.0, 0.5, 1.0, 1.0]);
+    column.height_gradient([0.0, 0.0, 1.0], [1.0, 1.0, 0.0]);
+    for v in &column.vertices {
+        let t = (v.position[1] + 2.0) / 4.0;
+        // Multiplied with the green-halved tint.
+        assert_color(v.color, [t, 0.5 * t, 1.0 - t]);
+    }
+
+    // A flat mesh takes the bottom colour instead of dividing by zero.
+    let mut plane = mesh(Geometry::Plane { size: 1.0, subdivisions: 1 }, [1.0; 4]);
+    plane.height_gradient([0.2; 3], [0.8; 3]);
+    assert!(plane.vertices.iter().all(|v| v.color == [0.2; 3]));
+}
+
+#[test]
+fn radial_gradient_saturates_at_radius() {
+    let mut plane = mesh(Geometry::Plane { size: 4.0, subdivisions: 1 }, [1.0; 4]);
+    plane.vertices[0].position = [0.0, 0.0, 0.0];
+    plane.vertices[1].position = [0.5, 0.0, 0.0];
+    plane.radial_gradient([0.0; 3], 1.0, [1.0, 1.0, 1.0], [0.0, 0.0, 0.0]);
+    assert_color(plane.vertices[0].color, [1.0; 3]);
+    assert_color(plane.vertices[1].color, [0.5; 3]);
+    // The remaining corners are sqrt(8) away: fully `outer`.
+    assert!(plane.vertices[2..].iter().all(|v| v.color == [0.0; 3]));
+}
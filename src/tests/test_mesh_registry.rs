@@ -0,0 +1,218 @@
+//! Unit tests for the reference-counted mesh registry.
+//!
+//! These tests exercise:
+//! - insert / get / remove lifecycle and ID uniqueness
+//! - retain / release bookkeeping (release saturates at zero)
+//! - garbage collection of unreferenced meshes
+//! - per-world reference counts kept on spawn, delete and `set_mesh`
+//! - manual counts and world references both keeping a mesh alive
+//! - serialisation skipping the runtime-only `Object::mesh` handle
+//!
+//! `MeshRegistry` is generic over the stored mesh so the bookkeeping can be
+//! tested with plain values instead of GPU buffers.
+
+use crate::geometry::GeometryId;
+use crate::mesh::MeshRegistry;
+use crate::objects::Object;
+use crate::world::World;
+
+fn object_with_mesh(str_id: &str, mesh: Option<GeometryId>) -> Object {
+    Object {
+        name: str_id.into(),
+        str_id: str_id.into(),
+        mesh,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn insert_returns_unique_ids() {
+    let mut reg: MeshRegistry<&str> = MeshRegistry::new();
+    let a = reg.insert("a");
+    let b = reg.insert("b");
+    assert_ne!(a, b);
+    assert_eq!(reg.get(a), Some(&"a"));
+    assert_eq!(reg.get(b), Some(&"b"));
+    assert_eq!(reg.len(), 2);
+}
+
+#[test]
+fn new_entries_start_unreferenced() {
+    let mut reg: MeshRegistry<u32> = MeshRegistry::new();
+    let id = reg.insert(7);
+    assert_eq!(reg.ref_count(id), Some(0));
+}
+
+#[test]
+fn remove_frees_entry_and_ids_are_not_reused() {
+    let mut reg: MeshRegistry<u32> = MeshRegistry::new();
+    let a = reg.insert(1);
+    assert_eq!(reg.remove(a), Some(1));
+    assert!(!reg.contains(a));
+    assert!(reg.get(a).is_none());
+    assert_eq!(reg.remove(a), None, "double remove must be a no-op");
+
+    let b = reg.insert(2);
+    assert_ne!(a, b, "removed IDs must never be handed out again");
+    assert!(reg.get(a).is_none());
+}
+
+#[test]
+fn retain_and_release_adjust_ref_count() {
+    let mut reg: MeshRegistry<u32> = MeshRegistry::new();
+    let id = reg.insert(0);
+    assert!(reg.retain(id));
+    assert!(reg.retain(id));
+    assert_eq!(reg.ref_count(id), Some(2));
+    assert!(reg.release(id));
+    assert_eq!(reg.ref_count(id), Some(1));
+}
+
+#[test]
+fn release_saturates_at_zero() {
+    let mut reg: MeshRegistry<u32> = MeshRegistry::new();
+    let id = reg.insert(0);
+    assert!(reg.release(id));
+    assert_eq!(reg.ref_count(id), Some(0));
+}
+
+#[test]
+fn retain_release_unknown_id_return_false() {
+    let mut reg: MeshRegistry<u32> = MeshRegistry::new();
+    assert!(!reg.retain(GeometryId(42)));
+    assert!(!reg.release(GeometryId(42)));
+    assert_eq!(reg.ref_count(GeometryId(42)), None);
+}
+
+#[test]
+fn collect_garbage_drops_only_unreferenced() {
+    let mut reg: MeshRegistry<u32> = MeshRegistry::new();
+    let kept = reg.insert(1);
+    let dropped = reg.insert(2);
+    reg.retain(kept);
+
+    assert_eq!(reg.collect_garbage([]), 1);
+    assert!(reg.contains(kept));
+    assert!(!reg.contains(dropped));
+
+    reg.release(kept);
+    assert_eq!(reg.collect_garbage([]), 1);
+    assert!(reg.is_empty());
+}
+
+#[test]
+fn world_counts_spawned_objects() {
+    let shared = GeometryId(0);
+    let single = GeometryId(1);
+
+    let mut world = World::new();
+    world.spawn_object(object_with_mesh("a", Some(shared)), None);
+    world.spawn_object(object_with_mesh("b", Some(shared)), None);
+    world.spawn_object(object_with_mesh("c", Some(single)), None);
+    world.spawn_object(object_with_mesh("d", None), None);
+
+    assert_eq!(world.mesh_ref_count(shared), 2);
+    assert_eq!(world.mesh_ref_count(single), 1);
+    assert_eq!(world.mesh_ref_count(GeometryId(2)), 0);
+}
+
+#[test]
+fn delete_releases_descendant_references() {
+    let id = GeometryId(0);
+    let mut world = World::new();
+    let parent = world.spawn_object(object_with_mesh("parent", Some(id)), None);
+    world.spawn_object(object_with_mesh("child", Some(id)), Some(parent));
+    assert_eq!(world.mesh_ref_count(id), 2);
+
+    world.delete(parent);
+    assert_eq!(world.mesh_ref_count(id), 0);
+}
+
+#[test]
+fn set_mesh_moves_the_reference() {
+    let (old, new) = (GeometryId(0), GeometryId(1));
+    let mut world = World::new();
+    let a = world.spawn_object(object_with_mesh("a", Some(old)), None);
+    world.end_frame();
+
+    assert!(world.set_mesh(a, Some(new)));
+    assert_eq!(world.mesh_ref_count(old), 0);
+    assert_eq!(world.mesh_ref_count(new), 1);
+    assert_eq!(world.objects[&a].mesh, Some(new));
+
+    assert!(world.set_mesh(a, None));
+    assert_eq!(world.mesh_ref_count(new), 0);
+    assert!(!world.set_mesh(999, Some(new)));
+
+    world.end_frame();
+    assert_eq!(world.changes().modified, vec![a]);
+}
+
+#[test]
+fn from_parts_counts_loaded_objects() {
+    let id = GeometryId(4);
+    let objects = [(0, object_with_mesh("a", Some(id))), (1, object_with_mesh("b", Some(id)))]
+        .into_iter()
+        .collect();
+    let world = World::from_parts(objects, vec![0, 1], 2);
+    assert_eq!(world.mesh_ref_count(id), 2);
+}
+
+#[test]
+fn despawn_then_collect_frees_mesh() {
+    let mut reg: MeshRegistry<u32> = MeshRegistry::new();
+    let id = reg.insert(1);
+
+    let mut world = World::new();
+    let a = world.spawn_object(object_with_mesh("a", Some(id)), None);
+    let b = world.spawn_object(object_with_mesh("b", Some(id)), None);
+    assert_eq!(reg.collect_garbage([&world]), 0);
+
+    world.delete(a);
+    assert_eq!(reg.collect_garbage([&world]), 0, "still referenced by 'b'");
+
+    world.delete(b);
+    assert_eq!(reg.collect_garbage([&world]), 1);
+    assert!(!reg.contains(id));
+}
+
+#[test]
+fn collect_garbage_checks_every_world() {
+    let mut reg: MeshRegistry<u32> = MeshRegistry::new();
+    let hud = reg.insert(1);
+
+    let main = World::new();
+    let mut overlay = World::new();
+    overlay.spawn_object(object_with_mesh("bar", Some(hud)), None);
+
+    assert_eq!(reg.collect_garbage([&main, &overlay]), 0);
+    assert_eq!(reg.collect_garbage([&main]), 1, "a world left out does not keep the mesh");
+}
+
+#[test]
+fn manual_counts_add_to_world_references() {
+    let mut reg: MeshRegistry<u32> = MeshRegistry::new();
+    let id = reg.insert(1);
+    reg.retain(id);
+
+    let mut world = World::new();
+    let a = world.spawn_object(object_with_mesh("a", Some(id)), None);
+    world.delete(a);
+
+    assert_eq!(reg.collect_garbage([&world]), 0, "retained mesh outlives its objects");
+    assert_eq!(reg.ref_count(id), Some(1));
+    reg.release(id);
+    assert_eq!(reg.collect_garbage([&world]), 1);
+}
+
+#[test]
+fn mesh_handle_is_not_serialised() {
+    let obj = object_with_mesh("a", Some(GeometryId(3)));
+    let mut buf = Vec::new();
+    let mut world = World::new();
+    world.spawn_object(obj, None);
+    crate::vtr::write(&mut buf, &crate::camera::Camera::new(), &world).unwrap();
+    let restored = crate::vtr::read(&mut std::io::Cursor::new(buf)).unwrap();
+    let id = restored.world.get_id("a").unwrap();
+    assert_eq!(restored.world.objects[&id].mesh, None);
+}
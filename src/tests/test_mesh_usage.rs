@@ -0,0 +1,94 @@
+//! Unit tests for static/dynamic mesh usage bookkeeping.
+//!
+//! These tests exercise:
+//! - default usage is `Dynamic`
+//! - the static set marks the cache dirty only when it changes
+//! - pruning forgets deleted objects
+//! - `set_batches` clears the dirty flag
+//! - buffer usage flags per hint
+
+use crate::mesh::{BatchKey, MeshUsage, StaticBatches};
+use crate::objects::Object;
+use crate::world::World;
+
+fn spawn(world: &mut World, str_id: &str) -> usize {
+    world.spawn_object(Object { str_id: str_id.into(), ..Default::default() }, None)
+}
+
+#[test]
+fn default_usage_is_dynamic() {
+    let batches: StaticBatches<u32> = StaticBatches::new();
+    assert_eq!(MeshUsage::default(), MeshUsage::Dynamic);
+    assert_eq!(batches.usage(7), MeshUsage::Dynamic);
+    assert!(!batches.is_dirty());
+}
+
+#[test]
+fn marking_static_sets_dirty() {
+    let mut batches: StaticBatches<u32> = StaticBatches::new();
+    batches.set_usage(1, MeshUsage::Static);
+    assert_eq!(batches.usage(1), MeshUsage::Static);
+    assert!(batches.is_dirty());
+    assert_eq!(batches.len(), 1);
+}
+
+#[test]
+fn unchanged_usage_does_not_dirty() {
+    let mut batches: StaticBatches<u32> = StaticBatches::new();
+    batches.set_usage(1, MeshUsage::Dynamic);
+    assert!(!batches.is_dirty(), "dynamic -> dynamic is a no-op");
+
+    batches.set_usage(1, MeshUsage::Static);
+    batches.set_batches(Vec::new());
+    batches.set_usage(1, MeshUsage::Static);
+    assert!(!batches.is_dirty(), "static -> static is a no-op");
+}
+
+#[test]
+fn rehinting_dynamic_removes_and_dirties() {
+    let mut batches: StaticBatches<u32> = StaticBatches::new();
+    batches.set_usage(1, MeshUsage::Static);
+    batches.set_batches(vec![(BatchKey::default(), 0)]);
+    batches.set_usage(1, MeshUsage::Dynamic);
+    assert!(batches.is_empty());
+    assert!(batches.is_dirty());
+}
+
+#[test]
+fn set_batches_clears_dirty() {
+    let mut batches: StaticBatches<u32> = StaticBatches::new();
+    batches.set_usage(1, MeshUsage::Static);
+    batches.set_batches(vec![(BatchKey { texture_path: Some("tex.png".into()), ..BatchKey::default() }, 5)]);
+    assert!(!batches.is_dirty());
+    assert_eq!(batches.batches().len(), 1);
+
+    batches.invalidate();
+    assert!(batches.is_dirty());
+}
+
+#[test]
+fn prune_forgets_deleted_objects() {
+    let mut world = World::new();
+    let a = spawn(&mut world, "a");
+    let b = spawn(&mut world, "b");
+
+    let mut batches: StaticBatches<u32> = StaticBatches::new();
+    batches.set_usage(a, MeshUsage::Static);
+    batches.set_usage(b, MeshUsage::Static);
+    batches.set_batches(Vec::new());
+
+    batches.prune(&world);
+    assert!(!batches.is_dirty(), "nothing deleted, nothing to rebuild");
+
+    world.delete(a);
+    batches.prune(&world);
+    assert!(batches.is_dirty());
+    assert_eq!(batches.usage(a), MeshUsage::Dynamic);
+    assert_eq!(batches.usage(b), MeshUsage::Static);
+}
+
+#[test]
+fn buffer_usage_flags_follow_hint() {
+    assert!(MeshUsage::Static.extra_buffer_usages().is_empty());
+    assert!(MeshUsage::Dynamic.extra_buffer_usages().contains(wgpu::BufferUsages::COPY_DST));
+}
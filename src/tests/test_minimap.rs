@@ -0,0 +1,128 @@
+//! Unit tests for orthographic cameras and the top-down minimap.
+//!
+//! These tests exercise:
+//! - Orthographic projection size is independent of distance
+//! - The map camera follows or pins its centre, north-up or heading-up
+//! - Zoom scales the span within its limits
+//! - The camera marker points along the heading and hides off the map
+//! - The map renders into a registered texture shown in the overlay
+
+use crate::camera::{Camera, Viewport};
+use crate::constants::minimap::{MAX_SPAN, MIN_SPAN};
+use crate::geometry::Geometry;
+use crate::minimap::{Minimap, TEXTURE_KEY};
+use crate::objects::Object;
+use crate::overlay::{Anchor, Layout};
+use crate::tests::golden;
+
+const VIEWPORT: [f32; 2] = [400.0, 300.0];
+
+fn close(a: [f32; 2], b: [f32; 2]) -> bool {
+    (a[0] - b[0]).abs() < 1e-3 && (a[1] - b[1]).abs() < 1e-3
+}
+
+fn centered() -> Minimap {
+    Minimap::default().with_layout(Layout::new(Anchor::Center, [100.0, 100.0]))
+}
+
+#[test]
+fn orthographic_size_ignores_distance() {
+    let camera = Camera { eye: [0.0, 0.0, -10.0], target: [0.0, 0.0, 0.0], aspect: 2.0, ..Camera::new() }
+        .with_ortho_height(Some(10.0));
+    let viewport = Viewport::new(200.0, 100.0);
+    for z in [0.0, 50.0, 400.0] {
+        let top_right = camera.world_to_screen([10.0, 5.0, z], viewport).unwrap();
+        assert!(close([top_right[0], top_right[1]], [200.0, 0.0]), "{top_right:?}");
+    }
+    let near = camera.world_to_screen([0.0, 0.0, -10.0 + camera.znear], viewport).unwrap();
+    assert!(near[2].abs() < 1e-5);
+    assert!(camera.with_ortho_height(None).world_to_screen([10.0, 5.0, 400.0], viewport).unwrap()[0] < 150.0);
+}
+
+#[test]
+fn map_camera_follows_and_turns() {
+    let scene_camera = Camera { eye: [4.0, 3.0, -2.0], target: [5.0, 3.0, -2.0], ..Camera::new() };
+    let map = centered().with_span(20.0);
+    let camera = map.camera(&scene_camera, 1.0);
+    assert_eq!(camera.ortho_height, Some(20.0));
+    assert_eq!([camera.eye[0], camera.eye[2]], [4.0, -2.0]);
+
+    // North-up: +X right, +Z up, scene camera in the middle.
+    let center = [200.0, 150.0];
+    assert!(close(map.world_to_map(&scene_camera, VIEWPORT, [4.0, 0.0, -2.0]).unwrap(), center));
+    assert!(close(map.world_to_map(&scene_camera, VIEWPORT, [9.0, 0.0, -2.0]).unwrap(), [225.0, 150.0]));
+    assert!(close(map.world_to_map(&scene_camera, VIEWPORT, [4.0, 0.0, 3.0]).unwrap(), [200.0, 125.0]));
+
+    // Heading-up: the camera faces +X, so +X points up the map.
+    let rotated = map.clone().with_rotate(true);
+    assert!(close(rotated.world_to_map(&scene_camera, VIEWPORT, [9.0, 0.0, -2.0]).unwrap(), [200.0, 125.0]));
+
+    let pinned = map.with_center(Some([0.0, 0.0]));
+    assert!(close(pinned.world_to_map(&scene_camera, VIEWPORT, [0.0, 7.0, 0.0]).unwrap(), center));
+}
+
+#[test]
+fn zoom_scales_the_span() {
+    let mut map = Minimap::default().with_span(40.0);
+    map.zoom(2.0);
+    assert_eq!(map.span, 20.0);
+    map.zoom(0.5);
+    assert_eq!(map.span, 40.0);
+    map.zoom(0.0);
+    assert_eq!(map.span, 40.0);
+    map.zoom(1e6);
+    assert_eq!(map.span, MIN_SPAN);
+    map.zoom(1e-9);
+    assert_eq!(map.span, MAX_SPAN);
+}
+
+#[test]
+fn marker_points_along_the_heading() {
+    // Facing +X on a north-up map: the marker points right.
+    let scene_camera = Camera { eye: [0.0, 1.0, 0.0], target: [1.0, 1.0, 0.0], ..Camera::new() };
+    let map = centered();
+    let marker = map.marker(&scene_camera, VIEWPORT);
+    assert_eq!(marker.indices.len(), 3);
+    let tip = marker.vertices[0].position;
+    assert!(tip[0] > 200.0 && (tip[1] - 150.0).abs() < 1e-3, "{tip:?}");
+
+    let quad = map.quad(VIEWPORT);
+    assert_eq!(quad.bounds(), Some(([150.0, 100.0, 0.0], [250.0, 200.0, 0.0])));
+
+    let far = map.clone().with_center(Some([1000.0, 0.0]));
+    assert!(far.marker(&scene_camera, VIEWPORT).indices.is_empty());
+    assert!(map.with_marker(None).marker(&scene_camera, VIEWPORT).indices.is_empty());
+}
+
+#[test]
+fn minimap_renders_into_the_overlay() {
+    let Some(mut scene) = golden::headless_scene(64, 64, Camera::new()) else { return };
+    scene.spawn(Object {
+        geometry: Some(Geometry::Cube { size: 4.0 }),
+        color: [1.0, 0.1, 0.1, 1.0],
+        ..Default::default()
+    }, None);
+    let plain = golden::render(&mut scene);
+    let textures = scene.gpu_memory().texture_bytes;
+
+    scene.overlay.minimap = Some(
+        Minimap::default()
+            .with_layout(Layout::new(Anchor::Center, [32.0, 32.0]))
+            .with_center(Some([0.0, 0.0]))
+            .with_span(8.0)
+            .with_marker(None),
+    );
+    let frame = golden::render(&mut scene);
+    assert!(scene.has_texture(TEXTURE_KEY));
+    assert!(scene.gpu_memory().texture_bytes > textures);
+    assert_ne!(frame.pixels, plain.pixels);
+    // The cube's top fills the middle of the map.
+    let at = |x: usize, y: usize| &frame.pixels[(y * 64 + x) * 4..(y * 64 + x) * 4 + 3];
+    let [r, g, b] = [0, 1, 2].map(|k| u32::from(at(32, 32)[k]));
+    assert!(r > 100 && r > g * 2 && r > b * 2, "{r} {g} {b}");
+
+    scene.overlay.minimap = None;
+    assert_eq!(golden::render(&mut scene).pixels, plain.pixels);
+    assert!(!scene.has_texture(TEXTURE_KEY));
+    assert_eq!(scene.gpu_memory().texture_bytes, textures);
+}
@@ -0,0 +1,133 @@
+//! Unit tests for planar mirrors, portals and their projections.
+//!
+//! These tests exercise:
+//! - `Matrix4::frustum` maps its near rectangle to the NDC square
+//! - Oblique near-plane clipping puts the clip plane at depth 0
+//! - Mirror views reflect the eye and line the surface up with the texture
+//! - Portal views carry the eye to the exit and keep the image unflipped
+//! - Mirrors render reflections, skip geometry behind them, and clean up
+
+use crate::camera::Camera;
+use crate::constants::layers;
+use crate::geometry::Geometry;
+use crate::math::Matrix4;
+use crate::mirror::{self, Mirror};
+use crate::objects::Object;
+use crate::tests::golden;
+use crate::transform::Transform;
+
+fn close(a: [f32; 3], b: [f32; 3]) -> bool {
+    (0..3).all(|k| (a[k] - b[k]).abs() < 1e-3)
+}
+
+#[test]
+fn frustum_maps_the_near_rectangle() {
+    let m = Matrix4::frustum(-1.0, 3.0, 0.5, 2.5, 2.0, 50.0);
+    assert!(close(m.project_point([-1.0, 0.5, 2.0]), [-1.0, -1.0, 0.0]));
+    assert!(close(m.project_point([3.0, 2.5, 2.0]), [1.0, 1.0, 0.0]));
+    // Points further away stay on the same rays.
+    assert!(close(m.project_point([30.0, 25.0, 20.0]), [1.0, 1.0, m.project_point([0.0, 0.0, 20.0])[2]]));
+    assert!((m.project_point([0.0, 0.0, 50.0])[2] - 1.0).abs() < 1e-5);
+}
+
+#[test]
+fn oblique_plane_becomes_the_near_plane() {
+    let projection = Matrix4::perspective(60.0, 1.0, 0.1, 100.0);
+    // Keep z + y > 4: a plane tilted against the view direction.
+    let clipped = projection.oblique_near_plane([0.0, 1.0, 1.0, -4.0]);
+    let depth = |p: [f32; 3]| {
+        let v = clipped.mul_vec4([p[0], p[1], p[2], 1.0]);
+        v[2] / v[3]
+    };
+    assert!(depth([0.0, 0.0, 4.0]).abs() < 1e-5);
+    assert!(depth([0.5, 1.0, 3.0]).abs() < 1e-5);
+    assert!(depth([0.0, 0.0, 3.0]) < 0.0, "behind the plane is clipped");
+    let beyond = depth([0.0, 0.0, 10.0]);
+    assert!(beyond > 0.0 && beyond < 1.0);
+    // x and y are untouched.
+    let [a, b] = [projection, clipped].map(|m| m.mul_vec4([1.0, 2.0, 7.0, 1.0]));
+    assert_eq!([a[0], a[1], a[3]], [b[0], b[1], b[3]]);
+}
+
+#[test]
+fn mirror_view_reflects_the_eye() {
+    let camera = Camera { eye: [1.0, 2.0, -5.0], target: [0.0, 1.0, 0.0], ..Camera::new() };
+    let surface = Transform::from_position(0.0, 1.0, 0.0);
+    let size = [4.0, 2.0];
+    let view = Mirror::default().view(&camera, &surface, size, None).unwrap();
+    assert!(close(view.camera.eye, [1.0, 2.0, 5.0]));
+    assert!(!view.camera.sees_layer(layers::MIRROR));
+
+    // The surface fills the texture, flipped as `uv` expects.
+    let corner = |x: f32, y: f32| view.view_projection.project_point([x, 1.0 + y, 0.0]);
+    assert!(close(corner(-2.0, -1.0), [1.0, -1.0, 0.0]));
+    assert!(close(corner(2.0, 1.0), [-1.0, 1.0, 0.0]));
+    assert_eq!(Mirror::default().uv(), [1.0, 0.0, 0.0, 1.0]);
+
+    // In front of the mirror is kept, behind it is clipped.
+    let depth = |z: f32| view.view_projection.project_point([0.0, 1.0, z])[2];
+    assert!(depth(-3.0) > 0.0 && depth(-3.0) < 1.0);
+    assert!(depth(2.0) < 0.0);
+
+    let behind = Camera { eye: [0.0, 1.0, 3.0], ..camera };
+    assert!(Mirror::default().view(&behind, &surface, size, None).is_none());
+}
+
+#[test]
+fn portal_view_comes_out_of_the_exit() {
+    let camera = Camera { eye: [0.5, 0.0, -5.0], target: [0.0, 0.0, 0.0], ..Camera::new() };
+    let entry = Transform::default();
+    let exit = Transform { position: [10.0, 0.0, 0.0], rotation: [0.0, 90.0, 0.0], ..Transform::default() };
+    let portal = Mirror::default().with_exit(Some(1));
+    let size = [2.0, 2.0];
+    let view = portal.view(&camera, &entry, size, Some(&exit)).unwrap();
+
+    // The eye sits behind the exit, as far as it was in front of the entry.
+    let local_eye = exit.to_matrix().inverse().unwrap().mul_vec4([view.camera.eye[0], view.camera.eye[1], view.camera.eye[2], 1.0]);
+    assert!(close([local_eye[0], local_eye[1], local_eye[2]], [-0.5, 0.0, 5.0]));
+
+    // The entry's left edge shows the exit's right edge, so the image is
+    // not flipped.
+    let exit_corner = |x: f32, y: f32| {
+        let p = exit.to_matrix().mul_vec4([x, y, 0.0, 1.0]);
+        view.view_projection.project_point([p[0], p[1], p[2]])
+    };
+    assert!(close(exit_corner(1.0, -1.0), [-1.0, -1.0, 0.0]));
+    assert_eq!(portal.uv(), [0.0, 0.0, 1.0, 1.0]);
+    assert_eq!(portal.with_resolution(100).texture_size([4.0, 1.0]), [400, 100]);
+}
+
+#[test]
+fn mirrors_reflect_what_is_in_front() {
+    let camera = Camera { eye: [0.0, 1.0, -6.0], target: [0.0, 1.0, 0.0], ..Camera::new() };
+    let Some(mut scene) = golden::headless_scene(48, 48, camera) else { return };
+    let cube = |color, z| Object {
+        geometry: Some(Geometry::Cube { size: 2.0 }),
+        color,
+        transform: Transform::from_position(0.0, 1.0, z),
+        ..Default::default()
+    };
+    // Behind the camera, so only its reflection is visible; the green cube
+    // behind the mirror must not be reflected.
+    scene.spawn(cube([1.0, 0.1, 0.1, 1.0], -9.0), None);
+    scene.spawn(cube([0.1, 1.0, 0.1, 1.0], 3.0), None);
+    let id = scene.spawn_mirror(Mirror::default().with_resolution(64), [4.0, 4.0], Transform::from_position(0.0, 1.0, 0.0), None);
+    let key = mirror::texture_key(id);
+    assert_eq!(scene.world.objects[&id].texture_path.as_deref(), Some(key.as_str()));
+
+    let frame = golden::render(&mut scene);
+    assert!(scene.has_texture(&key));
+    let at = (24 * 48 + 24) * 4;
+    let [r, g, b] = [0, 1, 2].map(|k| u32::from(frame.pixels[at + k]));
+    assert!(r > 40 && r > g * 2 && r > b * 2, "{r} {g} {b}");
+
+    let targets = scene.gpu_memory().render_target_bytes;
+    scene.mirror_mut(id).unwrap().resolution = 32;
+    golden::render(&mut scene);
+    assert!(scene.gpu_memory().render_target_bytes < targets);
+
+    scene.world.delete(id);
+    golden::render(&mut scene);
+    assert!(scene.mirror(id).is_none());
+    assert!(!scene.has_texture(&key));
+}
@@ -0,0 +1,132 @@
+//! Tests for the 2D shape generators (object2d.rs): winding, extents,
+//! outline thickness and vertex counts, and nine-slice panels.
+
+use crate::mesh::Vertex;
+use crate::object2d::{NineSlice, Shape2D, Style2D};
+
+const EPS: f32 = 1e-4;
+
+const SHAPES: [Shape2D; 5] = [
+    Shape2D::Rect { width: 2.0, height: 1.0 },
+    Shape2D::Circle { radius: 1.0, segments: 24 },
+    Shape2D::RegularPolygon { radius: 1.0, sides: 6 },
+    Shape2D::RoundedRect { width: 3.0, height: 2.0, radius: 0.5, segments: 4 },
+    Shape2D::Ring { inner_radius: 0.5, outer_radius: 1.0, segments: 24 },
+];
+
+const STYLES: [Style2D; 2] = [Style2D::Fill, Style2D::Outline { thickness: 0.1 }];
+
+fn xy_bounds(vertices: &[Vertex]) -> ([f32; 2], [f32; 2]) {
+    vertices.iter().fold(([f32::MAX; 2], [f32::MIN; 2]), |(lo, hi), v| {
+        let [x, y, _] = v.position;
+        ([lo[0].min(x), lo[1].min(y)], [hi[0].max(x), hi[1].max(y)])
+    })
+}
+
+#[test]
+fn triangles_are_counter_clockwise_and_flat() {
+    for shape in SHAPES {
+        for style in STYLES {
+            let (vertices, indices) = shape.build(style);
+            assert!(!indices.is_empty(), "{shape:?} {style:?}");
+            for tri in indices.chunks(3) {
+                let [a, b, c] = [0, 1, 2].map(|k| vertices[tri[k] as usize].position);
+                let area = (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0]);
+                assert!(area > 1e-7, "{shape:?} {style:?}: triangle {tri:?} has area {area}");
+                assert_eq!([a[2], b[2], c[2]], [0.0; 3]);
+            }
+        }
+    }
+}
+
+#[test]
+fn outline_keeps_the_filled_extents() {
+    for shape in SHAPES {
+        let (fill, _) = shape.build(Style2D::Fill);
+        let (outline, _) = shape.build(Style2D::Outline { thickness: 0.1 });
+        let (fill_lo, fill_hi) = xy_bounds(&fill);
+        let (line_lo, line_hi) = xy_bounds(&outline);
+        for k in 0..2 {
+            assert!((fill_lo[k] - line_lo[k]).abs() < EPS, "{shape:?}");
+            assert!((fill_hi[k] - line_hi[k]).abs() < EPS, "{shape:?}");
+        }
+    }
+}
+
+#[test]
+fn rect_outline_is_inset_by_thickness() {
+    let (vertices, _) = Shape2D::Rect { width: 2.0, height: 1.0 }.build(Style2D::Outline { thickness: 0.1 });
+    // Outer loop first, then the inner loop.
+    let inner: Vec<[f32; 2]> = vertices[4..].iter().map(|v| [v.position[0], v.position[1]]).collect();
+    for (got, want) in inner.iter().zip([[-0.9, -0.4], [0.9, -0.4], [0.9, 0.4], [-0.9, 0.4]]) {
+        assert!((got[0] - want[0]).abs() < EPS && (got[1] - want[1]).abs() < EPS, "{got:?} != {want:?}");
+    }
+}
+
+#[test]
+fn ring_leaves_a_hole_and_outlines_both_edges() {
+    let ring = Shape2D::Ring { inner_radius: 0.5, outer_radius: 1.0, segments: 16 };
+    let (vertices, indices) = ring.build(Style2D::Fill);
+    assert!(vertices.iter().all(|v| v.position[0].hypot(v.position[1]) > 0.5 - EPS));
+    assert_eq!((vertices.len(), indices.len()), (32, 16 * 6));
+
+    let (vertices, indices) = ring.build(Style2D::Outline { thickness: 0.1 });
+    assert_eq!((vertices.len(), indices.len()), (64, 2 * 16 * 6));
+    let radius = |v: &Vertex| v.position[0].hypot(v.position[1]);
+    assert!(vertices.iter().all(|v| radius(v) > 0.5 - EPS && radius(v) < 1.0 + EPS));
+}
+
+#[test]
+fn fill_counts_and_uvs() {
+    // A fan: the centre plus one vertex per perimeter point.
+    let (vertices, indices) = Shape2D::RegularPolygon { radius: 1.0, sides: 5 }.build(Style2D::Fill);
+    assert_eq!((vertices.len(), indices.len()), (6, 15));
+    let (vertices, _) = Shape2D::RoundedRect { width: 2.0, height: 2.0, radius: 0.5, segments: 3 }.build(Style2D::Fill);
+    assert_eq!(vertices.len(), 1 + 4 * 4);
+
+    let (vertices, _) = Shape2D::Rect { width: 4.0, height: 2.0 }.build(Style2D::Fill);
+    assert_eq!(vertices[0].uv, [0.5, 0.5]);
+    assert_eq!(vertices[1].uv, [0.0, 1.0]); // bottom-left
+    assert_eq!(vertices[3].uv, [1.0, 0.0]); // top-right
+}
+
+#[test]
+fn fully_rounded_rect_is_a_capsule_outline() {
+    // Radius clamps to half the height; the short sides collapse, so the
+    // four arcs of 5 points share 2 of them.
+    let shape = Shape2D::RoundedRect { width: 4.0, height: 2.0, radius: 5.0, segments: 4 };
+    assert_eq!(shape.perimeter().len(), 4 * 5 - 2);
+    let (vertices, _) = Shape2D::RoundedRect { width: 2.0, height: 2.0, radius: 1.0, segments: 4 }.build(Style2D::Fill);
+    assert_eq!(vertices.len(), 1 + 16);
+}
+
+#[test]
+fn nine_slice_keeps_its_borders_when_resized() {
+    let column = |panel: NineSlice| {
+        let (vertices, indices) = panel.build();
+        assert_eq!((vertices.len(), indices.len()), (16, 9 * 6));
+        // The top row of the 4 × 4 vertex grid, left to right.
+        vertices[..4].iter().map(|v| (v.position[0], v.uv[0])).collect::<Vec<_>>()
+    };
+    let small = column(NineSlice::new(100.0, 50.0, 10.0, 0.25));
+    let wide = column(NineSlice::new(300.0, 50.0, 10.0, 0.25));
+    assert_eq!(small, [(-50.0, 0.0), (-40.0, 0.25), (40.0, 0.75), (50.0, 1.0)]);
+    assert_eq!(wide, [(-150.0, 0.0), (-140.0, 0.25), (140.0, 0.75), (150.0, 1.0)]);
+
+    let (vertices, indices) = NineSlice::new(100.0, 50.0, 10.0, 0.25).with_border([10.0, 5.0, 20.0, 15.0]).build();
+    let ys: Vec<f32> = vertices.iter().step_by(4).map(|v| v.position[1]).collect();
+    assert_eq!(ys, [25.0, 20.0, -10.0, -25.0], "top row first, at +Y");
+    for tri in indices.chunks(3) {
+        let [a, b, c] = [0, 1, 2].map(|k| vertices[tri[k] as usize].position);
+        let area = (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0]);
+        assert!(area > 0.0, "triangle {tri:?} is not counter-clockwise");
+    }
+}
+
+#[test]
+fn nine_slice_shrinks_borders_that_do_not_fit() {
+    // 30 + 10 of border in a 20-wide panel scale to 15 + 5.
+    let (vertices, _) = NineSlice::new(20.0, 50.0, 0.0, 0.25).with_border([30.0, 0.0, 10.0, 0.0]).build();
+    let xs: Vec<f32> = vertices[..4].iter().map(|v| v.position[0]).collect();
+    assert_eq!(xs, [-10.0, 5.0, 5.0, 10.0]);
+}
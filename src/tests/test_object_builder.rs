@@ -0,0 +1,64 @@
+//! Tests for the fluent object builder.
+//!
+//! Coverage:
+//!   - unset fields keep the `Object::default` values
+//!   - every setter lands in the built object
+//!   - `spawn_built` parents the object and applies the usage hint
+//!     (skipped without a software adapter)
+
+use crate::camera::Camera;
+use crate::constants::layers;
+use crate::geometry::Geometry;
+use crate::mesh::MeshUsage;
+use crate::objects::Object;
+use crate::tests::golden;
+use crate::transform::Transform;
+
+#[test]
+fn defaults_match_object_default() {
+    let built = Object::builder().build();
+    let default = Object::default();
+    assert_eq!(built.name, default.name);
+    assert_eq!(built.transform, Transform::default());
+    assert_eq!((built.geometry, built.color, built.layer), (None, [1.0; 4], layers::DEFAULT));
+    assert!(!built.str_id.is_empty());
+    assert_ne!(built.str_id, Object::builder().build().str_id, "fresh UUID per builder");
+    assert_eq!(Object::builder().mesh_usage(), MeshUsage::Dynamic);
+}
+
+#[test]
+fn setters_fill_the_object() {
+    let object: Object = Object::builder()
+        .name("crate")
+        .str_id("crate_01")
+        .geometry(Geometry::Cube { size: 1.0 })
+        .color([0.5, 0.25, 0.0, 1.0])
+        .texture("wood.png")
+        .at(1.0, 2.0, 3.0)
+        .rotated([0.0, 45.0, 0.0])
+        .scaled([2.0; 3])
+        .layer(layers::UI)
+        .into();
+    assert_eq!((object.name.as_str(), object.str_id.as_str()), ("crate", "crate_01"));
+    assert_eq!(object.geometry, Some(Geometry::Cube { size: 1.0 }));
+    assert_eq!(object.color, [0.5, 0.25, 0.0, 1.0]);
+    assert_eq!(object.texture_path.as_deref(), Some("wood.png"));
+    assert_eq!(object.transform, Transform { position: [1.0, 2.0, 3.0], rotation: [0.0, 45.0, 0.0], scale: [2.0; 3] });
+    assert_eq!(object.layer, layers::UI);
+
+    let moved = Object::builder().at(1.0, 2.0, 3.0).transform(Transform::from_position(4.0, 0.0, 0.0)).build();
+    assert_eq!(moved.transform.position, [4.0, 0.0, 0.0], "later calls win");
+}
+
+#[test]
+fn spawn_built_applies_parent_and_usage() {
+    let Some(mut scene) = golden::headless_scene(8, 8, Camera::new()) else { return };
+    let root = scene.spawn_built(Object::builder().name("root"), None);
+    let child = scene.spawn_built(
+        Object::builder().name("crate").geometry(Geometry::Cube { size: 1.0 }).usage(MeshUsage::Static),
+        Some(root),
+    );
+    assert_eq!(scene.world.objects.get(&child).and_then(|obj| obj.parent), Some(root));
+    assert_eq!(scene.static_batches.usage(child), MeshUsage::Static);
+    assert_eq!(scene.static_batches.usage(root), MeshUsage::Dynamic);
+}
@@ -0,0 +1,133 @@
+//! Unit tests for hardware occlusion queries.
+//!
+//! These tests exercise:
+//! - Query boxes enclose the transformed bounds with padding
+//! - Results mark flagged objects occluded and unflagging clears them
+//! - Hidden objects are skipped the frame after their query, and return
+//!   once uncovered
+//! - Objects whose box holds the camera are never hidden
+
+use crate::camera::Camera;
+use crate::geometry::Geometry;
+use crate::math::Matrix4;
+use crate::objects::Object;
+use crate::occlusion::{self, OcclusionBox, QueriedObjects};
+use crate::pipeline::RenderStats;
+use crate::scene::Scene;
+use crate::tests::golden;
+use crate::transform::Transform;
+
+fn cube(size: f32, color: [f32; 4], z: f32) -> Object {
+    Object {
+        geometry: Some(Geometry::Cube { size }),
+        color,
+        transform: Transform::from_position(0.0, 0.0, z),
+        ..Default::default()
+    }
+}
+
+/// Render a frame and wait for its query results.
+fn frame(scene: &mut Scene) -> (RenderStats, golden::Frame) {
+    let stats = scene.draw_world().unwrap();
+    let frame = golden::read_back(&scene.pipeline);
+    let _ = scene.pipeline.device.poll(wgpu::PollType::wait_indefinitely());
+    (stats, frame)
+}
+
+fn center_is_red((_, frame): &(RenderStats, golden::Frame)) -> bool {
+    let at = (16 * 32 + 16) * 4;
+    let [r, g, b] = [0, 1, 2].map(|k| u32::from(frame.pixels[at + k]));
+    r > 100 && r > g * 2 && r > b * 2
+}
+
+#[test]
+fn boxes_enclose_transformed_bounds() {
+    let model = Transform { position: [5.0, 0.0, 0.0], rotation: [0.0, 0.0, 90.0], ..Transform::default() }.to_matrix();
+    let bbox = OcclusionBox::from_local(7, ([-1.0, -2.0, -0.5], [1.0, 2.0, 0.5]), &model);
+    assert_eq!(bbox.key, 7);
+    // Rotated a quarter turn about Z: 4 wide, 2 tall, padded by 1 % of 4.
+    for (k, half) in [2.0, 1.0, 0.5].into_iter().enumerate() {
+        let center = [5.0, 0.0, 0.0][k];
+        assert!((bbox.max[k] - (center + half + 0.04)).abs() < 1e-4, "{bbox:?}");
+        assert!((bbox.min[k] - (center - half - 0.04)).abs() < 1e-4, "{bbox:?}");
+    }
+    assert!(bbox.contains([5.0, 0.0, 0.0]));
+    assert!(!bbox.contains([5.0, 1.5, 0.0]));
+    assert_eq!(bbox.corners()[0], bbox.min);
+    assert_eq!(bbox.corners()[7], bbox.max);
+
+    let a = Some(([0.0, 0.0, 0.0], [1.0, 1.0, 1.0]));
+    let b = Some(([-1.0, 0.5, 0.5], [0.5, 2.0, 0.5]));
+    assert_eq!(occlusion::union(a, b), Some(([-1.0, 0.0, 0.0], [1.0, 2.0, 1.0])));
+    assert_eq!(occlusion::union(None, b), b);
+    let identity = OcclusionBox::from_local(0, ([0.0; 3], [0.0; 3]), &Matrix4::identity());
+    assert_eq!((identity.min, identity.max), ([0.0; 3], [0.0; 3]));
+}
+
+#[test]
+fn results_track_flagged_objects() {
+    let mut queried = QueriedObjects::default();
+    queried.set(1, true);
+    queried.set(2, true);
+    queried.apply(&[(1, false), (2, true), (3, false)]);
+    assert!(queried.occluded.contains(&1));
+    assert!(!queried.occluded.contains(&2));
+    assert!(!queried.occluded.contains(&3), "unflagged objects are never hidden");
+
+    queried.apply(&[(1, true)]);
+    assert!(queried.occluded.is_empty());
+    queried.apply(&[(1, false), (2, false)]);
+    queried.set(1, false);
+    queried.prune(|id| id != 2);
+    assert!(queried.ids.is_empty() && queried.occluded.is_empty());
+}
+
+#[test]
+fn hidden_objects_skip_draws_until_uncovered() {
+    let camera = Camera { eye: [0.0, 0.0, -10.0], target: [0.0, 0.0, 0.0], ..Camera::new() };
+    let Some(mut scene) = golden::headless_scene(32, 32, camera) else { return };
+    let wall = scene.spawn(cube(6.0, [0.1, 0.1, 1.0, 1.0], 0.0), None);
+    let hidden = scene.spawn(cube(1.0, [1.0, 0.1, 0.1, 1.0], 6.0), None);
+    let mut in_front = cube(0.5, [0.1, 1.0, 0.1, 1.0], -5.0);
+    in_front.transform.position[0] = -1.5;
+    let seen = scene.spawn(in_front, None);
+    assert!(!scene.set_occlusion_query(999, true));
+    assert!(scene.set_occlusion_query(hidden, true) && scene.set_occlusion_query(seen, true));
+    assert!(scene.has_occlusion_query(hidden));
+
+    let first = frame(&mut scene);
+    assert!(!scene.is_occluded(hidden));
+    let second = frame(&mut scene);
+    assert!(scene.is_occluded(hidden));
+    assert!(!scene.is_occluded(seen));
+    assert!(second.0.triangle_count < first.0.triangle_count);
+
+    // Uncovered: drawn again once the next query comes back.
+    scene.world.objects.get_mut(&wall).unwrap().transform.position[1] = 20.0;
+    frame(&mut scene);
+    let uncovered = frame(&mut scene);
+    assert!(!scene.is_occluded(hidden));
+    assert!(center_is_red(&uncovered));
+
+    // Unflagging clears the result, so the object is drawn again.
+    scene.world.objects.get_mut(&wall).unwrap().transform.position[1] = 0.0;
+    frame(&mut scene);
+    frame(&mut scene);
+    assert!(scene.is_occluded(hidden));
+    scene.set_occlusion_query(hidden, false);
+    assert!(!scene.is_occluded(hidden));
+}
+
+#[test]
+fn camera_inside_a_box_keeps_it_drawn() {
+    let camera = Camera { eye: [0.0, 0.0, -10.0], target: [0.0, 0.0, 0.0], ..Camera::new() };
+    let Some(mut scene) = golden::headless_scene(32, 32, camera) else { return };
+    scene.spawn(cube(6.0, [0.1, 0.1, 1.0, 1.0], 0.0), None);
+    // A room around the camera, behind the wall in its own box's terms.
+    let room = scene.spawn(cube(30.0, [1.0, 0.1, 0.1, 1.0], -10.0), None);
+    scene.set_occlusion_query(room, true);
+    for _ in 0..3 {
+        frame(&mut scene);
+        assert!(!scene.is_occluded(room));
+    }
+}
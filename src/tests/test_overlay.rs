@@ -0,0 +1,208 @@
+//! Tests for the pixel-space overlay (overlay.rs) and the screen pass.
+//!
+//! Coverage:
+//!   - `OverlayCamera` maps the window corners to clip space, Y down
+//!   - elements land on exact pixels with exact colours, over 3D geometry,
+//!     with and without HDR
+//!   - paint order follows the scene graph and consecutive procedural
+//!     elements share one draw call
+//!   - the overlay camera follows `Scene::resize`
+//!   - anchors and margins place elements, stretch anchors scale them, and
+//!     layouts follow the camera size and forget deleted elements
+//!   - with a scale factor, units are logical pixels and survive resizes
+//!   - registry meshes used only by overlay elements survive garbage
+//!     collection
+//!
+//! GPU tests are skipped without a software adapter.
+
+use crate::camera::Camera;
+use crate::geometry::Geometry;
+use crate::mesh::MeshData;
+use crate::object2d::{Shape2D, Style2D};
+use crate::objects::Object;
+use crate::overlay::{Anchor, Layout, Margins, Overlay, OverlayCamera};
+use crate::post::PostSettings;
+use crate::scene::Scene;
+use crate::tests::golden::{self, Frame};
+use crate::transform::Transform;
+
+fn pixel(frame: &Frame, x: u32, y: u32) -> [u8; 3] {
+    let i = ((y * frame.width + x) * 4) as usize;
+    [frame.pixels[i], frame.pixels[i + 1], frame.pixels[i + 2]]
+}
+
+/// A 32 × 32 scene whose view is filled by a white cube.
+fn white_wall_scene() -> Option<Scene> {
+    let mut scene = golden::headless_scene(32, 32, Camera::new().with_position([0.0, 0.0, -3.0]))?;
+    scene.spawn(Object { geometry: Some(Geometry::Cube { size: 4.0 }), ..Default::default() }, None);
+    Some(scene)
+}
+
+/// An overlay cube `size` pixels wide whose top-left corner is at `(x, y)`.
+fn square(x: f32, y: f32, size: f32, color: [f32; 4]) -> Object {
+    Object {
+        geometry: Some(Geometry::Cube { size }),
+        transform: Transform::from_position(x + size * 0.5, y + size * 0.5, 0.0),
+        color,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn camera_maps_pixel_corners_to_clip_space() {
+    let m = OverlayCamera::new(800.0, 600.0).view_projection();
+    let clip = |x, y| {
+        let [cx, cy, cz, _] = m.mul_vec4([x, y, 0.0, 1.0]);
+        [cx, cy, cz]
+    };
+    assert_eq!(clip(0.0, 0.0)[..2], [-1.0, 1.0]);
+    assert_eq!(clip(800.0, 600.0)[..2], [1.0, -1.0]);
+    assert_eq!(clip(400.0, 300.0)[..2], [0.0, 0.0]);
+    let depth = clip(0.0, 0.0)[2];
+    assert!(depth > 0.0 && depth < 1.0, "z = 0 is inside the depth range, got {depth}");
+}
+
+#[test]
+fn rect_lands_on_exact_pixels() {
+    let Some(mut scene) = white_wall_scene() else { return };
+    let (vertices, indices) = Shape2D::Rect { width: 8.0, height: 4.0 }.build(Style2D::Fill);
+    let rect = scene.register_mesh(&MeshData { vertices, indices, ..MeshData::new() });
+    // Covers pixels 2..10 × 2..6.
+    scene.overlay.world.spawn_object(Object {
+        mesh: Some(rect),
+        transform: Transform::from_position(6.0, 4.0, 0.0),
+        color: [1.0, 0.0, 0.0, 1.0],
+        ..Default::default()
+    }, None);
+    assert_eq!(scene.collect_mesh_garbage(), 0, "overlay elements keep their meshes");
+
+    for settings in [PostSettings::default(), PostSettings::default().with_hdr(true)] {
+        let hdr = settings.hdr;
+        scene.pipeline.set_post_settings(settings);
+        let frame = golden::render(&mut scene);
+        for (x, y) in [(2, 2), (9, 2), (2, 5), (9, 5)] {
+            assert_eq!(pixel(&frame, x, y), [255, 0, 0], "inside at ({x}, {y}), hdr {hdr}");
+        }
+        for (x, y) in [(1, 2), (10, 2), (2, 1), (2, 6)] {
+            assert_ne!(pixel(&frame, x, y), [255, 0, 0], "outside at ({x}, {y}), hdr {hdr}");
+        }
+    }
+}
+
+#[test]
+fn later_elements_paint_over_earlier_ones() {
+    let Some(mut scene) = golden::headless_scene(32, 32, Camera::new()) else { return };
+    let base = scene.draw_world().unwrap().draw_calls;
+
+    let red = scene.overlay.world.spawn_object(square(0.0, 0.0, 16.0, [1.0, 0.0, 0.0, 1.0]), None);
+    scene.overlay.world.spawn_object(square(8.0, 8.0, 16.0, [0.0, 0.0, 1.0, 1.0]), None);
+    // A child is painted after its parent even though it is further back.
+    // Parent-relative: centred at (2, 2), covering 0..4.
+    scene.overlay.world.spawn_object(
+        Object { transform: Transform::from_position(-6.0, -6.0, 50.0), ..square(0.0, 0.0, 4.0, [0.0, 1.0, 0.0, 1.0]) },
+        Some(red),
+    );
+
+    let frame = golden::render(&mut scene);
+    assert_eq!(pixel(&frame, 4, 4), [255, 0, 0]);
+    assert_eq!(pixel(&frame, 12, 12), [0, 0, 255], "blue was spawned after red");
+    assert_eq!(pixel(&frame, 20, 20), [0, 0, 255]);
+    assert_eq!(pixel(&frame, 1, 1), [0, 255, 0], "the child covers its parent");
+    // All three are procedural geometry without a texture: one batch.
+    assert_eq!(scene.draw_world().unwrap().draw_calls, base + 1);
+
+    // A registry mesh between them splits the batch.
+    let (vertices, indices) = Shape2D::Rect { width: 2.0, height: 2.0 }.build(Style2D::Fill);
+    let mesh = scene.register_mesh(&MeshData { vertices, indices, ..MeshData::new() });
+    scene.overlay.world.spawn_object(Object { mesh: Some(mesh), ..Default::default() }, Some(red));
+    assert_eq!(scene.draw_world().unwrap().draw_calls, base + 3);
+}
+
+#[test]
+fn overlay_camera_follows_resize() {
+    let Some(mut scene) = golden::headless_scene(32, 16, Camera::new()) else { return };
+    assert_eq!(scene.overlay.camera, OverlayCamera::new(32.0, 16.0));
+    scene.resize(64, 48);
+    assert_eq!(scene.overlay.camera, OverlayCamera::new(64.0, 48.0));
+}
+
+#[test]
+fn anchors_align_within_the_margins() {
+    let viewport = [200.0, 100.0];
+    let margins = Margins { left: 10.0, top: 5.0, right: 20.0, bottom: 15.0 };
+    let rect = |anchor| Layout::new(anchor, [40.0, 20.0]).with_margins(margins).rect(viewport);
+    assert_eq!(rect(Anchor::TopLeft), ([10.0, 5.0], [40.0, 20.0]));
+    assert_eq!(rect(Anchor::BottomRight), ([140.0, 65.0], [40.0, 20.0]));
+    // Centred in the 170 × 80 inset area.
+    assert_eq!(rect(Anchor::Center), ([75.0, 35.0], [40.0, 20.0]));
+    assert_eq!(rect(Anchor::Stretch), ([10.0, 5.0], [170.0, 80.0]));
+    assert_eq!(rect(Anchor::BottomStretch), ([10.0, 65.0], [170.0, 20.0]));
+    assert_eq!(rect(Anchor::RightStretch), ([140.0, 5.0], [40.0, 80.0]));
+    // Margins wider than the window collapse a stretch to nothing.
+    let squeezed = Layout::new(Anchor::Stretch, [1.0; 2]).with_margins(Margins::all(60.0));
+    assert_eq!(squeezed.rect([100.0, 100.0]).1, [0.0, 0.0]);
+}
+
+#[test]
+fn layout_follows_the_camera_size() {
+    let mut overlay = Overlay::new(200.0, 100.0);
+    let corner = overlay.world.spawn_object(Object::default(), None);
+    let bar = overlay.world.spawn_object(Object::default(), None);
+    overlay.set_layout(corner, Layout::new(Anchor::BottomRight, [20.0, 10.0]).with_margins(Margins::all(4.0)));
+    overlay.set_layout(bar, Layout::new(Anchor::TopStretch, [50.0, 8.0]));
+
+    overlay.apply_layout();
+    let transform = |overlay: &Overlay, id| overlay.world.objects[&id].transform.clone();
+    assert_eq!(transform(&overlay, corner).position, [186.0, 91.0, 0.0]);
+    assert_eq!(transform(&overlay, bar).position, [100.0, 4.0, 0.0]);
+    assert_eq!(transform(&overlay, bar).scale, [4.0, 1.0, 1.0]);
+
+    overlay.camera = OverlayCamera::new(400.0, 300.0);
+    overlay.apply_layout();
+    assert_eq!(transform(&overlay, corner).position, [386.0, 291.0, 0.0]);
+    assert_eq!(transform(&overlay, corner).scale, [1.0; 3], "point anchors keep the scale");
+    assert_eq!(transform(&overlay, bar).scale, [8.0, 1.0, 1.0]);
+
+    overlay.world.delete(bar);
+    overlay.apply_layout();
+    assert!(overlay.layout(bar).is_none());
+    assert!(overlay.remove_layout(corner));
+    assert!(overlay.layout(corner).is_none());
+}
+
+#[test]
+fn scale_factor_makes_units_logical_pixels() {
+    let Some(mut scene) = white_wall_scene() else { return };
+    scene.set_scale_factor(2.0);
+    // 4 × 2 logical pixels at (1, 1): physical pixels 2..10 × 2..6.
+    let (vertices, indices) = Shape2D::Rect { width: 4.0, height: 2.0 }.build(Style2D::Fill);
+    let rect = scene.register_mesh(&MeshData { vertices, indices, ..MeshData::new() });
+    scene.overlay.world.spawn_object(Object {
+        mesh: Some(rect),
+        transform: Transform::from_position(3.0, 2.0, 0.0),
+        color: [1.0, 0.0, 0.0, 1.0],
+        ..Default::default()
+    }, None);
+
+    let frame = golden::render(&mut scene);
+    for (x, y) in [(2, 2), (9, 2), (2, 5), (9, 5)] {
+        assert_eq!(pixel(&frame, x, y), [255, 0, 0], "inside at ({x}, {y})");
+    }
+    for (x, y) in [(1, 2), (10, 2), (2, 1), (2, 6)] {
+        assert_ne!(pixel(&frame, x, y), [255, 0, 0], "outside at ({x}, {y})");
+    }
+
+    scene.resize(64, 48);
+    assert_eq!(scene.scale_factor(), 2.0, "resizing keeps the scale factor");
+    assert_eq!(scene.overlay.camera.size(), [32.0, 24.0]);
+}
+
+#[test]
+fn layout_uses_logical_size() {
+    let mut overlay = Overlay::new(400.0, 200.0);
+    overlay.camera = overlay.camera.with_scale_factor(2.0);
+    let corner = overlay.world.spawn_object(Object::default(), None);
+    overlay.set_layout(corner, Layout::new(Anchor::BottomRight, [20.0, 10.0]));
+    overlay.apply_layout();
+    assert_eq!(overlay.world.objects[&corner].transform.position, [190.0, 95.0, 0.0]);
+}
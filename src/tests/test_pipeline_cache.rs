@@ -0,0 +1,92 @@
+//! Tests for render pipeline permutations.
+//!
+//! Coverage:
+//!   - the built-in keys differ only in depth and culling
+//!   - a key's pipeline is created once and reused (skipped without a
+//!     software adapter)
+//!   - added shaders and reloads give distinct pipelines for the same state
+//!   - a wireframe key compiles whether or not the device supports lines
+//!   - shader variants pass only non-default override constants and every
+//!     permutation of the scene shader validates
+
+use crate::pipeline_cache::{BlendMode, DepthMode, PipelineKey, ShaderId, ShaderVariant};
+use crate::shader::{SCENE_SHADER, ShaderPreprocessor};
+use crate::tests::golden;
+
+const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+fn scene_shader() -> String {
+    ShaderPreprocessor::new().process("shader.wgsl", SCENE_SHADER).unwrap()
+}
+
+#[test]
+fn builtin_keys() {
+    let scene = PipelineKey::scene(FORMAT);
+    assert_eq!(scene.depth, DepthMode::Test);
+    assert_eq!(scene.cull_mode, Some(wgpu::Face::Back));
+    assert_eq!(scene.blend, BlendMode::Alpha);
+    assert_eq!(scene.shader, ShaderId::SCENE);
+    assert_eq!(PipelineKey::overlay(FORMAT), scene.with_depth(DepthMode::Always).with_cull_mode(None));
+    assert_eq!(PipelineKey::screen(FORMAT), scene.with_depth(DepthMode::Disabled).with_cull_mode(None));
+}
+
+#[test]
+fn pipelines_are_reused_per_key() {
+    let Some(pipeline) = golden::headless_pipeline(8, 8) else { return };
+    let scene = PipelineKey::scene(FORMAT);
+    assert_eq!(pipeline.render_pipeline(scene), pipeline.render_pipeline(scene));
+    let additive = scene.with_blend(BlendMode::Additive);
+    assert_ne!(pipeline.render_pipeline(scene), pipeline.render_pipeline(additive));
+    assert_eq!(pipeline.render_pipeline(additive), pipeline.render_pipeline(additive));
+}
+
+#[test]
+fn shaders_select_distinct_pipelines() {
+    let Some(mut pipeline) = golden::headless_pipeline(8, 8) else { return };
+    let scene = PipelineKey::scene(FORMAT);
+    let custom = pipeline.add_shader("custom", &scene_shader());
+    assert_ne!(custom, ShaderId::SCENE);
+    assert_ne!(pipeline.render_pipeline(scene), pipeline.render_pipeline(scene.with_shader(custom)));
+
+    let before = pipeline.render_pipeline(scene);
+    pollster::block_on(pipeline.reload_shader(&scene_shader())).expect("built-in shader reloads");
+    assert_ne!(before, pipeline.render_pipeline(scene), "reloading replaces the scene pipelines");
+}
+
+#[test]
+fn wireframe_key_compiles() {
+    let Some(pipeline) = golden::headless_pipeline(8, 8) else { return };
+    let wireframe = PipelineKey::scene(FORMAT)
+        .with_polygon_mode(wgpu::PolygonMode::Line)
+        .with_cull_mode(None);
+    assert_ne!(pipeline.render_pipeline(wireframe), pipeline.render_pipeline(PipelineKey::scene(FORMAT)));
+}
+
+#[test]
+fn variant_constants() {
+    assert!(ShaderVariant::DEFAULT.constants().is_empty());
+    assert_eq!(ShaderVariant::DEFAULT.with_lit(false).constants(), [("LIT", 0.0)]);
+    assert_eq!(
+        ShaderVariant { lit: false, textured: false }.constants(),
+        [("LIT", 0.0), ("TEXTURED", 0.0)],
+    );
+    assert_eq!(PipelineKey::scene(FORMAT).variant, ShaderVariant::default());
+}
+
+#[test]
+fn scene_shader_variants_validate() {
+    let Some(pipeline) = golden::headless_pipeline(8, 8) else { return };
+    let scope = pipeline.device.push_error_scope(wgpu::ErrorFilter::Validation);
+    let scene = PipelineKey::scene(FORMAT);
+    let mut variants = Vec::new();
+    for lit in [true, false] {
+        for textured in [true, false] {
+            let variant = ShaderVariant { lit, textured };
+            variants.push(pipeline.render_pipeline(scene.with_variant(variant)));
+        }
+    }
+    assert!(pollster::block_on(scope.pop()).is_none(), "every variant compiles");
+    for (i, a) in variants.iter().enumerate() {
+        assert!(variants[i + 1..].iter().all(|b| a != b));
+    }
+}
@@ -0,0 +1,335 @@
+//! Tests for the post-processing stage (HDR target, exposure, tonemapping,
+//! FXAA, depth of field, bloom).
+//!
+//! Coverage:
+//!   - `PostSettings` / `AutoExposure` defaults and builders
+//!   - camera exposure scales the tonemapped image
+//!   - the fade is still drawn on top with HDR enabled
+//!   - auto exposure brightens a dark frame and darkens a bright one
+//!   - auto exposure still converges with a capture pass in the frame
+//!   - toggling HDR back off restores the direct-to-surface path
+//!   - FXAA blends jagged edges, alone and after the tonemapper
+//!   - depth of field blurs what is off the focus plane, and nothing at zero
+//!     aperture
+//!   - bloom spreads emissive light past its object, leaves light under
+//!     the threshold alone, and runs only with HDR
+//!
+//! GPU tests are skipped without a software adapter.
+
+use crate::camera::Camera;
+use crate::constants::post;
+use crate::geometry::Geometry;
+use crate::material::Material;
+use crate::minimap::Minimap;
+use crate::objects::Object;
+use crate::overlay::{Anchor, Layout};
+use crate::post::{AutoExposure, Bloom, PostSettings};
+use crate::scene::Scene;
+use crate::tests::golden::{self, Frame};
+use crate::transform::Transform;
+
+/// Mean of the RGB channels over the whole frame, in `0..=255`.
+fn mean(frame: &Frame) -> f32 {
+    let sum: u64 = frame.pixels.chunks(4).flat_map(|p| &p[..3]).map(|&c| c as u64).sum();
+    sum as f32 / (frame.pixels.len() / 4 * 3) as f32
+}
+
+/// A scene whose whole view is filled by the near face of a white cube.
+fn white_wall_scene() -> Option<Scene> {
+    let mut scene = golden::headless_scene(32, 32, Camera::new().with_position([0.0, 0.0, -3.0]))?;
+    scene.spawn(Object { geometry: Some(Geometry::Cube { size: 4.0 }), ..Default::default() }, None);
+    Some(scene)
+}
+
+fn hdr() -> PostSettings {
+    PostSettings::default().with_hdr(true)
+}
+
+#[test]
+fn settings_defaults() {
+    let settings = PostSettings::default();
+    assert!(!settings.hdr);
+    assert_eq!(settings.auto_exposure, None);
+    assert!(!settings.fxaa);
+    assert!(!settings.depth_of_field);
+    assert_eq!(settings.bloom, None);
+    assert_eq!(Bloom::default().threshold, post::BLOOM_THRESHOLD);
+    assert!(PostSettings::default().with_fxaa(true).fxaa);
+
+    let auto = AutoExposure::default();
+    assert_eq!(auto.key, post::EXPOSURE_KEY);
+    assert!(auto.min_log_luminance < auto.max_log_luminance);
+
+    let settings = hdr().with_auto_exposure(Some(auto));
+    assert!(settings.hdr);
+    assert_eq!(settings.auto_exposure, Some(auto));
+    assert_eq!(Camera::new().exposure, 1.0);
+}
+
+#[test]
+fn exposure_scales_tonemapped_frame() {
+    let Some(mut scene) = white_wall_scene() else { return };
+    scene.pipeline.set_post_settings(hdr());
+
+    scene.camera.exposure = 0.0;
+    let black = golden::render(&mut scene);
+    assert_eq!(mean(&black), 0.0, "zero exposure tonemaps to black");
+
+    scene.camera.exposure = 0.25;
+    let dim = mean(&golden::render(&mut scene));
+    scene.camera.exposure = 1.0;
+    let bright = mean(&golden::render(&mut scene));
+    assert!(dim > 0.0 && bright > dim + 20.0, "dim {dim}, bright {bright}");
+}
+
+#[test]
+fn fade_draws_over_tonemapped_frame() {
+    let Some(mut scene) = white_wall_scene() else { return };
+    scene.pipeline.set_post_settings(hdr());
+    scene.pipeline.set_fade([0.0, 0.0, 1.0, 1.0]);
+    let frame = golden::render(&mut scene);
+    assert!(frame.pixels.chunks(4).all(|p| p[..3] == [0, 0, 255]));
+}
+
+#[test]
+fn auto_exposure_adapts_to_frame_brightness() {
+    let Some(mut scene) = white_wall_scene() else { return };
+    if !scene.pipeline.supports_auto_exposure() {
+        eprintln!("skipping auto exposure test: no compute support");
+        return;
+    }
+    scene.pipeline.set_post_settings(hdr());
+    let manual = mean(&golden::render(&mut scene));
+
+    scene.pipeline.set_post_settings(hdr().with_auto_exposure(Some(AutoExposure::default())));
+    let auto = mean(&golden::render(&mut scene));
+    assert!(auto < manual - 20.0, "a white frame is exposed down: manual {manual}, auto {auto}");
+
+    // An empty scene shows only the dark clear colour and is exposed up.
+    scene.world = crate::world::World::new();
+    scene.pipeline.set_post_settings(hdr());
+    let dark_manual = mean(&golden::render(&mut scene));
+    scene.pipeline.set_post_settings(hdr().with_auto_exposure(Some(AutoExposure { speed: 1e6, ..Default::default() })));
+    golden::render(&mut scene);
+    let dark_auto = mean(&golden::render(&mut scene));
+    assert!(dark_auto > dark_manual + 20.0, "manual {dark_manual}, auto {dark_auto}");
+
+    // Camera exposure compensates on top of the adapted value.
+    scene.camera.exposure = 0.25;
+    let compensated = mean(&golden::render(&mut scene));
+    assert!(compensated < dark_auto, "auto {dark_auto}, compensated {compensated}");
+}
+
+#[test]
+fn auto_exposure_converges_with_a_capture_pass() {
+    let Some(mut settled) = white_wall_scene() else { return };
+    if !settled.pipeline.supports_auto_exposure() {
+        eprintln!("skipping auto exposure test: no compute support");
+        return;
+    }
+    let instant = AutoExposure { speed: 1e6, ..Default::default() };
+    settled.pipeline.set_post_settings(hdr().with_auto_exposure(Some(instant)));
+    golden::render(&mut settled);
+    let at = |frame: &Frame| frame.pixels[(24 * 32 + 24) * 4];
+    let target = at(&golden::render(&mut settled));
+
+    // Settle on an empty scene, then show the wall and let the exposure
+    // follow at a modest speed. The minimap renders just before the main
+    // pass every frame; the main pass must still adapt over the whole
+    // frame interval.
+    let mut scene = golden::headless_scene(32, 32, Camera::new().with_position([0.0, 0.0, -3.0])).unwrap();
+    scene.overlay.minimap = Some(Minimap::default().with_layout(Layout::new(Anchor::TopLeft, [4.0, 4.0])));
+    scene.pipeline.set_post_settings(hdr().with_auto_exposure(Some(instant)));
+    golden::render(&mut scene);
+    golden::render(&mut scene);
+    scene.spawn(Object { geometry: Some(Geometry::Cube { size: 4.0 }), ..Default::default() }, None);
+    scene.pipeline.set_post_settings(hdr().with_auto_exposure(Some(AutoExposure { speed: 20.0, ..Default::default() })));
+    let mut frame = golden::render(&mut scene);
+    for _ in 0..8 {
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        frame = golden::render(&mut scene);
+    }
+    assert!(at(&frame).abs_diff(target) <= 4, "settled {target}, with capture {}", at(&frame));
+}
+
+#[test]
+fn disabling_hdr_restores_direct_path() {
+    let Some(mut scene) = white_wall_scene() else { return };
+    let before = golden::render(&mut scene);
+    scene.pipeline.set_post_settings(hdr());
+    let stats = scene.draw_world().unwrap();
+    assert_eq!(stats.draw_calls, 2, "scene draw + tonemap");
+    scene.pipeline.set_post_settings(PostSettings::default());
+    let after = golden::render(&mut scene);
+    assert_eq!(before.pixels, after.pixels);
+}
+
+/// A cube turned so its silhouette is diagonal across the frame.
+fn tilted_cube_scene() -> Option<Scene> {
+    let mut scene = golden::headless_scene(48, 48, Camera::new().with_position([0.0, 0.0, -4.0]))?;
+    scene.spawn(
+        Object {
+            geometry: Some(Geometry::Cube { size: 1.5 }),
+            color: [1.0, 1.0, 1.0, 1.0],
+            transform: Transform { rotation: [0.0, 0.0, 30.0], ..Default::default() },
+            ..Default::default()
+        },
+        None,
+    );
+    Some(scene)
+}
+
+/// Pixels that are neither the background nor the lit cube face.
+fn blended_pixels(frame: &Frame) -> usize {
+    let background = frame.pixels[..4].to_vec();
+    let face = {
+        let mid = (frame.height / 2 * frame.width + frame.width / 2) as usize * 4;
+        frame.pixels[mid..mid + 4].to_vec()
+    };
+    let near = |p: &[u8], q: &[u8]| p[..3].iter().zip(&q[..3]).all(|(a, b)| a.abs_diff(*b) <= 4);
+    frame.pixels.chunks(4).filter(|p| !near(p, &background) && !near(p, &face)).count()
+}
+
+#[test]
+fn fxaa_blends_jagged_edges() {
+    let Some(mut scene) = tilted_cube_scene() else { return };
+    let aliased = golden::render(&mut scene);
+    scene.pipeline.set_post_settings(PostSettings::default().with_fxaa(true));
+    let smoothed = golden::render(&mut scene);
+    let (before, after) = (blended_pixels(&aliased), blended_pixels(&smoothed));
+    assert!(after > before + 20, "edge pixels: aliased {before}, fxaa {after}");
+
+    // Flat regions pass through untouched.
+    assert_eq!(aliased.pixels[..4], smoothed.pixels[..4]);
+
+    scene.pipeline.set_post_settings(PostSettings::default());
+    assert_eq!(golden::render(&mut scene).pixels, aliased.pixels);
+}
+
+#[test]
+fn fxaa_runs_after_tonemap_and_before_fade() {
+    let Some(mut scene) = tilted_cube_scene() else { return };
+    scene.pipeline.set_post_settings(hdr().with_fxaa(true));
+    let stats = scene.draw_world().unwrap();
+    assert_eq!(stats.draw_calls, 3, "scene draw + tonemap + fxaa");
+
+    scene.pipeline.set_fade([1.0, 0.0, 0.0, 1.0]);
+    let frame = golden::render(&mut scene);
+    assert!(frame.pixels.chunks(4).all(|p| p[..3] == [255, 0, 0]));
+}
+
+/// A small cube close to the camera on the left and a large one far away on
+/// the right.
+fn near_and_far_scene() -> Option<Scene> {
+    let mut scene = golden::headless_scene(64, 32, Camera::new().with_position([0.0, 0.0, -4.0]))?;
+    scene.camera.target = [0.0, 0.0, 0.0];
+    for (position, size) in [([-0.5, 0.0, -2.0], 0.4), ([5.0, 0.0, 16.0], 4.0)] {
+        scene.spawn(
+            Object {
+                geometry: Some(Geometry::Cube { size }),
+                transform: Transform { position, ..Default::default() },
+                ..Default::default()
+            },
+            None,
+        );
+    }
+    Some(scene)
+}
+
+/// Count of pixels differing between `a` and `b` in the left and right
+/// halves of the frame.
+fn changed_by_half(a: &Frame, b: &Frame) -> (usize, usize) {
+    let mut halves = (0, 0);
+    for (i, (p, q)) in a.pixels.chunks(4).zip(b.pixels.chunks(4)).enumerate() {
+        if p[..3].iter().zip(&q[..3]).any(|(x, y)| x.abs_diff(*y) > 8) {
+            if (i as u32 % a.width) < a.width / 2 { halves.0 += 1 } else { halves.1 += 1 }
+        }
+    }
+    halves
+}
+
+#[test]
+fn depth_of_field_blurs_off_focus_plane() {
+    let Some(mut scene) = near_and_far_scene() else { return };
+    let sharp = golden::render(&mut scene);
+    scene.pipeline.set_post_settings(PostSettings::default().with_depth_of_field(true));
+
+    scene.camera.focus_distance = 2.0;
+    let (near, far) = changed_by_half(&sharp, &golden::render(&mut scene));
+    assert!(far > near + 20, "focused near: near changed {near}, far changed {far}");
+
+    scene.camera.focus_distance = 20.0;
+    let (near, far) = changed_by_half(&sharp, &golden::render(&mut scene));
+    assert!(near > far + 20, "focused far: near changed {near}, far changed {far}");
+
+    scene.camera.aperture = 0.0;
+    assert_eq!(golden::render(&mut scene).pixels, sharp.pixels, "zero aperture keeps the frame sharp");
+}
+
+#[test]
+fn depth_of_field_runs_before_tonemap() {
+    let Some(mut scene) = near_and_far_scene() else { return };
+    scene.pipeline.set_post_settings(hdr().with_depth_of_field(true).with_fxaa(true));
+    let stats = scene.draw_world().unwrap();
+    assert_eq!(stats.draw_calls, 5, "batched scene draw + two blur passes + tonemap + fxaa");
+
+    scene.pipeline.set_post_settings(PostSettings::default().with_depth_of_field(true));
+    scene.pipeline.set_fade([0.0, 1.0, 0.0, 1.0]);
+    let frame = golden::render(&mut scene);
+    assert!(frame.pixels.chunks(4).all(|p| p[..3] == [0, 255, 0]), "fade drawn after a direct blur");
+}
+
+/// A small cube in the middle of the view, emitting `strength` white.
+fn glowing_cube_scene(strength: f32) -> Option<Scene> {
+    let camera = Camera { eye: [0.0, 0.0, -6.0], target: [0.0, 0.0, 0.0], ..Camera::new() };
+    let mut scene = golden::headless_scene(64, 64, camera)?;
+    scene.spawn(
+        Object {
+            geometry: Some(Geometry::Cube { size: 0.5 }),
+            color: [0.0, 0.0, 0.0, 1.0],
+            material: Material::default().with_emission([1.0; 3], strength),
+            ..Default::default()
+        },
+        None,
+    );
+    Some(scene)
+}
+
+/// Mean brightness of a ring of pixels around the centre of a 64×64 frame,
+/// outside the glowing cube.
+fn halo(frame: &Frame) -> f32 {
+    let ring = [(32, 20), (32, 44), (20, 32), (44, 32)];
+    let sum: u32 = ring.iter().flat_map(|&(x, y)| &frame.pixels[(y * 64 + x) * 4..][..3]).map(|&c| c as u32).sum();
+    sum as f32 / 12.0
+}
+
+#[test]
+fn bloom_spreads_emissive_light() {
+    let Some(mut scene) = glowing_cube_scene(8.0) else { return };
+    scene.pipeline.set_post_settings(hdr());
+    let plain = golden::render(&mut scene);
+    scene.pipeline.set_post_settings(hdr().with_bloom(Some(Bloom::default())));
+    let stats = scene.draw_world().unwrap();
+    assert_eq!(stats.draw_calls, 5, "batched scene draw + threshold + two blur passes + tonemap");
+    let bloomed = golden::render(&mut scene);
+    assert!(halo(&bloomed) > halo(&plain) + 10.0, "halo {} -> {}", halo(&plain), halo(&bloomed));
+
+    // A stronger glow reaches further.
+    let Some(mut dim) = glowing_cube_scene(2.0) else { return };
+    dim.pipeline.set_post_settings(hdr().with_bloom(Some(Bloom::default())));
+    assert!(halo(&golden::render(&mut dim)) < halo(&bloomed));
+}
+
+#[test]
+fn bloom_ignores_light_under_threshold() {
+    let Some(mut scene) = glowing_cube_scene(0.8) else { return };
+    scene.pipeline.set_post_settings(hdr());
+    let plain = golden::render(&mut scene);
+    scene.pipeline.set_post_settings(hdr().with_bloom(Some(Bloom::default())));
+    let bloomed = golden::render(&mut scene);
+    assert_eq!(golden::compare(&bloomed.pixels, &plain.pixels, 1).mismatched_pixels, 0);
+
+    // Without HDR the frame never exceeds the threshold, so bloom is off.
+    scene.pipeline.set_post_settings(PostSettings::default().with_bloom(Some(Bloom::default())));
+    assert_eq!(scene.draw_world().unwrap().draw_calls, 1);
+}
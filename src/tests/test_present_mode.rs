@@ -0,0 +1,85 @@
+//! Tests for present-mode selection, surface events and render errors.
+//!
+//! Coverage:
+//!   - requested modes resolve along the documented fallback chains
+//!   - explicit present modes override vsync
+//!   - render errors describe themselves
+//!
+//! GPU tests (skipped without a software adapter):
+//!   - headless pipelines record the requested mode, and surface events
+//!     drain oldest first within the queue cap
+//!   - headless frames never fail
+
+use crate::camera::Camera;
+use crate::constants;
+use crate::pipeline::{resolve_present_mode, PipelineConfig, RenderError, SurfaceEvent, SurfaceStatus};
+use crate::tests::golden;
+use wgpu::PresentMode::{AutoNoVsync, AutoVsync, Fifo, FifoRelaxed, Immediate, Mailbox};
+
+#[test]
+fn supported_modes_are_used_as_is() {
+    let all = [Fifo, FifoRelaxed, Immediate, Mailbox];
+    for mode in all {
+        assert_eq!(resolve_present_mode(mode, &all), mode);
+    }
+    assert_eq!(resolve_present_mode(Mailbox, &[]), Mailbox, "no surface");
+}
+
+#[test]
+fn unsupported_modes_follow_the_chain() {
+    assert_eq!(resolve_present_mode(Immediate, &[Fifo, Mailbox]), Mailbox);
+    assert_eq!(resolve_present_mode(Immediate, &[Fifo]), Fifo);
+    assert_eq!(resolve_present_mode(Mailbox, &[Fifo, Immediate]), Immediate);
+    assert_eq!(resolve_present_mode(FifoRelaxed, &[Fifo, Immediate]), Fifo);
+    assert_eq!(resolve_present_mode(AutoNoVsync, &[Fifo, Mailbox]), Mailbox);
+    assert_eq!(resolve_present_mode(AutoNoVsync, &[Fifo]), Fifo);
+    assert_eq!(resolve_present_mode(AutoVsync, &[Fifo, FifoRelaxed]), FifoRelaxed);
+    assert_eq!(resolve_present_mode(AutoVsync, &[Fifo, Immediate]), Fifo);
+}
+
+#[test]
+fn explicit_mode_overrides_vsync() {
+    assert_eq!(PipelineConfig::default().requested_present_mode(), AutoVsync);
+    assert_eq!(PipelineConfig::default().with_vsync(false).requested_present_mode(), AutoNoVsync);
+    let config = PipelineConfig::default().with_vsync(false).with_present_mode(Fifo);
+    assert_eq!(config.requested_present_mode(), Fifo);
+}
+
+#[test]
+fn render_errors_describe_themselves() {
+    let error: Box<dyn std::error::Error> = Box::new(RenderError::SurfaceLost);
+    assert_eq!(error.to_string(), "Surface lost");
+    assert_eq!(RenderError::OutOfMemory.to_string(), "Out of GPU memory");
+    assert!(RenderError::Timeout.to_string().contains("Timed out"));
+}
+
+#[test]
+fn headless_records_mode_and_queues_events() {
+    let Some(mut scene) = golden::headless_scene(8, 8, Camera::new()) else { return };
+    let pipeline = &mut scene.pipeline;
+    assert!(pipeline.drain_surface_events().is_empty());
+    assert_eq!(pipeline.set_present_mode(Mailbox), Mailbox);
+    assert_eq!(pipeline.present_mode(), Mailbox);
+    assert!(pipeline.drain_surface_events().is_empty(), "nothing to fall back from");
+
+    let event = |i| SurfaceEvent::Reconfigured {
+        reason: if i % 2 == 0 { SurfaceStatus::Outdated } else { SurfaceStatus::Lost },
+    };
+    let cap = constants::pipeline::MAX_SURFACE_EVENTS;
+    for i in 0..cap + 2 {
+        pipeline.push_surface_event(event(i));
+    }
+    let events = pipeline.drain_surface_events();
+    assert_eq!(events.len(), cap);
+    assert_eq!(events[0], event(2), "oldest are dropped first");
+    assert!(pipeline.drain_surface_events().is_empty());
+}
+
+#[test]
+fn headless_frames_never_fail() {
+    let Some(mut scene) = golden::headless_scene(8, 8, Camera::new()) else { return };
+    for _ in 0..3 {
+        assert!(scene.draw_world().is_ok());
+    }
+    assert!(scene.pipeline.drain_surface_events().is_empty());
+}
@@ -0,0 +1,75 @@
+//! Tests for ready-made objects.
+//!
+//! Coverage:
+//!   - preset builders carry their documented defaults
+//!   - the skybox box encloses the origin with the requested colours
+//!
+//! GPU tests (skipped without a software adapter):
+//!   - the spawn helpers add objects, register the axes mesh and apply the
+//!     static hint
+//!   - the skybox paints the background and is removed again with `None`
+
+use crate::camera::Camera;
+use crate::constants::presets as consts;
+use crate::geometry::Geometry;
+use crate::mesh::MeshUsage;
+use crate::presets;
+use crate::tests::golden;
+
+#[test]
+fn builders_carry_defaults() {
+    let ground = presets::ground_plane(10.0);
+    assert_eq!(ground.mesh_usage(), MeshUsage::Static);
+    let ground = ground.build();
+    assert_eq!(ground.geometry, Some(Geometry::Plane { size: 10.0, subdivisions: consts::GROUND_SUBDIVISIONS }));
+    assert_eq!(ground.color, consts::GROUND_COLOR);
+
+    let marker = presets::point_light_marker([1.0, 2.0, 3.0]).build();
+    assert_eq!(marker.transform.position, [1.0, 2.0, 3.0]);
+    assert_eq!(marker.color, consts::LIGHT_MARKER_COLOR);
+
+    let axes = presets::debug_axes_mesh(2.0);
+    let reach = axes.vertices.iter().map(|v| v.position[0]).fold(f32::MIN, f32::max);
+    assert!((reach - 2.0).abs() < 1e-4, "X arrow tip at the length, got {reach}");
+}
+
+#[test]
+fn skybox_encloses_origin() {
+    let (top, sides, bottom) = ([0.0, 0.0, 1.0, 1.0], [0.0, 1.0, 0.0, 1.0], [1.0, 0.0, 0.0, 1.0]);
+    let sky = presets::skybox_mesh(top, sides, bottom);
+    assert_eq!(sky.indices.len(), 6 * 6);
+    let s = consts::SKYBOX_HALF_EXTENT;
+    assert!(sky.vertices.iter().all(|v| v.position.iter().all(|c| c.abs() == s)));
+    let with_color = |color: [f32; 3]| sky.vertices.iter().filter(|v| v.color == color).collect::<Vec<_>>();
+    assert!(with_color([0.0, 0.0, 1.0]).iter().all(|v| v.position[1] == s), "top overhead");
+    assert!(with_color([1.0, 0.0, 0.0]).iter().all(|v| v.position[1] == -s), "bottom underfoot");
+    assert_eq!(with_color([0.0, 1.0, 0.0]).len(), 4 * with_color([0.0, 0.0, 1.0]).len(), "four walls");
+}
+
+#[test]
+fn spawn_helpers_populate_the_world() {
+    let Some(mut scene) = golden::headless_scene(8, 8, Camera::new()) else { return };
+    let ground = scene.spawn_ground_plane(20.0);
+    let marker = scene.spawn_point_light_marker([0.0, 3.0, 0.0]);
+    let axes = scene.spawn_debug_axes(1.0, Some(marker));
+    assert_eq!(scene.world.objects.len(), 3);
+    assert_eq!(scene.static_batches.usage(ground), MeshUsage::Static);
+    assert_eq!(scene.world.objects[&axes].parent, Some(marker));
+    let mesh = scene.world.objects[&axes].mesh.expect("axes use a registry mesh");
+    assert!(scene.mesh_registry.contains(mesh));
+    assert!(scene.draw_world().unwrap().triangle_count > 0);
+}
+
+#[test]
+fn skybox_paints_background() {
+    let Some(mut scene) = golden::headless_scene(8, 8, Camera::new()) else { return };
+    let empty = golden::render(&mut scene);
+    scene.spawn_skybox();
+    let sky = golden::render(&mut scene);
+    assert_ne!(sky.pixels, empty.pixels);
+    assert!(sky.pixels.chunks(4).all(|p| p[2] > p[0]), "blue-ish everywhere");
+    assert!(scene.gpu_memory().vertex_bytes > 0);
+
+    scene.set_skybox(None);
+    assert_eq!(golden::render(&mut scene).pixels, empty.pixels);
+}
@@ -0,0 +1,66 @@
+//! Tests for reading frames back from the GPU.
+//!
+//! Coverage (skipped without a software adapter):
+//!   - `read_depth` finds the surface under a pixel, reports the far plane
+//!     where nothing was drawn and rejects pixels outside the frame
+//!   - `read_pixels` returns a sub-rectangle of the full frame and rejects
+//!     rectangles that do not fit
+
+use crate::camera::Camera;
+use crate::geometry::Geometry;
+use crate::objects::Object;
+use crate::readback::PixelRect;
+use crate::scene::Scene;
+use crate::tests::golden;
+use crate::transform::Transform;
+
+/// A 32×32 scene looking down +z at a cube whose near face is at `z = -2`
+/// and covers the right half of the view.
+fn half_wall_scene() -> Option<Scene> {
+    let mut scene = golden::headless_scene(32, 32, Camera::new().with_position([0.0, 0.0, -3.0]))?;
+    scene.spawn(
+        Object {
+            geometry: Some(Geometry::Cube { size: 2.0 }),
+            transform: Transform::from_position(1.0, 0.0, -1.0),
+            ..Default::default()
+        },
+        None,
+    );
+    scene.draw_world().unwrap();
+    Some(scene)
+}
+
+#[test]
+fn read_depth_reconstructs_world_position() {
+    let Some(scene) = half_wall_scene() else { return };
+    let viewport = scene.viewport();
+    let depth = pollster::block_on(scene.pipeline.read_depth(24, 16)).expect("software adapters copy depth");
+    let point = scene.camera.screen_to_world([24.5, 16.5], depth, viewport).unwrap();
+    assert!((point[2] + 2.0).abs() < 0.01, "hit the near face, got {point:?}");
+
+    assert_eq!(pollster::block_on(scene.pipeline.read_depth(4, 16)), Some(1.0));
+    assert_eq!(pollster::block_on(scene.pipeline.read_depth(32, 0)), None);
+}
+
+#[test]
+fn read_pixels_returns_sub_rectangles() {
+    assert!(PixelRect::full(4, 4).fits(4, 4));
+    assert!(!PixelRect::new(2, 0, 3, 1).fits(4, 4));
+    assert!(!PixelRect::new(0, 0, 0, 1).fits(4, 4));
+    assert!(!PixelRect::new(u32::MAX, 0, 2, 1).fits(4, 4));
+
+    let Some(scene) = half_wall_scene() else { return };
+    let full = pollster::block_on(scene.pipeline.read_pixels(PixelRect::full(32, 32))).unwrap();
+    assert_eq!(full.len(), 32 * 32 * 4);
+    let rect = PixelRect::new(12, 5, 8, 3);
+    let part = pollster::block_on(scene.pipeline.read_pixels(rect)).unwrap();
+    for (row, pixels) in part.chunks(8 * 4).enumerate() {
+        let start = ((5 + row) * 32 + 12) * 4;
+        assert_eq!(pixels, &full[start..start + 8 * 4]);
+    }
+    // The wall is white, the background is not.
+    assert_eq!(full[(16 * 32 + 24) * 4..][..4], [255; 4]);
+    assert_ne!(full[(16 * 32 + 4) * 4..][..3], [255; 3]);
+
+    assert_eq!(pollster::block_on(scene.pipeline.read_pixels(PixelRect::new(30, 0, 4, 1))), None);
+}
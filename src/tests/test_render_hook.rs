@@ -0,0 +1,106 @@
+//! Tests for user render hooks.
+//!
+//! Coverage:
+//!   - hooks get distinct IDs and can be removed once
+//!
+//! GPU tests (skipped without a software adapter):
+//!   - stages run in frame order, hooks within a stage in registration
+//!     order, with the scene's resources
+//!   - the context describes each stage's target and the camera binding
+//!   - a pass cleared after the overlay replaces the frame; one cleared
+//!     before the scene is overwritten
+//!   - removed hooks stop running
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::camera::Camera;
+use crate::render_hook::{RenderContext, RenderHooks, RenderStage};
+use crate::tests::golden;
+
+/// Hook body clearing `view` to opaque red.
+fn clear_red(encoder: &mut wgpu::CommandEncoder, ctx: &RenderContext) {
+    encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view: ctx.view(),
+            resolve_target: None,
+            ops: wgpu::Operations { load: wgpu::LoadOp::Clear(wgpu::Color::RED), store: wgpu::StoreOp::Store },
+            depth_slice: None,
+        })],
+        ..Default::default()
+    });
+}
+
+fn is_red(frame: &golden::Frame) -> bool {
+    frame.pixels.chunks(4).all(|p| p == [255, 0, 0, 255])
+}
+
+#[test]
+fn hooks_are_added_and_removed() {
+    let mut hooks = RenderHooks::new();
+    let a = hooks.add(RenderStage::AfterScene, Box::new(|_, _| {}));
+    let b = hooks.add(RenderStage::AfterScene, Box::new(|_, _| {}));
+    assert_ne!(a, b);
+    assert_eq!(hooks.len(), 2);
+    assert!(hooks.remove(a));
+    assert!(!hooks.remove(a));
+    assert_eq!(hooks.len(), 1);
+}
+
+#[test]
+fn stages_run_in_frame_order() {
+    let Some(mut scene) = golden::headless_scene(8, 8, Camera::new()) else { return };
+    scene.insert_resource("tag");
+    let calls = Rc::new(RefCell::new(Vec::new()));
+    let stages = [RenderStage::AfterOverlay, RenderStage::AfterPost, RenderStage::AfterScene, RenderStage::BeforeScene];
+    for (i, stage) in stages.into_iter().enumerate() {
+        for j in 0..2 {
+            let calls = calls.clone();
+            scene.add_render_hook(stage, move |_, ctx| {
+                assert_eq!(ctx.stage(), stage);
+                assert_eq!(ctx.resources().get::<&str>(), Some(&"tag"));
+                calls.borrow_mut().push((3 - i, j));
+            });
+        }
+    }
+    scene.draw_world().unwrap();
+    let expected: Vec<_> = (0..4).flat_map(|i| [(i, 0), (i, 1)]).collect();
+    assert_eq!(*calls.borrow(), expected);
+}
+
+#[test]
+fn hooks_draw_into_the_frame() {
+    let Some(mut scene) = golden::headless_scene(8, 8, Camera::new()) else { return };
+    let id = scene.add_render_hook(RenderStage::AfterOverlay, clear_red);
+    assert!(is_red(&golden::render(&mut scene)));
+
+    assert!(scene.remove_render_hook(id));
+    assert!(!is_red(&golden::render(&mut scene)));
+
+    scene.add_render_hook(RenderStage::BeforeScene, clear_red);
+    assert!(!is_red(&golden::render(&mut scene)), "the scene pass clears after it");
+}
+
+#[test]
+fn context_describes_the_stage() {
+    let Some(mut scene) = golden::headless_scene(16, 8, Camera::new()) else { return };
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    for stage in [RenderStage::AfterScene, RenderStage::AfterOverlay] {
+        let seen = seen.clone();
+        scene.add_render_hook(stage, move |_, ctx| {
+            assert_eq!(ctx.size(), (16, 8));
+            assert_eq!(ctx.depth_view().texture().format(), wgpu::TextureFormat::Depth32Float);
+            // The engine's layouts slot into a hook's own pipelines.
+            let _layout = ctx.device().create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: None,
+                bind_group_layouts: &[Some(ctx.camera_bind_group_layout()), Some(ctx.texture_bind_group_layout())],
+                immediate_size: 0,
+            });
+            assert_eq!(ctx.camera_offset() % ctx.device().limits().min_uniform_buffer_offset_alignment, 0);
+            seen.borrow_mut().push(ctx.format());
+        });
+    }
+    scene.draw_world().unwrap();
+    let output = scene.pipeline.surface_config.format;
+    assert_eq!(*seen.borrow(), [output, output], "no post-processing: both stages draw to the output");
+}
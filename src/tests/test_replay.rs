@@ -0,0 +1,99 @@
+//! Tests for input recording and replay (replay.rs).
+//!
+//! Coverage:
+//!   - live winit events classify into `StepKind` / `InputEvent`
+//!   - replayable inputs rebuild into equivalent winit events; keys and
+//!     text do not
+//!   - IME commits classify as `InputEvent::Text`
+//!   - `Recording` save / load round-trip and newer-version rejection
+//!   - `Player` hands out one frame (up to `FrameEnd`) at a time
+//!
+//! Driving a real window through a replay needs a display, so the event
+//! loop itself is not covered here.
+
+use winit::event::Ime;
+use winit::keyboard::KeyCode;
+use winit::window::WindowId;
+use crate::event::{Event, MouseButton, WindowEvent};
+use crate::replay::{InputEvent, Player, Recording, StepKind, FORMAT_VERSION};
+use crate::tests::golden::TempFile;
+
+fn window_id() -> WindowId {
+    WindowId::dummy()
+}
+
+#[test]
+fn events_classify_into_steps() {
+    assert_eq!(StepKind::from_event(&Event::AboutToWait), StepKind::FrameEnd);
+    let redraw = Event::WindowEvent { window_id: window_id(), event: WindowEvent::RedrawRequested };
+    assert_eq!(StepKind::from_event(&redraw), StepKind::Redraw);
+    assert_eq!(StepKind::from_event(&Event::Resumed), StepKind::Other);
+    let focus = Event::WindowEvent { window_id: window_id(), event: WindowEvent::Focused(true) };
+    assert_eq!(StepKind::from_event(&focus), StepKind::Input(InputEvent::Focused(true)));
+}
+
+#[test]
+fn inputs_rebuild_into_winit_events() {
+    let inputs = [
+        InputEvent::MouseButton { button: MouseButton::Right, pressed: true },
+        InputEvent::CursorMoved { x: 12.5, y: 40.0 },
+        InputEvent::MouseWheel { dx: 0.0, dy: -1.0, pixels: false },
+        InputEvent::MouseWheel { dx: 3.0, dy: 4.0, pixels: true },
+        InputEvent::MouseMotion { dx: -2.0, dy: 1.5 },
+        InputEvent::Pinch { delta: 0.25 },
+        InputEvent::Touch { id: 7, phase: winit::event::TouchPhase::Moved, x: 3.0, y: 4.0 },
+        InputEvent::Focused(false),
+        InputEvent::ImePreedit { text: "にほん".into(), cursor: Some((9, 9)) },
+        InputEvent::ImeEnabled(true),
+    ];
+    for input in inputs {
+        let event = input.to_event(window_id()).expect("replayable");
+        assert_eq!(InputEvent::from_event(&event), Some(input));
+    }
+    let key = InputEvent::Key { code: KeyCode::KeyW, pressed: true, repeat: false };
+    assert!(key.to_event(window_id()).is_none(), "winit cannot build key events");
+    assert!(InputEvent::Text("a".into()).to_event(window_id()).is_none());
+}
+
+#[test]
+fn ime_commit_is_text() {
+    let commit = Event::<()>::WindowEvent {
+        window_id: window_id(),
+        event: WindowEvent::Ime(Ime::Commit("日本".into())),
+    };
+    assert_eq!(InputEvent::from_event(&commit), Some(InputEvent::Text("日本".into())));
+    assert_eq!(InputEvent::text_from_event(&commit), None, "only key presses carry extra text");
+}
+
+#[test]
+fn recording_roundtrips_through_a_file() {
+    let mut recording = Recording::new();
+    recording.push(0.016, StepKind::Input(InputEvent::Key { code: KeyCode::Space, pressed: true, repeat: false }));
+    recording.push(0.001, StepKind::Redraw);
+    recording.push(0.002, StepKind::FrameEnd);
+    recording.push(0.5, StepKind::Other);
+    assert!((recording.duration() - 0.519).abs() < 1e-6);
+
+    let file = TempFile::new("replay.json");
+    recording.save(&file.path).unwrap();
+    assert_eq!(Recording::load(&file.path).unwrap(), recording);
+
+    let newer = Recording { version: FORMAT_VERSION + 1, ..Recording::new() };
+    newer.save(&file.path).unwrap();
+    assert!(Recording::load(&file.path).is_err());
+}
+
+#[test]
+fn player_hands_out_one_frame_at_a_time() {
+    let mut recording = Recording::new();
+    for kind in [StepKind::Redraw, StepKind::Other, StepKind::FrameEnd, StepKind::Redraw, StepKind::FrameEnd, StepKind::Other] {
+        recording.push(0.01, kind);
+    }
+    let mut player = Player::new(recording);
+    let kinds = |steps: Vec<crate::replay::Step>| steps.into_iter().map(|s| s.kind).collect::<Vec<_>>();
+    assert_eq!(kinds(player.next_frame()), [StepKind::Redraw, StepKind::Other, StepKind::FrameEnd]);
+    assert_eq!(kinds(player.next_frame()), [StepKind::Redraw, StepKind::FrameEnd]);
+    assert!(!player.is_finished());
+    assert_eq!(kinds(player.next_frame()), [StepKind::Other], "a trailing partial frame still plays");
+    assert!(player.is_finished());
+}
@@ -0,0 +1,60 @@
+//! Unit tests for `Resources`.
+//!
+//! These tests exercise:
+//! - one value per type: insert replaces and returns the previous value
+//! - get / get_mut / remove / contains by type, and `get_or_insert_with`
+//! - the `Scene` wrappers, surviving a scene switch (skipped without a
+//!   software adapter)
+
+use crate::camera::Camera;
+use crate::resources::Resources;
+use crate::tests::golden;
+
+#[derive(Debug, PartialEq)]
+struct Score(u32);
+
+#[derive(Debug, PartialEq)]
+struct Config {
+    speed: f32,
+}
+
+#[test]
+fn one_value_per_type() {
+    let mut resources = Resources::new();
+    assert!(resources.is_empty());
+    assert_eq!(resources.insert(Score(1)), None);
+    assert_eq!(resources.insert(Config { speed: 2.0 }), None);
+    assert_eq!(resources.insert(Score(3)), Some(Score(1)));
+    assert_eq!(resources.len(), 2);
+
+    assert_eq!(resources.get::<Score>(), Some(&Score(3)));
+    resources.get_mut::<Config>().unwrap().speed = 4.0;
+    assert_eq!(resources.get::<Config>(), Some(&Config { speed: 4.0 }));
+    assert_eq!(resources.get::<u32>(), None);
+
+    assert_eq!(resources.remove::<Score>(), Some(Score(3)));
+    assert!(!resources.contains::<Score>());
+    assert!(resources.contains::<Config>());
+    resources.clear();
+    assert!(resources.is_empty());
+}
+
+#[test]
+fn get_or_insert_with_initialises_once() {
+    let mut resources = Resources::new();
+    resources.get_or_insert_with(|| Score(0)).0 += 1;
+    resources.get_or_insert_with(|| Score(100)).0 += 1;
+    assert_eq!(resources.get::<Score>(), Some(&Score(2)));
+}
+
+#[test]
+fn scene_resources_survive_switches() {
+    let Some(mut scene) = golden::headless_scene(8, 8, Camera::new()) else { return };
+    scene.insert_resource(Score(5));
+    scene.resource_mut::<Score>().unwrap().0 += 1;
+    scene.add_scene("other", crate::world::World::new(), Camera::new());
+    assert!(scene.switch_to("other"));
+    assert_eq!(scene.resource::<Score>(), Some(&Score(6)));
+    assert_eq!(scene.remove_resource::<Score>(), Some(Score(6)));
+    assert_eq!(scene.resource::<Score>(), None);
+}
@@ -0,0 +1,168 @@
+//! Tests for `SceneManager` and the scene-switching API on `Scene`.
+//!
+//! Coverage:
+//!   - parking, switching and re-parking scenes without a GPU
+//!   - fade transitions: opacity ramp, swap at the midpoint, completion
+//!   - meshes used only by a parked scene surviving garbage collection
+//!   - `Scene::switch_to_with_fade` drawing the fade overlay on a headless
+//!     pipeline (skipped without a software adapter)
+
+use crate::camera::Camera;
+use crate::constants::scene::MAIN_SCENE_ID;
+use crate::geometry::Geometry;
+use crate::mesh::{MeshData, Vertex};
+use crate::objects::Object;
+use crate::scene_manager::{FadePhase, SceneManager, SceneSlot};
+use crate::tests::golden;
+use crate::world::World;
+
+fn world_with(n: usize) -> World {
+    let mut world = World::new();
+    for _ in 0..n {
+        let cube = Object { geometry: Some(Geometry::Cube { size: 1.0 }), ..Default::default() };
+        world.spawn_object(cube, None);
+    }
+    world
+}
+
+#[test]
+fn starts_on_main_scene() {
+    let manager = SceneManager::new();
+    assert_eq!(manager.active(), MAIN_SCENE_ID);
+    assert!(manager.contains(MAIN_SCENE_ID));
+    assert_eq!(manager.parked_ids().count(), 0);
+}
+
+#[test]
+fn switch_swaps_active_and_parked() {
+    let mut manager = SceneManager::new();
+    let mut active = SceneSlot::new(world_with(1), Camera::new());
+    manager.insert("level", SceneSlot::new(world_with(3), Camera::new()));
+
+    assert!(manager.switch("level", &mut active));
+    assert_eq!(manager.active(), "level");
+    assert_eq!(active.world.objects.len(), 3);
+    assert!(manager.contains(MAIN_SCENE_ID));
+
+    assert!(manager.switch(MAIN_SCENE_ID, &mut active));
+    assert_eq!(active.world.objects.len(), 1);
+    assert_eq!(manager.parked_ids().collect::<Vec<_>>(), ["level"]);
+}
+
+#[test]
+fn switch_to_unknown_scene_changes_nothing() {
+    let mut manager = SceneManager::new();
+    let mut active = SceneSlot::new(world_with(2), Camera::new());
+    assert!(!manager.switch("missing", &mut active));
+    assert!(!manager.switch(MAIN_SCENE_ID, &mut active));
+    assert!(!manager.begin_transition("missing", 1.0));
+    assert_eq!(manager.active(), MAIN_SCENE_ID);
+    assert_eq!(active.world.objects.len(), 2);
+}
+
+#[test]
+#[should_panic(expected = "active scene")]
+fn inserting_over_active_scene_panics() {
+    let mut manager = SceneManager::new();
+    manager.insert(MAIN_SCENE_ID, SceneSlot::new(World::new(), Camera::new()));
+}
+
+#[test]
+fn fade_transition_swaps_at_midpoint() {
+    let mut manager = SceneManager::new();
+    let mut active = SceneSlot::new(world_with(1), Camera::new());
+    manager.insert("pause", SceneSlot::new(world_with(4), Camera::new()));
+    assert!(manager.begin_transition("pause", 1.0));
+
+    // Fading out: still on the old scene, opacity rising.
+    let opacity = manager.update(0.25, &mut active);
+    assert!((opacity - 0.5).abs() < 1e-5, "opacity {opacity}");
+    assert_eq!(manager.active(), MAIN_SCENE_ID);
+
+    // Midpoint: fully covered, swapped.
+    assert_eq!(manager.update(0.25, &mut active), 1.0);
+    assert_eq!(manager.active(), "pause");
+    assert_eq!(active.world.objects.len(), 4);
+    assert_eq!(manager.transition().map(|t| t.phase), Some(FadePhase::In));
+
+    // Fading in.
+    let opacity = manager.update(0.25, &mut active);
+    assert!((opacity - 0.5).abs() < 1e-5, "opacity {opacity}");
+    assert_eq!(manager.update(0.25, &mut active), 0.0);
+    assert!(manager.transition().is_none());
+    assert_eq!(manager.update(0.25, &mut active), 0.0);
+}
+
+#[test]
+fn zero_length_fade_switches_on_first_update() {
+    let mut manager = SceneManager::new();
+    let mut active = SceneSlot::new(World::new(), Camera::new());
+    manager.insert("menu", SceneSlot::new(world_with(2), Camera::new()));
+    assert!(manager.begin_transition("menu", 0.0));
+    assert_eq!(manager.update(0.0, &mut active), 1.0);
+    assert_eq!(manager.active(), "menu");
+    assert_eq!(manager.update(0.0, &mut active), 0.0);
+    assert!(manager.transition().is_none());
+}
+
+#[test]
+fn removing_target_cancels_transition() {
+    let mut manager = SceneManager::new();
+    let mut active = SceneSlot::new(World::new(), Camera::new());
+    manager.insert("level", SceneSlot::new(World::new(), Camera::new()));
+    manager.begin_transition("level", 1.0);
+    assert!(manager.remove("level").is_some());
+    assert!(manager.transition().is_none());
+    assert_eq!(manager.update(1.0, &mut active), 0.0);
+    assert_eq!(manager.active(), MAIN_SCENE_ID);
+}
+
+#[test]
+fn scene_fade_covers_frame_and_clears() {
+    let Some(mut scene) = golden::headless_scene(32, 32, Camera::new()) else { return };
+    scene.add_scene("level", world_with(1), Camera::new());
+    scene.scenes.fade_color = [1.0, 0.0, 0.0];
+    assert!(scene.switch_to_with_fade("level", 1.0));
+
+    scene.update_transition(0.5);
+    assert_eq!(scene.active_scene_id(), "level");
+    let frame = golden::render(&mut scene);
+    // Full opacity: every pixel is the fade colour.
+    assert!(frame.pixels.chunks(4).all(|p| p[0] == 255 && p[1] == 0 && p[2] == 0));
+
+    scene.update_transition(0.5);
+    assert_eq!(scene.pipeline.fade()[3], 0.0);
+    let frame = golden::render(&mut scene);
+    assert!(frame.pixels.chunks(4).any(|p| p[..3] != [255, 0, 0]));
+}
+
+#[test]
+fn scene_switch_to_is_immediate() {
+    let Some(mut scene) = golden::headless_scene(16, 16, Camera::new()) else { return };
+    scene.world = world_with(2);
+    scene.add_scene("menu", World::new(), Camera::new());
+    assert!(scene.switch_to("menu"));
+    assert_eq!(scene.world.objects.len(), 0);
+    assert!(scene.switch_to(MAIN_SCENE_ID));
+    assert_eq!(scene.world.objects.len(), 2);
+    assert!(!scene.switch_to("missing"));
+}
+
+#[test]
+fn parked_scene_meshes_survive_garbage_collection() {
+    let Some(mut scene) = golden::headless_scene(16, 16, Camera::new()) else { return };
+    let vertices = vec![Vertex { position: [0.0; 3], color: [1.0; 3], uv: [0.0; 2] }; 3];
+    let mesh = scene.register_mesh(&MeshData { vertices, indices: vec![0, 1, 2], ..MeshData::new() });
+    let mut level = World::new();
+    level.spawn_object(Object { mesh: Some(mesh), ..Default::default() }, None);
+    scene.add_scene("level", level, Camera::new());
+
+    assert_eq!(scene.collect_mesh_garbage(), 0);
+    assert!(scene.switch_to("level"));
+    assert!(scene.mesh_registry.contains(mesh));
+    assert!(scene.switch_to(MAIN_SCENE_ID));
+    assert_eq!(scene.collect_mesh_garbage(), 0, "the parked level still uses the mesh");
+
+    scene.scenes.remove("level");
+    assert_eq!(scene.collect_mesh_garbage(), 1);
+}
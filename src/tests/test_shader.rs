@@ -0,0 +1,89 @@
+//! Tests for the WGSL preprocessor.
+//!
+//! Coverage:
+//!   - includes are expanded once, from registered chunks and include dirs
+//!   - `#ifdef` / `#ifndef` / `#else`, including nested inactive blocks
+//!   - `#define` substitutes whole words until `#undef`
+//!   - errors carry the file and line of the offending directive
+//!   - the built-in scene shader expands every directive
+
+use crate::shader::{PreprocessError, SCENE_SHADER, ShaderPreprocessor};
+
+fn lines(source: &str) -> Vec<&str> {
+    source.lines().map(str::trim).filter(|line| !line.is_empty()).collect()
+}
+
+#[test]
+fn includes_expand_once() {
+    let preprocessor = ShaderPreprocessor::new()
+        .with_chunk("common.wgsl", "const ONE: f32 = 1.0;")
+        .with_chunk("a.wgsl", "#include \"common.wgsl\"\nconst A: f32 = ONE;");
+    let out = preprocessor
+        .process("main.wgsl", "#include \"a.wgsl\"\n#include \"common.wgsl\"\nconst B: f32 = A;")
+        .unwrap();
+    assert_eq!(lines(&out), ["const ONE: f32 = 1.0;", "const A: f32 = ONE;", "const B: f32 = A;"]);
+}
+
+#[test]
+fn conditionals_select_lines() {
+    let source = "#ifdef LIT\nlit\n#ifndef SHADOWS\nno_shadows\n#else\nshadows\n#endif\n#else\nunlit\n#ifdef SHADOWS\nunlit_shadows\n#else\nunlit_no_shadows\n#endif\n#endif";
+    let unlit = ShaderPreprocessor::new().process("s.wgsl", source).unwrap();
+    assert_eq!(lines(&unlit), ["unlit", "unlit_no_shadows"]);
+    let lit = ShaderPreprocessor::new().with_define("LIT", "").process("s.wgsl", source).unwrap();
+    assert_eq!(lines(&lit), ["lit", "no_shadows"]);
+    let shadows = ShaderPreprocessor::new()
+        .with_define("LIT", "")
+        .with_define("SHADOWS", "")
+        .process("s.wgsl", source)
+        .unwrap();
+    assert_eq!(lines(&shadows), ["lit", "shadows"]);
+    assert_eq!(source.lines().count(), shadows.lines().count(), "line numbers are preserved");
+}
+
+#[test]
+fn defines_substitute_whole_words() {
+    let source = "#define COUNT 4u\nlet n = COUNT + COUNTER;\n#undef COUNT\nlet m = COUNT;";
+    let out = ShaderPreprocessor::new().process("s.wgsl", source).unwrap();
+    assert_eq!(lines(&out), ["let n = 4u + COUNTER;", "let m = COUNT;"]);
+}
+
+#[test]
+fn errors_report_location() {
+    let process = |source: &str| ShaderPreprocessor::new().process("s.wgsl", source).unwrap_err();
+    assert_eq!(
+        process("a\n#include \"missing.wgsl\""),
+        PreprocessError::MissingInclude { file: "s.wgsl".into(), line: 2, name: "missing.wgsl".into() },
+    );
+    assert_eq!(
+        process("#endif"),
+        PreprocessError::UnmatchedDirective { file: "s.wgsl".into(), line: 1, directive: "#endif".into() },
+    );
+    assert_eq!(
+        process("\n#ifdef X\n"),
+        PreprocessError::UnterminatedConditional { file: "s.wgsl".into(), line: 2 },
+    );
+    assert!(matches!(process("#pragma once"), PreprocessError::InvalidDirective { line: 1, .. }));
+    assert_eq!(process("#include nope").to_string(), "s.wgsl:1: invalid directive `#include nope`");
+}
+
+#[test]
+fn process_file_resolves_sibling_includes() {
+    let dir = std::env::temp_dir().join(format!("vertra-shader-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("tint.wgsl"), "const TINT: f32 = 0.5;").unwrap();
+    std::fs::write(dir.join("main.wgsl"), "#include \"tint.wgsl\"\n#include \"vertra/model.wgsl\"").unwrap();
+    let out = ShaderPreprocessor::new().process_file(&dir.join("main.wgsl"));
+    std::fs::remove_dir_all(&dir).unwrap();
+    let out = out.unwrap();
+    assert!(out.contains("const TINT: f32 = 0.5;"));
+    assert!(out.contains("model_data"));
+}
+
+#[test]
+fn scene_shader_expands() {
+    let out = ShaderPreprocessor::new().process("shader.wgsl", SCENE_SHADER).unwrap();
+    assert!(out.lines().all(|line| !line.trim_start().starts_with('#')));
+    for name in ["var<uniform> camera", "model_data", "struct VertexOutput", "fn ambient_light", "fn fs_main"] {
+        assert!(out.contains(name), "missing {name}");
+    }
+}
@@ -0,0 +1,105 @@
+//! Unit tests for transform snapping and snapped editor gizmo drags.
+//!
+//! These tests exercise:
+//! - `snap`, `Transform::snap_position`, `snap_rotation` and `snap_scale`
+//! - Non-positive steps leave values unchanged
+//! - Editor translate drags land on the grid when snapping is enabled
+//! - Holding Ctrl inverts snapping, and toggling it mid-drag re-applies
+
+use crate::camera::Camera;
+use crate::editor::{DragAxis, DragKind, DragState, EditorEvent, EditorState};
+use crate::geometry::Geometry;
+use crate::objects::Object;
+use crate::transform::{snap, Transform};
+use crate::world::World;
+
+#[test]
+fn snap_rounds_to_nearest_step() {
+    assert_eq!(snap(0.74, 0.5), 0.5);
+    assert_eq!(snap(0.76, 0.5), 1.0);
+    assert_eq!(snap(-1.3, 1.0), -1.0);
+    assert_eq!(snap(0.74, 0.0), 0.74);
+    assert_eq!(snap(0.74, -1.0), 0.74);
+}
+
+#[test]
+fn transform_snaps_each_component() {
+    let mut t = Transform {
+        position: [1.26, -0.4, 7.9],
+        rotation: [44.0, 8.0, -97.0],
+        scale:    [0.1, 1.3, 2.6],
+    };
+    t.snap_position(0.5);
+    t.snap_rotation(15.0);
+    t.snap_scale(0.25);
+    assert_eq!(t.position, [1.5, -0.5, 8.0]);
+    assert_eq!(t.rotation, [45.0, 15.0, -90.0]);
+    assert_eq!(t.scale, [0.25, 1.25, 2.5]);
+
+    let before = t.clone();
+    t.snap_position(0.0);
+    t.snap_rotation(0.0);
+    t.snap_scale(0.0);
+    assert_eq!(t, before);
+}
+
+/// Editor, camera looking down -Z (+X is screen-left), and a cube at
+/// `x = 0.3` mid-drag on X.
+fn dragging_cube() -> (EditorState, Camera, World, usize) {
+    let mut world = World::new();
+    let id = world.spawn_object(
+        Object::builder().geometry(Geometry::Cube { size: 1.0 }).at(0.3, 0.0, 0.0).build(),
+        None,
+    );
+    let mut camera = Camera::new();
+    camera.eye = [0.0, 0.0, 10.0];
+    camera.target = [0.0, 0.0, 0.0];
+    camera.aspect = 800.0 / 600.0;
+    let mut editor = EditorState::new(800.0, 600.0);
+    editor.drag = Some(DragState {
+        object_id:   id,
+        axis:        DragAxis::X,
+        center:      [0.3, 0.0, 0.0],
+        kind:        DragKind::Translate,
+        origins:     vec![(id, world.objects[&id].transform.clone())],
+        accumulated: 0.0,
+    });
+    (editor, camera, world, id)
+}
+
+#[test]
+fn drag_without_snapping_moves_freely() {
+    let (mut editor, mut camera, mut world, id) = dragging_cube();
+    editor.process(&mut camera, &mut world, EditorEvent::MouseMotionDelta { dx: -2.0, dy: 0.0 });
+    let x = world.objects[&id].transform.position[0];
+    assert!(x > 0.3 && x < 0.4, "x = {x}");
+}
+
+#[test]
+fn enabled_snapping_lands_on_grid() {
+    let (mut editor, mut camera, mut world, id) = dragging_cube();
+    editor.snap.enabled = true;
+    editor.process(&mut camera, &mut world, EditorEvent::MouseMotionDelta { dx: -2.0, dy: 0.0 });
+    assert_eq!(world.objects[&id].transform.position[0], 0.5);
+
+    // Dragging far enough reaches the next grid line, not 0.5 + one step.
+    for _ in 0..20 {
+        editor.process(&mut camera, &mut world, EditorEvent::MouseMotionDelta { dx: -2.0, dy: 0.0 });
+    }
+    let x = world.objects[&id].transform.position[0];
+    assert_eq!(x % 0.5, 0.0, "x = {x}");
+    assert!(x > 0.5);
+}
+
+#[test]
+fn ctrl_inverts_snapping_mid_drag() {
+    let (mut editor, mut camera, mut world, id) = dragging_cube();
+    editor.process(&mut camera, &mut world, EditorEvent::MouseMotionDelta { dx: -2.0, dy: 0.0 });
+    let free = world.objects[&id].transform.position[0];
+    assert_ne!(free, 0.5);
+
+    editor.process(&mut camera, &mut world, EditorEvent::ModifiersChanged { alt: false, ctrl: true });
+    assert_eq!(world.objects[&id].transform.position[0], 0.5);
+    editor.process(&mut camera, &mut world, EditorEvent::ModifiersChanged { alt: false, ctrl: false });
+    assert_eq!(world.objects[&id].transform.position[0], free);
+}
@@ -1,30 +1,33 @@
-/// Tests for the play-mode snapshot / restore mechanism.
-///
-/// `Scene` requires a live GPU `Pipeline` and cannot be instantiated in unit
-/// tests.  The snapshot feature is, however, pure VTR logic:
-///
-/// * `disable_editor_mode` -> `vtr::write(camera, world)` into a `Vec<u8>`
-/// * `enable_editor_mode`  -> `vtr::read` that buffer and replace camera/world
-///
-/// Every test below exercises that contract directly, so no GPU context is
-/// needed.  The helpers `make_snapshot` / `restore_snapshot` mirror the exact
-/// code paths used in `Scene`.
-///
-/// Coverage:
-///   - snapshot bytes are valid VTR and non-empty
-///   - object transform mutations during play are reverted on restore
-///   - camera mutations during play are reverted on restore
-///   - objects spawned during play do not persist after restore
-///   - objects deleted during play are restored
-///   - multiple toggle cycles each create a fresh snapshot of the current state
-///   - restoring from snapshot rebuilds the `str_id` name-handle cache
-///   - all object fields (color, geometry, texture_path) survive the round-trip
+//! Tests for the play-mode snapshot / restore mechanism.
+//!
+//! `Scene` requires a live GPU `Pipeline` and cannot be instantiated in unit
+//! tests.  The snapshot feature is, however, pure VTR logic:
+//!
+//! * `disable_editor_mode` -> `vtr::write(camera, world)` into a `Vec<u8>`
+//! * `enable_editor_mode`  -> `vtr::read` that buffer and replace camera/world
+//!
+//! Every test below exercises that contract directly, so no GPU context is
+//! needed.  The helpers `make_snapshot` / `restore_snapshot` mirror the exact
+//! code paths used in `Scene`.
+//!
+//! Coverage:
+//!   - snapshot bytes are valid VTR and non-empty
+//!   - object transform mutations during play are reverted on restore
+//!   - camera mutations during play are reverted on restore
+//!   - objects spawned during play do not persist after restore
+//!   - objects deleted during play are restored
+//!   - multiple toggle cycles each create a fresh snapshot of the current state
+//!   - restoring from snapshot rebuilds the `str_id` name-handle cache
+//!   - all object fields (color, geometry, texture_path) survive the round-trip
 
 use std::io::Cursor;
 
 use crate::camera::Camera;
-use crate::geometry::Geometry;
+use crate::constants::layers;
+use crate::geometry::{Geometry, SphereTessellation};
+use crate::material::{CullMode, Material};
 use crate::objects::Object;
+use crate::pipeline_cache::DepthBias;
 use crate::transform::Transform;
 use crate::vtr;
 use crate::world::World;
@@ -58,6 +61,9 @@ fn cube_object(str_id: &str, pos: [f32; 3]) -> Object {
         children: Vec::new(),
         parent: None,
         texture_path: None,
+        mesh: None,
+        layer: layers::DEFAULT,
+        material: Material::default(),
     }
 }
 
@@ -219,11 +225,17 @@ fn all_object_fields_survive_snapshot_roundtrip() {
                 rotation: [10.0, 20.0, 30.0],
                 scale: [2.0, 3.0, 4.0],
             },
-            geometry: Some(Geometry::Sphere { radius: 1.5, subdivisions: 16 }),
+            geometry: Some(Geometry::Sphere { radius: 1.5, subdivisions: 16, tessellation: SphereTessellation::Uv }),
             color: [0.1, 0.2, 0.3, 0.4],
             texture_path: Some("textures/test.png".to_string()),
             children: Vec::new(),
             parent: None,
+            mesh: None,
+            layer: layers::DEFAULT,
+            material: Material::default()
+                .with_cull_mode(CullMode::Front)
+                .with_depth_write(false)
+                .with_depth_bias(DepthBias { constant: -2, slope_scale: 0.5 }),
         },
         None,
     );
@@ -239,8 +251,11 @@ fn all_object_fields_survive_snapshot_roundtrip() {
     assert_eq!(obj.transform.scale,    [2.0, 3.0, 4.0]);
     assert_eq!(obj.color,              [0.1, 0.2, 0.3, 0.4]);
     assert_eq!(obj.texture_path.as_deref(), Some("textures/test.png"));
-    assert!(matches!(obj.geometry, Some(Geometry::Sphere { radius, subdivisions })
+    assert!(matches!(obj.geometry, Some(Geometry::Sphere { radius, subdivisions, tessellation: SphereTessellation::Uv })
         if (radius - 1.5).abs() < 1e-6 && subdivisions == 16));
+    assert_eq!(obj.material.cull_mode, CullMode::Front);
+    assert!(!obj.material.depth_write);
+    assert_eq!(obj.material.depth_bias, DepthBias { constant: -2, slope_scale: 0.5 });
 }
 
 #[test]
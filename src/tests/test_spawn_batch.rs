@@ -0,0 +1,94 @@
+//! Tests for `Scene::spawn_batch`.
+//!
+//! Coverage:
+//!   - `World::reserve` leaves the world unchanged
+//!
+//! GPU tests (skipped without a software adapter):
+//!   - IDs come back in order under the given parent
+//!   - repeated dynamic geometry shares one registry mesh and keeps its
+//!     geometry for picking; unique shapes and static batches get no mesh
+//!   - a shared batch renders the same as spawning one by one, and draws
+//!     an object's own geometry once it is changed
+
+use crate::camera::Camera;
+use crate::geometry::{Geometry, SphereTessellation};
+use crate::mesh::MeshUsage;
+use crate::objects::Object;
+use crate::tests::golden;
+use crate::world::World;
+
+const CUBE: Geometry = Geometry::Cube { size: 1.0 };
+
+fn cubes(n: usize) -> Vec<Object> {
+    (0..n)
+        .map(|i| Object::builder().geometry(CUBE).at(i as f32 * 1.5 - 1.5, 0.0, 0.0).color([1.0, 0.5, 0.2, 1.0]).build())
+        .collect()
+}
+
+#[test]
+fn reserve_keeps_world_empty() {
+    let mut world = World::new();
+    world.reserve(1000);
+    assert!(world.objects.is_empty());
+    assert!(world.changes().is_empty());
+}
+
+#[test]
+fn ids_follow_input_order() {
+    let Some(mut scene) = golden::headless_scene(8, 8, Camera::new()) else { return };
+    let parent = scene.spawn(Object::default(), None);
+    let ids = scene.spawn_batch(cubes(3), Some(parent), MeshUsage::Dynamic);
+    assert_eq!(ids.len(), 3);
+    assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+    assert_eq!(scene.world.get(parent).unwrap().children, ids);
+    let xs: Vec<f32> = ids.iter().map(|id| scene.world.get(*id).unwrap().transform.position[0]).collect();
+    assert_eq!(xs, [-1.5, 0.0, 1.5]);
+}
+
+#[test]
+fn repeated_geometry_shares_a_mesh() {
+    let Some(mut scene) = golden::headless_scene(8, 8, Camera::new()) else { return };
+    let mut objects = cubes(3);
+    objects.push(Object::builder().geometry(Geometry::Sphere { radius: 1.0, subdivisions: 8, tessellation: SphereTessellation::Uv }).build());
+    let ids = scene.spawn_batch(objects, None, MeshUsage::Dynamic);
+    assert_eq!(scene.mesh_registry.len(), 1, "one mesh for the three cubes");
+
+    let cube_meshes: Vec<_> = ids[..3].iter().map(|id| scene.world.get(*id).unwrap().mesh).collect();
+    assert!(cube_meshes[0].is_some() && cube_meshes.iter().all(|m| *m == cube_meshes[0]));
+    assert!(ids[..3].iter().all(|id| scene.world.get(*id).unwrap().geometry == Some(CUBE)));
+    let hit = scene.world.raycast([1.5, 0.0, -5.0], [0.0, 0.0, 1.0], |_, _| true).expect("batched cubes are pickable");
+    assert_eq!(hit.id, ids[2]);
+    let sphere = scene.world.get(ids[3]).unwrap();
+    assert!(sphere.mesh.is_none() && sphere.geometry.is_some(), "unique shapes keep their geometry");
+
+    let ids = scene.spawn_batch(cubes(2), None, MeshUsage::Static);
+    assert_eq!(scene.mesh_registry.len(), 1, "static objects are batched instead");
+    assert!(ids.iter().all(|id| scene.world.get(*id).unwrap().geometry.is_some()));
+    assert!(ids.iter().all(|id| scene.static_batches.usage(*id) == MeshUsage::Static));
+}
+
+#[test]
+fn shared_batch_renders_like_single_spawns() {
+    let camera = Camera::new().with_position([0.0, 0.0, -6.0]);
+    let Some(mut single) = golden::headless_scene(32, 32, camera) else { return };
+    for object in cubes(3) {
+        single.spawn(object, None);
+    }
+    let Some(mut batched) = golden::headless_scene(32, 32, camera) else { return };
+    batched.spawn_batch(cubes(3), None, MeshUsage::Dynamic);
+    assert_eq!(batched.mesh_registry.len(), 1);
+    let frame = golden::render(&mut batched);
+    assert!(frame.pixels.chunks(4).any(|p| p[0] > p[2] + 50), "the orange cubes are drawn");
+    let diff = golden::compare(&frame.pixels, &golden::render(&mut single).pixels, 2);
+    assert_eq!(diff.mismatched_pixels, 0, "{diff:?}");
+
+    // Shrinking one cube draws the new geometry in place of the shared mesh.
+    let resize = |scene: &mut crate::scene::Scene| {
+        let id = *scene.world.roots.iter().min().unwrap();
+        scene.world.get_mut(id).unwrap().geometry = Some(Geometry::Cube { size: 0.5 });
+    };
+    resize(&mut single);
+    resize(&mut batched);
+    let diff = golden::compare(&golden::render(&mut batched).pixels, &golden::render(&mut single).pixels, 2);
+    assert_eq!(diff.mismatched_pixels, 0, "{diff:?}");
+}
@@ -0,0 +1,78 @@
+//! Tests for hierarchical spawning.
+//!
+//! Coverage:
+//!   - `World::world_transform` combines every ancestor's transform
+//!   - `Transform::relative_to` undoes `combine`
+//!
+//! GPU tests (skipped without a software adapter):
+//!   - `spawn_child` keeps the transform local and rejects missing parents
+//!   - `spawn_child_at_world` places the child at the given world transform
+
+use crate::camera::Camera;
+use crate::objects::Object;
+use crate::tests::golden;
+use crate::transform::Transform;
+use crate::world::World;
+
+fn assert_near(a: [f32; 3], b: [f32; 3]) {
+    assert!(a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-4), "{a:?} != {b:?}");
+}
+
+fn parent_transform() -> Transform {
+    Transform { position: [1.0, 2.0, 3.0], rotation: [0.0, 90.0, 0.0], scale: [2.0, 2.0, 2.0] }
+}
+
+#[test]
+fn world_transform_combines_ancestors() {
+    let mut world = World::new();
+    let root = world.spawn_object(Object::builder().transform(parent_transform()).build(), None);
+    let child = world.spawn_object(Object::builder().at(1.0, 0.0, 0.0).build(), Some(root));
+    let grandchild = world.spawn_object(Object::builder().at(0.0, 1.0, 0.0).build(), Some(child));
+
+    assert_eq!(world.world_transform(root), Some(parent_transform()));
+    let expected = parent_transform()
+        .combine(&Transform::from_position(1.0, 0.0, 0.0))
+        .combine(&Transform::from_position(0.0, 1.0, 0.0));
+    assert_eq!(world.world_transform(grandchild), Some(expected));
+    assert_eq!(world.world_transform(999), None);
+}
+
+#[test]
+fn relative_to_undoes_combine() {
+    let parent = parent_transform();
+    let world = Transform { position: [4.0, -1.0, 0.5], rotation: [10.0, 20.0, 30.0], scale: [1.0, 3.0, 0.5] };
+    let local = world.relative_to(&parent);
+    let back = parent.combine(&local);
+    assert_near(back.position, world.position);
+    assert_near(back.rotation, world.rotation);
+    assert_near(back.scale, world.scale);
+
+    assert_eq!(world.relative_to(&Transform::default()), world);
+}
+
+#[test]
+fn spawn_child_is_parent_relative() {
+    let Some(mut scene) = golden::headless_scene(8, 8, Camera::new()) else { return };
+    let parent = scene.spawn(Object::builder().transform(parent_transform()).build(), None);
+    let child = scene.spawn_child(parent, Object::builder().at(1.0, 0.0, 0.0).build()).unwrap();
+    assert_eq!(scene.world.get(child).unwrap().parent, Some(parent));
+    assert_eq!(scene.world.get(child).unwrap().transform, Transform::from_position(1.0, 0.0, 0.0));
+    // Rotated 90° about Y and doubled: local +X lands at world -Z.
+    assert_near(scene.world.world_transform(child).unwrap().position, [1.0, 2.0, 1.0]);
+
+    let count = scene.world.objects.len();
+    assert_eq!(scene.spawn_child(999, Object::default()), None);
+    assert_eq!(scene.spawn_child_at_world(999, Object::default()), None);
+    assert_eq!(scene.world.objects.len(), count);
+}
+
+#[test]
+fn spawn_child_at_world_keeps_placement() {
+    let Some(mut scene) = golden::headless_scene(8, 8, Camera::new()) else { return };
+    let parent = scene.spawn(Object::builder().transform(parent_transform()).build(), None);
+    let child = scene.spawn_child_at_world(parent, Object::builder().at(5.0, 5.0, 5.0).build()).unwrap();
+    let placed = scene.world.world_transform(child).unwrap();
+    assert_near(placed.position, [5.0, 5.0, 5.0]);
+    assert_near(placed.rotation, [0.0; 3]);
+    assert_near(placed.scale, [1.0; 3]);
+}
@@ -0,0 +1,85 @@
+//! Tests for texture atlases and sprite animation (sprite.rs).
+//!
+//! Coverage:
+//!   - grid atlases number frames row by row from the top-left
+//!   - named regions and names on grid frames
+//!   - frame stepping, looping, holding the last frame and pausing
+//!   - the attached script rewrites the object's sprite geometry
+
+use crate::geometry::Geometry;
+use crate::objects::Object;
+use crate::script::ScriptRegistry;
+use crate::sprite::{AnimatedSprite, Atlas};
+use crate::world::World;
+
+#[test]
+fn grid_frames_run_left_to_right_top_to_bottom() {
+    let atlas = Atlas::grid(4, 2);
+    assert_eq!(atlas.len(), 8);
+    assert_eq!(atlas.region(0), Some([0.0, 0.0, 0.25, 0.5]));
+    assert_eq!(atlas.region(1), Some([0.25, 0.0, 0.5, 0.5]));
+    assert_eq!(atlas.region(4), Some([0.0, 0.5, 0.25, 1.0]));
+    assert_eq!(atlas.region(8), None);
+}
+
+#[test]
+fn regions_can_be_named() {
+    let atlas = Atlas::grid(2, 1)
+        .with_name("idle", 1)
+        .with_name("missing", 9)
+        .with_region("coin", [0.5, 0.5, 0.75, 0.75]);
+    assert_eq!(atlas.len(), 3);
+    assert_eq!(atlas.named("idle"), atlas.region(1));
+    assert_eq!(atlas.index_of("coin"), Some(2));
+    assert_eq!(atlas.named("missing"), None);
+    assert!(Atlas::new().is_empty());
+
+    let sprite = AnimatedSprite::new(atlas, [1.0, 1.0]).with_named_frames(&["coin", "nope", "idle"]);
+    assert_eq!(sprite.region(), [0.5, 0.5, 0.75, 0.75]);
+}
+
+#[test]
+fn frames_advance_at_the_frame_rate() {
+    let mut sprite = AnimatedSprite::new(Atlas::grid(4, 1), [1.0, 1.0]).with_fps(10.0);
+    assert_eq!(sprite.frame(), 0);
+    sprite.update(0.25);
+    assert_eq!(sprite.frame(), 2);
+    sprite.update(0.2);
+    assert_eq!(sprite.frame(), 0, "looping wraps after 4 frames");
+    assert!(!sprite.is_finished());
+
+    let mut once = sprite.clone().with_looping(false);
+    once.update(0.35);
+    assert_eq!(once.frame(), 3);
+    assert!(!once.is_finished());
+    once.update(10.0);
+    assert_eq!(once.frame(), 3, "holds the last frame");
+    assert!(once.is_finished());
+
+    sprite.playing = false;
+    sprite.update(0.1);
+    assert_eq!(sprite.frame(), 0);
+    sprite.set_frame(3);
+    assert_eq!(sprite.region(), [0.75, 0.0, 1.0, 1.0]);
+}
+
+#[test]
+fn script_writes_the_current_frame_into_the_object() {
+    let mut world = World::new();
+    let id = world.spawn_object(Object::default(), None);
+    let mut scripts = ScriptRegistry::new();
+    let sprite = AnimatedSprite::new(Atlas::grid(2, 2), [3.0, 2.0]).with_fps(1.0);
+    scripts.attach(id, Box::new(sprite));
+
+    let uv = |world: &World| match world.objects[&id].geometry {
+        Some(Geometry::Sprite { width, height, uv }) => {
+            assert_eq!([width, height], [3.0, 2.0]);
+            uv
+        }
+        ref other => panic!("expected a sprite, got {other:?}"),
+    };
+    scripts.run_update(&mut world, 0.0);
+    assert_eq!(uv(&world), [0.0, 0.0, 0.5, 0.5]);
+    scripts.run_update(&mut world, 2.5);
+    assert_eq!(uv(&world), [0.0, 0.5, 0.5, 1.0]);
+}
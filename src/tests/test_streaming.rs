@@ -0,0 +1,182 @@
+//! Unit tests for world streaming.
+//!
+//! These tests exercise:
+//! - Cells in range load nearest first, a budget's worth per update, and
+//!   unload only past the margin
+//! - Worker threads generate cells off the calling thread
+//! - A panicking generator fails only its cell, which is reported once and
+//!   not left pending, on a worker or inline
+//! - `Scene::stream_objects` spawns and despawns object groups with their
+//!   meshes, and `Scene::stream_voxels` bakes and drops voxel chunks
+//! - Scene streaming reports which cell failed and why
+
+use std::time::{Duration, Instant};
+use crate::camera::Camera;
+use crate::constants::voxel::CHUNK_SIZE;
+use crate::mesh::MeshData;
+use crate::objects::Object;
+use crate::scene::Scene;
+use crate::streaming::{CellKey, ObjectGroup, StreamStats, Streamer};
+use crate::tests::golden;
+use crate::voxel::{Chunk, VoxelGrid};
+
+/// A streamer over 10-unit cells of one row, generating inline.
+fn row(radius: f32) -> Streamer<CellKey> {
+    Streamer::new(10.0, radius, |key| key).with_workers(0).with_vertical_range(0, 0)
+}
+
+#[test]
+fn cells_load_nearest_first_within_budget() {
+    let mut streamer = row(15.0).with_bake_budget(4);
+    let first = streamer.update([5.0, 0.0, 5.0]);
+    assert_eq!(first.load.len(), 4);
+    assert_eq!(first.load[0], ([0, 0, 0], [0, 0, 0]), "the camera's cell comes first");
+    assert!(first.load[1..].iter().all(|(key, _)| key[0].abs() + key[2].abs() == 1));
+    for _ in 0..2 {
+        streamer.update([5.0, 0.0, 5.0]);
+    }
+    assert_eq!(streamer.loaded().count(), 9, "a 3 × 3 block of cells");
+    assert!(streamer.update([5.0, 0.0, 5.0]).load.is_empty());
+
+    // Moving one cell over loads the next column; the old one stays within
+    // the margin until the camera moves on.
+    let moved = streamer.update([15.0, 0.0, 5.0]);
+    assert!(moved.unload.is_empty() && moved.load.iter().all(|(key, _)| key[0] == 2));
+    assert!(streamer.is_loaded([-1, 0, 0]));
+    let far = streamer.update([105.0, 0.0, 5.0]);
+    assert_eq!(far.unload.len(), 12);
+    assert!(streamer.loaded().all(|key| key[0] >= 9));
+}
+
+#[test]
+fn workers_generate_off_the_calling_thread() {
+    let mut streamer = Streamer::new(10.0, 15.0, |_| std::thread::current().id()).with_vertical_range(0, 0);
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let mut threads = Vec::new();
+    while streamer.loaded().count() < 9 && Instant::now() < deadline {
+        threads.extend(streamer.update([5.0, 0.0, 5.0]).load.into_iter().map(|(_, id)| id));
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    assert_eq!(threads.len(), 9);
+    assert!(threads.iter().all(|&id| id != std::thread::current().id()));
+}
+
+#[test]
+fn panicking_generator_fails_only_its_cell() {
+    let mut streamer = Streamer::new(10.0, 15.0, |key: CellKey| {
+        assert_ne!(key, [0, 0, 0], "bad cell");
+        key
+    })
+    .with_workers(1)
+    .with_vertical_range(0, 0);
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let mut failed = Vec::new();
+    while (streamer.loaded().count() < 8 || streamer.pending() > 0) && Instant::now() < deadline {
+        failed.extend(streamer.update([5.0, 0.0, 5.0]).failed);
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    assert_eq!(streamer.loaded().count(), 8, "the worker survives the panic");
+    assert_eq!(streamer.pending(), 0);
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].0, [0, 0, 0]);
+    assert!(failed[0].1.contains("bad cell"));
+    assert!(!streamer.is_loaded([0, 0, 0]));
+}
+
+#[test]
+fn panicking_inline_generator_fails_only_its_cell() {
+    let mut streamer = Streamer::new(10.0, 15.0, |key: CellKey| {
+        assert_ne!(key, [0, 0, 0], "bad cell");
+        key
+    })
+    .with_workers(0)
+    .with_vertical_range(0, 0);
+    let mut failed = Vec::new();
+    for _ in 0..10 {
+        failed.extend(streamer.update([5.0, 0.0, 5.0]).failed);
+    }
+    assert_eq!(streamer.loaded().count(), 8);
+    assert_eq!(streamer.pending(), 0);
+    assert_eq!(failed.len(), 1, "the failed cell is not retried");
+    assert_eq!(failed[0].0, [0, 0, 0]);
+    assert!(failed[0].1.contains("bad cell"));
+    assert!(!streamer.is_loaded([0, 0, 0]));
+}
+
+/// Run a streaming update until nothing is pending; returns the cells
+/// loaded.
+fn settle(mut update: impl FnMut() -> StreamStats) -> usize {
+    let mut loaded = 0;
+    for _ in 0..1000 {
+        let stats = update();
+        loaded += stats.loaded;
+        if stats.pending == 0 && stats.loaded == 0 {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    loaded
+}
+
+fn move_camera(scene: &mut Scene, x: f32) {
+    scene.camera.eye = [x, 5.0, -20.0];
+    scene.camera.target = [x, 0.0, 0.0];
+}
+
+#[test]
+fn scene_streams_objects_and_voxels() {
+    let Some(mut scene) = golden::headless_scene(16, 16, Camera::new()) else { return };
+    move_camera(&mut scene, 0.0);
+    let mut trees = Streamer::new(10.0, 25.0, |[x, _, z]| {
+        let mut group = ObjectGroup::new();
+        let mut mesh = MeshData::new();
+        mesh.push_quad([[0.0; 3], [0.0, 1.0, 0.0], [1.0, 1.0, 0.0], [1.0, 0.0, 0.0]], [1.0; 4]);
+        let object = Object::builder().at(x as f32 * 10.0, 0.0, z as f32 * 10.0).build();
+        group.push_with_mesh(object, mesh);
+        group
+    }).with_vertical_range(0, 0);
+    let cells = settle(|| scene.stream_objects(&mut trees));
+    assert!(cells > 0 && cells == trees.loaded().count());
+    assert_eq!((scene.world.objects.len(), scene.mesh_registry.len()), (cells, cells));
+
+    let mut terrain = Streamer::voxel_chunks(20.0, |_| {
+        let mut chunk = Chunk::new();
+        for z in 0..CHUNK_SIZE {
+            for x in 0..CHUNK_SIZE {
+                chunk.set([x, 0, z], 1);
+            }
+        }
+        chunk
+    }).with_vertical_range(0, 0).with_bake_budget(2);
+    let mut grid = VoxelGrid::new();
+    let chunks = settle(|| scene.stream_voxels(&mut terrain, &mut grid));
+    assert!(chunks > 0 && grid.chunks().count() == chunks);
+    assert_eq!(scene.world.objects.len(), cells + chunks, "one object per chunk");
+    assert_eq!(grid.dirty_count(), 0);
+
+    move_camera(&mut scene, 1000.0);
+    scene.stream_objects(&mut trees);
+    settle(|| scene.stream_voxels(&mut terrain, &mut grid));
+    let stray = scene.world.objects.values().filter(|o| o.transform.position[0] < 500.0).count();
+    assert_eq!(stray, 0, "everything near the old position unloaded");
+    assert_eq!(grid.chunks().filter(|(key, _)| key[0] < 30).count(), 0);
+}
+
+#[test]
+fn scene_streaming_reports_failed_cells() {
+    let Some(mut scene) = golden::headless_scene(16, 16, Camera::new()) else { return };
+    scene.camera.eye = [5.0, 0.0, 5.0];
+    let mut streamer = Streamer::new(10.0, 15.0, |key: CellKey| {
+        assert_ne!(key, [0, 0, 0], "bad cell");
+        ObjectGroup::new()
+    }).with_vertical_range(0, 0);
+    let mut failed = Vec::new();
+    settle(|| {
+        let stats = scene.stream_objects(&mut streamer);
+        failed.extend(stats.failed.iter().cloned());
+        stats
+    });
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].0, [0, 0, 0]);
+    assert!(failed[0].1.contains("bad cell"));
+}
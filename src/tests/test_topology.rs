@@ -0,0 +1,62 @@
+//! Unit tests for line and point topologies.
+//!
+//! These tests exercise:
+//! - Polyline, closed polyline and point builders index their vertices
+//! - Appending keeps indices relative to the receiving builder
+//! - A registered line mesh is drawn unlit with a line-list pipeline
+
+use crate::camera::Camera;
+use crate::mesh::{MeshData, Topology};
+use crate::objects::Object;
+use crate::tests::golden;
+
+const SQUARE: [[f32; 3]; 4] = [[-1.0, -1.0, 0.0], [1.0, -1.0, 0.0], [1.0, 1.0, 0.0], [-1.0, 1.0, 0.0]];
+
+#[test]
+fn builders_index_segments_and_points() {
+    let mut open = MeshData::lines();
+    open.push_polyline(&SQUARE, [1.0; 4]);
+    assert_eq!(open.indices, [0, 1, 1, 2, 2, 3]);
+    open.push_polyline(&SQUARE[..1], [1.0; 4]);
+    assert_eq!(open.indices.len(), 6, "a single point makes no segment");
+
+    let mut closed = MeshData::lines();
+    closed.push_closed_polyline(&SQUARE, [1.0; 4]);
+    assert_eq!(closed.indices, [0, 1, 1, 2, 2, 3, 3, 0]);
+
+    let mut scan = MeshData::points();
+    scan.push_points(&SQUARE, [0.5, 0.5, 0.5, 1.0]);
+    assert_eq!(scan.indices, [0, 1, 2, 3]);
+    assert_eq!(scan.vertices[2].color, [0.5; 3]);
+    assert_eq!(Topology::PointList.vertices_per_primitive(), 1);
+    assert_eq!(MeshData::new().topology, Topology::TriangleList);
+}
+
+#[test]
+fn append_offsets_line_indices() {
+    let mut a = MeshData::lines();
+    a.push_polyline(&SQUARE[..2], [1.0; 4]);
+    let mut b = MeshData::lines();
+    b.push_closed_polyline(&SQUARE[1..], [1.0; 4]);
+    a.append(b);
+    assert_eq!(a.indices, [0, 1, 2, 3, 3, 4, 4, 2]);
+    assert_eq!(a.topology, Topology::LineList);
+}
+
+#[test]
+fn line_mesh_renders_unlit() {
+    let camera = Camera { eye: [0.0, 0.0, -5.0], target: [0.0, 0.0, 0.0], aspect: 1.0, ..Camera::new() };
+    let Some(mut scene) = golden::headless_scene(32, 32, camera) else { return };
+    let clear = golden::render(&mut scene);
+
+    let mut outline = MeshData::lines();
+    outline.push_closed_polyline(&SQUARE, [1.0, 0.0, 0.0, 1.0]);
+    let mesh = scene.register_mesh(&outline);
+    scene.spawn(Object { mesh: Some(mesh), ..Default::default() }, None);
+    let frame = golden::render(&mut scene);
+    let red = frame.pixels.chunks(4).filter(|p| p[0] > 200 && p[1] < 60 && p[2] < 60).count();
+    assert!(red > 20, "{red} red pixels");
+    // Only the outline is drawn: the middle keeps the clear colour.
+    let at = (16 * 32 + 16) * 4;
+    assert_eq!(frame.pixels[at..at + 4], clear.pixels[at..at + 4]);
+}
@@ -0,0 +1,65 @@
+//! Tests for touch gesture recognition (touch.rs).
+//!
+//! Coverage:
+//!   - a finger lifted in place taps; one that strays past the slop pans
+//!     and does not
+//!   - two fingers pan their centroid and pinch about it
+//!   - cancelled touches and non-touch input produce nothing
+
+use winit::event::TouchPhase;
+use crate::constants::touch::TAP_SLOP;
+use crate::replay::InputEvent;
+use crate::touch::{Gesture, GestureRecognizer};
+
+fn touch(id: u64, phase: TouchPhase, x: f64, y: f64) -> InputEvent {
+    InputEvent::Touch { id, phase, x, y }
+}
+
+#[test]
+fn lifting_in_place_taps() {
+    let mut gestures = GestureRecognizer::new();
+    assert!(gestures.handle(&touch(3, TouchPhase::Started, 10.0, 20.0)).is_empty());
+    assert_eq!(gestures.touch_count(), 1);
+    gestures.handle(&touch(3, TouchPhase::Moved, 12.0, 20.0));
+    assert_eq!(gestures.touch_position(3), Some([12.0, 20.0]));
+    assert_eq!(gestures.handle(&touch(3, TouchPhase::Ended, 12.0, 20.0)), [Gesture::Tap { x: 12.0, y: 20.0 }]);
+    assert_eq!(gestures.touch_count(), 0);
+}
+
+#[test]
+fn dragging_pans_instead_of_tapping() {
+    let mut gestures = GestureRecognizer::new();
+    gestures.handle(&touch(0, TouchPhase::Started, 0.0, 0.0));
+    let far = f64::from(TAP_SLOP) + 1.0;
+    assert_eq!(
+        gestures.handle(&touch(0, TouchPhase::Moved, far, 0.0)),
+        [Gesture::Pan { dx: far as f32, dy: 0.0, fingers: 1 }],
+    );
+    gestures.handle(&touch(0, TouchPhase::Moved, 0.0, 0.0));
+    assert!(gestures.handle(&touch(0, TouchPhase::Ended, 0.0, 0.0)).is_empty(), "strayed past the slop");
+}
+
+#[test]
+fn two_fingers_pan_and_pinch() {
+    let mut gestures = GestureRecognizer::new();
+    gestures.handle(&touch(0, TouchPhase::Started, 100.0, 100.0));
+    gestures.handle(&touch(1, TouchPhase::Started, 200.0, 100.0));
+    // Moving one finger out doubles the spread and shifts the midpoint.
+    let moved = gestures.handle(&touch(1, TouchPhase::Moved, 300.0, 100.0));
+    assert_eq!(moved, [
+        Gesture::Pan { dx: 50.0, dy: 0.0, fingers: 2 },
+        Gesture::Pinch { scale: 2.0, x: 200.0, y: 100.0 },
+    ]);
+    gestures.handle(&touch(0, TouchPhase::Ended, 100.0, 100.0));
+    assert!(gestures.handle(&touch(1, TouchPhase::Ended, 300.0, 100.0)).is_empty(), "multi-finger sequences never tap");
+}
+
+#[test]
+fn cancel_and_other_input_do_nothing() {
+    let mut gestures = GestureRecognizer::new();
+    gestures.handle(&touch(0, TouchPhase::Started, 5.0, 5.0));
+    assert!(gestures.handle(&touch(0, TouchPhase::Cancelled, 5.0, 5.0)).is_empty());
+    assert_eq!(gestures.touch_count(), 0);
+    assert!(gestures.handle(&touch(0, TouchPhase::Moved, 50.0, 5.0)).is_empty(), "unknown finger");
+    assert!(gestures.handle(&InputEvent::Focused(true)).is_empty());
+}
@@ -0,0 +1,72 @@
+//! Tests for the per-frame uniform allocator.
+//!
+//! Coverage (skipped without a software adapter):
+//!   - slots are aligned for dynamic offsets and handed out in order
+//!   - pushes past the capacity return `None`
+//!   - `reserve` grows to a power of two and reports replacement
+//!   - a frame with more mesh draws than the initial slots renders them all
+
+use crate::camera::Camera;
+use crate::geometry::Geometry;
+use crate::mesh::MeshData;
+use crate::objects::Object;
+use crate::tests::golden;
+use crate::transform::Transform;
+use crate::uniforms::UniformAllocator;
+
+#[test]
+fn offsets_are_aligned_and_sequential() {
+    let Some(pipeline) = golden::headless_pipeline(8, 8) else { return };
+    let uniforms = UniformAllocator::new(&pipeline.device, 96, 3);
+    let alignment = pipeline.device.limits().min_uniform_buffer_offset_alignment as u64;
+    assert!(uniforms.stride() >= 96);
+    assert_eq!(uniforms.stride() % alignment, 0);
+
+    let mut frame = uniforms.begin_frame();
+    assert!(frame.is_empty());
+    let stride = uniforms.stride() as u32;
+    assert_eq!(frame.push(&[1.0f32; 16]), Some(0));
+    assert_eq!(frame.push(&[2.0f32; 24]), Some(stride));
+    assert_eq!(frame.push(&7u32), Some(2 * stride));
+    assert_eq!(frame.push(&8u32), None, "buffer is full");
+    assert_eq!(frame.len(), 3);
+    assert_eq!(frame.remaining(), 0);
+    assert_eq!(frame.finish(&pipeline.queue), 3 * uniforms.stride());
+}
+
+#[test]
+fn reserve_grows_to_power_of_two() {
+    let Some(pipeline) = golden::headless_pipeline(8, 8) else { return };
+    let mut uniforms = UniformAllocator::new(&pipeline.device, 64, 4);
+    assert!(!uniforms.reserve(&pipeline.device, 4));
+    assert!(uniforms.reserve(&pipeline.device, 5));
+    assert_eq!(uniforms.capacity(), 8);
+    assert_eq!(uniforms.buffer().size(), 8 * uniforms.stride());
+}
+
+#[test]
+fn many_mesh_draws_share_one_buffer() {
+    let Some(mut scene) = golden::headless_scene(64, 64, Camera::new()) else { return };
+    let mut quad = MeshData::new();
+    quad.push_quad([[-0.1, -0.1, 0.0], [0.1, -0.1, 0.0], [0.1, 0.1, 0.0], [-0.1, 0.1, 0.0]], [1.0; 4]);
+    let mesh = scene.register_mesh(&quad);
+    scene.draw_world().unwrap(); // flush the upload counters
+    for i in 0..200 {
+        scene.world.spawn_object(Object {
+            mesh: Some(mesh),
+            transform: Transform::from_position(i as f32 * 0.01 - 1.0, 0.0, 5.0),
+            ..Default::default()
+        }, None);
+    }
+    scene.world.spawn_object(Object { geometry: Some(Geometry::Cube { size: 1.0 }), ..Default::default() }, None);
+
+    let first = scene.draw_world().unwrap();
+    assert_eq!(first.draw_calls, 201, "every mesh draw got a uniform slot");
+    let second = scene.draw_world().unwrap();
+    assert_eq!(second.draw_calls, 201);
+    assert_eq!(
+        first.buffer_reallocations,
+        second.buffer_reallocations + 1,
+        "the uniform buffer grew once, on the first frame",
+    );
+}
@@ -0,0 +1,119 @@
+//! Unit tests for the screen-corner orientation widget.
+//!
+//! These tests exercise:
+//! - Axis ends follow the camera's projection and are ordered back to front
+//! - Hit testing prefers the disc nearest the viewer
+//! - Snapping turns the camera to axis-aligned views around a pivot
+//! - The widget stays inside its layout rectangle
+//! - Clicks over a disc snap the scene camera and are reported as used
+
+use crate::camera::{Camera, Viewport};
+use crate::overlay::{Anchor, Layout};
+use crate::tests::golden;
+use crate::view_axes::{snap_camera, ViewAxes};
+
+const VIEWPORT: [f32; 2] = [400.0, 300.0];
+
+fn close(a: [f32; 3], b: [f32; 3]) -> bool {
+    (0..3).all(|k| (a[k] - b[k]).abs() < 1e-3)
+}
+
+fn centered() -> ViewAxes {
+    ViewAxes::default().with_layout(Layout::new(Anchor::Center, [100.0, 100.0]))
+}
+
+#[test]
+fn handles_follow_the_projection() {
+    let camera = Camera { eye: [3.0, 4.0, 6.0], target: [0.5, 0.0, -1.0], ..Camera::new() };
+    let handles = centered().handles(&camera, VIEWPORT);
+    assert!(handles.windows(2).all(|pair| pair[0].depth >= pair[1].depth));
+
+    // Each axis points the same way on screen as a short world-space
+    // segment along it, projected by the camera itself.
+    let viewport = Viewport::new(300.0, 300.0);
+    let origin = camera.world_to_screen(camera.target, viewport).unwrap();
+    let center = [VIEWPORT[0] * 0.5, VIEWPORT[1] * 0.5];
+    for handle in handles {
+        let tip = [0, 1, 2].map(|k| camera.target[k] + handle.axis[k] * 0.01);
+        let projected = camera.world_to_screen(tip, viewport).unwrap();
+        let expected = [projected[0] - origin[0], projected[1] - origin[1]];
+        let got = [handle.position[0] - center[0], handle.position[1] - center[1]];
+        assert!(expected[0] * got[0] + expected[1] * got[1] > 0.0, "{:?}", handle.axis);
+    }
+}
+
+#[test]
+fn hit_prefers_the_nearest_disc() {
+    // Looking straight down -Z, both Z discs sit on the centre.
+    let camera = Camera { eye: [0.0, 0.0, 5.0], target: [0.0, 0.0, 0.0], ..Camera::new() };
+    let axes = centered();
+    let center = [VIEWPORT[0] * 0.5, VIEWPORT[1] * 0.5];
+    assert_eq!(axes.hit(&camera, VIEWPORT, center), Some([0.0, 0.0, 1.0]));
+
+    let plus_y = axes.handles(&camera, VIEWPORT).into_iter().find(|h| h.axis == [0.0, 1.0, 0.0]).unwrap();
+    assert!(plus_y.position[1] < center[1], "+Y is drawn upwards");
+    assert_eq!(axes.hit(&camera, VIEWPORT, plus_y.position), Some([0.0, 1.0, 0.0]));
+    assert_eq!(axes.hit(&camera, VIEWPORT, [5.0, 5.0]), None);
+}
+
+#[test]
+fn snapping_orbits_the_pivot() {
+    let pivot = [1.0, 0.0, 2.0];
+    let mut camera = Camera { eye: [4.0, 5.0, 6.0], target: pivot, ..Camera::new() };
+    let distance = (9.0f32 + 25.0 + 16.0).sqrt();
+
+    snap_camera(&mut camera, [1.0, 0.0, 0.0], pivot);
+    assert!(close(camera.eye, [1.0 + distance, 0.0, 2.0]), "{:?}", camera.eye);
+    let (forward, _) = camera.get_directions();
+    assert!(close(forward, [-1.0, 0.0, 0.0]));
+
+    snap_camera(&mut camera, [0.0, 0.0, -1.0], pivot);
+    assert!(close(camera.eye, [1.0, 0.0, 2.0 - distance]), "{:?}", camera.eye);
+    assert_eq!(camera.ud_rot, 0.0);
+
+    snap_camera(&mut camera, [0.0, 1.0, 0.0], pivot);
+    assert_eq!(camera.ud_rot, -89.0);
+    assert!(camera.eye[1] > distance * 0.99);
+    let offset = [0, 1, 2].map(|k| camera.eye[k] - pivot[k]);
+    assert!(((offset[0] * offset[0] + offset[1] * offset[1] + offset[2] * offset[2]).sqrt() - distance).abs() < 1e-3);
+}
+
+#[test]
+fn widget_stays_in_its_corner() {
+    let axes = ViewAxes::default();
+    let (min, size) = axes.layout.rect(VIEWPORT);
+    let mesh = axes.build(&Camera::new(), VIEWPORT);
+    assert!(!mesh.indices.is_empty());
+    let (lo, hi) = mesh.bounds().unwrap();
+    assert!(lo[0] >= min[0] - 1e-3 && lo[1] >= min[1] - 1e-3);
+    assert!(hi[0] <= min[0] + size[0] + 1e-3 && hi[1] <= min[1] + size[1] + 1e-3);
+    assert_eq!(min[0] + size[0], VIEWPORT[0] - axes.layout.margins.right, "top-right by default");
+}
+
+#[test]
+fn clicks_snap_the_scene_camera() {
+    let Some(mut scene) = golden::headless_scene(64, 64, Camera::new()) else { return };
+    let plain = golden::render(&mut scene);
+    scene.overlay.view_axes = Some(ViewAxes::default().with_layout(Layout::new(Anchor::Center, [48.0, 48.0])));
+    assert_ne!(golden::render(&mut scene).pixels, plain.pixels);
+
+    scene.set_cursor_position(Some([1.0, 1.0]));
+    assert!(!scene.click_view_axes());
+    let size = scene.overlay.camera.size();
+    let handle = scene.overlay.view_axes.as_ref().unwrap()
+        .handles(&scene.camera, size)
+        .into_iter()
+        .find(|h| h.axis == [1.0, 0.0, 0.0])
+        .unwrap();
+    let target = scene.camera.target;
+    let offset = [0, 1, 2].map(|k| scene.camera.eye[k] - target[k]);
+    let distance = (offset[0] * offset[0] + offset[1] * offset[1] + offset[2] * offset[2]).sqrt();
+    scene.set_cursor_position(Some(handle.position));
+    assert!(scene.click_view_axes());
+    let (forward, _) = scene.camera.get_directions();
+    assert!(close(forward, [-1.0, 0.0, 0.0]));
+    assert!(close(scene.camera.eye, [target[0] + distance, target[1], target[2]]));
+
+    scene.overlay.view_axes.as_mut().unwrap().snap_on_click = false;
+    assert!(!scene.click_view_axes());
+}
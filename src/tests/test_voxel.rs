@@ -0,0 +1,174 @@
+//! Unit tests for chunked voxel grids.
+//!
+//! These tests exercise:
+//! - Blocks map to chunks and back, negative positions included
+//! - Meshing keeps only faces towards air, across chunk borders too, wound
+//!   like the cube primitive
+//! - Greedy meshing merges flat terrain into a handful of quads without
+//!   merging colours, and draws the same picture
+//! - Edits mark their chunk dirty, and the neighbour across a border; empty
+//!   chunks are dropped
+//! - `Scene::sync_voxels` bakes one object per chunk and rebakes, or
+//!   removes, only what an edit touched
+
+use crate::camera::Camera;
+use crate::constants::voxel::CHUNK_SIZE;
+use crate::geometry::Geometry;
+use crate::mesh::MeshData;
+use crate::tests::golden;
+use crate::voxel::{AIR, Meshing, VoxelGrid};
+
+const STONE: u16 = 1;
+const EDGE: i32 = CHUNK_SIZE as i32;
+
+fn faces(mesh: &MeshData) -> usize {
+    mesh.indices.len() / 6
+}
+
+/// Signed volume of each triangle with the mesh centre, positive when it
+/// faces away from the centre.
+fn outward(mesh: &MeshData, centre: [f32; 3]) -> Vec<f32> {
+    let sub = |a: [f32; 3], b: [f32; 3]| [a[0] - b[0], a[1] - b[1], a[2] - b[2]];
+    mesh.indices.chunks(3).map(|t| {
+        let [a, b, c] = [0, 1, 2].map(|k| mesh.vertices[t[k] as usize].position);
+        let (u, v, w) = (sub(b, a), sub(c, a), sub(a, centre));
+        let n = [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]];
+        n[0] * w[0] + n[1] * w[1] + n[2] * w[2]
+    }).collect()
+}
+
+#[test]
+fn positions_locate_chunks() {
+    assert_eq!(VoxelGrid::locate([0, 5, EDGE - 1]), ([0, 0, 0], [0, 5, CHUNK_SIZE - 1]));
+    assert_eq!(VoxelGrid::locate([-1, EDGE, -EDGE]), ([-1, 1, -1], [CHUNK_SIZE - 1, 0, 0]));
+    assert_eq!(VoxelGrid::chunk_origin([-1, 2, 0]), [-(EDGE as f32), 2.0 * EDGE as f32, 0.0]);
+
+    let mut grid = VoxelGrid::new();
+    assert_eq!(grid.set([-3, 7, 40], STONE), AIR);
+    assert_eq!(grid.get([-3, 7, 40]), STONE);
+    assert_eq!(grid.chunk([-1, 0, 2]).map(|c| c.solid_count()), Some(1));
+    assert_eq!(grid.set([-3, 7, 40], AIR), STONE);
+    assert!(grid.chunk([-1, 0, 2]).is_none(), "empty chunks are dropped");
+}
+
+#[test]
+fn meshing_keeps_faces_towards_air() {
+    let mut grid = VoxelGrid::new().with_palette(vec![[0.0; 4], [0.5, 0.5, 0.5, 1.0]]);
+    grid.set([2, 2, 2], STONE);
+    let single = grid.mesh_chunk([0, 0, 0]);
+    assert_eq!(faces(&single), 6);
+    assert!(single.vertices.iter().all(|v| v.color == [0.5, 0.5, 0.5]));
+
+    // Same winding as the cube primitive.
+    let mut cube = MeshData::new();
+    Geometry::Cube { size: 1.0 }.generate_mesh_data(&mut cube, &Default::default(), [1.0; 4]);
+    let expected = outward(&cube, [0.0; 3])[0].signum();
+    assert!(outward(&single, [2.5; 3]).iter().all(|v| v.signum() == expected));
+
+    grid.set([3, 2, 2], STONE);
+    assert_eq!(faces(&grid.mesh_chunk([0, 0, 0])), 10, "the shared faces are hidden");
+    grid.fill([0, 0, 0], [3, 3, 3], STONE);
+    assert_eq!(faces(&grid.mesh_chunk([0, 0, 0])), 6 * 16, "only the solid's skin is left");
+
+    // A row across the border between chunks -1 and 0.
+    let mut row = VoxelGrid::new();
+    row.fill([-1, 0, 0], [0, 0, 0], STONE);
+    assert_eq!(faces(&row.mesh_chunk([-1, 0, 0])), 5);
+    assert_eq!(faces(&row.mesh_chunk([0, 0, 0])), 5);
+    let (lo, hi) = row.mesh_chunk([-1, 0, 0]).bounds().unwrap();
+    assert_eq!((lo, hi), ([EDGE as f32 - 1.0, 0.0, 0.0], [EDGE as f32, 1.0, 1.0]), "chunk-local positions");
+    assert!(row.mesh_chunk([5, 0, 0]).indices.is_empty());
+}
+
+/// Summed area of a mesh's triangles.
+fn area(mesh: &MeshData) -> f32 {
+    let sub = |a: [f32; 3], b: [f32; 3]| [a[0] - b[0], a[1] - b[1], a[2] - b[2]];
+    mesh.indices.chunks(3).map(|t| {
+        let [a, b, c] = [0, 1, 2].map(|k| mesh.vertices[t[k] as usize].position);
+        let (u, v) = (sub(b, a), sub(c, a));
+        let n = [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]];
+        (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt() / 2.0
+    }).sum()
+}
+
+#[test]
+fn greedy_meshing_merges_flat_faces() {
+    let palette = vec![[0.0; 4], [0.5, 0.5, 0.5, 1.0], [0.2, 0.6, 0.2, 1.0]];
+    let mut grid = VoxelGrid::new().with_palette(palette);
+    grid.fill([0, 0, 0], [EDGE - 1, 0, EDGE - 1], STONE);
+    let culled = grid.mesh_chunk([0, 0, 0]);
+    grid.meshing = Meshing::Greedy;
+    let greedy = grid.mesh_chunk([0, 0, 0]);
+    assert_eq!((faces(&culled), faces(&greedy)), (2 * 16 * 16 + 4 * 16, 6));
+    assert_eq!(area(&greedy), area(&culled));
+    assert_eq!(greedy.bounds(), culled.bounds());
+    let expected = outward(&culled, [8.0, 0.5, 8.0])[0].signum();
+    assert!(outward(&greedy, [8.0, 0.5, 8.0]).iter().all(|v| v.signum() == expected), "same winding");
+
+    // A green stripe splits the top and bottom, and sides between
+    // chunks stay hidden.
+    grid.fill([0, 0, 4], [EDGE - 1, 0, 4], 2);
+    grid.fill([EDGE, 0, 0], [EDGE, 0, EDGE - 1], STONE);
+    let striped = grid.mesh_chunk([0, 0, 0]);
+    assert_eq!(faces(&striped), 2 * 3 + 3 + 1 + 1, "top and bottom in three strips, -X side in three, +Z and -Z");
+    assert_eq!(area(&striped), area(&culled) - 16.0);
+}
+
+#[test]
+fn edits_mark_touched_chunks_dirty() {
+    let mut grid = VoxelGrid::new();
+    grid.set([EDGE, 0, 0], STONE);
+    grid.set([EDGE + 4, 4, 4], STONE);
+    assert_eq!(grid.take_dirty(), [[1, 0, 0]]);
+    assert!(grid.take_dirty().is_empty());
+
+    // Inside a chunk only it changes; on a border its neighbour's faces do
+    // too, as long as the neighbour exists.
+    grid.set([EDGE + 5, 4, 4], STONE);
+    assert_eq!(grid.take_dirty(), [[1, 0, 0]]);
+    grid.set([EDGE - 1, 0, 0], STONE);
+    assert_eq!(grid.take_dirty(), [[0, 0, 0], [1, 0, 0]]);
+    grid.set([EDGE - 1, 0, 0], STONE);
+    assert!(grid.take_dirty().is_empty(), "unchanged blocks mark nothing");
+
+    grid.set([EDGE - 1, 0, 0], AIR);
+    assert!(grid.is_dirty([0, 0, 0]) && grid.chunk([0, 0, 0]).is_none(), "removed chunks still need unbaking");
+    grid.take_dirty();
+    grid.mark_all_dirty();
+    assert_eq!(grid.take_dirty(), [[1, 0, 0]]);
+}
+
+#[test]
+fn sync_rebakes_only_edited_chunks() {
+    let camera = Camera { eye: [8.0, 8.0, -30.0], target: [8.0, 8.0, 0.0], aspect: 1.0, ortho_height: Some(32.0), ..Camera::new() };
+    let Some(mut scene) = golden::headless_scene(32, 32, camera) else { return };
+    let mut grid = VoxelGrid::new().with_palette(vec![[0.0; 4], [1.0, 0.0, 0.0, 1.0]]);
+    grid.fill([0, 0, 0], [EDGE + 3, 3, 3], STONE);
+    assert_eq!(scene.sync_voxels(&mut grid), 2);
+    assert_eq!(scene.world.objects.len(), 2);
+    assert_eq!(scene.sync_voxels(&mut grid), 0);
+    let red = |scene: &mut crate::scene::Scene| {
+        golden::render(scene).pixels.chunks(4).filter(|p| p[0] > 200 && p[1] < 60 && p[2] < 60).count()
+    };
+    let culled = golden::render(&mut scene);
+    assert!(red(&mut scene) > 0);
+    grid.meshing = Meshing::Greedy;
+    grid.mark_all_dirty();
+    scene.sync_voxels(&mut grid);
+    let greedy = golden::render(&mut scene);
+    assert_eq!(golden::compare(&greedy.pixels, &culled.pixels, 8).mismatched_pixels, 0);
+
+    let (object, mesh) = grid.baked[&[0, 0, 0]];
+    grid.set([8, 2, 0], AIR);
+    assert_eq!(scene.sync_voxels(&mut grid), 1);
+    let (rebaked, new_mesh) = grid.baked[&[0, 0, 0]];
+    assert_eq!(rebaked, object, "the chunk keeps its object");
+    assert_ne!(new_mesh, mesh);
+    assert!(scene.mesh_registry.get(mesh).is_none(), "the old mesh is freed");
+    assert_eq!(scene.world.objects[&object].mesh, Some(new_mesh));
+
+    grid.fill([0, 0, 0], [EDGE + 3, 3, 3], AIR);
+    assert_eq!(scene.sync_voxels(&mut grid), 2);
+    assert!(scene.world.objects.is_empty() && grid.baked.is_empty());
+    assert_eq!(red(&mut scene), 0);
+}
@@ -0,0 +1,110 @@
+//! Tests for the VTM binary mesh cache format (vtm.rs).
+//!
+//! These tests exercise:
+//! - Round trips keep names, topology, every vertex attribute and index
+//!   bit for bit
+//! - Headers read alone; bad magic, other versions, unknown topologies,
+//!   out-of-range indices and truncated data are errors
+//! - `cached_mesh` builds once, loads afterwards and rebuilds when the key
+//!   changes or the file is corrupt
+
+use std::cell::Cell;
+use std::io::Cursor;
+use crate::geometry::{Geometry, SphereTessellation};
+use crate::mesh::MeshData;
+use crate::tests::golden::TempFile;
+use crate::vtm::{self, FORMAT_VERSION, MeshBundle, VtmError};
+
+fn sphere() -> MeshData {
+    let mut mesh = MeshData::new();
+    Geometry::Sphere { radius: 1.5, subdivisions: 12, tessellation: SphereTessellation::Uv }.generate_mesh_data(&mut mesh, &Default::default(), [0.2, 0.4, 0.6, 1.0]);
+    mesh
+}
+
+fn bytes(bundle: &MeshBundle) -> Vec<u8> {
+    let mut out = Vec::new();
+    vtm::write(&mut out, bundle).unwrap();
+    out
+}
+
+fn same(a: &MeshData, b: &MeshData) -> bool {
+    let bits = |m: &MeshData| m.vertices.iter()
+        .flat_map(|v| v.position.iter().chain(&v.color).chain(&v.uv).map(|f| f.to_bits()).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    a.topology == b.topology && a.indices == b.indices && bits(a) == bits(b)
+}
+
+#[test]
+fn bundles_round_trip() {
+    let mut path = MeshData::lines();
+    path.push_polyline(&[[0.0; 3], [1.0, 2.0, 3.0], [-1.0, 0.5, 0.0]], [1.0, 0.0, 0.0, 1.0]);
+    let mut bundle = MeshBundle::new(0xDEAD_BEEF_0123);
+    bundle.push("sphere", sphere());
+    bundle.push("path ✓", path);
+    bundle.push("empty", MeshData::new());
+
+    let data = bytes(&bundle);
+    let header = vtm::read_header(&mut Cursor::new(&data)).unwrap();
+    assert_eq!((header.format_version, header.cache_key, header.mesh_count), (FORMAT_VERSION, 0xDEAD_BEEF_0123, 3));
+    let read = vtm::read(&mut Cursor::new(&data)).unwrap();
+    assert_eq!(read.cache_key, bundle.cache_key);
+    let names: Vec<&str> = read.meshes.iter().map(|(n, _)| n.as_str()).collect();
+    assert_eq!(names, ["sphere", "path ✓", "empty"]);
+    for (name, mesh) in &bundle.meshes {
+        assert!(same(read.get(name).unwrap(), mesh), "{name}");
+    }
+    let vertices = bundle.meshes.iter().map(|(_, m)| m.vertices.len()).sum::<usize>();
+    let indices = bundle.meshes.iter().map(|(_, m)| m.indices.len()).sum::<usize>();
+    let names = bundle.meshes.iter().map(|(n, _)| n.len()).sum::<usize>();
+    assert_eq!(data.len(), 20 + 3 * 11 + names + 32 * vertices + 4 * indices, "no padding");
+}
+
+#[test]
+fn bad_files_are_errors() {
+    let mut bundle = MeshBundle::new(1);
+    bundle.push("sphere", sphere());
+    let data = bytes(&bundle);
+    let read = |data: &[u8]| vtm::read(&mut Cursor::new(data));
+
+    let mut magic = data.clone();
+    magic[0] = b'X';
+    assert!(matches!(read(&magic), Err(VtmError::InvalidMagic)));
+    let mut version = data.clone();
+    version[4..6].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+    assert!(matches!(read(&version), Err(VtmError::UnsupportedVersion { found }) if found == FORMAT_VERSION + 1));
+    // Name length, "sphere", then the topology byte.
+    let mut topology = data.clone();
+    topology[20 + 2 + 6] = 9;
+    assert!(matches!(read(&topology), Err(VtmError::UnknownTopology(9))));
+    let mut index = data.clone();
+    let last = index.len() - 4;
+    index[last..].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(matches!(read(&index), Err(VtmError::IndexOutOfRange { index: u32::MAX, .. })));
+    assert!(matches!(read(&data[..data.len() - 1]), Err(VtmError::Io(_))));
+    // A count far larger than the data fails without allocating it.
+    let mut count = data.clone();
+    count[20 + 2 + 6 + 1..20 + 2 + 6 + 5].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(matches!(read(&count), Err(VtmError::Io(_))));
+}
+
+#[test]
+fn cached_mesh_builds_once_per_key() {
+    let file = TempFile::new("cache/mesh.vtm");
+    let builds = Cell::new(0);
+    let build = || {
+        builds.set(builds.get() + 1);
+        sphere()
+    };
+    let first = vtm::cached_mesh(&file.path, 7, build).unwrap();
+    let second = vtm::cached_mesh(&file.path, 7, build).unwrap();
+    assert_eq!(builds.get(), 1, "the second call loads the file");
+    assert!(same(&first, &second));
+    assert_eq!(vtm::header_from_file(&file.path).unwrap().cache_key, 7);
+
+    vtm::cached_mesh(&file.path, 8, build).unwrap();
+    assert_eq!(builds.get(), 2, "a new key rebuilds");
+    std::fs::write(&file.path, b"VTM\0garbage").unwrap();
+    let rebuilt = vtm::cached_mesh(&file.path, 8, build).unwrap();
+    assert_eq!(builds.get(), 3, "a corrupt cache rebuilds");
+    assert!(same(&rebuilt, &first));
+}
@@ -1,32 +1,39 @@
-/// Tests for the VTR binary scene format (vtr.rs).
-///
-/// All tests operate on in-memory buffers (`Vec<u8>` / `std::io::Cursor`) so
-/// no filesystem I/O is required and the suite runs fully offline.
-///
-/// Coverage:
-///   - round-trip: empty scene, single object, full hierarchy
-///   - every Geometry variant
-///   - camera field fidelity
-///   - root ordering preservation
-///   - next_id continuity after load (no ID collision on spawn)
-///   - deterministic / idempotent output
-///   - header-only reads (`read_header`)
-///   - `VtrHeader::engine_version_string`
-///   - error path: bad magic bytes
-///   - error path: unsupported format version
-///   - error path: unknown geometry tag
-///   - error path: truncated data (unexpected EOF)
-///   - UTF-8 object names (including multibyte characters)
-///   - object with long names (including a 300-byte name)
-///   - deeply nested hierarchy (3 levels)
-///   - multiple root objects, order preserved
-///   - object with no geometry but non-default transform and color
-///   - object deleted after load doesn't affect sibling IDs
+//! Tests for the VTR binary scene format (vtr.rs).
+//!
+//! All tests operate on in-memory buffers (`Vec<u8>` / `std::io::Cursor`) so
+//! no filesystem I/O is required and the suite runs fully offline.
+//!
+//! Coverage:
+//!   - round-trip: empty scene, single object, full hierarchy
+//!   - every Geometry variant
+//!   - camera field fidelity
+//!   - root ordering preservation
+//!   - next_id continuity after load (no ID collision on spawn)
+//!   - deterministic / idempotent output
+//!   - header-only reads (`read_header`)
+//!   - `VtrHeader::engine_version_string`
+//!   - error path: bad magic bytes
+//!   - error path: unsupported format version
+//!   - error path: unknown geometry tag, cull mode and sphere tessellation
+//!   - error path: segment counts above `MAX_SEGMENTS`
+//!   - error path: truncated data (unexpected EOF)
+//!   - UTF-8 object names (including multibyte characters)
+//!   - object with long names (including a 300-byte name)
+//!   - deeply nested hierarchy (3 levels)
+//!   - multiple root objects, order preserved
+//!   - object with no geometry but non-default transform and color
+//!   - object deleted after load doesn't affect sibling IDs
+//!   - render layers and materials round-trip; version 2 / 3 / 4 / 5 / 6 / 7
+//!     / 8 / 9 / 10 files load with defaults
+//!   - camera bookmarks: camera-only files, and cameras read from full scenes
 
 use std::io::Cursor;
 use crate::camera::Camera;
-use crate::geometry::Geometry;
+use crate::constants::layers;
+use crate::geometry::{Geometry, SphereTessellation};
+use crate::material::{CullMode, Material};
 use crate::objects::{Object, ObjectConstructor};
+use crate::pipeline_cache::DepthBias;
 use crate::transform::Transform;
 use crate::vtr::{
     self, ENGINE_VERSION_MAJOR, ENGINE_VERSION_MINOR, ENGINE_VERSION_PATCH, FORMAT_VERSION, MAGIC,
@@ -51,6 +58,11 @@ fn custom_camera() -> Camera {
         zfar: 500.0,
         lr_rot: 45.0,
         ud_rot: -15.0,
+        layers_mask: layers::DEFAULT | layers::UI,
+        exposure: 2.5,
+        focus_distance: 7.5,
+        aperture: 3.0,
+        ortho_height: Some(12.0),
     }
 }
 
@@ -84,6 +96,11 @@ fn assert_cameras_eq(a: &Camera, b: &Camera) {
     assert_eq!(a.zfar, b.zfar, "zfar mismatch");
     assert_eq!(a.lr_rot, b.lr_rot, "lr_rot mismatch");
     assert_eq!(a.ud_rot, b.ud_rot, "ud_rot mismatch");
+    assert_eq!(a.layers_mask, b.layers_mask, "layers_mask mismatch");
+    assert_eq!(a.exposure, b.exposure, "exposure mismatch");
+    assert_eq!(a.focus_distance, b.focus_distance, "focus_distance mismatch");
+    assert_eq!(a.aperture, b.aperture, "aperture mismatch");
+    assert_eq!(a.ortho_height, b.ortho_height, "ortho_height mismatch");
 }
 
 /// Assert two objects are field-for-field equal.
@@ -92,6 +109,7 @@ fn assert_objects_eq(a: &Object, b: &Object) {
     assert_eq!(a.transform, b.transform, "transform mismatch");
     assert_eq!(a.geometry, b.geometry, "geometry mismatch");
     assert_eq!(a.color, b.color, "color mismatch");
+    assert_eq!(a.layer, b.layer, "layer mismatch");
     assert_eq!(a.parent, b.parent, "parent mismatch");
     // Sort children before comparing - insertion order may differ on reload.
     let mut ca = a.children.clone();
@@ -181,9 +199,9 @@ fn empty_scene_roundtrip() {
 
 #[test]
 fn empty_scene_minimum_size() {
-    // header(20) + camera(60) + roots_count(4) = 84 bytes minimum
+    // header(20) + camera(80) + roots_count(4) = 104 bytes minimum
     let bytes = serialize(&test_camera(), &World::new());
-    assert_eq!(bytes.len(), 84, "minimum file size should be 84 bytes");
+    assert_eq!(bytes.len(), 104, "minimum file size should be 104 bytes");
 }
 
 // camera round-trip
@@ -206,6 +224,11 @@ fn camera_negative_values() {
         zfar: 10_000.0,
         lr_rot: -180.0,
         ud_rot: -89.0,
+        layers_mask: 0,
+        exposure: 0.0,
+        focus_distance: 0.0,
+        aperture: 0.0,
+        ortho_height: None,
     };
     let data = roundtrip(&camera, &World::new());
     assert_cameras_eq(&camera, &data.camera);
@@ -250,6 +273,154 @@ fn single_object_default() {
     assert_objects_eq(loaded, original);
 }
 
+#[test]
+fn object_layer_roundtrip() {
+    let mut world = World::new();
+    let id = world.spawn_object(Object { layer: layers::UI | 0b100, ..Default::default() }, None);
+    let data = roundtrip(&test_camera(), &world);
+    assert_eq!(data.world.objects[&id].layer, layers::UI | 0b100);
+}
+
+#[test]
+fn object_material_roundtrip() {
+    let mut world = World::new();
+    let material = Material::default()
+        .with_cull_mode(CullMode::None)
+        .with_depth_write(false)
+        .with_depth_bias(DepthBias { constant: -8, slope_scale: -1.5 })
+        .with_emission([1.0, 0.5, 0.25], 3.0)
+        .with_displacement([0.1, 0.0, 0.2], [0.5, 0.0, 1.0], 2.0);
+    let id = world.spawn_object(Object { material, ..Default::default() }, None);
+    let data = roundtrip(&test_camera(), &world);
+    assert_eq!(data.world.objects[&id].material, material);
+}
+
+#[test]
+fn version_9_file_loads_without_emission() {
+    let mut world = World::new();
+    let material = Material::default().with_cull_mode(CullMode::Front).with_emission([1.0; 3], 2.0);
+    let id = world.spawn_object(Object { material, ..Default::default() }, None);
+    let mut bytes = serialize(&test_camera(), &world);
+    // Downgrade to the version 9 layout: drop the emission and
+    // displacement (just before the trailing children_count).
+    bytes[4..6].copy_from_slice(&9u16.to_le_bytes());
+    let end = bytes.len();
+    bytes.drain(end - 48..end - 4);
+
+    let data = deserialize(&bytes);
+    assert_eq!(data.world.objects[&id].material, Material::default().with_cull_mode(CullMode::Front));
+}
+
+#[test]
+fn version_10_file_loads_without_displacement() {
+    let mut world = World::new();
+    let material = Material::default().with_emission([1.0; 3], 2.0).with_displacement([1.0; 3], [1.0; 3], 1.0);
+    let id = world.spawn_object(Object { material, ..Default::default() }, None);
+    let mut bytes = serialize(&test_camera(), &world);
+    // Downgrade to the version 10 layout: drop the displacement (just
+    // before the trailing children_count).
+    bytes[4..6].copy_from_slice(&10u16.to_le_bytes());
+    let end = bytes.len();
+    bytes.drain(end - 32..end - 4);
+
+    let data = deserialize(&bytes);
+    assert_eq!(data.world.objects[&id].material, Material::default().with_emission([1.0; 3], 2.0));
+}
+
+#[test]
+fn version_7_file_loads_with_default_material() {
+    let mut world = World::new();
+    let material = Material::default().with_cull_mode(CullMode::Front);
+    let id = world.spawn_object(Object { material, ..Default::default() }, None);
+    let mut bytes = serialize(&test_camera(), &world);
+    // Downgrade to the version 7 layout: drop the material (just before
+    // the trailing children_count).
+    bytes[4..6].copy_from_slice(&7u16.to_le_bytes());
+    let end = bytes.len();
+    bytes.drain(end - 58..end - 4);
+
+    let data = deserialize(&bytes);
+    assert_eq!(data.world.objects[&id].material, Material::default());
+    assert_eq!(data.world.objects[&id].layer, layers::DEFAULT);
+}
+
+#[test]
+fn unknown_cull_mode_is_an_error() {
+    let mut world = World::new();
+    world.spawn_object(Object::default(), None);
+    let mut bytes = serialize(&test_camera(), &world);
+    let at = bytes.len() - 58;
+    bytes[at] = 9;
+    let result = vtr::read(&mut Cursor::new(bytes));
+    assert!(matches!(result, Err(vtr::VtrError::UnknownCullMode(9))));
+}
+
+#[test]
+fn version_2_file_loads_with_default_layers() {
+    let mut world = World::new();
+    let id = world.spawn_object(Object { layer: layers::UI, ..Default::default() }, None);
+    let camera = custom_camera();
+    let mut bytes = serialize(&camera, &world);
+
+    // Downgrade to the version 2 layout: drop the camera mask, exposure,
+    // focus and orthographic height (bytes 80..100) and the object layer
+    // and material (just before the trailing children_count).
+    bytes[4..6].copy_from_slice(&2u16.to_le_bytes());
+    let end = bytes.len();
+    bytes.drain(end - 62..end - 4);
+    bytes.drain(80..100);
+
+    let data = deserialize(&bytes);
+    assert_eq!(data.camera.layers_mask, layers::ALL);
+    assert_eq!(data.camera.exposure, 1.0);
+    assert_eq!(data.camera.fov, camera.fov);
+    assert_eq!(data.world.objects[&id].layer, layers::DEFAULT);
+    assert_eq!(data.world.objects[&id].name, world.objects[&id].name);
+}
+
+#[test]
+fn version_3_file_loads_with_default_exposure() {
+    let camera = custom_camera();
+    let mut bytes = serialize(&camera, &World::new());
+    // Downgrade to the version 3 layout: drop the exposure, focus and
+    // orthographic height (bytes 84..100).
+    bytes[4..6].copy_from_slice(&3u16.to_le_bytes());
+    bytes.drain(84..100);
+
+    let data = deserialize(&bytes);
+    assert_eq!(data.camera.layers_mask, camera.layers_mask);
+    assert_eq!(data.camera.exposure, 1.0);
+}
+
+#[test]
+fn version_4_file_loads_with_default_focus() {
+    let camera = custom_camera();
+    let mut bytes = serialize(&camera, &World::new());
+    // Downgrade to the version 4 layout: drop the focus and orthographic
+    // height (bytes 88..100).
+    bytes[4..6].copy_from_slice(&4u16.to_le_bytes());
+    bytes.drain(88..100);
+
+    let data = deserialize(&bytes);
+    assert_eq!(data.camera.exposure, camera.exposure);
+    assert_eq!(data.camera.focus_distance, Camera::new().focus_distance);
+    assert_eq!(data.camera.aperture, Camera::new().aperture);
+}
+
+#[test]
+fn version_6_file_loads_with_perspective_camera() {
+    let camera = custom_camera();
+    let mut bytes = serialize(&camera, &World::new());
+    // Downgrade to the version 6 layout: drop the orthographic height
+    // (bytes 96..100).
+    bytes[4..6].copy_from_slice(&6u16.to_le_bytes());
+    bytes.drain(96..100);
+
+    let data = deserialize(&bytes);
+    assert_eq!(data.camera.aperture, camera.aperture);
+    assert_eq!(data.camera.ortho_height, None);
+}
+
 // geometry variants
 fn roundtrip_geometry(geom: Geometry) -> Option<Geometry> {
     let mut world = World::new();
@@ -278,19 +449,68 @@ fn geometry_none_roundtrip() {
 
 #[test]
 fn geometry_cube_roundtrip() {
-    let g = Geometry::Cube { size: 3.14 };
+    let g = Geometry::Cube { size: std::f32::consts::PI };
     assert_eq!(roundtrip_geometry(g.clone()), Some(g));
 }
 
 #[test]
 fn geometry_box_roundtrip() {
-    let g = Geometry::Box { width: 1.0, height: 2.5, depth: 0.75 };
+    let g = Geometry::Box { width: 1.0, height: 2.5, depth: 0.75, segments: [2, 3, 4] };
     assert_eq!(roundtrip_geometry(g.clone()), Some(g));
 }
 
 #[test]
 fn geometry_plane_roundtrip() {
-    let g = Geometry::Plane { size: 10.0 };
+    let g = Geometry::Plane { size: 10.0, subdivisions: 8 };
+    assert_eq!(roundtrip_geometry(g.clone()), Some(g));
+}
+
+#[test]
+fn version_5_file_loads_with_default_subdivisions() {
+    let mut world = World::new();
+    world.spawn_object(
+        Object::new(ObjectConstructor {
+            name: "ground".to_string(),
+            transform: None,
+            geometry: Some(Geometry::Plane { size: 10.0, subdivisions: 8 }),
+            color: None,
+            str_id: None,
+            texture_path: None,
+        }),
+        None,
+    );
+    let mut bytes = serialize(&test_camera(), &world);
+    // Downgrade to the version 5 layout: drop the object material, the u32
+    // after the plane size and the camera's orthographic height.
+    let end = bytes.len();
+    bytes.drain(end - 58..end - 4);
+    let mut plane = vec![3u8];
+    plane.extend_from_slice(&10.0f32.to_le_bytes());
+    let at = bytes.windows(5).position(|w| w == plane).unwrap() + 5;
+    bytes.drain(at..at + 4);
+    bytes.drain(96..100);
+    bytes[4..6].copy_from_slice(&5u16.to_le_bytes());
+
+    let data = deserialize(&bytes);
+    let geometry = data.world.objects.into_values().next().unwrap().geometry;
+    assert_eq!(geometry, Some(Geometry::Plane { size: 10.0, subdivisions: 1 }));
+}
+
+#[test]
+fn geometry_rounded_box_roundtrip() {
+    let g = Geometry::RoundedBox { width: 2.0, height: 1.0, depth: 0.5, radius: 0.1, subdivisions: 4 };
+    assert_eq!(roundtrip_geometry(g.clone()), Some(g));
+}
+
+#[test]
+fn geometry_arrow_roundtrip() {
+    let g = Geometry::Arrow { length: 2.0, shaft_radius: 0.05, head_size: 0.4 };
+    assert_eq!(roundtrip_geometry(g.clone()), Some(g));
+}
+
+#[test]
+fn geometry_sprite_roundtrip() {
+    let g = Geometry::Sprite { width: 1.5, height: 2.0, uv: [0.0, 0.25, 0.125, 0.5] };
     assert_eq!(roundtrip_geometry(g.clone()), Some(g));
 }
 
@@ -308,10 +528,57 @@ fn geometry_capsule_roundtrip() {
 
 #[test]
 fn geometry_sphere_roundtrip() {
-    let g = Geometry::Sphere { radius: 1.0, subdivisions: 32 };
+    let g = Geometry::Sphere { radius: 1.0, subdivisions: 32, tessellation: SphereTessellation::Uv };
+    assert_eq!(roundtrip_geometry(g.clone()), Some(g));
+    let g = Geometry::Sphere { radius: 2.5, subdivisions: 12, tessellation: SphereTessellation::Icosphere };
     assert_eq!(roundtrip_geometry(g.clone()), Some(g));
 }
 
+/// Bytes of a scene holding one icosphere, and the offset of its
+/// tessellation byte.
+fn icosphere_scene() -> (Vec<u8>, usize) {
+    let mut world = World::new();
+    let geometry = Geometry::Sphere { radius: 2.5, subdivisions: 12, tessellation: SphereTessellation::Icosphere };
+    world.spawn_object(Object { geometry: Some(geometry), ..Default::default() }, None);
+    let bytes = serialize(&test_camera(), &world);
+    let mut sphere = vec![6u8];
+    sphere.extend_from_slice(&2.5f32.to_le_bytes());
+    sphere.extend_from_slice(&12u32.to_le_bytes());
+    let at = bytes.windows(9).position(|w| w == sphere).unwrap() + 9;
+    (bytes, at)
+}
+
+#[test]
+fn version_8_file_loads_uv_spheres() {
+    let (mut bytes, at) = icosphere_scene();
+    let end = bytes.len();
+    bytes.drain(end - 48..end - 4);
+    bytes.remove(at);
+    bytes[4..6].copy_from_slice(&8u16.to_le_bytes());
+    let data = deserialize(&bytes);
+    let geometry = data.world.objects.into_values().next().unwrap().geometry;
+    assert_eq!(geometry, Some(Geometry::Sphere { radius: 2.5, subdivisions: 12, tessellation: SphereTessellation::Uv }));
+}
+
+#[test]
+fn unknown_tessellation_is_an_error() {
+    let (mut bytes, at) = icosphere_scene();
+    bytes[at] = 7;
+    let result = vtr::read(&mut Cursor::new(bytes));
+    assert!(matches!(result, Err(vtr::VtrError::UnknownTessellation(7))));
+}
+
+#[test]
+fn oversized_segment_count_is_an_error() {
+    let (mut bytes, at) = icosphere_scene();
+    bytes[at - 4..at].copy_from_slice(&u32::MAX.to_le_bytes());
+    let result = vtr::read(&mut Cursor::new(bytes));
+    assert!(matches!(result, Err(vtr::VtrError::TooManySegments { found: u32::MAX })));
+
+    let g = Geometry::Plane { size: 1.0, subdivisions: vtr::MAX_SEGMENTS as usize };
+    assert_eq!(roundtrip_geometry(g.clone()), Some(g), "the limit itself is accepted");
+}
+
 #[test]
 fn geometry_capsule_large_subdivisions() {
     let g = Geometry::Capsule { radius: 1.0, height: 5.0, subdivisions: 256 };
@@ -337,7 +604,7 @@ fn parent_child_roundtrip() {
         Object::new(ObjectConstructor {
             name: "Child".to_string(),
             transform: Some(Transform::from_position(5.0, 0.0, 0.0)),
-            geometry: Some(Geometry::Sphere { radius: 0.5, subdivisions: 8 }),
+            geometry: Some(Geometry::Sphere { radius: 0.5, subdivisions: 8, tessellation: SphereTessellation::Uv }),
             color: Some([0.0, 1.0, 0.0, 1.0]),
             str_id: None,
             texture_path: None,
@@ -368,7 +635,7 @@ fn deep_three_level_hierarchy() {
         Object::new(ObjectConstructor {
             name: "Sun".to_string(),
             transform: None,
-            geometry: Some(Geometry::Sphere { radius: 2.0, subdivisions: 32 }),
+            geometry: Some(Geometry::Sphere { radius: 2.0, subdivisions: 32, tessellation: SphereTessellation::Uv }),
             color: Some([1.0, 0.9, 0.2, 1.0]),
             str_id: None,
             texture_path: None,
@@ -379,7 +646,7 @@ fn deep_three_level_hierarchy() {
         Object::new(ObjectConstructor {
             name: "Planet".to_string(),
             transform: Some(Transform::from_position(6.0, 0.0, 0.0)),
-            geometry: Some(Geometry::Sphere { radius: 0.8, subdivisions: 24 }),
+            geometry: Some(Geometry::Sphere { radius: 0.8, subdivisions: 24, tessellation: SphereTessellation::Uv }),
             color: Some([0.2, 0.5, 1.0, 1.0]),
             str_id: None,
             texture_path: None,
@@ -390,7 +657,7 @@ fn deep_three_level_hierarchy() {
         Object::new(ObjectConstructor {
             name: "Moon".to_string(),
             transform: Some(Transform::from_position(1.5, 0.0, 0.0)),
-            geometry: Some(Geometry::Sphere { radius: 0.3, subdivisions: 16 }),
+            geometry: Some(Geometry::Sphere { radius: 0.3, subdivisions: 16, tessellation: SphereTessellation::Uv }),
             color: Some([0.7, 0.7, 0.7, 1.0]),
             str_id: None,
             texture_path: None,
@@ -403,7 +670,7 @@ fn deep_three_level_hierarchy() {
     assert_eq!(data.world.roots, vec![sun_id], "only sun is root");
     assert_eq!(data.world.objects[&sun_id].children, vec![planet_id]);
     assert_eq!(data.world.objects[&planet_id].children, vec![moon_id]);
-    assert_eq!(data.world.objects[&moon_id].children, vec![]);
+    assert_eq!(data.world.objects[&moon_id].children, Vec::<usize>::new());
     assert_eq!(data.world.objects[&moon_id].parent, Some(planet_id));
 }
 
@@ -551,7 +818,7 @@ fn transform_all_fields() {
     let t = Transform {
         position: [-12.34, 56.78, -0.001],
         rotation: [180.0, -90.0, 45.0],
-        scale: [0.1, 100.0, 3.14159],
+        scale: [0.1, 100.0, std::f32::consts::PI],
     };
     let mut world = World::new();
     let id = world.spawn_object(
@@ -630,7 +897,7 @@ fn idempotent_roundtrip() {
         Object::new(ObjectConstructor {
             name: "Sphere".to_string(),
             transform: Some(Transform::from_position(1.0, 2.0, 3.0)),
-            geometry: Some(Geometry::Sphere { radius: 1.0, subdivisions: 16 }),
+            geometry: Some(Geometry::Sphere { radius: 1.0, subdivisions: 16, tessellation: SphereTessellation::Uv }),
             color: Some([0.8, 0.2, 0.4, 1.0]),
             str_id: None,
             texture_path: None,
@@ -697,7 +964,7 @@ fn error_bad_magic() {
 
 #[test]
 fn error_wrong_magic_all_zeros() {
-    let bytes = vec![0u8; 20];
+    let bytes = [0u8; 20];
     let mut cur = Cursor::new(&bytes[..]);
     assert!(matches!(vtr::read(&mut cur), Err(vtr::VtrError::InvalidMagic)));
 }
@@ -844,7 +1111,7 @@ fn solar_system_full_roundtrip() {
         Object::new(ObjectConstructor {
             name: "Sun".to_string(),
             transform: Some(Transform::from_position(0.0, 0.0, 0.0)),
-            geometry: Some(Geometry::Sphere { radius: 2.0, subdivisions: 32 }),
+            geometry: Some(Geometry::Sphere { radius: 2.0, subdivisions: 32, tessellation: SphereTessellation::Uv }),
             color: Some([1.0, 0.9, 0.2, 1.0]),
             str_id: None,
             texture_path: None,
@@ -855,7 +1122,7 @@ fn solar_system_full_roundtrip() {
         Object::new(ObjectConstructor {
             name: "Planet".to_string(),
             transform: Some(Transform::from_position(6.0, 0.0, 0.0)),
-            geometry: Some(Geometry::Sphere { radius: 0.8, subdivisions: 24 }),
+            geometry: Some(Geometry::Sphere { radius: 0.8, subdivisions: 24, tessellation: SphereTessellation::Uv }),
             color: Some([0.2, 0.5, 1.0, 1.0]),
             str_id: None,
             texture_path: None,
@@ -866,7 +1133,7 @@ fn solar_system_full_roundtrip() {
         Object::new(ObjectConstructor {
             name: "Moon".to_string(),
             transform: Some(Transform::from_position(1.5, 0.0, 0.0)),
-            geometry: Some(Geometry::Sphere { radius: 0.3, subdivisions: 16 }),
+            geometry: Some(Geometry::Sphere { radius: 0.3, subdivisions: 16, tessellation: SphereTessellation::Uv }),
             color: Some([0.7, 0.7, 0.7, 1.0]),
             str_id: None,
             texture_path: None,
@@ -880,7 +1147,7 @@ fn solar_system_full_roundtrip() {
                 Object::new(ObjectConstructor {
                     name: format!("Asteroid {i}"),
                     transform: Some(Transform::from_position(4.0 + i as f32 * 0.2, 0.0, 0.0)),
-                    geometry: Some(Geometry::Sphere { radius: 0.05, subdivisions: 4 }),
+                    geometry: Some(Geometry::Sphere { radius: 0.05, subdivisions: 4, tessellation: SphereTessellation::Uv }),
                     color: Some([0.6, 0.5, 0.4, 1.0]),
                     str_id: None,
                     texture_path: None,
@@ -1018,7 +1285,7 @@ fn str_id_unicode_handles() {
     );
 
     let data = roundtrip(&test_camera(), &world);
-    assert_eq!(data.world.get_id(&unicode_id).is_some(), true);
+    assert!(data.world.get_id(&unicode_id).is_some());
 }
 
 // --- texture_path roundtrip tests ---
@@ -1156,3 +1423,23 @@ fn texture_path_error_display() {
     assert!(s.contains("70000") || s.contains("70_000"), "display should mention the length: {s}");
     assert!(s.contains("65535") || s.contains("u16"), "display should mention the limit: {s}");
 }
+
+#[test]
+fn camera_bookmark_roundtrip() {
+    let camera = custom_camera();
+    let mut buf = Vec::new();
+    vtr::write_camera(&mut buf, &camera).unwrap();
+    assert_eq!(buf.len(), 104, "a bookmark is an empty scene");
+    assert_eq!(vtr::read_camera(&mut Cursor::new(&buf)).unwrap(), camera);
+    assert_eq!(vtr::read(&mut Cursor::new(&buf)).unwrap().world.objects.len(), 0);
+}
+
+#[test]
+fn camera_read_from_full_scene() {
+    let camera = custom_camera();
+    let mut world = World::new();
+    world.spawn_object(Object { geometry: Some(Geometry::Cube { size: 1.0 }), ..Default::default() }, None);
+    let mut buf = Vec::new();
+    vtr::write(&mut buf, &camera, &world).unwrap();
+    assert_eq!(vtr::read_camera(&mut Cursor::new(&buf)).unwrap(), camera);
+}
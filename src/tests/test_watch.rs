@@ -0,0 +1,230 @@
+//! Tests for the polling file watcher and scene hot reloading.
+//!
+//! Coverage:
+//!   - unchanged files are not reported
+//!   - a modification is reported exactly once
+//!   - files that appear after `watch` are reported, deletions are not
+//!   - `unwatch` and the poll interval
+//!   - `Scene::poll_hot_reload` swaps the world and keeps the camera,
+//!     ignores the scene's own `save_vtr_file`,
+//!     re-uploads textures, and rebuilds pipelines for edited shaders
+//!     (needs a fallback adapter, skipped otherwise)
+//!
+//! Modification times are set explicitly so the tests do not depend on the
+//! file system's timestamp granularity.
+
+use std::fs::File;
+use std::time::{Duration, SystemTime};
+
+use crate::camera::Camera;
+use crate::objects::Object;
+use crate::scene::Scene;
+use crate::tests::golden::{self, TempFile};
+use crate::vtr;
+use crate::watch::{AssetKind, FileWatcher};
+use crate::world::World;
+
+fn watcher() -> FileWatcher {
+    FileWatcher::new().with_interval(0.0)
+}
+
+/// Write `contents` to `file` and set its modification time with [`touch`].
+fn write(file: &TempFile, contents: &[u8], age_secs: u64) {
+    std::fs::write(&file.path, contents).unwrap();
+    touch(file, age_secs);
+}
+
+/// Set the modification time to `age_secs` seconds after the epoch baseline,
+/// so each call can produce a distinct, ordered timestamp.
+fn touch(file: &TempFile, age_secs: u64) {
+    let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000 + age_secs);
+    File::options().write(true).open(&file.path).unwrap().set_modified(time).unwrap();
+}
+
+#[test]
+fn unchanged_file_is_not_reported() {
+    let file = TempFile::new("watched.txt");
+    write(&file, b"a", 0);
+    let mut w = watcher();
+    w.watch(&file.path);
+    assert!(w.poll().is_empty());
+    assert!(w.poll().is_empty());
+}
+
+#[test]
+fn modification_is_reported_once() {
+    let file = TempFile::new("watched.txt");
+    write(&file, b"a", 0);
+    let mut w = watcher();
+    w.watch(&file.path);
+
+    write(&file, b"b", 1);
+    assert_eq!(w.poll(), vec![file.path.clone()]);
+    assert!(w.poll().is_empty(), "a change must only be reported once");
+}
+
+#[test]
+fn file_created_after_watch_is_reported() {
+    let file = TempFile::new("watched.txt");
+    let mut w = watcher();
+    w.watch(&file.path);
+    assert!(w.poll().is_empty());
+
+    write(&file, b"a", 0);
+    assert_eq!(w.poll(), vec![file.path.clone()]);
+}
+
+#[test]
+fn deletion_is_not_reported_until_file_returns() {
+    let file = TempFile::new("watched.txt");
+    write(&file, b"a", 0);
+    let mut w = watcher();
+    w.watch(&file.path);
+
+    std::fs::remove_file(&file.path).unwrap();
+    assert!(w.poll().is_empty());
+    write(&file, b"a", 0);
+    assert_eq!(w.poll(), vec![file.path.clone()], "re-created file counts as a change");
+}
+
+#[test]
+fn unwatch_stops_reporting() {
+    let file = TempFile::new("watched.txt");
+    write(&file, b"a", 0);
+    let mut w = watcher();
+    w.watch(&file.path);
+    assert!(w.unwatch(&file.path));
+    assert!(!w.unwatch(&file.path));
+    assert!(w.is_empty());
+
+    write(&file, b"b", 1);
+    assert!(w.poll().is_empty());
+}
+
+#[test]
+fn poll_interval_throttles_but_poll_now_does_not() {
+    let file = TempFile::new("watched.txt");
+    write(&file, b"a", 0);
+    let mut w = FileWatcher::new().with_interval(3600.0);
+    w.watch(&file.path);
+    assert!(w.poll().is_empty(), "first poll establishes the interval");
+
+    write(&file, b"b", 1);
+    assert!(w.poll().is_empty(), "within the interval");
+    assert_eq!(w.poll_now(), vec![file.path.clone()]);
+}
+
+fn headless_scene() -> Option<Scene> {
+    let mut scene = golden::headless_scene(16, 16, Camera::new())?;
+    scene.set_hot_reload_interval(0.0);
+    Some(scene)
+}
+
+#[test]
+fn scene_hot_reload_replaces_world_and_keeps_camera() {
+    let Some(mut scene) = headless_scene() else { return };
+    let file = TempFile::new("watched.vtr");
+    let mut world = World::new();
+    world.spawn_object(Object { str_id: "a".into(), ..Default::default() }, None);
+    vtr::write_to_file(&file.path, &Camera::new(), &world).unwrap();
+    touch(&file, 0);
+
+    scene.watch_vtr_file(&file.path).unwrap();
+    assert_eq!(scene.watched_vtr_file(), Some(file.path.as_path()));
+    assert!(scene.world.get_id("a").is_some());
+    scene.camera.eye = [7.0, 7.0, 7.0];
+
+    world.spawn_object(Object { str_id: "b".into(), ..Default::default() }, None);
+    vtr::write_to_file(&file.path, &Camera::new(), &world).unwrap();
+    touch(&file, 1);
+
+    let reloads = scene.poll_hot_reload();
+    assert_eq!(reloads.len(), 1);
+    assert_eq!(reloads[0].kind, AssetKind::Scene);
+    assert!(reloads[0].result.is_ok());
+    assert!(scene.world.get_id("b").is_some(), "edited file must be reloaded");
+    assert_eq!(scene.camera.eye, [7.0, 7.0, 7.0], "camera must be preserved");
+    assert!(scene.poll_hot_reload().is_empty());
+
+    scene.unwatch_vtr_file();
+    assert!(scene.watched_vtr_file().is_none());
+}
+
+#[test]
+fn scene_hot_reload_keeps_world_on_parse_error() {
+    let Some(mut scene) = headless_scene() else { return };
+    let file = TempFile::new("watched.vtr");
+    let mut world = World::new();
+    world.spawn_object(Object { str_id: "a".into(), ..Default::default() }, None);
+    vtr::write_to_file(&file.path, &Camera::new(), &world).unwrap();
+    touch(&file, 0);
+
+    scene.watch_vtr_file(&file.path).unwrap();
+
+    write(&file, b"not a vtr file", 1);
+    let reloads = scene.poll_hot_reload();
+    assert!(reloads[0].result.is_err());
+    assert!(scene.world.get_id("a").is_some(), "old world must survive a bad reload");
+}
+
+#[test]
+fn own_save_is_not_reloaded() {
+    let Some(mut scene) = headless_scene() else { return };
+    let file = TempFile::new("watched.vtr");
+    vtr::write_to_file(&file.path, &Camera::new(), &World::new()).unwrap();
+    touch(&file, 0);
+    scene.watch_vtr_file(&file.path).unwrap();
+
+    scene.world.spawn_object(Object { str_id: "kept".into(), ..Default::default() }, None);
+    scene.save_vtr_file(&file.path).unwrap();
+    assert!(scene.poll_hot_reload().is_empty(), "saving must not trigger a reload");
+
+    touch(&file, 1);
+    assert_eq!(scene.poll_hot_reload().len(), 1, "later edits are still picked up");
+}
+
+#[test]
+fn texture_hot_reload_swaps_entry() {
+    let Some(mut scene) = headless_scene() else { return };
+    let file = TempFile::new("watched.png");
+    image::save_buffer(&file.path, &[255, 0, 0, 255], 1, 1, image::ColorType::Rgba8).unwrap();
+    touch(&file, 0);
+    let key = file.path.to_str().unwrap().to_string();
+
+    scene.watch_texture(&key).unwrap();
+    assert_eq!(scene.textures[&key].texture.width(), 1);
+
+    image::save_buffer(&file.path, &[0u8; 16], 2, 2, image::ColorType::Rgba8).unwrap();
+    touch(&file, 1);
+    let reloads = scene.poll_hot_reload();
+    assert_eq!(reloads.len(), 1);
+    assert_eq!(reloads[0].kind, AssetKind::Texture);
+    assert!(reloads[0].result.is_ok());
+    assert_eq!(scene.textures[&key].texture.width(), 2, "entry must point at the new upload");
+
+    assert!(scene.unwatch_asset(&file.path));
+    assert!(scene.has_texture(&key), "unwatching keeps the texture loaded");
+}
+
+#[test]
+fn shader_hot_reload_keeps_previous_shader_on_error() {
+    let Some(mut scene) = headless_scene() else { return };
+    let file = TempFile::new("watched.wgsl");
+    let source = include_str!("../shader.wgsl");
+    write(&file, source.as_bytes(), 0);
+
+    scene.watch_shader(&file.path).unwrap();
+
+    write(&file, b"this is not wgsl", 1);
+    let reloads = scene.poll_hot_reload();
+    assert_eq!(reloads[0].kind, AssetKind::Shader);
+    assert!(reloads[0].result.is_err(), "invalid WGSL must be rejected");
+    // The previous pipelines are still usable.
+    scene.draw_world().unwrap();
+
+    let edited = source.replace("fn fs_main", "// edited\nfn fs_main");
+    write(&file, edited.as_bytes(), 2);
+    let reloads = scene.poll_hot_reload();
+    assert!(reloads[0].result.is_ok(), "{:?}", reloads[0].result);
+    scene.draw_world().unwrap();
+}
@@ -0,0 +1,132 @@
+//! Unit tests for animated water surfaces.
+//!
+//! These tests exercise:
+//! - Gerstner displacement: sine height, crest pinching and wave travel
+//! - Normals stay upright on flat water and tilt on slopes
+//! - Waves beyond `MAX_WAVES` are ignored, as in the shader
+//! - The plane mesh covers the configured extent at the rest level
+//! - The reflection camera mirrors the eye and target in the water plane
+//! - Water draws into the frame and its reflection target follows the setting
+
+use std::f32::consts::TAU;
+use crate::camera::Camera;
+use crate::constants::water::MAX_WAVES;
+use crate::geometry::Geometry;
+use crate::objects::Object;
+use crate::tests::golden;
+use crate::water::{Water, Wave};
+
+fn single_wave(wave: Wave) -> Water {
+    Water::default().with_level(2.0).with_waves(vec![wave])
+}
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-4
+}
+
+#[test]
+fn sine_wave_height_and_travel() {
+    let mut water = single_wave(Wave::new([1.0, 0.0], 0.5, 8.0).with_speed(2.0));
+    // A quarter wavelength along the direction is the crest.
+    assert!(close(water.height_at(2.0, 7.0), 2.5));
+    assert!(close(water.height_at(6.0, -3.0), 1.5));
+    // Steepness 0 leaves the point where it was horizontally.
+    let p = water.displace(1.3, 4.0);
+    assert!(close(p[0], 1.3) && close(p[2], 4.0));
+
+    // After one second the crest has moved `speed` units downwind.
+    water.time = 1.0;
+    assert!(close(water.height_at(4.0, 0.0), 2.5));
+}
+
+#[test]
+fn steep_crests_pinch_towards_the_peak() {
+    let water = single_wave(Wave::new([0.0, 2.0], 0.5, 8.0).with_steepness(1.0));
+    // At the rest point below the crest there is no horizontal motion; in
+    // between, points slide towards the crest at `z = 2`.
+    let crest = water.displace(0.0, 2.0);
+    assert!(close(crest[2], 2.0) && close(crest[1], 2.5));
+    let before = water.displace(0.0, 0.0);
+    assert!(close(before[2], 0.5), "{before:?}");
+    assert!(close(before[0], 0.0));
+}
+
+#[test]
+fn normals_follow_the_slope() {
+    let flat = Water::default().with_waves(Vec::new());
+    assert_eq!(flat.normal_at(3.0, -1.0), [0.0, 1.0, 0.0]);
+    assert!(close(flat.height_at(3.0, -1.0), 0.0));
+
+    let water = single_wave(Wave::new([1.0, 0.0], 0.5, 8.0));
+    // Rising side of the wave (phase 0) faces back against the direction.
+    let n = water.normal_at(0.0, 0.0);
+    let slope = 0.5 * TAU / 8.0;
+    assert!(close(n[0], -slope / (1.0 + slope * slope).sqrt()));
+    assert!(close(n[0] * n[0] + n[1] * n[1] + n[2] * n[2], 1.0));
+    let crest = water.normal_at(2.0, 0.0);
+    assert!(close(crest[0], 0.0) && close(crest[1], 1.0));
+}
+
+#[test]
+fn extra_waves_are_ignored() {
+    let wave = Wave::new([1.0, 0.0], 0.1, 4.0);
+    let full = Water::default().with_waves(vec![wave; MAX_WAVES]);
+    let extra = Water::default().with_waves(vec![wave; MAX_WAVES + 2]);
+    assert_eq!(full.displace(0.7, 0.2), extra.displace(0.7, 0.2));
+}
+
+#[test]
+fn mesh_spans_the_extent() {
+    let water = Water::default().with_level(-1.0).with_extent([10.0, -4.0], 6.0).with_resolution(3);
+    let mesh = water.mesh();
+    assert_eq!(mesh.vertices.len(), 16);
+    assert_eq!(mesh.indices.len(), 3 * 3 * 6);
+    assert_eq!(mesh.bounds(), Some(([7.0, -1.0, -7.0], [13.0, -1.0, -1.0])));
+    // Wound to face +Y.
+    let [a, b, c] = [0, 1, 2].map(|i| mesh.vertices[mesh.indices[i] as usize].position);
+    let cross_y = (b[2] - a[2]) * (c[0] - a[0]) - (b[0] - a[0]) * (c[2] - a[2]);
+    assert!(cross_y > 0.0);
+}
+
+#[test]
+fn reflection_camera_mirrors_the_view() {
+    let water = Water::default().with_level(1.0);
+    let camera = Camera { eye: [2.0, 5.0, -3.0], target: [0.0, -1.0, 4.0], ..Camera::new() };
+    let mirrored = water.reflection_camera(&camera);
+    assert_eq!(mirrored.eye, [2.0, -3.0, -3.0]);
+    assert_eq!(mirrored.target, [0.0, 3.0, 4.0]);
+    assert_eq!(mirrored.up, camera.up);
+    assert_eq!(mirrored.fov, camera.fov);
+}
+
+#[test]
+fn water_draws_and_reflects() {
+    let camera = Camera { eye: [0.0, 4.0, -6.0], target: [0.0, 0.0, 0.0], ..Camera::new() };
+    let Some(mut scene) = golden::headless_scene(32, 32, camera) else { return };
+    scene.spawn(Object {
+        geometry: Some(Geometry::Cube { size: 2.0 }),
+        color: [1.0, 0.2, 0.2, 1.0],
+        ..Default::default()
+    }, None);
+    let dry = golden::render(&mut scene);
+
+    scene.set_water(Some(Water::default().with_level(0.5).with_extent([0.0, 0.0], 40.0).with_resolution(8)));
+    let wet = golden::render(&mut scene);
+    assert_ne!(wet.pixels, dry.pixels);
+    let plain_targets = scene.gpu_memory().render_target_bytes;
+
+    scene.water_mut().unwrap().reflection = true;
+    scene.update_water(0.5);
+    assert_eq!(scene.water().unwrap().time, 0.5);
+    let reflected = golden::render(&mut scene);
+    assert_ne!(reflected.pixels, wet.pixels);
+    assert!(scene.gpu_memory().render_target_bytes > plain_targets);
+
+    scene.water_mut().unwrap().reflection = false;
+    golden::render(&mut scene);
+    assert_eq!(scene.gpu_memory().render_target_bytes, plain_targets);
+
+    scene.set_water(None);
+    assert!(scene.water().is_none());
+    assert_eq!(golden::render(&mut scene).pixels, dry.pixels);
+}
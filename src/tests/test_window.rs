@@ -0,0 +1,64 @@
+//! Tests for window placement (window.rs).
+//!
+//! Coverage:
+//!   - `MonitorInfo::window_position` centres on the monitor, applies
+//!     offsets relative to it, and clamps windows larger than the monitor
+//!   - `WindowConfig` defaults leave monitor and position to the OS and keep
+//!     updating while hidden
+//!   - `WindowError` names the replay or recording file that failed and keeps
+//!     the underlying error
+//!
+//! Listing real monitors needs an event loop and a display, so it is not
+//! covered here.
+
+use std::error::Error;
+
+use crate::window::{MonitorInfo, WindowConfig, WindowError};
+
+fn monitor(position: [i32; 2], size: [u32; 2]) -> MonitorInfo {
+    MonitorInfo {
+        index: 1,
+        name: Some("Test".into()),
+        size,
+        position,
+        refresh_rate_hz: Some(60.0),
+        scale_factor: 1.0,
+        primary: false,
+    }
+}
+
+#[test]
+fn window_position_on_monitor() {
+    // A second display to the right of a 1920-wide primary.
+    let m = monitor([1920, 0], [2560, 1440]);
+    assert_eq!(m.window_position([800, 600], None), [1920 + 880, 420], "centred");
+    assert_eq!(m.window_position([800, 600], Some([10, 20])), [1930, 20]);
+    // Too large to centre: pinned to the monitor's corner.
+    assert_eq!(m.window_position([4000, 600], None), [1920, 420]);
+}
+
+#[test]
+fn config_defaults_leave_placement_to_the_os() {
+    let config = WindowConfig::default();
+    assert!(!config.fullscreen);
+    assert_eq!(config.monitor, None);
+    assert_eq!(config.position, None);
+    assert!(!config.pause_when_hidden);
+}
+
+#[test]
+fn window_errors_describe_themselves() {
+    let e = WindowError::LoadReplay {
+        path: "missing.vrec.json".into(),
+        error: std::io::Error::from(std::io::ErrorKind::NotFound),
+    };
+    assert!(e.to_string().starts_with("Could not load replay missing.vrec.json: "), "{e}");
+    assert!(e.source().is_some());
+
+    let e = WindowError::SaveRecording {
+        path: "out.vrec.json".into(),
+        error: std::io::Error::from(std::io::ErrorKind::PermissionDenied),
+    };
+    assert!(e.to_string().starts_with("Could not save recording out.vrec.json: "), "{e}");
+    assert!(e.source().is_some());
+}
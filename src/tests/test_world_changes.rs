@@ -0,0 +1,113 @@
+//! Unit tests for per-frame change detection on `World`.
+//!
+//! These tests exercise:
+//! - spawned and deleted objects (with descendants) are reported once
+//! - objects spawned and deleted in the same frame are not reported
+//! - only real changes through `get_mut` count, and transforms separately
+//! - `mark_modified` covers writes through `objects`, and reparenting counts
+//! - `rename_str_id` counts unless the identifier is unchanged
+//! - `Scene::draw_world` publishes the frame's changes (skipped without a
+//!   software adapter)
+
+use crate::camera::Camera;
+use crate::objects::Object;
+use crate::tests::golden;
+use crate::world::{World, WorldChanges};
+
+fn spawn(world: &mut World, parent: Option<usize>) -> usize {
+    world.spawn_object(Object::default(), parent)
+}
+
+#[test]
+fn spawn_and_delete_are_reported() {
+    let mut world = World::new();
+    let root = spawn(&mut world, None);
+    let child = spawn(&mut world, Some(root));
+    let other = spawn(&mut world, None);
+    assert!(world.changes().is_empty(), "nothing published before end_frame");
+    world.end_frame();
+    assert_eq!(world.changes().added, [root, child, other]);
+
+    world.delete(root);
+    world.end_frame();
+    assert_eq!(*world.changes(), WorldChanges { removed: vec![root, child], ..Default::default() });
+
+    world.end_frame();
+    assert!(world.changes().is_empty(), "each frame stands alone");
+}
+
+#[test]
+fn transient_objects_are_not_reported() {
+    let mut world = World::new();
+    let kept = spawn(&mut world, None);
+    world.end_frame();
+    let brief = spawn(&mut world, None);
+    world.get_mut(kept).unwrap().color = [0.0; 4];
+    world.get_mut(brief).unwrap().color = [0.0; 4];
+    world.delete(brief);
+    world.end_frame();
+    assert_eq!(*world.changes(), WorldChanges { modified: vec![kept], ..Default::default() });
+}
+
+#[test]
+fn only_real_changes_count() {
+    let mut world = World::new();
+    let [a, b, c] = [(); 3].map(|()| spawn(&mut world, None));
+    world.end_frame();
+
+    let _ = world.get_mut(a);
+    world.get_mut(b).unwrap().transform.position[1] += 1.0;
+    world.get_mut(c).unwrap().name = "renamed".into();
+    spawn(&mut world, Some(c));
+    world.end_frame();
+    let changes = world.changes();
+    assert_eq!(changes.modified, [b, c], "untouched fields and new children do not count");
+    assert_eq!(changes.transform_changed, [b]);
+
+    // Moved and moved back within a frame.
+    let object = world.get_mut(b).unwrap();
+    object.transform.position[1] += 1.0;
+    object.transform.position[1] -= 1.0;
+    world.end_frame();
+    assert!(world.changes().modified.is_empty());
+}
+
+#[test]
+fn marks_and_reparenting_count() {
+    let mut world = World::new();
+    let [a, b] = [(); 2].map(|()| spawn(&mut world, None));
+    world.end_frame();
+
+    world.objects.get_mut(&a).unwrap().transform.scale = [2.0; 3];
+    world.mark_modified(a);
+    world.reparent(b, Some(a));
+    world.mark_modified(999);
+    world.end_frame();
+    let changes = world.changes();
+    assert_eq!(changes.modified, [a, b]);
+    assert_eq!(changes.transform_changed, [a], "marked objects count as moved");
+}
+
+#[test]
+fn renaming_counts() {
+    let mut world = World::new();
+    let [a, b] = [(); 2].map(|()| spawn(&mut world, None));
+    world.end_frame();
+
+    assert!(world.rename_str_id(a, "door".into()));
+    let same = world.get(b).unwrap().str_id.clone();
+    assert!(world.rename_str_id(b, same));
+    world.end_frame();
+    assert_eq!(*world.changes(), WorldChanges { modified: vec![a], ..Default::default() });
+    assert_eq!(world.get_id("door"), Some(a));
+}
+
+#[test]
+fn draw_world_publishes_changes() {
+    let Some(mut scene) = golden::headless_scene(8, 8, Camera::new()) else { return };
+    let id = scene.spawn(Object::default(), None);
+    scene.draw_world().unwrap();
+    assert_eq!(scene.world.changes().added, [id]);
+    scene.draw_world().unwrap();
+    assert!(scene.world.changes().is_empty());
+}
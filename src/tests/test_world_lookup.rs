@@ -0,0 +1,65 @@
+//! Unit tests for `World` hierarchy lookups.
+//!
+//! These tests exercise:
+//! - `children` yields direct children as objects, in insertion order
+//! - `descendants` walks the subtree depth-first, parents first
+//! - `find_descendant_by_name` returns the first match and skips `id` itself
+//! - missing IDs yield nothing
+
+use crate::objects::Object;
+use crate::world::World;
+
+/// ```text
+/// body
+/// ├── arm_l
+/// │   └── hand
+/// └── arm_r
+///     └── hand
+/// ```
+fn rig() -> (World, [usize; 5]) {
+    let mut world = World::new();
+    let mut spawn = |name: &str, parent| world.spawn_object(Object::builder().name(name).build(), parent);
+    let body = spawn("body", None);
+    let arm_l = spawn("arm_l", Some(body));
+    let hand_l = spawn("hand", Some(arm_l));
+    let arm_r = spawn("arm_r", Some(body));
+    let hand_r = spawn("hand", Some(arm_r));
+    (world, [body, arm_l, hand_l, arm_r, hand_r])
+}
+
+#[test]
+fn children_are_direct_only() {
+    let (world, [body, arm_l, _, arm_r, _]) = rig();
+    let names: Vec<(usize, &str)> = world.children(body).map(|(id, obj)| (id, obj.name.as_str())).collect();
+    assert_eq!(names, [(arm_l, "arm_l"), (arm_r, "arm_r")]);
+    assert_eq!(world.get(body).map(|obj| obj.name.as_str()), Some("body"));
+}
+
+#[test]
+fn descendants_are_depth_first() {
+    let (world, [body, arm_l, hand_l, arm_r, hand_r]) = rig();
+    let ids: Vec<usize> = world.descendants(body).map(|(id, _)| id).collect();
+    assert_eq!(ids, [arm_l, hand_l, arm_r, hand_r]);
+    assert_eq!(world.descendants(hand_l).count(), 0);
+}
+
+#[test]
+fn find_by_name_returns_first_match() {
+    let (world, [body, arm_l, hand_l, arm_r, hand_r]) = rig();
+    assert_eq!(world.find_descendant_by_name(body, "hand"), Some(hand_l));
+    assert_eq!(world.find_descendant_by_name(arm_r, "hand"), Some(hand_r));
+    assert_eq!(world.find_descendant_by_name(body, "arm_l"), Some(arm_l));
+    assert_eq!(world.find_descendant_by_name(body, "body"), None, "the start object is not searched");
+    assert_eq!(world.find_descendant_by_name(body, "tail"), None);
+}
+
+#[test]
+fn missing_ids_yield_nothing() {
+    let (mut world, [body, arm_l, ..]) = rig();
+    assert_eq!(world.children(999).count(), 0);
+    assert_eq!(world.descendants(999).count(), 0);
+    assert!(world.get(999).is_none());
+
+    world.delete(arm_l);
+    assert_eq!(world.descendants(body).count(), 2, "arm_r and its hand");
+}
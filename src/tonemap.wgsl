@@ -0,0 +1,46 @@
+// Resolves the HDR scene target into the presentation format: adds the
+// bloom, scales by the exposure (manual, or written by the auto-exposure
+// pass) and applies a filmic ACES curve.  Output stays linear; sRGB targets
+// encode on store.
+
+struct TonemapUniform {
+    // x = exposure multiplier, y = bloom intensity, zw unused.
+    params: vec4<f32>,
+};
+@group(0) @binding(0) var<uniform> tonemap: TonemapUniform;
+@group(0) @binding(1) var t_hdr: texture_2d<f32>;
+@group(0) @binding(2) var s_hdr: sampler;
+// The blurred bloom target, or the HDR target again with bloom off.
+@group(0) @binding(3) var t_bloom: texture_2d<f32>;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let x = f32(i32(index & 1u) * 4 - 1);
+    let y = f32(i32(index >> 1u) * 4 - 1);
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(x, y, 0.0, 1.0);
+    out.uv = vec2<f32>((x + 1.0) * 0.5, (1.0 - y) * 0.5);
+    return out;
+}
+
+// Narkowicz's fit of the ACES reference rendering transform.
+fn aces(x: vec3<f32>) -> vec3<f32> {
+    let a = 2.51;
+    let b = 0.03;
+    let c = 2.43;
+    let d = 0.59;
+    let e = 0.14;
+    return clamp((x * (a * x + b)) / (x * (c * x + d) + e), vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let hdr = textureSample(t_hdr, s_hdr, in.uv).rgb;
+    let glow = textureSample(t_bloom, s_hdr, in.uv).rgb * tonemap.params.y;
+    return vec4<f32>(aces((hdr + glow) * tonemap.params.x), 1.0);
+}
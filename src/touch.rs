@@ -0,0 +1,139 @@
+//! Touch points and simple gesture recognition.
+//!
+//! Touches arrive through [`crate::window::Window::on_input`] as
+//! [`InputEvent::Touch`], one per finger per phase, and are recorded and
+//! replayed like any other input.  Feed them to a [`GestureRecognizer`] to
+//! turn them into taps, pans and pinches:
+//!
+//! ```
+//! # use vertra::replay::InputEvent;
+//! # use vertra::touch::{Gesture, GestureRecognizer};
+//! # use winit::event::TouchPhase;
+//! let mut gestures = GestureRecognizer::new();
+//! let touch = |phase, x| InputEvent::Touch { id: 0, phase, x, y: 100.0 };
+//! gestures.handle(&touch(TouchPhase::Started, 100.0));
+//! let pan = gestures.handle(&touch(TouchPhase::Moved, 130.0));
+//! assert_eq!(pan, vec![Gesture::Pan { dx: 30.0, dy: 0.0, fingers: 1 }]);
+//! ```
+
+use std::collections::BTreeMap;
+use winit::event::TouchPhase;
+use crate::constants::touch::TAP_SLOP;
+use crate::replay::InputEvent;
+
+/// A gesture recognised from touch input.  Positions and distances are in
+/// physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gesture {
+    /// A single finger lifted within [`TAP_SLOP`] of where it went down,
+    /// with no other finger involved.
+    Tap { x: f32, y: f32 },
+    /// The fingers moved by `(dx, dy)`: the finger itself for one, their
+    /// centroid for several.
+    Pan { dx: f32, dy: f32, fingers: u32 },
+    /// Two fingers moved apart (`scale > 1`) or together, about the
+    /// midpoint `(x, y)`.  `scale` is relative to the previous event, so
+    /// multiply a zoom factor by it.
+    Pinch { scale: f32, x: f32, y: f32 },
+}
+
+/// A finger currently on the surface.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Contact {
+    start: [f32; 2],
+    position: [f32; 2],
+}
+
+/// Turns [`InputEvent::Touch`] streams into [`Gesture`]s.
+///
+/// A moving finger pans immediately; a finger that never strays further
+/// than [`TAP_SLOP`] and lifts while alone is also a tap.  With two fingers
+/// down, each move pans their centroid and pinches about it.  Further
+/// fingers only pan.
+#[derive(Debug, Clone, Default)]
+pub struct GestureRecognizer {
+    contacts: BTreeMap<u64, Contact>,
+    /// Cleared once the current touch sequence can no longer be a tap.
+    tap_candidate: bool,
+}
+
+impl GestureRecognizer {
+    /// A recognizer with no fingers down.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of fingers currently down.
+    pub fn touch_count(&self) -> usize {
+        self.contacts.len()
+    }
+
+    /// Position of finger `id`, if it is down.
+    pub fn touch_position(&self, id: u64) -> Option<[f32; 2]> {
+        self.contacts.get(&id).map(|contact| contact.position)
+    }
+
+    /// Update the fingers from `input` and return the gestures it
+    /// completes; empty for anything but a touch.
+    pub fn handle(&mut self, input: &InputEvent) -> Vec<Gesture> {
+        let InputEvent::Touch { id, phase, x, y } = *input else { return Vec::new(); };
+        let position = [x as f32, y as f32];
+        let mut gestures = Vec::new();
+        match phase {
+            TouchPhase::Started => {
+                self.tap_candidate = self.contacts.is_empty();
+                self.contacts.insert(id, Contact { start: position, position });
+            }
+            TouchPhase::Moved => {
+                let before = self.centroid_and_spread();
+                let Some(contact) = self.contacts.get_mut(&id) else { return gestures; };
+                contact.position = position;
+                let [sx, sy] = contact.start;
+                if (x as f32 - sx).hypot(y as f32 - sy) > TAP_SLOP {
+                    self.tap_candidate = false;
+                }
+                let after = self.centroid_and_spread();
+                let (Some((from, spread_before)), Some((to, spread_after))) = (before, after) else {
+                    return gestures;
+                };
+                let (dx, dy) = (to[0] - from[0], to[1] - from[1]);
+                if dx != 0.0 || dy != 0.0 {
+                    gestures.push(Gesture::Pan { dx, dy, fingers: self.contacts.len() as u32 });
+                }
+                if self.contacts.len() == 2 && spread_before > 0.0 && spread_after != spread_before {
+                    gestures.push(Gesture::Pinch { scale: spread_after / spread_before, x: to[0], y: to[1] });
+                }
+            }
+            TouchPhase::Ended => {
+                if self.contacts.remove(&id).is_some() && self.tap_candidate && self.contacts.is_empty() {
+                    gestures.push(Gesture::Tap { x: position[0], y: position[1] });
+                }
+            }
+            TouchPhase::Cancelled => {
+                self.contacts.remove(&id);
+                self.tap_candidate = false;
+            }
+        }
+        gestures
+    }
+
+    /// Forget every finger, e.g. when the window loses focus.
+    pub fn reset(&mut self) {
+        self.contacts.clear();
+        self.tap_candidate = false;
+    }
+
+    /// Centroid of the fingers and, with exactly two, their distance.
+    fn centroid_and_spread(&self) -> Option<([f32; 2], f32)> {
+        let n = self.contacts.len() as f32;
+        if n == 0.0 {
+            return None;
+        }
+        let sum = self.contacts.values().fold([0.0, 0.0], |acc, c| [acc[0] + c.position[0], acc[1] + c.position[1]]);
+        let spread = match self.contacts.values().collect::<Vec<_>>()[..] {
+            [a, b] => (a.position[0] - b.position[0]).hypot(a.position[1] - b.position[1]),
+            _ => 0.0,
+        };
+        Some(([sum[0] / n, sum[1] / n], spread))
+    }
+}
@@ -128,22 +128,70 @@ impl Transform {
         let child_m = child.to_matrix();
         let combined_m = parent_m * child_m;
 
-        let mut t = Transform::default();
-        t.position = [
-            combined_m.data[3][0],
-            combined_m.data[3][1],
-            combined_m.data[3][2],
-        ];
-        t.rotation = [
-            self.rotation[0] + child.rotation[0],
-            self.rotation[1] + child.rotation[1],
-            self.rotation[2] + child.rotation[2],
-        ];
-        t.scale = [
-            self.scale[0] * child.scale[0],
-            self.scale[1] * child.scale[1],
-            self.scale[2] * child.scale[2],
-        ];
-        t
+        Transform {
+            position: [
+                combined_m.data[3][0],
+                combined_m.data[3][1],
+                combined_m.data[3][2],
+            ],
+            rotation: [
+                self.rotation[0] + child.rotation[0],
+                self.rotation[1] + child.rotation[1],
+                self.rotation[2] + child.rotation[2],
+            ],
+            scale: [
+                self.scale[0] * child.scale[0],
+                self.scale[1] * child.scale[1],
+                self.scale[2] * child.scale[2],
+            ],
+        }
+    }
+
+    /// Round the position to the nearest multiple of `grid` on every axis.
+    /// A `grid` of zero or less leaves it unchanged.
+    pub fn snap_position(&mut self, grid: f32) {
+        self.position = self.position.map(|v| snap(v, grid));
+    }
+
+    /// Round each Euler angle to the nearest multiple of `step_degrees`.
+    /// A step of zero or less leaves the rotation unchanged.
+    pub fn snap_rotation(&mut self, step_degrees: f32) {
+        self.rotation = self.rotation.map(|v| snap(v, step_degrees));
+    }
+
+    /// Round each scale factor to the nearest multiple of `step`, never
+    /// below one `step`.  A step of zero or less leaves the scale unchanged.
+    pub fn snap_scale(&mut self, step: f32) {
+        self.scale = self.scale.map(|v| if step > 0.0 { snap(v, step).max(step) } else { v });
     }
-}
\ No newline at end of file
+
+    /// Express this world-space transform relative to `parent`'s world
+    /// transform, i.e. the `child` for which `parent.combine(&child)`
+    /// reproduces `self`.
+    ///
+    /// Zero parent scale components leave the child's scale unchanged, and a
+    /// singular parent matrix leaves the position unchanged.
+    pub fn relative_to(&self, parent: &Transform) -> Self {
+        let position = match parent.to_matrix().inverse() {
+            Some(inverse) => {
+                let [x, y, z] = self.position;
+                let local = inverse.mul_vec4([x, y, z, 1.0]);
+                [local[0], local[1], local[2]]
+            }
+            None => self.position,
+        };
+        Transform {
+            position,
+            rotation: std::array::from_fn(|i| self.rotation[i] - parent.rotation[i]),
+            scale: std::array::from_fn(|i| {
+                if parent.scale[i] == 0.0 { self.scale[i] } else { self.scale[i] / parent.scale[i] }
+            }),
+        }
+    }
+}
+
+/// Round `value` to the nearest multiple of `step`; non-positive steps leave
+/// it unchanged.
+pub fn snap(value: f32, step: f32) -> f32 {
+    if step > 0.0 { (value / step).round() * step } else { value }
+}
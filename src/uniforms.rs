@@ -0,0 +1,141 @@
+//! Per-frame uniform allocation with dynamic offsets.
+//!
+//! [`UniformAllocator`] owns one GPU buffer split into equally sized slots.
+//! Each frame, [`UniformAllocator::begin_frame`] hands out a [`UniformFrame`]
+//! that packs the camera matrices and per-draw model data into consecutive
+//! slots; every push returns the dynamic offset to bind that value at, and
+//! [`UniformFrame::finish`] uploads the lot in a single write.  One bind
+//! group per layout therefore covers every draw of the frame: objects need
+//! neither their own bind group nor CPU-baked vertices to be drawn with
+//! their own transform.
+//!
+//! ```no_run
+//! # use vertra::pipeline::{Pipeline, PipelineConfig};
+//! # use vertra::uniforms::UniformAllocator;
+//! # let pipeline = pollster::block_on(Pipeline::headless(&PipelineConfig::default(), 64, 64)).unwrap();
+//! let uniforms = UniformAllocator::new(&pipeline.device, 64, 16);
+//! let mut frame = uniforms.begin_frame();
+//! let first = frame.push(&[[1.0f32; 4]; 4]);  // Some(0)
+//! let second = frame.push(&[[0.0f32; 4]; 4]); // Some(uniforms.stride())
+//! frame.finish(&pipeline.queue);
+//! ```
+
+use wgpu::{Device, Queue};
+
+/// A uniform buffer of fixed-size slots addressed by dynamic offset.
+pub struct UniformAllocator {
+    buffer:   wgpu::Buffer,
+    /// Byte distance between consecutive slots.
+    stride:   u64,
+    capacity: usize,
+}
+
+impl UniformAllocator {
+    /// Allocate `capacity` slots, each holding up to `max_size` bytes and
+    /// aligned to the device's `min_uniform_buffer_offset_alignment`.
+    pub fn new(device: &Device, max_size: u64, capacity: usize) -> Self {
+        let stride = wgpu::util::align_to(max_size, device.limits().min_uniform_buffer_offset_alignment as u64);
+        Self { buffer: create_buffer(device, stride, capacity), stride, capacity }
+    }
+
+    /// The backing buffer.  Bind groups over it must be recreated whenever
+    /// [`Self::reserve`] returns `true`.
+    pub fn buffer(&self) -> &wgpu::Buffer {
+        &self.buffer
+    }
+
+    /// Byte distance between consecutive slots; every offset handed out is a
+    /// multiple of it.
+    pub fn stride(&self) -> u64 {
+        self.stride
+    }
+
+    /// Number of slots the buffer holds.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Make room for at least `slots` slots, growing to the next power of
+    /// two.  Returns `true` when the buffer was replaced.
+    pub fn reserve(&mut self, device: &Device, slots: usize) -> bool {
+        if slots <= self.capacity {
+            return false;
+        }
+        self.capacity = slots.next_power_of_two();
+        self.buffer = create_buffer(device, self.stride, self.capacity);
+        true
+    }
+
+    /// Binding resource exposing one `size`-byte value of the buffer, for a
+    /// bind group entry whose layout has `has_dynamic_offset: true`.
+    pub fn binding(&self, size: u64) -> wgpu::BindingResource<'_> {
+        wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+            buffer: &self.buffer,
+            offset: 0,
+            size: wgpu::BufferSize::new(size),
+        })
+    }
+
+    /// Start filling the buffer from its first slot.
+    pub fn begin_frame(&self) -> UniformFrame<'_> {
+        UniformFrame { allocator: self, bytes: Vec::new() }
+    }
+}
+
+/// The slots written so far this frame.  Nothing reaches the GPU until
+/// [`Self::finish`].
+pub struct UniformFrame<'a> {
+    allocator: &'a UniformAllocator,
+    bytes:     Vec<u8>,
+}
+
+impl UniformFrame<'_> {
+    /// Copy `value` into the next free slot and return its dynamic offset,
+    /// or `None` once every slot is taken.
+    ///
+    /// # Panics
+    /// If `T` is larger than a slot.
+    pub fn push<T: bytemuck::Pod>(&mut self, value: &T) -> Option<u32> {
+        let stride = self.allocator.stride as usize;
+        assert!(size_of::<T>() <= stride, "uniform of {} bytes exceeds the {stride}-byte slot", size_of::<T>());
+        if self.remaining() == 0 {
+            return None;
+        }
+        let offset = self.bytes.len();
+        self.bytes.resize(offset + stride, 0);
+        self.bytes[offset..offset + size_of::<T>()].copy_from_slice(bytemuck::bytes_of(value));
+        Some(offset as u32)
+    }
+
+    /// Slots written so far.
+    pub fn len(&self) -> usize {
+        self.bytes.len() / self.allocator.stride as usize
+    }
+
+    /// Returns `true` if nothing has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Slots still free.
+    pub fn remaining(&self) -> usize {
+        self.allocator.capacity - self.len()
+    }
+
+    /// Upload the written slots and return the number of bytes sent.
+    pub fn finish(self, queue: &Queue) -> u64 {
+        if !self.bytes.is_empty() {
+            queue.write_buffer(&self.allocator.buffer, 0, &self.bytes);
+        }
+        self.bytes.len() as u64
+    }
+}
+
+fn create_buffer(device: &Device, stride: u64, capacity: usize) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Frame Uniform Buffer"),
+        size: stride * capacity as u64,
+        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
@@ -0,0 +1,203 @@
+//! Screen-corner orientation widget.
+//!
+//! [`ViewAxes`] draws the world X, Y and Z axes as seen by the scene
+//! camera in a corner of the [`crate::overlay`]: a line and a coloured disc
+//! for each positive axis and a dimmer disc for each negative one, painted
+//! back to front so the axes nearest the viewer stay on top.  It is rebuilt
+//! every frame, so it turns with the camera.
+//!
+//! With [`ViewAxes::snap_on_click`] on, clicking a disc turns the camera to
+//! look at the scene from that side — clicking `+X` looks along `-X` — at
+//! the same distance from the editor's orbit pivot, or from
+//! [`Camera::target`] outside editor mode.  Top and bottom views stop at the
+//! camera's ±89° pitch limit.
+//!
+//! ```no_run
+//! # fn demo(scene: &mut vertra::scene::Scene) {
+//! use vertra::overlay::{Anchor, Layout, Margins};
+//! use vertra::view_axes::ViewAxes;
+//! scene.overlay.view_axes = Some(ViewAxes::default()
+//!     .with_layout(Layout::new(Anchor::BottomLeft, [80.0, 80.0]).with_margins(Margins::all(8.0))));
+//! # }
+//! ```
+
+use crate::camera::Camera;
+use crate::constants::overlay::{
+    VIEW_AXES_CAP_RADIUS, VIEW_AXES_COLORS, VIEW_AXES_LINE_WIDTH, VIEW_AXES_MARGIN, VIEW_AXES_NEGATIVE_SHADE,
+    VIEW_AXES_SIZE,
+};
+use crate::mesh::MeshData;
+use crate::object2d::{Shape2D, Style2D};
+use crate::overlay::{Anchor, Layout, Margins};
+use crate::transform::Transform;
+
+/// Edges of the axis-end discs.
+const CAP_SEGMENTS: usize = 20;
+
+/// One axis end of a [`ViewAxes`] widget as currently drawn.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AxisHandle {
+    /// The world axis, e.g. `[0.0, 0.0, -1.0]` for `-Z`.
+    pub axis: [f32; 3],
+    /// Centre of the disc in overlay pixels.
+    pub position: [f32; 2],
+    /// How far the axis points away from the viewer, from `-1` (straight
+    /// at the camera) to `1`.
+    pub depth: f32,
+}
+
+/// Orientation widget drawn in the overlay.  Install it on
+/// [`crate::overlay::Overlay::view_axes`]; see the [module docs](self).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViewAxes {
+    /// Placement of the widget's square; only the anchor, margins and size
+    /// are used.
+    pub layout: Layout,
+    /// Thickness of the positive axis lines.
+    pub line_width: f32,
+    /// Radius of the discs at the axis ends.
+    pub cap_radius: f32,
+    /// Colours of the X, Y and Z axes.
+    pub colors: [[f32; 4]; 3],
+    /// Snap the camera to an axis-aligned view when a disc is clicked.
+    pub snap_on_click: bool,
+}
+
+impl Default for ViewAxes {
+    fn default() -> Self {
+        Self {
+            layout: Layout::new(Anchor::TopRight, [VIEW_AXES_SIZE; 2]).with_margins(Margins::all(VIEW_AXES_MARGIN)),
+            line_width: VIEW_AXES_LINE_WIDTH,
+            cap_radius: VIEW_AXES_CAP_RADIUS,
+            colors: VIEW_AXES_COLORS,
+            snap_on_click: true,
+        }
+    }
+}
+
+impl ViewAxes {
+    /// Place the widget by `layout`.
+    pub fn with_layout(mut self, layout: Layout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Enable or disable click-to-snap.
+    pub fn with_snap_on_click(mut self, snap_on_click: bool) -> Self {
+        self.snap_on_click = snap_on_click;
+        self
+    }
+
+    /// The six axis ends for `camera` in an overlay of `viewport` logical
+    /// pixels, farthest first.
+    pub fn handles(&self, camera: &Camera, viewport: [f32; 2]) -> [AxisHandle; 6] {
+        let (min, size) = self.layout.rect(viewport);
+        let center = [min[0] + size[0] * 0.5, min[1] + size[1] * 0.5];
+        let radius = (size[0].min(size[1]) * 0.5 - self.cap_radius).max(0.0);
+        let (forward, right, up) = view_basis(camera);
+        let mut handles = [0, 1, 2, 3, 4, 5].map(|i| {
+            let mut axis = [0.0; 3];
+            axis[i % 3] = if i < 3 { 1.0 } else { -1.0 };
+            AxisHandle {
+                axis,
+                position: [center[0] + dot(axis, right) * radius, center[1] - dot(axis, up) * radius],
+                depth: dot(axis, forward),
+            }
+        });
+        handles.sort_by(|a, b| b.depth.total_cmp(&a.depth));
+        handles
+    }
+
+    /// The widget's triangles in overlay pixels, painted back to front.
+    pub fn build(&self, camera: &Camera, viewport: [f32; 2]) -> MeshData {
+        let (min, size) = self.layout.rect(viewport);
+        let center = [min[0] + size[0] * 0.5, min[1] + size[1] * 0.5];
+        let disc = Shape2D::Circle { radius: self.cap_radius, segments: CAP_SEGMENTS };
+        let mut mesh = MeshData::new();
+        for handle in self.handles(camera, viewport) {
+            let axis = handle.axis.iter().position(|&c| c != 0.0).unwrap_or(0);
+            let mut color = self.colors[axis];
+            if handle.axis[axis] > 0.0 {
+                push_line(&mut mesh, center, handle.position, self.line_width, color);
+            } else {
+                for channel in &mut color[..3] {
+                    *channel *= VIEW_AXES_NEGATIVE_SHADE;
+                }
+            }
+            let [x, y] = handle.position;
+            disc.generate_mesh_data(&mut mesh, Style2D::Fill, &Transform::from_position(x, y, 0.0), color);
+        }
+        mesh
+    }
+
+    /// The axis whose disc covers `point` (overlay pixels), preferring the
+    /// disc nearest the viewer where they overlap.
+    pub fn hit(&self, camera: &Camera, viewport: [f32; 2], point: [f32; 2]) -> Option<[f32; 3]> {
+        self.handles(camera, viewport)
+            .into_iter()
+            .rev()
+            .find(|handle| {
+                let [dx, dy] = [point[0] - handle.position[0], point[1] - handle.position[1]];
+                dx * dx + dy * dy <= self.cap_radius * self.cap_radius
+            })
+            .map(|handle| handle.axis)
+    }
+}
+
+/// Turn `camera` to look at `pivot` from the side `axis` points to, keeping
+/// its distance from `pivot`.  `axis` should be a unit world axis such as
+/// [`AxisHandle::axis`]; views along Y keep the camera's yaw and stop at
+/// ±89° pitch.
+pub fn snap_camera(camera: &mut Camera, axis: [f32; 3], pivot: [f32; 3]) {
+    let (yaw, pitch) = if axis[1] != 0.0 {
+        (camera.lr_rot, -89.0 * axis[1].signum())
+    } else {
+        ((-axis[2]).atan2(-axis[0]).to_degrees(), 0.0)
+    };
+    let offset = [0, 1, 2].map(|k| camera.eye[k] - pivot[k]);
+    let distance = dot(offset, offset).sqrt();
+    *camera = camera.with_rotation(yaw, pitch);
+    let forward = [0, 1, 2].map(|k| camera.target[k] - camera.eye[k]);
+    camera.eye = [0, 1, 2].map(|k| pivot[k] - forward[k] * distance);
+    camera.target = [0, 1, 2].map(|k| camera.eye[k] + forward[k]);
+}
+
+/// Unit forward, screen-right and screen-up vectors of `camera`, as
+/// [`crate::math::Matrix4::look_at`] builds them.
+fn view_basis(camera: &Camera) -> ([f32; 3], [f32; 3], [f32; 3]) {
+    let f = normalize([0, 1, 2].map(|k| camera.target[k] - camera.eye[k]));
+    let r = normalize(cross(camera.up, f));
+    (f, r, cross(f, r))
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+fn normalize(v: [f32; 3]) -> [f32; 3] {
+    let length = dot(v, v).sqrt().max(1e-4);
+    v.map(|c| c / length)
+}
+
+/// Append a `width` thick segment from `a` to `b`.
+fn push_line(mesh: &mut MeshData, a: [f32; 2], b: [f32; 2], width: f32, color: [f32; 4]) {
+    let [dx, dy] = [b[0] - a[0], b[1] - a[1]];
+    let length = (dx * dx + dy * dy).sqrt();
+    if length <= 0.0 {
+        return;
+    }
+    let [nx, ny] = [-dy / length * width * 0.5, dx / length * width * 0.5];
+    mesh.push_quad(
+        [
+            [a[0] + nx, a[1] + ny, 0.0],
+            [b[0] + nx, b[1] + ny, 0.0],
+            [b[0] - nx, b[1] - ny, 0.0],
+            [a[0] - nx, a[1] - ny, 0.0],
+        ],
+        color,
+    );
+}
@@ -0,0 +1,391 @@
+//! Chunked voxel grids for block-world prototypes.
+//!
+//! A [`VoxelGrid`] stores a [`BlockId`] per integer position, split into
+//! cubic [`Chunk`]s of [`CHUNK_SIZE`] blocks per side that are created on
+//! the first solid block and dropped once they hold only [`AIR`].  Each
+//! block is a unit cube whose minimum corner sits at its position.
+//!
+//! [`VoxelGrid::mesh_chunk`] emits only the faces between a solid block and
+//! air, looking across chunk borders, coloured from
+//! [`VoxelGrid::palette`]; with [`Meshing::Greedy`] neighbouring faces of
+//! the same colour are merged into larger quads.  Edits mark the chunks whose faces they change
+//! as dirty, and [`crate::scene::Scene::sync_voxels`] rebakes just those
+//! into registry meshes, one object per chunk.
+//!
+//! ```no_run
+//! # fn demo(scene: &mut vertra::scene::Scene) {
+//! use vertra::voxel::VoxelGrid;
+//! const GRASS: u16 = 1;
+//! const STONE: u16 = 2;
+//! let mut grid = VoxelGrid::new()
+//!     .with_palette(vec![[0.0; 4], [0.3, 0.7, 0.2, 1.0], [0.5, 0.5, 0.5, 1.0]]);
+//! grid.fill([-32, -4, -32], [32, -1, 32], STONE);
+//! grid.fill([-32, 0, -32], [32, 0, 32], GRASS);
+//! scene.sync_voxels(&mut grid);
+//!
+//! // Dig a hole: only the chunks around it are rebaked.
+//! grid.set([3, 0, 5], vertra::voxel::AIR);
+//! scene.sync_voxels(&mut grid);
+//! # }
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use crate::constants::voxel::CHUNK_SIZE;
+use crate::geometry::GeometryId;
+use crate::mesh::MeshData;
+
+/// Block type stored per position; index into [`VoxelGrid::palette`].
+pub type BlockId = u16;
+
+/// The empty block.
+pub const AIR: BlockId = 0;
+
+/// Chunk coordinates: block position divided by [`CHUNK_SIZE`], rounded
+/// down.
+pub type ChunkKey = [i32; 3];
+
+/// The six face directions with the corners of the unit cube face on that
+/// side, wound like [`crate::geometry::Geometry::Box`] faces.
+const FACES: [([i32; 3], [[f32; 3]; 4]); 6] = [
+    ([0, 0, 1], [[0.0, 0.0, 1.0], [0.0, 1.0, 1.0], [1.0, 1.0, 1.0], [1.0, 0.0, 1.0]]),
+    ([0, 0, -1], [[1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 0.0]]),
+    ([-1, 0, 0], [[0.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 1.0, 1.0], [0.0, 0.0, 1.0]]),
+    ([1, 0, 0], [[1.0, 0.0, 1.0], [1.0, 1.0, 1.0], [1.0, 1.0, 0.0], [1.0, 0.0, 0.0]]),
+    ([0, 1, 0], [[0.0, 1.0, 1.0], [0.0, 1.0, 0.0], [1.0, 1.0, 0.0], [1.0, 1.0, 1.0]]),
+    ([0, -1, 0], [[0.0, 0.0, 0.0], [0.0, 0.0, 1.0], [1.0, 0.0, 1.0], [1.0, 0.0, 0.0]]),
+];
+
+/// [`CHUNK_SIZE`]³ blocks, indexed by position within the chunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chunk {
+    blocks: Vec<BlockId>,
+    /// Blocks other than [`AIR`].
+    solid: usize,
+}
+
+impl Default for Chunk {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Chunk {
+    /// A chunk of air.
+    pub fn new() -> Self {
+        Self { blocks: vec![AIR; CHUNK_SIZE.pow(3)], solid: 0 }
+    }
+
+    /// The block at `local`, each coordinate below [`CHUNK_SIZE`].
+    pub fn get(&self, local: [usize; 3]) -> BlockId {
+        self.blocks[Self::index(local)]
+    }
+
+    /// Replace the block at `local` and return the previous one.
+    pub fn set(&mut self, local: [usize; 3], id: BlockId) -> BlockId {
+        let previous = std::mem::replace(&mut self.blocks[Self::index(local)], id);
+        match (previous == AIR, id == AIR) {
+            (true, false) => self.solid += 1,
+            (false, true) => self.solid -= 1,
+            _ => {}
+        }
+        previous
+    }
+
+    /// Number of blocks other than [`AIR`].
+    pub fn solid_count(&self) -> usize {
+        self.solid
+    }
+
+    /// Whether the chunk holds only air.
+    pub fn is_empty(&self) -> bool {
+        self.solid == 0
+    }
+
+    fn index([x, y, z]: [usize; 3]) -> usize {
+        x + CHUNK_SIZE * (y + CHUNK_SIZE * z)
+    }
+}
+
+/// An unbounded grid of blocks stored in chunks.  See the
+/// [module docs](self).
+#[derive(Debug, Clone, Default)]
+pub struct VoxelGrid {
+    chunks: HashMap<ChunkKey, Chunk>,
+    /// Colour of each block ID; IDs past the end are white.
+    pub palette: Vec<[f32; 4]>,
+    /// How chunks are meshed; changing it only affects chunks meshed
+    /// afterwards (see [`Self::mark_all_dirty`]).
+    pub meshing: Meshing,
+    /// Chunks whose mesh no longer matches their blocks.
+    dirty: HashSet<ChunkKey>,
+    /// Object and registry mesh of each chunk baked by
+    /// [`crate::scene::Scene::sync_voxels`].
+    pub(crate) baked: HashMap<ChunkKey, (usize, GeometryId)>,
+}
+
+impl VoxelGrid {
+    /// An empty grid with an empty palette.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the colour of each block ID.
+    pub fn with_palette(mut self, palette: Vec<[f32; 4]>) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    /// Set how chunks are meshed.
+    pub fn with_meshing(mut self, meshing: Meshing) -> Self {
+        self.meshing = meshing;
+        self
+    }
+
+    /// The chunk holding block `position` and the block's position inside
+    /// it.
+    pub fn locate(position: [i32; 3]) -> (ChunkKey, [usize; 3]) {
+        let size = CHUNK_SIZE as i32;
+        (position.map(|v| v.div_euclid(size)), position.map(|v| v.rem_euclid(size) as usize))
+    }
+
+    /// World position of the minimum corner of chunk `key`.
+    pub fn chunk_origin(key: ChunkKey) -> [f32; 3] {
+        key.map(|v| (v * CHUNK_SIZE as i32) as f32)
+    }
+
+    /// The block at `position`; [`AIR`] outside every chunk.
+    pub fn get(&self, position: [i32; 3]) -> BlockId {
+        let (key, local) = Self::locate(position);
+        self.chunks.get(&key).map_or(AIR, |chunk| chunk.get(local))
+    }
+
+    /// Replace the block at `position` and return the previous one.  Marks
+    /// its chunk dirty, and the neighbouring chunk across any border the
+    /// block touches, when the block changes.
+    pub fn set(&mut self, position: [i32; 3], id: BlockId) -> BlockId {
+        let (key, local) = Self::locate(position);
+        let previous = match self.chunks.get_mut(&key) {
+            Some(chunk) => chunk.set(local, id),
+            None if id == AIR => return AIR,
+            None => self.chunks.entry(key).or_default().set(local, id),
+        };
+        if previous == id {
+            return previous;
+        }
+        if self.chunks.get(&key).is_some_and(Chunk::is_empty) {
+            self.chunks.remove(&key);
+        }
+        self.dirty.insert(key);
+        for axis in 0..3 {
+            let step = match local[axis] {
+                0 => -1,
+                v if v == CHUNK_SIZE - 1 => 1,
+                _ => continue,
+            };
+            let mut neighbour = key;
+            neighbour[axis] += step;
+            if self.chunks.contains_key(&neighbour) {
+                self.dirty.insert(neighbour);
+            }
+        }
+        previous
+    }
+
+    /// Set every block from `min` to `max`, both inclusive, to `id`.
+    pub fn fill(&mut self, min: [i32; 3], max: [i32; 3], id: BlockId) {
+        for z in min[2]..=max[2] {
+            for y in min[1]..=max[1] {
+                for x in min[0]..=max[0] {
+                    self.set([x, y, z], id);
+                }
+            }
+        }
+    }
+
+    /// Put `chunk` at `key`, replacing what was there, and mark it and its
+    /// six neighbours dirty.  A chunk of air just clears `key`.
+    pub fn insert_chunk(&mut self, key: ChunkKey, chunk: Chunk) -> Option<Chunk> {
+        let previous = if chunk.is_empty() { self.chunks.remove(&key) } else { self.chunks.insert(key, chunk) };
+        self.mark_around(key);
+        previous
+    }
+
+    /// Take chunk `key` out of the grid, marking it and its six neighbours
+    /// dirty.
+    pub fn remove_chunk(&mut self, key: ChunkKey) -> Option<Chunk> {
+        let previous = self.chunks.remove(&key);
+        if previous.is_some() {
+            self.mark_around(key);
+        }
+        previous
+    }
+
+    /// Mark chunk `key` dirty, and those of its face neighbours that exist.
+    fn mark_around(&mut self, key: ChunkKey) {
+        self.dirty.insert(key);
+        for (normal, _) in &FACES {
+            let neighbour = [0, 1, 2].map(|k| key[k] + normal[k]);
+            if self.chunks.contains_key(&neighbour) {
+                self.dirty.insert(neighbour);
+            }
+        }
+    }
+
+    /// The chunk at `key`, if it holds any solid block.
+    pub fn chunk(&self, key: ChunkKey) -> Option<&Chunk> {
+        self.chunks.get(&key)
+    }
+
+    /// Every chunk holding a solid block, in no particular order.
+    pub fn chunks(&self) -> impl Iterator<Item = (ChunkKey, &Chunk)> {
+        self.chunks.iter().map(|(key, chunk)| (*key, chunk))
+    }
+
+    /// Whether chunk `key` changed since it was last taken with
+    /// [`Self::take_dirty`].
+    pub fn is_dirty(&self, key: ChunkKey) -> bool {
+        self.dirty.contains(&key)
+    }
+
+    /// Number of dirty chunks.
+    pub fn dirty_count(&self) -> usize {
+        self.dirty.len()
+    }
+
+    /// Mark every chunk dirty, e.g. to rebake all of them after changing
+    /// [`Self::palette`] or [`Self::meshing`].
+    pub fn mark_all_dirty(&mut self) {
+        self.dirty.extend(self.chunks.keys().copied());
+    }
+
+    /// The dirty chunks in sorted order, now marked clean.
+    pub fn take_dirty(&mut self) -> Vec<ChunkKey> {
+        let mut keys: Vec<ChunkKey> = self.dirty.drain().collect();
+        keys.sort_unstable();
+        keys
+    }
+
+    /// Up to `max` dirty chunks, nearest to chunk `center` first, now
+    /// marked clean.
+    pub fn take_dirty_nearest(&mut self, center: ChunkKey, max: usize) -> Vec<ChunkKey> {
+        let mut keys: Vec<ChunkKey> = self.dirty.iter().copied().collect();
+        let distance = |key: &ChunkKey| (0..3).map(|k| (i64::from(key[k]) - i64::from(center[k])).pow(2)).sum::<i64>();
+        keys.sort_unstable_by_key(|key| (distance(key), *key));
+        keys.truncate(max);
+        for key in &keys {
+            self.dirty.remove(key);
+        }
+        keys
+    }
+
+    /// The visible faces of chunk `key`, relative to
+    /// [`Self::chunk_origin`]: those of solid blocks facing air, including
+    /// air in neighbouring chunks, merged as [`Self::meshing`] says.  Empty
+    /// for chunks without solid blocks.
+    pub fn mesh_chunk(&self, key: ChunkKey) -> MeshData {
+        let mut mesh = MeshData::new();
+        let Some(chunk) = self.chunks.get(&key) else { return mesh };
+        match self.meshing {
+            Meshing::Culled => self.mesh_culled(key, chunk, &mut mesh),
+            Meshing::Greedy => self.mesh_greedy(key, chunk, &mut mesh),
+        }
+        mesh
+    }
+
+    /// The palette colour of `id`.
+    pub fn color(&self, id: BlockId) -> [f32; 4] {
+        self.palette.get(usize::from(id)).copied().unwrap_or([1.0; 4])
+    }
+
+    /// Whether the solid block at `local` in chunk `key` shows its face
+    /// towards `normal`.
+    fn open(&self, key: ChunkKey, chunk: &Chunk, local: [i32; 3], normal: [i32; 3]) -> bool {
+        let beside = [0, 1, 2].map(|k| local[k] + normal[k]);
+        if beside.iter().all(|&v| (0..CHUNK_SIZE as i32).contains(&v)) {
+            chunk.get(beside.map(|v| v as usize)) == AIR
+        } else {
+            self.get([0, 1, 2].map(|k| key[k] * CHUNK_SIZE as i32 + beside[k])) == AIR
+        }
+    }
+
+    /// One quad per visible face.
+    fn mesh_culled(&self, key: ChunkKey, chunk: &Chunk, mesh: &mut MeshData) {
+        for z in 0..CHUNK_SIZE {
+            for y in 0..CHUNK_SIZE {
+                for x in 0..CHUNK_SIZE {
+                    let id = chunk.get([x, y, z]);
+                    if id == AIR {
+                        continue;
+                    }
+                    let local = [x, y, z].map(|v| v as i32);
+                    let color = self.color(id);
+                    for (normal, corners) in &FACES {
+                        if self.open(key, chunk, local, *normal) {
+                            mesh.push_quad(corners.map(|c| [0, 1, 2].map(|k| c[k] + local[k] as f32)), color);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Visible faces merged into rectangles: each slice of the chunk
+    /// facing one direction is swept row by row, growing a quad first along
+    /// the row and then over the following rows while every face it covers
+    /// is visible and the same colour.
+    fn mesh_greedy(&self, key: ChunkKey, chunk: &Chunk, mesh: &mut MeshData) {
+        let mut mask: Vec<Option<[f32; 4]>> = vec![None; CHUNK_SIZE * CHUNK_SIZE];
+        for (normal, corners) in &FACES {
+            let axis = normal.iter().position(|&v| v != 0).unwrap_or(0);
+            let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+            for layer in 0..CHUNK_SIZE {
+                let at = |i: usize, j: usize| {
+                    let mut local = [0; 3];
+                    (local[axis], local[u], local[v]) = (layer, i, j);
+                    local
+                };
+                for j in 0..CHUNK_SIZE {
+                    for i in 0..CHUNK_SIZE {
+                        let local = at(i, j);
+                        let id = chunk.get(local);
+                        let visible = id != AIR && self.open(key, chunk, local.map(|c| c as i32), *normal);
+                        mask[i + CHUNK_SIZE * j] = visible.then(|| self.color(id));
+                    }
+                }
+                for j in 0..CHUNK_SIZE {
+                    let mut i = 0;
+                    while i < CHUNK_SIZE {
+                        let Some(color) = mask[i + CHUNK_SIZE * j] else {
+                            i += 1;
+                            continue;
+                        };
+                        let same = |i: usize, j: usize| mask[i + CHUNK_SIZE * j] == Some(color);
+                        let width = (i..CHUNK_SIZE).take_while(|&i| same(i, j)).count();
+                        let height = (j..CHUNK_SIZE).take_while(|&j| (i..i + width).all(|i| same(i, j))).count();
+                        for row in j..j + height {
+                            mask[i + CHUNK_SIZE * row..i + width + CHUNK_SIZE * row].fill(None);
+                        }
+                        let origin = at(i, j);
+                        let mut size = [1.0; 3];
+                        (size[u], size[v]) = (width as f32, height as f32);
+                        mesh.push_quad(corners.map(|c| [0, 1, 2].map(|k| c[k] * size[k] + origin[k] as f32)), color);
+                        i += width;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// How [`VoxelGrid::mesh_chunk`] turns visible block faces into quads.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Meshing {
+    /// One quad per visible face.
+    #[default]
+    Culled,
+    /// Adjacent coplanar faces of the same colour merged into as few
+    /// rectangles as a row-by-row sweep finds.  Flat terrain needs a
+    /// fraction of the vertices; texture coordinates span each merged quad
+    /// instead of repeating per block.
+    Greedy,
+}
@@ -0,0 +1,355 @@
+//! # VTM Binary Mesh Format
+//!
+//! A compact little-endian cache format for [`MeshData`], so meshes that
+//! are expensive to generate or import are built once, written to disk and
+//! loaded in milliseconds on later runs.
+//!
+//! A file holds a [`MeshBundle`]: any number of named meshes, e.g. every
+//! mesh a scene registers, plus a caller-chosen `cache_key` identifying the
+//! inputs they were built from.  [`cached_bundle`] and [`cached_mesh`]
+//! rebuild the file whenever the key, or the format version, changes.
+//!
+//! ## File Layout
+//!
+//! ```text
+//! ┌──────────────────────────────────────────────────────────────┐
+//! │  HEADER  (20 bytes)                                          │
+//! │  [0..4]   magic:          b"VTM\x00"                         │
+//! │  [4..6]   format_version: u16 LE  (= FORMAT_VERSION)         │
+//! │  [6..8]   flags:          u16 LE  (= 0, reserved)            │
+//! │  [8..16]  cache_key:      u64 LE                             │
+//! │  [16..20] mesh_count:     u32 LE                             │
+//! ├──────────────────────────────────────────────────────────────┤
+//! │  MESHES SECTION  (mesh_count entries)                        │
+//! │  Per mesh:                                                   │
+//! │    name_len:     u16 LE                                      │
+//! │    name:         utf-8 bytes [name_len]                      │
+//! │    topology:     u8  (0=TriangleList 1=LineList 2=PointList) │
+//! │    vertex_count: u32 LE                                      │
+//! │    index_count:  u32 LE                                      │
+//! │    vertices:     vertex_count × (position[3], color[3],      │
+//! │                  uv[2]: f32 LE)  (32 bytes each)             │
+//! │    indices:      u32 LE * index_count                        │
+//! └──────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! Vertex and index arrays are read in one block each, and indices are
+//! checked against the vertex count, so a corrupt file fails to load
+//! instead of producing a mesh that reads out of bounds on the GPU.
+//!
+//! ```no_run
+//! # use std::path::Path;
+//! # fn expensive_terrain() -> vertra::mesh::MeshData { unimplemented!() }
+//! # fn demo(scene: &mut vertra::scene::Scene) -> Result<(), vertra::vtm::VtmError> {
+//! // Bump the key whenever the generator or its parameters change.
+//! let terrain = vertra::vtm::cached_mesh(Path::new("cache/terrain.vtm"), 3, expensive_terrain)?;
+//! let id = scene.register_mesh(&terrain);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::mesh::{MeshData, Topology, Vertex};
+
+// Constants
+/// Magic bytes that identify every valid VTM file.
+pub const MAGIC: [u8; 4] = [0x56, 0x54, 0x4D, 0x00]; // "VTM\0"
+
+/// Bump this whenever the binary layout changes; caches written with any
+/// other version are rebuilt.
+pub const FORMAT_VERSION: u16 = 1;
+
+/// Size of one vertex in the file.
+const VERTEX_BYTES: usize = 32;
+
+// Public types
+
+/// Named meshes stored together in one `.vtm` file.
+#[derive(Default)]
+pub struct MeshBundle {
+    /// Identifies the inputs the meshes were built from; see
+    /// [`cached_bundle`].
+    pub cache_key: u64,
+    /// The meshes in file order.  Names need not be unique, but
+    /// [`Self::get`] finds only the first of each.
+    pub meshes: Vec<(String, MeshData)>,
+}
+
+impl MeshBundle {
+    /// An empty bundle tagged with `cache_key`.
+    pub fn new(cache_key: u64) -> Self {
+        Self { cache_key, meshes: Vec::new() }
+    }
+
+    /// Append `mesh` under `name`.
+    pub fn push(&mut self, name: impl Into<String>, mesh: MeshData) {
+        self.meshes.push((name.into(), mesh));
+    }
+
+    /// The first mesh called `name`.
+    pub fn get(&self, name: &str) -> Option<&MeshData> {
+        self.meshes.iter().find(|(n, _)| n == name).map(|(_, mesh)| mesh)
+    }
+}
+
+/// Metadata from the file header — readable without loading any mesh.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VtmHeader {
+    /// Version of the binary layout.
+    pub format_version: u16,
+    /// The bundle's [`MeshBundle::cache_key`].
+    pub cache_key: u64,
+    /// Number of meshes in the file.
+    pub mesh_count: u32,
+}
+
+/// Errors that can occur when reading or writing `.vtm` files.
+#[derive(Debug)]
+pub enum VtmError {
+    Io(io::Error),
+    /// The first four bytes do not match `b"VTM\0"`.
+    InvalidMagic,
+    /// The `format_version` field is not [`FORMAT_VERSION`].
+    UnsupportedVersion { found: u16 },
+    /// A mesh name contained invalid UTF-8.
+    InvalidUtf8(std::string::FromUtf8Error),
+    /// An unknown `topology` byte was encountered.
+    UnknownTopology(u8),
+    /// A mesh name is longer than `u16::MAX` bytes.
+    NameTooLong { len: usize },
+    /// A mesh has more vertices or indices than a `u32` count can hold.
+    MeshTooLarge { name: String },
+    /// An index of mesh `name` refers past its last vertex.
+    IndexOutOfRange { name: String, index: u32 },
+}
+
+impl std::fmt::Display for VtmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VtmError::Io(e) => write!(f, "I/O error: {e}"),
+            VtmError::InvalidMagic => write!(f, "Not a valid VTM file (magic bytes mismatch)"),
+            VtmError::UnsupportedVersion { found } => {
+                write!(f, "Unsupported VTM format version {found} (this build supports version {FORMAT_VERSION})")
+            }
+            VtmError::InvalidUtf8(e) => write!(f, "Invalid UTF-8 in mesh name: {e}"),
+            VtmError::UnknownTopology(tag) => write!(f, "Unknown topology byte: {tag:#04x}"),
+            VtmError::NameTooLong { len } => {
+                write!(f, "mesh name is {len} bytes, which exceeds the maximum of {} bytes", u16::MAX)
+            }
+            VtmError::MeshTooLarge { name } => write!(f, "mesh {name:?} has more than u32::MAX vertices or indices"),
+            VtmError::IndexOutOfRange { name, index } => {
+                write!(f, "mesh {name:?} refers to vertex {index}, past its last vertex")
+            }
+        }
+    }
+}
+
+impl std::error::Error for VtmError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            VtmError::Io(e) => Some(e),
+            VtmError::InvalidUtf8(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for VtmError {
+    fn from(e: io::Error) -> Self {
+        VtmError::Io(e)
+    }
+}
+
+impl From<std::string::FromUtf8Error> for VtmError {
+    fn from(e: std::string::FromUtf8Error) -> Self {
+        VtmError::InvalidUtf8(e)
+    }
+}
+
+// Byte helpers
+
+#[inline]
+fn r_u16(r: &mut impl Read) -> io::Result<u16> {
+    let mut b = [0u8; 2];
+    r.read_exact(&mut b)?;
+    Ok(u16::from_le_bytes(b))
+}
+
+#[inline]
+fn r_u32(r: &mut impl Read) -> io::Result<u32> {
+    let mut b = [0u8; 4];
+    r.read_exact(&mut b)?;
+    Ok(u32::from_le_bytes(b))
+}
+
+/// Read `count` little-endian 32-bit words in one block.  The buffer grows
+/// with the data actually read, so a corrupt count cannot allocate more
+/// than the file holds.
+fn r_words(r: &mut impl Read, count: usize) -> io::Result<Vec<[u8; 4]>> {
+    let len = count as u64 * 4;
+    let mut bytes = Vec::new();
+    r.take(len).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 != len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(bytes.chunks_exact(4).map(|c| [c[0], c[1], c[2], c[3]]).collect())
+}
+
+fn topology_tag(topology: Topology) -> u8 {
+    match topology {
+        Topology::TriangleList => 0,
+        Topology::LineList => 1,
+        Topology::PointList => 2,
+    }
+}
+
+fn write_mesh_entry(w: &mut impl Write, name: &str, mesh: &MeshData) -> Result<(), VtmError> {
+    let name_len = u16::try_from(name.len()).map_err(|_| VtmError::NameTooLong { len: name.len() })?;
+    let too_large = || VtmError::MeshTooLarge { name: name.to_owned() };
+    let vertex_count = u32::try_from(mesh.vertices.len()).map_err(|_| too_large())?;
+    let index_count = u32::try_from(mesh.indices.len()).map_err(|_| too_large())?;
+    w.write_all(&name_len.to_le_bytes())?;
+    w.write_all(name.as_bytes())?;
+    w.write_all(&[topology_tag(mesh.topology)])?;
+    w.write_all(&vertex_count.to_le_bytes())?;
+    w.write_all(&index_count.to_le_bytes())?;
+
+    let mut block = Vec::with_capacity(mesh.vertices.len() * VERTEX_BYTES);
+    for v in &mesh.vertices {
+        for c in v.position.iter().chain(&v.color).chain(&v.uv) {
+            block.extend_from_slice(&c.to_le_bytes());
+        }
+    }
+    w.write_all(&block)?;
+    block.clear();
+    for i in &mesh.indices {
+        block.extend_from_slice(&i.to_le_bytes());
+    }
+    w.write_all(&block)?;
+    Ok(())
+}
+
+fn read_mesh_entry(r: &mut impl Read) -> Result<(String, MeshData), VtmError> {
+    let name_len = r_u16(r)? as usize;
+    let mut name = vec![0u8; name_len];
+    r.read_exact(&mut name)?;
+    let name = String::from_utf8(name)?;
+    let mut tag = [0u8; 1];
+    r.read_exact(&mut tag)?;
+    let topology = match tag[0] {
+        0 => Topology::TriangleList,
+        1 => Topology::LineList,
+        2 => Topology::PointList,
+        unknown => return Err(VtmError::UnknownTopology(unknown)),
+    };
+    let vertex_count = r_u32(r)? as usize;
+    let index_count = r_u32(r)? as usize;
+
+    let floats: Vec<f32> = r_words(r, vertex_count * VERTEX_BYTES / 4)?.into_iter().map(f32::from_le_bytes).collect();
+    let vertices = floats.chunks_exact(VERTEX_BYTES / 4).map(|f| Vertex {
+        position: [f[0], f[1], f[2]],
+        color: [f[3], f[4], f[5]],
+        uv: [f[6], f[7]],
+    }).collect();
+    let indices: Vec<u32> = r_words(r, index_count)?.into_iter().map(u32::from_le_bytes).collect();
+    if let Some(&index) = indices.iter().find(|&&i| i as usize >= vertex_count) {
+        return Err(VtmError::IndexOutOfRange { name, index });
+    }
+    Ok((name, MeshData { vertices, indices, topology }))
+}
+
+// Public API
+/// Read only the 20-byte file header from any [`Read`] source.
+pub fn read_header(r: &mut impl Read) -> Result<VtmHeader, VtmError> {
+    let mut magic = [0u8; 4];
+    r.read_exact(&mut magic)?;
+    if magic != MAGIC {
+        return Err(VtmError::InvalidMagic);
+    }
+    let format_version = r_u16(r)?;
+    if format_version != FORMAT_VERSION {
+        return Err(VtmError::UnsupportedVersion { found: format_version });
+    }
+    let _flags = r_u16(r)?; // reserved
+    let mut key = [0u8; 8];
+    r.read_exact(&mut key)?;
+    let mesh_count = r_u32(r)?;
+    Ok(VtmHeader { format_version, cache_key: u64::from_le_bytes(key), mesh_count })
+}
+
+/// Serialize `bundle` to any [`Write`] sink.
+pub fn write(w: &mut impl Write, bundle: &MeshBundle) -> Result<(), VtmError> {
+    let mesh_count = u32::try_from(bundle.meshes.len())
+        .map_err(|_| VtmError::MeshTooLarge { name: String::new() })?;
+    w.write_all(&MAGIC)?;
+    w.write_all(&FORMAT_VERSION.to_le_bytes())?;
+    w.write_all(&0u16.to_le_bytes())?; // flags - reserved, must be zero
+    w.write_all(&bundle.cache_key.to_le_bytes())?;
+    w.write_all(&mesh_count.to_le_bytes())?;
+    for (name, mesh) in &bundle.meshes {
+        write_mesh_entry(w, name, mesh)?;
+    }
+    w.flush()?;
+    Ok(())
+}
+
+/// Deserialize a bundle from any [`Read`] source.
+pub fn read(r: &mut impl Read) -> Result<MeshBundle, VtmError> {
+    let header = read_header(r)?;
+    let meshes = (0..header.mesh_count).map(|_| read_mesh_entry(r)).collect::<Result<_, _>>()?;
+    Ok(MeshBundle { cache_key: header.cache_key, meshes })
+}
+
+/// Write `bundle` to a file at the given path, creating or truncating it.
+pub fn write_to_file(path: &Path, bundle: &MeshBundle) -> Result<(), VtmError> {
+    let file = File::create(path)?;
+    let mut writer = BufWriter::new(file);
+    write(&mut writer, bundle)
+}
+
+/// Read a bundle from a `.vtm` file at the given path.
+pub fn read_from_file(path: &Path) -> Result<MeshBundle, VtmError> {
+    let file = File::open(path)?;
+    let mut reader = BufReader::new(file);
+    read(&mut reader)
+}
+
+/// Peek at the header of a `.vtm` file without loading its meshes.
+pub fn header_from_file(path: &Path) -> Result<VtmHeader, VtmError> {
+    let file = File::open(path)?;
+    let mut reader = BufReader::new(file);
+    read_header(&mut reader)
+}
+
+/// Load the bundle cached at `path` if it was written with `cache_key` by
+/// this format version; otherwise call `build`, write its result there
+/// (creating missing directories) and return it.
+///
+/// A cache that is missing, stale or unreadable is rebuilt; only failing
+/// to write the new one is an error.
+pub fn cached_bundle(path: &Path, cache_key: u64, build: impl FnOnce() -> MeshBundle) -> Result<MeshBundle, VtmError> {
+    if let Ok(bundle) = read_from_file(path)
+        && bundle.cache_key == cache_key
+    {
+        return Ok(bundle);
+    }
+    let bundle = MeshBundle { cache_key, ..build() };
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    write_to_file(path, &bundle)?;
+    Ok(bundle)
+}
+
+/// [`cached_bundle`] for a single mesh.
+pub fn cached_mesh(path: &Path, cache_key: u64, build: impl FnOnce() -> MeshData) -> Result<MeshData, VtmError> {
+    let bundle = cached_bundle(path, cache_key, || {
+        let mut bundle = MeshBundle::new(cache_key);
+        bundle.push("", build());
+        bundle
+    })?;
+    Ok(bundle.meshes.into_iter().next().map(|(_, mesh)| mesh).unwrap_or_default())
+}
@@ -15,9 +15,13 @@
 //! │  [12..16] flags:          u32 LE  (= 0, reserved)            │
 //! │  [16..20] object_count:   u32 LE                             │
 //! ├──────────────────────────────────────────────────────────────┤
-//! │  CAMERA BLOCK  (60 bytes)                                    │
+//! │  CAMERA BLOCK  (80 bytes; 76 in v5-6, 68/64/60 in v4/3/2)    │
 //! │  eye[3], target[3], up[3]: f32 LE  (36 bytes)                │
 //! │  aspect, fov, znear, zfar, lr_rot, ud_rot: f32 LE (24 bytes) │
+//! │  layers_mask:    u32 LE  (version 3+)                        │
+//! │  exposure:       f32 LE  (version 4+)                        │
+//! │  focus_distance, aperture: f32 LE  (version 5+)              │
+//! │  ortho_height:   f32 LE  (version 7+, 0 = perspective)       │
 //! ├──────────────────────────────────────────────────────────────┤
 //! │  ROOTS SECTION                                               │
 //! │  roots_count: u32 LE                                         │
@@ -38,15 +42,32 @@
  //! │    geometry_tag:   u8                                        │
  //! │      0=None  1=Cube  2=Box  3=Plane                          │
  //! │      4=Pyramid  5=Capsule  6=Sphere                          │
- //! │    geometry_data:  (varies by tag)                           │
+ //! │      7=RoundedBox  8=Arrow  9=Sprite  (version 6+)           │
+ //! │    geometry_data:  (varies by tag; Box segments and Plane    │
+ //! │                     subdivisions are version 6+, Sphere      │
+ //! │                     tessellation u8 0=Uv 1=Icosphere is 9+)  │
  //! │    texture_path_len: u16 LE  (0 = no texture)                │
  //! │    texture_path:  utf-8 bytes [texture_path_len]             │
+ //! │    layer:          u32 LE  (version 3+)                      │
+//! │    cull_mode:      u8  (0=Back 1=Front 2=None; version 8+)   │
+//! │    depth_write:    u8  (version 8+)                          │
+//! │    depth_bias:     i32 LE, slope_scale: f32 LE (version 8+)  │
+//! │    emission:       f32 LE * 4  (rgb, strength; version 10+)  │
+//! │    displacement:   f32 LE * 7  (amplitude xyz, wave vector   │
+//! │                     xyz, speed; version 11+)                 │
  //! │    children_count: u32 LE                                    │
 //! │    children:       u32 LE * children_count                   │
 //! └──────────────────────────────────────────────────────────────┘
 //! ```
 //!
-//! Minimum valid file (header + empty camera + no objects): **84 bytes**.
+//! Minimum valid file (header + empty camera + no objects): **104 bytes**.
+//!
+//! Files back to [`MIN_FORMAT_VERSION`] are still read; fields they lack
+//! (render layers before version 3, camera exposure before version 4, depth
+//! of field before version 5, Box and Plane subdivisions before version 6,
+//! orthographic cameras before version 7, object materials before version
+//! 8, sphere tessellation before version 9, emission before version 10,
+//! vertex displacement before version 11) take their defaults.
 //! Compare to an equivalent JSON representation which would be several kilobytes
 //! even for trivial scenes.
 
@@ -56,8 +77,11 @@ use std::io::{self, BufReader, BufWriter, Read, Write};
 use std::path::Path;
 
 use crate::camera::Camera;
-use crate::geometry::Geometry;
+use crate::constants::{self, layers};
+use crate::geometry::{Geometry, SphereTessellation};
+use crate::material::{CullMode, Displacement, Emission, Material};
 use crate::objects::Object;
+use crate::pipeline_cache::DepthBias;
 use crate::transform::Transform;
 use crate::world::World;
 
@@ -66,13 +90,20 @@ use crate::world::World;
 pub const MAGIC: [u8; 4] = [0x56, 0x54, 0x52, 0x00]; // "VTR\0"
 
 /// Bump this whenever the binary layout changes in a backward-incompatible way.
-pub const FORMAT_VERSION: u16 = 2;
+pub const FORMAT_VERSION: u16 = 11;
+
+/// Oldest layout [`read`] still accepts.
+pub const MIN_FORMAT_VERSION: u16 = 2;
 
 /// Engine version embedded in the header for informational purposes.
 pub const ENGINE_VERSION_MAJOR: u16 = 0;
 pub const ENGINE_VERSION_MINOR: u16 = 2;
 pub const ENGINE_VERSION_PATCH: u16 = 0;
 
+/// Largest segment or subdivision count [`read`] accepts for a geometry, so
+/// a crafted file cannot request an enormous mesh.
+pub const MAX_SEGMENTS: u32 = 1024;
+
 /// Sentinel stored in `parent_id` when an object has no parent.
 const NO_PARENT: u32 = u32::MAX;
 
@@ -88,7 +119,8 @@ pub struct SceneData {
 /// Metadata from the file header — readable without parsing the full scene.
 #[derive(Debug, Clone, PartialEq)]
 pub struct VtrHeader {
-    /// Version of the binary layout (must equal [`FORMAT_VERSION`] to load).
+    /// Version of the binary layout (between [`MIN_FORMAT_VERSION`] and
+    /// [`FORMAT_VERSION`] to load).
     pub format_version: u16,
     /// Engine major version that wrote this file.
     pub engine_major: u16,
@@ -119,6 +151,12 @@ pub enum VtrError {
     InvalidUtf8(std::string::FromUtf8Error),
     /// An unknown `geometry_tag` byte was encountered.
     UnknownGeometryTag(u8),
+    /// An unknown `cull_mode` byte was encountered.
+    UnknownCullMode(u8),
+    /// An unknown sphere `tessellation` byte was encountered.
+    UnknownTessellation(u8),
+    /// A geometry's segment or subdivision count exceeds [`MAX_SEGMENTS`].
+    TooManySegments { found: u32 },
     /// An object's `texture_path` is longer than `u16::MAX` bytes and cannot
     /// be encoded in the VTR on-disk length field.
     TexturePathTooLong { len: usize },
@@ -135,13 +173,22 @@ impl std::fmt::Display for VtrError {
                 write!(
                     f,
                     "Unsupported VTR format version {found} \
-                     (this build supports version {FORMAT_VERSION})"
+                     (this build supports versions {MIN_FORMAT_VERSION}-{FORMAT_VERSION})"
                 )
             }
             VtrError::InvalidUtf8(e) => write!(f, "Invalid UTF-8 in object name: {e}"),
             VtrError::UnknownGeometryTag(tag) => {
                 write!(f, "Unknown geometry tag byte: {tag:#04x}")
             }
+            VtrError::UnknownCullMode(tag) => {
+                write!(f, "Unknown cull mode byte: {tag:#04x}")
+            }
+            VtrError::UnknownTessellation(tag) => {
+                write!(f, "Unknown sphere tessellation byte: {tag:#04x}")
+            }
+            VtrError::TooManySegments { found } => {
+                write!(f, "Geometry segment count {found} exceeds the maximum of {MAX_SEGMENTS}")
+            }
             VtrError::TexturePathTooLong { len } => {
                 write!(
                     f,
@@ -250,6 +297,9 @@ mod tag {
     pub const PYRAMID: u8 = 4;
     pub const CAPSULE: u8 = 5;
     pub const SPHERE: u8 = 6;
+    pub const ROUNDED_BOX: u8 = 7;
+    pub const ARROW: u8 = 8;
+    pub const SPRITE: u8 = 9;
 }
 
 fn write_geometry(w: &mut impl Write, geom: &Option<Geometry>) -> io::Result<()> {
@@ -259,15 +309,25 @@ fn write_geometry(w: &mut impl Write, geom: &Option<Geometry>) -> io::Result<()>
             w.write_all(&[tag::CUBE])?;
             w_f32(w, *size)
         }
-        Some(Geometry::Box { width, height, depth }) => {
+        Some(Geometry::Box { width, height, depth, segments }) => {
             w.write_all(&[tag::BOX])?;
             w_f32(w, *width)?;
             w_f32(w, *height)?;
-            w_f32(w, *depth)
+            w_f32(w, *depth)?;
+            segments.iter().try_for_each(|s| w_u32(w, *s as u32))
         }
-        Some(Geometry::Plane { size }) => {
+        Some(Geometry::RoundedBox { width, height, depth, radius, subdivisions }) => {
+            w.write_all(&[tag::ROUNDED_BOX])?;
+            w_f32(w, *width)?;
+            w_f32(w, *height)?;
+            w_f32(w, *depth)?;
+            w_f32(w, *radius)?;
+            w_u32(w, *subdivisions as u32)
+        }
+        Some(Geometry::Plane { size, subdivisions }) => {
             w.write_all(&[tag::PLANE])?;
-            w_f32(w, *size)
+            w_f32(w, *size)?;
+            w_u32(w, *subdivisions as u32)
         }
         Some(Geometry::Pyramid { base_size, height }) => {
             w.write_all(&[tag::PYRAMID])?;
@@ -280,15 +340,85 @@ fn write_geometry(w: &mut impl Write, geom: &Option<Geometry>) -> io::Result<()>
             w_f32(w, *height)?;
             w_u32(w, *subdivisions as u32)
         }
-        Some(Geometry::Sphere { radius, subdivisions }) => {
+        Some(Geometry::Arrow { length, shaft_radius, head_size }) => {
+            w.write_all(&[tag::ARROW])?;
+            w_f32(w, *length)?;
+            w_f32(w, *shaft_radius)?;
+            w_f32(w, *head_size)
+        }
+        Some(Geometry::Sprite { width, height, uv }) => {
+            w.write_all(&[tag::SPRITE])?;
+            w_f32(w, *width)?;
+            w_f32(w, *height)?;
+            uv.iter().try_for_each(|c| w_f32(w, *c))
+        }
+        Some(Geometry::Sphere { radius, subdivisions, tessellation }) => {
             w.write_all(&[tag::SPHERE])?;
             w_f32(w, *radius)?;
-            w_u32(w, *subdivisions as u32)
+            w_u32(w, *subdivisions as u32)?;
+            w.write_all(&[match tessellation {
+                SphereTessellation::Uv => 0,
+                SphereTessellation::Icosphere => 1,
+            }])
         }
     }
 }
 
-fn read_geometry(r: &mut impl Read) -> Result<Option<Geometry>, VtrError> {
+fn write_material(w: &mut impl Write, material: &Material) -> io::Result<()> {
+    let cull = match material.cull_mode {
+        CullMode::Back => 0u8,
+        CullMode::Front => 1,
+        CullMode::None => 2,
+    };
+    w.write_all(&[cull, u8::from(material.depth_write)])?;
+    w.write_all(&material.depth_bias.constant.to_le_bytes())?;
+    w_f32(w, material.depth_bias.slope_scale)?;
+    let Emission { color: [r, g, b], strength } = material.emission;
+    [r, g, b, strength].iter().try_for_each(|c| w_f32(w, *c))?;
+    let Displacement { amplitude: [ax, ay, az], wave_vector: [kx, ky, kz], speed } = material.displacement;
+    [ax, ay, az, kx, ky, kz, speed].iter().try_for_each(|c| w_f32(w, *c))
+}
+
+fn read_material(r: &mut impl Read, format_version: u16) -> Result<Material, VtrError> {
+    let mut buf = [0u8; 2];
+    r.read_exact(&mut buf)?;
+    let cull_mode = match buf[0] {
+        0 => CullMode::Back,
+        1 => CullMode::Front,
+        2 => CullMode::None,
+        tag => return Err(VtrError::UnknownCullMode(tag)),
+    };
+    let constant = r_u32(r)? as i32;
+    let slope_scale = r_f32(r)?;
+    let emission = if format_version >= 10 {
+        Emission::new([r_f32(r)?, r_f32(r)?, r_f32(r)?], r_f32(r)?)
+    } else {
+        Emission::NONE
+    };
+    let displacement = if format_version >= 11 {
+        Displacement::new([r_f32(r)?, r_f32(r)?, r_f32(r)?], [r_f32(r)?, r_f32(r)?, r_f32(r)?], r_f32(r)?)
+    } else {
+        Displacement::NONE
+    };
+    Ok(Material {
+        cull_mode,
+        depth_write: buf[1] != 0,
+        depth_bias: DepthBias { constant, slope_scale },
+        emission,
+        displacement,
+    })
+}
+
+/// Read a segment or subdivision count, rejecting ones above [`MAX_SEGMENTS`].
+fn r_segments(r: &mut impl Read) -> Result<usize, VtrError> {
+    match r_u32(r)? {
+        found if found > MAX_SEGMENTS => Err(VtrError::TooManySegments { found }),
+        count => Ok(count as usize),
+    }
+}
+
+fn read_geometry(r: &mut impl Read, format_version: u16) -> Result<Option<Geometry>, VtrError> {
+    let has_segments = format_version >= 6;
     let mut buf = [0u8; 1];
     r.read_exact(&mut buf)?;
     match buf[0] {
@@ -298,8 +428,23 @@ fn read_geometry(r: &mut impl Read) -> Result<Option<Geometry>, VtrError> {
             width: r_f32(r)?,
             height: r_f32(r)?,
             depth: r_f32(r)?,
+            segments: if has_segments {
+                [r_segments(r)?, r_segments(r)?, r_segments(r)?]
+            } else {
+                [1; 3]
+            },
+        })),
+        tag::ROUNDED_BOX => Ok(Some(Geometry::RoundedBox {
+            width: r_f32(r)?,
+            height: r_f32(r)?,
+            depth: r_f32(r)?,
+            radius: r_f32(r)?,
+            subdivisions: r_segments(r)?,
+        })),
+        tag::PLANE => Ok(Some(Geometry::Plane {
+            size: r_f32(r)?,
+            subdivisions: if has_segments { r_segments(r)? } else { 1 },
         })),
-        tag::PLANE => Ok(Some(Geometry::Plane { size: r_f32(r)? })),
         tag::PYRAMID => Ok(Some(Geometry::Pyramid {
             base_size: r_f32(r)?,
             height: r_f32(r)?,
@@ -307,11 +452,31 @@ fn read_geometry(r: &mut impl Read) -> Result<Option<Geometry>, VtrError> {
         tag::CAPSULE => Ok(Some(Geometry::Capsule {
             radius: r_f32(r)?,
             height: r_f32(r)?,
-            subdivisions: r_u32(r)? as usize,
+            subdivisions: r_segments(r)?,
+        })),
+        tag::ARROW => Ok(Some(Geometry::Arrow {
+            length: r_f32(r)?,
+            shaft_radius: r_f32(r)?,
+            head_size: r_f32(r)?,
+        })),
+        tag::SPRITE => Ok(Some(Geometry::Sprite {
+            width: r_f32(r)?,
+            height: r_f32(r)?,
+            uv: [r_f32(r)?, r_f32(r)?, r_f32(r)?, r_f32(r)?],
         })),
         tag::SPHERE => Ok(Some(Geometry::Sphere {
             radius: r_f32(r)?,
-            subdivisions: r_u32(r)? as usize,
+            subdivisions: r_segments(r)?,
+            tessellation: if format_version >= 9 {
+                r.read_exact(&mut buf)?;
+                match buf[0] {
+                    0 => SphereTessellation::Uv,
+                    1 => SphereTessellation::Icosphere,
+                    tag => return Err(VtrError::UnknownTessellation(tag)),
+                }
+            } else {
+                SphereTessellation::Uv
+            },
         })),
         unknown => Err(VtrError::UnknownGeometryTag(unknown)),
     }
@@ -329,7 +494,7 @@ pub fn read_header(r: &mut impl Read) -> Result<VtrHeader, VtrError> {
         return Err(VtrError::InvalidMagic);
     }
     let format_version = r_u16(r)?;
-    if format_version != FORMAT_VERSION {
+    if !(MIN_FORMAT_VERSION..=FORMAT_VERSION).contains(&format_version) {
         return Err(VtrError::UnsupportedVersion { found: format_version });
     }
     let engine_major = r_u16(r)?;
@@ -370,6 +535,11 @@ pub fn write(w: &mut impl Write, camera: &Camera, world: &World) -> Result<(), V
     w_f32(w, camera.zfar)?;
     w_f32(w, camera.lr_rot)?;
     w_f32(w, camera.ud_rot)?;
+    w_u32(w, camera.layers_mask)?;
+    w_f32(w, camera.exposure)?;
+    w_f32(w, camera.focus_distance)?;
+    w_f32(w, camera.aperture)?;
+    w_f32(w, camera.ortho_height.unwrap_or(0.0))?;
 
     // Roots
     // Store the ordered root list explicitly so load-time order is preserved.
@@ -420,6 +590,9 @@ pub fn write(w: &mut impl Write, camera: &Camera, world: &World) -> Result<(), V
             None => w_u16(w, 0)?,
         }
 
+        w_u32(w, obj.layer)?;
+        write_material(w, &obj.material)?;
+
         w_u32(w, obj.children.len() as u32)?;
         for &child_id in &obj.children {
             w_u32(w, child_id as u32)?;
@@ -435,6 +608,11 @@ pub fn read(r: &mut impl Read) -> Result<SceneData, VtrError> {
     // Header
     let header = read_header(r)?;
     let object_count = header.object_count as usize;
+    let has_layers = header.format_version >= 3;
+    let has_exposure = header.format_version >= 4;
+    let has_focus = header.format_version >= 5;
+    let has_ortho = header.format_version >= 7;
+    let has_material = header.format_version >= 8;
 
     // Camera
     let camera = Camera {
@@ -447,6 +625,11 @@ pub fn read(r: &mut impl Read) -> Result<SceneData, VtrError> {
         zfar: r_f32(r)?,
         lr_rot: r_f32(r)?,
         ud_rot: r_f32(r)?,
+        layers_mask: if has_layers { r_u32(r)? } else { layers::ALL },
+        exposure: if has_exposure { r_f32(r)? } else { constants::camera::DEFAULT_EXPOSURE },
+        focus_distance: if has_focus { r_f32(r)? } else { constants::camera::DEFAULT_FOCUS_DISTANCE },
+        aperture: if has_focus { r_f32(r)? } else { constants::camera::DEFAULT_APERTURE },
+        ortho_height: if has_ortho { Some(r_f32(r)?).filter(|&height| height > 0.0) } else { None },
     };
 
     // Roots
@@ -478,13 +661,13 @@ pub fn read(r: &mut impl Read) -> Result<SceneData, VtrError> {
 
         let mut sid_bytes = vec![0u8; str_id_len];
         r.read_exact(&mut sid_bytes)?;
-        let str_id = Some(String::from_utf8(sid_bytes)?);
+        let str_id = String::from_utf8(sid_bytes)?;
 
         let position = r_f32x3(r)?;
         let rotation = r_f32x3(r)?;
         let scale = r_f32x3(r)?;
         let color = r_f32x4(r)?;
-        let geometry = read_geometry(r)?;
+        let geometry = read_geometry(r, header.format_version)?;
 
         // texture_path: u16-prefixed UTF-8 string (0 length = no texture)
         let tp_len = r_u16(r)? as usize;
@@ -496,6 +679,9 @@ pub fn read(r: &mut impl Read) -> Result<SceneData, VtrError> {
             None
         };
 
+        let layer = if has_layers { r_u32(r)? } else { layers::DEFAULT };
+        let material = if has_material { read_material(r, header.format_version)? } else { Material::default() };
+
         let children_count = r_u32(r)? as usize;
         let mut children = Vec::with_capacity(children_count);
         for _ in 0..children_count {
@@ -510,13 +696,16 @@ pub fn read(r: &mut impl Read) -> Result<SceneData, VtrError> {
             id,
             Object {
                 name,
-                str_id: str_id.unwrap(),
+                str_id,
                 transform: Transform { position, rotation, scale },
                 geometry,
                 color,
                 children,
                 parent,
                 texture_path,
+                mesh: None,
+                layer,
+                material,
             },
         );
     }
@@ -529,6 +718,20 @@ pub fn read(r: &mut impl Read) -> Result<SceneData, VtrError> {
     Ok(SceneData { camera, world })
 }
 
+/// Serialize just `camera`, as a scene file without objects.
+///
+/// Used for viewpoint bookmarks; [`read_camera`] reads it back.
+pub fn write_camera(w: &mut impl Write, camera: &Camera) -> Result<(), VtrError> {
+    write(w, camera, &World::new())
+}
+
+/// Read only the camera of a scene, ignoring its objects.
+///
+/// Accepts both [`write_camera`] output and complete scene files.
+pub fn read_camera(r: &mut impl Read) -> Result<Camera, VtrError> {
+    read(r).map(|data| data.camera)
+}
+
 /// Write a scene to a file at the given path, creating or truncating it.
 pub fn write_to_file(path: &Path, camera: &Camera, world: &World) -> Result<(), VtrError> {
     let file = File::create(path)?;
@@ -550,3 +753,16 @@ pub fn header_from_file(path: &Path) -> Result<VtrHeader, VtrError> {
     read_header(&mut reader)
 }
 
+/// Write a camera bookmark to a file at the given path (see [`write_camera`]).
+pub fn write_camera_to_file(path: &Path, camera: &Camera) -> Result<(), VtrError> {
+    let file = File::create(path)?;
+    let mut writer = BufWriter::new(file);
+    write_camera(&mut writer, camera)
+}
+
+/// Read the camera from a bookmark or scene file (see [`read_camera`]).
+pub fn read_camera_from_file(path: &Path) -> Result<Camera, VtrError> {
+    let file = File::open(path)?;
+    let mut reader = BufReader::new(file);
+    read_camera(&mut reader)
+}
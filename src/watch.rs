@@ -0,0 +1,142 @@
+//! Polling file watcher used for hot reloading.
+//!
+//! [`FileWatcher`] remembers the modification time of every watched path and
+//! reports the paths whose timestamp changed since the previous poll.  It
+//! uses plain `std::fs` metadata, so it needs no platform notification API
+//! and costs one `stat` per file per poll; polls are throttled to
+//! [`DEFAULT_POLL_INTERVAL_SECS`] by default.
+//!
+//! [`crate::scene::Scene`] builds hot reloading of scene files, textures
+//! and shaders on top of it; see [`crate::scene::Scene::poll_hot_reload`].
+//!
+//! A file that disappears (editors often save by delete + rename) is not
+//! reported until it exists again, so a half-written file is never picked
+//! up as a change.  On `wasm32` there is no file system and nothing is ever
+//! reported.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::constants::watch::DEFAULT_POLL_INTERVAL_SECS;
+
+/// What a watched file is reloaded as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssetKind {
+    /// A `.vtr` scene file; reloads replace the world.
+    Scene,
+    /// An image registered with `Scene::load_texture`.
+    Texture,
+    /// The WGSL scene shader.
+    Shader,
+}
+
+/// Outcome of reloading one changed file.
+#[derive(Debug)]
+pub struct AssetReload {
+    pub path: PathBuf,
+    pub kind: AssetKind,
+    /// `Err` holds a description of why the reload failed; the previous
+    /// version of the asset stays in use.
+    pub result: Result<(), String>,
+}
+
+/// Modification-time based watcher for a set of files.
+#[derive(Debug)]
+pub struct FileWatcher {
+    /// Watched path → last observed modification time (`None` if missing).
+    entries: HashMap<PathBuf, Option<SystemTime>>,
+    interval_secs: f32,
+    last_poll: Option<web_time::Instant>,
+}
+
+impl Default for FileWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl FileWatcher {
+    pub fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+            interval_secs: DEFAULT_POLL_INTERVAL_SECS,
+            last_poll: None,
+        }
+    }
+
+    /// Set the minimum time between two polls in seconds.  `0.0` checks on
+    /// every call to [`Self::poll`].
+    pub fn with_interval(mut self, secs: f32) -> Self {
+        self.set_interval(secs);
+        self
+    }
+
+    /// See [`Self::with_interval`].
+    pub fn set_interval(&mut self, secs: f32) {
+        self.interval_secs = secs.max(0.0);
+    }
+
+    /// Start watching `path`.  Its current modification time becomes the
+    /// baseline, so only later edits are reported.
+    pub fn watch(&mut self, path: impl Into<PathBuf>) {
+        let path = path.into();
+        let stamp = modified(&path);
+        self.entries.insert(path, stamp);
+    }
+
+    /// Stop watching `path`.  Returns `false` if it was not watched.
+    pub fn unwatch(&mut self, path: &Path) -> bool {
+        self.entries.remove(path).is_some()
+    }
+
+    /// Whether `path` is being watched.
+    pub fn is_watching(&self, path: &Path) -> bool {
+        self.entries.contains_key(path)
+    }
+
+    /// Stop watching every path.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Number of watched paths.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Return the watched paths modified since the last poll, or nothing if
+    /// the poll interval has not elapsed yet.
+    pub fn poll(&mut self) -> Vec<PathBuf> {
+        let now = web_time::Instant::now();
+        if let Some(last) = self.last_poll
+            && now.duration_since(last).as_secs_f32() < self.interval_secs
+        {
+            return Vec::new();
+        }
+        self.last_poll = Some(now);
+        self.poll_now()
+    }
+
+    /// Like [`Self::poll`], but ignores the poll interval.
+    pub fn poll_now(&mut self) -> Vec<PathBuf> {
+        let mut changed = Vec::new();
+        for (path, last) in &mut self.entries {
+            let stamp = modified(path);
+            if stamp.is_some() && stamp != *last {
+                changed.push(path.clone());
+            }
+            *last = stamp;
+        }
+        changed.sort();
+        changed
+    }
+}
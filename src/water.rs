@@ -0,0 +1,591 @@
+//! Animated water surfaces.
+//!
+//! [`Water`] describes a square plane at a fixed height whose vertices are
+//! displaced on the GPU by a sum of Gerstner [`Wave`]s: crests sharpen and
+//! troughs flatten as [`Wave::steepness`] rises, and pure sine waves remain
+//! at steepness `0`.  The fragment shader blends from
+//! [`Water::deep_color`] looking straight down to [`Water::fresnel_color`]
+//! at grazing angles, lit by the scene's ambient light.
+//!
+//! With [`Water::reflection`] on, the scene is rendered a second time each
+//! frame from the camera mirrored in the water plane, into a texture
+//! [`Water::reflection_scale`] times the viewport size, and that image takes
+//! the place of the fresnel colour, rippled by the wave normals.  The
+//! reflection pass has no clip plane, so objects below the surface show up
+//! in it too.
+//!
+//! Install water with [`crate::scene::Scene::set_water`];
+//! [`crate::window::Window`] advances [`Water::time`] every frame.
+//!
+//! ```no_run
+//! # fn demo(scene: &mut vertra::scene::Scene) {
+//! use vertra::water::{Water, Wave};
+//! scene.set_water(Some(
+//!     Water::default()
+//!         .with_level(-1.0)
+//!         .with_waves(vec![Wave::new([1.0, 0.3], 0.4, 12.0).with_steepness(0.6)])
+//!         .with_reflection(true),
+//! ));
+//! # }
+//! ```
+//!
+//! [`Water::displace`] and [`Water::height_at`] evaluate the same waves on
+//! the CPU, e.g. to float objects on the surface.
+
+use std::f32::consts::TAU;
+use std::sync::{Mutex, PoisonError};
+use wgpu::{Device, PipelineCompilationOptions};
+use crate::camera::{Camera, Viewport};
+use crate::constants::water::{
+    DEEP_COLOR, DEFAULT_RESOLUTION, DEFAULT_SIZE, DISTORTION, FRESNEL_COLOR, FRESNEL_POWER, GRAVITY, MAX_WAVES,
+    REFLECTION_SCALE,
+};
+use crate::mesh::{BakedMesh, MeshData, Vertex};
+use crate::pipeline::{self, CaptureTarget, GpuMemory, Pipeline, RenderStats, VERTEX_ATTRS};
+use crate::shader::ShaderPreprocessor;
+
+/// Colour format of the reflection target.
+pub const REFLECTION_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+/// One Gerstner wave travelling across the water.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Wave {
+    /// Direction of travel on the XZ plane; normalised when used.
+    pub direction: [f32; 2],
+    /// Crest height above the rest level, in world units.
+    pub amplitude: f32,
+    /// Distance between crests, in world units.
+    pub wavelength: f32,
+    /// Speed the crests travel at, in world units per second.
+    pub speed: f32,
+    /// Horizontal pinching of the crests: `0` is a sine wave, `1` the
+    /// sharpest crest.  Crests loop over themselves once
+    /// `steepness · amplitude · 2π / wavelength` exceeds `1`.
+    pub steepness: f32,
+}
+
+impl Wave {
+    /// Sine wave moving along `direction` at the speed of a deep-water wave
+    /// of this `wavelength`.
+    pub fn new(direction: [f32; 2], amplitude: f32, wavelength: f32) -> Self {
+        Self {
+            direction,
+            amplitude,
+            wavelength,
+            speed: (GRAVITY * wavelength / TAU).sqrt(),
+            steepness: 0.0,
+        }
+    }
+
+    /// Set the crest speed.
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Set the crest steepness.
+    pub fn with_steepness(mut self, steepness: f32) -> Self {
+        self.steepness = steepness;
+        self
+    }
+
+    /// Unit direction, wavenumber and phase of this wave at `(x, z)` and
+    /// `time`, or `None` for degenerate waves.
+    fn phase(&self, x: f32, z: f32, time: f32) -> Option<([f32; 2], f32, f32)> {
+        let [dx, dz] = self.direction;
+        let length = (dx * dx + dz * dz).sqrt();
+        if length <= 0.0 || self.wavelength <= 0.0 {
+            return None;
+        }
+        let d = [dx / length, dz / length];
+        let k = TAU / self.wavelength;
+        Some((d, k, k * (d[0] * x + d[1] * z - self.speed * time)))
+    }
+}
+
+/// A square water plane with animated waves.  See the [module
+/// docs](self).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Water {
+    /// Rest height of the surface.
+    pub level: f32,
+    /// Centre of the plane on the XZ plane.
+    pub center: [f32; 2],
+    /// Side length of the plane.
+    pub size: f32,
+    /// Grid cells along each side; more cells give smoother waves.
+    pub resolution: u32,
+    /// Waves summed over the surface.  Only the first
+    /// [`MAX_WAVES`] are used.
+    pub waves: Vec<Wave>,
+    /// Colour looking straight down; alpha is the water's opacity.
+    pub deep_color: [f32; 4],
+    /// Colour at grazing angles while [`Self::reflection`] is off.
+    pub fresnel_color: [f32; 4],
+    /// Exponent of the fresnel term; higher keeps the tint closer to the
+    /// horizon.
+    pub fresnel_power: f32,
+    /// Render a planar reflection each frame.  Costs a second scene
+    /// render.
+    pub reflection: bool,
+    /// Reflection target size as a fraction of the viewport.
+    pub reflection_scale: f32,
+    /// How far the wave normals ripple the reflection, in UV units.
+    pub distortion: f32,
+    /// Animation time in seconds, advanced by
+    /// [`crate::scene::Scene::update_water`].
+    pub time: f32,
+}
+
+impl Default for Water {
+    fn default() -> Self {
+        Self {
+            level: 0.0,
+            center: [0.0, 0.0],
+            size: DEFAULT_SIZE,
+            resolution: DEFAULT_RESOLUTION,
+            waves: vec![
+                Wave::new([1.0, 0.2], 0.25, 9.0).with_steepness(0.5),
+                Wave::new([-0.4, 1.0], 0.15, 5.5).with_steepness(0.4),
+                Wave::new([0.7, -0.6], 0.06, 2.3).with_steepness(0.3),
+            ],
+            deep_color: DEEP_COLOR,
+            fresnel_color: FRESNEL_COLOR,
+            fresnel_power: FRESNEL_POWER,
+            reflection: false,
+            reflection_scale: REFLECTION_SCALE,
+            distortion: DISTORTION,
+            time: 0.0,
+        }
+    }
+}
+
+impl Water {
+    /// Set the rest height.
+    pub fn with_level(mut self, level: f32) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Set the centre and side length of the plane.
+    pub fn with_extent(mut self, center: [f32; 2], size: f32) -> Self {
+        self.center = center;
+        self.size = size;
+        self
+    }
+
+    /// Set the grid cells along each side.
+    pub fn with_resolution(mut self, resolution: u32) -> Self {
+        self.resolution = resolution;
+        self
+    }
+
+    /// Replace the waves.
+    pub fn with_waves(mut self, waves: Vec<Wave>) -> Self {
+        self.waves = waves;
+        self
+    }
+
+    /// Set the deep and grazing-angle colours.
+    pub fn with_colors(mut self, deep_color: [f32; 4], fresnel_color: [f32; 4]) -> Self {
+        self.deep_color = deep_color;
+        self.fresnel_color = fresnel_color;
+        self
+    }
+
+    /// Enable or disable the planar reflection.
+    pub fn with_reflection(mut self, reflection: bool) -> Self {
+        self.reflection = reflection;
+        self
+    }
+
+    /// Waves the shader uses.
+    fn active_waves(&self) -> &[Wave] {
+        &self.waves[..self.waves.len().min(MAX_WAVES)]
+    }
+
+    /// Where the rest-plane point `(x, z)` is displaced to at
+    /// [`Self::time`], exactly as the shader moves the vertex there.
+    pub fn displace(&self, x: f32, z: f32) -> [f32; 3] {
+        let mut p = [x, self.level, z];
+        for wave in self.active_waves() {
+            if let Some((d, _, f)) = wave.phase(x, z, self.time) {
+                let (s, c) = f.sin_cos();
+                let q = wave.steepness * wave.amplitude * c;
+                p[0] += d[0] * q;
+                p[1] += wave.amplitude * s;
+                p[2] += d[1] * q;
+            }
+        }
+        p
+    }
+
+    /// Approximate surface height above `(x, z)` at [`Self::time`]: the
+    /// displaced height of the rest point below, ignoring the horizontal
+    /// pinching of steep crests.
+    pub fn height_at(&self, x: f32, z: f32) -> f32 {
+        self.displace(x, z)[1]
+    }
+
+    /// Unit surface normal at the displaced rest-plane point `(x, z)`.
+    pub fn normal_at(&self, x: f32, z: f32) -> [f32; 3] {
+        let mut n = [0.0, 1.0, 0.0];
+        for wave in self.active_waves() {
+            if let Some((d, k, f)) = wave.phase(x, z, self.time) {
+                let (s, c) = f.sin_cos();
+                let ka = k * wave.amplitude;
+                n[0] -= d[0] * ka * c;
+                n[1] -= wave.steepness * ka * s;
+                n[2] -= d[1] * ka * c;
+            }
+        }
+        let length = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+        n.map(|c| c / length)
+    }
+
+    /// The undisplaced grid: `(resolution + 1)²` vertices at [`Self::level`],
+    /// UVs spanning `0..1`, triangles facing +Y.
+    pub fn mesh(&self) -> MeshData {
+        let cells = self.resolution.max(1);
+        let half = self.size * 0.5;
+        let mut mesh = MeshData::new();
+        for row in 0..=cells {
+            for col in 0..=cells {
+                let (u, v) = (col as f32 / cells as f32, row as f32 / cells as f32);
+                mesh.vertices.push(Vertex {
+                    position: [self.center[0] - half + u * self.size, self.level, self.center[1] - half + v * self.size],
+                    color: [1.0; 3],
+                    uv: [u, v],
+                });
+            }
+        }
+        let stride = cells + 1;
+        for row in 0..cells {
+            for col in 0..cells {
+                let i = row * stride + col;
+                mesh.indices.extend_from_slice(&[i, i + stride, i + 1, i + 1, i + stride, i + stride + 1]);
+            }
+        }
+        mesh
+    }
+
+    /// `camera` mirrored in the rest plane, with its up vector flipped back
+    /// so the reflection renders upside down: a water pixel at `(u, v)`
+    /// finds its reflection at `(u, 1 - v)`.
+    pub fn reflection_camera(&self, camera: &Camera) -> Camera {
+        let mirror = |p: [f32; 3]| [p[0], 2.0 * self.level - p[1], p[2]];
+        let [ux, uy, uz] = camera.up;
+        Camera { eye: mirror(camera.eye), target: mirror(camera.target), up: [-ux, uy, -uz], ..*camera }
+    }
+
+    /// Size of the reflection target for `viewport`.
+    fn reflection_size(&self, viewport: Viewport) -> [u32; 2] {
+        [viewport.width, viewport.height].map(|side| ((side * self.reflection_scale).round() as u32).max(1))
+    }
+}
+
+/// Water uniform mirrored by `WaterUniform` in `water.wgsl`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct WaterUniform {
+    /// `xy` unit direction, `z` amplitude, `w` wavenumber.
+    waves: [[f32; 4]; MAX_WAVES],
+    /// `x` angular speed (`k · speed`), `y` steepness.
+    motion: [[f32; 4]; MAX_WAVES],
+    deep_color: [f32; 4],
+    fresnel_color: [f32; 4],
+    /// `xyz` camera eye, `w` time.
+    eye: [f32; 4],
+    /// `x` fresnel power, `y` reflection weight, `z` distortion.
+    params: [f32; 4],
+    /// Viewport origin and size in pixels.
+    viewport: [f32; 4],
+}
+
+impl WaterUniform {
+    fn new(water: &Water, camera: &Camera, viewport: Viewport) -> Self {
+        let mut uniform = Self {
+            waves: [[0.0, 0.0, 0.0, 1.0]; MAX_WAVES],
+            motion: [[0.0; 4]; MAX_WAVES],
+            deep_color: water.deep_color,
+            fresnel_color: water.fresnel_color,
+            eye: [camera.eye[0], camera.eye[1], camera.eye[2], water.time],
+            params: [water.fresnel_power, if water.reflection { 1.0 } else { 0.0 }, water.distortion, 0.0],
+            viewport: [viewport.x, viewport.y, viewport.width, viewport.height],
+        };
+        let slots = uniform.waves.iter_mut().zip(&mut uniform.motion);
+        for ((slot, motion), wave) in slots.zip(water.active_waves()) {
+            // Evaluated at the origin, the phase is `-k · speed · time`.
+            if let Some((d, k, _)) = wave.phase(0.0, 0.0, 0.0) {
+                *slot = [d[0], d[1], wave.amplitude, k];
+                *motion = [k * wave.speed, wave.steepness, 0.0, 0.0];
+            }
+        }
+        uniform
+    }
+}
+
+/// Colour and depth targets of the reflection pass.
+struct ReflectionTarget {
+    size: [u32; 2],
+    color: wgpu::Texture,
+    depth: wgpu::TextureView,
+}
+
+/// GPU state of the installed [`Water`]: the plane mesh, uniform, the
+/// reflection target and one pipeline per scene format.
+pub(crate) struct WaterPass {
+    pub(crate) settings: Water,
+    shader: wgpu::ShaderModule,
+    pipeline_layout: wgpu::PipelineLayout,
+    pipelines: Mutex<Vec<(wgpu::TextureFormat, wgpu::RenderPipeline)>>,
+    layout: wgpu::BindGroupLayout,
+    buffer: wgpu::Buffer,
+    sampler: wgpu::Sampler,
+    /// Bound in place of the reflection while it is off.
+    placeholder: wgpu::TextureView,
+    bind_group: wgpu::BindGroup,
+    reflection: Option<ReflectionTarget>,
+    /// The plane and the `(center, size, resolution, level)` it was built for.
+    mesh: Option<(BakedMesh, [f32; 5])>,
+}
+
+impl WaterPass {
+    pub(crate) fn new(pipeline: &Pipeline, settings: Water) -> Self {
+        let device = &pipeline.device;
+        let source = ShaderPreprocessor::new()
+            .process("water.wgsl", include_str!("water.wgsl"))
+            .expect("built-in shader preprocesses");
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("water.wgsl"),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        });
+        let visibility = wgpu::ShaderStages::VERTEX_FRAGMENT;
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+            label: Some("water_bind_group_layout"),
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Water Pipeline Layout"),
+            bind_group_layouts: &[Some(&pipeline.camera_bind_group_layout), Some(&layout)],
+            immediate_size: 0,
+        });
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Water Uniform Buffer"),
+            size: size_of::<WaterUniform>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Water Reflection Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let placeholder = create_color_target(device, [1, 1]).create_view(&wgpu::TextureViewDescriptor::default());
+        let bind_group = create_bind_group(device, &layout, &buffer, &placeholder, &sampler);
+        Self {
+            settings,
+            shader,
+            pipeline_layout,
+            pipelines: Mutex::new(Vec::new()),
+            layout,
+            buffer,
+            sampler,
+            placeholder,
+            bind_group,
+            reflection: None,
+            mesh: None,
+        }
+    }
+
+    /// The capture target for this frame's reflection, resized to match
+    /// `viewport`, or `None` while reflections are off (which also frees
+    /// the target).
+    pub(crate) fn reflection_target(&mut self, device: &Device, viewport: Viewport) -> Option<CaptureTarget> {
+        if !self.settings.reflection {
+            if self.reflection.take().is_some() {
+                self.bind_group = create_bind_group(device, &self.layout, &self.buffer, &self.placeholder, &self.sampler);
+            }
+            return None;
+        }
+        let size = self.settings.reflection_size(viewport);
+        if self.reflection.as_ref().is_none_or(|target| target.size != size) {
+            let color = create_color_target(device, size);
+            let depth = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("Water Reflection Depth"),
+                size: wgpu::Extent3d { width: size[0], height: size[1], depth_or_array_layers: 1 },
+                mip_level_count: 1, sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Depth32Float,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[],
+            });
+            let view = color.create_view(&wgpu::TextureViewDescriptor::default());
+            self.bind_group = create_bind_group(device, &self.layout, &self.buffer, &view, &self.sampler);
+            let depth = depth.create_view(&wgpu::TextureViewDescriptor::default());
+            self.reflection = Some(ReflectionTarget { size, color, depth });
+        }
+        let target = self.reflection.as_ref()?;
+        Some(CaptureTarget {
+            color: target.color.create_view(&wgpu::TextureViewDescriptor::default()),
+            depth: target.depth.clone(),
+            view_projection: None,
+        })
+    }
+
+    /// Rebuild the plane if its shape changed since the last frame.
+    pub(crate) fn update_mesh(&mut self, pipeline: &Pipeline) {
+        let s = &self.settings;
+        let key = [s.center[0], s.center[1], s.size, s.resolution as f32, s.level];
+        if self.mesh.as_ref().is_none_or(|(_, built)| *built != key) {
+            self.mesh = Some((s.mesh().bake(pipeline), key));
+        }
+    }
+
+    /// Upload this frame's uniform.  Returns the bytes written.
+    pub(crate) fn prepare(&self, queue: &wgpu::Queue, camera: &Camera, viewport: Viewport) -> u64 {
+        let uniform = WaterUniform::new(&self.settings, camera, viewport);
+        queue.write_buffer(&self.buffer, 0, bytemuck::bytes_of(&uniform));
+        size_of::<WaterUniform>() as u64
+    }
+
+    /// Draw the surface into `rp`, whose group 0 holds the scene camera.
+    pub(crate) fn draw(
+        &self,
+        device: &Device,
+        rp: &mut wgpu::RenderPass<'_>,
+        format: wgpu::TextureFormat,
+        stats: &mut RenderStats,
+    ) {
+        let Some((mesh, _)) = &self.mesh else { return };
+        if mesh.index_count == 0 {
+            return;
+        }
+        rp.set_pipeline(&self.pipeline(device, format));
+        rp.set_bind_group(1, &self.bind_group, &[]);
+        rp.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+        rp.set_index_buffer(mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+        rp.draw_indexed(0..mesh.index_count, 0, 0..1);
+        stats.record_draw(mesh.index_count, 1);
+    }
+
+    /// Plane mesh, uniform and reflection target sizes.
+    pub(crate) fn gpu_memory(&self) -> GpuMemory {
+        let mut memory = GpuMemory {
+            uniform_bytes: self.buffer.size(),
+            render_target_bytes: self.reflection.as_ref()
+                .map_or(0, |target| pipeline::texture_bytes(&target.color) + pipeline::texture_bytes(target.depth.texture())),
+            ..GpuMemory::default()
+        };
+        if let Some((mesh, _)) = &self.mesh {
+            memory.add_mesh(mesh);
+        }
+        memory
+    }
+
+    /// The pipeline drawing into `format`, created on first use.
+    fn pipeline(&self, device: &Device, format: wgpu::TextureFormat) -> wgpu::RenderPipeline {
+        let mut pipelines = self.pipelines.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some((_, pipeline)) = pipelines.iter().find(|(f, _)| *f == format) {
+            return pipeline.clone();
+        }
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Water Pipeline"),
+            layout: Some(&self.pipeline_layout),
+            cache: None, multiview_mask: None,
+            vertex: wgpu::VertexState {
+                module: &self.shader, entry_point: Some("vs_main"),
+                compilation_options: PipelineCompilationOptions::default(),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: size_of::<Vertex>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &VERTEX_ATTRS,
+                }],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &self.shader, entry_point: Some("fs_main"),
+                compilation_options: PipelineCompilationOptions::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            // Visible from below as well; translucent, so depth is tested
+            // but not written.
+            primitive: wgpu::PrimitiveState { cull_mode: None, ..Default::default() },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_write_enabled: Some(false),
+                depth_compare: Some(wgpu::CompareFunction::Less),
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+        });
+        pipelines.push((format, pipeline.clone()));
+        pipeline
+    }
+}
+
+/// A sampled [`REFLECTION_FORMAT`] render target of `size`.
+fn create_color_target(device: &Device, size: [u32; 2]) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Water Reflection"),
+        size: wgpu::Extent3d { width: size[0], height: size[1], depth_or_array_layers: 1 },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: REFLECTION_FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+        view_formats: &[],
+    })
+}
+
+fn create_bind_group(
+    device: &Device,
+    layout: &wgpu::BindGroupLayout,
+    buffer: &wgpu::Buffer,
+    reflection: &wgpu::TextureView,
+    sampler: &wgpu::Sampler,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry { binding: 0, resource: buffer.as_entire_binding() },
+            wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(reflection) },
+            wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::Sampler(sampler) },
+        ],
+        label: Some("water_bind_group"),
+    })
+}
@@ -0,0 +1,76 @@
+// Animated water surface (see `water.rs`): Gerstner waves displace the
+// plane, and a fresnel term blends the deep colour towards the grazing tint
+// or the planar reflection.
+#include "vertra/lighting.wgsl"
+#include "vertra/vertex.wgsl"
+
+// Matches `constants::water::MAX_WAVES`.
+const MAX_WAVES: u32 = 4u;
+
+struct WaterUniform {
+    // xy unit direction, z amplitude, w wavenumber.
+    waves: array<vec4<f32>, 4>,
+    // x angular speed, y steepness.
+    motion: array<vec4<f32>, 4>,
+    deep_color: vec4<f32>,
+    fresnel_color: vec4<f32>,
+    // xyz camera eye, w time.
+    eye: vec4<f32>,
+    // x fresnel power, y reflection weight, z distortion.
+    params: vec4<f32>,
+    // Viewport origin and size in pixels.
+    viewport: vec4<f32>,
+};
+@group(1) @binding(0)
+var<uniform> water: WaterUniform;
+@group(1) @binding(1)
+var reflection_texture: texture_2d<f32>;
+@group(1) @binding(2)
+var reflection_sampler: sampler;
+
+struct WaterOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) world_position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+};
+
+@vertex
+fn vs_main(in: VertexInput) -> WaterOutput {
+    let rest = in.position.xz;
+    var position = in.position;
+    var normal = vec3<f32>(0.0, 1.0, 0.0);
+    for (var i = 0u; i < MAX_WAVES; i++) {
+        let wave = water.waves[i];
+        let motion = water.motion[i];
+        let f = wave.w * dot(wave.xy, rest) - motion.x * water.eye.w;
+        let c = cos(f);
+        let s = sin(f);
+        let ka = wave.w * wave.z;
+        position += vec3<f32>(wave.x * motion.y * wave.z * c, wave.z * s, wave.y * motion.y * wave.z * c);
+        normal -= vec3<f32>(wave.x * ka * c, motion.y * ka * s, wave.y * ka * c);
+    }
+    var out: WaterOutput;
+    out.clip_position = camera.view_proj * vec4<f32>(position, 1.0);
+    out.world_position = position;
+    out.normal = normal;
+    return out;
+}
+
+@fragment
+fn fs_main(in: WaterOutput) -> @location(0) vec4<f32> {
+    let to_eye = normalize(water.eye.xyz - in.world_position);
+    var normal = normalize(in.normal);
+    // Seen from below, the surface faces the other way.
+    normal = select(normal, -normal, dot(normal, to_eye) < 0.0);
+    let fresnel = pow(1.0 - clamp(dot(normal, to_eye), 0.0, 1.0), water.params.x);
+
+    // The reflection is rendered upside down; see `Water::reflection_camera`.
+    let screen = (in.clip_position.xy - water.viewport.xy) / water.viewport.zw;
+    let uv = vec2<f32>(screen.x, 1.0 - screen.y) + normal.xz * water.params.z;
+    let reflected = textureSample(reflection_texture, reflection_sampler, uv).rgb;
+    let grazing = mix(water.fresnel_color.rgb, reflected, water.params.y);
+
+    let body = water.deep_color.rgb * ambient_light(normal);
+    let alpha = mix(water.deep_color.a, 1.0, fresnel);
+    return vec4<f32>(mix(body, grazing, fresnel), alpha);
+}
@@ -1,24 +1,32 @@
 use winit::{
-    window::WindowBuilder,
-    dpi::PhysicalSize
+    application::ApplicationHandler,
+    event::{DeviceId, StartCause},
+    window::{Fullscreen, WindowAttributes, WindowId},
+    dpi::{LogicalSize, PhysicalPosition, PhysicalSize, Size},
+    error::EventLoopError,
+    monitor::MonitorHandle,
 };
+use std::path::PathBuf;
 use std::sync::Arc;
+#[cfg(target_os = "android")]
+use crate::event::AndroidApp;
 use crate::event::{
-    Event, EventLoopWindowTarget, EventLoop, WindowEvent,
+    ActiveEventLoop, ControlFlow, Event, EventLoop, EventLoopProxy, WindowEvent,
     MouseButton, MouseScrollDelta, ElementState, DeviceEvent,
 };
-use crate::pipeline::Pipeline;
+use crate::pipeline::{parse_flag, Pipeline, PipelineConfig, PipelineError, RenderError, RenderStats, SurfaceEvent};
 use crate::frame_stats::FrameStats;
-use crate::camera::Camera;use crate::mesh::MeshRegistry;
+use crate::camera::Camera;
 use crate::scene::Scene;
 use crate::editor::{EditorEvent, EditorStateEvent};
 use crate::constants::{window, frame_stats};
 use crate::objects::Object;
-use crate::world::World;
+use crate::replay::{InputEvent, Player, Recording, StepKind};
+use crate::watch::AssetReload;
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::JsCast;
 #[cfg(target_arch = "wasm32")]
-use winit::platform::web::WindowBuilderExtWebSys;
+use winit::platform::web::WindowAttributesExtWebSys;
 /// Per-frame timing and performance information passed to every callback.
 pub struct FrameContext {
     /// Delta-time in seconds since the previous frame.
@@ -31,11 +39,244 @@ pub struct FrameContext {
     pub draw_calls: u32,
     /// Triangles rendered during the most recently rendered frame.
     pub triangle_count: u32,
+    /// All renderer counters (instances, uploads, buffer allocations, …) of
+    /// the most recently rendered frame.
+    pub render_stats: RenderStats,
+    /// Physical pixels per logical pixel of the display the window is on.
+    pub scale_factor: f32,
+    /// Seconds since startup: the sum of every `dt` so far (replays
+    /// reproduce it exactly).
+    pub elapsed: f64,
+    /// Frames rendered so far; the frame being drawn in `on_draw_request`.
+    pub frame: u64,
+    /// How far the simulation is between the last fixed update and the
+    /// next, in `[0, 1)`.  Interpolate between the previous and current
+    /// fixed-update state by this much for smooth rendering.
+    pub alpha: f32,
+    /// Render target size in physical pixels.
+    pub window_size: (u32, u32),
+    /// The cursor mode last set through [`Self::commands`].
+    pub cursor_mode: CursorMode,
+    /// Window and loop requests, applied once the callback returns.
+    pub commands: AppCommands,
+}
+/// How the mouse cursor behaves over the window; see
+/// [`AppCommands::set_cursor_mode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CursorMode {
+    /// Visible and free to leave the window.
+    #[default]
+    Normal,
+    /// Invisible over the window, but free to leave it.
+    Hidden,
+    /// Visible and kept inside the window.
+    Confined,
+    /// Invisible and held in place, for mouse-look; read motion from
+    /// [`InputEvent::MouseMotion`].  Falls back to confining the cursor on
+    /// platforms that cannot lock it.
+    Locked,
+}
+impl CursorMode {
+    /// Whether the cursor is kept inside the window.
+    pub fn is_grabbed(self) -> bool {
+        matches!(self, Self::Confined | Self::Locked)
+    }
+}
+/// A request queued on [`AppCommands`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppCommand {
+    /// Exit the event loop after the current pass.
+    Exit,
+    /// Replace the window title.
+    SetTitle(String),
+    /// Enter (`true`) or leave borderless fullscreen on the current monitor.
+    SetFullscreen(bool),
+    /// Switch between windowed and borderless fullscreen.
+    ToggleFullscreen,
+    /// Change how the cursor behaves over the window.
+    SetCursorMode(CursorMode),
+    /// Allow or forbid input-method composition; see
+    /// [`AppCommands::set_ime_allowed`].
+    SetImeAllowed(bool),
+    /// Place the input method's candidate window next to the text caret,
+    /// as `(x, y, width, height)` in physical pixels.
+    SetImeCursorArea(f32, f32, f32, f32),
+}
+/// Window and loop requests from `on_startup`, `on_update`,
+/// `on_fixed_update` and `on_draw_request`, which have no access to the
+/// event loop.  Commands run in order once the callback returns.
+///
+/// ```
+/// # use vertra::window::{CursorMode, FrameContext};
+/// fn on_update(_: &mut (), _: &mut vertra::scene::Scene, ctx: &mut FrameContext) {
+///     ctx.commands.set_title(format!("demo — {:.0} fps", ctx.fps));
+///     ctx.commands.set_cursor_mode(CursorMode::Locked);
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AppCommands {
+    queue: Vec<AppCommand>,
+}
+impl FrameContext {
+    /// [`Self::window_size`] in logical pixels.
+    pub fn logical_size(&self) -> (f32, f32) {
+        let (w, h) = self.window_size;
+        (w as f32 / self.scale_factor, h as f32 / self.scale_factor)
+    }
+}
+impl AppCommands {
+    /// Exit the event loop, as closing the window does by default.
+    pub fn exit(&mut self) {
+        self.queue.push(AppCommand::Exit);
+    }
+    /// Replace the window title.
+    pub fn set_title(&mut self, title: impl Into<String>) {
+        self.queue.push(AppCommand::SetTitle(title.into()));
+    }
+    /// Enter or leave borderless fullscreen on the current monitor.
+    pub fn set_fullscreen(&mut self, fullscreen: bool) {
+        self.queue.push(AppCommand::SetFullscreen(fullscreen));
+    }
+    /// Switch between windowed and borderless fullscreen.
+    pub fn toggle_fullscreen(&mut self) {
+        self.queue.push(AppCommand::ToggleFullscreen);
+    }
+    /// Change how the cursor behaves over the window.
+    pub fn set_cursor_mode(&mut self, mode: CursorMode) {
+        self.queue.push(AppCommand::SetCursorMode(mode));
+    }
+    /// Let an input method compose text in the window, e.g. while a text
+    /// field has focus.  Composition then arrives as
+    /// [`InputEvent::ImePreedit`] and [`InputEvent::Text`]; key presses
+    /// are not reported while a composition is in progress.
+    pub fn set_ime_allowed(&mut self, allowed: bool) {
+        self.queue.push(AppCommand::SetImeAllowed(allowed));
+    }
+    /// Place the input method's candidate window next to the text caret,
+    /// the rectangle `(x, y)`–`(x + width, y + height)` in physical pixels.
+    pub fn set_ime_cursor_area(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        self.queue.push(AppCommand::SetImeCursorArea(x, y, width, height));
+    }
+    /// Queue a command directly.
+    pub fn push(&mut self, command: AppCommand) {
+        self.queue.push(command);
+    }
+    /// Number of queued commands.
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+    /// Whether no commands are queued.
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+    /// Remove and return the queued commands, oldest first.
+    pub fn drain(&mut self) -> impl Iterator<Item = AppCommand> + '_ {
+        self.queue.drain(..)
+    }
+    /// Carry out the queued commands on the loop's window.
+    fn apply(&mut self, lp: &mut LoopState, elwt: &ActiveEventLoop) {
+        use winit::window::CursorGrabMode;
+        let window = &lp.window_handle;
+        for command in self.drain() {
+            match command {
+                AppCommand::Exit => elwt.exit(),
+                AppCommand::SetTitle(title) => window.set_title(&title),
+                AppCommand::SetFullscreen(on) => {
+                    window.set_fullscreen(on.then_some(Fullscreen::Borderless(None)));
+                }
+                AppCommand::ToggleFullscreen => {
+                    let on = window.fullscreen().is_none();
+                    window.set_fullscreen(on.then_some(Fullscreen::Borderless(None)));
+                }
+                AppCommand::SetImeAllowed(allowed) => window.set_ime_allowed(allowed),
+                AppCommand::SetImeCursorArea(x, y, width, height) => {
+                    window.set_ime_cursor_area(PhysicalPosition::new(x, y), PhysicalSize::new(width, height));
+                }
+                AppCommand::SetCursorMode(mode) => {
+                    let (visible, grab) = match mode {
+                        CursorMode::Normal   => (true, CursorGrabMode::None),
+                        CursorMode::Hidden   => (false, CursorGrabMode::None),
+                        CursorMode::Confined => (true, CursorGrabMode::Confined),
+                        CursorMode::Locked   => (false, CursorGrabMode::Locked),
+                    };
+                    window.set_cursor_visible(visible);
+                    if window.set_cursor_grab(grab).is_err() && mode == CursorMode::Locked {
+                        let _ = window.set_cursor_grab(CursorGrabMode::Confined);
+                    }
+                    lp.cursor_mode = mode;
+                }
+            }
+        }
+    }
 }
 type DrawCallback<S>             = Box<dyn FnMut(&mut S, &mut Scene, &mut FrameContext)>;
-type EventCallback<S>            = Box<dyn FnMut(&mut S, &mut Scene, Event<()>, &EventLoopWindowTarget<()>)>;
-type CloseCallback<S>            = Box<dyn FnMut(&mut S, WindowEvent, &EventLoopWindowTarget<()>)>;
+type EventCallback<S, E>         = Box<dyn FnMut(&mut S, &mut Scene, Event<E>, &ActiveEventLoop)>;
+type CloseCallback<S>            = Box<dyn FnMut(&mut S, WindowEvent, &ActiveEventLoop)>;
 type EditorStateEventCallback<S> = Box<dyn FnMut(&mut S, &mut Scene, EditorStateEvent, Option<Object>)>;
+type MonitorSelector             = Box<dyn FnOnce(&[MonitorInfo]) -> Option<usize>>;
+type InputCallback<S>            = Box<dyn FnMut(&mut S, &mut Scene, &InputEvent)>;
+type SurfaceEventCallback<S>     = Box<dyn FnMut(&mut S, &mut Scene, SurfaceEvent)>;
+type RenderErrorCallback<S>      = Box<dyn FnMut(&mut S, &mut Scene, RenderError)>;
+type ResizeCallback<S>           = Box<dyn FnMut(&mut S, &mut Scene, (u32, u32), (u32, u32))>;
+type HotReloadCallback<S>        = Box<dyn FnMut(&mut S, &mut Scene, &AssetReload)>;
+
+/// A connected display, as reported by the OS.
+///
+/// Listed by [`list_monitors`] and passed to
+/// [`Window::with_monitor_selector`].  Sizes and positions are in physical
+/// pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitorInfo {
+    /// Position in the OS monitor list; pass to [`Window::with_monitor`].
+    pub index: usize,
+    /// Human-readable name, when the platform provides one.
+    pub name: Option<String>,
+    /// Resolution `[width, height]`.
+    pub size: [u32; 2],
+    /// Top-left corner on the virtual desktop.
+    pub position: [i32; 2],
+    /// Refresh rate in hertz, when known.
+    pub refresh_rate_hz: Option<f32>,
+    /// DPI scale factor (`2.0` on a typical high-DPI laptop panel).
+    pub scale_factor: f64,
+    /// Whether this is the primary display.
+    pub primary: bool,
+}
+
+impl MonitorInfo {
+    fn from_handle(index: usize, handle: &MonitorHandle, primary: Option<&MonitorHandle>) -> Self {
+        let (size, position) = (handle.size(), handle.position());
+        Self {
+            index,
+            name: handle.name(),
+            size: [size.width, size.height],
+            position: [position.x, position.y],
+            refresh_rate_hz: handle.refresh_rate_millihertz().map(|mhz| mhz as f32 / 1000.0),
+            scale_factor: handle.scale_factor(),
+            primary: primary == Some(handle),
+        }
+    }
+
+    /// Top-left corner for a window of `size` placed on this monitor: at
+    /// `offset` from the monitor's corner, or centred when `offset` is
+    /// `None`.
+    pub fn window_position(&self, size: [u32; 2], offset: Option<[i32; 2]>) -> [i32; 2] {
+        let offset = offset.unwrap_or_else(|| {
+            [0, 1].map(|k| (self.size[k] as i32 - size[k] as i32).max(0) / 2)
+        });
+        [self.position[0] + offset[0], self.position[1] + offset[1]]
+    }
+}
+
+/// List the connected monitors, e.g. from a [`Window::with_event_handler`]
+/// callback.  Empty on the web.
+pub fn list_monitors(target: &ActiveEventLoop) -> Vec<MonitorInfo> {
+    let primary = target.primary_monitor();
+    target.available_monitors()
+        .enumerate()
+        .map(|(index, handle)| MonitorInfo::from_handle(index, &handle, primary.as_ref()))
+        .collect()
+}
 
 /// Initial window configuration.
 ///
@@ -45,16 +286,47 @@ type EditorStateEventCallback<S> = Box<dyn FnMut(&mut S, &mut Scene, EditorState
 pub struct WindowConfig {
     /// OS window title bar text.
     pub title: String,
-    /// Initial window height in physical pixels.
+    /// Initial window height, in physical pixels unless
+    /// [`Self::logical_size`] is set.
     pub height: u32,
-    /// Initial window width in physical pixels.
+    /// Initial window width, in physical pixels unless
+    /// [`Self::logical_size`] is set.
     pub width: u32,
+    /// Treat `width` / `height` as logical pixels, scaled by the display's
+    /// DPI factor so the window has the same apparent size everywhere.
+    pub logical_size: bool,
     /// Minimum allowed window dimensions `[width, height]` in physical pixels.
     pub minimum_dimension: [u32; 2],
+    /// Open borderless fullscreen (on [`Self::monitor`] if set).
+    pub fullscreen: bool,
+    /// Index of the monitor to open on (see [`list_monitors`]).  `None`
+    /// leaves the choice to the OS.
+    pub monitor: Option<usize>,
+    /// Initial top-left corner in physical pixels: relative to
+    /// [`Self::monitor`] when one is set (which otherwise centres the
+    /// window), else on the virtual desktop.
+    pub position: Option<[i32; 2]>,
     /// *(WASM only)* `id` attribute of the `<canvas>` element to render into.
     pub canvas_id: Option<String>,
     /// Sleep time between two frame stats.
     pub stats_sample_window_secs: f32,
+    /// Also stop `on_update`, `on_fixed_update` and scripts while the window
+    /// is minimised or fully covered.  Rendering always stops then.
+    pub pause_when_hidden: bool,
+    /// Deliver [`InputEvent::MouseMotion`] even while the cursor is free.
+    /// By default raw motion only reaches [`Window::on_input`] while
+    /// [`CursorMode::Confined`] or [`CursorMode::Locked`] is set.
+    pub mouse_motion_always: bool,
+    /// Record every event-loop pass to this file (see [`crate::replay`]).
+    pub recording: Option<PathBuf>,
+    /// Play this recording back instead of reading live input.
+    pub replay: Option<PathBuf>,
+    /// Close the window when [`Self::replay`] ends, instead of switching to
+    /// live input.
+    pub exit_after_replay: bool,
+    /// Adapter / backend selection.  `VERTRA_*` environment variables are
+    /// applied on top when the pipeline is created.
+    pub pipeline: PipelineConfig,
 }
 
 impl Default for WindowConfig {
@@ -63,12 +335,113 @@ impl Default for WindowConfig {
             title: "untitled".into(),
             width: window::DEFAULT_WIDTH,
             height: window::DEFAULT_HEIGHT,
+            logical_size: false,
             minimum_dimension: window::MIN_DIMENSION,
+            fullscreen: false,
+            monitor: None,
+            position: None,
             canvas_id: None,
             stats_sample_window_secs: frame_stats::DEFAULT_SAMPLE_WINDOW_SECS,
+            pause_when_hidden: false,
+            mouse_motion_always: false,
+            recording: None,
+            replay: None,
+            exit_after_replay: false,
+            pipeline: PipelineConfig::default(),
+        }
+    }
+}
+
+impl WindowConfig {
+    /// Apply environment overrides on top of this configuration, so window
+    /// setup can be changed for a debugging session without code changes:
+    ///
+    /// | Variable            | Effect                                           |
+    /// |---------------------|--------------------------------------------------|
+    /// | `VERTRA_SIZE`       | Physical size as `WIDTHxHEIGHT` (e.g. `1920x1080`) |
+    /// | `VERTRA_FULLSCREEN` | `1` / `true` opens borderless fullscreen         |
+    /// | `VERTRA_MONITOR`    | Index of the monitor to open on                  |
+    ///
+    /// plus the [`PipelineConfig`] variables (`VERTRA_BACKEND`,
+    /// `VERTRA_VSYNC`, …).  Unset, empty or malformed variables leave fields
+    /// untouched.  [`Window::create`] calls this.
+    pub fn with_env_overrides(mut self) -> Self {
+        if let Some((width, height)) = std::env::var(window::SIZE_ENV).ok().and_then(|v| parse_size(&v)) {
+            self.width = width;
+            self.height = height;
+            self.logical_size = false;
+        }
+        if let Some(fullscreen) = std::env::var(window::FULLSCREEN_ENV).ok().and_then(|v| parse_flag(&v)) {
+            self.fullscreen = fullscreen;
+        }
+        if let Some(monitor) = std::env::var(window::MONITOR_ENV).ok().and_then(|v| v.trim().parse().ok()) {
+            self.monitor = Some(monitor);
+        }
+        self.pipeline = self.pipeline.with_env_overrides();
+        self
+    }
+}
+
+/// Parse a `WIDTHxHEIGHT` size (`"1920x1080"`).  `None` when malformed or
+/// when either side is zero.
+pub(crate) fn parse_size(value: &str) -> Option<(u32, u32)> {
+    let (width, height) = value.trim().to_ascii_lowercase().split_once('x')
+        .map(|(w, h)| (w.trim().parse().ok(), h.trim().parse().ok()))?;
+    match (width?, height?) {
+        (0, _) | (_, 0) => None,
+        size => Some(size),
+    }
+}
+/// Why [`Window::create`] or an [`App`] failed: the window could not be set
+/// up, or the recording could not be written on exit.
+#[derive(Debug)]
+pub enum WindowError {
+    /// winit could not create or run the event loop.
+    EventLoop(EventLoopError),
+    /// The OS refused to open the window.
+    CreateWindow(winit::error::OsError),
+    /// No surface, adapter or device could be set up for the window.
+    Pipeline(PipelineError),
+    /// The recording passed to [`Window::with_replay`] could not be loaded.
+    LoadReplay { path: PathBuf, error: std::io::Error },
+    /// The recording made with [`Window::with_recording`] could not be
+    /// written when the loop exited.
+    SaveRecording { path: PathBuf, error: std::io::Error },
+}
+
+impl std::fmt::Display for WindowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WindowError::EventLoop(e) => write!(f, "Event loop failed: {e}"),
+            WindowError::CreateWindow(e) => write!(f, "Failed to open the window: {e}"),
+            WindowError::Pipeline(e) => write!(f, "{e}"),
+            WindowError::LoadReplay { path, error } => {
+                write!(f, "Could not load replay {}: {error}", path.display())
+            }
+            WindowError::SaveRecording { path, error } => {
+                write!(f, "Could not save recording {}: {error}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for WindowError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WindowError::EventLoop(e) => Some(e),
+            WindowError::CreateWindow(e) => Some(e),
+            WindowError::Pipeline(e) => Some(e),
+            WindowError::LoadReplay { error, .. } | WindowError::SaveRecording { error, .. } => Some(error),
         }
     }
 }
+
+impl From<EventLoopError> for WindowError {
+    fn from(e: EventLoopError) -> Self {
+        WindowError::EventLoop(e)
+    }
+}
+
 /// Builder-style window handle that wires together engine systems and
 /// user-supplied callbacks before opening the OS window.
 ///
@@ -76,13 +449,13 @@ impl Default for WindowConfig {
 ///
 /// ```text
 /// Window::new(state)
-///     .on_startup(…)         // called once before the loop starts
+///     .on_startup(…)         // called once, as soon as the GPU pipeline is ready
 ///     .on_update(…)          // called every frame  ⚠ suppressed in editor mode
 ///     .on_fixed_update(…)    // called at a fixed timestep  ⚠ suppressed in editor mode
 ///     .on_draw_request(…)    // called on RedrawRequested  ⚠ suppressed in editor mode
 ///     .on_editor_event(…)    // called when editor state changes
 ///     .on_window_close(…)    // called on CloseRequested
-///     .create();             // consumes self, opens the OS window, runs the loop
+///     .create();             // consumes self, runs the loop, opens the OS window on resume
 /// ```
 ///
 /// > **Editor mode:** when [`Scene::enable_editor_mode`](crate::scene::Scene::enable_editor_mode)
@@ -90,11 +463,15 @@ impl Default for WindowConfig {
 /// > are **suppressed** so that game logic does not interfere with the editor.
 /// > Use [`on_editor_event`](Window::on_editor_event) to react to editor state
 /// > changes instead.
-pub struct Window<S: 'static> {
+///
+/// `E` is the type of custom events sent through [`Self::proxy`], delivered
+/// to [`Self::with_event_handler`] as [`Event::UserEvent`]; build with
+/// [`Self::new_with_user_events`] to choose it.
+pub struct Window<S: 'static, E: 'static = ()> {
     pub handle: Option<Arc<winit::window::Window>>,
     state: S,
     pub(crate) config: WindowConfig,
-    event_handler: Option<EventCallback<S>>,
+    event_handler: Option<EventCallback<S, E>>,
     on_window_close_fn: CloseCallback<S>,
     on_update_fn: Option<DrawCallback<S>>,
     on_draw_requested_fn: Option<DrawCallback<S>>,
@@ -102,10 +479,28 @@ pub struct Window<S: 'static> {
     on_fixed_update_fn: Option<DrawCallback<S>>,
     on_editor_state_event_fn: Option<EditorStateEventCallback<S>>,
     camera: Option<Camera>,
+    monitor_selector: Option<MonitorSelector>,
+    on_input_fn: Option<InputCallback<S>>,
+    on_surface_event_fn: Option<SurfaceEventCallback<S>>,
+    on_render_error_fn: Option<RenderErrorCallback<S>>,
+    on_resize_fn: Option<ResizeCallback<S>>,
+    on_hot_reload_fn: Option<HotReloadCallback<S>>,
+    /// Created early by [`Self::proxy`], otherwise by [`Self::create`].
+    event_loop: Option<EventLoop<E>>,
+    /// Set by [`Self::with_android_app`]; required to build the event loop.
+    #[cfg(target_os = "android")]
+    android_app: Option<AndroidApp>,
 }
 impl<S> Window<S> {
     /// Create a new window builder with the given initial application state.
     pub fn new(initial_state: S) -> Self {
+        Self::new_with_user_events(initial_state)
+    }
+}
+impl<S, E> Window<S, E> {
+    /// Create a new window builder whose loop also carries custom events of
+    /// type `E`, e.g. `Window::<_, Loaded>::new_with_user_events(state)`.
+    pub fn new_with_user_events(initial_state: S) -> Self {
         Self {
             state: initial_state,
             handle: None,
@@ -120,7 +515,54 @@ impl<S> Window<S> {
             on_fixed_update_fn: None,
             on_editor_state_event_fn: None,
             camera: None,
+            monitor_selector: None,
+            on_input_fn: None,
+            on_surface_event_fn: None,
+            on_render_error_fn: None,
+            on_resize_fn: None,
+            on_hot_reload_fn: None,
+            event_loop: None,
+            #[cfg(target_os = "android")]
+            android_app: None,
+        }
+    }
+    /// A handle for sending `E` values into the loop from any thread, e.g.
+    /// when an asset finishes loading or a network message arrives.  Each
+    /// one wakes the loop and reaches [`Self::with_event_handler`] as
+    /// [`Event::UserEvent`]; events sent before the scene exists are held
+    /// until it does.  User events are not part of recordings.
+    ///
+    /// Creates the event loop, so call it on the main thread, before
+    /// [`Self::create`].
+    ///
+    /// # Panics
+    /// Panics if winit cannot create the event loop, e.g. without a display
+    /// or when one already exists.
+    ///
+    /// ```no_run
+    /// # use vertra::{event::Event, window::Window};
+    /// enum Loaded { Mesh(Vec<u8>) }
+    ///
+    /// let mut window = Window::<u32, Loaded>::new_with_user_events(0);
+    /// let proxy = window.proxy();
+    /// std::thread::spawn(move || {
+    ///     let bytes = std::fs::read("assets/ship.bin").unwrap_or_default();
+    ///     let _ = proxy.send_event(Loaded::Mesh(bytes));
+    /// });
+    /// window
+    ///     .with_event_handler(|meshes, _scene, event, _| {
+    ///         if let Event::UserEvent(Loaded::Mesh(_bytes)) = event {
+    ///             *meshes += 1;
+    ///         }
+    ///     })
+    ///     .create()?;
+    /// # Ok::<(), vertra::window::WindowError>(())
+    /// ```
+    pub fn proxy(&mut self) -> EventLoopProxy<E> {
+        if self.event_loop.is_none() {
+            self.event_loop = Some(self.new_event_loop().expect("could not create the event loop"));
         }
+        self.event_loop.as_ref().expect("created above").create_proxy()
     }
     /// Set the OS window title.
     pub fn with_title(mut self, title: impl Into<String>) -> Self {
@@ -131,6 +573,51 @@ impl<S> Window<S> {
     pub fn with_dimensions(mut self, width: u32, height: u32) -> Self {
         self.config.width = width;
         self.config.height = height;
+        self.config.logical_size = false;
+        self
+    }
+    /// Set the initial window dimensions in logical pixels: on a display
+    /// with scale factor 2 a `800 × 600` window is `1600 × 1200` physical
+    /// pixels.
+    pub fn with_logical_dimensions(mut self, width: u32, height: u32) -> Self {
+        self.config.width = width;
+        self.config.height = height;
+        self.config.logical_size = true;
+        self
+    }
+    /// Open the window borderless fullscreen on the current monitor.
+    pub fn with_fullscreen(mut self, fullscreen: bool) -> Self {
+        self.config.fullscreen = fullscreen;
+        self
+    }
+    /// Open the window on monitor `index` (see [`list_monitors`]), centred
+    /// unless [`Self::with_position`] is also set.  Unknown indices are
+    /// ignored.
+    pub fn with_monitor(mut self, index: usize) -> Self {
+        self.config.monitor = Some(index);
+        self
+    }
+    /// Choose the monitor when the window opens: `selector` receives every
+    /// connected monitor and returns the index to use, or `None` to keep
+    /// [`Self::with_monitor`] / the OS default.
+    ///
+    /// # Examples
+    /// ```
+    /// # use vertra::window::Window;
+    /// // Prefer the largest display.
+    /// let window = Window::new(()).with_monitor_selector(|monitors| {
+    ///     monitors.iter().max_by_key(|m| m.size[0] * m.size[1]).map(|m| m.index)
+    /// });
+    /// ```
+    pub fn with_monitor_selector<F>(mut self, selector: F) -> Self
+    where F: FnOnce(&[MonitorInfo]) -> Option<usize> + 'static {
+        self.monitor_selector = Some(Box::new(selector));
+        self
+    }
+    /// Place the window's top-left corner at `(x, y)` physical pixels,
+    /// relative to the chosen monitor if there is one.
+    pub fn with_position(mut self, x: i32, y: i32) -> Self {
+        self.config.position = Some([x, y]);
         self
     }
     /// Attach a pre-configured [`Camera`].  The aspect ratio is automatically
@@ -160,6 +647,7 @@ impl<S> Window<S> {
     ///
     /// # Examples
     /// ```
+    /// # use vertra::window::Window;
     /// let window = Window::new(()).with_stats_sample_window(0.5);
     /// ```
     pub fn with_stats_sample_window(mut self, secs: f32) -> Self {
@@ -171,12 +659,91 @@ impl<S> Window<S> {
         self.config.stats_sample_window_secs = secs;
         self
     }
+    /// While the window is minimised or fully covered nothing is rendered.
+    /// By default updates keep running at the fixed-update rate so game
+    /// time advances; pass `true` to pause them as well.  Paused time is
+    /// skipped: the first frame after the window reappears starts from a
+    /// zero delta instead of catching up.
+    pub fn with_pause_when_hidden(mut self, pause: bool) -> Self {
+        self.config.pause_when_hidden = pause;
+        self
+    }
+    /// Raw, unaccelerated mouse motion ([`InputEvent::MouseMotion`]) keeps
+    /// coming when the cursor hits the window edge, which makes it the
+    /// right input for mouse-look.  It reaches [`Self::on_input`] only
+    /// while the cursor is grabbed with
+    /// [`AppCommands::set_cursor_mode`], so moving the mouse over a free
+    /// cursor does not turn the camera; pass `true` to always deliver it.
+    /// The raw event handler always sees the underlying device event.
+    pub fn with_mouse_motion_always(mut self, always: bool) -> Self {
+        self.config.mouse_motion_always = always;
+        self
+    }
+    /// Record every event-loop pass — its `dt` and the input that triggered
+    /// it — and write the recording to `path` when the loop exits.  A failed
+    /// write is returned by [`Self::create`] (or [`App::take_error`]) as
+    /// [`WindowError::SaveRecording`].
+    pub fn with_recording(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.recording = Some(path.into());
+        self
+    }
+    /// Replay a recording made with [`Self::with_recording`] instead of
+    /// reading live input, then either close the window (`exit_when_done`)
+    /// or continue live.  See [`crate::replay`] for what is reproduced.
+    ///
+    /// Replayed key presses reach [`Self::on_input`] and the editor, but not
+    /// the raw [`Self::with_event_handler`]: winit key events cannot be
+    /// constructed outside winit.
+    ///
+    /// [`Self::create`] and [`Self::into_app`] fail with
+    /// [`WindowError::LoadReplay`] if the recording cannot be loaded.
+    pub fn with_replay(mut self, path: impl Into<PathBuf>, exit_when_done: bool) -> Self {
+        self.config.replay = Some(path.into());
+        self.config.exit_after_replay = exit_when_done;
+        self
+    }
+    /// *(Android only)* Run inside the activity passed to `android_main`.
+    /// winit cannot build an event loop on Android without it, so call this
+    /// before [`Self::proxy`], [`Self::create`] or [`Self::into_app`]; see
+    /// [`Self::create`] for the entry point.
+    #[cfg(target_os = "android")]
+    pub fn with_android_app(mut self, app: AndroidApp) -> Self {
+        self.android_app = Some(app);
+        self
+    }
+    /// Set the adapter / backend selection used to create the GPU pipeline.
+    ///
+    /// Environment variables (see [`PipelineConfig`] and
+    /// [`WindowConfig::with_env_overrides`]) still take precedence, so CI can
+    /// force a software adapter without code changes.
+    ///
+    /// # Examples
+    /// ```
+    /// # use vertra::window::Window;
+    /// # use vertra::pipeline::PipelineConfig;
+    /// let window = Window::new(())
+    ///     .with_pipeline_config(PipelineConfig::default().with_force_fallback_adapter(true));
+    /// ```
+    pub fn with_pipeline_config(mut self, config: PipelineConfig) -> Self {
+        self.config.pipeline = config;
+        self
+    }
+    /// Register a callback for keyboard, mouse and focus input.
+    ///
+    /// Unlike [`Self::with_event_handler`] it also receives replayed key
+    /// presses (see [`Self::with_replay`]), so input handled here replays
+    /// faithfully.  Fires in editor mode too.
+    pub fn on_input<F>(mut self, function: F) -> Self
+    where F: FnMut(&mut S, &mut Scene, &InputEvent) + 'static {
+        self.on_input_fn = Some(Box::new(function));
+        self
+    }
     /// Register a raw winit event handler that receives every [`Event`].
     ///
     /// This callback fires even in editor mode and is intended for advanced use
     /// cases.  Prefer [`on_update`](Self::on_update) for normal game logic.
     pub fn with_event_handler<F>(mut self, function: F) -> Self
-    where F: FnMut(&mut S, &mut Scene, Event<()>, &EventLoopWindowTarget<()>) + 'static {
+    where F: FnMut(&mut S, &mut Scene, Event<E>, &ActiveEventLoop) + 'static {
         self.event_handler = Some(Box::new(function));
         self
     }
@@ -230,35 +797,197 @@ impl<S> Window<S> {
         self.on_editor_state_event_fn = Some(Box::new(function));
         self
     }
+    /// Register a callback for presentation surface events: present-mode
+    /// fallbacks, reconfigurations and dropped frames (see [`SurfaceEvent`]).
+    ///
+    /// Delivered after each redraw and on `Resumed`.  Without a callback
+    /// the events are discarded.  Fires in editor mode too.
+    pub fn on_surface_event<F>(mut self, function: F) -> Self
+    where F: FnMut(&mut S, &mut Scene, SurfaceEvent) + 'static {
+        self.on_surface_event_fn = Some(Box::new(function));
+        self
+    }
+    /// Register a callback for dropped frames.
+    ///
+    /// The window reacts before calling it: a lost surface is re-created and
+    /// a timeout delays the next redraw by
+    /// [`RENDER_TIMEOUT_BACKOFF_SECS`](crate::constants::window::RENDER_TIMEOUT_BACKOFF_SECS).
+    /// Running out of memory has no built-in remedy; free resources or exit
+    /// here.  Fires in editor mode too.
+    pub fn on_render_error<F>(mut self, function: F) -> Self
+    where F: FnMut(&mut S, &mut Scene, RenderError) + 'static {
+        self.on_render_error_fn = Some(Box::new(function));
+        self
+    }
+    /// Register a callback for render-target resizes, called with the old
+    /// and new `(width, height)` in physical pixels once the surface, camera
+    /// aspect and overlay camera have been updated.  Use it to rebuild
+    /// size-dependent render targets or re-run overlay layouts.
+    ///
+    /// Minimising (a zero size) keeps the old target and does not fire it.
+    /// Fires in editor mode too.
+    pub fn on_resize<F>(mut self, function: F) -> Self
+    where F: FnMut(&mut S, &mut Scene, (u32, u32), (u32, u32)) + 'static {
+        self.on_resize_fn = Some(Box::new(function));
+        self
+    }
+    /// Register a callback for hot-reloaded scene files, textures and
+    /// shaders (see [`Scene::poll_hot_reload`]), called once per changed
+    /// file with the outcome of its reload.  A failed reload keeps the
+    /// previous version of the asset; without a callback the outcome is
+    /// discarded.  Fires in editor mode too.
+    pub fn on_hot_reload<F>(mut self, function: F) -> Self
+    where F: FnMut(&mut S, &mut Scene, &AssetReload) + 'static {
+        self.on_hot_reload_fn = Some(Box::new(function));
+        self
+    }
     /// Override the default window-close behaviour.
     ///
     /// By default, closing the window exits the event loop.
     pub fn on_window_close<F>(mut self, function: F) -> Self
-    where F: FnMut(&mut S, WindowEvent, &EventLoopWindowTarget<()>) + 'static {
+    where F: FnMut(&mut S, WindowEvent, &ActiveEventLoop) + 'static {
         self.on_window_close_fn = Box::new(function);
         self
     }
-    /// Register a one-shot startup callback, called once before the event loop
-    /// begins.  Use this to spawn objects, load assets, and optionally call
+    /// Register a one-shot startup callback, called once as soon as the GPU
+    /// pipeline is ready after the first `Resumed` event (on WASM the adapter
+    /// is requested asynchronously, so it fires a few events later).  Use
+    /// this to spawn objects, load assets, and optionally call
     /// [`Scene::enable_editor_mode`](crate::scene::Scene::enable_editor_mode).
     pub fn on_startup<F>(mut self, function: F) -> Self
     where F: FnMut(&mut S, &mut Scene, &mut FrameContext) + 'static {
         self.on_startup_fn = Some(Box::new(function));
         self
     }
-    /// Consume the builder, open the OS window, and start the event loop.
+    /// Consume the builder and run the event loop as a winit
+    /// [`ApplicationHandler`].
     ///
-    /// Does not return on native targets (blocks until the window is closed).
+    /// Does not return on native targets until the window is closed.
     /// Returns immediately on WASM (the loop is spawned asynchronously).
-    pub fn create(mut self) {
-        let event_loop = EventLoop::new().unwrap();
-        #[allow(unused_mut)]
-        let mut builder = WindowBuilder::new()
-            .with_inner_size(PhysicalSize::new(self.config.width, self.config.height))
+    ///
+    /// The OS window and surface follow winit's lifecycle on every platform:
+    /// the window is opened on the first `Resumed` event, the surface is
+    /// created then and dropped on `Suspended` (nothing is rendered while
+    /// suspended), so the same code runs on desktop, mobile and the web.
+    /// Rendering also stops while the window is minimised or occluded; see
+    /// [`Self::with_pause_when_hidden`].
+    ///
+    /// `VERTRA_*` environment variables override the builder settings; see
+    /// [`WindowConfig::with_env_overrides`].
+    ///
+    /// # Errors
+    /// Fails when the event loop cannot be created or run, the replay cannot
+    /// be loaded, or — once the loop has exited — the OS window or its GPU
+    /// pipeline could not be created or the recording could not be saved
+    /// (see [`WindowError`]).
+    ///
+    /// # Panics
+    /// On WASM the pipeline is created after `create` has returned, so a
+    /// missing adapter panics instead; so does a
+    /// [`Self::with_canvas_id`] naming no `<canvas>` element.
+    ///
+    /// # Mobile
+    ///
+    /// On iOS call `create` from `main` as on desktop; it never returns, the
+    /// app stays in UIKit's run loop until the OS terminates it.
+    ///
+    /// Android apps are built as a `cdylib` whose entry point is
+    /// `android_main`, which receives the activity that the event loop must
+    /// be built with.  Enable one of winit's `android-native-activity` /
+    /// `android-game-activity` features in the app's manifest to pick the
+    /// activity glue:
+    ///
+    /// ```ignore
+    /// use vertra::event::AndroidApp;
+    /// use vertra::window::Window;
+    ///
+    /// #[unsafe(no_mangle)]
+    /// fn android_main(app: AndroidApp) {
+    ///     Window::new(()).with_android_app(app).create().expect("could not start vertra");
+    /// }
+    /// ```
+    pub fn create(mut self) -> Result<(), WindowError> {
+        self.config = std::mem::take(&mut self.config).with_env_overrides();
+        let event_loop = match self.event_loop.take() {
+            Some(event_loop) => event_loop,
+            None => self.new_event_loop()?,
+        };
+        let runner = Runner::new(self)?;
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let mut runner = runner;
+            event_loop.run_app(&mut runner)?;
+            runner.error.map_or(Ok(()), Err)
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            use winit::platform::web::EventLoopExtWebSys;
+            event_loop.spawn_app(runner);
+            Ok(())
+        }
+    }
+    /// Consume the builder into an [`App`] whose loop the caller drives, for
+    /// embedding vertra in a tool with its own main loop.  Nothing happens
+    /// until the first [`App::pump_events`] or [`App::render_frame`].
+    ///
+    /// Fails when winit cannot create the event loop, e.g. without a
+    /// display or when one already exists, or when the replay cannot be
+    /// loaded; window and pipeline failures end the app later, see
+    /// [`App::take_error`].  Not available on the web or iOS, whose event
+    /// loops cannot be pumped.
+    #[cfg(pump_events)]
+    pub fn into_app(mut self) -> Result<App<S, E>, WindowError> {
+        self.config = std::mem::take(&mut self.config).with_env_overrides();
+        let event_loop = match self.event_loop.take() {
+            Some(event_loop) => event_loop,
+            None => self.new_event_loop()?,
+        };
+        let mut runner = Runner::new(self)?;
+        runner.external = true;
+        Ok(App { event_loop, runner })
+    }
+    /// The event loop behind [`Self::create`], [`Self::proxy`] and
+    /// [`Self::into_app`], attached to the Android activity if one was set.
+    fn new_event_loop(&self) -> Result<EventLoop<E>, EventLoopError> {
+        #[cfg(target_os = "android")]
+        if let Some(app) = &self.android_app {
+            use winit::platform::android::EventLoopBuilderExtAndroid;
+            return EventLoop::with_user_event().with_android_app(app.clone()).build();
+        }
+        EventLoop::with_user_event().build()
+    }
+    /// Attributes of the OS window, on the monitor chosen by
+    /// [`Self::with_monitor_selector`] / [`Self::with_monitor`].
+    fn window_attributes(&mut self, event_loop: &ActiveEventLoop) -> WindowAttributes {
+        if let Some(selector) = self.monitor_selector.take()
+            && let Some(index) = selector(&list_monitors(event_loop)) {
+            self.config.monitor = Some(index);
+        }
+        let monitor = self.config.monitor.and_then(|i| event_loop.available_monitors().nth(i));
+        let inner_size: Size = if self.config.logical_size {
+            LogicalSize::new(self.config.width, self.config.height).into()
+        } else {
+            PhysicalSize::new(self.config.width, self.config.height).into()
+        };
+        let mut attributes = winit::window::Window::default_attributes()
+            .with_inner_size(inner_size)
             .with_min_inner_size(PhysicalSize::new(
                 self.config.minimum_dimension[0], self.config.minimum_dimension[1]
             ))
-            .with_title(self.config.title.clone());
+            .with_title(self.config.title.clone())
+            .with_fullscreen(self.config.fullscreen.then(|| Fullscreen::Borderless(monitor.clone())));
+        let position = match &monitor {
+            Some(handle) => {
+                let info = MonitorInfo::from_handle(0, handle, None);
+                let scale = if self.config.logical_size { info.scale_factor } else { 1.0 };
+                let size = [self.config.width, self.config.height].map(|v| (v as f64 * scale) as u32);
+                Some(info.window_position(size, self.config.position))
+            }
+            None => self.config.position,
+        };
+        if let Some([x, y]) = position {
+            attributes = attributes.with_position(PhysicalPosition::new(x, y));
+        }
         #[cfg(target_arch = "wasm32")]
         {
             if let Some(id) = &self.config.canvas_id {
@@ -267,199 +996,652 @@ impl<S> Window<S> {
                     .and_then(|doc| doc.get_element_by_id(id))
                     .and_then(|ent| ent.dyn_into::<web_sys::HtmlCanvasElement>().ok())
                     .expect("Could not find canvas with the provided ID");
-                builder = builder.with_canvas(Some(canvas));
+                attributes = attributes.with_canvas(Some(canvas));
             }
         }
-        let winit_window = builder.build(&event_loop).unwrap();
-        let window_handle = Arc::new(winit_window);
-        self.handle = Some(Arc::clone(&window_handle));
-        #[cfg(target_arch = "wasm32")]
-        {
-            let window_handle_clone = Arc::clone(&window_handle);
-            wasm_bindgen_futures::spawn_local(async move {
-                let pipeline = Pipeline::initialize(Arc::clone(&window_handle_clone)).await;
-                self.run_loop(event_loop, pipeline, window_handle_clone);
-            });
+        attributes
+    }
+    /// Build the [`Scene`] around a freshly initialised `pipeline` and fire
+    /// `on_startup`.
+    fn start_scene(&mut self, pipeline: Pipeline, lp: &mut LoopState, elwt: &ActiveEventLoop) -> Box<Scene> {
+        // Environment overrides may have changed the size since `with_camera`.
+        let camera = self.camera.take().unwrap_or_default()
+            .with_aspect(self.config.width as f32 / self.config.height as f32);
+        // Box the scene so its heap address is stable from this point forward.
+        // on_startup fires before the first frame; without Boxing the scene
+        // would live on the stack here and later be moved into the running
+        // `Runner`.  Any raw pointer derived from `&mut scene` during
+        // on_startup would therefore dangle after the first move.  With
+        // Box::new the contents never move, only the thin pointer does, so the
+        // address stays valid for the entire lifetime of the engine.
+        let mut scene = Box::new(Scene::new(pipeline, camera));
+        if let Some(handle) = &self.handle {
+            scene.set_scale_factor(handle.scale_factor() as f32);
         }
-        #[cfg(not(target_arch = "wasm32"))]
-        {
-            let pipeline = pollster::block_on(Pipeline::initialize(Arc::clone(&window_handle)));
-            self.run_loop(event_loop, pipeline, window_handle);
+        if let Some(startup_fn) = &mut self.on_startup_fn {
+            let mut ctx = make_frame_context(0.0, lp, &scene);
+            startup_fn(&mut self.state, &mut scene, &mut ctx);
+            ctx.commands.apply(lp, elwt);
         }
+        scene
     }
-    fn run_loop(
-        mut self,
-        event_loop: EventLoop<()>,
-        pipeline: Pipeline,
-        window_handle: Arc<winit::window::Window>,
+    /// Run one pass of the loop: updates, editor, callbacks, then whatever
+    /// `event` calls for.  `event` is `None` for replayed steps winit cannot
+    /// represent; `input` is the [`InputEvent`] it carries, if any.
+    fn step(
+        &mut self,
+        lp: &mut LoopState,
+        scene: &mut Scene,
+        event: Option<Event<()>>,
+        input: Option<InputEvent>,
+        dt: f32,
+        elwt: &ActiveEventLoop,
     ) {
-        fn make_frame_context(dt: f32, stats: &FrameStats) -> FrameContext {
-            FrameContext {
-                dt,
-                fps: stats.fps,
-                frame_time_ms: stats.frame_time_ms,
-                draw_calls: stats.draw_calls,
-                triangle_count: stats.triangle_count,
+        lp.elapsed += dt as f64;
+        if let Some(InputEvent::CursorMoved { x, y }) = input {
+            scene.set_cursor_position(Some([x as f32, y as f32]));
+        }
+        let paused = lp.hidden && self.config.pause_when_hidden;
+        if scene.editor.is_none() && !paused {
+            profile_scope!("update");
+            scene.run_scripts(dt);
+            if let Some(f) = &mut self.on_update_fn {
+                let mut ctx = make_frame_context(dt, lp, scene);
+                f(&mut self.state, scene, &mut ctx);
+                ctx.commands.apply(lp, elwt);
             }
         }
+        scene.update_transition(dt);
+        scene.update_time(dt);
+        scene.update_water(dt);
+        #[cfg(all(feature = "audio", not(target_arch = "wasm32")))]
+        scene.update_audio();
 
-        let mesh_registry = MeshRegistry::new();
-        let mut last_update_inst = web_time::Instant::now();
-        let mut frame_stats = FrameStats::new()
-            .with_sample_window(self.config.stats_sample_window_secs);
-        let camera = self.camera.unwrap_or_else(|| {
-            Camera::new().with_aspect(self.config.width as f32 / self.config.height as f32)
-        });
-        // Box the scene so its heap address is stable from this point forward.
-        // on_startup fires before the event loop starts; without Boxing the scene
-        // lives on the Rust stack here and is later moved into the main_loop
-        // closure (and again when the closure is boxed for spawn_local on WASM).
-        // Any raw pointer derived from `&mut scene` during on_startup would
-        // therefore dangle after the first move.  With Box::new the contents
-        // never move, only the thin pointer does, so the address stays valid
-        // for the entire lifetime of the engine.
-        let mut scene = Box::new(Scene {
-            pipeline,
-            mesh_registry,
-            camera,
-            world: World::new(),
-            editor: None,
-            textures: std::collections::HashMap::new(),
-            snapshot: None,
-            script_registry: crate::script::ScriptRegistry::new(),
-        });
-        if let Some(startup_fn) = &mut self.on_startup_fn {
-            startup_fn(&mut self.state, &mut *scene, &mut make_frame_context(0.0, &frame_stats));
-        }
-        let mut accumulator = 0.0_f32;
-        let main_loop = move |event: Event<()>, elwt: &EventLoopWindowTarget<()>| {
-            let now = web_time::Instant::now();
-            let dt  = now.duration_since(last_update_inst).as_secs_f32();
-            last_update_inst = now;
-
-            if scene.editor.is_none() {
-                scene.run_scripts(dt);
-                if let Some(f) = &mut self.on_update_fn {
-                    f(&mut self.state, &mut *scene, &mut make_frame_context(dt, &frame_stats));
+        let axes_clicked = matches!(input, Some(InputEvent::MouseButton { button: MouseButton::Left, pressed: true }))
+            && scene.click_view_axes();
+        // winit key events cannot be rebuilt from a recording, so replayed
+        // keys reach the editor directly instead of through `event`.
+        let replayed_key = match (&event, &input) {
+            (None, Some(InputEvent::Key { code, pressed, .. })) => Some((*code, *pressed)),
+            _ => None,
+        };
+        if scene.editor.is_some() && (event.is_some() || replayed_key.is_some()) {
+            scene.update_editor(dt);
+
+            let prev_gizmo_mode   = scene.editor.as_ref().map(|ed| ed.gizmo_mode);
+            let prev_drag_active  = scene.editor.as_ref().is_some_and(|ed| ed.drag.is_some());
+            let prev_drag_obj_id  = scene.editor.as_ref()
+                .and_then(|ed| ed.drag.as_ref().map(|d| d.object_id));
+            let prev_selection_id = scene.editor.as_ref()
+                .and_then(|ed| ed.inspector.selected.as_ref().map(|s| s.id));
+
+            if !axes_clicked {
+                match (&event, replayed_key) {
+                    (Some(event), _)              => dispatch_editor_event(scene, event),
+                    (None, Some((code, pressed))) => dispatch_editor_key(scene, code, pressed),
+                    (None, None)                  => {}
                 }
             }
 
-            if scene.editor.is_some() {
-                scene.update_editor(dt);
-
-                let prev_gizmo_mode   = scene.editor.as_ref().map(|ed| ed.gizmo_mode);
-                let prev_drag_active  = scene.editor.as_ref().map_or(false, |ed| ed.drag.is_some());
-                let prev_drag_obj_id  = scene.editor.as_ref()
-                    .and_then(|ed| ed.drag.as_ref().map(|d| d.object_id));
-                let prev_selection_id = scene.editor.as_ref()
-                    .and_then(|ed| ed.inspector.selected.as_ref().map(|s| s.id));
-
-                dispatch_editor_event(&mut *scene, &event);
-
-                if self.on_editor_state_event_fn.is_some() {
-                    let mut to_fire: Vec<(EditorStateEvent, Option<Object>)> = Vec::new();
-                    if let Some(ed) = &scene.editor {
-                        if let Some(prev) = prev_gizmo_mode {
-                            if prev != ed.gizmo_mode {
-                                let mode = ed.gizmo_mode;
-                                let obj  = ed.inspector.selected.as_ref()
-                                    .and_then(|s| scene.world.objects.get(&s.id).cloned());
-                                to_fire.push((EditorStateEvent::GizmoModeChanged(mode), obj));
-                            }
-                        }
+            if self.on_editor_state_event_fn.is_some() {
+                let mut to_fire: Vec<(EditorStateEvent, Option<Object>)> = Vec::new();
+                if let Some(ed) = &scene.editor {
+                    if let Some(prev) = prev_gizmo_mode && prev != ed.gizmo_mode {
+                        let mode = ed.gizmo_mode;
+                        let obj  = ed.inspector.selected.as_ref()
+                            .and_then(|s| scene.world.objects.get(&s.id).cloned());
+                        to_fire.push((EditorStateEvent::GizmoModeChanged(mode), obj));
+                    }
 
-                        if !prev_drag_active {
-                            if let Some(drag) = &ed.drag {
-                                let axis = drag.axis;
-                                let obj  = scene.world.objects.get(&drag.object_id).cloned();
-                                to_fire.push((EditorStateEvent::DragStart { axis }, obj));
-                            }
-                        }
+                    if !prev_drag_active && let Some(drag) = &ed.drag {
+                        let axis = drag.axis;
+                        let obj  = scene.world.objects.get(&drag.object_id).cloned();
+                        to_fire.push((EditorStateEvent::DragStart { axis }, obj));
+                    }
 
-                        if prev_drag_active && ed.drag.is_none() {
-                            let obj = prev_drag_obj_id
-                                .and_then(|id| scene.world.objects.get(&id).cloned());
-                            to_fire.push((EditorStateEvent::DragEnd, obj));
-                        }
+                    if prev_drag_active && ed.drag.is_none() {
+                        let obj = prev_drag_obj_id
+                            .and_then(|id| scene.world.objects.get(&id).cloned());
+                        to_fire.push((EditorStateEvent::DragEnd, obj));
+                    }
+
+                    let new_selection_id = ed.inspector.selected.as_ref().map(|s| s.id);
+                    if prev_selection_id != new_selection_id {
+                        let obj = new_selection_id
+                            .and_then(|id| scene.world.objects.get(&id).cloned());
+                        to_fire.push((EditorStateEvent::SelectionChanged, obj));
+                    }
+                }
+                for (ev, obj) in to_fire {
+                    if let Some(f) = &mut self.on_editor_state_event_fn {
+                        f(&mut self.state, scene, ev, obj);
+                    }
+                }
+            }
+        }
+
+        if let Some(input) = &input && let Some(f) = &mut self.on_input_fn {
+            f(&mut self.state, scene, input);
+        }
+
+        let Some(event) = event else { return; };
+        if let Some(f) = &mut self.event_handler {
+            let event = event.clone().map_nonuser_event().expect("loop events carry no user payload");
+            f(&mut self.state, scene, event, elwt);
+        }
 
-                        let new_selection_id = ed.inspector.selected.as_ref().map(|s| s.id);
-                        if prev_selection_id != new_selection_id {
-                            let obj = new_selection_id
-                                .and_then(|id| scene.world.objects.get(&id).cloned());
-                            to_fire.push((EditorStateEvent::SelectionChanged, obj));
+        match event {
+            Event::AboutToWait => {
+                for reload in scene.poll_hot_reload() {
+                    if let Some(f) = &mut self.on_hot_reload_fn {
+                        f(&mut self.state, scene, &reload);
+                    }
+                }
+                lp.hidden = lp.occluded || lp.zero_size || lp.window_handle.is_minimized() == Some(true);
+                if !paused {
+                    lp.accumulator += dt;
+                }
+                while lp.accumulator >= window::FIXED_DELTA {
+                    if scene.editor.is_none() {
+                        profile_scope!("fixed_update");
+                        scene.run_fixed_update_scripts(window::FIXED_DELTA);
+                        if let Some(f) = &mut self.on_fixed_update_fn {
+                            let mut ctx = make_frame_context(window::FIXED_DELTA, lp, scene);
+                            f(&mut self.state, scene, &mut ctx);
+                            ctx.commands.apply(lp, elwt);
                         }
                     }
-                    for (ev, obj) in to_fire {
-                        if let Some(f) = &mut self.on_editor_state_event_fn {
-                            f(&mut self.state, &mut *scene, ev, obj);
+                    lp.accumulator -= window::FIXED_DELTA;
+                }
+                // Back off after a timed-out frame.
+                let backoff = lp.redraw_after.filter(|&until| web_time::Instant::now() < until);
+                lp.redraw_after = backoff;
+                // No surface while suspended, so skip the redraw entirely.
+                if scene.pipeline.surface.is_some() && !lp.hidden && backoff.is_none() && !lp.external {
+                    lp.window_handle.request_redraw();
+                }
+                // Redraws drive the loop; without them, wake up for the
+                // next fixed update unless updates are paused as well.
+                elwt.set_control_flow(if lp.hidden && !self.config.pause_when_hidden {
+                    ControlFlow::wait_duration(std::time::Duration::from_secs_f32(window::FIXED_DELTA))
+                } else if let Some(until) = backoff {
+                    ControlFlow::wait_duration(until.saturating_duration_since(web_time::Instant::now()))
+                } else {
+                    ControlFlow::Wait
+                });
+            }
+            Event::WindowEvent { event: WindowEvent::RedrawRequested, .. } => {
+                if scene.editor.is_none() && let Some(f) = &mut self.on_draw_requested_fn {
+                    profile_scope!("on_draw_request");
+                    let mut ctx = make_frame_context(dt, lp, scene);
+                    f(&mut self.state, scene, &mut ctx);
+                    ctx.commands.apply(lp, elwt);
+                }
+                let result = scene.draw_world();
+                match result {
+                    Ok(render_stats) => lp.frame_stats.set_render_stats(render_stats),
+                    Err(error) => {
+                        match error {
+                            RenderError::SurfaceLost => {
+                                scene.pipeline.suspend();
+                                scene.pipeline.resume(Arc::clone(&lp.window_handle));
+                            }
+                            RenderError::Timeout => {
+                                let backoff = std::time::Duration::from_secs_f32(window::RENDER_TIMEOUT_BACKOFF_SECS);
+                                lp.redraw_after = Some(web_time::Instant::now() + backoff);
+                            }
+                            RenderError::OutOfMemory => {}
+                        }
+                        if let Some(f) = &mut self.on_render_error_fn {
+                            f(&mut self.state, scene, error);
                         }
                     }
                 }
+                // After the error handling, which may re-create the surface.
+                self.deliver_surface_events(scene);
+                lp.frame_stats.tick(dt);
+                lp.frame += 1;
+                profile_finish_frame!();
+            }
+            // Nothing is drawn while a re-created surface is unusable, so
+            // report it now rather than after the next frame.
+            Event::Resumed => self.deliver_surface_events(scene),
+            Event::WindowEvent { event: window_event, .. } => {
+                self.window_event(lp, scene, window_event, elwt);
+            }
+            _ => {}
+        }
+    }
+    /// Hand queued [`SurfaceEvent`]s to [`Self::on_surface_event`].
+    fn deliver_surface_events(&mut self, scene: &mut Scene) {
+        for event in scene.pipeline.drain_surface_events() {
+            if let Some(f) = &mut self.on_surface_event_fn {
+                f(&mut self.state, scene, event);
+            }
+        }
+    }
+    /// Window-management events, handled live even during a replay.
+    fn window_event(
+        &mut self,
+        lp: &mut LoopState,
+        scene: &mut Scene,
+        window_event: WindowEvent,
+        elwt: &ActiveEventLoop,
+    ) {
+        match window_event {
+            WindowEvent::CloseRequested => {
+                (self.on_window_close_fn)(&mut self.state, window_event, elwt);
+            }
+            // Also fired for canvas (CSS) size changes on the web.
+            WindowEvent::Resized(new_size) => {
+                lp.zero_size = new_size.width == 0 || new_size.height == 0;
+                let target_size = |scene: &Scene| {
+                    (scene.pipeline.surface_config.width, scene.pipeline.surface_config.height)
+                };
+                let old = target_size(scene);
+                scene.resize(new_size.width, new_size.height);
+                let new = target_size(scene);
+                if new != old && let Some(f) = &mut self.on_resize_fn {
+                    f(&mut self.state, scene, old, new);
+                }
+            }
+            WindowEvent::CursorLeft { .. } => {
+                scene.set_cursor_position(None);
+            }
+            WindowEvent::Occluded(is_occluded) => {
+                lp.occluded = is_occluded;
+            }
+            // A `Resized` with the new physical size follows.
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                scene.set_scale_factor(scale_factor as f32);
+            }
+            _ => {}
+        }
+    }
+}
+/// A [`Window`] whose event loop is driven by the caller, built with
+/// [`Window::into_app`].
+///
+/// The OS window opens on the first call.  Frames are drawn only by
+/// [`Self::render_frame`] (and when the OS asks for one, e.g. after an
+/// expose); updates and fixed updates run on every pass as usual.
+///
+/// ```no_run
+/// # use vertra::window::Window;
+/// let mut app = Window::new(0u32)
+///     .on_update(|frames, _, _| *frames += 1)
+///     .into_app()
+///     .expect("event loop");
+/// while app.render_frame() {
+///     // … the host's own per-frame work …
+/// }
+/// if let Some(e) = app.take_error() {
+///     eprintln!("{e}");
+/// }
+/// ```
+#[cfg(pump_events)]
+pub struct App<S: 'static, E: 'static = ()> {
+    event_loop: EventLoop<E>,
+    runner: Runner<S, E>,
+}
+
+#[cfg(pump_events)]
+impl<S, E> App<S, E> {
+    /// Dispatch pending window and device events, waiting up to `timeout`
+    /// for one to arrive (`None` waits indefinitely, `Some(ZERO)` returns
+    /// at once).  Returns `false` once the app has exited, e.g. after the
+    /// window was closed; stop calling it then.
+    pub fn pump_events(&mut self, timeout: Option<std::time::Duration>) -> bool {
+        use winit::platform::pump_events::{EventLoopExtPumpEvents, PumpStatus};
+        matches!(self.event_loop.pump_app_events(timeout, &mut self.runner), PumpStatus::Continue)
+    }
+
+    /// Dispatch pending events without waiting, then update and draw one
+    /// frame (skipped while the window is minimised or occluded).  Returns
+    /// `false` once the app has exited.
+    pub fn render_frame(&mut self) -> bool {
+        self.runner.frame_requested = true;
+        self.pump_events(Some(std::time::Duration::ZERO))
+    }
+
+    /// The scene, once the GPU pipeline exists (after the first pump).
+    pub fn scene(&mut self) -> Option<&mut Scene> {
+        self.runner.scene.as_deref_mut()
+    }
+
+    /// The application state passed to every callback.
+    pub fn state(&mut self) -> &mut S {
+        &mut self.runner.window.state
+    }
+
+    /// The OS window, once opened.
+    pub fn window_handle(&self) -> Option<&Arc<winit::window::Window>> {
+        self.runner.window.handle.as_ref()
+    }
+
+    /// Why the app failed, e.g. because the OS window or its GPU pipeline
+    /// could not be created, or the recording could not be saved on exit;
+    /// check it once [`Self::pump_events`] returns `false`.  `None` after a
+    /// normal exit.
+    pub fn take_error(&mut self) -> Option<WindowError> {
+        self.runner.error.take()
+    }
+}
+
+/// A [`Window`] running inside winit's event loop.
+///
+/// Every [`ApplicationHandler`] callback is turned back into the [`Event`]
+/// it stands for and passed through [`Window::step`], so user event
+/// handlers, recordings and replays see one event stream on every platform.
+struct Runner<S: 'static, E: 'static> {
+    window: Window<S, E>,
+    scene: Option<Box<Scene>>,
+    /// Created with the OS window on the first `Resumed`.
+    lp: Option<LoopState>,
+    last_update_inst: web_time::Instant,
+    recording: Option<Recording>,
+    player: Option<Player>,
+    /// Driven by [`App`]: frames are drawn on request, not continuously.
+    external: bool,
+    /// Draw a frame at the end of the current pass; see [`App::render_frame`].
+    frame_requested: bool,
+    /// User events waiting for the scene to exist.
+    user_events: Vec<E>,
+    /// A setup failure that ended the loop (see [`Self::fail`]), or a
+    /// recording that could not be saved on exit.
+    error: Option<WindowError>,
+    /// Filled by the asynchronous adapter request started on `Resumed`.
+    #[cfg(target_arch = "wasm32")]
+    pending_pipeline: std::rc::Rc<std::cell::RefCell<Option<Pipeline>>>,
+}
+
+impl<S, E> Runner<S, E> {
+    fn new(window: Window<S, E>) -> Result<Self, WindowError> {
+        let recording = window.config.recording.is_some().then(Recording::new);
+        let player = match &window.config.replay {
+            Some(path) => {
+                let recording = Recording::load(path)
+                    .map_err(|error| WindowError::LoadReplay { path: path.clone(), error })?;
+                Some(Player::new(recording))
             }
+            None => None,
+        };
+        Ok(Self {
+            window,
+            scene: None,
+            lp: None,
+            last_update_inst: web_time::Instant::now(),
+            recording,
+            player,
+            external: false,
+            frame_requested: false,
+            user_events: Vec::new(),
+            error: None,
+            #[cfg(target_arch = "wasm32")]
+            pending_pipeline: Default::default(),
+        })
+    }
+
+    /// Keep `error` for [`Window::create`] / [`App::take_error`] and stop
+    /// the loop.
+    fn fail(&mut self, error: WindowError, event_loop: &ActiveEventLoop) {
+        self.error = Some(error);
+        event_loop.exit();
+    }
 
-            if let Some(f) = &mut self.event_handler {
-                f(&mut self.state, &mut *scene, event.clone(), elwt);
+    /// Open the OS window and start creating the pipeline for it.
+    fn open_window(&mut self, event_loop: &ActiveEventLoop) -> Result<Arc<winit::window::Window>, WindowError> {
+        let attributes = self.window.window_attributes(event_loop);
+        let window_handle = Arc::new(event_loop.create_window(attributes).map_err(WindowError::CreateWindow)?);
+        self.window.handle = Some(Arc::clone(&window_handle));
+        let frame_stats = FrameStats::new()
+            .with_sample_window(self.window.config.stats_sample_window_secs);
+        self.lp = Some(LoopState {
+            window_handle: Arc::clone(&window_handle),
+            frame_stats,
+            accumulator: 0.0,
+            occluded: false,
+            zero_size: false,
+            hidden: false,
+            redraw_after: None,
+            elapsed: 0.0,
+            frame: 0,
+            cursor_mode: CursorMode::Normal,
+            external: self.external,
+        });
+        // The adapter request cannot block the browser's event loop; the
+        // scene starts on the first event after it resolves.
+        #[cfg(target_arch = "wasm32")]
+        {
+            let pending = std::rc::Rc::clone(&self.pending_pipeline);
+            let handle = Arc::clone(&window_handle);
+            let pipeline_config = self.window.config.pipeline.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let pipeline = Pipeline::initialize_with_config(Arc::clone(&handle), &pipeline_config).await
+                    .unwrap_or_else(|e| panic!("{e}"));
+                *pending.borrow_mut() = Some(pipeline);
+                handle.request_redraw();
+            });
+        }
+        Ok(window_handle)
+    }
+
+    /// Hand queued user events to the event handler once the scene exists.
+    fn deliver_user_events(&mut self, elwt: &ActiveEventLoop) {
+        let Some(scene) = self.scene.as_deref_mut() else { return; };
+        for event in self.user_events.drain(..) {
+            if let Some(f) = &mut self.window.event_handler {
+                f(&mut self.window.state, scene, Event::UserEvent(event), elwt);
             }
+        }
+    }
 
+    /// Run one pass of the loop for `event`, recording or replaying it.
+    fn handle(&mut self, event: Event<()>, elwt: &ActiveEventLoop) {
+        self.deliver_user_events(elwt);
+        let Some(lp) = &mut self.lp else { return; };
+        #[cfg(target_arch = "wasm32")]
+        if self.scene.is_none() && let Some(pipeline) = self.pending_pipeline.borrow_mut().take() {
+            self.scene = Some(self.window.start_scene(pipeline, lp, elwt));
+            self.last_update_inst = web_time::Instant::now();
+        }
+        // Nothing to update or draw until the pipeline exists.
+        let Some(scene) = self.scene.as_deref_mut() else { return; };
+
+        let now = web_time::Instant::now();
+        // Time spent hidden with updates paused is skipped rather than
+        // caught up on once the window is shown again.
+        let dt = if lp.hidden && self.window.config.pause_when_hidden {
+            0.0
+        } else {
+            now.duration_since(self.last_update_inst).as_secs_f32().min(window::MAX_FRAME_DELTA)
+        };
+        self.last_update_inst = now;
+
+        if let Some(replay) = &mut self.player {
+            // Live input and timing are ignored; each live frame plays
+            // back one recorded frame instead.
             match event {
                 Event::AboutToWait => {
-                    accumulator += dt;
-                    while accumulator >= window::FIXED_DELTA {
-                        if scene.editor.is_none() {
-                            scene.run_fixed_update_scripts(window::FIXED_DELTA);
-                            if let Some(f) = &mut self.on_fixed_update_fn {
-                                f(
-                                    &mut self.state,
-                                    &mut *scene,
-                                    &mut make_frame_context(window::FIXED_DELTA, &frame_stats),
-                                );
-                            }
+                    let window_id = lp.window_handle.id();
+                    for step in replay.next_frame() {
+                        let (event, input) = match step.kind {
+                            StepKind::Input(input) => (input.to_event(window_id), Some(input)),
+                            StepKind::Redraw => (Some(Event::WindowEvent {
+                                window_id,
+                                event: WindowEvent::RedrawRequested,
+                            }), None),
+                            StepKind::FrameEnd => (Some(Event::AboutToWait), None),
+                            StepKind::Other => (None, None),
+                        };
+                        self.window.step(lp, scene, event, input, step.dt, elwt);
+                    }
+                    if replay.is_finished() {
+                        if self.window.config.exit_after_replay {
+                            elwt.exit();
                         }
-                        accumulator -= window::FIXED_DELTA;
+                        self.player = None;
                     }
-                    window_handle.request_redraw();
                 }
                 Event::WindowEvent { event: window_event, .. } => {
-                    match window_event {
-                        WindowEvent::CloseRequested => {
-                            (self.on_window_close_fn)(&mut self.state, window_event, elwt);
-                        }
-                        WindowEvent::RedrawRequested => {
-                            if scene.editor.is_none() {
-                                if let Some(f) = &mut self.on_draw_requested_fn {
-                                    f(&mut self.state, &mut *scene, &mut make_frame_context(dt, &frame_stats));
-                                }
-                            }
-                            let render_stats = scene.draw_world();
-                            frame_stats.set_gpu_stats(render_stats.draw_calls, render_stats.triangle_count);
-                            frame_stats.tick(dt);
-                        }
-                        WindowEvent::Resized(new_size) => {
-                            scene.pipeline.resize(new_size);
-                            scene.camera.aspect = new_size.width as f32 / new_size.height as f32;
-                            if let Some(ed) = &mut scene.editor {
-                                ed.set_viewport_size(new_size.width as f32, new_size.height as f32);
-                            }
-                        }
-                        _ => {}
-                    }
+                    self.window.window_event(lp, scene, window_event, elwt);
                 }
                 _ => {}
             }
+            return;
+        }
+
+        let mut input = InputEvent::from_event(&event);
+        let free_motion = matches!(input, Some(InputEvent::MouseMotion { .. }))
+            && !lp.cursor_mode.is_grabbed()
+            && !self.window.config.mouse_motion_always;
+        if free_motion {
+            input = None;
+        }
+        if let Some(recording) = &mut self.recording {
+            recording.push(dt, if free_motion { StepKind::Other } else { StepKind::from_event(&event) });
+            if matches!(event, Event::LoopExiting)
+                && let Some(path) = &self.window.config.recording
+                && let Err(error) = recording.save(path) {
+                self.error.get_or_insert(WindowError::SaveRecording { path: path.clone(), error });
+            }
+        }
+        let text = InputEvent::text_from_event(&event);
+        self.window.step(lp, scene, Some(event), input, dt, elwt);
+        // Typed text gets a pass of its own, so replays match.
+        if let Some(text) = text {
+            if let Some(recording) = &mut self.recording {
+                recording.push(0.0, StepKind::Input(text.clone()));
+            }
+            self.window.step(lp, scene, None, Some(text), 0.0, elwt);
+        }
+    }
+}
+
+impl<S, E: 'static> ApplicationHandler<E> for Runner<S, E> {
+    fn new_events(&mut self, event_loop: &ActiveEventLoop, cause: StartCause) {
+        self.handle(Event::NewEvents(cause), event_loop);
+    }
+
+    fn user_event(&mut self, event_loop: &ActiveEventLoop, event: E) {
+        self.user_events.push(event);
+        self.deliver_user_events(event_loop);
+    }
+
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        if self.error.is_some() {
+            return;
+        }
+        let window_handle = match &self.lp {
+            Some(lp) => Arc::clone(&lp.window_handle),
+            None => match self.open_window(event_loop) {
+                Ok(window_handle) => window_handle,
+                Err(e) => return self.fail(e, event_loop),
+            },
         };
-        #[cfg(not(target_arch = "wasm32"))]
-        event_loop.run(main_loop).unwrap();
-        #[cfg(target_arch = "wasm32")]
-        {
-            use winit::platform::web::EventLoopExtWebSys;
-            event_loop.spawn(main_loop);
+        match &mut self.scene {
+            Some(scene) => scene.pipeline.resume(window_handle),
+            // On Android / iOS the native window, and therefore any surface,
+            // only exists from here on.
+            #[cfg(not(target_arch = "wasm32"))]
+            None => {
+                let pipeline = match pollster::block_on(Pipeline::initialize_with_config(
+                    window_handle,
+                    &self.window.config.pipeline,
+                )) {
+                    Ok(pipeline) => pipeline,
+                    Err(e) => return self.fail(WindowError::Pipeline(e), event_loop),
+                };
+                let lp = self.lp.as_mut().expect("window opened above");
+                self.scene = Some(self.window.start_scene(pipeline, lp, event_loop));
+            }
+            #[cfg(target_arch = "wasm32")]
+            None => {}
+        }
+        // Don't count adapter / device setup, or the time spent suspended,
+        // as frame time.
+        self.last_update_inst = web_time::Instant::now();
+        self.handle(Event::Resumed, event_loop);
+    }
+
+    fn window_event(&mut self, event_loop: &ActiveEventLoop, window_id: WindowId, event: WindowEvent) {
+        self.handle(Event::WindowEvent { window_id, event }, event_loop);
+    }
+
+    fn device_event(&mut self, event_loop: &ActiveEventLoop, device_id: DeviceId, event: DeviceEvent) {
+        self.handle(Event::DeviceEvent { device_id, event }, event_loop);
+    }
+
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        self.handle(Event::AboutToWait, event_loop);
+        if std::mem::take(&mut self.frame_requested)
+            && let Some(lp) = &self.lp
+            && !lp.hidden {
+            let window_id = lp.window_handle.id();
+            self.handle(Event::WindowEvent { window_id, event: WindowEvent::RedrawRequested }, event_loop);
+        }
+    }
+
+    fn suspended(&mut self, event_loop: &ActiveEventLoop) {
+        if let Some(scene) = &mut self.scene {
+            scene.pipeline.suspend();
         }
+        self.handle(Event::Suspended, event_loop);
+    }
+
+    fn exiting(&mut self, event_loop: &ActiveEventLoop) {
+        self.handle(Event::LoopExiting, event_loop);
+    }
+
+    fn memory_warning(&mut self, event_loop: &ActiveEventLoop) {
+        self.handle(Event::MemoryWarning, event_loop);
+    }
+}
+
+/// Event-loop state shared by every pass of [`Window::step`].
+struct LoopState {
+    window_handle: Arc<winit::window::Window>,
+    frame_stats: FrameStats,
+    /// Unsimulated time carried over to the next fixed update.
+    accumulator: f32,
+    occluded: bool,
+    zero_size: bool,
+    /// Minimised or fully covered: nothing to present.
+    hidden: bool,
+    /// No redraws before this instant; set when a frame times out.
+    redraw_after: Option<web_time::Instant>,
+    /// The host decides when to draw; see [`App`].
+    external: bool,
+    /// See [`FrameContext::elapsed`].
+    elapsed: f64,
+    /// See [`FrameContext::frame`].
+    frame: u64,
+    /// See [`FrameContext::cursor_mode`].
+    cursor_mode: CursorMode,
+}
+fn make_frame_context(dt: f32, lp: &LoopState, scene: &Scene) -> FrameContext {
+    let stats = &lp.frame_stats;
+    let config = &scene.pipeline.surface_config;
+    FrameContext {
+        dt,
+        fps: stats.fps,
+        frame_time_ms: stats.frame_time_ms,
+        draw_calls: stats.draw_calls,
+        triangle_count: stats.triangle_count,
+        render_stats: stats.render,
+        scale_factor: scene.scale_factor(),
+        elapsed: lp.elapsed,
+        frame: lp.frame,
+        alpha: (lp.accumulator / window::FIXED_DELTA).clamp(0.0, 1.0),
+        window_size: (config.width, config.height),
+        cursor_mode: lp.cursor_mode,
+        commands: AppCommands::default(),
     }
 }
 /// Convert winit platform events into [`EditorEvent`]s and dispatch them.
 /// No-op when editor mode is inactive.
 fn dispatch_editor_event(scene: &mut Scene, event: &Event<()>) {
-    use winit::keyboard::{PhysicalKey, KeyCode};
+    use winit::keyboard::PhysicalKey;
     match event {
         Event::WindowEvent { event: wev, .. } => match wev {
             WindowEvent::CursorMoved { position, .. } => {
@@ -483,6 +1665,10 @@ fn dispatch_editor_event(scene: &mut Scene, event: &Event<()>) {
                 };
                 scene.handle_editor_event(EditorEvent::Scroll { delta: scroll });
             }
+            WindowEvent::PinchGesture { delta, .. } => {
+                let scroll = *delta as f32 * crate::constants::camera::PINCH_SCROLL_STEPS;
+                scene.handle_editor_event(EditorEvent::Scroll { delta: scroll });
+            }
             WindowEvent::ModifiersChanged(mods) => {
                 let s = mods.state();
                 scene.handle_editor_event(EditorEvent::ModifiersChanged {
@@ -492,17 +1678,7 @@ fn dispatch_editor_event(scene: &mut Scene, event: &Event<()>) {
             }
             WindowEvent::KeyboardInput { event: ke, .. } => {
                 if let PhysicalKey::Code(code) = ke.physical_key {
-                    match ke.state {
-                        ElementState::Pressed => {
-                            scene.handle_editor_event(EditorEvent::KeyPressed(code));
-                            if code == KeyCode::KeyF {
-                                scene.handle_editor_event(EditorEvent::FocusKey);
-                            }
-                        }
-                        ElementState::Released => {
-                            scene.handle_editor_event(EditorEvent::KeyReleased(code));
-                        }
-                    }
+                    dispatch_editor_key(scene, code, ke.state == ElementState::Pressed);
                 }
             }
             _ => {}
@@ -516,3 +1692,16 @@ fn dispatch_editor_event(scene: &mut Scene, event: &Event<()>) {
         _ => {}
     }
 }
+
+/// Forward a key press or release to the editor; shared by live and replayed input.
+fn dispatch_editor_key(scene: &mut Scene, code: winit::keyboard::KeyCode, pressed: bool) {
+    use winit::keyboard::KeyCode;
+    if pressed {
+        scene.handle_editor_event(EditorEvent::KeyPressed(code));
+        if code == KeyCode::KeyF {
+            scene.handle_editor_event(EditorEvent::FocusKey);
+        }
+    } else {
+        scene.handle_editor_event(EditorEvent::KeyReleased(code));
+    }
+}
@@ -1,5 +1,7 @@
 use std::collections::HashMap;
+use crate::geometry::GeometryId;
 use crate::objects::Object;
+use crate::transform::Transform;
 
 /// Describes a structural change to the scene hierarchy.
 ///
@@ -23,6 +25,52 @@ impl std::fmt::Debug for SceneGraphCallback {
     }
 }
 
+/// The nearest object along a ray; see [`World::raycast`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit {
+    /// The object hit.
+    pub id: usize,
+    /// Distance from the ray origin, in world units.
+    pub distance: f32,
+    /// World-space point where the ray enters the object's bounds.
+    pub point: [f32; 3],
+}
+
+/// Objects spawned, deleted or changed during one frame; see
+/// [`World::changes`].
+///
+/// Each list is sorted and free of duplicates.  An object spawned and
+/// deleted within the same frame appears nowhere, and objects in `added` or
+/// `removed` are not also reported as modified.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorldChanges {
+    /// Objects spawned.
+    pub added: Vec<usize>,
+    /// Objects deleted, including the descendants of deleted objects.
+    pub removed: Vec<usize>,
+    /// Objects whose fields changed, other than their `children` list.
+    pub modified: Vec<usize>,
+    /// The subset of `modified` whose [`Object::transform`] changed.
+    pub transform_changed: Vec<usize>,
+}
+
+impl WorldChanges {
+    /// `true` when nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// Changes collected since the last [`World::end_frame`].
+#[derive(Debug, Default)]
+struct PendingChanges {
+    added: Vec<usize>,
+    removed: Vec<usize>,
+    /// State of each object when it was first borrowed mutably this frame,
+    /// without its children; `None` for [`World::mark_modified`].
+    touched: HashMap<usize, Option<Object>>,
+}
+
 #[derive(Debug)]
 pub struct World {
     pub objects: HashMap<usize, Object>,
@@ -31,6 +79,19 @@ pub struct World {
     next_id: usize,
     /// Optional callback invoked after every structural scene-graph change.
     pub on_scene_graph_modified: Option<SceneGraphCallback>,
+    pending: PendingChanges,
+    changes: WorldChanges,
+    /// Number of objects whose [`Object::mesh`] is each registry mesh.
+    mesh_refs: HashMap<GeometryId, usize>,
+    /// Entities mirroring the objects; see [`crate::ecs`].
+    #[cfg(feature = "ecs-mirror")]
+    ecs: crate::ecs::EcsMirror,
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl World {
@@ -41,6 +102,11 @@ impl World {
             next_id: 0,
             name_handles: HashMap::new(),
             on_scene_graph_modified: None,
+            pending: PendingChanges::default(),
+            changes: WorldChanges::default(),
+            mesh_refs: HashMap::new(),
+            #[cfg(feature = "ecs-mirror")]
+            ecs: crate::ecs::EcsMirror::new(),
         }
     }
 
@@ -58,11 +124,26 @@ impl World {
         next_id: usize,
     ) -> Self {
         let mut name_handles = HashMap::with_capacity(objects.len());
+        let mut mesh_refs = HashMap::new();
 
         for (&id, obj) in &objects {
             name_handles.insert(obj.str_id.clone(), id);
+            if let Some(mesh) = obj.mesh {
+                *mesh_refs.entry(mesh).or_insert(0) += 1;
+            }
+        }
+        Self {
+            #[cfg(feature = "ecs-mirror")]
+            ecs: crate::ecs::EcsMirror::from_objects(&objects),
+            objects,
+            roots,
+            next_id,
+            name_handles,
+            on_scene_graph_modified: None,
+            pending: PendingChanges::default(),
+            changes: WorldChanges::default(),
+            mesh_refs,
         }
-        Self { objects, roots, next_id, name_handles, on_scene_graph_modified: None }
     }
 
     pub fn spawn_object(&mut self, object: Object, parent_id: Option<usize>) -> usize {
@@ -71,6 +152,14 @@ impl World {
         id
     }
 
+    /// Reserve room for `additional` more objects, so spawning them does not
+    /// reallocate the world's maps.
+    pub fn reserve(&mut self, additional: usize) {
+        self.objects.reserve(additional);
+        self.name_handles.reserve(additional);
+        self.pending.added.reserve(additional);
+    }
+
     /// Reserve the next object ID without inserting any object.
     ///
     /// The ID is immediately retired from the counter so that a subsequent
@@ -109,7 +198,11 @@ impl World {
             self.roots.push(id);
         }
 
+        self.add_mesh_ref(object.mesh);
+        #[cfg(feature = "ecs-mirror")]
+        self.ecs.upsert(id, &object);
         self.objects.insert(id, object);
+        self.pending.added.push(id);
 
         if let Some(cb) = &mut self.on_scene_graph_modified {
             (cb.0)(SceneGraphEvent::ObjectAdded { id, parent_id: resolved_parent });
@@ -142,12 +235,189 @@ impl World {
         self.name_handles.get(str_id).copied()
     }
 
+    /// Borrow object `id`.
+    pub fn get(&self, id: usize) -> Option<&Object> {
+        self.objects.get(&id)
+    }
+
+    /// World-space transform of object `id`: its own transform combined with
+    /// every ancestor's, as it is rendered.
+    pub fn world_transform(&self, id: usize) -> Option<Transform> {
+        self.objects.contains_key(&id).then(|| crate::editor::math::compute_world_transform(self, id))
+    }
+
+    /// The nearest object hit by the ray from `origin` along the unit
+    /// vector `direction`, among those `filter` accepts.
+    ///
+    /// Objects are tested against their approximate world-space bounding
+    /// box, as the editor picks them; objects without [`Object::geometry`]
+    /// (registry meshes) are not hit.  An origin inside a box hits it at
+    /// its far side.
+    pub fn raycast(
+        &self,
+        origin: [f32; 3],
+        direction: [f32; 3],
+        mut filter: impl FnMut(usize, &Object) -> bool,
+    ) -> Option<RayHit> {
+        use crate::editor::math::{approx_half_extents, compute_world_transform, ray_aabb};
+        let mut best: Option<RayHit> = None;
+        for (&id, object) in &self.objects {
+            if object.geometry.is_none() || !filter(id, object) {
+                continue;
+            }
+            let transform = compute_world_transform(self, id);
+            let half = approx_half_extents(&object.geometry, &transform);
+            if let Some(t) = ray_aabb(origin, direction, transform.position, half)
+                && best.is_none_or(|hit| t < hit.distance) {
+                let point = [origin[0] + direction[0] * t, origin[1] + direction[1] * t, origin[2] + direction[2] * t];
+                best = Some(RayHit { id, distance: t, point });
+            }
+        }
+        best
+    }
+
+    /// Borrow an object mutably.  Changes made through the borrow are
+    /// reported by [`Self::changes`] after the next [`Self::end_frame`];
+    /// writes through [`Self::objects`] directly are not, unless followed
+    /// by [`Self::mark_modified`].
     pub fn get_mut(&mut self, id: usize) -> Option<&mut Object> {
+        self.touch(id);
         self.objects.get_mut(&id)
     }
 
-    /// Rename the stable string identifier of a live object and keep the
-    /// internal `name_handles` cache in sync.
+    /// Report `id` as modified, with its transform changed, in the next
+    /// [`Self::changes`], e.g. after writing to it through
+    /// [`Self::objects`].
+    pub fn mark_modified(&mut self, id: usize) {
+        if self.objects.contains_key(&id) {
+            self.pending.touched.insert(id, None);
+        }
+    }
+
+    /// Remember the state of `id` before its first mutable borrow this
+    /// frame, to compare against in [`Self::end_frame`].
+    fn touch(&mut self, id: usize) {
+        if let Some(obj) = self.objects.get(&id) {
+            self.pending.touched
+                .entry(id)
+                .or_insert_with(|| Some(Object { children: Vec::new(), ..obj.clone() }));
+        }
+    }
+
+    /// Changes published by the last [`Self::end_frame`]: everything spawned,
+    /// deleted or modified during the frame before it.
+    ///
+    /// [`crate::scene::Scene::draw_world`] ends the frame before drawing, so
+    /// the renderer and anything running in the next frame's callbacks see
+    /// the same lists.  Replacing the whole world (loading a VTR file,
+    /// leaving play mode) is not reported.
+    pub fn changes(&self) -> &WorldChanges {
+        &self.changes
+    }
+
+    /// Publish the changes collected since the previous call as
+    /// [`Self::changes`] and start collecting anew.
+    pub fn end_frame(&mut self) {
+        let pending = std::mem::take(&mut self.pending);
+        let sorted = |mut ids: Vec<usize>| {
+            ids.sort_unstable();
+            ids.dedup();
+            ids
+        };
+        let (added, removed) = (sorted(pending.added), sorted(pending.removed));
+        let transient: Vec<usize> = added.iter().copied().filter(|id| removed.binary_search(id).is_ok()).collect();
+        let added: Vec<usize> = added.into_iter().filter(|id| transient.binary_search(id).is_err()).collect();
+        let removed: Vec<usize> = removed.into_iter().filter(|id| transient.binary_search(id).is_err()).collect();
+
+        let (mut modified, mut transform_changed) = (Vec::new(), Vec::new());
+        for (id, before) in pending.touched {
+            if added.binary_search(&id).is_ok() {
+                continue;
+            }
+            let Some(obj) = self.objects.get_mut(&id) else { continue };
+            let Some(before) = before else {
+                modified.push(id);
+                transform_changed.push(id);
+                continue;
+            };
+            let children = std::mem::take(&mut obj.children);
+            if *obj != before {
+                modified.push(id);
+                if obj.transform != before.transform {
+                    transform_changed.push(id);
+                }
+            }
+            obj.children = children;
+        }
+        self.changes = WorldChanges {
+            added,
+            removed,
+            modified: sorted(modified),
+            transform_changed: sorted(transform_changed),
+        };
+        // Objects edited in the frame they were spawned are only `added`.
+        #[cfg(feature = "ecs-mirror")]
+        for &id in self.changes.added.iter().chain(&self.changes.modified) {
+            if let Some(obj) = self.objects.get(&id) {
+                self.ecs.upsert(id, obj);
+            }
+        }
+    }
+
+    /// The read-only archetype ECS mirroring this world, one frame behind
+    /// edits made through [`Self::get_mut`]; see [`crate::ecs`].
+    #[cfg(feature = "ecs-mirror")]
+    pub fn ecs(&self) -> &hecs::World {
+        &self.ecs.world
+    }
+
+    /// The entity mirroring object `id`.
+    #[cfg(feature = "ecs-mirror")]
+    pub fn entity(&self, id: usize) -> Option<hecs::Entity> {
+        self.ecs.entity(id)
+    }
+
+    /// Direct children of `id` with their IDs, in insertion order.  Empty
+    /// when `id` does not exist.
+    pub fn children(&self, id: usize) -> impl Iterator<Item = (usize, &Object)> {
+        self.objects
+            .get(&id)
+            .map(|obj| obj.children.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|&child| self.objects.get(&child).map(|obj| (child, obj)))
+    }
+
+    /// Every object below `id` with its ID, depth-first with each parent
+    /// before its children.  `id` itself is not included.
+    pub fn descendants(&self, id: usize) -> impl Iterator<Item = (usize, &Object)> {
+        let mut stack: Vec<usize> = self.objects
+            .get(&id)
+            .map(|obj| obj.children.iter().rev().copied().collect())
+            .unwrap_or_default();
+        std::iter::from_fn(move || {
+            while let Some(next) = stack.pop() {
+                if let Some(obj) = self.objects.get(&next) {
+                    stack.extend(obj.children.iter().rev());
+                    return Some((next, obj));
+                }
+            }
+            None
+        })
+    }
+
+    /// ID of the first descendant of `id` whose display
+    /// [`Object::name`] is `name`, in [`Self::descendants`] order.
+    ///
+    /// Names need not be unique; use [`Self::get_id`] for a guaranteed
+    /// match by `str_id`.
+    pub fn find_descendant_by_name(&self, id: usize, name: &str) -> Option<usize> {
+        self.descendants(id).find(|(_, obj)| obj.name == name).map(|(id, _)| id)
+    }
+
+    /// Rename the stable string identifier of a live object, keep the
+    /// internal `name_handles` cache in sync and report the object as
+    /// modified in the next [`Self::changes`].
     ///
     /// **Always prefer this over writing to `object.str_id` directly** when the
     /// object is already inside a `World`.  Direct field assignment bypasses the
@@ -156,6 +426,7 @@ impl World {
     ///
     /// Returns `false` (no-op) when `id` does not exist.
     pub fn rename_str_id(&mut self, id: usize, new_str_id: String) -> bool {
+        self.touch(id);
         if let Some(obj) = self.objects.get_mut(&id) {
             let old = std::mem::replace(&mut obj.str_id, new_str_id.clone());
             self.name_handles.remove(&old);
@@ -166,10 +437,54 @@ impl World {
         }
     }
 
+    /// Point object `id` at registry mesh `mesh` (or at none), keeping
+    /// [`Self::mesh_ref_count`] current and reporting the object as
+    /// modified in the next [`Self::changes`].
+    ///
+    /// **Prefer this over writing to `object.mesh` directly** once the object
+    /// is inside a `World`: direct writes are not counted, so
+    /// [`crate::scene::Scene::collect_mesh_garbage`] may free a mesh that is
+    /// still in use.
+    ///
+    /// Returns `false` (no-op) when `id` does not exist.
+    pub fn set_mesh(&mut self, id: usize, mesh: Option<GeometryId>) -> bool {
+        self.touch(id);
+        let Some(obj) = self.objects.get_mut(&id) else { return false };
+        let old = std::mem::replace(&mut obj.mesh, mesh);
+        self.drop_mesh_ref(old);
+        self.add_mesh_ref(mesh);
+        true
+    }
+
+    /// Number of objects in this world whose [`Object::mesh`] is `mesh`.
+    pub fn mesh_ref_count(&self, mesh: GeometryId) -> usize {
+        self.mesh_refs.get(&mesh).copied().unwrap_or(0)
+    }
+
+    fn add_mesh_ref(&mut self, mesh: Option<GeometryId>) {
+        if let Some(mesh) = mesh {
+            *self.mesh_refs.entry(mesh).or_insert(0) += 1;
+        }
+    }
+
+    fn drop_mesh_ref(&mut self, mesh: Option<GeometryId>) {
+        let Some(mesh) = mesh else { return };
+        if let Some(count) = self.mesh_refs.get_mut(&mesh) {
+            *count -= 1;
+            if *count == 0 {
+                self.mesh_refs.remove(&mesh);
+            }
+        }
+    }
+
     fn recursive_remove(&mut self, id: usize) {
         // Remove the object and take ownership of its children list
         if let Some(obj) = self.objects.remove(&id) {
             self.name_handles.remove(&obj.str_id);
+            self.drop_mesh_ref(obj.mesh);
+            self.pending.removed.push(id);
+            #[cfg(feature = "ecs-mirror")]
+            self.ecs.remove(id);
             for child_id in obj.children {
                 self.recursive_remove(child_id);
             }
@@ -185,6 +500,10 @@ impl World {
         };
 
         self.name_handles.remove(&obj.str_id);
+        self.drop_mesh_ref(obj.mesh);
+        self.pending.removed.push(id);
+        #[cfg(feature = "ecs-mirror")]
+        self.ecs.remove(id);
 
         // Unlink from parent / root list
         if let Some(p_id) = obj.parent {
@@ -247,16 +566,13 @@ impl World {
         if old_parent == new_parent { return false; }
 
         // Target parent must exist (unless moving to root)
-        if let Some(p_id) = new_parent {
-            if !self.objects.contains_key(&p_id) { return false; }
-        }
+        if let Some(p_id) = new_parent && !self.objects.contains_key(&p_id) { return false; }
 
         // Cycle guard: new_parent must not be inside id's subtree
         // TODO: Instead of failing, we can instead switch the position of those objects
-        if let Some(p_id) = new_parent {
-            if self.is_in_subtree(id, p_id) { return false; }
-        }
+        if let Some(p_id) = new_parent && self.is_in_subtree(id, p_id) { return false; }
 
+        self.touch(id);
         // Detach from current location
         if let Some(p_id) = old_parent {
             if let Some(p) = self.objects.get_mut(&p_id) {
@@ -277,6 +593,8 @@ impl World {
 
         if let Some(obj) = self.objects.get_mut(&id) {
             obj.parent = new_parent;
+            #[cfg(feature = "ecs-mirror")]
+            self.ecs.upsert(id, obj);
         }
 
         if let Some(cb) = &mut self.on_scene_graph_modified {
@@ -284,4 +602,4 @@ impl World {
         }
         true
     }
-}
\ No newline at end of file
+}